@@ -1,16 +1,22 @@
+use std::path::PathBuf;
+
 use anyhow::Context as _;
 use clap::{Parser, Subcommand};
 use tokio::io::{self, AsyncReadExt};
 use zksync_config::{
-    configs::ObservabilityConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig,
-    PostgresConfig,
+    configs::{chain::NetworkConfig, ObservabilityConfig},
+    ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, PostgresConfig,
 };
-use zksync_core::block_reverter::{
-    BlockReverter, BlockReverterEthConfig, BlockReverterFlags, L1ExecutedBatchesRevert, NodeRole,
+use zksync_core::{
+    block_reverter::{
+        BlockReverter, BlockReverterEthConfig, BlockReverterFlags, L1ExecutedBatchesRevert,
+        NodeRole, RollbackPlan, RollbackRequest,
+    },
+    state_keeper::{BatchReplayer, ReplayOptions},
 };
 use zksync_dal::{ConnectionPool, Core};
 use zksync_env_config::FromEnv;
-use zksync_types::{L1BatchNumber, U256};
+use zksync_types::{L1BatchNumber, ProtocolVersionId, VmVersion, U256};
 
 #[derive(Debug, Parser)]
 #[command(author = "Matter Labs", version, about = "Block revert utility", long_about = None)]
@@ -64,9 +70,65 @@ enum Command {
         allow_executed_block_reversion: bool,
     },
 
+    /// Creates a rollback plan without changing any state. The plan is checked for safety and contains
+    /// a report on what would be reverted; it can be executed with the `execute-plan` command.
+    #[command(name = "plan")]
+    Plan {
+        /// L1 batch number used to rollback to.
+        #[arg(long)]
+        l1_batch_number: u32,
+        /// Flag that specifies if Postgres DB should be rolled back.
+        #[arg(long)]
+        rollback_postgres: bool,
+        /// Flag that specifies if RocksDB with tree should be rolled back.
+        #[arg(long)]
+        rollback_tree: bool,
+        /// Flag that specifies if RocksDB with state keeper cache should be rolled back.
+        #[arg(long)]
+        rollback_sk_cache: bool,
+        /// Flag that specifies if L1 batches committed on L1 should be reverted on L1.
+        #[arg(long)]
+        revert_on_l1: bool,
+        /// Priority fee used for rollback Ethereum transaction.
+        #[arg(long)]
+        priority_fee_per_gas: Option<u64>,
+        /// Path to write the plan to. If not specified, the plan is printed to stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Executes a rollback plan created by the `plan` command, provided that the node state hasn't changed since.
+    #[command(name = "execute-plan")]
+    ExecutePlan {
+        /// Path to the JSON plan.
+        #[arg(long)]
+        plan: PathBuf,
+    },
+
     /// Clears failed L1 transactions.
     #[command(name = "clear-failed-transactions")]
     ClearFailedL1Transactions,
+
+    /// Re-executes a sealed L1 batch on top of the Postgres state and compares results with the stored data.
+    #[command(name = "replay-batch")]
+    ReplayBatch {
+        /// L1 batch number to replay.
+        #[arg(long)]
+        l1_batch_number: u32,
+        /// Protocol version determining the VM version to use. By default, the VM version is determined
+        /// by the protocol version of the replayed batch.
+        #[arg(long)]
+        vm_protocol_version: Option<u16>,
+        /// Collects call traces for replayed transactions and includes them into the report.
+        #[arg(long)]
+        call_traces: bool,
+        /// Falls back to executing transactions without bytecode compression (as on external nodes).
+        #[arg(long)]
+        optional_bytecode_compression: bool,
+        /// Outputs the report as a JSON object, so that it is machine-readable.
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[tokio::main]
@@ -94,7 +156,7 @@ async fn main() -> anyhow::Result<()> {
         U256::from(eth_sender.gas_adjuster.default_priority_fee_per_gas);
     let contracts = ContractsConfig::from_env().context("ContractsConfig::from_env()")?;
     let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
-    let config = BlockReverterEthConfig::new(eth_sender, contracts, eth_client.web3_url.clone());
+    let config = BlockReverterEthConfig::new(eth_sender, contracts, &eth_client);
 
     let connection_pool = ConnectionPool::<Core>::builder(
         postgres_config.master_url()?,
@@ -108,13 +170,13 @@ async fn main() -> anyhow::Result<()> {
         db_config.state_keeper_db_path,
         db_config.merkle_tree.path,
         Some(config),
-        connection_pool,
+        connection_pool.clone(),
         L1ExecutedBatchesRevert::Disallowed,
     );
 
     match Cli::parse().command {
         Command::Display { json } => {
-            let suggested_values = block_reverter.suggested_values().await;
+            let suggested_values = block_reverter.suggested_values().await?;
             if json {
                 println!("{}", serde_json::to_string(&suggested_values).unwrap());
             } else {
@@ -134,7 +196,7 @@ async fn main() -> anyhow::Result<()> {
                     priority_fee_per_gas,
                     nonce,
                 )
-                .await
+                .await?;
         }
         Command::RollbackDB {
             l1_batch_number,
@@ -188,7 +250,73 @@ async fn main() -> anyhow::Result<()> {
                 .rollback_db(L1BatchNumber(l1_batch_number), flags)
                 .await
         }
+        Command::Plan {
+            l1_batch_number,
+            rollback_postgres,
+            rollback_tree,
+            rollback_sk_cache,
+            revert_on_l1,
+            priority_fee_per_gas,
+            output,
+        } => {
+            let request = RollbackRequest {
+                last_l1_batch_to_keep: L1BatchNumber(l1_batch_number),
+                rollback_postgres,
+                rollback_tree,
+                rollback_sk_cache,
+                revert_on_l1,
+                priority_fee_per_gas,
+            };
+            let plan = block_reverter.plan_rollback(request).await?;
+            let plan = serde_json::to_string_pretty(&plan).unwrap();
+            if let Some(output) = output {
+                std::fs::write(&output, plan)
+                    .with_context(|| format!("failed writing plan to `{}`", output.display()))?;
+            } else {
+                println!("{plan}");
+            }
+        }
+        Command::ExecutePlan { plan } => {
+            let plan = std::fs::read_to_string(&plan)
+                .with_context(|| format!("failed reading plan from `{}`", plan.display()))?;
+            let plan: RollbackPlan = serde_json::from_str(&plan).context("invalid plan")?;
+            block_reverter.execute_plan(&plan).await?;
+        }
         Command::ClearFailedL1Transactions => block_reverter.clear_failed_l1_transactions().await,
+        Command::ReplayBatch {
+            l1_batch_number,
+            vm_protocol_version,
+            call_traces,
+            optional_bytecode_compression,
+            json,
+        } => {
+            let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+            let vm_version = vm_protocol_version
+                .map(|version| {
+                    let version = ProtocolVersionId::try_from(version)
+                        .with_context(|| format!("unknown protocol version: {version}"))?;
+                    anyhow::Ok(VmVersion::from(version))
+                })
+                .transpose()?;
+            let options = ReplayOptions {
+                vm_version,
+                save_call_traces: call_traces,
+                optional_bytecode_compression,
+            };
+            let report = BatchReplayer::new(connection_pool, network_config.zksync_network_id)
+                .with_options(options)
+                .replay(L1BatchNumber(l1_batch_number))
+                .await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            } else {
+                println!("Replay report: {report:#?}");
+            }
+            anyhow::ensure!(
+                report.is_consistent(),
+                "replayed L1 batch #{l1_batch_number} diverges from the stored data"
+            );
+        }
     }
     Ok(())
 }
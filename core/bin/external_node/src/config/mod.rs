@@ -1,4 +1,4 @@
-use std::{env, time::Duration};
+use std::{env, num::NonZeroUsize, time::Duration};
 
 use anyhow::Context;
 use serde::Deserialize;
@@ -97,6 +97,20 @@ pub enum BlockFetcher {
     Consensus,
 }
 
+/// Mode in which the external node syncs L2 blocks from the main node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    /// Transactions are re-executed locally by the state keeper.
+    #[default]
+    Full,
+    /// Execution results (storage logs, events etc.) are copied from the main node without re-executing transactions.
+    /// This mode is cheaper in terms of CPU and storage (no state keeper cache is maintained), but it doesn't provide
+    /// traces for the `debug_*` namespace. The copied data is still verified by the Merkle tree and
+    /// the consistency checker.
+    Light,
+}
+
 /// This part of the external node config is completely optional to provide.
 /// It can tweak limits of the API, delay intervals of certain components, etc.
 /// If any of the fields are not provided, the default values will be used.
@@ -165,6 +179,13 @@ pub struct OptionalENConfig {
     /// different node.
     #[serde(default)]
     pub filters_disabled: bool,
+    /// Enables private API mode, in which account-specific data is only returned to callers authenticated
+    /// with a session token signed by the account.
+    #[serde(default)]
+    pub private_api_mode: bool,
+    /// Maximum lifetime of session tokens accepted in the private API mode, in seconds.
+    #[serde(default = "OptionalENConfig::default_max_session_token_ttl_sec")]
+    pub max_session_token_ttl_sec: u64,
 
     // Health checks
     /// Time limit in milliseconds to mark a health check as slow and log the corresponding warning.
@@ -249,6 +270,14 @@ pub struct OptionalENConfig {
     // This is intentionally not a part of `RemoteENConfig` because fetching this info from the main node would defeat
     // its purpose; the consistency checker assumes that the main node may provide false information.
     pub contracts_diamond_proxy_addr: Option<Address>,
+    /// Addresses of additional Ethereum node APIs used by the consistency checker if the main Ethereum node API
+    /// misbehaves. Comma-separated.
+    /// Intentionally private: use getter method as it manages the missing port.
+    #[serde(default)]
+    eth_client_fallback_urls: Vec<String>,
+    /// Mode in which L2 blocks are synced from the main node. Default is `full`.
+    #[serde(default)]
+    pub sync_mode: SyncMode,
 }
 
 impl OptionalENConfig {
@@ -363,6 +392,10 @@ impl OptionalENConfig {
         10_000
     }
 
+    const fn default_max_session_token_ttl_sec() -> u64 {
+        86_400
+    }
+
     pub fn polling_interval(&self) -> Duration {
         Duration::from_millis(self.polling_interval)
     }
@@ -434,6 +467,20 @@ impl OptionalENConfig {
     pub fn mempool_cache_update_interval(&self) -> Duration {
         Duration::from_millis(self.mempool_cache_update_interval)
     }
+
+    pub fn eth_client_fallback_urls(&self) -> anyhow::Result<Vec<String>> {
+        self.eth_client_fallback_urls
+            .iter()
+            .map(|url| {
+                RequiredENConfig::get_url(url)
+                    .with_context(|| format!("Could not parse fallback L1 client URL `{url}`"))
+            })
+            .collect()
+    }
+
+    pub fn max_session_token_ttl(&self) -> Duration {
+        Duration::from_secs(self.max_session_token_ttl_sec)
+    }
 }
 
 /// This part of the external node config is required for its operation.
@@ -516,14 +563,32 @@ pub(crate) fn read_consensus_config() -> anyhow::Result<Option<consensus::Config
 #[derive(Debug, Clone)]
 pub struct SnapshotsRecoveryConfig {
     pub snapshots_object_store: ObjectStoreConfig,
+    /// Address of the trusted signer of snapshot manifests. If set, the node only recovers from snapshots
+    /// with manifests signed by this address, and checks the root hash of the recovered state.
+    pub manifest_signer: Option<Address>,
+    /// Maximum number of storage logs chunks processed concurrently during recovery. If not set,
+    /// the snapshots applier default is used.
+    pub max_concurrency: Option<NonZeroUsize>,
 }
 
 pub(crate) fn read_snapshots_recovery_config() -> anyhow::Result<SnapshotsRecoveryConfig> {
     let snapshots_object_store = envy::prefixed("EN_SNAPSHOTS_OBJECT_STORE_")
         .from_env::<ObjectStoreConfig>()
         .context("failed loading snapshot object store config from env variables")?;
+    let manifest_signer = std::env::var("EN_SNAPSHOTS_MANIFEST_SIGNER")
+        .ok()
+        .map(|address| address.parse())
+        .transpose()
+        .context("EN_SNAPSHOTS_MANIFEST_SIGNER is not a valid address")?;
+    let max_concurrency = env::var("EN_SNAPSHOTS_RECOVERY_MAX_CONCURRENCY")
+        .ok()
+        .map(|value| value.parse())
+        .transpose()
+        .context("EN_SNAPSHOTS_RECOVERY_MAX_CONCURRENCY is not a positive integer")?;
     Ok(SnapshotsRecoveryConfig {
         snapshots_object_store,
+        manifest_signer,
+        max_concurrency,
     })
 }
 
@@ -638,6 +703,8 @@ impl From<ExternalNodeConfig> for InternalApiConfig {
             req_entities_limit: config.optional.req_entities_limit,
             fee_history_limit: config.optional.fee_history_limit,
             filters_disabled: config.optional.filters_disabled,
+            private_mode: config.optional.private_api_mode,
+            max_session_token_ttl: config.optional.max_session_token_ttl(),
             mempool_cache_update_interval: config.optional.mempool_cache_update_interval(),
             mempool_cache_size: config.optional.mempool_cache_size,
         }
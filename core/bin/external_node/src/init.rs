@@ -6,7 +6,7 @@ use zksync_core::sync_layer::genesis::perform_genesis_if_needed;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_health_check::AppHealthCheck;
 use zksync_object_store::ObjectStoreFactory;
-use zksync_snapshots_applier::SnapshotsApplierConfig;
+use zksync_snapshots_applier::{SnapshotManifestSigner, SnapshotsApplierConfig};
 use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
 
 use crate::config::read_snapshots_recovery_config;
@@ -86,7 +86,22 @@ pub(crate) async fn ensure_storage_initialized(
                 .create_store()
                 .await;
 
-            let config = SnapshotsApplierConfig::default();
+            let mut config = SnapshotsApplierConfig::default();
+            config.manifest_signer =
+                recovery_config
+                    .manifest_signer
+                    .map(|address| SnapshotManifestSigner {
+                        address,
+                        chain_id: l2_chain_id,
+                    });
+            if let Some(max_concurrency) = recovery_config.max_concurrency {
+                config.max_concurrency = max_concurrency;
+            }
+            if config.manifest_signer.is_none() {
+                tracing::warn!(
+                    "Trusted snapshot manifest signer is not configured; snapshot files will not be authenticated"
+                );
+            }
             app_health.insert_component(config.health_check());
             config
                 .run(pool, main_node_client, &blob_store)
@@ -1,4 +1,4 @@
-use std::{future, sync::Arc, time::Duration};
+use std::{future, iter, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use clap::Parser;
@@ -29,12 +29,13 @@ use zksync_core::{
         MiniblockSealerHandle, ZkSyncStateKeeper,
     },
     sync_layer::{
-        batch_status_updater::BatchStatusUpdater, external_io::ExternalIO, ActionQueue,
-        MainNodeClient, SyncState,
+        batch_status_updater::BatchStatusUpdater, external_io::ExternalIO,
+        state_diff_syncer::StateDiffSyncer, ActionQueue, MainNodeClient, SyncState,
     },
 };
 use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core, CoreDal};
 use zksync_db_connection::healthcheck::ConnectionPoolHealthCheck;
+use zksync_eth_client::clients::FallbackEthClient;
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_state::PostgresStorageCaches;
 use zksync_storage::RocksDB;
@@ -42,7 +43,7 @@ use zksync_utils::wait_for_tasks::ManagedTasks;
 use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
 
 use crate::{
-    config::{observability::observability_config_from_env, ExternalNodeConfig},
+    config::{observability::observability_config_from_env, ExternalNodeConfig, SyncMode},
     helpers::MainNodeHealthCheck,
     init::ensure_storage_initialized,
 };
@@ -111,52 +112,21 @@ async fn build_state_keeper(
     ))
 }
 
-async fn init_tasks(
+/// Spawns tasks syncing L2 blocks from the main node and re-executing them with the state keeper.
+async fn init_full_sync_tasks(
     config: &ExternalNodeConfig,
     connection_pool: ConnectionPool<Core>,
     main_node_client: HttpClient,
+    sync_state: SyncState,
     task_handles: &mut Vec<task::JoinHandle<anyhow::Result<()>>>,
-    app_health: &AppHealthCheck,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
-    let release_manifest: serde_json::Value = serde_json::from_str(RELEASE_MANIFEST)
-        .expect("release manifest is a valid json document; qed");
-    let release_manifest_version = release_manifest["core"].as_str().expect(
-        "a release-please manifest with \"core\" version field was specified at build time; qed.",
-    );
-
-    let version = semver::Version::parse(release_manifest_version)
-        .expect("version in manifest is a correct semver format; qed");
-    // Create components.
-    let fee_params_fetcher = Arc::new(MainNodeFeeParamsFetcher::new(main_node_client.clone()));
-
-    let sync_state = SyncState::default();
-    app_health.insert_custom_component(Arc::new(sync_state.clone()));
     let (action_queue_sender, action_queue) = ActionQueue::new();
-
     let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
         connection_pool.clone(),
         config.optional.miniblock_seal_queue_capacity,
     );
     task_handles.push(tokio::spawn(miniblock_sealer.run()));
-    let pool = connection_pool.clone();
-    task_handles.push(tokio::spawn(async move {
-        loop {
-            let protocol_version = pool
-                .connection()
-                .await
-                .unwrap()
-                .protocol_versions_dal()
-                .last_used_version_id()
-                .await
-                .map(|version| version as u16);
-
-            EN_METRICS.version[&(format!("{}", version), protocol_version)].set(1);
-
-            tokio::time::sleep(Duration::from_secs(10)).await;
-        }
-    }));
-
     let state_keeper = build_state_keeper(
         action_queue,
         config.required.state_cache_path.clone(),
@@ -177,7 +147,7 @@ async fn init_tasks(
         let fetcher = consensus::Fetcher {
             store: consensus::Store(connection_pool.clone()),
             sync_state: sync_state.clone(),
-            client: Box::new(main_node_client.clone()),
+            client: Box::new(main_node_client),
             limiter: limiter::Limiter::new(
                 &ctx,
                 limiter::Rate {
@@ -210,6 +180,78 @@ async fn init_tasks(
         }
     }));
 
+    let fee_address_migration_handle =
+        task::spawn(state_keeper.run_fee_address_migration(connection_pool));
+    let sk_handle = task::spawn(state_keeper.run());
+    task_handles.extend([sk_handle, fee_address_migration_handle]);
+    Ok(())
+}
+
+async fn init_tasks(
+    config: &ExternalNodeConfig,
+    connection_pool: ConnectionPool<Core>,
+    main_node_client: HttpClient,
+    task_handles: &mut Vec<task::JoinHandle<anyhow::Result<()>>>,
+    app_health: &AppHealthCheck,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let release_manifest: serde_json::Value = serde_json::from_str(RELEASE_MANIFEST)
+        .expect("release manifest is a valid json document; qed");
+    let release_manifest_version = release_manifest["core"].as_str().expect(
+        "a release-please manifest with \"core\" version field was specified at build time; qed.",
+    );
+
+    let version = semver::Version::parse(release_manifest_version)
+        .expect("version in manifest is a correct semver format; qed");
+    // Create components.
+    let fee_params_fetcher = Arc::new(MainNodeFeeParamsFetcher::new(main_node_client.clone()));
+
+    let sync_state = SyncState::default();
+    app_health.insert_custom_component(Arc::new(sync_state.clone()));
+
+    let pool = connection_pool.clone();
+    task_handles.push(tokio::spawn(async move {
+        loop {
+            let protocol_version = pool
+                .connection()
+                .await
+                .unwrap()
+                .protocol_versions_dal()
+                .last_used_version_id()
+                .await
+                .map(|version| version as u16);
+
+            EN_METRICS.version[&(format!("{}", version), protocol_version)].set(1);
+
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        }
+    }));
+
+    match config.optional.sync_mode {
+        SyncMode::Full => {
+            init_full_sync_tasks(
+                config,
+                connection_pool.clone(),
+                main_node_client.clone(),
+                sync_state.clone(),
+                task_handles,
+                stop_receiver.clone(),
+            )
+            .await?;
+        }
+        SyncMode::Light => {
+            tracing::info!("Syncing in light mode: transactions won't be re-executed locally");
+            let state_diff_syncer = StateDiffSyncer::new(
+                main_node_client.clone(),
+                connection_pool.clone(),
+                sync_state.clone(),
+                config.remote.l2_erc20_bridge_addr,
+            );
+            app_health.insert_component(state_diff_syncer.health_check());
+            task_handles.push(tokio::spawn(state_diff_syncer.run(stop_receiver.clone())));
+        }
+    }
+
     let reorg_detector = ReorgDetector::new(main_node_client.clone(), connection_pool.clone());
     app_health.insert_component(reorg_detector.health_check().clone());
     task_handles.push(tokio::spawn({
@@ -254,18 +296,25 @@ async fn init_tasks(
         remote_diamond_proxy_addr
     };
 
+    let eth_client_url = config
+        .required
+        .eth_client_url()
+        .context("L1 client URL is incorrect")?;
+    let eth_client_fallback_urls = config
+        .optional
+        .eth_client_fallback_urls()
+        .context("fallback L1 client URLs are incorrect")?;
+    let eth_client_urls = iter::once(&eth_client_url).chain(&eth_client_fallback_urls);
+    let eth_client = FallbackEthClient::from_urls(eth_client_urls.map(String::as_str))
+        .context("cannot create L1 client")?;
     let consistency_checker = ConsistencyChecker::new(
-        &config
-            .required
-            .eth_client_url()
-            .context("L1 client URL is incorrect")?,
+        Box::new(eth_client),
         10, // TODO (BFT-97): Make it a part of a proper EN config
         singleton_pool_builder
             .build()
             .await
             .context("failed to build connection pool for ConsistencyChecker")?,
     )
-    .context("cannot initialize consistency checker")?
     .with_diamond_proxy_addr(diamond_proxy_addr);
 
     app_health.insert_component(consistency_checker.health_check().clone());
@@ -298,9 +347,6 @@ async fn init_tasks(
     let commitment_generator_handle = tokio::spawn(commitment_generator.run(stop_receiver.clone()));
 
     let updater_handle = task::spawn(batch_status_updater.run(stop_receiver.clone()));
-    let fee_address_migration_handle =
-        task::spawn(state_keeper.run_fee_address_migration(connection_pool.clone()));
-    let sk_handle = task::spawn(state_keeper.run());
     let fee_params_fetcher_handle =
         tokio::spawn(fee_params_fetcher.clone().run(stop_receiver.clone()));
 
@@ -417,8 +463,6 @@ async fn init_tasks(
     task_handles.extend(cache_update_handle);
     task_handles.push(proxy_cache_updater_handle);
     task_handles.extend([
-        sk_handle,
-        fee_address_migration_handle,
         updater_handle,
         tree_handle,
         consistency_checker_handle,
@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use clap::Parser;
+use tokio::sync::watch;
+use zksync_config::{
+    configs::ObservabilityConfig, ContractsConfig, ETHClientConfig, ObjectStoreConfig,
+    PostgresConfig,
+};
+use zksync_core::l1_recovery::{L1RecoveryConfig, L1StateRecovery, ObjectStoreBlobProvider};
+use zksync_dal::{ConnectionPool, Core};
+use zksync_env_config::FromEnv;
+use zksync_eth_client::clients::QueryClient;
+use zksync_object_store::ObjectStoreFactory;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Recovers node state from data published on L1",
+    long_about = None
+)]
+struct Cli {
+    /// L1 block to start scanning for L1 batch commitments from. Should be no later than the block
+    /// in which L1 batch #1 was committed.
+    #[arg(long)]
+    from_l1_block: u64,
+    /// Maximum number of L1 blocks to query events for in a single request.
+    #[arg(long, default_value_t = 10_000)]
+    l1_block_range: u64,
+    /// Path to the RocksDB directory for the recovered Merkle tree.
+    #[arg(long)]
+    merkle_tree_path: PathBuf,
+    /// Load blobs for L1 batches with pubdata published in blobs from the blob archive maintained
+    /// by the server (configured via `OBJECT_STORE_*` env variables).
+    #[arg(long)]
+    blobs_from_archive: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = Cli::parse();
+
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build();
+
+    let eth_client_config = ETHClientConfig::from_env().context("ETHClientConfig::from_env()")?;
+    let contracts_config = ContractsConfig::from_env().context("ContractsConfig::from_env()")?;
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+
+    let connection_pool = ConnectionPool::<Core>::builder(
+        postgres_config.master_url()?,
+        postgres_config.max_connections()?,
+    )
+    .build()
+    .await
+    .context("failed to build a connection pool")?;
+    let l1_client =
+        QueryClient::new(&eth_client_config.web3_url).context("failed creating L1 client")?;
+
+    let config = L1RecoveryConfig {
+        diamond_proxy_addr: contracts_config.diamond_proxy_addr,
+        from_l1_block: opts.from_l1_block,
+        l1_block_range: opts.l1_block_range,
+        merkle_tree_path: opts.merkle_tree_path,
+    };
+    let mut recovery = L1StateRecovery::new(Box::new(l1_client), connection_pool, config);
+    if opts.blobs_from_archive {
+        let object_store_config =
+            ObjectStoreConfig::from_env().context("ObjectStoreConfig::from_env()")?;
+        let blob_store = ObjectStoreFactory::new(object_store_config)
+            .create_store()
+            .await;
+        recovery = recovery.with_blob_provider(Box::new(ObjectStoreBlobProvider::new(blob_store)));
+    }
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            tracing::info!("Received Ctrl+C, stopping L1 state recovery");
+            stop_sender.send_replace(true);
+        }
+    });
+
+    let last_recovered_batch = recovery.run(stop_receiver).await?;
+    tracing::info!(
+        "L1 state recovery finished; last recovered L1 batch is #{last_recovered_batch}"
+    );
+    Ok(())
+}
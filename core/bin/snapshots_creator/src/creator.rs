@@ -1,18 +1,20 @@
 //! [`SnapshotCreator`] and tightly related types.
 
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
 use anyhow::Context as _;
 use tokio::sync::Semaphore;
 use zksync_config::SnapshotsCreatorConfig;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
-use zksync_object_store::ObjectStore;
+use zksync_eth_signer::{EthereumSigner, PrivateKeySigner};
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
 use zksync_types::{
     snapshots::{
-        uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotFactoryDependency,
-        SnapshotMetadata, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        snapshot_blob_hash, uniform_hashed_keys_chunk, SnapshotFactoryDependencies,
+        SnapshotFactoryDependency, SnapshotManifest, SnapshotMetadata, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
-    L1BatchNumber, MiniblockNumber,
+    Eip712Domain, L1BatchNumber, L2ChainId, MiniblockNumber, H256,
 };
 
 use crate::metrics::{FactoryDepsStage, StorageChunkStage, METRICS};
@@ -23,6 +25,8 @@ use crate::tests::HandleEvent;
 #[derive(Debug)]
 struct SnapshotProgress {
     l1_batch_number: L1BatchNumber,
+    /// L1 batch of the base snapshot if the snapshot is incremental.
+    base_l1_batch_number: Option<L1BatchNumber>,
     /// `true` if the snapshot is new (i.e., its progress is not recovered from Postgres).
     is_new_snapshot: bool,
     chunk_count: u64,
@@ -30,9 +34,14 @@ struct SnapshotProgress {
 }
 
 impl SnapshotProgress {
-    fn new(l1_batch_number: L1BatchNumber, chunk_count: u64) -> Self {
+    fn new(
+        l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: Option<L1BatchNumber>,
+        chunk_count: u64,
+    ) -> Self {
         Self {
             l1_batch_number,
+            base_l1_batch_number,
             is_new_snapshot: true,
             chunk_count,
             remaining_chunk_ids: (0..chunk_count).collect(),
@@ -49,6 +58,7 @@ impl SnapshotProgress {
 
         Self {
             l1_batch_number: snapshot.l1_batch_number,
+            base_l1_batch_number: snapshot.base_l1_batch_number,
             is_new_snapshot: false,
             chunk_count: snapshot.storage_logs_filepaths.len() as u64,
             remaining_chunk_ids,
@@ -56,12 +66,42 @@ impl SnapshotProgress {
     }
 }
 
+/// Signs manifests of created snapshots with the operator key.
+pub(crate) struct ManifestSigner {
+    pub signer: PrivateKeySigner,
+    pub chain_id: L2ChainId,
+}
+
+impl fmt::Debug for ManifestSigner {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ManifestSigner")
+            .field("chain_id", &self.chain_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Serializes and stores a snapshot blob. Returns the blob key and the hash of the stored bytes.
+async fn put_hashed_blob<V: StoredObject>(
+    blob_store: &dyn ObjectStore,
+    key: V::Key<'_>,
+    value: &V,
+) -> Result<(String, H256), ObjectStoreError> {
+    let key = V::encode_key(key);
+    let bytes = value.serialize().map_err(ObjectStoreError::Serialization)?;
+    let hash = snapshot_blob_hash(&bytes);
+    blob_store.put_raw(V::BUCKET, &key, bytes).await?;
+    Ok((key, hash))
+}
+
 /// Creator of a single storage snapshot.
 #[derive(Debug)]
 pub(crate) struct SnapshotCreator {
     pub blob_store: Arc<dyn ObjectStore>,
     pub master_pool: ConnectionPool<Core>,
     pub replica_pool: ConnectionPool<Core>,
+    /// If set, manifests of created snapshots are signed by this signer.
+    pub manifest_signer: Option<ManifestSigner>,
     #[cfg(test)]
     pub event_listener: Box<dyn HandleEvent>,
 }
@@ -78,6 +118,7 @@ impl SnapshotCreator {
         semaphore: &Semaphore,
         miniblock_number: MiniblockNumber,
         l1_batch_number: L1BatchNumber,
+        base_miniblock_number: Option<MiniblockNumber>,
         chunk_id: u64,
         chunk_count: u64,
     ) -> anyhow::Result<()> {
@@ -92,11 +133,21 @@ impl SnapshotCreator {
 
         let latency =
             METRICS.storage_logs_processing_duration[&StorageChunkStage::LoadFromPostgres].start();
-        let logs = conn
-            .snapshots_creator_dal()
-            .get_storage_logs_chunk(miniblock_number, l1_batch_number, hashed_keys_range)
-            .await
-            .context("Error fetching storage logs count")?;
+        let logs = if let Some(base_miniblock_number) = base_miniblock_number {
+            conn.snapshots_creator_dal()
+                .get_storage_logs_diff_chunk(
+                    miniblock_number,
+                    l1_batch_number,
+                    base_miniblock_number,
+                    hashed_keys_range,
+                )
+                .await
+        } else {
+            conn.snapshots_creator_dal()
+                .get_storage_logs_chunk(miniblock_number, l1_batch_number, hashed_keys_range)
+                .await
+        };
+        let logs = logs.context("Error fetching storage logs chunk")?;
         drop(conn);
         let latency = latency.observe();
         tracing::info!(
@@ -111,9 +162,7 @@ impl SnapshotCreator {
             l1_batch_number,
             chunk_id,
         };
-        let filename = self
-            .blob_store
-            .put(key, &storage_logs_chunk)
+        let (filename, chunk_hash) = put_hashed_blob(&*self.blob_store, key, &storage_logs_chunk)
             .await
             .context("Error storing storage logs chunk in blob store")?;
         let output_filepath_prefix = self
@@ -128,7 +177,12 @@ impl SnapshotCreator {
             .await?;
         master_conn
             .snapshots_dal()
-            .add_storage_logs_filepath_for_snapshot(l1_batch_number, chunk_id, &output_filepath)
+            .add_storage_logs_filepath_for_snapshot(
+                l1_batch_number,
+                chunk_id,
+                &output_filepath,
+                chunk_hash,
+            )
             .await?;
         #[cfg(test)]
         self.event_listener.on_chunk_saved();
@@ -145,16 +199,22 @@ impl SnapshotCreator {
         &self,
         miniblock_number: MiniblockNumber,
         l1_batch_number: L1BatchNumber,
-    ) -> anyhow::Result<String> {
+        base_miniblock_number: Option<MiniblockNumber>,
+    ) -> anyhow::Result<(String, H256)> {
         let mut conn = self.connect_to_replica().await?;
 
         tracing::info!("Loading factory deps from Postgres...");
         let latency =
             METRICS.factory_deps_processing_duration[&FactoryDepsStage::LoadFromPostgres].start();
-        let factory_deps = conn
-            .snapshots_creator_dal()
-            .get_all_factory_deps(miniblock_number)
-            .await?;
+        let factory_deps = if let Some(base_miniblock_number) = base_miniblock_number {
+            conn.snapshots_creator_dal()
+                .get_new_factory_deps(base_miniblock_number, miniblock_number)
+                .await?
+        } else {
+            conn.snapshots_creator_dal()
+                .get_all_factory_deps(miniblock_number)
+                .await?
+        };
         drop(conn);
         let latency = latency.observe();
         tracing::info!("Loaded {} factory deps in {latency:?}", factory_deps.len());
@@ -169,11 +229,10 @@ impl SnapshotCreator {
             })
             .collect();
         let factory_deps = SnapshotFactoryDependencies { factory_deps };
-        let filename = self
-            .blob_store
-            .put(l1_batch_number, &factory_deps)
-            .await
-            .context("Error storing factory deps in blob store")?;
+        let (filename, factory_deps_hash) =
+            put_hashed_blob(&*self.blob_store, l1_batch_number, &factory_deps)
+                .await
+                .context("Error storing factory deps in blob store")?;
         let output_filepath_prefix = self
             .blob_store
             .get_storage_prefix::<SnapshotFactoryDependencies>();
@@ -184,7 +243,7 @@ impl SnapshotCreator {
             factory_deps.factory_deps.len()
         );
 
-        Ok(output_filepath)
+        Ok((output_filepath, factory_deps_hash))
     }
 
     /// Returns `Ok(None)` if the created snapshot would coincide with `latest_snapshot`.
@@ -212,21 +271,84 @@ impl SnapshotCreator {
             return Ok(None);
         }
 
-        let distinct_storage_logs_keys_count = conn
-            .snapshots_creator_dal()
-            .get_distinct_storage_logs_keys_count(l1_batch_number)
-            .await?;
+        let base_l1_batch_number =
+            Self::select_base_snapshot(config, latest_snapshot, conn).await?;
+        let storage_logs_count = if let Some(base_l1_batch_number) = base_l1_batch_number {
+            let base_miniblock_number =
+                Self::last_miniblock_in_batch(conn, base_l1_batch_number).await?;
+            let miniblock_number = Self::last_miniblock_in_batch(conn, l1_batch_number).await?;
+            conn.snapshots_creator_dal()
+                .get_changed_storage_logs_count(base_miniblock_number, miniblock_number)
+                .await?
+        } else {
+            conn.snapshots_creator_dal()
+                .get_distinct_storage_logs_keys_count(l1_batch_number)
+                .await?
+        };
         let chunk_size = config.storage_logs_chunk_size;
         // We force the minimum number of chunks to avoid situations where only one chunk is created in tests.
-        let chunk_count = distinct_storage_logs_keys_count
-            .div_ceil(chunk_size)
-            .max(min_chunk_count);
+        let chunk_count = storage_logs_count.div_ceil(chunk_size).max(min_chunk_count);
 
         tracing::info!(
-            "Selected storage logs chunking for L1 batch {l1_batch_number}: \
+            "Selected storage logs chunking for L1 batch {l1_batch_number} (base snapshot: {base_l1_batch_number:?}): \
             {chunk_count} chunks of expected size {chunk_size}"
         );
-        Ok(Some(SnapshotProgress::new(l1_batch_number, chunk_count)))
+        Ok(Some(SnapshotProgress::new(
+            l1_batch_number,
+            base_l1_batch_number,
+            chunk_count,
+        )))
+    }
+
+    /// Selects the base snapshot for a new snapshot. Returns `Ok(None)` if a full snapshot should be created.
+    async fn select_base_snapshot(
+        config: &SnapshotsCreatorConfig,
+        latest_snapshot: Option<&SnapshotMetadata>,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        if config.max_incremental_snapshots == 0 {
+            return Ok(None);
+        }
+        let Some(latest_snapshot) = latest_snapshot.filter(|snapshot| snapshot.is_complete())
+        else {
+            return Ok(None);
+        };
+
+        // Compute the number of incremental snapshots in the chain ending with the latest snapshot.
+        let mut incremental_snapshot_count = 0;
+        let mut base_l1_batch_number = latest_snapshot.base_l1_batch_number;
+        while let Some(l1_batch_number) = base_l1_batch_number {
+            incremental_snapshot_count += 1;
+            if incremental_snapshot_count >= config.max_incremental_snapshots {
+                return Ok(None);
+            }
+            let base_snapshot = conn
+                .snapshots_dal()
+                .get_snapshot_metadata(l1_batch_number)
+                .await?;
+            let Some(base_snapshot) = base_snapshot else {
+                tracing::warn!(
+                    "Base snapshot for L1 batch #{l1_batch_number} is missing; creating a full snapshot"
+                );
+                return Ok(None);
+            };
+            base_l1_batch_number = base_snapshot.base_l1_batch_number;
+        }
+        Ok(Some(latest_snapshot.l1_batch_number))
+    }
+
+    async fn last_miniblock_in_batch(
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<MiniblockNumber> {
+        let (_, last_miniblock_number) = conn
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await?
+            .with_context(|| {
+                format!("Error fetching last miniblock number for L1 batch #{l1_batch_number}")
+            })?;
+        Ok(last_miniblock_number)
     }
 
     /// Returns `Ok(None)` if a snapshot should not be created / resumed.
@@ -276,28 +398,45 @@ impl SnapshotCreator {
             .load_or_initialize_snapshot_progress(&config, min_chunk_count)
             .await?
         else {
-            // No snapshot creation is necessary; a snapshot for the current L1 batch is already created
+            // No snapshot creation is necessary; a snapshot for the current L1 batch is already created.
+            // Its manifest may be unsigned yet, e.g. if the tree lagged behind during the previous run.
+            self.sign_manifest_if_necessary(None).await?;
             return Ok(());
         };
 
         let mut conn = self.connect_to_replica().await?;
-        let (_, last_miniblock_number_in_batch) = conn
-            .blocks_dal()
-            .get_miniblock_range_of_l1_batch(progress.l1_batch_number)
-            .await?
-            .context("Error fetching last miniblock number")?;
+        let last_miniblock_number_in_batch =
+            Self::last_miniblock_in_batch(&mut conn, progress.l1_batch_number).await?;
+        let base_miniblock_number = match progress.base_l1_batch_number {
+            Some(base_l1_batch_number) => {
+                Some(Self::last_miniblock_in_batch(&mut conn, base_l1_batch_number).await?)
+            }
+            None => None,
+        };
         drop(conn);
 
         METRICS.storage_logs_chunks_count.set(progress.chunk_count);
-        tracing::info!(
-            "Creating snapshot for storage logs up to miniblock {last_miniblock_number_in_batch}, \
-            L1 batch {}",
-            progress.l1_batch_number
-        );
+        if let Some(base_l1_batch_number) = progress.base_l1_batch_number {
+            tracing::info!(
+                "Creating incremental snapshot for storage logs up to miniblock {last_miniblock_number_in_batch}, \
+                L1 batch {} based on snapshot for L1 batch {base_l1_batch_number}",
+                progress.l1_batch_number
+            );
+        } else {
+            tracing::info!(
+                "Creating snapshot for storage logs up to miniblock {last_miniblock_number_in_batch}, \
+                L1 batch {}",
+                progress.l1_batch_number
+            );
+        }
 
         if progress.is_new_snapshot {
-            let factory_deps_output_file = self
-                .process_factory_deps(last_miniblock_number_in_batch, progress.l1_batch_number)
+            let (factory_deps_output_file, factory_deps_hash) = self
+                .process_factory_deps(
+                    last_miniblock_number_in_batch,
+                    progress.l1_batch_number,
+                    base_miniblock_number,
+                )
                 .await?;
 
             let mut master_conn = self
@@ -308,8 +447,10 @@ impl SnapshotCreator {
                 .snapshots_dal()
                 .add_snapshot(
                     progress.l1_batch_number,
+                    progress.base_l1_batch_number,
                     progress.chunk_count,
                     &factory_deps_output_file,
+                    factory_deps_hash,
                 )
                 .await?;
         }
@@ -323,11 +464,19 @@ impl SnapshotCreator {
                 &semaphore,
                 last_miniblock_number_in_batch,
                 progress.l1_batch_number,
+                base_miniblock_number,
                 chunk_id,
                 progress.chunk_count,
             )
         });
         futures::future::try_join_all(tasks).await?;
+        self.sign_manifest_if_necessary(Some(progress.l1_batch_number))
+            .await?;
+
+        if let Some(retained_full_snapshots) = config.retained_full_snapshots {
+            self.remove_obsolete_snapshots(retained_full_snapshots)
+                .await?;
+        }
 
         METRICS
             .snapshot_l1_batch
@@ -342,4 +491,152 @@ impl SnapshotCreator {
         );
         Ok(())
     }
+
+    /// Signs the manifest for the snapshot with the specified L1 batch number (or the newest snapshot
+    /// if the number is not specified) unless it is incomplete or already signed.
+    async fn sign_manifest_if_necessary(
+        &self,
+        l1_batch_number: Option<L1BatchNumber>,
+    ) -> anyhow::Result<()> {
+        let Some(manifest_signer) = &self.manifest_signer else {
+            return Ok(());
+        };
+
+        let mut master_conn = self
+            .master_pool
+            .connection_tagged("snapshots_creator")
+            .await?;
+        let snapshot = if let Some(l1_batch_number) = l1_batch_number {
+            master_conn
+                .snapshots_dal()
+                .get_snapshot_metadata(l1_batch_number)
+                .await?
+        } else {
+            master_conn
+                .snapshots_dal()
+                .get_newest_snapshot_metadata()
+                .await?
+        };
+        let Some(snapshot) = snapshot else {
+            return Ok(());
+        };
+        if !snapshot.is_complete() || snapshot.manifest_signature.is_some() {
+            return Ok(());
+        }
+
+        let l1_batch_number = snapshot.l1_batch_number;
+        let storage_logs_chunk_hashes: Option<Vec<_>> =
+            snapshot.storage_logs_hashes.iter().copied().collect();
+        let (Some(factory_deps_hash), Some(storage_logs_chunk_hashes)) =
+            (snapshot.factory_deps_hash, storage_logs_chunk_hashes)
+        else {
+            tracing::warn!(
+                "Snapshot for L1 batch #{l1_batch_number} has no recorded file hashes; its manifest cannot be signed"
+            );
+            return Ok(());
+        };
+        let l1_batch_root_hash = master_conn
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await?;
+        let Some(l1_batch_root_hash) = l1_batch_root_hash else {
+            tracing::info!(
+                "Root hash for L1 batch #{l1_batch_number} is not computed yet; snapshot manifest will be signed on a later run"
+            );
+            return Ok(());
+        };
+        let miniblock_number =
+            Self::last_miniblock_in_batch(&mut master_conn, l1_batch_number).await?;
+
+        let manifest = SnapshotManifest {
+            l1_batch_number,
+            base_l1_batch_number: snapshot.base_l1_batch_number,
+            miniblock_number,
+            l1_batch_root_hash,
+            factory_deps_hash,
+            storage_logs_chunk_hashes,
+        };
+        let domain = Eip712Domain::new(manifest_signer.chain_id);
+        let signature = manifest_signer
+            .signer
+            .sign_typed_data(&domain, &manifest)
+            .await
+            .context("failed signing snapshot manifest")?;
+        master_conn
+            .snapshots_dal()
+            .set_manifest_signature(l1_batch_number, &signature)
+            .await?;
+        tracing::info!("Signed manifest for snapshot for L1 batch #{l1_batch_number}");
+        Ok(())
+    }
+
+    /// Removes all snapshots older than the `retained_full_snapshots`th newest complete full snapshot.
+    /// Since incremental snapshots are always based on the preceding snapshot, this never breaks
+    /// a snapshot chain for retained snapshots.
+    async fn remove_obsolete_snapshots(&self, retained_full_snapshots: u32) -> anyhow::Result<()> {
+        let mut master_conn = self
+            .master_pool
+            .connection_tagged("snapshots_creator")
+            .await?;
+        let all_snapshots = master_conn
+            .snapshots_dal()
+            .get_all_snapshots_metadata()
+            .await?;
+        drop(master_conn);
+
+        let retained_full_snapshots = retained_full_snapshots.max(1) as usize;
+        let oldest_retained_snapshot = all_snapshots
+            .iter()
+            .filter(|snapshot| !snapshot.is_incremental() && snapshot.is_complete())
+            .nth(retained_full_snapshots - 1);
+        let Some(oldest_retained_snapshot) = oldest_retained_snapshot else {
+            return Ok(());
+        };
+        let obsolete_snapshots = all_snapshots
+            .iter()
+            .filter(|snapshot| snapshot.l1_batch_number < oldest_retained_snapshot.l1_batch_number);
+        for snapshot in obsolete_snapshots {
+            self.remove_snapshot(snapshot).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_snapshot(&self, snapshot: &SnapshotMetadata) -> anyhow::Result<()> {
+        let l1_batch_number = snapshot.l1_batch_number;
+        tracing::info!("Removing obsolete snapshot for L1 batch #{l1_batch_number}");
+
+        // Chunks are removed regardless of whether they are recorded in Postgres since a chunk
+        // may have been uploaded before the snapshot creator was interrupted.
+        let chunk_keys = (0..snapshot.storage_logs_filepaths.len() as u64).map(|chunk_id| {
+            SnapshotStorageLogsChunk::encode_key(SnapshotStorageLogsStorageKey {
+                l1_batch_number,
+                chunk_id,
+            })
+        });
+        let factory_deps_key = SnapshotFactoryDependencies::encode_key(l1_batch_number);
+        for key in chunk_keys.chain([factory_deps_key]) {
+            match self
+                .blob_store
+                .remove_raw(SnapshotStorageLogsChunk::BUCKET, &key)
+                .await
+            {
+                Ok(()) | Err(ObjectStoreError::KeyNotFound(_)) => { /* OK */ }
+                Err(err) => {
+                    return Err(anyhow::Error::from(err)
+                        .context(format!("failed removing snapshot file `{key}`")));
+                }
+            }
+        }
+
+        let mut master_conn = self
+            .master_pool
+            .connection_tagged("snapshots_creator")
+            .await?;
+        master_conn
+            .snapshots_dal()
+            .delete_snapshot(l1_batch_number)
+            .await?;
+        METRICS.removed_snapshots.inc();
+        Ok(())
+    }
 }
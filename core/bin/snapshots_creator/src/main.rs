@@ -9,18 +9,22 @@
 //! It is assumed that the snapshot creator is run as a singleton process (no more than 1 instance
 //! at a time).
 
+use std::env;
+
 use anyhow::Context as _;
 use prometheus_exporter::PrometheusExporterConfig;
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_config::{
-    configs::{ObservabilityConfig, PrometheusConfig},
+    configs::{chain::NetworkConfig, ObservabilityConfig, PrometheusConfig},
     PostgresConfig, SnapshotsCreatorConfig,
 };
 use zksync_dal::{ConnectionPool, Core};
 use zksync_env_config::{object_store::SnapshotsObjectStoreConfig, FromEnv};
+use zksync_eth_signer::PrivateKeySigner;
 use zksync_object_store::ObjectStoreFactory;
+use zksync_types::H256;
 
-use crate::creator::SnapshotCreator;
+use crate::creator::{ManifestSigner, SnapshotCreator};
 
 mod creator;
 mod metrics;
@@ -46,6 +50,29 @@ async fn maybe_enable_prometheus_metrics(
     }
 }
 
+/// Env variable with the operator private key (same as used by `ETHSenderConfig`).
+const OPERATOR_PRIVATE_KEY_VAR: &str = "ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY";
+
+/// Loads the operator key used to sign snapshot manifests. Returns `Ok(None)` if the key is not configured;
+/// errors if the key is configured, but cannot be loaded.
+fn load_manifest_signer() -> anyhow::Result<Option<ManifestSigner>> {
+    let private_key = match env::var(OPERATOR_PRIVATE_KEY_VAR) {
+        Ok(private_key) => private_key,
+        Err(env::VarError::NotPresent) => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed reading `{OPERATOR_PRIVATE_KEY_VAR}`"))
+        }
+    };
+    let private_key: H256 = private_key
+        .parse()
+        .with_context(|| format!("`{OPERATOR_PRIVATE_KEY_VAR}` is not a valid private key"))?;
+    let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    Ok(Some(ManifestSigner {
+        signer: PrivateKeySigner::new(private_key),
+        chain_id: network_config.zksync_network_id,
+    }))
+}
+
 /// Minimum number of storage log chunks to produce.
 const MIN_CHUNK_COUNT: u64 = 10;
 
@@ -92,10 +119,18 @@ async fn main() -> anyhow::Result<()> {
         .build()
         .await?;
 
+    let manifest_signer = load_manifest_signer()?;
+    if manifest_signer.is_none() {
+        tracing::warn!(
+            "Operator private key is not configured; snapshot manifests will not be signed"
+        );
+    }
+
     let creator = SnapshotCreator {
         blob_store,
         master_pool,
         replica_pool,
+        manifest_signer,
         #[cfg(test)]
         event_listener: Box::new(()),
     };
@@ -11,15 +11,16 @@ use std::{
 
 use rand::{thread_rng, Rng};
 use zksync_dal::{Connection, CoreDal};
-use zksync_object_store::ObjectStore;
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        snapshot_blob_hash, SnapshotFactoryDependencies, SnapshotFactoryDependency,
+        SnapshotManifest, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
-    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, ProtocolVersion, StorageKey,
-    StorageLog, H256,
+    AccountTreeId, Address, L1BatchNumber, L2ChainId, MiniblockNumber, PackedEthSignature,
+    ProtocolVersion, StorageKey, StorageLog, H256,
 };
 
 use super::*;
@@ -27,10 +28,14 @@ use super::*;
 const TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 10,
+    max_incremental_snapshots: 0,
+    retained_full_snapshots: None,
 };
 const SEQUENTIAL_TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 1,
+    max_incremental_snapshots: 0,
+    retained_full_snapshots: None,
 };
 
 #[derive(Debug)]
@@ -66,10 +71,21 @@ impl SnapshotCreator {
             blob_store,
             master_pool: pool.clone(),
             replica_pool: pool,
+            manifest_signer: None,
             event_listener: Box::new(()),
         }
     }
 
+    fn with_manifest_signer(self, private_key: H256) -> Self {
+        Self {
+            manifest_signer: Some(ManifestSigner {
+                signer: PrivateKeySigner::new(private_key),
+                chain_id: L2ChainId::default(),
+            }),
+            ..self
+        }
+    }
+
     fn stop_after_chunk_count(self, stop_after_chunk_count: usize) -> Self {
         Self {
             event_listener: Box::new(TestEventListener::new(stop_after_chunk_count)),
@@ -329,20 +345,31 @@ async fn persisting_snapshot_logs() {
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
 }
 
-async fn assert_storage_logs(
+async fn load_storage_logs(
     object_store: &dyn ObjectStore,
     snapshot_l1_batch_number: L1BatchNumber,
-    expected_outputs: &ExpectedOutputs,
-) {
-    let mut actual_logs = HashSet::new();
+) -> Vec<SnapshotStorageLog> {
+    let mut logs = vec![];
     for chunk_id in 0..MIN_CHUNK_COUNT {
         let key = SnapshotStorageLogsStorageKey {
             l1_batch_number: snapshot_l1_batch_number,
             chunk_id,
         };
         let chunk: SnapshotStorageLogsChunk = object_store.get(key).await.unwrap();
-        actual_logs.extend(chunk.storage_logs.into_iter());
+        logs.extend(chunk.storage_logs);
     }
+    logs
+}
+
+async fn assert_storage_logs(
+    object_store: &dyn ObjectStore,
+    snapshot_l1_batch_number: L1BatchNumber,
+    expected_outputs: &ExpectedOutputs,
+) {
+    let actual_logs: HashSet<_> = load_storage_logs(object_store, snapshot_l1_batch_number)
+        .await
+        .into_iter()
+        .collect();
     assert_eq!(actual_logs, expected_outputs.storage_logs);
 }
 
@@ -456,3 +483,245 @@ async fn recovery_workflow_with_varying_chunk_size() {
     let object_store = object_store_factory.create_store().await;
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
 }
+
+/// Adds a miniblock and an L1 batch with the same number containing new and updated storage logs.
+async fn add_l1_batch(
+    conn: &mut Connection<'_, Core>,
+    number: u32,
+    new_logs: Vec<StorageLog>,
+    updated_logs: Vec<StorageLog>,
+) {
+    let all_logs = new_logs.iter().copied().chain(updated_logs).collect();
+    create_miniblock(conn, MiniblockNumber(number), all_logs).await;
+    create_l1_batch(conn, L1BatchNumber(number), &new_logs).await;
+}
+
+#[tokio::test]
+async fn creating_incremental_snapshots() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.connection().await.unwrap();
+    let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
+    let config = SnapshotsCreatorConfig {
+        max_incremental_snapshots: 1,
+        ..TEST_CONFIG
+    };
+
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(config.clone(), MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+
+    let updated_logs: Vec<_> = expected_outputs
+        .storage_logs
+        .iter()
+        .take(20)
+        .map(|log| StorageLog::new_write_log(log.key, H256(rng.gen())))
+        .collect();
+    add_l1_batch(&mut conn, 10, gen_storage_logs(&mut rng, 30), updated_logs).await;
+    add_l1_batch(&mut conn, 11, gen_storage_logs(&mut rng, 10), vec![]).await;
+
+    let object_store = object_store_factory.create_store().await;
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(config.clone(), MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(L1BatchNumber(10))
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    assert!(snapshot_metadata.is_complete());
+    assert_eq!(
+        snapshot_metadata.base_l1_batch_number,
+        Some(L1BatchNumber(8))
+    );
+
+    // The incremental snapshot must only contain factory deps for miniblocks #9 and #10.
+    let object_store = object_store_factory.create_store().await;
+    let SnapshotFactoryDependencies { factory_deps } =
+        object_store.get(L1BatchNumber(10)).await.unwrap();
+    assert_eq!(factory_deps.len(), 10);
+    assert!(factory_deps
+        .iter()
+        .all(|dep| !expected_outputs.deps.contains(dep)));
+
+    // Applying the incremental snapshot on top of the base one must produce the full storage state.
+    let mut merged_logs: HashMap<_, _> = load_storage_logs(&*object_store, L1BatchNumber(8))
+        .await
+        .into_iter()
+        .map(|log| (log.key, log))
+        .collect();
+    let diff_logs = load_storage_logs(&*object_store, L1BatchNumber(10)).await;
+    assert_eq!(diff_logs.len(), 100 + 30 + 20); // L1 batch #9 + new logs + updated logs
+    merged_logs.extend(diff_logs.into_iter().map(|log| (log.key, log)));
+    let merged_logs: HashSet<_> = merged_logs.into_values().collect();
+
+    let expected_logs: HashSet<_> = conn
+        .snapshots_creator_dal()
+        .get_storage_logs_chunk(
+            MiniblockNumber(10),
+            L1BatchNumber(10),
+            H256::zero()..=H256::repeat_byte(0xff),
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(merged_logs, expected_logs);
+
+    // The maximum number of incremental snapshots is reached, so the next snapshot must be full.
+    add_l1_batch(&mut conn, 12, gen_storage_logs(&mut rng, 10), vec![]).await;
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(config, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_newest_snapshot_metadata()
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    assert_eq!(snapshot_metadata.l1_batch_number, L1BatchNumber(11));
+    assert_eq!(snapshot_metadata.base_l1_batch_number, None);
+}
+
+#[tokio::test]
+async fn removing_obsolete_snapshots() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+    let config = SnapshotsCreatorConfig {
+        retained_full_snapshots: Some(1),
+        ..TEST_CONFIG
+    };
+
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(config.clone(), MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    add_l1_batch(&mut conn, 10, gen_storage_logs(&mut rng, 10), vec![]).await;
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(config, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+
+    let snapshots = conn
+        .snapshots_dal()
+        .get_all_complete_snapshots()
+        .await
+        .unwrap();
+    assert_eq!(snapshots.snapshots_l1_batch_numbers, [L1BatchNumber(9)]);
+
+    let err = object_store
+        .get::<SnapshotFactoryDependencies>(L1BatchNumber(8))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+    for chunk_id in 0..MIN_CHUNK_COUNT {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: L1BatchNumber(8),
+            chunk_id,
+        };
+        let err = object_store
+            .get::<SnapshotStorageLogsChunk>(key)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+    }
+    load_storage_logs(&*object_store, L1BatchNumber(9)).await;
+}
+
+#[tokio::test]
+async fn signing_snapshot_manifest() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+
+    let private_key = H256::repeat_byte(0x42);
+    let operator_address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .with_manifest_signer(private_key)
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(snapshot_l1_batch_number)
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    // The root hash for the snapshot L1 batch is not computed, so the manifest cannot be signed yet.
+    assert_eq!(snapshot_metadata.manifest_signature, None);
+
+    let factory_deps_key = SnapshotFactoryDependencies::encode_key(snapshot_l1_batch_number);
+    let factory_deps_bytes = object_store
+        .get_raw(SnapshotFactoryDependencies::BUCKET, &factory_deps_key)
+        .await
+        .unwrap();
+    assert_eq!(
+        snapshot_metadata.factory_deps_hash,
+        Some(snapshot_blob_hash(&factory_deps_bytes))
+    );
+    for (chunk_id, hash) in snapshot_metadata.storage_logs_hashes.iter().enumerate() {
+        let key = SnapshotStorageLogsChunk::encode_key(SnapshotStorageLogsStorageKey {
+            l1_batch_number: snapshot_l1_batch_number,
+            chunk_id: chunk_id as u64,
+        });
+        let chunk_bytes = object_store
+            .get_raw(SnapshotStorageLogsChunk::BUCKET, &key)
+            .await
+            .unwrap();
+        assert_eq!(*hash, Some(snapshot_blob_hash(&chunk_bytes)));
+    }
+
+    let root_hash = H256::repeat_byte(0xff);
+    conn.blocks_dal()
+        .set_l1_batch_hash(snapshot_l1_batch_number, root_hash)
+        .await
+        .unwrap();
+    // The snapshot is already created, so the creator should only sign its manifest.
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .with_manifest_signer(private_key)
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(snapshot_l1_batch_number)
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    let signature = snapshot_metadata
+        .manifest_signature
+        .expect("manifest is not signed");
+    let manifest = SnapshotManifest {
+        l1_batch_number: snapshot_l1_batch_number,
+        base_l1_batch_number: None,
+        miniblock_number: MiniblockNumber(8),
+        l1_batch_root_hash: root_hash,
+        factory_deps_hash: snapshot_metadata.factory_deps_hash.unwrap(),
+        storage_logs_chunk_hashes: snapshot_metadata
+            .storage_logs_hashes
+            .iter()
+            .map(|hash| hash.unwrap())
+            .collect(),
+    };
+    let signer = manifest
+        .recover_signer(L2ChainId::default(), &signature)
+        .unwrap();
+    assert_eq!(signer, operator_address);
+}
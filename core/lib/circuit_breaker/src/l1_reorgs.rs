@@ -0,0 +1,32 @@
+use anyhow::Context as _;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+use crate::{CircuitBreaker, CircuitBreakerError};
+
+/// Trips if an L1 reorg that cannot be handled automatically was detected (e.g., a reorg removing
+/// priority operations that were already executed on L2).
+#[derive(Debug)]
+pub struct FatalL1ReorgChecker {
+    pub pool: ConnectionPool<Core>,
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for FatalL1ReorgChecker {
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let reorg = self
+            .pool
+            .connection_tagged("circuit_breaker")
+            .await?
+            .l1_reorgs_dal()
+            .get_last_fatal_l1_reorg()
+            .await
+            .context("cannot get last fatal L1 reorg")?;
+        if let Some(reorg) = reorg {
+            return Err(CircuitBreakerError::FatalL1Reorg {
+                component: reorg.component,
+                l1_block: reorg.reverted_to_l1_block,
+            });
+        }
+        Ok(())
+    }
+}
@@ -4,8 +4,11 @@ use thiserror::Error;
 use tokio::sync::{oneshot, watch};
 use zksync_config::configs::chain::CircuitBreakerConfig;
 
+pub mod l1_reorgs;
 pub mod l1_txs;
 mod metrics;
+pub mod operator_balance;
+pub mod priority_ops;
 pub mod replication_lag;
 
 #[derive(Debug, Error)]
@@ -14,6 +17,20 @@ pub enum CircuitBreakerError {
     FailedL1Transaction,
     #[error("Replication lag ({0}) is above the threshold ({1})")]
     ReplicationLag(u32, u32),
+    #[error(
+        "Fatal L1 reorg detected by {component}; L1 block #{l1_block} is the last canonical one"
+    )]
+    FatalL1Reorg { component: String, l1_block: u64 },
+    #[error(
+        "Balance of operator {address} ({balance_gwei} gwei) is below the minimum ({min_balance_gwei} gwei)"
+    )]
+    OperatorBalanceTooLow {
+        address: String,
+        balance_gwei: u64,
+        min_balance_gwei: u64,
+    },
+    #[error("Priority operation #{op_id} is {seconds_left}s away from its deadline on L1")]
+    PriorityOpNearDeadline { op_id: u64, seconds_left: u64 },
     #[error("Internal error running circuit breaker checks")]
     Internal(#[from] anyhow::Error),
 }
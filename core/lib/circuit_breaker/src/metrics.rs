@@ -1,12 +1,15 @@
 //! Circuit breaker metrics.
 
-use vise::{Gauge, Global, Metrics};
+use vise::{Gauge, Global, LabeledFamily, Metrics};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "circuit_breaker")]
 pub(crate) struct CircuitBreakerMetrics {
     /// Replication lag for Postgres in seconds.
     pub replication_lag: Gauge<u64>,
+    /// ETH balance of operator accounts in gwei.
+    #[metrics(labels = ["address"])]
+    pub operator_balance_gwei: LabeledFamily<String, Gauge<u64>>,
 }
 
 #[vise::register]
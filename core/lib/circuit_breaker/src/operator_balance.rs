@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use zksync_eth_client::BoundEthInterface;
+use zksync_types::U256;
+
+use crate::{metrics::METRICS, CircuitBreaker, CircuitBreakerError};
+
+/// Trips if the ETH balance of any operator account drops below the configured minimum.
+#[derive(Debug)]
+pub struct OperatorBalanceChecker {
+    pub accounts: Vec<Arc<dyn BoundEthInterface>>,
+    pub min_balance_gwei: u64,
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for OperatorBalanceChecker {
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let min_balance = U256::from(self.min_balance_gwei) * U256::exp10(9);
+        for account in &self.accounts {
+            let address = account.sender_account();
+            let balance = account
+                .sender_eth_balance("circuit_breaker")
+                .await
+                .with_context(|| format!("cannot get ETH balance of operator {address:?}"))?;
+            let balance_gwei = u64::try_from(balance / U256::exp10(9)).unwrap_or(u64::MAX);
+            METRICS.operator_balance_gwei[&format!("{address:?}")].set(balance_gwei);
+
+            if balance < min_balance {
+                return Err(CircuitBreakerError::OperatorBalanceTooLow {
+                    address: format!("{address:?}"),
+                    balance_gwei,
+                    min_balance_gwei: self.min_balance_gwei,
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context as _;
+use zksync_eth_client::{CallFunctionArgs, EthInterface};
+use zksync_types::{
+    ethabi::{self, Token},
+    web3::contract::tokens::Detokenize,
+    Address, PriorityOpId, H256, U256,
+};
+
+use crate::{CircuitBreaker, CircuitBreakerError};
+
+/// Front of the priority queue in the diamond proxy, i.e., the oldest priority operation (L1 -> L2 transaction)
+/// not yet processed by an L1 batch executed on L1. Priority operations are processed in order, so this operation
+/// has the earliest deadline among all pending ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1PriorityQueueFront {
+    pub id: PriorityOpId,
+    pub canonical_tx_hash: H256,
+    /// Number of priority operations in the L1 queue.
+    pub queue_size: u64,
+    /// UNIX timestamp (in seconds) until which the operation must be processed, as enforced by L1 contracts.
+    pub expiration_timestamp: u64,
+}
+
+impl L1PriorityQueueFront {
+    /// Loads the front of the priority queue from the diamond proxy. Returns `None` if the queue is empty.
+    pub async fn load(
+        eth_client: &dyn EthInterface,
+        diamond_proxy_addr: Address,
+        diamond_proxy_abi: &ethabi::Contract,
+    ) -> anyhow::Result<Option<Self>> {
+        let call = |function_name: &'static str| {
+            let call = CallFunctionArgs::new(function_name, ())
+                .for_contract(diamond_proxy_addr, diamond_proxy_abi.clone());
+            async move {
+                eth_client
+                    .call_contract_function(call)
+                    .await
+                    .with_context(|| format!("failed calling `{function_name}` on diamond proxy"))
+            }
+        };
+
+        let queue_size = U256::from_tokens(call("getPriorityQueueSize").await?)
+            .context("invalid `getPriorityQueueSize` output")?
+            .as_u64();
+        if queue_size == 0 {
+            return Ok(None);
+        }
+        let id = U256::from_tokens(call("getFirstUnprocessedPriorityTx").await?)
+            .context("invalid `getFirstUnprocessedPriorityTx` output")?
+            .as_u64();
+        let front_op = call("priorityQueueFrontOperation").await?;
+        let (canonical_tx_hash, expiration_timestamp) = Self::parse_front_operation(front_op)
+            .context("invalid `priorityQueueFrontOperation` output")?;
+        Ok(Some(Self {
+            id: PriorityOpId(id),
+            canonical_tx_hash,
+            queue_size,
+            expiration_timestamp,
+        }))
+    }
+
+    /// Parses the `PriorityOperation` struct consisting of `canonicalTxHash`, `expirationTimestamp`
+    /// and `layer2Tip` fields.
+    fn parse_front_operation(tokens: Vec<Token>) -> Option<(H256, u64)> {
+        let [Token::Tuple(fields)] = tokens.as_slice() else {
+            return None;
+        };
+        let [Token::FixedBytes(hash), Token::Uint(expiration_timestamp), _] = fields.as_slice()
+        else {
+            return None;
+        };
+        (hash.len() == 32).then(|| (H256::from_slice(hash), expiration_timestamp.as_u64()))
+    }
+
+    /// Returns the time left until the operation expires as of `now` (a UNIX timestamp in seconds);
+    /// zero if the operation has already expired.
+    pub fn time_to_deadline(&self, now: u64) -> Duration {
+        Duration::from_secs(self.expiration_timestamp.saturating_sub(now))
+    }
+}
+
+/// Current UNIX timestamp in seconds.
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("incorrect system time")
+        .as_secs()
+}
+
+/// Trips if the oldest priority operation not processed on L1 nears its deadline enforced by L1 contracts.
+/// Missing the deadline would freeze the chain.
+#[derive(Debug)]
+pub struct PriorityOpsDeadlineChecker {
+    pub eth_client: Arc<dyn EthInterface>,
+    pub diamond_proxy_addr: Address,
+    pub diamond_proxy_abi: ethabi::Contract,
+    pub deadline_margin: Duration,
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for PriorityOpsDeadlineChecker {
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let front_op = L1PriorityQueueFront::load(
+            self.eth_client.as_ref(),
+            self.diamond_proxy_addr,
+            &self.diamond_proxy_abi,
+        )
+        .await?;
+        let Some(front_op) = front_op else {
+            return Ok(());
+        };
+
+        let time_to_deadline = front_op.time_to_deadline(unix_timestamp());
+        if time_to_deadline < self.deadline_margin {
+            return Err(CircuitBreakerError::PriorityOpNearDeadline {
+                op_id: front_op.id.0,
+                seconds_left: time_to_deadline.as_secs(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_eth_client::clients::MockEthereum;
+
+    use super::*;
+
+    const DIAMOND_PROXY_ADDR: Address = Address::repeat_byte(0x01);
+
+    fn mock_eth_client(queue_size: u64, expiration_timestamp: u64) -> MockEthereum {
+        MockEthereum::default().with_call_handler(move |call| {
+            assert_eq!(call.contract_address(), DIAMOND_PROXY_ADDR);
+            match call.function_name() {
+                "getPriorityQueueSize" => Token::Uint(queue_size.into()),
+                "getFirstUnprocessedPriorityTx" => Token::Uint(5.into()),
+                "priorityQueueFrontOperation" => Token::Tuple(vec![
+                    Token::FixedBytes(vec![0x23; 32]),
+                    Token::Uint(expiration_timestamp.into()),
+                    Token::Uint(0.into()),
+                ]),
+                name => panic!("unexpected call: {name}"),
+            }
+        })
+    }
+
+    /// Returns an empty ABI; the ABI is not used by the mock client.
+    fn empty_abi() -> ethabi::Contract {
+        ethabi::Contract::load(&b"[]"[..]).unwrap()
+    }
+
+    fn checker(eth_client: MockEthereum) -> PriorityOpsDeadlineChecker {
+        PriorityOpsDeadlineChecker {
+            eth_client: Arc::new(eth_client),
+            diamond_proxy_addr: DIAMOND_PROXY_ADDR,
+            diamond_proxy_abi: empty_abi(),
+            deadline_margin: Duration::from_secs(3_600),
+        }
+    }
+
+    #[tokio::test]
+    async fn loading_l1_priority_queue_front() {
+        let eth_client = mock_eth_client(3, 1_000);
+        let front_op = L1PriorityQueueFront::load(&eth_client, DIAMOND_PROXY_ADDR, &empty_abi())
+            .await
+            .unwrap()
+            .expect("no front operation");
+        assert_eq!(
+            front_op,
+            L1PriorityQueueFront {
+                id: PriorityOpId(5),
+                canonical_tx_hash: H256::repeat_byte(0x23),
+                queue_size: 3,
+                expiration_timestamp: 1_000,
+            }
+        );
+        assert_eq!(front_op.time_to_deadline(400), Duration::from_secs(600));
+        assert_eq!(front_op.time_to_deadline(2_000), Duration::ZERO);
+
+        let eth_client = mock_eth_client(0, 1_000);
+        let front_op = L1PriorityQueueFront::load(&eth_client, DIAMOND_PROXY_ADDR, &empty_abi())
+            .await
+            .unwrap();
+        assert_eq!(front_op, None);
+    }
+
+    #[tokio::test]
+    async fn deadline_checker_basics() {
+        let now = unix_timestamp();
+        checker(mock_eth_client(0, 0)).check().await.unwrap();
+        checker(mock_eth_client(1, now + 7_200))
+            .check()
+            .await
+            .unwrap();
+
+        let err = checker(mock_eth_client(1, now + 600))
+            .check()
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            CircuitBreakerError::PriorityOpNearDeadline { op_id: 5, seconds_left }
+                if seconds_left <= 600
+        );
+        let err = checker(mock_eth_client(1, now - 1))
+            .check()
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            CircuitBreakerError::PriorityOpNearDeadline {
+                op_id: 5,
+                seconds_left: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn deadline_checker_with_unavailable_l1() {
+        let eth_client = mock_eth_client(1, unix_timestamp() + 7_200);
+        eth_client.set_unavailable(true);
+        let err = checker(eth_client).check().await.unwrap_err();
+        assert_matches!(err, CircuitBreakerError::Internal(_));
+    }
+}
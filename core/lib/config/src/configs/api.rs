@@ -1,7 +1,7 @@
 use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
 
 use serde::Deserialize;
-use zksync_basic_types::H256;
+use zksync_basic_types::{Address, H256};
 
 pub use crate::configs::PrometheusConfig;
 
@@ -101,6 +101,40 @@ pub struct Web3JsonRpcConfig {
     pub mempool_cache_update_interval: Option<u64>,
     /// Maximum number of transactions to be stored in the mempool cache. Default is 10000.
     pub mempool_cache_size: Option<usize>,
+    /// Path to a file with addresses that are not allowed to send or receive L2 transactions, one address per line.
+    /// The file is reloaded on modification. If not set, no addresses are denied.
+    pub tx_deny_list_path: Option<String>,
+    /// Addresses allowed to deploy contracts. If not set, anyone can deploy contracts.
+    pub tx_deployer_allow_list: Option<Vec<Address>>,
+    /// Maximum number of pending transactions in the mempool per sender. If not set, the number is not limited
+    /// (apart from the limit on nonces ahead).
+    pub max_pending_txs_per_sender: Option<u32>,
+    /// Minimum max priority fee per gas (in wei) for submitted L2 transactions. If not set, any priority fee is accepted.
+    pub min_priority_fee_per_gas: Option<u64>,
+    /// Enables private mode, in which account-specific data (balances, nonces, transactions, logs etc.) is only returned
+    /// to callers authenticated with a session token signed by the account. Block headers remain public.
+    #[serde(default)]
+    pub private_mode: bool,
+    /// Maximum lifetime of session tokens accepted in the private mode, in seconds. Tokens expiring further
+    /// in the future are rejected. Default is 1 day.
+    pub max_session_token_ttl_sec: Option<u64>,
+    /// Enables the admin `txpool` namespace exposing the mempool content. Only has effect if the state keeper
+    /// runs in the same process as the API server.
+    #[serde(default)]
+    pub txpool_namespace_enabled: bool,
+    /// Enables collecting statistics on the accuracy of `eth_estimateGas` for submitted transactions.
+    /// The statistics are aggregated per contract method and exposed via `zks_getGasEstimationStats` and metrics.
+    ///
+    /// Estimates are matched with submitted transactions in memory of the API server process. Thus, a transaction
+    /// is only sampled if it's submitted to the same API server instance that has estimated it; with several replicas
+    /// behind a load balancer without sticky sessions, only a fraction of transactions is sampled.
+    #[serde(default)]
+    pub gas_estimation_stats_enabled: bool,
+    /// Lower bound for the adaptive gas estimation scale factor. Adaptive scaling is used only if both bounds are set
+    /// and `gas_estimation_stats_enabled` is `true`; otherwise, `estimate_gas_scale_factor` is always used.
+    pub estimate_gas_min_scale_factor: Option<f64>,
+    /// Upper bound for the adaptive gas estimation scale factor.
+    pub estimate_gas_max_scale_factor: Option<f64>,
 }
 
 impl Web3JsonRpcConfig {
@@ -138,9 +172,30 @@ impl Web3JsonRpcConfig {
             mempool_cache_update_interval: Default::default(),
             mempool_cache_size: Default::default(),
             tree_api_url: None,
+            tx_deny_list_path: None,
+            tx_deployer_allow_list: None,
+            max_pending_txs_per_sender: None,
+            min_priority_fee_per_gas: None,
+            private_mode: false,
+            max_session_token_ttl_sec: None,
+            txpool_namespace_enabled: false,
+            gas_estimation_stats_enabled: false,
+            estimate_gas_min_scale_factor: None,
+            estimate_gas_max_scale_factor: None,
         }
     }
 
+    /// Returns bounds for the adaptive gas estimation scale factor, or `None` if adaptive scaling is disabled.
+    pub fn estimate_gas_scale_factor_bounds(&self) -> Option<(f64, f64)> {
+        if !self.gas_estimation_stats_enabled {
+            return None;
+        }
+        Some((
+            self.estimate_gas_min_scale_factor?,
+            self.estimate_gas_max_scale_factor?,
+        ))
+    }
+
     pub fn http_bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.http_port)
     }
@@ -225,6 +280,14 @@ impl Web3JsonRpcConfig {
     pub fn mempool_cache_size(&self) -> usize {
         self.mempool_cache_size.unwrap_or(10_000)
     }
+
+    pub fn tx_deny_list_path(&self) -> Option<&str> {
+        self.tx_deny_list_path.as_deref()
+    }
+
+    pub fn max_session_token_ttl(&self) -> Duration {
+        Duration::from_secs(self.max_session_token_ttl_sec.unwrap_or(86_400))
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -116,6 +116,25 @@ pub struct StateKeeperConfig {
 
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
     pub enum_index_migration_chunk_size: Option<usize>,
+
+    /// Path to a JSON file with the declarative seal policy (e.g., sealing L1 batches after a certain number
+    /// of priority operations or at a fixed wall-clock cadence). The file is reloaded if it changes.
+    #[serde(default)]
+    pub seal_policy_path: Option<String>,
+
+    /// Protocol version of the candidate VM to execute all L1 batches on in the shadow mode. Divergences
+    /// from the main VM are reported via metrics; shadow execution never affects the main execution or sealing.
+    #[serde(default)]
+    pub shadow_vm_protocol_version: Option<u16>,
+    /// Path to a JSON Lines file to append shadow VM divergences to.
+    #[serde(default)]
+    pub shadow_vm_divergence_log_path: Option<String>,
+
+    /// Number of upcoming mempool transactions speculatively pre-executed on worker threads. Pre-execution prefetches
+    /// storage slots and allows rejecting transactions that are certain to fail validation without executing them.
+    /// If not set or 0, pre-execution is disabled.
+    #[serde(default)]
+    pub pre_execution_lookahead: Option<usize>,
 }
 
 impl StateKeeperConfig {
@@ -150,6 +169,10 @@ impl StateKeeperConfig {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: None,
+            seal_policy_path: None,
+            shadow_vm_protocol_version: None,
+            shadow_vm_divergence_log_path: None,
+            pre_execution_lookahead: None,
         }
     }
 
@@ -176,9 +199,21 @@ pub struct CircuitBreakerConfig {
     pub http_req_max_retry_number: usize,
     pub http_req_retry_interval_sec: u8,
     pub replication_lag_limit_sec: Option<u32>,
+    /// Minimum time before the L1 deadline of an unprocessed priority operation, in seconds; the circuit breaker
+    /// trips once the operation gets closer to the deadline. Defaults to 1 hour.
+    pub priority_op_deadline_margin_sec: Option<u64>,
 }
 
 impl CircuitBreakerConfig {
+    pub const DEFAULT_PRIORITY_OP_DEADLINE_MARGIN_SEC: u64 = 3_600;
+
+    pub fn priority_op_deadline_margin(&self) -> Duration {
+        Duration::from_secs(
+            self.priority_op_deadline_margin_sec
+                .unwrap_or(Self::DEFAULT_PRIORITY_OP_DEADLINE_MARGIN_SEC),
+        )
+    }
+
     pub fn sync_interval(&self) -> Duration {
         Duration::from_millis(self.sync_interval_ms)
     }
@@ -188,6 +223,17 @@ impl CircuitBreakerConfig {
     }
 }
 
+/// Policy used by the mempool to free space once it reaches its global limits.
+///  - `PurgeQueued`, drop all accounts that only have queued transactions (i.e., transactions with a nonce gap).
+///  - `LowestPriority`, in addition to purging queued accounts, evict transactions with the highest nonces
+///  from the lowest-priority accounts until the mempool fits into its limits.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+pub enum MempoolEvictionPolicy {
+    #[default]
+    PurgeQueued,
+    LowestPriority,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct MempoolConfig {
     pub sync_interval_ms: u64,
@@ -196,6 +242,15 @@ pub struct MempoolConfig {
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
     pub delay_interval: u64,
+    /// Maximum total size of L2 transactions in the mempool in bytes. If not set, only `capacity` is enforced.
+    pub max_size_bytes: Option<u64>,
+    /// Maximum number of L2 transactions per account in the mempool.
+    pub max_txs_per_account: Option<usize>,
+    /// Maximum total size of L2 transactions per account in the mempool in bytes.
+    pub max_bytes_per_account: Option<u64>,
+    /// Policy used to free space once the mempool reaches its global limits.
+    #[serde(default)]
+    pub eviction_policy: MempoolEvictionPolicy,
 }
 
 impl MempoolConfig {
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for the data availability (DA) dispatcher, which publishes L1 batch pubdata
+/// to an external DA layer when `PubdataSendingMode::Custom` is used.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DADispatcherConfig {
+    #[serde(flatten)]
+    pub client: DAClientConfig,
+    /// Interval between polling the database for L1 batches to dispatch or to check inclusion for, in ms.
+    #[serde(default = "DADispatcherConfig::default_polling_interval_ms")]
+    pub polling_interval_ms: u32,
+    /// Maximum number of L1 batches dispatched to the DA layer in a single iteration.
+    #[serde(default = "DADispatcherConfig::default_max_rows_to_dispatch")]
+    pub max_rows_to_dispatch: u32,
+    /// Maximum number of attempts to dispatch pubdata of a single L1 batch before giving up.
+    #[serde(default = "DADispatcherConfig::default_max_retries")]
+    pub max_retries: u16,
+}
+
+impl DADispatcherConfig {
+    const fn default_polling_interval_ms() -> u32 {
+        5_000
+    }
+
+    const fn default_max_rows_to_dispatch() -> u32 {
+        100
+    }
+
+    const fn default_max_retries() -> u16 {
+        5
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms.into())
+    }
+}
+
+/// DA layer client used by the dispatcher.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "client")]
+pub enum DAClientConfig {
+    /// Stores pubdata in the local file system. Intended for local development and testing.
+    FileBacked { file_backed_base_path: String },
+}
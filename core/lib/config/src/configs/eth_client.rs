@@ -8,4 +8,12 @@ pub struct ETHClientConfig {
     pub chain_id: u64,
     /// Address of the Ethereum node API.
     pub web3_url: String,
+    /// Addresses of additional Ethereum node APIs used if the main node API misbehaves.
+    #[serde(default)]
+    pub fallback_web3_urls: Vec<String>,
+    /// Maximum lag (in L1 blocks) of an Ethereum node API behind other APIs after which it is considered stale.
+    pub max_block_lag: Option<u64>,
+    /// Number of Ethereum node APIs that must agree on the response for safety-critical reads
+    /// (logs, transaction receipts and statuses). If not set, such reads are not checked.
+    pub read_quorum: Option<u32>,
 }
@@ -36,6 +36,11 @@ impl ETHSenderConfig {
                 max_acceptable_priority_fee_in_gwei: 100000000000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
                 pubdata_sending_mode: PubdataSendingMode::Calldata,
+                operator_rotation_l1_batch: None,
+                operator_rotation_roles: vec![],
+                min_operator_balance_gwei: None,
+                l1_fee_postpone_percentile: None,
+                l1_fee_max_postpone_seconds: None,
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 1000000000,
@@ -72,6 +77,16 @@ pub enum PubdataSendingMode {
     #[default]
     Calldata,
     Blobs,
+    /// Pubdata is published to an external DA layer by the DA dispatcher.
+    Custom,
+}
+
+/// Type of L1 operations sent by an operator account.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperatorRole {
+    Commit,
+    Prove,
+    Execute,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -108,8 +123,26 @@ pub struct SenderConfig {
     /// The mode in which proofs are loaded, either from DB/GCS for FRI/Old proof.
     pub proof_loading_mode: ProofLoadingMode,
 
-    /// The mode in which we send pubdata, either Calldata or Blobs
+    /// The mode in which we send pubdata: Calldata, Blobs or Custom (i.e., via an external DA layer)
     pub pubdata_sending_mode: PubdataSendingMode,
+
+    /// First L1 batch for which operations are sent by the rotation operator account
+    /// (see [`Self::private_key_rotation()`]). The account only starts sending transactions
+    /// once in-flight transactions of the accounts it replaces are confirmed.
+    pub operator_rotation_l1_batch: Option<u32>,
+    /// Roles taken over by the rotation operator account. If empty, the account takes over all roles.
+    #[serde(default)]
+    pub operator_rotation_roles: Vec<OperatorRole>,
+    /// Minimum balance of each operator account in gwei. If set, the circuit breaker trips
+    /// once the balance of any operator account drops below this value.
+    pub min_operator_balance_gwei: Option<u64>,
+    /// If set, L1 batch operations are postponed while the current L1 base fee (or the blob base fee
+    /// for commit operations in the blobs mode) exceeds this percentile (0..=100) of fees in recent L1 blocks
+    /// tracked by `GasAdjuster`. While postponed, operations are only sent once the aggregated range is full.
+    pub l1_fee_postpone_percentile: Option<f64>,
+    /// Maximum time in seconds an operation can be postponed because of high L1 fees on top
+    /// of the corresponding `aggregated_block_*_deadline`.
+    pub l1_fee_max_postpone_seconds: Option<u64>,
 }
 
 impl SenderConfig {
@@ -118,6 +151,14 @@ impl SenderConfig {
         Duration::from_secs(self.tx_poll_period)
     }
 
+    const DEFAULT_L1_FEE_MAX_POSTPONE_SECONDS: u64 = 3_600;
+
+    /// Returns `self.l1_fee_max_postpone_seconds` or the default value (1 hour) if it's not set.
+    pub fn l1_fee_max_postpone_seconds(&self) -> u64 {
+        self.l1_fee_max_postpone_seconds
+            .unwrap_or(Self::DEFAULT_L1_FEE_MAX_POSTPONE_SECONDS)
+    }
+
     /// Converts `self.aggregate_tx_poll_period` into `Duration`.
     pub fn aggregate_tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.aggregate_tx_poll_period)
@@ -136,6 +177,13 @@ impl SenderConfig {
             .ok()
             .map(|pk| pk.parse().unwrap())
     }
+
+    // Don't load rotation private key, if it's not required
+    pub fn private_key_rotation(&self) -> Option<H256> {
+        std::env::var("ETH_SENDER_SENDER_OPERATOR_ROTATION_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
 }
 
 #[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
@@ -4,6 +4,7 @@ pub use self::{
     api::ApiConfig,
     contract_verifier::ContractVerifierConfig,
     contracts::ContractsConfig,
+    da_dispatcher::DADispatcherConfig,
     database::{DBConfig, PostgresConfig},
     eth_client::ETHClientConfig,
     eth_sender::{ETHSenderConfig, GasAdjusterConfig},
@@ -26,6 +27,7 @@ pub mod api;
 pub mod chain;
 pub mod contract_verifier;
 pub mod contracts;
+pub mod da_dispatcher;
 pub mod database;
 pub mod eth_client;
 pub mod eth_sender;
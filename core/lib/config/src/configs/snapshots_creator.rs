@@ -7,6 +7,16 @@ pub struct SnapshotsCreatorConfig {
 
     #[serde(default = "snapshots_creator_concurrent_queries_count")]
     pub concurrent_queries_count: u32,
+
+    /// Maximum number of consecutive incremental snapshots created on top of a full snapshot. Once this number
+    /// is reached, the next created snapshot is full. If set to 0 (the default), all snapshots are full.
+    #[serde(default)]
+    pub max_incremental_snapshots: u32,
+
+    /// Number of newest full snapshots to retain together with incremental snapshots based on them.
+    /// Older snapshots are removed from Postgres and the object store after a new snapshot is created;
+    /// at least one full snapshot is always retained. If not specified, snapshots are never removed.
+    pub retained_full_snapshots: Option<u32>,
 }
 
 fn snapshots_creator_storage_logs_chunk_size_default() -> u64 {
@@ -212,6 +212,16 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
             tree_api_url: g.gen(),
             mempool_cache_update_interval: g.gen(),
             mempool_cache_size: g.gen(),
+            tx_deny_list_path: g.gen(),
+            tx_deployer_allow_list: g.gen(),
+            max_pending_txs_per_sender: g.gen(),
+            min_priority_fee_per_gas: g.gen(),
+            private_mode: g.gen(),
+            max_session_token_ttl_sec: g.gen(),
+            txpool_namespace_enabled: g.gen(),
+            gas_estimation_stats_enabled: g.gen(),
+            estimate_gas_min_scale_factor: g.gen(),
+            estimate_gas_max_scale_factor: g.gen(),
         }
     }
 }
@@ -290,6 +300,10 @@ impl RandomConfig for configs::chain::StateKeeperConfig {
             virtual_blocks_per_miniblock: g.gen(),
             upload_witness_inputs_to_gcs: g.gen(),
             enum_index_migration_chunk_size: g.gen(),
+            seal_policy_path: g.gen(),
+            shadow_vm_protocol_version: g.gen(),
+            shadow_vm_divergence_log_path: g.gen(),
+            pre_execution_lookahead: g.gen(),
         }
     }
 }
@@ -309,6 +323,7 @@ impl RandomConfig for configs::chain::CircuitBreakerConfig {
             http_req_max_retry_number: g.gen(),
             http_req_retry_interval_sec: g.gen(),
             replication_lag_limit_sec: g.gen(),
+            priority_op_deadline_margin_sec: g.gen(),
         }
     }
 }
@@ -322,6 +337,19 @@ impl RandomConfig for configs::chain::MempoolConfig {
             stuck_tx_timeout: g.gen(),
             remove_stuck_txs: g.gen(),
             delay_interval: g.gen(),
+            max_size_bytes: g.gen(),
+            max_txs_per_account: g.gen(),
+            max_bytes_per_account: g.gen(),
+            eviction_policy: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::chain::MempoolEvictionPolicy {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::PurgeQueued,
+            _ => Self::LowestPriority,
         }
     }
 }
@@ -438,6 +466,9 @@ impl RandomConfig for configs::ETHClientConfig {
         Self {
             chain_id: g.gen(),
             web3_url: g.gen(),
+            fallback_web3_urls: g.gen(),
+            max_block_lag: g.gen(),
+            read_quorum: g.gen(),
         }
     }
 }
@@ -472,9 +503,20 @@ impl RandomConfig for configs::eth_sender::ProofLoadingMode {
 
 impl RandomConfig for configs::eth_sender::PubdataSendingMode {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
-        match g.rng.gen_range(0..2) {
+        match g.rng.gen_range(0..3) {
             0 => Self::Calldata,
-            _ => Self::Blobs,
+            1 => Self::Blobs,
+            _ => Self::Custom,
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::OperatorRole {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..3) {
+            0 => Self::Commit,
+            1 => Self::Prove,
+            _ => Self::Execute,
         }
     }
 }
@@ -500,6 +542,11 @@ impl RandomConfig for configs::eth_sender::SenderConfig {
             max_acceptable_priority_fee_in_gwei: g.gen(),
             proof_loading_mode: g.gen(),
             pubdata_sending_mode: PubdataSendingMode::Calldata,
+            operator_rotation_l1_batch: g.gen(),
+            operator_rotation_roles: g.gen(),
+            min_operator_balance_gwei: g.gen(),
+            l1_fee_postpone_percentile: g.gen(),
+            l1_fee_max_postpone_seconds: g.gen(),
         }
     }
 }
@@ -661,6 +708,7 @@ impl RandomConfig for configs::house_keeper::HouseKeeperConfig {
             fri_prover_stats_reporting_interval_ms: g.gen(),
             fri_proof_compressor_job_retrying_interval_ms: g.gen(),
             fri_proof_compressor_stats_reporting_interval_ms: g.gen(),
+            priority_queue_monitoring_interval_ms: g.gen(),
         }
     }
 }
@@ -694,6 +742,25 @@ impl RandomConfig for configs::ObjectStoreConfig {
     }
 }
 
+impl RandomConfig for configs::da_dispatcher::DAClientConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self::FileBacked {
+            file_backed_base_path: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::DADispatcherConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            client: g.gen(),
+            polling_interval_ms: g.gen(),
+            max_rows_to_dispatch: g.gen(),
+            max_retries: g.gen(),
+        }
+    }
+}
+
 impl RandomConfig for configs::proof_data_handler::ProtocolVersionLoadingMode {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         match g.rng.gen_range(0..2) {
@@ -719,6 +786,8 @@ impl RandomConfig for configs::SnapshotsCreatorConfig {
         Self {
             storage_logs_chunk_size: g.gen(),
             concurrent_queries_count: g.gen(),
+            max_incremental_snapshots: g.gen(),
+            retained_full_snapshots: g.gen(),
         }
     }
 }
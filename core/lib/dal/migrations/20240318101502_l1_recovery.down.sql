@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS l1_recovery_storage_logs;
+DROP TABLE IF EXISTS l1_recovery_batches;
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS l1_recovery_batches
+(
+    l1_batch_number BIGINT PRIMARY KEY,
+    miniblock_number BIGINT NOT NULL,
+    root_hash BYTEA NOT NULL,
+    commit_tx_hash BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+-- Pubdata published on L1 only contains hashed storage keys, so storage logs recovered from L1 have no key preimages
+-- and are kept separately from `storage_logs`.
+CREATE TABLE IF NOT EXISTS l1_recovery_storage_logs
+(
+    hashed_key BYTEA NOT NULL,
+    l1_batch_number BIGINT NOT NULL,
+    enumeration_index BIGINT NOT NULL,
+    value BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (hashed_key, l1_batch_number)
+);
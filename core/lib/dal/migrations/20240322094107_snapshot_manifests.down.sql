@@ -0,0 +1,4 @@
+ALTER TABLE snapshots
+    DROP COLUMN manifest_signature,
+    DROP COLUMN storage_logs_hashes,
+    DROP COLUMN factory_deps_hash;
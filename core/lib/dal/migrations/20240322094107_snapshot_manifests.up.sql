@@ -0,0 +1,4 @@
+ALTER TABLE snapshots
+    ADD COLUMN factory_deps_hash BYTEA,
+    ADD COLUMN storage_logs_hashes BYTEA[] NOT NULL DEFAULT '{}',
+    ADD COLUMN manifest_signature BYTEA;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS gas_estimation_stats;
+DROP TABLE IF EXISTS gas_estimates;
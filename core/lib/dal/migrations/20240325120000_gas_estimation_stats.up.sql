@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS gas_estimates
+(
+    tx_hash BYTEA PRIMARY KEY,
+    contract_address BYTEA NOT NULL,
+    selector BYTEA NOT NULL,
+    estimated_gas BIGINT NOT NULL,
+    scale_factor DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS gas_estimates_created_at_idx ON gas_estimates (created_at);
+
+CREATE TABLE IF NOT EXISTS gas_estimation_stats
+(
+    contract_address BYTEA NOT NULL,
+    selector BYTEA NOT NULL,
+    samples BIGINT NOT NULL,
+    failed_samples BIGINT NOT NULL,
+    estimated_to_used_ratio_sum DOUBLE PRECISION NOT NULL,
+    refund_ratio_sum DOUBLE PRECISION NOT NULL,
+    unscaled_usage_ratio_sum DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (contract_address, selector)
+);
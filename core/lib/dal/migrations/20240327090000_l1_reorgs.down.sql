@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS l1_reorgs;
+DROP TABLE IF EXISTS eth_txs_confirmations;
+DROP TABLE IF EXISTS eth_watch_checkpoints;
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS eth_watch_checkpoints
+(
+    l1_block_number BIGINT PRIMARY KEY,
+    l1_block_hash BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS eth_txs_confirmations
+(
+    eth_tx_id INT PRIMARY KEY REFERENCES eth_txs (id) ON DELETE CASCADE,
+    l1_block_number BIGINT NOT NULL,
+    l1_block_hash BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS eth_txs_confirmations_l1_block_number_idx
+    ON eth_txs_confirmations (l1_block_number);
+
+CREATE TABLE IF NOT EXISTS l1_reorgs
+(
+    id BIGSERIAL PRIMARY KEY,
+    component TEXT NOT NULL,
+    reverted_to_l1_block BIGINT NOT NULL,
+    is_fatal BOOLEAN NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
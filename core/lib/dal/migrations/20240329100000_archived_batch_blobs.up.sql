@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS archived_batch_blobs
+(
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    eth_tx_id INT NOT NULL REFERENCES eth_txs (id) ON DELETE CASCADE,
+    blobs_count INT NOT NULL,
+    -- Set if blobs have failed local verification and thus were not archived.
+    verification_error TEXT,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS archived_batch_blobs_eth_tx_id_idx
+    ON archived_batch_blobs (eth_tx_id);
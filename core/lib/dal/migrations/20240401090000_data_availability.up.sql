@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS data_availability
+(
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    -- Identifier (e.g., a blob pointer) assigned to the published pubdata by the DA layer.
+    blob_id TEXT NOT NULL,
+    -- Inclusion proof or another data returned by the DA layer once the pubdata is included.
+    -- It's embedded into the commit operation for the batch; `NULL` until the inclusion is confirmed.
+    inclusion_data BYTEA,
+    sent_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS data_availability_awaiting_inclusion_idx
+    ON data_availability (l1_batch_number) WHERE inclusion_data IS NULL;
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS eth_watch_processor_cursors;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS eth_watch_processor_cursors
+(
+    processor_name TEXT PRIMARY KEY,
+    last_processed_l1_block BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
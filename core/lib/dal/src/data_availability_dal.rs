@@ -0,0 +1,180 @@
+//! Storage for the status of L1 batch pubdata published to an external data availability (DA) layer.
+
+use std::collections::HashMap;
+
+use zksync_db_connection::connection::Connection;
+use zksync_types::L1BatchNumber;
+
+use crate::Core;
+
+/// Pubdata of an L1 batch that wasn't dispatched to the DA layer yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct L1BatchPubdata {
+    pub l1_batch_number: L1BatchNumber,
+    pub pubdata: Vec<u8>,
+}
+
+/// L1 batch pubdata dispatched to the DA layer, but not yet confirmed to be included in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataAvailabilityBlob {
+    pub l1_batch_number: L1BatchNumber,
+    pub blob_id: String,
+}
+
+#[derive(Debug)]
+pub struct DataAvailabilityDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl DataAvailabilityDal<'_, '_> {
+    /// Records that pubdata of the specified L1 batch was dispatched to the DA layer under `blob_id`.
+    /// Does nothing if the batch is already recorded.
+    pub async fn insert_l1_batch_da(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        blob_id: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                data_availability (l1_batch_number, blob_id, sent_at, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO NOTHING
+            "#,
+            i64::from(l1_batch_number.0),
+            blob_id
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Saves inclusion data returned by the DA layer for the specified L1 batch. Inclusion data
+    /// can only be set once; returns `false` if the batch is unknown or already has inclusion data.
+    pub async fn save_l1_batch_inclusion_data(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        inclusion_data: &[u8],
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE data_availability
+            SET
+                inclusion_data = $1,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $2
+                AND inclusion_data IS NULL
+            "#,
+            inclusion_data,
+            i64::from(l1_batch_number.0)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns up to `limit` earliest dispatched L1 batches for which inclusion in the DA layer isn't confirmed yet.
+    pub async fn get_da_blobs_awaiting_inclusion(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<DataAvailabilityBlob>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                blob_id
+            FROM
+                data_availability
+            WHERE
+                inclusion_data IS NULL
+            ORDER BY
+                l1_batch_number
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DataAvailabilityBlob {
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                blob_id: row.blob_id,
+            })
+            .collect())
+    }
+
+    /// Returns up to `limit` earliest L1 batches with pubdata that wasn't dispatched to the DA layer yet.
+    pub async fn get_ready_for_da_dispatch_l1_batches(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<L1BatchPubdata>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                number,
+                pubdata_input
+            FROM
+                l1_batches
+                LEFT JOIN data_availability ON data_availability.l1_batch_number = l1_batches.number
+            WHERE
+                number != 0
+                AND data_availability.blob_id IS NULL
+                AND pubdata_input IS NOT NULL
+            ORDER BY
+                number
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchPubdata {
+                l1_batch_number: L1BatchNumber(row.number as u32),
+                // `unwrap` is safe due to the check in the query
+                pubdata: row.pubdata_input.unwrap(),
+            })
+            .collect())
+    }
+
+    /// Returns inclusion data for L1 batches in the specified range. Batches without confirmed inclusion
+    /// are not present in the returned map.
+    pub async fn get_l1_batches_inclusion_data(
+        &mut self,
+        first_l1_batch: L1BatchNumber,
+        last_l1_batch: L1BatchNumber,
+    ) -> sqlx::Result<HashMap<L1BatchNumber, Vec<u8>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                inclusion_data
+            FROM
+                data_availability
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND inclusion_data IS NOT NULL
+            "#,
+            i64::from(first_l1_batch.0),
+            i64::from(last_l1_batch.0)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let inclusion_data = row.inclusion_data?;
+                Some((L1BatchNumber(row.l1_batch_number as u32), inclusion_data))
+            })
+            .collect())
+    }
+}
@@ -5,17 +5,27 @@ use sqlx::types::chrono::{DateTime, Utc};
 use zksync_db_connection::{connection::Connection, interpolate_query, match_query_as};
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    eth_sender::{EthTx, EthTxBlobSidecar, TxHistory, TxHistoryToSend},
+    eth_sender::{EthTx, EthTxBlobSidecar, L1BatchBlobs, TxHistory, TxHistoryToSend},
     Address, L1BatchNumber, H256, U256,
 };
 
 use crate::{
+    l1_reorgs_dal::L1BlockCheckpoint,
     models::storage_eth_tx::{
         L1BatchEthSenderStats, StorageEthTx, StorageTxHistory, StorageTxHistoryToSend,
     },
     Core,
 };
 
+/// Brief information about an Ethereum transaction stored in the `eth_txs` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EthTxSummary {
+    pub id: u32,
+    pub tx_type: AggregatedActionType,
+    /// Whether the transaction is confirmed on L1.
+    pub confirmed: bool,
+}
+
 #[derive(Debug)]
 pub struct EthSenderDal<'a, 'c> {
     pub(crate) storage: &'a mut Connection<'c, Core>,
@@ -377,6 +387,249 @@ impl EthSenderDal<'_, '_> {
         Ok(Some(H256::from_str(tx_hash).context("invalid tx_hash")?))
     }
 
+    /// Records the L1 block in which the confirmed transaction was included.
+    pub async fn save_confirmation_block(
+        &mut self,
+        eth_tx_id: u32,
+        block: L1BlockCheckpoint,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_txs_confirmations (eth_tx_id, l1_block_number, l1_block_hash, created_at)
+            VALUES
+                ($1, $2, $3, NOW())
+            ON CONFLICT (eth_tx_id) DO
+            UPDATE
+            SET
+                l1_block_number = excluded.l1_block_number,
+                l1_block_hash = excluded.l1_block_hash
+            "#,
+            eth_tx_id as i32,
+            block.number as i64,
+            block.hash.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns confirmed transactions included in L1 blocks starting from the specified one,
+    /// together with these blocks.
+    pub async fn get_confirmation_blocks_since(
+        &mut self,
+        from_l1_block: u64,
+    ) -> sqlx::Result<Vec<(u32, L1BlockCheckpoint)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                eth_txs_confirmations.eth_tx_id,
+                eth_txs_confirmations.l1_block_number,
+                eth_txs_confirmations.l1_block_hash
+            FROM
+                eth_txs_confirmations
+                INNER JOIN eth_txs ON eth_txs.id = eth_txs_confirmations.eth_tx_id
+            WHERE
+                eth_txs_confirmations.l1_block_number >= $1
+                AND eth_txs.confirmed_eth_tx_history_id IS NOT NULL
+            ORDER BY
+                eth_txs_confirmations.l1_block_number
+            "#,
+            from_l1_block as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let block = L1BlockCheckpoint {
+                    number: row.l1_block_number as u64,
+                    hash: H256::from_slice(&row.l1_block_hash),
+                };
+                (row.eth_tx_id as u32, block)
+            })
+            .collect())
+    }
+
+    /// Reverts confirmation of a transaction (e.g., because the L1 block it was included in was reorged),
+    /// making the transaction in-flight again.
+    pub async fn revert_tx_confirmation(&mut self, eth_tx_id: u32) -> anyhow::Result<()> {
+        let mut transaction = self
+            .storage
+            .start_transaction()
+            .await
+            .context("start_transaction()")?;
+
+        sqlx::query!(
+            r#"
+            UPDATE eth_txs
+            SET
+                gas_used = NULL,
+                confirmed_eth_tx_history_id = NULL
+            WHERE
+                id = $1
+            "#,
+            eth_tx_id as i32
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE eth_txs_history
+            SET
+                updated_at = NOW(),
+                confirmed_at = NULL
+            WHERE
+                eth_tx_id = $1
+            "#,
+            eth_tx_id as i32
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM eth_txs_confirmations
+            WHERE
+                eth_tx_id = $1
+            "#,
+            eth_tx_id as i32
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        // The transaction may be confirmed with another hash, so blobs need to be archived anew.
+        sqlx::query!(
+            r#"
+            DELETE FROM archived_batch_blobs
+            WHERE
+                eth_tx_id = $1
+            "#,
+            eth_tx_id as i32
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Returns blobs published by confirmed commit transactions for L1 batches that don't have their blobs
+    /// archived yet, together with the IDs of these transactions. Batches are returned in the ascending order.
+    pub async fn get_unarchived_batch_blobs(
+        &mut self,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(u32, L1BatchBlobs)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batches.number,
+                eth_txs.id AS eth_tx_id,
+                eth_txs.blob_sidecar AS "blob_sidecar!",
+                eth_txs_history.tx_hash
+            FROM
+                l1_batches
+                INNER JOIN eth_txs ON eth_txs.id = l1_batches.eth_commit_tx_id
+                INNER JOIN eth_txs_history ON eth_txs_history.id = eth_txs.confirmed_eth_tx_history_id
+                LEFT JOIN archived_batch_blobs ON archived_batch_blobs.l1_batch_number = l1_batches.number
+            WHERE
+                eth_txs.blob_sidecar IS NOT NULL
+                AND archived_batch_blobs.l1_batch_number IS NULL
+            ORDER BY
+                l1_batches.number
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let sidecar: EthTxBlobSidecar = bincode::deserialize(&row.blob_sidecar)
+                    .context("EthTxBlobSidecar is encoded incorrectly")?;
+                let EthTxBlobSidecar::EthTxBlobSidecarV1(sidecar) = sidecar;
+                let tx_hash = row.tx_hash.trim_start_matches("0x");
+                let blobs = L1BatchBlobs {
+                    l1_batch_number: L1BatchNumber(row.number as u32),
+                    commit_tx_hash: H256::from_str(tx_hash).context("invalid tx_hash")?,
+                    blobs: sidecar.blobs,
+                };
+                Ok((row.eth_tx_id as u32, blobs))
+            })
+            .collect()
+    }
+
+    /// Marks blobs for the specified L1 batch as archived.
+    pub async fn mark_batch_blobs_as_archived(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        eth_tx_id: u32,
+        blobs_count: usize,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                archived_batch_blobs (l1_batch_number, eth_tx_id, blobs_count, created_at)
+            VALUES
+                ($1, $2, $3, NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                eth_tx_id = excluded.eth_tx_id,
+                blobs_count = excluded.blobs_count,
+                verification_error = NULL
+            "#,
+            i64::from(l1_batch_number.0),
+            eth_tx_id as i32,
+            blobs_count as i32
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Marks blobs for the specified L1 batch as failed verification, so that they are not returned
+    /// by [`Self::get_unarchived_batch_blobs()`] anymore.
+    pub async fn mark_batch_blobs_as_invalid(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        eth_tx_id: u32,
+        blobs_count: usize,
+        verification_error: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                archived_batch_blobs (
+                    l1_batch_number,
+                    eth_tx_id,
+                    blobs_count,
+                    verification_error,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                eth_tx_id = excluded.eth_tx_id,
+                blobs_count = excluded.blobs_count,
+                verification_error = excluded.verification_error
+            "#,
+            i64::from(l1_batch_number.0),
+            eth_tx_id as i32,
+            blobs_count as i32,
+            verification_error
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
     /// This method inserts a fake transaction into the database that would make the corresponding L1 batch
     /// to be considered committed/proven/executed.
     ///
@@ -533,39 +786,51 @@ impl EthSenderDal<'_, '_> {
         Ok(history_item.map(|tx| tx.into()))
     }
 
-    /// Returns the next nonce for the operator account
-    ///
-    /// # Params
-    /// * `from_address`: an optional value indicating that nonce must be returned for a custom
-    ///   operator address which is not the "main" one. For example, a separate custom operator
-    ///   sends the blob transactions. For such a case this should be `Some`. For requesting the
-    ///   none of the main operator this parameter should be set to `None`.
-    pub async fn get_next_nonce(
-        &mut self,
-        from_address: Option<Address>,
-    ) -> sqlx::Result<Option<u64>> {
-        struct NonceRow {
-            nonce: i64,
-        }
-
-        let query = match_query_as!(
-            NonceRow,
-            [
-                "SELECT nonce FROM eth_txs WHERE ",
-                _, // WHERE condition
-                " ORDER BY id DESC LIMIT 1"
-            ],
-            match (from_address) {
-                Some(address) => ("from_addr = $1::bytea"; address.as_bytes()),
-                None => ("from_addr IS NULL";),
-            }
-        );
+    /// Returns the next nonce for the specified operator account based on the transactions sent from it,
+    /// or `None` if no transactions were sent from the account yet.
+    pub async fn get_next_nonce(&mut self, from_address: Address) -> sqlx::Result<Option<u64>> {
+        let nonce = sqlx::query_scalar!(
+            r#"
+            SELECT
+                nonce
+            FROM
+                eth_txs
+            WHERE
+                from_addr = $1
+            ORDER BY
+                id DESC
+            LIMIT
+                1
+            "#,
+            from_address.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(nonce.map(|nonce| nonce as u64 + 1))
+    }
 
-        let nonce = query
-            .fetch_optional(self.storage.conn())
-            .await?
-            .map(|row| row.nonce as u64);
-        Ok(nonce.map(|n| n + 1))
+    /// Attributes unconfirmed transactions without a sender address (i.e., ones saved before
+    /// per-account nonce tracking was introduced) to the specified operator account.
+    /// Returns the number of updated transactions.
+    pub async fn assign_sender_to_legacy_txs(
+        &mut self,
+        from_address: Address,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE eth_txs
+            SET
+                from_addr = $1,
+                updated_at = NOW()
+            WHERE
+                from_addr IS NULL
+                AND confirmed_eth_tx_history_id IS NULL
+            "#,
+            from_address.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected())
     }
 
     pub async fn mark_failed_transaction(&mut self, eth_tx_id: u32) -> sqlx::Result<()> {
@@ -621,6 +886,71 @@ impl EthSenderDal<'_, '_> {
         Ok(())
     }
 
+    /// Returns transactions that would be deleted by [`Self::delete_eth_txs()`] with the same argument,
+    /// ordered by ID.
+    pub async fn get_eth_txs_to_delete(
+        &mut self,
+        last_batch_to_keep: L1BatchNumber,
+    ) -> anyhow::Result<Vec<EthTxSummary>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                tx_type,
+                confirmed_eth_tx_history_id IS NOT NULL AS "confirmed!"
+            FROM
+                eth_txs
+            WHERE
+                id IN (
+                    (
+                        SELECT
+                            eth_commit_tx_id
+                        FROM
+                            l1_batches
+                        WHERE
+                            number > $1
+                    )
+                    UNION
+                    (
+                        SELECT
+                            eth_prove_tx_id
+                        FROM
+                            l1_batches
+                        WHERE
+                            number > $1
+                    )
+                    UNION
+                    (
+                        SELECT
+                            eth_execute_tx_id
+                        FROM
+                            l1_batches
+                        WHERE
+                            number > $1
+                    )
+                )
+            ORDER BY
+                id
+            "#,
+            i64::from(last_batch_to_keep.0)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let tx_type = AggregatedActionType::from_str(&row.tx_type)
+                    .map_err(anyhow::Error::msg)
+                    .with_context(|| format!("invalid type of eth_tx {}", row.id))?;
+                Ok(EthTxSummary {
+                    id: row.id as u32,
+                    tx_type,
+                    confirmed: row.confirmed,
+                })
+            })
+            .collect()
+    }
+
     pub async fn delete_eth_txs(&mut self, last_batch_to_keep: L1BatchNumber) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
@@ -0,0 +1,101 @@
+//! Storage for cursors of extra event processors registered in `eth_watch`.
+
+use zksync_db_connection::connection::Connection;
+
+use crate::Core;
+
+#[derive(Debug)]
+pub struct EthWatchDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl EthWatchDal<'_, '_> {
+    /// Returns the last L1 block processed by the event processor with the specified name, or `None`
+    /// if the processor has never run.
+    pub async fn get_processor_cursor(
+        &mut self,
+        processor_name: &str,
+    ) -> sqlx::Result<Option<u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_processed_l1_block
+            FROM
+                eth_watch_processor_cursors
+            WHERE
+                processor_name = $1
+            "#,
+            processor_name
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.map(|row| row.last_processed_l1_block as u64))
+    }
+
+    /// Creates a cursor for the event processor unless it already exists. Returns the cursor value
+    /// after the operation.
+    pub async fn init_processor_cursor(
+        &mut self,
+        processor_name: &str,
+        last_processed_l1_block: u64,
+    ) -> sqlx::Result<u64> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_watch_processor_cursors (processor_name, last_processed_l1_block, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (processor_name) DO NOTHING
+            "#,
+            processor_name,
+            last_processed_l1_block as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+
+        let cursor = self.get_processor_cursor(processor_name).await?;
+        Ok(cursor.expect("cursor was just inserted"))
+    }
+
+    /// Advances the cursor of the event processor.
+    pub async fn set_processor_cursor(
+        &mut self,
+        processor_name: &str,
+        last_processed_l1_block: u64,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE eth_watch_processor_cursors
+            SET
+                last_processed_l1_block = $2,
+                updated_at = NOW()
+            WHERE
+                processor_name = $1
+            "#,
+            processor_name,
+            last_processed_l1_block as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Moves all processor cursors pointing after the specified L1 block back to it. Used to handle L1 reorgs.
+    /// Returns the number of affected cursors.
+    pub async fn rewind_processor_cursors(&mut self, l1_block_number: u64) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE eth_watch_processor_cursors
+            SET
+                last_processed_l1_block = $1,
+                updated_at = NOW()
+            WHERE
+                last_processed_l1_block > $1
+            "#,
+            l1_block_number as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
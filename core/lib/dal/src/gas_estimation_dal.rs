@@ -0,0 +1,588 @@
+//! Storage for gas estimation accuracy statistics.
+//!
+//! Gas estimates for submitted transactions are stored in `gas_estimates` until the corresponding transaction
+//! is included into a miniblock. After that, the estimate is compared with the actual gas usage and aggregated
+//! into per-method statistics in `gas_estimation_stats`.
+
+use std::time::Duration;
+
+use bigdecimal::ToPrimitive;
+use zksync_db_connection::{connection::Connection, utils::pg_interval_from_duration};
+use zksync_types::{Address, H256};
+
+use crate::Core;
+
+/// Gas estimate for a submitted transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasEstimate {
+    pub tx_hash: H256,
+    pub contract_address: Address,
+    /// Function selector; empty for transactions with calldata shorter than 4 bytes.
+    pub selector: Vec<u8>,
+    /// Estimated gas limit, including the scale factor.
+    pub estimated_gas: u64,
+    /// Scale factor applied to the estimate.
+    pub scale_factor: f64,
+}
+
+/// Gas estimate for a transaction included into a miniblock, together with the actual gas usage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasEstimationSample {
+    pub contract_address: Address,
+    pub selector: Vec<u8>,
+    pub estimated_gas: u64,
+    pub scale_factor: f64,
+    pub gas_limit: u64,
+    pub refunded_gas: u64,
+    /// Whether the transaction has failed.
+    pub failed: bool,
+}
+
+impl GasEstimationSample {
+    pub fn used_gas(&self) -> u64 {
+        self.gas_limit.saturating_sub(self.refunded_gas).max(1)
+    }
+
+    /// Ratio between the estimated and actually used gas.
+    pub fn estimated_to_used_ratio(&self) -> f64 {
+        self.estimated_gas as f64 / self.used_gas() as f64
+    }
+
+    /// Share of the gas limit refunded to the user.
+    pub fn refund_ratio(&self) -> f64 {
+        if self.gas_limit == 0 {
+            return 0.0;
+        }
+        self.refunded_gas as f64 / self.gas_limit as f64
+    }
+
+    /// Ratio between the actually used gas and the estimate before applying the scale factor.
+    pub fn unscaled_usage_ratio(&self) -> f64 {
+        let unscaled_estimate = (self.estimated_gas as f64 / self.scale_factor).max(1.0);
+        self.used_gas() as f64 / unscaled_estimate
+    }
+}
+
+/// Aggregated statistics on the accuracy of gas estimation for a certain contract method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasEstimationStats {
+    pub contract_address: Address,
+    pub selector: Vec<u8>,
+    pub samples: u64,
+    pub failed_samples: u64,
+    pub estimated_to_used_ratio_sum: f64,
+    pub refund_ratio_sum: f64,
+    pub unscaled_usage_ratio_sum: f64,
+}
+
+impl GasEstimationStats {
+    pub fn new(contract_address: Address, selector: Vec<u8>) -> Self {
+        Self {
+            contract_address,
+            selector,
+            samples: 0,
+            failed_samples: 0,
+            estimated_to_used_ratio_sum: 0.0,
+            refund_ratio_sum: 0.0,
+            unscaled_usage_ratio_sum: 0.0,
+        }
+    }
+
+    pub fn add_sample(&mut self, sample: &GasEstimationSample) {
+        self.samples += 1;
+        self.failed_samples += u64::from(sample.failed);
+        self.estimated_to_used_ratio_sum += sample.estimated_to_used_ratio();
+        self.refund_ratio_sum += sample.refund_ratio();
+        self.unscaled_usage_ratio_sum += sample.unscaled_usage_ratio();
+    }
+
+    fn avg(&self, sum: f64) -> Option<f64> {
+        (self.samples > 0).then(|| sum / self.samples as f64)
+    }
+
+    pub fn avg_estimated_to_used_ratio(&self) -> Option<f64> {
+        self.avg(self.estimated_to_used_ratio_sum)
+    }
+
+    pub fn avg_refund_ratio(&self) -> Option<f64> {
+        self.avg(self.refund_ratio_sum)
+    }
+
+    pub fn avg_unscaled_usage_ratio(&self) -> Option<f64> {
+        self.avg(self.unscaled_usage_ratio_sum)
+    }
+}
+
+#[derive(Debug)]
+pub struct GasEstimationDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl GasEstimationDal<'_, '_> {
+    pub async fn insert_gas_estimate(&mut self, estimate: &GasEstimate) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                gas_estimates (
+                    tx_hash,
+                    contract_address,
+                    selector,
+                    estimated_gas,
+                    scale_factor,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+            estimate.tx_hash.as_bytes(),
+            estimate.contract_address.as_bytes(),
+            &estimate.selector,
+            estimate.estimated_gas as i64,
+            estimate.scale_factor
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Removes up to `limit` estimates for transactions included into miniblocks and returns them
+    /// together with the actual gas usage.
+    pub async fn take_included_gas_estimates(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<GasEstimationSample>> {
+        let rows = sqlx::query!(
+            r#"
+            DELETE FROM gas_estimates
+            USING
+                transactions
+            WHERE
+                gas_estimates.tx_hash = transactions.hash
+                AND gas_estimates.tx_hash IN (
+                    SELECT
+                        gas_estimates.tx_hash
+                    FROM
+                        gas_estimates
+                        INNER JOIN transactions ON transactions.hash = gas_estimates.tx_hash
+                    WHERE
+                        transactions.miniblock_number IS NOT NULL
+                    LIMIT
+                        $1
+                )
+            RETURNING
+                gas_estimates.contract_address,
+                gas_estimates.selector,
+                gas_estimates.estimated_gas,
+                gas_estimates.scale_factor,
+                transactions.gas_limit,
+                transactions.refunded_gas,
+                transactions.error IS NOT NULL AS "failed!"
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let contract_address = Address::from_slice(&row.contract_address);
+                let Some(gas_limit) = row.gas_limit.as_ref().and_then(ToPrimitive::to_u64) else {
+                    // The estimate is deleted anyway, so the sample is just lost.
+                    tracing::warn!(
+                        "Skipping gas estimation sample for contract {contract_address:?}: \
+                         transaction has invalid gas limit {:?}",
+                        row.gas_limit
+                    );
+                    return None;
+                };
+                Some(GasEstimationSample {
+                    contract_address,
+                    selector: row.selector,
+                    estimated_gas: row.estimated_gas as u64,
+                    scale_factor: row.scale_factor,
+                    gas_limit,
+                    refunded_gas: row.refunded_gas as u64,
+                    failed: row.failed,
+                })
+            })
+            .collect())
+    }
+
+    /// Removes estimates for transactions that weren't included into a miniblock for the specified time
+    /// (e.g., because they were replaced or rejected). Returns the number of removed estimates.
+    pub async fn delete_stale_gas_estimates(&mut self, older_than: Duration) -> sqlx::Result<u64> {
+        let older_than = pg_interval_from_duration(older_than);
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM gas_estimates
+            WHERE
+                created_at < NOW() - $1::INTERVAL
+            "#,
+            &older_than
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Adds the provided statistics to the stored ones.
+    pub async fn update_gas_estimation_stats(
+        &mut self,
+        stats: &[GasEstimationStats],
+    ) -> sqlx::Result<()> {
+        let contract_addresses: Vec<_> = stats
+            .iter()
+            .map(|stats| stats.contract_address.as_bytes())
+            .collect();
+        let selectors: Vec<_> = stats
+            .iter()
+            .map(|stats| stats.selector.as_slice())
+            .collect();
+        let samples: Vec<_> = stats.iter().map(|stats| stats.samples as i64).collect();
+        let failed_samples: Vec<_> = stats
+            .iter()
+            .map(|stats| stats.failed_samples as i64)
+            .collect();
+        let estimated_to_used_ratio_sums: Vec<_> = stats
+            .iter()
+            .map(|stats| stats.estimated_to_used_ratio_sum)
+            .collect();
+        let refund_ratio_sums: Vec<_> = stats.iter().map(|stats| stats.refund_ratio_sum).collect();
+        let unscaled_usage_ratio_sums: Vec<_> = stats
+            .iter()
+            .map(|stats| stats.unscaled_usage_ratio_sum)
+            .collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                gas_estimation_stats (
+                    contract_address,
+                    selector,
+                    samples,
+                    failed_samples,
+                    estimated_to_used_ratio_sum,
+                    refund_ratio_sum,
+                    unscaled_usage_ratio_sum,
+                    created_at,
+                    updated_at
+                )
+            SELECT
+                u.contract_address,
+                u.selector,
+                u.samples,
+                u.failed_samples,
+                u.estimated_to_used_ratio_sum,
+                u.refund_ratio_sum,
+                u.unscaled_usage_ratio_sum,
+                NOW(),
+                NOW()
+            FROM
+                UNNEST(
+                    $1::bytea[],
+                    $2::bytea[],
+                    $3::BIGINT[],
+                    $4::BIGINT[],
+                    $5::DOUBLE PRECISION[],
+                    $6::DOUBLE PRECISION[],
+                    $7::DOUBLE PRECISION[]
+                ) AS u (
+                    contract_address,
+                    selector,
+                    samples,
+                    failed_samples,
+                    estimated_to_used_ratio_sum,
+                    refund_ratio_sum,
+                    unscaled_usage_ratio_sum
+                )
+            ON CONFLICT (contract_address, selector) DO
+            UPDATE
+            SET
+                samples = gas_estimation_stats.samples + excluded.samples,
+                failed_samples = gas_estimation_stats.failed_samples + excluded.failed_samples,
+                estimated_to_used_ratio_sum = gas_estimation_stats.estimated_to_used_ratio_sum + excluded.estimated_to_used_ratio_sum,
+                refund_ratio_sum = gas_estimation_stats.refund_ratio_sum + excluded.refund_ratio_sum,
+                unscaled_usage_ratio_sum = gas_estimation_stats.unscaled_usage_ratio_sum + excluded.unscaled_usage_ratio_sum,
+                updated_at = NOW()
+            "#,
+            &contract_addresses as &[&[u8]],
+            &selectors as &[&[u8]],
+            &samples,
+            &failed_samples,
+            &estimated_to_used_ratio_sums,
+            &refund_ratio_sums,
+            &unscaled_usage_ratio_sums
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns statistics for all contract methods with at least `min_samples` samples.
+    pub async fn get_all_gas_estimation_stats(
+        &mut self,
+        min_samples: u64,
+    ) -> sqlx::Result<Vec<GasEstimationStats>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                contract_address,
+                selector,
+                samples,
+                failed_samples,
+                estimated_to_used_ratio_sum,
+                refund_ratio_sum,
+                unscaled_usage_ratio_sum
+            FROM
+                gas_estimation_stats
+            WHERE
+                samples >= $1
+            "#,
+            min_samples as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| GasEstimationStats {
+                contract_address: Address::from_slice(&row.contract_address),
+                selector: row.selector,
+                samples: row.samples as u64,
+                failed_samples: row.failed_samples as u64,
+                estimated_to_used_ratio_sum: row.estimated_to_used_ratio_sum,
+                refund_ratio_sum: row.refund_ratio_sum,
+                unscaled_usage_ratio_sum: row.unscaled_usage_ratio_sum,
+            })
+            .collect())
+    }
+
+    /// Returns statistics for the specified contract. If `selector` is specified, only returns statistics
+    /// for the corresponding method.
+    pub async fn get_gas_estimation_stats(
+        &mut self,
+        contract_address: Address,
+        selector: Option<&[u8]>,
+    ) -> sqlx::Result<Vec<GasEstimationStats>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                contract_address,
+                selector,
+                samples,
+                failed_samples,
+                estimated_to_used_ratio_sum,
+                refund_ratio_sum,
+                unscaled_usage_ratio_sum
+            FROM
+                gas_estimation_stats
+            WHERE
+                contract_address = $1
+                AND (
+                    $2::BYTEA IS NULL
+                    OR selector = $2
+                )
+            ORDER BY
+                selector
+            "#,
+            contract_address.as_bytes(),
+            selector
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| GasEstimationStats {
+                contract_address: Address::from_slice(&row.contract_address),
+                selector: row.selector,
+                samples: row.samples as u64,
+                failed_samples: row.failed_samples as u64,
+                estimated_to_used_ratio_sum: row.estimated_to_used_ratio_sum,
+                refund_ratio_sum: row.refund_ratio_sum,
+                unscaled_usage_ratio_sum: row.unscaled_usage_ratio_sum,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{fee::TransactionExecutionMetrics, MiniblockNumber, ProtocolVersion, U256};
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool, CoreDal,
+    };
+
+    #[tokio::test]
+    async fn aggregating_gas_estimation_stats() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(0))
+            .await
+            .unwrap();
+
+        let contract_address = Address::repeat_byte(1);
+        let selector = vec![1, 2, 3, 4];
+        let included_tx = mock_l2_transaction();
+        let pending_tx = mock_l2_transaction();
+        for tx in [&included_tx, &pending_tx] {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+            let estimate = GasEstimate {
+                tx_hash: tx.hash(),
+                contract_address,
+                selector: selector.clone(),
+                estimated_gas: 1_000_000,
+                scale_factor: 1.25,
+            };
+            conn.gas_estimation_dal()
+                .insert_gas_estimate(&estimate)
+                .await
+                .unwrap();
+        }
+
+        let mut miniblock_header = create_miniblock_header(1);
+        miniblock_header.l2_tx_count = 1;
+        conn.blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await
+            .unwrap();
+        let mut tx_result = mock_execution_result(included_tx);
+        tx_result.refunded_gas = 600_000;
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], U256::from(1))
+            .await;
+
+        let samples = conn
+            .gas_estimation_dal()
+            .take_included_gas_estimates(100)
+            .await
+            .unwrap();
+        assert_eq!(samples.len(), 1);
+        let sample = &samples[0];
+        assert_eq!(sample.contract_address, contract_address);
+        assert_eq!(sample.selector, selector);
+        assert_eq!(sample.used_gas(), 400_000);
+        assert!(!sample.failed);
+        assert_eq!(sample.estimated_to_used_ratio(), 2.5);
+        assert_eq!(sample.refund_ratio(), 0.6);
+        assert_eq!(sample.unscaled_usage_ratio(), 0.5);
+
+        // The sample must be removed once taken.
+        let samples_after = conn
+            .gas_estimation_dal()
+            .take_included_gas_estimates(100)
+            .await
+            .unwrap();
+        assert!(samples_after.is_empty());
+
+        let mut stats = GasEstimationStats::new(contract_address, selector.clone());
+        stats.add_sample(sample);
+        for _ in 0..2 {
+            conn.gas_estimation_dal()
+                .update_gas_estimation_stats(&[stats.clone()])
+                .await
+                .unwrap();
+        }
+        let stored_stats = conn
+            .gas_estimation_dal()
+            .get_gas_estimation_stats(contract_address, Some(&selector))
+            .await
+            .unwrap();
+        assert_eq!(stored_stats.len(), 1);
+        assert_eq!(stored_stats[0].samples, 2);
+        assert_eq!(stored_stats[0].avg_estimated_to_used_ratio(), Some(2.5));
+        assert_eq!(stored_stats[0].avg_unscaled_usage_ratio(), Some(0.5));
+
+        let other_stats = conn
+            .gas_estimation_dal()
+            .get_gas_estimation_stats(contract_address, Some(&[0; 4]))
+            .await
+            .unwrap();
+        assert!(other_stats.is_empty());
+        let all_stats = conn
+            .gas_estimation_dal()
+            .get_all_gas_estimation_stats(3)
+            .await
+            .unwrap();
+        assert!(all_stats.is_empty());
+
+        let removed = conn
+            .gas_estimation_dal()
+            .delete_stale_gas_estimates(Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+    }
+
+    #[tokio::test]
+    async fn skipping_sample_with_invalid_gas_limit() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(0))
+            .await
+            .unwrap();
+
+        let mut tx = mock_l2_transaction();
+        // Gas limit doesn't fit into `u64`.
+        tx.common_data.fee.gas_limit = U256::MAX;
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        let estimate = GasEstimate {
+            tx_hash: tx.hash(),
+            contract_address: Address::repeat_byte(1),
+            selector: vec![1, 2, 3, 4],
+            estimated_gas: 1_000_000,
+            scale_factor: 1.25,
+        };
+        conn.gas_estimation_dal()
+            .insert_gas_estimate(&estimate)
+            .await
+            .unwrap();
+
+        let mut miniblock_header = create_miniblock_header(1);
+        miniblock_header.l2_tx_count = 1;
+        conn.blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await
+            .unwrap();
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(
+                MiniblockNumber(1),
+                &[mock_execution_result(tx)],
+                U256::from(1),
+            )
+            .await;
+
+        let samples = conn
+            .gas_estimation_dal()
+            .take_included_gas_estimates(100)
+            .await
+            .unwrap();
+        assert!(samples.is_empty(), "{samples:?}");
+        // The estimate must be removed nevertheless.
+        let removed = conn
+            .gas_estimation_dal()
+            .delete_stale_gas_estimates(Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(removed, 0);
+    }
+}
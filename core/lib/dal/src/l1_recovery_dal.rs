@@ -0,0 +1,317 @@
+//! Storage for the L1 state recovery tool.
+//!
+//! Initial writes and factory deps do not depend on storage key preimages, so they are written to the regular
+//! node tables. Storage logs recovered from pubdata lack key preimages and are kept in the `l1_recovery_storage_logs`
+//! table instead of `storage_logs`. The `l1_recovery_batches` table tracks recovery progress so that the tool
+//! can be restarted.
+
+use std::collections::HashMap;
+
+use zksync_db_connection::connection::Connection;
+use zksync_types::{L1BatchNumber, MiniblockNumber, H256};
+
+use crate::Core;
+
+/// Storage slot reconstructed from L1 pubdata. Since pubdata only contains hashed storage keys,
+/// the original `(address, key)` pair for a slot is not known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveredStorageEntry {
+    pub hashed_key: H256,
+    pub enumeration_index: u64,
+    pub value: H256,
+}
+
+/// L1 batch recovered from L1 data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveredL1Batch {
+    pub number: L1BatchNumber,
+    /// Last miniblock in the batch. Storage logs and factory deps recovered for the batch are assigned to it.
+    pub miniblock_number: MiniblockNumber,
+    pub root_hash: H256,
+}
+
+#[derive(Debug)]
+pub struct L1RecoveryDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl L1RecoveryDal<'_, '_> {
+    /// Returns the last L1 batch fully recovered from L1 data.
+    pub async fn get_last_recovered_batch(&mut self) -> sqlx::Result<Option<RecoveredL1Batch>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                miniblock_number,
+                root_hash
+            FROM
+                l1_recovery_batches
+            ORDER BY
+                l1_batch_number DESC
+            LIMIT
+                1
+            "#
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map(|row| RecoveredL1Batch {
+            number: L1BatchNumber(row.l1_batch_number as u32),
+            miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+            root_hash: H256::from_slice(&row.root_hash),
+        }))
+    }
+
+    /// Returns hashed keys for the specified enumeration indices. Indices without an initial write are omitted.
+    pub async fn get_hashed_keys_by_enumeration_index(
+        &mut self,
+        indices: &[u64],
+    ) -> sqlx::Result<HashMap<u64, H256>> {
+        let indices: Vec<_> = indices.iter().map(|&index| index as i64).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hashed_key,
+                INDEX
+            FROM
+                initial_writes
+            WHERE
+                INDEX = ANY ($1)
+            "#,
+            &indices
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.index as u64, H256::from_slice(&row.hashed_key)))
+            .collect())
+    }
+
+    /// Inserts initial writes for storage entries first written in the specified L1 batch.
+    pub async fn insert_initial_writes(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        entries: &[RecoveredStorageEntry],
+    ) -> sqlx::Result<()> {
+        let mut hashed_keys = Vec::with_capacity(entries.len());
+        let mut indices = Vec::with_capacity(entries.len());
+        for entry in entries {
+            hashed_keys.push(entry.hashed_key.as_bytes());
+            indices.push(entry.enumeration_index as i64);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                initial_writes (hashed_key, INDEX, l1_batch_number, created_at, updated_at)
+            SELECT
+                u.hashed_key,
+                u.index,
+                $3,
+                NOW(),
+                NOW()
+            FROM
+                UNNEST($1::bytea[], $2::BIGINT[]) AS u (hashed_key, INDEX)
+            "#,
+            &hashed_keys as &[&[u8]],
+            &indices,
+            i64::from(l1_batch_number.0)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Inserts storage logs for entries written in the specified L1 batch.
+    pub async fn insert_storage_logs(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        entries: &[RecoveredStorageEntry],
+    ) -> sqlx::Result<()> {
+        let mut hashed_keys = Vec::with_capacity(entries.len());
+        let mut indices = Vec::with_capacity(entries.len());
+        let mut values = Vec::with_capacity(entries.len());
+        for entry in entries {
+            hashed_keys.push(entry.hashed_key.as_bytes());
+            indices.push(entry.enumeration_index as i64);
+            values.push(entry.value.as_bytes());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_recovery_storage_logs (
+                    hashed_key,
+                    l1_batch_number,
+                    enumeration_index,
+                    value,
+                    created_at
+                )
+            SELECT
+                u.hashed_key,
+                $4,
+                u.enumeration_index,
+                u.value,
+                NOW()
+            FROM
+                UNNEST($1::bytea[], $2::BIGINT[], $3::bytea[]) AS u (hashed_key, enumeration_index, value)
+            "#,
+            &hashed_keys as &[&[u8]],
+            &indices,
+            &values as &[&[u8]],
+            i64::from(l1_batch_number.0)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the latest recovered values for the specified hashed keys. Keys not written in any recovered
+    /// L1 batch (e.g., only written in genesis) are omitted.
+    pub async fn get_storage_values(
+        &mut self,
+        hashed_keys: &[H256],
+    ) -> sqlx::Result<HashMap<H256, H256>> {
+        let hashed_keys: Vec<_> = hashed_keys.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT
+                ON (hashed_key) hashed_key,
+                value
+            FROM
+                l1_recovery_storage_logs
+            WHERE
+                hashed_key = ANY ($1)
+            ORDER BY
+                hashed_key,
+                l1_batch_number DESC
+            "#,
+            &hashed_keys as &[&[u8]]
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    H256::from_slice(&row.hashed_key),
+                    H256::from_slice(&row.value),
+                )
+            })
+            .collect())
+    }
+
+    /// Marks the specified L1 batch as recovered. This should be called in the same transaction
+    /// as inserting storage logs, initial writes and factory deps for the batch.
+    pub async fn insert_recovered_batch(
+        &mut self,
+        batch: &RecoveredL1Batch,
+        commit_tx_hash: H256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_recovery_batches (
+                    l1_batch_number,
+                    miniblock_number,
+                    root_hash,
+                    commit_tx_hash,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, NOW())
+            "#,
+            i64::from(batch.number.0),
+            i64::from(batch.miniblock_number.0),
+            batch.root_hash.as_bytes(),
+            commit_tx_hash.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn recovering_storage_entries() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        assert_eq!(
+            conn.l1_recovery_dal()
+                .get_last_recovered_batch()
+                .await
+                .unwrap(),
+            None
+        );
+
+        let entries: Vec<_> = (1..=3)
+            .map(|i| RecoveredStorageEntry {
+                hashed_key: H256::repeat_byte(i),
+                enumeration_index: i.into(),
+                value: H256::from_low_u64_be(i.into()),
+            })
+            .collect();
+        let batch = RecoveredL1Batch {
+            number: L1BatchNumber(1),
+            miniblock_number: MiniblockNumber(3),
+            root_hash: H256::repeat_byte(0xaa),
+        };
+        let mut dal = conn.l1_recovery_dal();
+        dal.insert_initial_writes(batch.number, &entries)
+            .await
+            .unwrap();
+        dal.insert_storage_logs(batch.number, &entries)
+            .await
+            .unwrap();
+        dal.insert_recovered_batch(&batch, H256::zero())
+            .await
+            .unwrap();
+        assert_eq!(dal.get_last_recovered_batch().await.unwrap(), Some(batch));
+
+        let updated_entry = RecoveredStorageEntry {
+            value: H256::repeat_byte(0xff),
+            ..entries[1]
+        };
+        dal.insert_storage_logs(L1BatchNumber(2), &[updated_entry])
+            .await
+            .unwrap();
+
+        let hashed_keys = dal
+            .get_hashed_keys_by_enumeration_index(&[1, 2, 4])
+            .await
+            .unwrap();
+        assert_eq!(
+            hashed_keys,
+            HashMap::from([(1, entries[0].hashed_key), (2, entries[1].hashed_key)])
+        );
+
+        let missing_key = H256::repeat_byte(0xfe);
+        let values = dal
+            .get_storage_values(&[entries[0].hashed_key, entries[1].hashed_key, missing_key])
+            .await
+            .unwrap();
+        assert_eq!(
+            values,
+            HashMap::from([
+                (entries[0].hashed_key, entries[0].value),
+                (entries[1].hashed_key, updated_entry.value)
+            ])
+        );
+
+        // Recovered logs must not leak into the node storage logs.
+        let node_values = conn
+            .storage_logs_dal()
+            .get_storage_values(&[entries[0].hashed_key], MiniblockNumber(u32::MAX))
+            .await
+            .unwrap();
+        assert_eq!(node_values[&entries[0].hashed_key], None);
+    }
+}
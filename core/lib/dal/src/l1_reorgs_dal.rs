@@ -0,0 +1,164 @@
+//! Storage for L1 block hashes that components rely on, and for detected L1 reorgs.
+
+use zksync_db_connection::connection::Connection;
+use zksync_types::H256;
+
+use crate::Core;
+
+/// L1 block that a component has relied on, identified by its number and hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1BlockCheckpoint {
+    pub number: u64,
+    pub hash: H256,
+}
+
+/// Information about an L1 reorg detected by one of the components.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct L1Reorg {
+    /// Name of the component that has detected the reorg.
+    pub component: String,
+    /// L1 block the component has rolled back its state to.
+    pub reverted_to_l1_block: u64,
+    /// Whether the reorg cannot be handled automatically (e.g., it affects already executed priority operations).
+    pub is_fatal: bool,
+}
+
+#[derive(Debug)]
+pub struct L1ReorgsDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl L1ReorgsDal<'_, '_> {
+    /// Records an L1 block processed by `eth_watch`.
+    pub async fn insert_eth_watch_checkpoint(
+        &mut self,
+        checkpoint: L1BlockCheckpoint,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_watch_checkpoints (l1_block_number, l1_block_hash, created_at)
+            VALUES
+                ($1, $2, NOW())
+            ON CONFLICT (l1_block_number) DO
+            UPDATE
+            SET
+                l1_block_hash = excluded.l1_block_hash
+            "#,
+            checkpoint.number as i64,
+            checkpoint.hash.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns up to `limit` most recent L1 blocks processed by `eth_watch`, starting from the newest one.
+    pub async fn get_eth_watch_checkpoints(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<L1BlockCheckpoint>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_block_number,
+                l1_block_hash
+            FROM
+                eth_watch_checkpoints
+            ORDER BY
+                l1_block_number DESC
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BlockCheckpoint {
+                number: row.l1_block_number as u64,
+                hash: H256::from_slice(&row.l1_block_hash),
+            })
+            .collect())
+    }
+
+    /// Removes `eth_watch` checkpoints for L1 blocks after the specified one.
+    pub async fn delete_eth_watch_checkpoints_after(
+        &mut self,
+        l1_block_number: u64,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM eth_watch_checkpoints
+            WHERE
+                l1_block_number > $1
+            "#,
+            l1_block_number as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Removes `eth_watch` checkpoints for L1 blocks before the specified one.
+    pub async fn prune_eth_watch_checkpoints(&mut self, l1_block_number: u64) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM eth_watch_checkpoints
+            WHERE
+                l1_block_number < $1
+            "#,
+            l1_block_number as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn insert_l1_reorg(&mut self, reorg: &L1Reorg) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_reorgs (component, reverted_to_l1_block, is_fatal, created_at)
+            VALUES
+                ($1, $2, $3, NOW())
+            "#,
+            &reorg.component,
+            reorg.reverted_to_l1_block as i64,
+            reorg.is_fatal
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the most recent fatal L1 reorg, if any. Fatal reorgs must be resolved manually by removing
+    /// the corresponding rows from the `l1_reorgs` table.
+    pub async fn get_last_fatal_l1_reorg(&mut self) -> sqlx::Result<Option<L1Reorg>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                component,
+                reverted_to_l1_block
+            FROM
+                l1_reorgs
+            WHERE
+                is_fatal = TRUE
+            ORDER BY
+                id DESC
+            LIMIT
+                1
+            "#
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map(|row| L1Reorg {
+            component: row.component,
+            reverted_to_l1_block: row.reverted_to_l1_block as u64,
+            is_fatal: true,
+        }))
+    }
+}
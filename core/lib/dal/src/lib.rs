@@ -10,9 +10,12 @@ pub use zksync_db_connection::{connection::Connection, connection_pool::Connecti
 use crate::{
     basic_witness_input_producer_dal::BasicWitnessInputProducerDal, blocks_dal::BlocksDal,
     blocks_web3_dal::BlocksWeb3Dal, consensus_dal::ConsensusDal,
-    contract_verification_dal::ContractVerificationDal, eth_sender_dal::EthSenderDal,
-    events_dal::EventsDal, events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
-    proof_generation_dal::ProofGenerationDal, protocol_versions_dal::ProtocolVersionsDal,
+    contract_verification_dal::ContractVerificationDal, data_availability_dal::DataAvailabilityDal,
+    eth_sender_dal::EthSenderDal, eth_watch_dal::EthWatchDal, events_dal::EventsDal,
+    events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
+    gas_estimation_dal::GasEstimationDal, l1_recovery_dal::L1RecoveryDal,
+    l1_reorgs_dal::L1ReorgsDal, proof_generation_dal::ProofGenerationDal,
+    protocol_versions_dal::ProtocolVersionsDal,
     protocol_versions_web3_dal::ProtocolVersionsWeb3Dal,
     snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
     snapshots_dal::SnapshotsDal, storage_logs_dal::StorageLogsDal,
@@ -27,10 +30,15 @@ pub mod blocks_dal;
 pub mod blocks_web3_dal;
 pub mod consensus_dal;
 pub mod contract_verification_dal;
+pub mod data_availability_dal;
 pub mod eth_sender_dal;
+pub mod eth_watch_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
 pub mod factory_deps_dal;
+pub mod gas_estimation_dal;
+pub mod l1_recovery_dal;
+pub mod l1_reorgs_dal;
 mod models;
 pub mod proof_generation_dal;
 pub mod protocol_versions_dal;
@@ -79,12 +87,16 @@ where
 
     fn eth_sender_dal(&mut self) -> EthSenderDal<'_, 'a>;
 
+    fn eth_watch_dal(&mut self) -> EthWatchDal<'_, 'a>;
+
     fn events_dal(&mut self) -> EventsDal<'_, 'a>;
 
     fn events_web3_dal(&mut self) -> EventsWeb3Dal<'_, 'a>;
 
     fn factory_deps_dal(&mut self) -> FactoryDepsDal<'_, 'a>;
 
+    fn gas_estimation_dal(&mut self) -> GasEstimationDal<'_, 'a>;
+
     fn storage_web3_dal(&mut self) -> StorageWeb3Dal<'_, 'a>;
 
     fn storage_logs_dal(&mut self) -> StorageLogsDal<'_, 'a>;
@@ -116,6 +128,12 @@ where
     fn snapshots_creator_dal(&mut self) -> SnapshotsCreatorDal<'_, 'a>;
 
     fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a>;
+
+    fn l1_recovery_dal(&mut self) -> L1RecoveryDal<'_, 'a>;
+
+    fn l1_reorgs_dal(&mut self) -> L1ReorgsDal<'_, 'a>;
+
+    fn data_availability_dal(&mut self) -> DataAvailabilityDal<'_, 'a>;
 }
 
 #[derive(Clone, Debug)]
@@ -155,6 +173,10 @@ impl<'a> CoreDal<'a> for Connection<'a, Core> {
         EthSenderDal { storage: self }
     }
 
+    fn eth_watch_dal(&mut self) -> EthWatchDal<'_, 'a> {
+        EthWatchDal { storage: self }
+    }
+
     fn events_dal(&mut self) -> EventsDal<'_, 'a> {
         EventsDal { storage: self }
     }
@@ -167,6 +189,10 @@ impl<'a> CoreDal<'a> for Connection<'a, Core> {
         FactoryDepsDal { storage: self }
     }
 
+    fn gas_estimation_dal(&mut self) -> GasEstimationDal<'_, 'a> {
+        GasEstimationDal { storage: self }
+    }
+
     fn storage_web3_dal(&mut self) -> StorageWeb3Dal<'_, 'a> {
         StorageWeb3Dal { storage: self }
     }
@@ -226,4 +252,16 @@ impl<'a> CoreDal<'a> for Connection<'a, Core> {
     fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a> {
         SnapshotRecoveryDal { storage: self }
     }
+
+    fn l1_recovery_dal(&mut self) -> L1RecoveryDal<'_, 'a> {
+        L1RecoveryDal { storage: self }
+    }
+
+    fn l1_reorgs_dal(&mut self) -> L1ReorgsDal<'_, 'a> {
+        L1ReorgsDal { storage: self }
+    }
+
+    fn data_availability_dal(&mut self) -> DataAvailabilityDal<'_, 'a> {
+        DataAvailabilityDal { storage: self }
+    }
 }
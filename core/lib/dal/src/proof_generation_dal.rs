@@ -163,6 +163,34 @@ impl ProofGenerationDal<'_, '_> {
         result
     }
 
+    /// Returns L1 batches after `l1_batch_number` with generated proofs, in ascending order.
+    pub async fn get_l1_batches_with_generated_proofs_after(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<L1BatchNumber>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number
+            FROM
+                proof_generation_details
+            WHERE
+                status = 'generated'
+                AND l1_batch_number > $1
+            ORDER BY
+                l1_batch_number ASC
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.l1_batch_number as u32))
+            .collect())
+    }
+
     pub async fn get_oldest_not_generated_batch(&mut self) -> Option<L1BatchNumber> {
         let result: Option<L1BatchNumber> = sqlx::query!(
             r#"
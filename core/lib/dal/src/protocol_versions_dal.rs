@@ -6,7 +6,7 @@ use zksync_db_connection::connection::Connection;
 use zksync_types::{
     protocol_upgrade::{ProtocolUpgradeTx, ProtocolVersion},
     protocol_version::{L1VerifierConfig, VerifierParams},
-    ProtocolVersionId, H256,
+    L1BlockNumber, ProtocolVersionId, H256,
 };
 
 use crate::{
@@ -355,6 +355,29 @@ impl ProtocolVersionsDal<'_, '_> {
             .collect()
     }
 
+    /// Returns the number of protocol versions with upgrade transactions received in L1 blocks
+    /// after the specified one.
+    pub async fn count_upgrades_after_l1_block(
+        &mut self,
+        l1_block_number: L1BlockNumber,
+    ) -> sqlx::Result<u64> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                protocol_versions
+                INNER JOIN transactions ON transactions.hash = protocol_versions.upgrade_tx_hash
+            WHERE
+                transactions.l1_block_number > $1
+            "#,
+            l1_block_number.0 as i32
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(count as u64)
+    }
+
     pub async fn get_protocol_upgrade_tx(
         &mut self,
         protocol_version_id: ProtocolVersionId,
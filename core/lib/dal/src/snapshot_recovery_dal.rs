@@ -5,6 +5,28 @@ use zksync_types::{
 
 use crate::Core;
 
+/// Secondary indexes on tables bulk-loaded during snapshot recovery, as `(name, definition)` tuples.
+/// Building these indexes once after the tables are filled is much faster than maintaining them during inserts.
+/// Primary keys and unique indexes are not deferred since they are required for correctness.
+const DEFERRED_STORAGE_INDEXES: &[(&str, &str)] = &[
+    (
+        "storage_logs_block_number_idx",
+        "storage_logs (miniblock_number)",
+    ),
+    (
+        "storage_logs_contract_address_tx_hash_idx_upd",
+        r"storage_logs (tx_hash) WHERE (address = '\x0000000000000000000000000000000000008002'::bytea)",
+    ),
+    (
+        "initial_writes_l1_batch_number_index",
+        "initial_writes (l1_batch_number)",
+    ),
+    (
+        "ix_initial_writes_t1",
+        "initial_writes USING btree (hashed_key) INCLUDE (l1_batch_number)",
+    ),
+];
+
 #[derive(Debug)]
 pub struct SnapshotRecoveryDal<'a, 'c> {
     pub(crate) storage: &'a mut Connection<'c, Core>,
@@ -66,6 +88,31 @@ impl SnapshotRecoveryDal<'_, '_> {
         Ok(())
     }
 
+    /// Drops secondary indexes on `storage_logs` and `initial_writes` before bulk-loading snapshot data.
+    /// This method is idempotent; indexes must be restored with [`Self::create_deferred_storage_indexes()`]
+    /// once all data is loaded.
+    pub async fn drop_deferred_storage_indexes(&mut self) -> sqlx::Result<()> {
+        for (name, _) in DEFERRED_STORAGE_INDEXES {
+            sqlx::query(&format!("DROP INDEX IF EXISTS {name}"))
+                .execute(self.storage.conn())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Creates secondary indexes dropped by [`Self::drop_deferred_storage_indexes()`]. This method is idempotent,
+    /// so it's safe to call it after a restart even if some or all of the indexes are already created.
+    pub async fn create_deferred_storage_indexes(&mut self) -> sqlx::Result<()> {
+        for (name, definition) in DEFERRED_STORAGE_INDEXES {
+            sqlx::query(&format!(
+                "CREATE INDEX IF NOT EXISTS {name} ON {definition}"
+            ))
+            .execute(self.storage.conn())
+            .await?;
+        }
+        Ok(())
+    }
+
     pub async fn get_applied_snapshot_status(
         &mut self,
     ) -> sqlx::Result<Option<SnapshotRecoveryStatus>> {
@@ -102,10 +149,12 @@ impl SnapshotRecoveryDal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
+    use zksync_db_connection::connection::Connection;
     use zksync_types::{
         snapshots::SnapshotRecoveryStatus, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256,
     };
 
+    use super::DEFERRED_STORAGE_INDEXES;
     use crate::{ConnectionPool, Core, CoreDal};
 
     #[tokio::test]
@@ -154,4 +203,39 @@ mod tests {
             .unwrap();
         assert_eq!(status, updated_status_from_db.unwrap());
     }
+
+    async fn count_deferred_indexes(conn: &mut Connection<'_, Core>) -> i64 {
+        let names: Vec<_> = DEFERRED_STORAGE_INDEXES
+            .iter()
+            .map(|&(name, _)| name)
+            .collect();
+        sqlx::query_scalar("SELECT COUNT(*) FROM pg_indexes WHERE indexname = ANY($1)")
+            .bind(names)
+            .fetch_one(conn.conn())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn dropping_and_creating_deferred_indexes() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        let index_count = DEFERRED_STORAGE_INDEXES.len() as i64;
+        assert_eq!(count_deferred_indexes(&mut conn).await, index_count);
+
+        for _ in 0..2 {
+            conn.snapshot_recovery_dal()
+                .drop_deferred_storage_indexes()
+                .await
+                .unwrap();
+            assert_eq!(count_deferred_indexes(&mut conn).await, 0);
+        }
+        for _ in 0..2 {
+            conn.snapshot_recovery_dal()
+                .create_deferred_storage_indexes()
+                .await
+                .unwrap();
+            assert_eq!(count_deferred_indexes(&mut conn).await, index_count);
+        }
+    }
 }
@@ -111,6 +111,112 @@ impl SnapshotsCreatorDal<'_, '_> {
         Ok(storage_logs)
     }
 
+    /// Returns an upper bound on the number of storage keys changed in the miniblock range
+    /// `(base_miniblock_number..=miniblock_number]`. Used to size chunks of incremental snapshots.
+    pub async fn get_changed_storage_logs_count(
+        &mut self,
+        base_miniblock_number: MiniblockNumber,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<u64> {
+        let count = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number > $1
+                AND miniblock_number <= $2
+            "#,
+            i64::from(base_miniblock_number.0),
+            i64::from(miniblock_number.0)
+        )
+        .instrument("get_changed_storage_logs_count")
+        .with_arg("base_miniblock_number", &base_miniblock_number)
+        .with_arg("miniblock_number", &miniblock_number)
+        .report_latency()
+        .expect_slow_query()
+        .fetch_one(self.storage)
+        .await?
+        .count;
+        Ok(count as u64)
+    }
+
+    /// Constructs a `storage_logs` chunk for an incremental snapshot. Only contains keys changed in the miniblock range
+    /// `(base_miniblock_number..=miniblock_number]`; values are taken as of the end of `l1_batch_number`.
+    /// `miniblock_number` MUST be the last miniblock of the `l1_batch_number` batch, and `base_miniblock_number`
+    /// MUST be the last miniblock of the base snapshot L1 batch.
+    pub async fn get_storage_logs_diff_chunk(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        l1_batch_number: L1BatchNumber,
+        base_miniblock_number: MiniblockNumber,
+        hashed_keys_range: std::ops::RangeInclusive<H256>,
+    ) -> sqlx::Result<Vec<SnapshotStorageLog>> {
+        // Since the latest write for each key is selected among writes after the base miniblock, it coincides
+        // with the latest write overall. Phantom writes are filtered out in the same way as for full snapshots.
+        let storage_logs = sqlx::query!(
+            r#"
+            SELECT
+                storage_logs.key AS "key!",
+                storage_logs.value AS "value!",
+                storage_logs.address AS "address!",
+                storage_logs.miniblock_number AS "miniblock_number!",
+                initial_writes.l1_batch_number AS "l1_batch_number!",
+                initial_writes.index
+            FROM
+                (
+                    SELECT
+                        hashed_key,
+                        MAX(ARRAY[miniblock_number, operation_number]::INT[]) AS op
+                    FROM
+                        storage_logs
+                    WHERE
+                        miniblock_number <= $1
+                        AND miniblock_number > $5
+                        AND hashed_key >= $3
+                        AND hashed_key <= $4
+                    GROUP BY
+                        hashed_key
+                    ORDER BY
+                        hashed_key
+                ) AS keys
+                INNER JOIN storage_logs ON keys.hashed_key = storage_logs.hashed_key
+                AND storage_logs.miniblock_number = keys.op[1]
+                AND storage_logs.operation_number = keys.op[2]
+                INNER JOIN initial_writes ON keys.hashed_key = initial_writes.hashed_key
+            WHERE
+                initial_writes.l1_batch_number <= $2
+            "#,
+            i64::from(miniblock_number.0),
+            i64::from(l1_batch_number.0),
+            hashed_keys_range.start().as_bytes(),
+            hashed_keys_range.end().as_bytes(),
+            i64::from(base_miniblock_number.0)
+        )
+        .instrument("get_storage_logs_diff_chunk")
+        .with_arg("miniblock_number", &miniblock_number)
+        .with_arg("base_miniblock_number", &base_miniblock_number)
+        .with_arg("min_hashed_key", &hashed_keys_range.start())
+        .with_arg("max_hashed_key", &hashed_keys_range.end())
+        .report_latency()
+        .expect_slow_query()
+        .fetch_all(self.storage)
+        .await?
+        .iter()
+        .map(|row| SnapshotStorageLog {
+            key: StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            ),
+            value: H256::from_slice(&row.value),
+            l1_batch_number_of_initial_write: L1BatchNumber(row.l1_batch_number as u32),
+            enumeration_index: row.index as u64,
+        })
+        .collect();
+        Ok(storage_logs)
+    }
+
     /// Returns all factory dependencies up to and including the specified `miniblock_number`.
     pub async fn get_all_factory_deps(
         &mut self,
@@ -139,6 +245,38 @@ impl SnapshotsCreatorDal<'_, '_> {
             .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
             .collect())
     }
+
+    /// Returns factory dependencies added in the miniblock range `(base_miniblock_number..=miniblock_number]`.
+    pub async fn get_new_factory_deps(
+        &mut self,
+        base_miniblock_number: MiniblockNumber,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<Vec<(H256, Vec<u8>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                bytecode_hash,
+                bytecode
+            FROM
+                factory_deps
+            WHERE
+                miniblock_number > $1
+                AND miniblock_number <= $2
+            "#,
+            i64::from(base_miniblock_number.0),
+            i64::from(miniblock_number.0),
+        )
+        .instrument("get_new_factory_deps")
+        .report_latency()
+        .expect_slow_query()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -260,6 +398,68 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn getting_storage_log_diff_chunks() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+
+        let keys: Vec<_> = (0..10)
+            .map(|i| StorageKey::new(AccountTreeId::default(), H256::from_low_u64_be(i)))
+            .collect();
+        let logs: Vec<_> = keys
+            .iter()
+            .map(|&key| StorageLog::new_write_log(key, H256::repeat_byte(1)))
+            .collect();
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), logs)])
+            .await
+            .unwrap();
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(1), &keys)
+            .await
+            .unwrap();
+
+        let new_key = StorageKey::new(AccountTreeId::default(), H256::repeat_byte(0xee));
+        let new_logs = vec![
+            StorageLog::new_write_log(keys[3], H256::repeat_byte(2)),
+            StorageLog::new_write_log(new_key, H256::repeat_byte(3)),
+        ];
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(2), &[(H256::zero(), new_logs)])
+            .await
+            .unwrap();
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(2), &[new_key])
+            .await
+            .unwrap();
+
+        let changed_count = conn
+            .snapshots_creator_dal()
+            .get_changed_storage_logs_count(MiniblockNumber(1), MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(changed_count, 2);
+
+        let mut logs = conn
+            .snapshots_creator_dal()
+            .get_storage_logs_diff_chunk(
+                MiniblockNumber(2),
+                L1BatchNumber(2),
+                MiniblockNumber(1),
+                H256::zero()..=H256::repeat_byte(0xff),
+            )
+            .await
+            .unwrap();
+        logs.sort_unstable_by_key(|log| log.enumeration_index);
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].key, keys[3]);
+        assert_eq!(logs[0].value, H256::repeat_byte(2));
+        assert_eq!(logs[0].l1_batch_number_of_initial_write, L1BatchNumber(1));
+        assert_eq!(logs[1].key, new_key);
+        assert_eq!(logs[1].value, H256::repeat_byte(3));
+        assert_eq!(logs[1].l1_batch_number_of_initial_write, L1BatchNumber(2));
+    }
+
     #[tokio::test]
     async fn phantom_writes_are_filtered_out() {
         let pool = ConnectionPool::<Core>::test_pool().await;
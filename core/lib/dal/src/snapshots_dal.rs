@@ -1,7 +1,7 @@
 use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
 use zksync_types::{
     snapshots::{AllSnapshots, SnapshotMetadata},
-    L1BatchNumber,
+    L1BatchNumber, PackedEthSignature, H256,
 };
 
 use crate::Core;
@@ -9,20 +9,40 @@ use crate::Core;
 #[derive(Debug, sqlx::FromRow)]
 struct StorageSnapshotMetadata {
     l1_batch_number: i64,
+    base_l1_batch_number: Option<i64>,
     storage_logs_filepaths: Vec<String>,
+    storage_logs_hashes: Vec<Vec<u8>>,
     factory_deps_filepath: String,
+    factory_deps_hash: Option<Vec<u8>>,
+    manifest_signature: Option<Vec<u8>>,
 }
 
 impl From<StorageSnapshotMetadata> for SnapshotMetadata {
     fn from(row: StorageSnapshotMetadata) -> Self {
+        // Hashes may be missing for snapshots created before they were recorded.
+        let storage_logs_hashes = (0..row.storage_logs_filepaths.len())
+            .map(|i| {
+                let hash = row.storage_logs_hashes.get(i)?;
+                (!hash.is_empty()).then(|| H256::from_slice(hash))
+            })
+            .collect();
         Self {
             l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            base_l1_batch_number: row
+                .base_l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
             storage_logs_filepaths: row
                 .storage_logs_filepaths
                 .into_iter()
                 .map(|path| (!path.is_empty()).then_some(path))
                 .collect(),
+            storage_logs_hashes,
             factory_deps_filepath: row.factory_deps_filepath,
+            factory_deps_hash: row.factory_deps_hash.map(|hash| H256::from_slice(&hash)),
+            manifest_signature: row.manifest_signature.map(|signature| {
+                PackedEthSignature::deserialize_packed(&signature)
+                    .expect("invalid snapshot manifest signature in Postgres")
+            }),
         }
     }
 }
@@ -33,28 +53,46 @@ pub struct SnapshotsDal<'a, 'c> {
 }
 
 impl SnapshotsDal<'_, '_> {
+    /// Adds a new snapshot. If `base_l1_batch_number` is specified, the snapshot is incremental, i.e.,
+    /// it only contains changes made after the snapshot for the base L1 batch.
     pub async fn add_snapshot(
         &mut self,
         l1_batch_number: L1BatchNumber,
+        base_l1_batch_number: Option<L1BatchNumber>,
         storage_logs_chunk_count: u64,
         factory_deps_filepaths: &str,
+        factory_deps_hash: H256,
     ) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
             INSERT INTO
                 snapshots (
                     l1_batch_number,
+                    base_l1_batch_number,
                     storage_logs_filepaths,
+                    storage_logs_hashes,
                     factory_deps_filepath,
+                    factory_deps_hash,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, ARRAY_FILL(''::TEXT, ARRAY[$2::INTEGER]), $3, NOW(), NOW())
+                (
+                    $1,
+                    $2,
+                    ARRAY_FILL(''::TEXT, ARRAY[$3::INTEGER]),
+                    ARRAY_FILL(''::BYTEA, ARRAY[$3::INTEGER]),
+                    $4,
+                    $5,
+                    NOW(),
+                    NOW()
+                )
             "#,
             l1_batch_number.0 as i32,
+            base_l1_batch_number.map(|number| i64::from(number.0)),
             storage_logs_chunk_count as i32,
             factory_deps_filepaths,
+            factory_deps_hash.as_bytes(),
         )
         .instrument("add_snapshot")
         .report_latency()
@@ -68,12 +106,14 @@ impl SnapshotsDal<'_, '_> {
         l1_batch_number: L1BatchNumber,
         chunk_id: u64,
         storage_logs_filepath: &str,
+        storage_logs_hash: H256,
     ) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
             UPDATE snapshots
             SET
                 storage_logs_filepaths[$2] = $3,
+                storage_logs_hashes[$2] = $4,
                 updated_at = NOW()
             WHERE
                 l1_batch_number = $1
@@ -81,6 +121,7 @@ impl SnapshotsDal<'_, '_> {
             l1_batch_number.0 as i32,
             chunk_id as i32 + 1,
             storage_logs_filepath,
+            storage_logs_hash.as_bytes(),
         )
         .execute(self.storage.conn())
         .await?;
@@ -88,6 +129,31 @@ impl SnapshotsDal<'_, '_> {
         Ok(())
     }
 
+    /// Sets the operator signature for the manifest of the specified snapshot.
+    pub async fn set_manifest_signature(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        signature: &PackedEthSignature,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE snapshots
+            SET
+                manifest_signature = $2,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i32,
+            signature.serialize_packed().as_slice()
+        )
+        .instrument("set_manifest_signature")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn get_all_complete_snapshots(&mut self) -> sqlx::Result<AllSnapshots> {
         let rows = sqlx::query!(
             r#"
@@ -122,8 +188,12 @@ impl SnapshotsDal<'_, '_> {
             r#"
             SELECT
                 l1_batch_number,
+                base_l1_batch_number,
                 factory_deps_filepath,
-                storage_logs_filepaths
+                factory_deps_hash,
+                storage_logs_filepaths,
+                storage_logs_hashes,
+                manifest_signature
             FROM
                 snapshots
             ORDER BY
@@ -149,8 +219,12 @@ impl SnapshotsDal<'_, '_> {
             r#"
             SELECT
                 l1_batch_number,
+                base_l1_batch_number,
                 factory_deps_filepath,
-                storage_logs_filepaths
+                factory_deps_hash,
+                storage_logs_filepaths,
+                storage_logs_hashes,
+                manifest_signature
             FROM
                 snapshots
             WHERE
@@ -165,11 +239,56 @@ impl SnapshotsDal<'_, '_> {
 
         Ok(row.map(Into::into))
     }
+
+    /// Returns metadata for all snapshots (including incomplete ones), ordered by descending L1 batch number.
+    pub async fn get_all_snapshots_metadata(&mut self) -> sqlx::Result<Vec<SnapshotMetadata>> {
+        let rows = sqlx::query_as!(
+            StorageSnapshotMetadata,
+            r#"
+            SELECT
+                l1_batch_number,
+                base_l1_batch_number,
+                factory_deps_filepath,
+                factory_deps_hash,
+                storage_logs_filepaths,
+                storage_logs_hashes,
+                manifest_signature
+            FROM
+                snapshots
+            ORDER BY
+                l1_batch_number DESC
+            "#
+        )
+        .instrument("get_all_snapshots_metadata")
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Removes metadata for the snapshot with the specified L1 batch number. Snapshot files must be removed
+    /// from the object store separately.
+    pub async fn delete_snapshot(&mut self, l1_batch_number: L1BatchNumber) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM snapshots
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i32
+        )
+        .instrument("delete_snapshot")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::L1BatchNumber;
+    use zksync_types::{L1BatchNumber, PackedEthSignature, H256};
 
     use crate::{ConnectionPool, Core, CoreDal};
 
@@ -179,9 +298,15 @@ mod tests {
         let mut conn = pool.connection().await.unwrap();
         let mut dal = conn.snapshots_dal();
         let l1_batch_number = L1BatchNumber(100);
-        dal.add_snapshot(l1_batch_number, 2, "gs:///bucket/factory_deps.bin")
-            .await
-            .expect("Failed to add snapshot");
+        dal.add_snapshot(
+            l1_batch_number,
+            None,
+            2,
+            "gs:///bucket/factory_deps.bin",
+            H256::zero(),
+        )
+        .await
+        .expect("Failed to add snapshot");
 
         let snapshots = dal
             .get_all_complete_snapshots()
@@ -194,6 +319,7 @@ mod tests {
                 l1_batch_number,
                 i,
                 "gs:///bucket/chunk.bin",
+                H256::repeat_byte(i as u8),
             )
             .await
             .unwrap();
@@ -211,6 +337,60 @@ mod tests {
             .expect("Failed to retrieve snapshot")
             .unwrap();
         assert_eq!(snapshot_metadata.l1_batch_number, l1_batch_number);
+        assert_eq!(snapshot_metadata.base_l1_batch_number, None);
+    }
+
+    #[tokio::test]
+    async fn adding_and_removing_incremental_snapshot() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        dal.add_snapshot(
+            L1BatchNumber(100),
+            None,
+            1,
+            "gs:///bucket/factory_deps.bin",
+            H256::zero(),
+        )
+        .await
+        .unwrap();
+        dal.add_snapshot(
+            L1BatchNumber(200),
+            Some(L1BatchNumber(100)),
+            1,
+            "gs:///bucket/factory_deps_diff.bin",
+            H256::zero(),
+        )
+        .await
+        .unwrap();
+
+        let snapshot_metadata = dal
+            .get_newest_snapshot_metadata()
+            .await
+            .unwrap()
+            .expect("no snapshot");
+        assert_eq!(snapshot_metadata.l1_batch_number, L1BatchNumber(200));
+        assert_eq!(
+            snapshot_metadata.base_l1_batch_number,
+            Some(L1BatchNumber(100))
+        );
+        assert!(snapshot_metadata.is_incremental());
+
+        let all_snapshots = dal.get_all_snapshots_metadata().await.unwrap();
+        let l1_batch_numbers: Vec<_> = all_snapshots
+            .iter()
+            .map(|snapshot| snapshot.l1_batch_number)
+            .collect();
+        assert_eq!(l1_batch_numbers, [L1BatchNumber(200), L1BatchNumber(100)]);
+
+        dal.delete_snapshot(L1BatchNumber(100)).await.unwrap();
+        assert!(dal
+            .get_snapshot_metadata(L1BatchNumber(100))
+            .await
+            .unwrap()
+            .is_none());
+        let all_snapshots = dal.get_all_snapshots_metadata().await.unwrap();
+        assert_eq!(all_snapshots.len(), 1);
     }
 
     #[tokio::test]
@@ -219,14 +399,25 @@ mod tests {
         let mut conn = pool.connection().await.unwrap();
         let mut dal = conn.snapshots_dal();
         let l1_batch_number = L1BatchNumber(100);
-        dal.add_snapshot(l1_batch_number, 2, "gs:///bucket/factory_deps.bin")
-            .await
-            .expect("Failed to add snapshot");
+        dal.add_snapshot(
+            l1_batch_number,
+            None,
+            2,
+            "gs:///bucket/factory_deps.bin",
+            H256::zero(),
+        )
+        .await
+        .expect("Failed to add snapshot");
 
         let storage_log_filepaths = ["gs:///bucket/test_file1.bin", "gs:///bucket/test_file2.bin"];
-        dal.add_storage_logs_filepath_for_snapshot(l1_batch_number, 1, storage_log_filepaths[1])
-            .await
-            .unwrap();
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            1,
+            storage_log_filepaths[1],
+            H256::repeat_byte(2),
+        )
+        .await
+        .unwrap();
 
         let files = dal
             .get_snapshot_metadata(l1_batch_number)
@@ -239,9 +430,14 @@ mod tests {
             [None, Some("gs:///bucket/test_file2.bin".to_string())]
         );
 
-        dal.add_storage_logs_filepath_for_snapshot(l1_batch_number, 0, storage_log_filepaths[0])
-            .await
-            .unwrap();
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            0,
+            storage_log_filepaths[0],
+            H256::repeat_byte(1),
+        )
+        .await
+        .unwrap();
 
         let files = dal
             .get_snapshot_metadata(l1_batch_number)
@@ -257,4 +453,53 @@ mod tests {
             ]
         );
     }
+
+    #[tokio::test]
+    async fn recording_hashes_and_manifest_signature() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        let l1_batch_number = L1BatchNumber(100);
+        let factory_deps_hash = H256::repeat_byte(0xff);
+        dal.add_snapshot(
+            l1_batch_number,
+            None,
+            2,
+            "gs:///bucket/factory_deps.bin",
+            factory_deps_hash,
+        )
+        .await
+        .unwrap();
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            1,
+            "gs:///bucket/test_file2.bin",
+            H256::repeat_byte(2),
+        )
+        .await
+        .unwrap();
+
+        let snapshot_metadata = dal
+            .get_snapshot_metadata(l1_batch_number)
+            .await
+            .unwrap()
+            .expect("no snapshot");
+        assert_eq!(snapshot_metadata.factory_deps_hash, Some(factory_deps_hash));
+        assert_eq!(
+            snapshot_metadata.storage_logs_hashes,
+            [None, Some(H256::repeat_byte(2))]
+        );
+        assert_eq!(snapshot_metadata.manifest_signature, None);
+
+        let signature = PackedEthSignature::sign_raw(&H256::repeat_byte(1), &H256::zero()).unwrap();
+        dal.set_manifest_signature(l1_batch_number, &signature)
+            .await
+            .unwrap();
+        let snapshot_metadata = dal
+            .get_snapshot_metadata(l1_batch_number)
+            .await
+            .unwrap()
+            .expect("no snapshot");
+        assert_eq!(snapshot_metadata.manifest_signature, Some(signature));
+    }
 }
@@ -111,6 +111,75 @@ impl StorageLogsDal<'_, '_> {
         Ok(())
     }
 
+    /// Inserts or updates storage logs from an incremental snapshot. Unlike [`Self::insert_storage_logs_from_snapshot()`],
+    /// this method can be used when some of the keys are already recovered from a previous snapshot;
+    /// their values are overwritten.
+    pub async fn upsert_storage_logs_from_snapshot(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        snapshot_storage_logs: &[SnapshotStorageLog],
+    ) -> sqlx::Result<()> {
+        let mut hashed_keys = Vec::with_capacity(snapshot_storage_logs.len());
+        let mut addresses = Vec::with_capacity(snapshot_storage_logs.len());
+        let mut keys = Vec::with_capacity(snapshot_storage_logs.len());
+        let mut values = Vec::with_capacity(snapshot_storage_logs.len());
+        let mut operation_numbers = Vec::with_capacity(snapshot_storage_logs.len());
+        for log in snapshot_storage_logs {
+            hashed_keys.push(log.key.hashed_key().0.to_vec());
+            addresses.push(log.key.address().as_bytes());
+            keys.push(log.key.key().as_bytes());
+            values.push(log.value.as_bytes());
+            operation_numbers.push(log.enumeration_index as i32);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                storage_logs (
+                    hashed_key,
+                    address,
+                    key,
+                    value,
+                    operation_number,
+                    tx_hash,
+                    miniblock_number,
+                    created_at,
+                    updated_at
+                )
+            SELECT
+                u.hashed_key,
+                u.address,
+                u.key,
+                u.value,
+                u.operation_number,
+                $6,
+                $7,
+                NOW(),
+                NOW()
+            FROM
+                UNNEST($1::bytea[], $2::bytea[], $3::bytea[], $4::bytea[], $5::INT[]) AS u (hashed_key, address, key, value, operation_number)
+            ON CONFLICT (hashed_key, miniblock_number, operation_number) DO
+            UPDATE
+            SET
+                value = excluded.value,
+                updated_at = NOW()
+            "#,
+            &hashed_keys,
+            &addresses as &[&[u8]],
+            &keys as &[&[u8]],
+            &values as &[&[u8]],
+            &operation_numbers,
+            H256::zero().as_bytes(),
+            i64::from(miniblock_number.0)
+        )
+        .instrument("upsert_storage_logs_from_snapshot")
+        .with_arg("miniblock_number", &miniblock_number)
+        .with_arg("logs.len", &snapshot_storage_logs.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn append_storage_logs(
         &mut self,
         block_number: MiniblockNumber,
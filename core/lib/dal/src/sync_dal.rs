@@ -1,7 +1,11 @@
+use anyhow::Context as _;
 use zksync_db_connection::{
     connection::Connection, instrument::InstrumentExt, metrics::MethodLatency,
 };
-use zksync_types::{api::en, MiniblockNumber};
+use zksync_types::{
+    api::en, web3::types::Bytes, AccountTreeId, Address, L1BatchNumber, MiniblockNumber,
+    StorageKey, StorageLog, H256,
+};
 
 use crate::{
     models::storage_sync::{StorageSyncBlock, SyncBlock},
@@ -103,14 +107,204 @@ impl SyncDal<'_, '_> {
         };
         Ok(Some(block.into_api(transactions)))
     }
+
+    /// Returns changes to the node state produced by the specified miniblock.
+    pub async fn sync_l2_block_state_diff(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> anyhow::Result<Option<en::L2BlockStateDiff>> {
+        let _latency = MethodLatency::new("sync_dal_sync_l2_block_state_diff");
+        let Some(block) = self.sync_block_inner(block_number).await? else {
+            return Ok(None);
+        };
+        let base_fee_per_gas = self
+            .storage
+            .blocks_dal()
+            .get_miniblock_header(block_number)
+            .await?
+            .with_context(|| format!("miniblock #{block_number} disappeared from storage"))?
+            .base_fee_per_gas;
+
+        let transactions = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                error,
+                refunded_gas
+            FROM
+                transactions
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                index_in_block
+            "#,
+            i64::from(block_number.0)
+        )
+        .instrument("sync_dal_sync_l2_block_state_diff.transactions")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
+        .await?
+        .into_iter()
+        .map(|row| en::SyncTxResult {
+            hash: H256::from_slice(&row.hash),
+            error: row.error,
+            refunded_gas: row.refunded_gas as u64,
+        })
+        .collect();
+
+        let storage_logs = sqlx::query!(
+            r#"
+            SELECT
+                address,
+                key,
+                value,
+                tx_hash
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                operation_number
+            "#,
+            i64::from(block_number.0)
+        )
+        .instrument("sync_dal_sync_l2_block_state_diff.storage_logs")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
+        .await?;
+        let mut grouped_storage_logs = Vec::<(H256, Vec<StorageLog>)>::new();
+        for row in storage_logs {
+            let tx_hash = H256::from_slice(&row.tx_hash);
+            let key = StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            );
+            let log = StorageLog::new_write_log(key, H256::from_slice(&row.value));
+            match grouped_storage_logs.last_mut() {
+                Some((last_hash, logs)) if *last_hash == tx_hash => logs.push(log),
+                _ => grouped_storage_logs.push((tx_hash, vec![log])),
+            }
+        }
+
+        let events = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash,
+                address,
+                topic1,
+                topic2,
+                topic3,
+                topic4,
+                value
+            FROM
+                events
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                event_index_in_block
+            "#,
+            i64::from(block_number.0)
+        )
+        .instrument("sync_dal_sync_l2_block_state_diff.events")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
+        .await?;
+        let mut grouped_events = Vec::<(H256, Vec<en::SyncEvent>)>::new();
+        for row in events {
+            let tx_hash = H256::from_slice(&row.tx_hash);
+            let indexed_topics = [row.topic1, row.topic2, row.topic3, row.topic4]
+                .into_iter()
+                .filter(|topic| !topic.is_empty())
+                .map(|topic| H256::from_slice(&topic))
+                .collect();
+            let event = en::SyncEvent {
+                address: Address::from_slice(&row.address),
+                indexed_topics,
+                value: Bytes(row.value),
+            };
+            match grouped_events.last_mut() {
+                Some((last_hash, events)) if *last_hash == tx_hash => events.push(event),
+                _ => grouped_events.push((tx_hash, vec![event])),
+            }
+        }
+
+        let factory_deps = sqlx::query!(
+            r#"
+            SELECT
+                bytecode_hash,
+                bytecode
+            FROM
+                factory_deps
+            WHERE
+                miniblock_number = $1
+            "#,
+            i64::from(block_number.0)
+        )
+        .instrument("sync_dal_sync_l2_block_state_diff.factory_deps")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
+        .await?
+        .into_iter()
+        .map(|row| (H256::from_slice(&row.bytecode_hash), Bytes(row.bytecode)))
+        .collect();
+
+        let l1_batch = if block.last_in_batch {
+            Some(self.l1_batch_seal_data(block.l1_batch_number).await?)
+        } else {
+            None
+        };
+
+        Ok(Some(en::L2BlockStateDiff {
+            number: block_number,
+            base_fee_per_gas,
+            transactions,
+            storage_logs: grouped_storage_logs,
+            events: grouped_events,
+            factory_deps,
+            l1_batch,
+        }))
+    }
+
+    async fn l1_batch_seal_data(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<en::L1BatchSealData> {
+        let mut blocks_dal = self.storage.blocks_dal();
+        let header = blocks_dal
+            .get_l1_batch_header(number)
+            .await?
+            .with_context(|| format!("L1 batch #{number} header is missing"))?;
+        let initial_bootloader_contents = blocks_dal
+            .get_initial_bootloader_heap(number)
+            .await?
+            .with_context(|| format!("L1 batch #{number} initial bootloader heap is missing"))?;
+        let events_queue = blocks_dal
+            .get_events_queue(number)
+            .await?
+            .with_context(|| format!("L1 batch #{number} events queue is missing"))?;
+        let storage_refunds = blocks_dal
+            .get_storage_refunds(number)
+            .await?
+            .with_context(|| format!("L1 batch #{number} storage refunds are missing"))?;
+        Ok(en::L1BatchSealData {
+            header,
+            initial_bootloader_contents,
+            events_queue,
+            storage_refunds,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use zksync_types::{
         block::{L1BatchHeader, MiniblockHeader},
         fee::TransactionExecutionMetrics,
-        Address, L1BatchNumber, ProtocolVersion, ProtocolVersionId, Transaction,
+        tx::{IncludedTxLocation, TransactionExecutionResult},
+        zk_evm_types::{LogQuery, Timestamp},
+        Address, L1BatchNumber, ProtocolVersion, ProtocolVersionId, Transaction, VmEvent, U256,
     };
 
     use super::*;
@@ -239,6 +433,214 @@ mod tests {
         assert_eq!(block.operator_address, miniblock_header.fee_account_address);
     }
 
+    async fn prepare_genesis(conn: &mut Connection<'_, Core>) {
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(0))
+            .await
+            .unwrap();
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(0),
+            0,
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&l1_batch_header)
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(0))
+            .await
+            .unwrap();
+    }
+
+    fn mock_events_queue() -> Vec<LogQuery> {
+        vec![LogQuery {
+            timestamp: Timestamp(1),
+            tx_number_in_block: 0,
+            aux_byte: 0,
+            shard_id: 0,
+            address: Address::repeat_byte(0x01),
+            key: U256::from(1),
+            read_value: U256::zero(),
+            written_value: U256::from(2),
+            rw_flag: true,
+            rollback: false,
+            is_service: false,
+        }]
+    }
+
+    #[tokio::test]
+    async fn sync_l2_block_state_diff_basics() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        prepare_genesis(&mut conn).await;
+
+        assert!(conn
+            .sync_dal()
+            .sync_l2_block_state_diff(MiniblockNumber(1))
+            .await
+            .unwrap()
+            .is_none());
+
+        let miniblock_header = create_miniblock_header(1);
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await
+            .unwrap();
+        let tx_result = TransactionExecutionResult {
+            refunded_gas: 10,
+            ..mock_execution_result(tx)
+        };
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], 1.into())
+            .await;
+
+        let tx_log = StorageLog::new_write_log(
+            StorageKey::new(AccountTreeId::new(Address::repeat_byte(0x01)), H256::zero()),
+            H256::repeat_byte(0x11),
+        );
+        // Emulates a write produced by the bootloader outside of transactions.
+        let fictive_log = StorageLog::new_write_log(
+            StorageKey::new(AccountTreeId::new(Address::repeat_byte(0x02)), H256::zero()),
+            H256::repeat_byte(0x22),
+        );
+        conn.storage_logs_dal()
+            .insert_storage_logs(
+                MiniblockNumber(1),
+                &[(tx_hash, vec![tx_log]), (H256::zero(), vec![fictive_log])],
+            )
+            .await
+            .unwrap();
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(0x03),
+            indexed_topics: vec![H256::repeat_byte(0x33)],
+            value: vec![1, 2, 3],
+        };
+        let tx_location = IncludedTxLocation {
+            tx_hash,
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::repeat_byte(0x04),
+        };
+        conn.events_dal()
+            .save_events(MiniblockNumber(1), &[(tx_location, vec![&event])])
+            .await;
+        let bytecode_hash = H256::repeat_byte(0x55);
+        let factory_deps = HashMap::from([(bytecode_hash, vec![0_u8; 32])]);
+        conn.factory_deps_dal()
+            .insert_factory_deps(MiniblockNumber(1), &factory_deps)
+            .await
+            .unwrap();
+
+        let state_diff = conn
+            .sync_dal()
+            .sync_l2_block_state_diff(MiniblockNumber(1))
+            .await
+            .unwrap()
+            .expect("no state diff");
+        assert_eq!(state_diff.number, MiniblockNumber(1));
+        assert_eq!(
+            state_diff.base_fee_per_gas,
+            miniblock_header.base_fee_per_gas
+        );
+        assert_eq!(
+            state_diff.transactions,
+            [en::SyncTxResult {
+                hash: tx_hash,
+                error: None,
+                refunded_gas: 10,
+            }]
+        );
+        assert_eq!(
+            state_diff.storage_logs,
+            [(tx_hash, vec![tx_log]), (H256::zero(), vec![fictive_log])]
+        );
+        assert_eq!(
+            state_diff.events,
+            [(
+                tx_hash,
+                vec![en::SyncEvent {
+                    address: event.address,
+                    indexed_topics: event.indexed_topics.clone(),
+                    value: Bytes(event.value.clone()),
+                }]
+            )]
+        );
+        assert_eq!(
+            state_diff.factory_deps,
+            HashMap::from([(bytecode_hash, Bytes(vec![0_u8; 32]))])
+        );
+        // The miniblock is not sealed as a part of an L1 batch yet.
+        assert_eq!(state_diff.l1_batch, None);
+
+        let mut l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        l1_batch_header.l2_tx_count = 1;
+        let initial_bootloader_contents = vec![(0, U256::from(1)), (5, U256::from(10))];
+        let events_queue = mock_events_queue();
+        let storage_refunds = vec![0, 100];
+        conn.blocks_dal()
+            .insert_l1_batch(
+                &l1_batch_header,
+                &initial_bootloader_contents,
+                Default::default(),
+                &events_queue,
+                &storage_refunds,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+
+        let state_diff = conn
+            .sync_dal()
+            .sync_l2_block_state_diff(MiniblockNumber(1))
+            .await
+            .unwrap()
+            .expect("no state diff");
+        let seal_data = state_diff.l1_batch.expect("no L1 batch seal data");
+        assert_eq!(seal_data.header.number, L1BatchNumber(1));
+        assert_eq!(seal_data.header.timestamp, 1);
+        assert_eq!(seal_data.header.l2_tx_count, 1);
+        assert_eq!(
+            seal_data.initial_bootloader_contents,
+            initial_bootloader_contents
+        );
+        assert_eq!(seal_data.events_queue, events_queue);
+        assert_eq!(seal_data.storage_refunds, storage_refunds);
+    }
+
+    #[tokio::test]
+    async fn l1_batch_seal_data_for_missing_batch() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        prepare_genesis(&mut conn).await;
+
+        let err = conn
+            .sync_dal()
+            .l1_batch_seal_data(L1BatchNumber(1))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("header is missing"), "{err:#}");
+    }
+
     #[tokio::test]
     async fn sync_block_after_snapshot_recovery() {
         let pool = ConnectionPool::<Core>::test_pool().await;
@@ -48,6 +48,27 @@ impl fmt::Display for L2TxSubmissionResult {
     }
 }
 
+/// Information about priority operations (L1 -> L2 transactions) not yet included into miniblocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriorityQueueInfo {
+    /// Number of unprocessed priority operations.
+    pub depth: u64,
+    /// ID of the oldest unprocessed priority operation.
+    pub oldest_op_id: Option<PriorityOpId>,
+    /// L1 block in which the oldest unprocessed priority operation was created.
+    pub oldest_op_l1_block: Option<L1BlockNumber>,
+}
+
+/// Priority operation (L1 -> L2 transaction) included into a miniblock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncludedPriorityOp {
+    pub id: PriorityOpId,
+    /// L1 block in which the operation was created.
+    pub l1_block: L1BlockNumber,
+    /// Timestamp of the miniblock the operation is included into.
+    pub miniblock_timestamp: u64,
+}
+
 #[derive(Debug)]
 pub struct TransactionsDal<'c, 'a> {
     pub(crate) storage: &'c mut Connection<'a, Core>,
@@ -458,14 +479,16 @@ impl TransactionsDal<'_, '_> {
         Ok(l2_tx_insertion_result)
     }
 
+    /// Marks transactions with the specified hashes as included into the L1 batch. Hashes must be ordered
+    /// in the same way as transactions in the batch.
     pub async fn mark_txs_as_executed_in_l1_batch(
         &mut self,
         block_number: L1BatchNumber,
-        transactions: &[TransactionExecutionResult],
+        tx_hashes: &[H256],
     ) {
         {
-            let hashes: Vec<_> = transactions.iter().map(|tx| tx.hash.as_bytes()).collect();
-            let l1_batch_tx_indexes: Vec<_> = (0..transactions.len() as i32).collect();
+            let hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+            let l1_batch_tx_indexes: Vec<_> = (0..tx_hashes.len() as i32).collect();
             sqlx::query!(
                 r#"
                 UPDATE transactions
@@ -844,6 +867,39 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns the number of L1 and L2 transactions (in this order) included into miniblocks
+    /// after `miniblock_number`. These transactions are returned to the mempool
+    /// by [`Self::reset_transactions_state()`].
+    pub async fn count_transactions_after_miniblock(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<(u64, u64)> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) FILTER (
+                    WHERE
+                        is_priority
+                ) AS "l1_tx_count!",
+                COUNT(*) FILTER (
+                    WHERE
+                        NOT is_priority
+                ) AS "l2_tx_count!"
+            FROM
+                transactions
+            WHERE
+                miniblock_number > $1
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("count_transactions_after_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok((row.l1_tx_count as u64, row.l2_tx_count as u64))
+    }
+
     pub async fn reset_transactions_state(&mut self, miniblock_number: MiniblockNumber) {
         {
             let tx_hashes = sqlx::query!(
@@ -1014,6 +1070,38 @@ impl TransactionsDal<'_, '_> {
         Ok(())
     }
 
+    /// Marks transactions evicted from the mempool as failed with the provided errors,
+    /// so that they are not loaded into the mempool again.
+    pub async fn mark_txs_as_evicted(&mut self, evicted: &[(H256, String)]) -> sqlx::Result<()> {
+        let (hashes, errors): (Vec<_>, Vec<_>) = evicted
+            .iter()
+            .map(|(hash, error)| (hash.as_bytes().to_vec(), error.clone()))
+            .unzip();
+        sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                error = data_table.error,
+                in_mempool = FALSE,
+                updated_at = NOW()
+            FROM
+                (
+                    SELECT
+                        UNNEST($1::bytea[]) AS hash,
+                        UNNEST($2::VARCHAR[]) AS error
+                ) AS data_table
+            WHERE
+                transactions.hash = data_table.hash
+                AND transactions.miniblock_number IS NULL
+            "#,
+            &hashes,
+            &errors,
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
     pub async fn get_last_processed_l1_block(&mut self) -> Option<L1BlockNumber> {
         {
             sqlx::query!(
@@ -1037,6 +1125,68 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns the number of priority operations received in L1 blocks after the specified one
+    /// that are already loaded into the state keeper mempool or included into miniblocks.
+    ///
+    /// All priority operations received after the specified L1 block are locked until the end of the current
+    /// DB transaction, so that they cannot be concurrently loaded into the mempool. Thus, this method should be called
+    /// in the same DB transaction as [`Self::remove_pending_priority_ops_after_l1_block()`].
+    pub async fn count_loaded_priority_ops_after_l1_block(
+        &mut self,
+        l1_block_number: L1BlockNumber,
+    ) -> sqlx::Result<u64> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COUNT(*) FILTER (
+                    WHERE
+                        in_mempool = TRUE
+                        OR miniblock_number IS NOT NULL
+                ) AS "count!"
+            FROM
+                (
+                    SELECT
+                        in_mempool,
+                        miniblock_number
+                    FROM
+                        transactions
+                    WHERE
+                        is_priority = TRUE
+                        AND priority_op_id IS NOT NULL
+                        AND l1_block_number > $1
+                    FOR UPDATE
+                ) AS priority_ops
+            "#,
+            l1_block_number.0 as i32
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(count as u64)
+    }
+
+    /// Removes priority operations received in L1 blocks after the specified one that are neither loaded
+    /// into the state keeper mempool nor included into miniblocks. Returns the number of removed operations.
+    pub async fn remove_pending_priority_ops_after_l1_block(
+        &mut self,
+        l1_block_number: L1BlockNumber,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE
+                is_priority = TRUE
+                AND priority_op_id IS NOT NULL
+                AND l1_block_number > $1
+                AND in_mempool = FALSE
+                AND miniblock_number IS NULL
+            "#,
+            l1_block_number.0 as i32
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn last_priority_id(&mut self) -> Option<PriorityOpId> {
         {
             let op_id = sqlx::query!(
@@ -1057,6 +1207,77 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    pub async fn get_priority_queue_info(&mut self) -> sqlx::Result<PriorityQueueInfo> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "depth!",
+                MIN(priority_op_id) AS "oldest_op_id",
+                MIN(l1_block_number) AS "oldest_op_l1_block"
+            FROM
+                transactions
+            WHERE
+                priority_op_id IS NOT NULL
+                AND miniblock_number IS NULL
+            "#
+        )
+        .instrument("get_priority_queue_info")
+        .report_latency()
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(PriorityQueueInfo {
+            depth: row.depth as u64,
+            oldest_op_id: row.oldest_op_id.map(|id| PriorityOpId(id as u64)),
+            oldest_op_l1_block: row
+                .oldest_op_l1_block
+                .map(|number| L1BlockNumber(number as u32)),
+        })
+    }
+
+    /// Returns priority operations with IDs starting from `first_op_id` that are included into miniblocks,
+    /// ordered by ID.
+    pub async fn get_included_priority_ops(
+        &mut self,
+        first_op_id: PriorityOpId,
+        limit: usize,
+    ) -> sqlx::Result<Vec<IncludedPriorityOp>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                transactions.priority_op_id AS "priority_op_id!",
+                transactions.l1_block_number AS "l1_block_number!",
+                miniblocks.timestamp AS "miniblock_timestamp"
+            FROM
+                transactions
+                JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE
+                transactions.priority_op_id >= $1
+            ORDER BY
+                transactions.priority_op_id
+            LIMIT
+                $2
+            "#,
+            first_op_id.0 as i64,
+            limit as i64
+        )
+        .instrument("get_included_priority_ops")
+        .with_arg("first_op_id", &first_op_id)
+        .with_arg("limit", &limit)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| IncludedPriorityOp {
+                id: PriorityOpId(row.priority_op_id as u64),
+                l1_block: L1BlockNumber(row.l1_block_number as u32),
+                miniblock_timestamp: row.miniblock_timestamp as u64,
+            })
+            .collect())
+    }
+
     pub async fn next_priority_id(&mut self) -> PriorityOpId {
         {
             sqlx::query!(
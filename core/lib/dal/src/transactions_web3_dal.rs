@@ -360,6 +360,31 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok(U256::from(pending_nonce))
     }
 
+    /// Returns the number of pending (i.e., not included into a miniblock and not rejected) L2 transactions
+    /// from the specified initiator account.
+    pub async fn get_pending_txs_count_by_initiator_account(
+        &mut self,
+        initiator_address: Address,
+    ) -> sqlx::Result<usize> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND is_priority = FALSE
+                AND miniblock_number IS NULL
+                AND error IS NULL
+            "#,
+            initiator_address.as_bytes()
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(count as usize)
+    }
+
     /// Returns the server transactions (not API ones) from a certain miniblock.
     /// Returns an empty list if the miniblock doesn't exist.
     pub async fn get_raw_miniblock_transactions(
@@ -607,6 +632,62 @@ mod tests {
         assert_eq!(next_nonce, 2.into());
     }
 
+    #[tokio::test]
+    async fn counting_pending_txs_by_initiator_account() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let initiator = Address::repeat_byte(1);
+        let mut txs = vec![];
+        for nonce in 0..3 {
+            let mut tx = mock_l2_transaction();
+            // Changing transaction fields invalidates its signature, but it's OK for test purposes
+            tx.common_data.nonce = Nonce(nonce);
+            tx.common_data.initiator_address = initiator;
+            txs.push(tx.clone());
+            conn.transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+        }
+        let other_tx = mock_l2_transaction();
+        conn.transactions_dal()
+            .insert_transaction_l2(other_tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+
+        let pending_count = conn
+            .transactions_web3_dal()
+            .get_pending_txs_count_by_initiator_account(initiator)
+            .await
+            .unwrap();
+        assert_eq!(pending_count, 3);
+
+        conn.transactions_dal()
+            .mark_tx_as_rejected(txs[2].hash(), "oops")
+            .await;
+        let mut miniblock = create_miniblock_header(1);
+        miniblock.l2_tx_count = 1;
+        conn.blocks_dal()
+            .insert_miniblock(&miniblock)
+            .await
+            .unwrap();
+        let executed_txs = [mock_execution_result(txs[0].clone())];
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(miniblock.number, &executed_txs, 1.into())
+            .await;
+
+        let pending_count = conn
+            .transactions_web3_dal()
+            .get_pending_txs_count_by_initiator_account(initiator)
+            .await
+            .unwrap();
+        assert_eq!(pending_count, 1);
+    }
+
     #[tokio::test]
     async fn getting_next_nonce_by_initiator_account_after_snapshot_recovery() {
         // Emulate snapshot recovery: no transactions with past nonces are present in the storage
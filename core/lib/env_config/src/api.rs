@@ -51,7 +51,7 @@ mod tests {
     use std::num::NonZeroU32;
 
     use super::*;
-    use crate::test_utils::{hash, EnvMutex};
+    use crate::test_utils::{addr, hash, EnvMutex};
 
     static MUTEX: EnvMutex = EnvMutex::new();
 
@@ -90,6 +90,19 @@ mod tests {
                 tree_api_url: None,
                 mempool_cache_update_interval: Some(50),
                 mempool_cache_size: Some(10000),
+                tx_deny_list_path: Some("/etc/zksync/deny_list.txt".to_owned()),
+                tx_deployer_allow_list: Some(vec![
+                    addr("0x0000000000000000000000000000000000000001"),
+                    addr("0x0000000000000000000000000000000000000002"),
+                ]),
+                max_pending_txs_per_sender: Some(16),
+                min_priority_fee_per_gas: Some(1000),
+                private_mode: true,
+                max_session_token_ttl_sec: Some(3600),
+                txpool_namespace_enabled: true,
+                gas_estimation_stats_enabled: true,
+                estimate_gas_min_scale_factor: Some(1.1),
+                estimate_gas_max_scale_factor: Some(2.0),
             },
             contract_verification: ContractVerificationApiConfig {
                 port: 3070,
@@ -139,6 +152,16 @@ mod tests {
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
             API_WEB3_JSON_RPC_MEMPOOL_CACHE_SIZE=10000
             API_WEB3_JSON_RPC_MEMPOOL_CACHE_UPDATE_INTERVAL=50
+            API_WEB3_JSON_RPC_TX_DENY_LIST_PATH="/etc/zksync/deny_list.txt"
+            API_WEB3_JSON_RPC_TX_DEPLOYER_ALLOW_LIST="0x0000000000000000000000000000000000000001,0x0000000000000000000000000000000000000002"
+            API_WEB3_JSON_RPC_MAX_PENDING_TXS_PER_SENDER=16
+            API_WEB3_JSON_RPC_MIN_PRIORITY_FEE_PER_GAS=1000
+            API_WEB3_JSON_RPC_PRIVATE_MODE=true
+            API_WEB3_JSON_RPC_MAX_SESSION_TOKEN_TTL_SEC=3600
+            API_WEB3_JSON_RPC_TXPOOL_NAMESPACE_ENABLED=true
+            API_WEB3_JSON_RPC_GAS_ESTIMATION_STATS_ENABLED=true
+            API_WEB3_JSON_RPC_ESTIMATE_GAS_MIN_SCALE_FACTOR=1.1
+            API_WEB3_JSON_RPC_ESTIMATE_GAS_MAX_SCALE_FACTOR=2.0
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_WEB3_JSON_RPC_MAX_RESPONSE_BODY_SIZE_MB=10
@@ -37,7 +37,7 @@ impl FromEnv for MempoolConfig {
 #[cfg(test)]
 mod tests {
     use zksync_basic_types::L2ChainId;
-    use zksync_config::configs::chain::FeeModelVersion;
+    use zksync_config::configs::chain::{FeeModelVersion, MempoolEvictionPolicy};
 
     use super::*;
     use crate::test_utils::{addr, EnvMutex};
@@ -94,6 +94,10 @@ mod tests {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: Some(2_000),
+            seal_policy_path: Some("/etc/zksync/seal_policy.json".to_owned()),
+            shadow_vm_protocol_version: Some(22),
+            shadow_vm_divergence_log_path: Some("/var/log/zksync/shadow_vm.jsonl".to_owned()),
+            pre_execution_lookahead: Some(16),
         }
     }
 
@@ -125,6 +129,10 @@ mod tests {
             CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
             CHAIN_STATE_KEEPER_UPLOAD_WITNESS_INPUTS_TO_GCS="false"
             CHAIN_STATE_KEEPER_ENUM_INDEX_MIGRATION_CHUNK_SIZE="2000"
+            CHAIN_STATE_KEEPER_SEAL_POLICY_PATH="/etc/zksync/seal_policy.json"
+            CHAIN_STATE_KEEPER_SHADOW_VM_PROTOCOL_VERSION="22"
+            CHAIN_STATE_KEEPER_SHADOW_VM_DIVERGENCE_LOG_PATH="/var/log/zksync/shadow_vm.jsonl"
+            CHAIN_STATE_KEEPER_PRE_EXECUTION_LOOKAHEAD="16"
             CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_PER_MINIBLOCK="1"
             CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_INTERVAL="1"
         "#;
@@ -160,6 +168,10 @@ mod tests {
             stuck_tx_timeout: 10,
             remove_stuck_txs: true,
             delay_interval: 100,
+            max_size_bytes: Some(1_000_000_000),
+            max_txs_per_account: Some(64),
+            max_bytes_per_account: None,
+            eviction_policy: MempoolEvictionPolicy::LowestPriority,
         }
     }
 
@@ -173,6 +185,9 @@ mod tests {
             CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
             CHAIN_MEMPOOL_DELAY_INTERVAL="100"
             CHAIN_MEMPOOL_CAPACITY="1000000"
+            CHAIN_MEMPOOL_MAX_SIZE_BYTES="1000000000"
+            CHAIN_MEMPOOL_MAX_TXS_PER_ACCOUNT="64"
+            CHAIN_MEMPOOL_EVICTION_POLICY="LowestPriority"
         "#;
         lock.set_env(config);
 
@@ -186,6 +201,7 @@ mod tests {
             http_req_max_retry_number: 5,
             http_req_retry_interval_sec: 2,
             replication_lag_limit_sec: Some(10),
+            priority_op_deadline_margin_sec: Some(7_200),
         }
     }
 
@@ -197,6 +213,7 @@ mod tests {
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_MAX_RETRY_NUMBER="5"
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_RETRY_INTERVAL_SEC="2"
             CHAIN_CIRCUIT_BREAKER_REPLICATION_LAG_LIMIT_SEC="10"
+            CHAIN_CIRCUIT_BREAKER_PRIORITY_OP_DEADLINE_MARGIN_SEC="7200"
         "#;
         lock.set_env(config);
 
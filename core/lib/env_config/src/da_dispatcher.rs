@@ -0,0 +1,45 @@
+use zksync_config::configs::DADispatcherConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for DADispatcherConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("da_dispatcher", "DA_DISPATCHER_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::da_dispatcher::DAClientConfig;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            DA_DISPATCHER_CLIENT="FileBacked"
+            DA_DISPATCHER_FILE_BACKED_BASE_PATH="artifacts/da"
+            DA_DISPATCHER_POLLING_INTERVAL_MS="1000"
+            DA_DISPATCHER_MAX_ROWS_TO_DISPATCH="10"
+            DA_DISPATCHER_MAX_RETRIES="3"
+        "#;
+        lock.set_env(config);
+
+        let actual = DADispatcherConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            DADispatcherConfig {
+                client: DAClientConfig::FileBacked {
+                    file_backed_base_path: "artifacts/da".to_owned(),
+                },
+                polling_interval_ms: 1_000,
+                max_rows_to_dispatch: 10,
+                max_retries: 3,
+            }
+        );
+    }
+}
@@ -27,7 +27,7 @@ impl FromEnv for GasAdjusterConfig {
 #[cfg(test)]
 mod tests {
     use zksync_config::configs::eth_sender::{
-        ProofLoadingMode, ProofSendingMode, PubdataSendingMode,
+        OperatorRole, ProofLoadingMode, ProofSendingMode, PubdataSendingMode,
     };
 
     use super::*;
@@ -57,6 +57,11 @@ mod tests {
                 max_acceptable_priority_fee_in_gwei: 100_000_000_000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
                 pubdata_sending_mode: PubdataSendingMode::Calldata,
+                operator_rotation_l1_batch: Some(100),
+                operator_rotation_roles: vec![OperatorRole::Prove, OperatorRole::Execute],
+                min_operator_balance_gwei: Some(500_000_000),
+                l1_fee_postpone_percentile: Some(75.0),
+                l1_fee_max_postpone_seconds: Some(7200),
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 20000000000,
@@ -107,6 +112,11 @@ mod tests {
             ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
             ETH_SENDER_SENDER_PROOF_LOADING_MODE="OldProofFromDb"
             ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Calldata"
+            ETH_SENDER_SENDER_OPERATOR_ROTATION_L1_BATCH="100"
+            ETH_SENDER_SENDER_OPERATOR_ROTATION_ROLES="Prove,Execute"
+            ETH_SENDER_SENDER_MIN_OPERATOR_BALANCE_GWEI="500000000"
+            ETH_SENDER_SENDER_L1_FEE_POSTPONE_PERCENTILE="75"
+            ETH_SENDER_SENDER_L1_FEE_MAX_POSTPONE_SECONDS="7200"
         "#;
         lock.set_env(config);
 
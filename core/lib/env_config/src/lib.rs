@@ -6,6 +6,7 @@ mod api;
 mod chain;
 mod contract_verifier;
 mod contracts;
+mod da_dispatcher;
 mod database;
 mod eth_client;
 mod eth_sender;
@@ -0,0 +1,645 @@
+//! Ethereum client routing requests to multiple L1 providers.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use futures::future;
+use vise::{Counter, Gauge, LabeledFamily, Metrics};
+use zksync_config::ETHClientConfig;
+use zksync_types::web3::{
+    self, ethabi,
+    types::{Address, BlockId, Filter, Log, Transaction, TransactionReceipt, H256, U256, U64},
+};
+
+use crate::{
+    clients::http::QueryClient,
+    types::{Error, ExecutedTxStatus, FailureInfo},
+    Block, ContractCall, EthInterface, RawTransactionBytes,
+};
+
+/// Penalty added to the health score of a provider on each failed request.
+const ERROR_PENALTY: u32 = 10;
+/// Maximum penalty of a provider.
+const MAX_PENALTY: u32 = 100;
+/// Interval after which the penalty of a provider is halved if it has no new failures.
+const PENALTY_HALF_LIFE: Duration = Duration::from_secs(30);
+/// Default maximum lag of a provider behind the most recent known L1 block after which the provider is considered stale.
+const DEFAULT_MAX_BLOCK_LAG: u64 = 5;
+
+type CallFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+#[derive(Debug, Default)]
+struct ProviderHealth {
+    penalty: u32,
+    last_failure: Option<Instant>,
+    last_block_number: Option<u64>,
+}
+
+impl ProviderHealth {
+    fn effective_penalty(&self, now: Instant) -> u32 {
+        let Some(last_failure) = self.last_failure else {
+            return self.penalty;
+        };
+        let half_lives = now.duration_since(last_failure).as_secs() / PENALTY_HALF_LIFE.as_secs();
+        self.penalty
+            .checked_shr(half_lives.try_into().unwrap_or(u32::MAX))
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug)]
+struct Provider {
+    name: String,
+    client: Box<dyn EthInterface>,
+    health: Mutex<ProviderHealth>,
+}
+
+impl Provider {
+    fn health(&self) -> std::sync::MutexGuard<'_, ProviderHealth> {
+        self.health.lock().expect("provider health is poisoned")
+    }
+
+    fn record_success(&self) {
+        let mut health = self.health();
+        health.penalty = health.penalty.saturating_sub(1);
+        METRICS.penalty[&self.name].set(health.penalty.into());
+    }
+
+    fn record_failure(&self, component: &'static str, err: &Error) {
+        tracing::warn!(
+            "Request to L1 provider `{}` from `{component}` failed: {err}",
+            self.name
+        );
+        METRICS.errors[&(self.name.clone(), component)].inc();
+
+        let now = Instant::now();
+        let mut health = self.health();
+        health.penalty = (health.effective_penalty(now) + ERROR_PENALTY).min(MAX_PENALTY);
+        health.last_failure = Some(now);
+        METRICS.penalty[&self.name].set(health.penalty.into());
+    }
+}
+
+/// Ethereum client routing requests to multiple L1 providers.
+///
+/// Requests are routed to the healthiest provider, i.e. the one with the least recent failures that doesn't lag
+/// behind other providers. If a request fails with a network or RPC error, it is retried with the next provider.
+///
+/// Optionally, the client can require a quorum for safety-critical reads ([`EthInterface::logs()`],
+/// [`EthInterface::tx_receipt()`] and [`EthInterface::get_tx_status()`]). In this case, the request is sent
+/// to all providers, and the response is only returned if at least the specified number of providers agree on it.
+#[derive(Debug)]
+pub struct FallbackEthClient {
+    providers: Vec<Provider>,
+    max_block_lag: u64,
+    quorum: Option<usize>,
+}
+
+impl FallbackEthClient {
+    /// Creates a client over the provided named clients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `providers` is empty.
+    pub fn new(providers: Vec<(String, Box<dyn EthInterface>)>) -> Self {
+        assert!(
+            !providers.is_empty(),
+            "at least one L1 provider is required"
+        );
+        let providers = providers
+            .into_iter()
+            .map(|(name, client)| Provider {
+                name,
+                client,
+                health: Mutex::default(),
+            })
+            .collect();
+        Self {
+            providers,
+            max_block_lag: DEFAULT_MAX_BLOCK_LAG,
+            quorum: None,
+        }
+    }
+
+    /// Creates an HTTP client for the specified URLs. The first URL is the main one; others are used as fallbacks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `urls` is empty.
+    pub fn from_urls<'a>(urls: impl IntoIterator<Item = &'a str>) -> Result<Self, Error> {
+        let providers = urls
+            .into_iter()
+            .enumerate()
+            .map(|(i, url)| {
+                let client: Box<dyn EthInterface> = Box::new(QueryClient::new(url)?);
+                // URLs may contain API keys, so they're not used as provider names.
+                Ok((format!("l1_provider_{i}"), client))
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Self::new(providers))
+    }
+
+    /// Creates an HTTP client for the main and fallback URLs specified in the config.
+    /// Returns an error if the read quorum in the config is zero or exceeds the number of URLs.
+    pub fn from_config(config: &ETHClientConfig) -> Result<Self, Error> {
+        let urls = std::iter::once(&config.web3_url).chain(&config.fallback_web3_urls);
+        let mut client = Self::from_urls(urls.map(String::as_str))?;
+        if let Some(max_block_lag) = config.max_block_lag {
+            client = client.with_max_block_lag(max_block_lag);
+        }
+        if let Some(quorum) = config.read_quorum {
+            let quorum = quorum as usize;
+            let providers = client.providers.len();
+            if quorum == 0 || quorum > providers {
+                return Err(Error::InvalidQuorum { quorum, providers });
+            }
+            client = client.with_quorum(quorum);
+        }
+        Ok(client)
+    }
+
+    /// Sets the maximum lag of a provider behind the most recent known L1 block. Providers lagging behind more
+    /// are considered stale and are only used if all other providers fail.
+    pub fn with_max_block_lag(mut self, max_block_lag: u64) -> Self {
+        self.max_block_lag = max_block_lag;
+        self
+    }
+
+    /// Requires agreement from at least `quorum` providers for safety-critical reads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `quorum` is zero or exceeds the number of providers.
+    pub fn with_quorum(mut self, quorum: usize) -> Self {
+        assert!(
+            quorum > 0 && quorum <= self.providers.len(),
+            "quorum must be in 1..={}",
+            self.providers.len()
+        );
+        self.quorum = Some(quorum);
+        self
+    }
+
+    fn latest_block_number(&self) -> Option<u64> {
+        self.providers
+            .iter()
+            .filter_map(|provider| provider.health().last_block_number)
+            .max()
+    }
+
+    fn is_stale(&self, block_number: Option<u64>, latest_block_number: Option<u64>) -> bool {
+        match (block_number, latest_block_number) {
+            (Some(number), Some(latest)) => latest.saturating_sub(number) > self.max_block_lag,
+            _ => false,
+        }
+    }
+
+    /// Returns providers ordered by their health: non-stale providers go first, then providers are ordered
+    /// by their penalty. Ties are resolved using the original provider order.
+    fn providers_by_health(&self) -> Vec<&Provider> {
+        let now = Instant::now();
+        let latest_block_number = self.latest_block_number();
+        let mut providers: Vec<_> = self
+            .providers
+            .iter()
+            .map(|provider| {
+                let health = provider.health();
+                let is_stale = self.is_stale(health.last_block_number, latest_block_number);
+                (is_stale, health.effective_penalty(now), provider)
+            })
+            .collect();
+        providers.sort_by_key(|&(is_stale, penalty, _)| (is_stale, penalty));
+        providers
+            .into_iter()
+            .map(|(_, _, provider)| provider)
+            .collect()
+    }
+
+    fn should_fail_over(err: &Error) -> bool {
+        matches!(
+            err,
+            Error::EthereumGateway(_) | Error::Contract(web3::contract::Error::Api(_))
+        )
+    }
+
+    /// Performs a call with the healthiest provider, failing over to other providers on errors.
+    async fn call<T>(
+        &self,
+        component: &'static str,
+        call: impl for<'a> Fn(&'a dyn EthInterface) -> CallFuture<'a, T>,
+    ) -> Result<T, Error> {
+        let mut last_error = None;
+        for provider in self.providers_by_health() {
+            METRICS.calls[&(provider.name.clone(), component)].inc();
+            match call(provider.client.as_ref()).await {
+                Ok(value) => {
+                    provider.record_success();
+                    return Ok(value);
+                }
+                Err(err) if Self::should_fail_over(&err) => {
+                    provider.record_failure(component, &err);
+                    last_error = Some(err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(last_error.expect("no L1 providers"))
+    }
+
+    /// Performs a call with all providers and returns the response that at least `quorum` providers agree on.
+    /// If the quorum is not configured, works the same as [`Self::call()`].
+    async fn call_with_quorum<T: PartialEq>(
+        &self,
+        component: &'static str,
+        call: impl for<'a> Fn(&'a dyn EthInterface) -> CallFuture<'a, T>,
+    ) -> Result<T, Error> {
+        let Some(quorum) = self.quorum else {
+            return self.call(component, call).await;
+        };
+
+        let call = &call;
+        let responses = self.providers.iter().map(|provider| async move {
+            METRICS.calls[&(provider.name.clone(), component)].inc();
+            (provider, call(provider.client.as_ref()).await)
+        });
+        let responses = future::join_all(responses).await;
+
+        let mut agreed_responses: Vec<(T, usize)> = vec![];
+        let mut last_error = None;
+        for (provider, response) in responses {
+            match response {
+                Ok(value) => {
+                    provider.record_success();
+                    match agreed_responses
+                        .iter_mut()
+                        .find(|(agreed, _)| *agreed == value)
+                    {
+                        Some((_, count)) => *count += 1,
+                        None => agreed_responses.push((value, 1)),
+                    }
+                }
+                Err(err) => {
+                    provider.record_failure(component, &err);
+                    last_error = Some(err);
+                }
+            }
+        }
+
+        if let Some(idx) = agreed_responses
+            .iter()
+            .position(|&(_, count)| count >= quorum)
+        {
+            return Ok(agreed_responses.swap_remove(idx).0);
+        }
+        METRICS.quorum_failures[&component].inc();
+        match (agreed_responses.is_empty(), last_error) {
+            (true, Some(err)) => Err(err),
+            _ => Err(Error::QuorumNotReached {
+                required: quorum,
+                agreed: agreed_responses
+                    .iter()
+                    .map(|&(_, count)| count)
+                    .max()
+                    .unwrap_or(0),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl EthInterface for FallbackEthClient {
+    async fn nonce_at_for_account(
+        &self,
+        account: Address,
+        block: web3::types::BlockNumber,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.call(component, |client| {
+            client.nonce_at_for_account(account, block, component)
+        })
+        .await
+    }
+
+    async fn base_fee_history(
+        &self,
+        from_block: usize,
+        block_count: usize,
+        component: &'static str,
+    ) -> Result<Vec<u64>, Error> {
+        self.call(component, |client| {
+            client.base_fee_history(from_block, block_count, component)
+        })
+        .await
+    }
+
+    async fn get_pending_block_base_fee_per_gas(
+        &self,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.call(component, |client| {
+            client.get_pending_block_base_fee_per_gas(component)
+        })
+        .await
+    }
+
+    async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
+        self.call(component, |client| client.get_gas_price(component))
+            .await
+    }
+
+    /// Queries all providers and returns the block number reported by the healthiest non-stale provider.
+    /// Block numbers reported by providers are used to detect stale providers.
+    async fn block_number(&self, component: &'static str) -> Result<U64, Error> {
+        let responses = self.providers.iter().map(|provider| async move {
+            METRICS.calls[&(provider.name.clone(), component)].inc();
+            (provider, provider.client.block_number(component).await)
+        });
+        let responses = future::join_all(responses).await;
+
+        let mut last_error = None;
+        for (provider, response) in responses {
+            match response {
+                Ok(number) => provider.health().last_block_number = Some(number.as_u64()),
+                Err(err) => {
+                    // The block number reported by the provider earlier is outdated, so it must not be returned
+                    // or influence staleness checks.
+                    provider.health().last_block_number = None;
+                    provider.record_failure(component, &err);
+                    last_error = Some(err);
+                }
+            }
+        }
+
+        // Only providers that have answered in this call have their block number set.
+        let latest_block_number = self.latest_block_number();
+        for provider in self.providers_by_health() {
+            let block_number = provider.health().last_block_number;
+            if self.is_stale(block_number, latest_block_number) {
+                tracing::warn!(
+                    "L1 provider `{}` is stale: its latest block is {block_number:?}, while other providers report {latest_block_number:?}",
+                    provider.name
+                );
+                METRICS.stale_responses[&provider.name].inc();
+            } else if let Some(number) = block_number {
+                provider.record_success();
+                return Ok(number.into());
+            }
+        }
+        Err(last_error.expect("no L1 providers"))
+    }
+
+    async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
+        self.call("send_raw_tx", |client| client.send_raw_tx(tx.clone()))
+            .await
+    }
+
+    async fn get_tx_status(
+        &self,
+        hash: H256,
+        component: &'static str,
+    ) -> Result<Option<ExecutedTxStatus>, Error> {
+        self.call_with_quorum(component, |client| client.get_tx_status(hash, component))
+            .await
+    }
+
+    async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
+        self.call("failure_reason", |client| client.failure_reason(tx_hash))
+            .await
+    }
+
+    async fn get_tx(
+        &self,
+        hash: H256,
+        component: &'static str,
+    ) -> Result<Option<Transaction>, Error> {
+        self.call(component, |client| client.get_tx(hash, component))
+            .await
+    }
+
+    async fn tx_receipt(
+        &self,
+        tx_hash: H256,
+        component: &'static str,
+    ) -> Result<Option<TransactionReceipt>, Error> {
+        self.call_with_quorum(component, |client| client.tx_receipt(tx_hash, component))
+            .await
+    }
+
+    async fn eth_balance(&self, address: Address, component: &'static str) -> Result<U256, Error> {
+        self.call(component, |client| client.eth_balance(address, component))
+            .await
+    }
+
+    async fn call_contract_function(
+        &self,
+        call: ContractCall,
+    ) -> Result<Vec<ethabi::Token>, Error> {
+        self.call("call_contract_function", |client| {
+            client.call_contract_function(call.clone())
+        })
+        .await
+    }
+
+    async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
+        self.call_with_quorum(component, |client| client.logs(filter.clone(), component))
+            .await
+    }
+
+    async fn block(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<Block<H256>>, Error> {
+        self.call(component, |client| client.block(block_id, component))
+            .await
+    }
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "eth_client_fallback")]
+struct FallbackClientMetrics {
+    /// Number of calls routed to a specific L1 provider.
+    #[metrics(labels = ["provider", "component"])]
+    calls: LabeledFamily<(String, &'static str), Counter, 2>,
+    /// Number of failed calls to a specific L1 provider.
+    #[metrics(labels = ["provider", "component"])]
+    errors: LabeledFamily<(String, &'static str), Counter, 2>,
+    /// Number of times a specific L1 provider was considered stale.
+    #[metrics(labels = ["provider"])]
+    stale_responses: LabeledFamily<String, Counter>,
+    /// Current penalty of a specific L1 provider; higher values correspond to less healthy providers.
+    #[metrics(labels = ["provider"])]
+    penalty: LabeledFamily<String, Gauge<u64>>,
+    /// Number of quorum reads for which providers didn't reach the quorum.
+    #[metrics(labels = ["component"])]
+    quorum_failures: LabeledFamily<&'static str, Counter>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<FallbackClientMetrics> = vise::Global::new();
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::clients::MockEthereum;
+
+    fn fallback_client(providers: &[Arc<MockEthereum>]) -> FallbackEthClient {
+        let providers = providers
+            .iter()
+            .enumerate()
+            .map(|(i, provider)| {
+                let client: Box<dyn EthInterface> = Box::new(provider.clone());
+                (format!("provider_{i}"), client)
+            })
+            .collect();
+        FallbackEthClient::new(providers)
+    }
+
+    #[tokio::test]
+    async fn failing_over_on_errors() {
+        let providers = [
+            Arc::new(MockEthereum::default()),
+            Arc::new(MockEthereum::default()),
+        ];
+        providers[1].advance_block_number(1);
+        let client = fallback_client(&providers);
+        assert_eq!(client.block_number("test").await.unwrap(), 1.into());
+        assert_eq!(client.get_gas_price("test").await.unwrap(), 100.into());
+
+        providers[0].set_unavailable(true);
+        assert_eq!(client.get_gas_price("test").await.unwrap(), 100.into());
+        // The failed provider should be deprioritized.
+        let ordered_names: Vec<_> = client
+            .providers_by_health()
+            .into_iter()
+            .map(|provider| provider.name.as_str())
+            .collect();
+        assert_eq!(ordered_names, ["provider_1", "provider_0"]);
+
+        providers[1].set_unavailable(true);
+        let err = client.get_gas_price("test").await.unwrap_err();
+        assert!(matches!(err, Error::EthereumGateway(_)), "{err:?}");
+
+        providers[0].set_unavailable(false);
+        assert_eq!(client.get_gas_price("test").await.unwrap(), 100.into());
+    }
+
+    #[tokio::test]
+    async fn failing_over_on_stale_block_number() {
+        let providers = [
+            Arc::new(MockEthereum::default()),
+            Arc::new(MockEthereum::default()),
+        ];
+        providers[0].advance_block_number(10);
+        providers[1].advance_block_number(20);
+        let client = fallback_client(&providers).with_max_block_lag(5);
+
+        assert_eq!(client.block_number("test").await.unwrap(), 20.into());
+        let ordered_names: Vec<_> = client
+            .providers_by_health()
+            .into_iter()
+            .map(|provider| provider.name.as_str())
+            .collect();
+        assert_eq!(ordered_names, ["provider_1", "provider_0"]);
+
+        // Once the provider catches up, it should be preferred again.
+        providers[0].advance_block_number(8);
+        assert_eq!(client.block_number("test").await.unwrap(), 18.into());
+
+        // If the only non-stale provider fails, the stale one is used.
+        providers[1].set_unavailable(true);
+        providers[0].set_unavailable(true);
+        client.block_number("test").await.unwrap_err();
+        providers[0].set_unavailable(false);
+        assert_eq!(client.block_number("test").await.unwrap(), 18.into());
+    }
+
+    #[test]
+    fn validating_read_quorum_in_config() {
+        let mut config = ETHClientConfig {
+            chain_id: 9,
+            web3_url: "http://127.0.0.1:8545".to_owned(),
+            fallback_web3_urls: vec!["http://127.0.0.1:8546".to_owned()],
+            max_block_lag: None,
+            read_quorum: Some(2),
+        };
+        let client = FallbackEthClient::from_config(&config).unwrap();
+        assert_eq!(client.quorum, Some(2));
+
+        for invalid_quorum in [0, 3] {
+            config.read_quorum = Some(invalid_quorum);
+            let err = FallbackEthClient::from_config(&config).unwrap_err();
+            let Error::InvalidQuorum { quorum, providers } = err else {
+                panic!("Unexpected error: {err:?}");
+            };
+            assert_eq!(quorum, invalid_quorum as usize);
+            assert_eq!(providers, 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn quorum_reads() {
+        let providers = [
+            Arc::new(MockEthereum::default()),
+            Arc::new(MockEthereum::default()),
+            Arc::new(MockEthereum::default()),
+        ];
+        let signed_tx = providers[0]
+            .sign_prepared_tx(
+                b"test".to_vec(),
+                Address::repeat_byte(1),
+                crate::Options {
+                    nonce: Some(0.into()),
+                    ..crate::Options::default()
+                },
+            )
+            .unwrap();
+        let tx_hash = signed_tx.hash;
+        for provider in &providers[..2] {
+            provider
+                .send_raw_tx(signed_tx.raw_tx.clone())
+                .await
+                .unwrap();
+            provider.execute_tx(tx_hash, true, 1);
+        }
+
+        let client = fallback_client(&providers).with_quorum(2);
+        let status = client.get_tx_status(tx_hash, "test").await.unwrap();
+        assert!(status.unwrap().success);
+
+        let client = fallback_client(&providers).with_quorum(3);
+        let err = client.get_tx_status(tx_hash, "test").await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::QuorumNotReached {
+                    required: 3,
+                    agreed: 2
+                }
+            ),
+            "{err:?}"
+        );
+
+        // Failed providers don't count towards the quorum.
+        providers[1].set_unavailable(true);
+        let client = fallback_client(&providers).with_quorum(2);
+        let err = client.get_tx_status(tx_hash, "test").await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::QuorumNotReached {
+                    required: 2,
+                    agreed: 1
+                }
+            ),
+            "{err:?}"
+        );
+    }
+}
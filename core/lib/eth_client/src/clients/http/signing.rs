@@ -20,6 +20,7 @@ use zksync_types::{
 
 use super::{query::QueryClient, Method, LATENCIES};
 use crate::{
+    clients::FallbackEthClient,
     types::{encode_blob_tx_with_sidecar, Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
     Block, BoundEthInterface, CallFunctionArgs, ContractCall, EthInterface, Options,
     RawTransactionBytes,
@@ -67,25 +68,41 @@ impl PKSigningClient {
         ))
     }
 
+    /// Create an signing client for the rotation account
+    pub fn from_config_rotation(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> Option<Self> {
+        let operator_private_key = eth_sender.sender.private_key_rotation()?;
+
+        Some(Self::from_config_inner(
+            eth_sender,
+            contracts_config,
+            eth_client,
+            operator_private_key,
+        ))
+    }
+
     fn from_config_inner(
         eth_sender: &ETHSenderConfig,
         contracts_config: &ContractsConfig,
         eth_client: &ETHClientConfig,
         operator_private_key: H256,
     ) -> Self {
-        let main_node_url = &eth_client.web3_url;
         let diamond_proxy_addr = contracts_config.diamond_proxy_addr;
         let default_priority_fee_per_gas = eth_sender.gas_adjuster.default_priority_fee_per_gas;
         let l1_chain_id = eth_client.chain_id;
 
-        let transport = Http::new(main_node_url).expect("Failed to create transport");
+        let query_client =
+            FallbackEthClient::from_config(eth_client).expect("Failed to create L1 client");
         let operator_address = PackedEthSignature::address_from_private_key(&operator_private_key)
             .expect("Failed to get address from private key");
 
         tracing::info!("Operator address: {:?}", operator_address);
 
-        SigningClient::new(
-            transport,
+        SigningClient::with_query_client(
+            Arc::new(query_client),
             zksync_contract(),
             operator_address,
             PrivateKeySigner::new(operator_private_key),
@@ -107,7 +124,7 @@ const FALLBACK_GAS_LIMIT: u64 = 3_000_000;
 #[derive(Clone)]
 pub struct SigningClient<S: EthereumSigner> {
     inner: Arc<ETHDirectClientInner<S>>,
-    query_client: QueryClient,
+    query_client: Arc<dyn EthInterface>,
 }
 
 struct ETHDirectClientInner<S: EthereumSigner> {
@@ -358,6 +375,28 @@ impl<S: EthereumSigner> SigningClient<S> {
         contract_eth_addr: H160,
         default_priority_fee_per_gas: U256,
         chain_id: L1ChainId,
+    ) -> Self {
+        Self::with_query_client(
+            Arc::new(QueryClient::from(transport)),
+            contract,
+            operator_eth_addr,
+            eth_signer,
+            contract_eth_addr,
+            default_priority_fee_per_gas,
+            chain_id,
+        )
+    }
+
+    /// Creates a client using the provided client for all L1 queries and sending transactions,
+    /// e.g. a [`FallbackEthClient`] over multiple L1 providers.
+    pub fn with_query_client(
+        query_client: Arc<dyn EthInterface>,
+        contract: ethabi::Contract,
+        operator_eth_addr: H160,
+        eth_signer: S,
+        contract_eth_addr: H160,
+        default_priority_fee_per_gas: U256,
+        chain_id: L1ChainId,
     ) -> Self {
         Self {
             inner: Arc::new(ETHDirectClientInner {
@@ -368,7 +407,7 @@ impl<S: EthereumSigner> SigningClient<S> {
                 contract,
                 default_priority_fee_per_gas,
             }),
-            query_client: transport.into(),
+            query_client,
         }
     }
 }
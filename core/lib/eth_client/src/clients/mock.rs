@@ -1,11 +1,12 @@
 use std::{
     collections::{BTreeMap, HashMap},
     fmt,
-    sync::{RwLock, RwLockWriteGuard},
+    sync::{OnceLock, RwLock, RwLockWriteGuard},
 };
 
 use async_trait::async_trait;
 use jsonrpc_core::types::error::Error as RpcError;
+use zksync_contracts::zksync_contract;
 use zksync_types::{
     web3::{
         contract::tokens::Tokenize,
@@ -78,9 +79,19 @@ struct MockEthereumInner {
     current_nonce: u64,
     pending_nonce: u64,
     nonces: BTreeMap<u64, u64>,
+    unavailable: bool,
+    /// Blocks starting from this number belong to an alternative fork and have different hashes.
+    fork_start: Option<u64>,
 }
 
 impl MockEthereumInner {
+    fn block_hash(&self, number: u64) -> H256 {
+        match self.fork_start {
+            Some(fork_start) if number >= fork_start => H256::from_low_u64_be(number | (1 << 63)),
+            _ => H256::from_low_u64_be(number),
+        }
+    }
+
     fn execute_tx(
         &mut self,
         tx_hash: H256,
@@ -137,9 +148,12 @@ pub struct MockEthereum {
     max_priority_fee_per_gas: U256,
     base_fee_history: Vec<u64>,
     excess_blob_gas_history: Vec<u64>,
+    /// Timestamps of L1 blocks indexed by the block number; blocks not in the history have zero timestamp.
+    block_timestamp_history: Vec<u64>,
     /// If true, the mock will not check the ordering nonces of the transactions.
     /// This is useful for testing the cases when the transactions are executed out of order.
     non_ordering_confirmations: bool,
+    sender_account: Address,
     inner: RwLock<MockEthereumInner>,
     call_handler: Box<dyn Fn(&ContractCall) -> ethabi::Token + Send + Sync>,
 }
@@ -152,10 +166,12 @@ impl fmt::Debug for MockEthereum {
             .field("max_priority_fee_per_gas", &self.max_priority_fee_per_gas)
             .field("base_fee_history", &self.base_fee_history)
             .field("excess_blob_gas_history", &self.excess_blob_gas_history)
+            .field("block_timestamp_history", &self.block_timestamp_history)
             .field(
                 "non_ordering_confirmations",
                 &self.non_ordering_confirmations,
             )
+            .field("sender_account", &self.sender_account)
             .field("inner", &self.inner)
             .finish_non_exhaustive()
     }
@@ -168,7 +184,9 @@ impl Default for MockEthereum {
             max_priority_fee_per_gas: 10.into(),
             base_fee_history: vec![],
             excess_blob_gas_history: vec![],
+            block_timestamp_history: vec![],
             non_ordering_confirmations: false,
+            sender_account: Address::repeat_byte(0x11),
             inner: RwLock::default(),
             call_handler: Box::new(|call| {
                 panic!("Unexpected eth_call: {call:?}");
@@ -189,11 +207,34 @@ impl MockEthereum {
         H256::from_low_u64_ne(result)
     }
 
+    /// Makes all subsequent requests to this client fail with a network error (or restores normal operation).
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.inner.write().unwrap().unavailable = unavailable;
+    }
+
+    fn check_available(&self) -> Result<(), Error> {
+        if self.inner.read().unwrap().unavailable {
+            return Err(Error::EthereumGateway(Web3Error::Unreachable));
+        }
+        Ok(())
+    }
+
     /// Returns the number of transactions sent via this client.
     pub fn sent_tx_count(&self) -> usize {
         self.inner.read().unwrap().sent_txs.len()
     }
 
+    /// Returns hashes of all transactions sent via this client in no particular order.
+    pub fn sent_tx_hashes(&self) -> Vec<H256> {
+        self.inner
+            .read()
+            .unwrap()
+            .sent_txs
+            .keys()
+            .copied()
+            .collect()
+    }
+
     /// Increments the blocks by a provided `confirmations` and marks the sent transaction
     /// as a success.
     pub fn execute_tx(
@@ -244,6 +285,12 @@ impl MockEthereum {
         ))
     }
 
+    /// Emulates an L1 reorg changing hashes of all blocks starting from `fork_start`. Executed transactions
+    /// retain their statuses, i.e., they are considered to be included into the new blocks with the same numbers.
+    pub fn reorg(&self, fork_start: u64) {
+        self.inner.write().unwrap().fork_start = Some(fork_start);
+    }
+
     pub fn advance_block_number(&self, val: u64) -> u64 {
         let mut inner = self.inner.write().unwrap();
         inner.block_number += val;
@@ -264,6 +311,13 @@ impl MockEthereum {
         }
     }
 
+    pub fn with_block_timestamp_history(self, history: Vec<u64>) -> Self {
+        Self {
+            block_timestamp_history: history,
+            ..self
+        }
+    }
+
     pub fn with_non_ordering_confirmation(self, non_ordering_confirmations: bool) -> Self {
         Self {
             non_ordering_confirmations,
@@ -271,6 +325,13 @@ impl MockEthereum {
         }
     }
 
+    pub fn with_sender_account(self, sender_account: Address) -> Self {
+        Self {
+            sender_account,
+            ..self
+        }
+    }
+
     pub fn with_call_handler<F>(self, call_handler: F) -> Self
     where
         F: 'static + Send + Sync + Fn(&ContractCall) -> ethabi::Token,
@@ -289,14 +350,25 @@ impl EthInterface for MockEthereum {
         hash: H256,
         _: &'static str,
     ) -> Result<Option<ExecutedTxStatus>, Error> {
-        Ok(self.inner.read().unwrap().tx_statuses.get(&hash).cloned())
+        self.check_available()?;
+        let inner = self.inner.read().unwrap();
+        Ok(inner.tx_statuses.get(&hash).map(|status| {
+            let mut status = status.clone();
+            status.receipt.block_hash = status
+                .receipt
+                .block_number
+                .map(|number| inner.block_hash(number.as_u64()));
+            status
+        }))
     }
 
     async fn block_number(&self, _: &'static str) -> Result<U64, Error> {
+        self.check_available()?;
         Ok(self.inner.read().unwrap().block_number.into())
     }
 
     async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
+        self.check_available()?;
         let mock_tx = MockTx::from(tx.0);
         let mock_tx_hash = mock_tx.hash;
         let mut inner = self.inner.write().unwrap();
@@ -326,6 +398,7 @@ impl EthInterface for MockEthereum {
     }
 
     async fn get_gas_price(&self, _: &'static str) -> Result<U256, Error> {
+        self.check_available()?;
         Ok(self.max_fee_per_gas)
     }
 
@@ -335,6 +408,7 @@ impl EthInterface for MockEthereum {
         block_count: usize,
         _component: &'static str,
     ) -> Result<Vec<u64>, Error> {
+        self.check_available()?;
         let start_block = from_block.saturating_sub(block_count - 1);
         Ok(self.base_fee_history[start_block..=from_block].to_vec())
     }
@@ -343,11 +417,12 @@ impl EthInterface for MockEthereum {
         &self,
         _component: &'static str,
     ) -> Result<U256, Error> {
+        self.check_available()?;
         Ok(U256::from(*self.base_fee_history.last().unwrap()))
     }
 
     async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
-        let tx_status = self.get_tx_status(tx_hash, "failure_reason").await.unwrap();
+        let tx_status = self.get_tx_status(tx_hash, "failure_reason").await?;
 
         Ok(tx_status.map(|status| FailureInfo {
             revert_code: status.success as i64,
@@ -361,6 +436,7 @@ impl EthInterface for MockEthereum {
         &self,
         call: ContractCall,
     ) -> Result<Vec<ethabi::Token>, Error> {
+        self.check_available()?;
         let response = (self.call_handler)(&call);
         Ok(vec![response])
     }
@@ -370,6 +446,7 @@ impl EthInterface for MockEthereum {
         hash: H256,
         _component: &'static str,
     ) -> Result<Option<Transaction>, Error> {
+        self.check_available()?;
         let txs = &self.inner.read().unwrap().sent_txs;
         let Some(tx) = txs.get(&hash) else {
             return Ok(None);
@@ -379,10 +456,22 @@ impl EthInterface for MockEthereum {
 
     async fn tx_receipt(
         &self,
-        _tx_hash: H256,
+        tx_hash: H256,
         _component: &'static str,
     ) -> Result<Option<TransactionReceipt>, Error> {
-        unimplemented!("Not needed right now")
+        self.check_available()?;
+        let inner = self.inner.read().unwrap();
+        Ok(inner.tx_statuses.get(&tx_hash).map(|status| {
+            let block_hash = status
+                .receipt
+                .block_number
+                .map(|number| inner.block_hash(number.as_u64()));
+            TransactionReceipt {
+                status: Some(U64::from(status.success as u64)),
+                block_hash,
+                ..status.receipt.clone()
+            }
+        }))
     }
 
     async fn eth_balance(
@@ -393,8 +482,61 @@ impl EthInterface for MockEthereum {
         unimplemented!("Not needed right now")
     }
 
-    async fn logs(&self, _filter: Filter, _component: &'static str) -> Result<Vec<Log>, Error> {
-        unimplemented!("Not needed right now")
+    /// Returns logs attached to executed transactions via [`MockExecutedTxHandle::with_logs()`]. Only block range,
+    /// address and the first topic of the filter are taken into account.
+    async fn logs(&self, filter: Filter, _component: &'static str) -> Result<Vec<Log>, Error> {
+        // Single-value filter components are serialized as values rather than arrays.
+        fn one_or_many<T: serde::de::DeserializeOwned>(
+            value: &serde_json::Value,
+        ) -> Option<Vec<T>> {
+            serde_json::from_value(value.clone())
+                .ok()
+                .or_else(|| Some(vec![serde_json::from_value(value.clone()).ok()?]))
+        }
+
+        self.check_available()?;
+        // Filter fields are private, so they are read from the JSON presentation of the filter.
+        let filter = serde_json::to_value(filter).expect("failed serializing filter");
+        let block_number = |name: &str| {
+            filter
+                .get(name)
+                .and_then(|number| serde_json::from_value::<U64>(number.clone()).ok())
+                .map(|number| number.as_u64())
+        };
+        let from_block = block_number("fromBlock").unwrap_or(0);
+        let to_block = block_number("toBlock").unwrap_or(u64::MAX);
+        let addresses: Option<Vec<Address>> = filter.get("address").and_then(one_or_many);
+        let topic: Option<Vec<H256>> = filter
+            .get("topics")
+            .and_then(|topics| topics.get(0))
+            .and_then(one_or_many);
+
+        let inner = self.inner.read().unwrap();
+        let mut logs = vec![];
+        for status in inner.tx_statuses.values() {
+            let block_number = status.receipt.block_number.unwrap_or_default().as_u64();
+            if !(from_block..=to_block).contains(&block_number) {
+                continue;
+            }
+            let matching_logs = status.receipt.logs.iter().filter(|log| {
+                let address_matches = addresses
+                    .as_ref()
+                    .map_or(true, |addresses| addresses.contains(&log.address));
+                let topic_matches = topic.as_ref().map_or(true, |topic| {
+                    log.topics
+                        .first()
+                        .map_or(false, |log_topic| topic.contains(log_topic))
+                });
+                address_matches && topic_matches
+            });
+            logs.extend(matching_logs.map(|log| Log {
+                block_number: Some(block_number.into()),
+                transaction_hash: Some(status.tx_hash),
+                ..log.clone()
+            }));
+        }
+        logs.sort_unstable_by_key(|log| log.block_number);
+        Ok(logs)
     }
 
     async fn block(
@@ -402,6 +544,7 @@ impl EthInterface for MockEthereum {
         block_id: BlockId,
         _component: &'static str,
     ) -> Result<Option<Block<H256>>, Error> {
+        self.check_available()?;
         match block_id {
             BlockId::Number(BlockNumber::Number(number)) => {
                 let excess_blob_gas = self
@@ -412,9 +555,16 @@ impl EthInterface for MockEthereum {
                     .base_fee_history
                     .get(number.as_usize())
                     .map(|base_fee| (*base_fee).into());
+                let timestamp = self
+                    .block_timestamp_history
+                    .get(number.as_usize())
+                    .copied()
+                    .unwrap_or_default();
 
                 Ok(Some(Block {
                     number: Some(number),
+                    hash: Some(self.inner.read().unwrap().block_hash(number.as_u64())),
+                    timestamp: timestamp.into(),
                     excess_blob_gas,
                     base_fee_per_gas,
                     ..Default::default()
@@ -428,7 +578,8 @@ impl EthInterface for MockEthereum {
 #[async_trait::async_trait]
 impl BoundEthInterface for MockEthereum {
     fn contract(&self) -> &ethabi::Contract {
-        unimplemented!("Not needed right now")
+        static CONTRACT: OnceLock<ethabi::Contract> = OnceLock::new();
+        CONTRACT.get_or_init(zksync_contract)
     }
 
     fn contract_addr(&self) -> H160 {
@@ -440,7 +591,7 @@ impl BoundEthInterface for MockEthereum {
     }
 
     fn sender_account(&self) -> Address {
-        Address::repeat_byte(0x11)
+        self.sender_account
     }
 
     async fn sign_prepared_tx_for_addr(
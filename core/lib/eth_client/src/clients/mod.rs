@@ -1,10 +1,14 @@
 //! Various Ethereum client implementations.
 
+mod fallback;
 mod generic;
 mod http;
 mod mock;
+mod simulator;
 
 pub use self::{
+    fallback::FallbackEthClient,
     http::{PKSigningClient, QueryClient, SigningClient},
     mock::MockEthereum,
+    simulator::{DiamondProxyStorage, L1Simulator},
 };
@@ -0,0 +1,308 @@
+//! Simplified model of the diamond proxy (the main zkSync contract on L1).
+
+use zksync_types::{
+    web3::{
+        ethabi::{self, Token},
+        signing::keccak256,
+    },
+    Address, ProtocolVersionId, H256, U256,
+};
+
+/// Part of the diamond proxy storage modeled by [`L1Simulator`](super::L1Simulator). Includes values returned
+/// by the getters queried by the server (e.g., ones aggregated by `eth_sender` via Multicall3) and batch counters
+/// updated by `commitBatches` / `proveBatches` / `executeBatches`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiamondProxyStorage {
+    pub bootloader_bytecode_hash: H256,
+    pub default_account_bytecode_hash: H256,
+    pub verifier_address: Address,
+    /// Recursion node-level VK hash, leaf-level VK hash and circuits set VKs hash, in this order.
+    pub verifier_params: [H256; 3],
+    /// Hash returned by `verificationKeyHash()` of the verifier contract.
+    pub verification_key_hash: H256,
+    pub protocol_version: U256,
+    pub total_batches_committed: u64,
+    pub total_batches_verified: u64,
+    pub total_batches_executed: u64,
+    /// Total number of priority operations requested on L1.
+    pub total_priority_txs: u64,
+    /// ID of the first priority operation not yet processed by an executed batch.
+    pub first_unprocessed_priority_tx: u64,
+}
+
+impl Default for DiamondProxyStorage {
+    fn default() -> Self {
+        Self {
+            bootloader_bytecode_hash: H256::zero(),
+            default_account_bytecode_hash: H256::zero(),
+            verifier_address: Address::repeat_byte(0x33),
+            verifier_params: [H256::zero(); 3],
+            verification_key_hash: H256::zero(),
+            protocol_version: (ProtocolVersionId::latest() as u16).into(),
+            total_batches_committed: 0,
+            total_batches_verified: 0,
+            total_batches_executed: 0,
+            total_priority_txs: 0,
+            first_unprocessed_priority_tx: 0,
+        }
+    }
+}
+
+/// Event emitted by the diamond proxy, with parameters in the ABI order.
+#[derive(Debug)]
+pub(super) struct ContractEvent {
+    pub name: &'static str,
+    pub params: Vec<Token>,
+}
+
+impl ContractEvent {
+    fn new(name: &'static str, params: Vec<Token>) -> Self {
+        Self { name, params }
+    }
+}
+
+impl DiamondProxyStorage {
+    /// Returns the encoded output of a getter with the specified selector, or `None` if the getter is not modeled.
+    pub(super) fn view(&self, selector: &[u8]) -> Option<Vec<u8>> {
+        let getter = |name: &str| ethabi::short_signature(name, &[]) == selector;
+        let output = if getter("getL2BootloaderBytecodeHash") {
+            vec![Token::FixedBytes(self.bootloader_bytecode_hash.0.to_vec())]
+        } else if getter("getL2DefaultAccountBytecodeHash") {
+            vec![Token::FixedBytes(
+                self.default_account_bytecode_hash.0.to_vec(),
+            )]
+        } else if getter("getVerifierParams") {
+            let params = self.verifier_params.iter();
+            vec![Token::Tuple(
+                params
+                    .map(|hash| Token::FixedBytes(hash.0.to_vec()))
+                    .collect(),
+            )]
+        } else if getter("getVerifier") {
+            vec![Token::Address(self.verifier_address)]
+        } else if getter("getProtocolVersion") {
+            vec![Token::Uint(self.protocol_version)]
+        } else if getter("getTotalBatchesCommitted") {
+            vec![Token::Uint(self.total_batches_committed.into())]
+        } else if getter("getTotalBatchesVerified") {
+            vec![Token::Uint(self.total_batches_verified.into())]
+        } else if getter("getTotalBatchesExecuted") {
+            vec![Token::Uint(self.total_batches_executed.into())]
+        } else if getter("getTotalPriorityTxs") {
+            vec![Token::Uint(self.total_priority_txs.into())]
+        } else if getter("getFirstUnprocessedPriorityTx") {
+            vec![Token::Uint(self.first_unprocessed_priority_tx.into())]
+        } else {
+            return None;
+        };
+        Some(ethabi::encode(&output))
+    }
+
+    /// Executes a state-changing call. On success, returns emitted events; on failure, returns the revert reason.
+    /// Calls to functions that are not modeled succeed without side effects.
+    pub(super) fn execute(
+        &mut self,
+        contract: &ethabi::Contract,
+        calldata: &[u8],
+    ) -> Result<Vec<ContractEvent>, String> {
+        if calldata.len() < 4 {
+            return Ok(vec![]);
+        }
+        let (selector, args) = calldata.split_at(4);
+        let Some(function) = contract
+            .functions()
+            .find(|function| function.short_signature() == selector)
+        else {
+            return Ok(vec![]);
+        };
+
+        let mut tokens = function
+            .decode_input(args)
+            .map_err(|err| format!("invalid calldata for `{}`: {err}", function.name))?;
+        if function.name.ends_with("SharedBridge") {
+            // The first argument of shared bridge methods is the L2 chain ID.
+            tokens.remove(0);
+        }
+        let mut tokens = tokens.into_iter();
+        let mut next_arg = || tokens.next().ok_or("missing argument");
+
+        match function.name.trim_end_matches("SharedBridge") {
+            "commitBatches" => self.commit_batches(&next_arg()?, next_arg()?),
+            "proveBatches" => self.prove_batches(&next_arg()?, next_arg()?),
+            "executeBatches" => self.execute_batches(next_arg()?),
+            "revertBatches" => {
+                let new_last_batch = next_arg()?.into_uint().ok_or("invalid batch number")?;
+                self.revert_batches(new_last_batch.as_u64())
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    fn commit_batches(
+        &mut self,
+        last_committed_batch: &Token,
+        new_batches: Token,
+    ) -> Result<Vec<ContractEvent>, String> {
+        let last_committed_number = batch_number(last_committed_batch)?;
+        if last_committed_number != self.total_batches_committed {
+            return Err(format!(
+                "last committed batch mismatch: expected #{}, got #{last_committed_number}",
+                self.total_batches_committed
+            ));
+        }
+
+        let new_batches = batch_array(new_batches)?;
+        let mut events = Vec::with_capacity(new_batches.len());
+        for (expected_number, batch) in (self.total_batches_committed + 1..).zip(&new_batches) {
+            let number = batch_number(batch)?;
+            if number != expected_number {
+                return Err(format!(
+                    "unexpected committed batch #{number}, expected #{expected_number}"
+                ));
+            }
+            // `newStateRoot` is used as the batch hash, same as in the real contract.
+            let batch_hash = batch_field(batch, 3)?;
+            let commitment = keccak256(&ethabi::encode(&[batch.clone()]));
+            events.push(ContractEvent::new(
+                "BlockCommit",
+                vec![
+                    Token::Uint(number.into()),
+                    batch_hash,
+                    Token::FixedBytes(commitment.to_vec()),
+                ],
+            ));
+        }
+        if events.is_empty() {
+            return Err("no batches to commit".to_owned());
+        }
+        self.total_batches_committed += events.len() as u64;
+        Ok(events)
+    }
+
+    fn prove_batches(
+        &mut self,
+        prev_batch: &Token,
+        committed_batches: Token,
+    ) -> Result<Vec<ContractEvent>, String> {
+        let prev_batch_number = batch_number(prev_batch)?;
+        if prev_batch_number != self.total_batches_verified {
+            return Err(format!(
+                "previous batch mismatch: expected #{}, got #{prev_batch_number}",
+                self.total_batches_verified
+            ));
+        }
+
+        let committed_batches = batch_array(committed_batches)?;
+        for (expected_number, batch) in (self.total_batches_verified + 1..).zip(&committed_batches)
+        {
+            let number = batch_number(batch)?;
+            if number != expected_number {
+                return Err(format!(
+                    "unexpected proven batch #{number}, expected #{expected_number}"
+                ));
+            }
+        }
+        let new_total_verified = self.total_batches_verified + committed_batches.len() as u64;
+        if committed_batches.is_empty() || new_total_verified > self.total_batches_committed {
+            return Err(format!(
+                "cannot prove batches up to #{new_total_verified}; last committed batch is #{}",
+                self.total_batches_committed
+            ));
+        }
+
+        let event = ContractEvent::new(
+            "BlocksVerification",
+            vec![
+                Token::Uint(self.total_batches_verified.into()),
+                Token::Uint(new_total_verified.into()),
+            ],
+        );
+        self.total_batches_verified = new_total_verified;
+        Ok(vec![event])
+    }
+
+    fn execute_batches(&mut self, batches: Token) -> Result<Vec<ContractEvent>, String> {
+        let batches = batch_array(batches)?;
+        let mut events = Vec::with_capacity(batches.len());
+        let mut priority_txs = 0_u64;
+        for (expected_number, batch) in (self.total_batches_executed + 1..).zip(&batches) {
+            let number = batch_number(batch)?;
+            if number != expected_number || number > self.total_batches_verified {
+                return Err(format!(
+                    "cannot execute batch #{number}; expected #{expected_number}, last verified batch is #{}",
+                    self.total_batches_verified
+                ));
+            }
+            let number_of_l1_txs = batch_field(batch, 3)?
+                .into_uint()
+                .ok_or("invalid `numberOfLayer1Txs`")?;
+            priority_txs += number_of_l1_txs.as_u64();
+            events.push(ContractEvent::new(
+                "BlockExecution",
+                vec![
+                    Token::Uint(number.into()),
+                    batch_field(batch, 1)?,
+                    batch_field(batch, 7)?,
+                ],
+            ));
+        }
+        if events.is_empty() {
+            return Err("no batches to execute".to_owned());
+        }
+
+        let first_unprocessed_priority_tx = self.first_unprocessed_priority_tx + priority_txs;
+        if first_unprocessed_priority_tx > self.total_priority_txs {
+            return Err(format!(
+                "executed batches process {priority_txs} priority txs, but only {} are in the queue",
+                self.total_priority_txs - self.first_unprocessed_priority_tx
+            ));
+        }
+        self.first_unprocessed_priority_tx = first_unprocessed_priority_tx;
+        self.total_batches_executed += events.len() as u64;
+        Ok(events)
+    }
+
+    fn revert_batches(&mut self, new_last_batch: u64) -> Result<Vec<ContractEvent>, String> {
+        if new_last_batch < self.total_batches_executed {
+            return Err(format!(
+                "cannot revert executed batch #{}",
+                self.total_batches_executed
+            ));
+        }
+        if new_last_batch < self.total_batches_committed {
+            self.total_batches_committed = new_last_batch;
+            self.total_batches_verified = self.total_batches_verified.min(new_last_batch);
+        }
+        Ok(vec![ContractEvent::new(
+            "BlocksRevert",
+            vec![
+                Token::Uint(self.total_batches_committed.into()),
+                Token::Uint(self.total_batches_verified.into()),
+                Token::Uint(self.total_batches_executed.into()),
+            ],
+        )])
+    }
+}
+
+fn batch_array(token: Token) -> Result<Vec<Token>, String> {
+    token
+        .into_array()
+        .ok_or_else(|| "batches must be an array".to_owned())
+}
+
+fn batch_field(batch: &Token, index: usize) -> Result<Token, String> {
+    let Token::Tuple(fields) = batch else {
+        return Err("batch info must be a tuple".to_owned());
+    };
+    fields
+        .get(index)
+        .cloned()
+        .ok_or_else(|| format!("batch info has no field #{index}"))
+}
+
+fn batch_number(batch: &Token) -> Result<u64, String> {
+    let number = batch_field(batch, 0)?
+        .into_uint()
+        .ok_or("batch number must be an integer")?;
+    Ok(number.as_u64())
+}
@@ -0,0 +1,747 @@
+//! In-process L1 simulator allowing to test server components interacting with L1 without an Ethereum node.
+
+use std::{
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use jsonrpc_core::types::error::Error as RpcError;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use zksync_contracts::{governance_contract, zksync_contract};
+use zksync_types::{
+    l1::L1Tx,
+    web3::{
+        contract,
+        ethabi::{self, ParamType, Token},
+        signing::keccak256,
+        types::{BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt, U64},
+        Error as Web3Error,
+    },
+    Address, L1ChainId, PriorityOpId, EIP_1559_TX_TYPE, EIP_4844_TX_TYPE, H160, H256,
+    PRIORITY_OPERATION_L2_TX_TYPE, U256,
+};
+
+pub use self::diamond_proxy::DiamondProxyStorage;
+use self::state::{HookEvent, SimulatedBlock, SimulatedTx, SimulatorState};
+use crate::{
+    types::{encode_blob_tx_with_sidecar, Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
+    Block, BoundEthInterface, ContractCall, EthInterface, Options, RawTransactionBytes,
+};
+
+mod diamond_proxy;
+mod state;
+#[cfg(test)]
+mod tests;
+
+const DEFAULT_BASE_FEE_PER_GAS: u64 = 1_000_000_000;
+const DEFAULT_PRIORITY_FEE_PER_GAS: u64 = 1_000_000_000;
+const DEFAULT_GAS_LIMIT: u64 = 10_000_000;
+const DEFAULT_BLOCK_GAS_LIMIT: u64 = 30_000_000;
+/// Time after which priority operations expire on L1.
+const PRIORITY_TX_EXPIRATION_SECS: u64 = 86_400;
+/// Error code returned by Ethereum nodes for reverted transactions.
+const REVERT_ERROR_CODE: i64 = 3;
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("incorrect system time")
+        .as_secs()
+}
+
+fn rpc_error(message: impl Into<String>) -> Error {
+    Error::EthereumGateway(Web3Error::Rpc(RpcError {
+        message: message.into(),
+        code: (-32_000).into(),
+        data: None,
+    }))
+}
+
+#[derive(Debug, Clone)]
+struct SimulatorConfig {
+    contract: ethabi::Contract,
+    diamond_proxy_address: Address,
+    multicall3_address: Address,
+    governance_address: Address,
+    chain_id: L1ChainId,
+}
+
+/// In-process stand-in for L1 with a simplified model of zkSync contracts.
+///
+/// Unlike [`MockEthereum`](super::MockEthereum), the simulator executes transactions: blocks are mined on demand
+/// using [`Self::mine_block()`], and transactions calling `commitBatches` / `proveBatches` / `executeBatches`
+/// (incl. their shared bridge versions) on the diamond proxy update [`DiamondProxyStorage`] and emit the same
+/// events as the real contract. Getters queried by the server (directly or via Multicall3) are served
+/// from the storage as well. Tests can additionally emit priority operations and protocol upgrades,
+/// and inject reverts, reorgs and fee spikes.
+///
+/// Clones of the simulator share the chain state. Use [`Self::for_sender()`] to get a client bound to
+/// another operator account. Transaction signatures are not modeled; a raw transaction just records its sender.
+#[derive(Debug, Clone)]
+pub struct L1Simulator {
+    config: Arc<SimulatorConfig>,
+    state: Arc<RwLock<SimulatorState>>,
+    sender_account: Address,
+}
+
+impl L1Simulator {
+    /// Creates a simulator with the diamond proxy deployed at the specified address and a genesis block.
+    pub fn new(diamond_proxy_address: Address) -> Self {
+        let config = SimulatorConfig {
+            contract: zksync_contract(),
+            diamond_proxy_address,
+            multicall3_address: Address::repeat_byte(0xca),
+            governance_address: Address::repeat_byte(0x90),
+            chain_id: L1ChainId(9),
+        };
+        let state = SimulatorState::new(unix_timestamp(), DEFAULT_BASE_FEE_PER_GAS);
+        Self {
+            config: Arc::new(config),
+            state: Arc::new(RwLock::new(state)),
+            sender_account: Address::repeat_byte(0x11),
+        }
+    }
+
+    #[must_use]
+    pub fn with_multicall3_address(mut self, address: Address) -> Self {
+        Arc::make_mut(&mut self.config).multicall3_address = address;
+        self
+    }
+
+    #[must_use]
+    pub fn with_governance_address(mut self, address: Address) -> Self {
+        Arc::make_mut(&mut self.config).governance_address = address;
+        self
+    }
+
+    #[must_use]
+    pub fn with_chain_id(mut self, chain_id: L1ChainId) -> Self {
+        Arc::make_mut(&mut self.config).chain_id = chain_id;
+        self
+    }
+
+    /// Returns a client sharing the chain state with this one, but sending transactions from another account.
+    pub fn for_sender(&self, sender_account: Address) -> Self {
+        Self {
+            sender_account,
+            ..self.clone()
+        }
+    }
+
+    pub fn diamond_proxy_address(&self) -> Address {
+        self.config.diamond_proxy_address
+    }
+
+    pub fn multicall3_address(&self) -> Address {
+        self.config.multicall3_address
+    }
+
+    pub fn governance_address(&self) -> Address {
+        self.config.governance_address
+    }
+
+    fn read_state(&self) -> RwLockReadGuard<'_, SimulatorState> {
+        self.state.read().expect("simulator state is poisoned")
+    }
+
+    fn write_state(&self) -> RwLockWriteGuard<'_, SimulatorState> {
+        self.state.write().expect("simulator state is poisoned")
+    }
+
+    /// Mines a block with all pending events and transactions that can be included. Returns the number
+    /// of the mined block.
+    pub fn mine_block(&self) -> u64 {
+        self.write_state().mine_block(
+            &self.config.contract,
+            self.config.diamond_proxy_address,
+            unix_timestamp(),
+        )
+    }
+
+    /// Mines the specified number of blocks. Returns the number of the last mined block.
+    pub fn mine_blocks(&self, count: u64) -> u64 {
+        let mut state = self.write_state();
+        for _ in 0..count {
+            state.mine_block(
+                &self.config.contract,
+                self.config.diamond_proxy_address,
+                unix_timestamp(),
+            );
+        }
+        state.head().number
+    }
+
+    /// Returns the number of transactions in the mempool.
+    pub fn pending_tx_count(&self) -> usize {
+        self.read_state().pending_tx_count()
+    }
+
+    /// Returns the current diamond proxy storage.
+    pub fn diamond_proxy(&self) -> DiamondProxyStorage {
+        self.read_state().head().diamond_proxy.clone()
+    }
+
+    /// Overrides the diamond proxy storage at the latest block (e.g., to set up the state after an upgrade).
+    pub fn update_diamond_proxy(&self, update: impl FnOnce(&mut DiamondProxyStorage)) {
+        update(&mut self.write_state().head_mut().diamond_proxy);
+    }
+
+    /// Sets the ETH balance of the specified account.
+    pub fn set_balance(&self, account: Address, balance: U256) {
+        self.write_state().balances.insert(account, balance);
+    }
+
+    /// Sets the base fee per gas for newly mined blocks.
+    pub fn set_base_fee_per_gas(&self, base_fee_per_gas: u64) {
+        self.write_state().base_fee_per_gas = base_fee_per_gas;
+    }
+
+    /// Sets the excess blob gas for newly mined blocks, which determines the blob base fee.
+    pub fn set_excess_blob_gas(&self, excess_blob_gas: u64) {
+        self.write_state().excess_blob_gas = excess_blob_gas;
+    }
+
+    /// Sets the number of blocks by which the safe and finalized blocks lag behind the latest one.
+    /// By default, all blocks are immediately finalized.
+    pub fn set_finality_lag(&self, safe_lag: u64, finalized_lag: u64) {
+        let mut state = self.write_state();
+        state.safe_lag = safe_lag;
+        state.finalized_lag = finalized_lag;
+    }
+
+    /// Makes the next `count` transactions calling the specified function of the main contract revert
+    /// regardless of the target address.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the main contract doesn't have the specified function.
+    pub fn revert_next(&self, function_name: &str, count: usize) {
+        let function = self
+            .config
+            .contract
+            .function(function_name)
+            .unwrap_or_else(|err| panic!("cannot revert `{function_name}`: {err}"));
+        self.write_state()
+            .inject_revert(function.short_signature(), count);
+    }
+
+    /// Removes the specified number of latest blocks. Transactions and events from the removed blocks return
+    /// to the mempool, so they will be included into the newly mined blocks (which have different hashes).
+    pub fn reorg(&self, depth: usize) {
+        self.write_state().reorg(depth);
+    }
+
+    /// Emits a `NewPriorityRequest` event for the specified L1 -> L2 transaction in the next mined block.
+    /// Returns the serial ID assigned to the transaction; the serial ID, L1 block and hashes specified
+    /// in `tx` are ignored.
+    pub fn add_priority_op(&self, tx: &L1Tx) -> PriorityOpId {
+        let mut state = self.write_state();
+        let serial_id = PriorityOpId(state.next_priority_op_id);
+        state.next_priority_op_id += 1;
+        let expiration_timestamp = state.head().timestamp + PRIORITY_TX_EXPIRATION_SECS;
+
+        let event = self
+            .config
+            .contract
+            .event("NewPriorityRequest")
+            .expect("`NewPriorityRequest` event is missing in ABI");
+        state.add_hook_event(HookEvent {
+            address: self.config.diamond_proxy_address,
+            topics: vec![event.signature()],
+            data: priority_op_event_data(tx, serial_id, expiration_timestamp),
+            is_priority_op: true,
+        });
+        serial_id
+    }
+
+    /// Emits a `TransparentOperationScheduled` event from the governance contract in the next mined block.
+    /// The scheduled operation calls `executeUpgrade` with the specified diamond cut on the diamond proxy.
+    pub fn schedule_governance_upgrade(&self, diamond_cut: Token) {
+        let execute_upgrade = self
+            .config
+            .contract
+            .function("executeUpgrade")
+            .expect("`executeUpgrade` function is missing in ABI");
+        let calldata = execute_upgrade
+            .encode_input(&[diamond_cut])
+            .expect("invalid diamond cut");
+        let call = Token::Tuple(vec![
+            Token::Address(self.config.diamond_proxy_address),
+            Token::Uint(U256::zero()),
+            Token::Bytes(calldata),
+        ]);
+        let operation = Token::Tuple(vec![
+            Token::Array(vec![call]),
+            Token::FixedBytes(H256::zero().0.to_vec()), // predecessor
+            Token::FixedBytes(H256::zero().0.to_vec()), // salt
+        ]);
+        let operation_id = keccak256(&ethabi::encode(&[operation.clone()]));
+
+        let event = governance_contract()
+            .event("TransparentOperationScheduled")
+            .expect("`TransparentOperationScheduled` event is missing in ABI")
+            .signature();
+        self.write_state().add_hook_event(HookEvent {
+            address: self.config.governance_address,
+            topics: vec![event, H256(operation_id)],
+            data: ethabi::encode(&[Token::FixedBytes(operation_id.to_vec()), operation]),
+            is_priority_op: false,
+        });
+    }
+
+    fn resolve_block_number(state: &SimulatorState, block: BlockNumber) -> u64 {
+        match block {
+            BlockNumber::Latest | BlockNumber::Pending => state.head().number,
+            BlockNumber::Earliest => 0,
+            BlockNumber::Safe => state.safe_block_number(),
+            BlockNumber::Finalized => state.finalized_block_number(),
+            BlockNumber::Number(number) => number.as_u64(),
+        }
+    }
+
+    /// Executes a read-only call against the latest block. Returns the encoded output, or `None` if the call reverts.
+    fn eth_call(
+        &self,
+        state: &SimulatorState,
+        target: Address,
+        calldata: &[u8],
+    ) -> Option<Vec<u8>> {
+        let (selector, args) = calldata.split_at(calldata.len().min(4));
+        let diamond_proxy = &state.head().diamond_proxy;
+        if target == self.config.diamond_proxy_address {
+            diamond_proxy.view(selector)
+        } else if target == diamond_proxy.verifier_address {
+            let vk_hash_selector = ethabi::short_signature("verificationKeyHash", &[]);
+            (selector == vk_hash_selector).then(|| {
+                ethabi::encode(&[Token::FixedBytes(
+                    diamond_proxy.verification_key_hash.0.to_vec(),
+                )])
+            })
+        } else if target == self.config.multicall3_address {
+            Self::aggregate3(selector, args, |target, calldata| {
+                self.eth_call(state, target, calldata)
+            })
+        } else {
+            None
+        }
+    }
+
+    fn aggregate3(
+        selector: &[u8],
+        args: &[u8],
+        call: impl Fn(Address, &[u8]) -> Option<Vec<u8>>,
+    ) -> Option<Vec<u8>> {
+        let call_type =
+            ParamType::Tuple(vec![ParamType::Address, ParamType::Bool, ParamType::Bytes]);
+        let calls_type = ParamType::Array(Box::new(call_type));
+        if selector != ethabi::short_signature("aggregate3", &[calls_type.clone()]) {
+            return None;
+        }
+
+        let calls = ethabi::decode(&[calls_type], args)
+            .ok()?
+            .pop()?
+            .into_array()?;
+        let mut results = Vec::with_capacity(calls.len());
+        for call_token in calls {
+            let mut fields = call_token.into_tuple()?.into_iter();
+            let target = fields.next()?.into_address()?;
+            let allow_failure = fields.next()?.into_bool()?;
+            let calldata = fields.next()?.into_bytes()?;
+            let output = call(target, &calldata);
+            if output.is_none() && !allow_failure {
+                return None;
+            }
+            results.push(Token::Tuple(vec![
+                Token::Bool(output.is_some()),
+                Token::Bytes(output.unwrap_or_default()),
+            ]));
+        }
+        Some(ethabi::encode(&[Token::Array(results)]))
+    }
+}
+
+fn priority_op_event_data(
+    tx: &L1Tx,
+    serial_id: PriorityOpId,
+    expiration_timestamp: u64,
+) -> Vec<u8> {
+    let common_data = &tx.common_data;
+    let refund_recipient = U256::from_big_endian(common_data.refund_recipient.as_bytes());
+    let tx_token = Token::Tuple(vec![
+        Token::Uint(PRIORITY_OPERATION_L2_TX_TYPE.into()),
+        Token::Address(common_data.sender),
+        Token::Address(tx.execute.contract_address),
+        Token::Uint(common_data.gas_limit),
+        Token::Uint(common_data.gas_per_pubdata_limit),
+        Token::Uint(common_data.max_fee_per_gas),
+        Token::Uint(U256::zero()),       // max priority fee per gas
+        Token::Address(Address::zero()), // paymaster
+        Token::Uint(serial_id.0.into()),
+        Token::Uint(tx.execute.value),
+        Token::FixedArray(vec![
+            Token::Uint(common_data.to_mint),
+            Token::Uint(refund_recipient),
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::zero()),
+        ]),
+        Token::Bytes(tx.execute.calldata.clone()),
+        Token::Bytes(vec![]), // signature
+        // Factory dependency hashes are not checked by the server, so we don't compute them.
+        Token::Array(vec![]),
+        Token::Bytes(vec![]), // paymaster input
+        Token::Bytes(vec![]), // reserved dynamic
+    ]);
+    let canonical_tx_hash = keccak256(&ethabi::encode(&[tx_token.clone()]));
+    let factory_deps = tx.execute.factory_deps.iter().flatten();
+    let factory_deps = factory_deps.map(|dep| Token::Bytes(dep.clone())).collect();
+
+    ethabi::encode(&[
+        Token::Uint(serial_id.0.into()),
+        Token::FixedBytes(canonical_tx_hash.to_vec()),
+        Token::Uint(expiration_timestamp.into()),
+        tx_token,
+        Token::Array(factory_deps),
+    ])
+}
+
+impl SimulatedBlock {
+    fn to_web3(&self) -> Block<H256> {
+        Block {
+            hash: Some(self.hash),
+            parent_hash: self.parent_hash,
+            number: Some(self.number.into()),
+            timestamp: self.timestamp.into(),
+            gas_limit: DEFAULT_BLOCK_GAS_LIMIT.into(),
+            base_fee_per_gas: Some(self.base_fee_per_gas.into()),
+            excess_blob_gas: Some(self.excess_blob_gas.into()),
+            transactions: self.tx_hashes.clone(),
+            ..Block::default()
+        }
+    }
+}
+
+/// Parsed version of [`Filter`]. Since filter fields are private, the filter is parsed from its JSON presentation.
+#[derive(Debug)]
+struct LogFilter {
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+    block_hash: Option<H256>,
+    addresses: Option<Vec<Address>>,
+    topics: Vec<Option<Vec<H256>>>,
+}
+
+impl LogFilter {
+    fn new(filter: &Filter) -> Result<Self, serde_json::Error> {
+        let mut fields = match serde_json::to_value(filter)? {
+            Value::Object(fields) => fields,
+            _ => serde_json::Map::new(),
+        };
+        let mut take = |name: &str| fields.remove(name).filter(|value| !value.is_null());
+
+        let from_block = take("fromBlock")
+            .map(Self::parse_block_number)
+            .transpose()?;
+        let to_block = take("toBlock").map(Self::parse_block_number).transpose()?;
+        let block_hash = take("blockHash").map(serde_json::from_value).transpose()?;
+        let addresses = take("address").map(Self::one_or_many).transpose()?;
+        let topics = match take("topics") {
+            Some(Value::Array(topics)) => topics
+                .into_iter()
+                .map(|topic| {
+                    (!topic.is_null())
+                        .then(|| Self::one_or_many(topic))
+                        .transpose()
+                })
+                .collect::<Result<_, _>>()?,
+            _ => vec![],
+        };
+        Ok(Self {
+            from_block: from_block.unwrap_or(BlockNumber::Latest),
+            to_block: to_block.unwrap_or(BlockNumber::Latest),
+            block_hash,
+            addresses,
+            topics,
+        })
+    }
+
+    fn parse_block_number(value: Value) -> Result<BlockNumber, serde_json::Error> {
+        Ok(match value.as_str() {
+            Some("latest") => BlockNumber::Latest,
+            Some("earliest") => BlockNumber::Earliest,
+            Some("pending") => BlockNumber::Pending,
+            Some("safe") => BlockNumber::Safe,
+            Some("finalized") => BlockNumber::Finalized,
+            _ => BlockNumber::Number(serde_json::from_value::<U64>(value)?),
+        })
+    }
+
+    fn one_or_many<T: DeserializeOwned>(value: Value) -> Result<Vec<T>, serde_json::Error> {
+        match value {
+            Value::Array(values) => values.into_iter().map(serde_json::from_value).collect(),
+            value => Ok(vec![serde_json::from_value(value)?]),
+        }
+    }
+
+    fn matches(&self, log: &Log) -> bool {
+        let address_matches = self
+            .addresses
+            .as_ref()
+            .map_or(true, |addresses| addresses.contains(&log.address));
+        let topics_match = self.topics.iter().enumerate().all(|(i, topics)| {
+            let Some(topics) = topics else {
+                return true;
+            };
+            log.topics
+                .get(i)
+                .map_or(false, |topic| topics.contains(topic))
+        });
+        address_matches && topics_match
+    }
+}
+
+#[async_trait]
+impl EthInterface for L1Simulator {
+    async fn nonce_at_for_account(
+        &self,
+        account: Address,
+        block: BlockNumber,
+        _component: &'static str,
+    ) -> Result<U256, Error> {
+        let state = self.read_state();
+        let nonce = if matches!(block, BlockNumber::Pending) {
+            state.pending_nonce(account)
+        } else {
+            state.nonce(account, Self::resolve_block_number(&state, block))
+        };
+        Ok(nonce.into())
+    }
+
+    async fn base_fee_history(
+        &self,
+        from_block: usize,
+        block_count: usize,
+        _component: &'static str,
+    ) -> Result<Vec<u64>, Error> {
+        let state = self.read_state();
+        let head = state.head().number as usize;
+        if from_block > head {
+            return Err(rpc_error(format!(
+                "requested block #{from_block} is after the head block #{head}"
+            )));
+        }
+        let start_block = (from_block + 1).saturating_sub(block_count);
+        let blocks = &state.blocks[start_block..=from_block];
+        Ok(blocks.iter().map(|block| block.base_fee_per_gas).collect())
+    }
+
+    async fn get_pending_block_base_fee_per_gas(
+        &self,
+        _component: &'static str,
+    ) -> Result<U256, Error> {
+        Ok(self.read_state().base_fee_per_gas.into())
+    }
+
+    async fn get_gas_price(&self, _component: &'static str) -> Result<U256, Error> {
+        let base_fee_per_gas = self.read_state().base_fee_per_gas;
+        Ok((base_fee_per_gas + DEFAULT_PRIORITY_FEE_PER_GAS).into())
+    }
+
+    async fn block_number(&self, _component: &'static str) -> Result<U64, Error> {
+        Ok(self.read_state().head().number.into())
+    }
+
+    async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
+        let tx = SimulatedTx::decode(&tx.0).map_err(rpc_error)?;
+        self.write_state().add_pending_tx(tx).map_err(rpc_error)
+    }
+
+    async fn get_tx_status(
+        &self,
+        hash: H256,
+        _component: &'static str,
+    ) -> Result<Option<ExecutedTxStatus>, Error> {
+        let state = self.read_state();
+        let Some(receipt) = state.receipts.get(&hash) else {
+            return Ok(None);
+        };
+        Ok(Some(ExecutedTxStatus {
+            tx_hash: hash,
+            success: receipt.revert_reason.is_none(),
+            receipt: receipt.to_web3(&state.txs[&hash]),
+        }))
+    }
+
+    async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
+        let state = self.read_state();
+        let Some(receipt) = state.receipts.get(&tx_hash) else {
+            return Ok(None);
+        };
+        Ok(receipt.revert_reason.as_ref().map(|reason| FailureInfo {
+            revert_code: REVERT_ERROR_CODE,
+            revert_reason: format!("execution reverted: {reason}"),
+            gas_used: Some(receipt.gas_used),
+            gas_limit: state.txs[&tx_hash].gas_limit,
+        }))
+    }
+
+    async fn get_tx(
+        &self,
+        hash: H256,
+        _component: &'static str,
+    ) -> Result<Option<Transaction>, Error> {
+        let state = self.read_state();
+        let tx = state.txs.get(&hash);
+        Ok(tx.map(|tx| tx.to_web3(state.receipts.get(&hash))))
+    }
+
+    async fn tx_receipt(
+        &self,
+        tx_hash: H256,
+        _component: &'static str,
+    ) -> Result<Option<TransactionReceipt>, Error> {
+        let state = self.read_state();
+        let receipt = state.receipts.get(&tx_hash);
+        Ok(receipt.map(|receipt| receipt.to_web3(&state.txs[&tx_hash])))
+    }
+
+    async fn eth_balance(&self, address: Address, _component: &'static str) -> Result<U256, Error> {
+        Ok(self.read_state().balance(address))
+    }
+
+    async fn call_contract_function(
+        &self,
+        call: ContractCall,
+    ) -> Result<Vec<ethabi::Token>, Error> {
+        let function = call.contract_abi.function(&call.inner.name)?;
+        let calldata = function.encode_input(&call.inner.params.0)?;
+        let output = self
+            .eth_call(&self.read_state(), call.contract_address, &calldata)
+            .ok_or_else(|| rpc_error("execution reverted"))?;
+        Ok(function.decode_output(&output)?)
+    }
+
+    async fn logs(&self, filter: Filter, _component: &'static str) -> Result<Vec<Log>, Error> {
+        let filter = LogFilter::new(&filter).map_err(|err| rpc_error(err.to_string()))?;
+        let state = self.read_state();
+        let (from_block, to_block) = if let Some(block_hash) = filter.block_hash {
+            let Some(block) = state.blocks.iter().find(|block| block.hash == block_hash) else {
+                return Err(rpc_error(format!("unknown block {block_hash:?}")));
+            };
+            (block.number, block.number)
+        } else {
+            let from_block = Self::resolve_block_number(&state, filter.from_block);
+            let to_block = Self::resolve_block_number(&state, filter.to_block);
+            (from_block, to_block.min(state.head().number))
+        };
+
+        let blocks = state
+            .blocks
+            .get(from_block as usize..=to_block as usize)
+            .unwrap_or_default();
+        let logs = blocks.iter().flat_map(|block| &block.logs);
+        Ok(logs.filter(|log| filter.matches(log)).cloned().collect())
+    }
+
+    async fn block(
+        &self,
+        block_id: BlockId,
+        _component: &'static str,
+    ) -> Result<Option<Block<H256>>, Error> {
+        let state = self.read_state();
+        let block = match block_id {
+            BlockId::Hash(hash) => state.blocks.iter().find(|block| block.hash == hash),
+            BlockId::Number(number) => {
+                let number = Self::resolve_block_number(&state, number);
+                state.blocks.get(number as usize)
+            }
+        };
+        Ok(block.map(SimulatedBlock::to_web3))
+    }
+}
+
+#[async_trait]
+impl BoundEthInterface for L1Simulator {
+    fn contract(&self) -> &ethabi::Contract {
+        &self.config.contract
+    }
+
+    fn contract_addr(&self) -> H160 {
+        self.config.diamond_proxy_address
+    }
+
+    fn chain_id(&self) -> L1ChainId {
+        self.config.chain_id
+    }
+
+    fn sender_account(&self) -> Address {
+        self.sender_account
+    }
+
+    async fn allowance_on_account(
+        &self,
+        _token_address: Address,
+        _address: Address,
+        _erc20_abi: ethabi::Contract,
+    ) -> Result<U256, Error> {
+        // ERC-20 tokens are not modeled by the simulator.
+        Err(Error::Contract(contract::Error::InterfaceUnsupported))
+    }
+
+    async fn sign_prepared_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        let nonce = match options.nonce {
+            Some(nonce) => nonce,
+            None => self.pending_nonce(component).await?,
+        };
+        let max_priority_fee_per_gas = options
+            .max_priority_fee_per_gas
+            .unwrap_or_else(|| DEFAULT_PRIORITY_FEE_PER_GAS.into());
+        let max_fee_per_gas = options.max_fee_per_gas.unwrap_or_else(|| {
+            let base_fee_per_gas = self.read_state().base_fee_per_gas;
+            U256::from(base_fee_per_gas) * 2 + max_priority_fee_per_gas
+        });
+        if max_fee_per_gas < max_priority_fee_per_gas {
+            return Err(Error::WrongFeeProvided(
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            ));
+        }
+        let tx_type = options
+            .transaction_type
+            .map_or(EIP_1559_TX_TYPE, |tx_type| tx_type.as_u64() as u8);
+        if tx_type == EIP_4844_TX_TYPE && options.max_fee_per_blob_gas.is_none() {
+            return Err(Error::Eip4844MissingMaxFeePerBlobGas);
+        }
+
+        let tx = SimulatedTx::new(
+            tx_type,
+            self.sender_account,
+            contract_addr,
+            nonce.as_u64(),
+            options.gas.unwrap_or_else(|| DEFAULT_GAS_LIMIT.into()),
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            options.max_fee_per_blob_gas,
+            data,
+        );
+        let mut raw_tx = tx.encode();
+        if let Some(sidecar) = &options.blob_tx_sidecar {
+            raw_tx = encode_blob_tx_with_sidecar(&raw_tx, sidecar);
+        }
+        Ok(SignedCallResult::new(
+            RawTransactionBytes(raw_tx),
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            nonce,
+            tx.hash,
+        ))
+    }
+}
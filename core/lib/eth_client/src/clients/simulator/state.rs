@@ -0,0 +1,607 @@
+//! Chain state of the L1 simulator: blocks, mempool, receipts and account nonces.
+
+use std::collections::HashMap;
+
+use rlp::{Rlp, RlpStream};
+use zksync_types::{
+    web3::{
+        ethabi,
+        signing::keccak256,
+        types::{Log, Transaction, TransactionReceipt},
+    },
+    Address, EIP_1559_TX_TYPE, EIP_4844_TX_TYPE, H256, U256, U64,
+};
+
+use super::diamond_proxy::DiamondProxyStorage;
+
+/// Gas consumed by a simulated transaction in addition to 16 gas per calldata byte.
+const BASE_TX_GAS: u64 = 21_000;
+/// Blob base fee parameters from EIP-4844.
+const MIN_BLOB_BASE_FEE: u64 = 1;
+const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3_338_477;
+/// Balance of accounts not explicitly funded, in ETH.
+const DEFAULT_BALANCE_ETH: u64 = 1_000_000;
+
+/// Transaction as seen by the simulator.
+///
+/// Raw transactions produced by the simulator have the form `tx_type || RLP(from, to, nonce, gas_limit,
+/// max_fee_per_gas, max_priority_fee_per_gas, max_fee_per_blob_gas, input)`; i.e., "signing" just records
+/// the sender. Blob transactions may additionally be wrapped together with their sidecar
+/// using [`encode_blob_tx_with_sidecar()`](crate::encode_blob_tx_with_sidecar).
+#[derive(Debug, Clone)]
+pub(super) struct SimulatedTx {
+    pub hash: H256,
+    pub tx_type: u8,
+    pub from: Address,
+    pub to: Address,
+    pub nonce: u64,
+    pub gas_limit: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_blob_gas: Option<U256>,
+    pub input: Vec<u8>,
+}
+
+impl SimulatedTx {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        tx_type: u8,
+        from: Address,
+        to: Address,
+        nonce: u64,
+        gas_limit: U256,
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+        max_fee_per_blob_gas: Option<U256>,
+        input: Vec<u8>,
+    ) -> Self {
+        let mut tx = Self {
+            hash: H256::zero(),
+            tx_type,
+            from,
+            to,
+            nonce,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+            input,
+        };
+        tx.hash = H256(keccak256(&tx.encode()));
+        tx
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(8);
+        stream.append(&self.from.as_bytes().to_vec());
+        stream.append(&self.to.as_bytes().to_vec());
+        stream.append(&self.nonce);
+        stream.append(&u256_bytes(self.gas_limit));
+        stream.append(&u256_bytes(self.max_fee_per_gas));
+        stream.append(&u256_bytes(self.max_priority_fee_per_gas));
+        stream.append(&self.max_fee_per_blob_gas.map_or_else(Vec::new, u256_bytes));
+        stream.append(&self.input);
+        [&[self.tx_type], stream.as_raw()].concat()
+    }
+
+    pub fn decode(raw_tx: &[u8]) -> Result<Self, String> {
+        let (&tx_type, payload) = raw_tx.split_first().ok_or("empty transaction")?;
+        if tx_type != EIP_1559_TX_TYPE && tx_type != EIP_4844_TX_TYPE {
+            return Err(format!("unsupported transaction type: {tx_type}"));
+        }
+        let rlp = Rlp::new(payload);
+        let first_item = rlp.at(0).map_err(|err| err.to_string())?;
+        // Blob transactions wrapped together with the sidecar have the transaction as the first list item.
+        let tx_rlp = if first_item.is_list() {
+            first_item
+        } else {
+            rlp
+        };
+
+        let field = |index: usize| -> Result<Vec<u8>, String> {
+            let item = tx_rlp.at(index).map_err(|err| err.to_string())?;
+            item.data()
+                .map(<[u8]>::to_vec)
+                .map_err(|err| err.to_string())
+        };
+        let nonce: u64 = tx_rlp.val_at(2).map_err(|err| err.to_string())?;
+        let max_fee_per_blob_gas = field(6)?;
+        let tx = Self::new(
+            tx_type,
+            Address::from_slice(&field(0)?),
+            Address::from_slice(&field(1)?),
+            nonce,
+            U256::from_big_endian(&field(3)?),
+            U256::from_big_endian(&field(4)?),
+            U256::from_big_endian(&field(5)?),
+            (!max_fee_per_blob_gas.is_empty())
+                .then(|| U256::from_big_endian(&max_fee_per_blob_gas)),
+            field(7)?,
+        );
+        Ok(tx)
+    }
+
+    fn is_includable(&self, base_fee_per_gas: u64, blob_base_fee: U256) -> bool {
+        self.max_fee_per_gas >= base_fee_per_gas.into()
+            && self
+                .max_fee_per_blob_gas
+                .map_or(true, |max_fee| max_fee >= blob_base_fee)
+    }
+
+    pub fn to_web3(&self, receipt: Option<&TxReceipt>) -> Transaction {
+        Transaction {
+            hash: self.hash,
+            nonce: self.nonce.into(),
+            block_hash: receipt.map(|receipt| receipt.block_hash),
+            block_number: receipt.map(|receipt| receipt.block_number.into()),
+            transaction_index: receipt.map(|receipt| receipt.index.into()),
+            from: Some(self.from),
+            to: Some(self.to),
+            gas: self.gas_limit,
+            input: self.input.clone().into(),
+            transaction_type: Some(self.tx_type.into()),
+            max_fee_per_gas: Some(self.max_fee_per_gas),
+            max_priority_fee_per_gas: Some(self.max_priority_fee_per_gas),
+            ..Transaction::default()
+        }
+    }
+}
+
+fn u256_bytes(value: U256) -> Vec<u8> {
+    let mut bytes = [0_u8; 32];
+    value.to_big_endian(&mut bytes);
+    bytes.to_vec()
+}
+
+/// Computes the blob base fee for the specified excess blob gas as per EIP-4844.
+pub(super) fn blob_base_fee(excess_blob_gas: u64) -> U256 {
+    let (factor, numerator, denominator) = (
+        U256::from(MIN_BLOB_BASE_FEE),
+        U256::from(excess_blob_gas),
+        U256::from(BLOB_BASE_FEE_UPDATE_FRACTION),
+    );
+    let mut output = U256::zero();
+    let mut accum = factor * denominator;
+    let mut i = U256::one();
+    while !accum.is_zero() {
+        output += accum;
+        accum = accum * numerator / (denominator * i);
+        i += U256::one();
+    }
+    output / denominator
+}
+
+/// Outcome of an executed transaction.
+#[derive(Debug, Clone)]
+pub(super) struct TxReceipt {
+    pub block_number: u64,
+    pub block_hash: H256,
+    pub index: u64,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    /// Revert reason, or `None` if the transaction succeeded.
+    pub revert_reason: Option<String>,
+    pub logs: Vec<Log>,
+}
+
+impl TxReceipt {
+    pub fn to_web3(&self, tx: &SimulatedTx) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: tx.hash,
+            transaction_index: self.index.into(),
+            block_hash: Some(self.block_hash),
+            block_number: Some(self.block_number.into()),
+            from: tx.from,
+            to: Some(tx.to),
+            gas_used: Some(self.gas_used),
+            effective_gas_price: Some(self.effective_gas_price),
+            status: Some(U64::from(u64::from(self.revert_reason.is_none()))),
+            logs: self.logs.clone(),
+            transaction_type: Some(tx.tx_type.into()),
+            ..TransactionReceipt::default()
+        }
+    }
+}
+
+/// Event emitted by a simulator hook rather than by a transaction.
+#[derive(Debug, Clone)]
+pub(super) struct HookEvent {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+    /// Whether this is a `NewPriorityRequest` event that should increase the priority queue size.
+    pub is_priority_op: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SimulatedBlock {
+    pub number: u64,
+    pub hash: H256,
+    pub parent_hash: H256,
+    pub timestamp: u64,
+    pub base_fee_per_gas: u64,
+    pub excess_blob_gas: u64,
+    pub tx_hashes: Vec<H256>,
+    pub hook_events: Vec<HookEvent>,
+    pub logs: Vec<Log>,
+    /// Account nonces after the block.
+    pub nonces: HashMap<Address, u64>,
+    /// Diamond proxy storage after the block.
+    pub diamond_proxy: DiamondProxyStorage,
+}
+
+#[derive(Debug)]
+struct PendingTx {
+    seq: u64,
+    tx: SimulatedTx,
+}
+
+#[derive(Debug)]
+struct InjectedRevert {
+    selector: [u8; 4],
+    count: usize,
+}
+
+/// Mutable state of the simulator.
+#[derive(Debug)]
+pub(super) struct SimulatorState {
+    /// Blocks of the canonical chain; the first block is genesis.
+    pub blocks: Vec<SimulatedBlock>,
+    pending_txs: Vec<PendingTx>,
+    next_tx_seq: u64,
+    /// All transactions accepted by the simulator, including pending and replaced ones.
+    pub txs: HashMap<H256, SimulatedTx>,
+    pub receipts: HashMap<H256, TxReceipt>,
+    pub balances: HashMap<Address, U256>,
+    pending_events: Vec<HookEvent>,
+    pub next_priority_op_id: u64,
+    injected_reverts: Vec<InjectedRevert>,
+    /// Base fee per gas and excess blob gas for newly mined blocks.
+    pub base_fee_per_gas: u64,
+    pub excess_blob_gas: u64,
+    pub safe_lag: u64,
+    pub finalized_lag: u64,
+    reorg_count: u64,
+}
+
+impl SimulatorState {
+    pub fn new(genesis_timestamp: u64, base_fee_per_gas: u64) -> Self {
+        let genesis = SimulatedBlock {
+            number: 0,
+            hash: Self::block_hash(H256::zero(), 0, 0),
+            parent_hash: H256::zero(),
+            timestamp: genesis_timestamp,
+            base_fee_per_gas,
+            excess_blob_gas: 0,
+            tx_hashes: vec![],
+            hook_events: vec![],
+            logs: vec![],
+            nonces: HashMap::new(),
+            diamond_proxy: DiamondProxyStorage::default(),
+        };
+        Self {
+            blocks: vec![genesis],
+            pending_txs: vec![],
+            next_tx_seq: 0,
+            txs: HashMap::new(),
+            receipts: HashMap::new(),
+            balances: HashMap::new(),
+            pending_events: vec![],
+            next_priority_op_id: 0,
+            injected_reverts: vec![],
+            base_fee_per_gas,
+            excess_blob_gas: 0,
+            safe_lag: 0,
+            finalized_lag: 0,
+            reorg_count: 0,
+        }
+    }
+
+    fn block_hash(parent_hash: H256, number: u64, reorg_count: u64) -> H256 {
+        let mut preimage = parent_hash.as_bytes().to_vec();
+        preimage.extend_from_slice(&number.to_be_bytes());
+        preimage.extend_from_slice(&reorg_count.to_be_bytes());
+        H256(keccak256(&preimage))
+    }
+
+    pub fn head(&self) -> &SimulatedBlock {
+        self.blocks.last().unwrap()
+    }
+
+    pub fn head_mut(&mut self) -> &mut SimulatedBlock {
+        self.blocks.last_mut().unwrap()
+    }
+
+    pub fn safe_block_number(&self) -> u64 {
+        self.head().number.saturating_sub(self.safe_lag)
+    }
+
+    pub fn finalized_block_number(&self) -> u64 {
+        self.head().number.saturating_sub(self.finalized_lag)
+    }
+
+    pub fn nonce(&self, account: Address, block_number: u64) -> u64 {
+        let Some(block) = self.blocks.get(block_number as usize) else {
+            return 0;
+        };
+        block.nonces.get(&account).copied().unwrap_or(0)
+    }
+
+    /// Returns the nonce taking into account pending transactions that can be included after the latest block.
+    pub fn pending_nonce(&self, account: Address) -> u64 {
+        let mut nonce = self.nonce(account, self.head().number);
+        while self
+            .pending_txs
+            .iter()
+            .any(|pending| pending.tx.from == account && pending.tx.nonce == nonce)
+        {
+            nonce += 1;
+        }
+        nonce
+    }
+
+    pub fn balance(&self, account: Address) -> U256 {
+        let default_balance = U256::from(DEFAULT_BALANCE_ETH) * U256::exp10(18);
+        self.balances
+            .get(&account)
+            .copied()
+            .unwrap_or(default_balance)
+    }
+
+    pub fn pending_tx_count(&self) -> usize {
+        self.pending_txs.len()
+    }
+
+    pub fn add_pending_tx(&mut self, tx: SimulatedTx) -> Result<H256, String> {
+        let current_nonce = self.nonce(tx.from, self.head().number);
+        if tx.nonce < current_nonce {
+            return Err(format!(
+                "nonce too low: address {:?}, tx: {} state: {current_nonce}",
+                tx.from, tx.nonce
+            ));
+        }
+
+        let replaced = self
+            .pending_txs
+            .iter()
+            .position(|pending| pending.tx.from == tx.from && pending.tx.nonce == tx.nonce);
+        if let Some(replaced) = replaced {
+            let replaced_tx = &self.pending_txs[replaced].tx;
+            if replaced_tx.hash == tx.hash {
+                return Err("already known".to_owned());
+            }
+            let is_bumped = tx.max_fee_per_gas >= replaced_tx.max_fee_per_gas
+                && tx.max_priority_fee_per_gas >= replaced_tx.max_priority_fee_per_gas
+                && (tx.max_fee_per_gas > replaced_tx.max_fee_per_gas
+                    || tx.max_priority_fee_per_gas > replaced_tx.max_priority_fee_per_gas);
+            if !is_bumped {
+                return Err("replacement transaction underpriced".to_owned());
+            }
+            self.pending_txs.remove(replaced);
+        }
+
+        let hash = tx.hash;
+        self.txs.insert(hash, tx.clone());
+        self.pending_txs.push(PendingTx {
+            seq: self.next_tx_seq,
+            tx,
+        });
+        self.next_tx_seq += 1;
+        Ok(hash)
+    }
+
+    pub fn add_hook_event(&mut self, event: HookEvent) {
+        self.pending_events.push(event);
+    }
+
+    pub fn inject_revert(&mut self, selector: [u8; 4], count: usize) {
+        self.injected_reverts
+            .push(InjectedRevert { selector, count });
+    }
+
+    fn take_injected_revert(&mut self, input: &[u8]) -> Option<String> {
+        let selector = input.get(..4)?;
+        let revert = self
+            .injected_reverts
+            .iter_mut()
+            .find(|revert| revert.count > 0 && revert.selector == selector)?;
+        revert.count -= 1;
+        Some("injected revert".to_owned())
+    }
+
+    /// Mines a new block including all pending hook events and all pending transactions that can be included
+    /// given their nonces and fee caps. Transactions are included in the order they were sent.
+    pub fn mine_block(
+        &mut self,
+        contract: &ethabi::Contract,
+        diamond_proxy_address: Address,
+        timestamp: u64,
+    ) -> u64 {
+        let parent = self.head();
+        let number = parent.number + 1;
+        let parent_hash = parent.hash;
+        let hash = Self::block_hash(parent_hash, number, self.reorg_count);
+        let timestamp = timestamp.max(parent.timestamp + 1);
+        let mut nonces = parent.nonces.clone();
+        let mut diamond_proxy = parent.diamond_proxy.clone();
+        let base_fee_per_gas = self.base_fee_per_gas;
+        let blob_base_fee = blob_base_fee(self.excess_blob_gas);
+
+        let mut logs = vec![];
+        let new_log =
+            |address, topics, data: Vec<u8>, tx_hash, tx_index: u64, log_index: usize| Log {
+                address,
+                topics,
+                data: data.into(),
+                block_hash: Some(hash),
+                block_number: Some(number.into()),
+                transaction_hash: Some(tx_hash),
+                transaction_index: Some(tx_index.into()),
+                log_index: Some(log_index.into()),
+                transaction_log_index: None,
+                log_type: None,
+                removed: Some(false),
+            };
+
+        let hook_events = std::mem::take(&mut self.pending_events);
+        for (i, event) in hook_events.iter().enumerate() {
+            if event.is_priority_op {
+                diamond_proxy.total_priority_txs += 1;
+            }
+            // Hook events are not backed by transactions, so we use a synthetic transaction hash.
+            let tx_hash = H256(keccak256(&[hash.as_bytes(), &i.to_be_bytes()].concat()));
+            let log = new_log(
+                event.address,
+                event.topics.clone(),
+                event.data.clone(),
+                tx_hash,
+                0,
+                logs.len(),
+            );
+            logs.push(log);
+        }
+
+        let mut tx_hashes = vec![];
+        while let Some(tx) = self.take_next_tx(&nonces, base_fee_per_gas, blob_base_fee) {
+            *nonces.entry(tx.from).or_default() += 1;
+            let tx_index = tx_hashes.len() as u64;
+            let mut receipt = TxReceipt {
+                block_number: number,
+                block_hash: hash,
+                index: tx_index,
+                gas_used: (BASE_TX_GAS + 16 * tx.input.len() as u64)
+                    .min(tx.gas_limit.low_u64())
+                    .into(),
+                effective_gas_price: (tx.max_fee_per_gas - U256::from(base_fee_per_gas))
+                    .min(tx.max_priority_fee_per_gas)
+                    + U256::from(base_fee_per_gas),
+                revert_reason: None,
+                logs: vec![],
+            };
+
+            let result = if let Some(reason) = self.take_injected_revert(&tx.input) {
+                Err(reason)
+            } else if tx.to == diamond_proxy_address {
+                // Changes are applied to a copy of the storage so that they are discarded on revert.
+                let mut new_diamond_proxy = diamond_proxy.clone();
+                let result = new_diamond_proxy.execute(contract, &tx.input);
+                if result.is_ok() {
+                    diamond_proxy = new_diamond_proxy;
+                }
+                result
+            } else {
+                Ok(vec![])
+            };
+
+            match result {
+                Ok(events) => {
+                    for event in events {
+                        let Ok(event_abi) = contract.event(event.name) else {
+                            tracing::warn!("Event `{}` is missing in the contract ABI", event.name);
+                            continue;
+                        };
+                        let (topics, data) = encode_event(event_abi, event.params);
+                        let log = new_log(
+                            diamond_proxy_address,
+                            topics,
+                            data,
+                            tx.hash,
+                            tx_index,
+                            logs.len(),
+                        );
+                        receipt.logs.push(log.clone());
+                        logs.push(log);
+                    }
+                }
+                Err(reason) => {
+                    receipt.revert_reason = Some(reason);
+                }
+            }
+
+            let fee = receipt.gas_used * receipt.effective_gas_price;
+            let balance = self.balance(tx.from).saturating_sub(fee);
+            self.balances.insert(tx.from, balance);
+            self.receipts.insert(tx.hash, receipt);
+            tx_hashes.push(tx.hash);
+        }
+        // Drop transactions that can no longer be included.
+        self.pending_txs.retain(|pending| {
+            pending.tx.nonce >= nonces.get(&pending.tx.from).copied().unwrap_or(0)
+        });
+
+        self.blocks.push(SimulatedBlock {
+            number,
+            hash,
+            parent_hash,
+            timestamp,
+            base_fee_per_gas,
+            excess_blob_gas: self.excess_blob_gas,
+            tx_hashes,
+            hook_events,
+            logs,
+            nonces,
+            diamond_proxy,
+        });
+        number
+    }
+
+    fn take_next_tx(
+        &mut self,
+        nonces: &HashMap<Address, u64>,
+        base_fee_per_gas: u64,
+        blob_base_fee: U256,
+    ) -> Option<SimulatedTx> {
+        let (index, _) = self
+            .pending_txs
+            .iter()
+            .enumerate()
+            .filter(|(_, pending)| {
+                let nonce = nonces.get(&pending.tx.from).copied().unwrap_or(0);
+                pending.tx.nonce == nonce
+                    && pending.tx.is_includable(base_fee_per_gas, blob_base_fee)
+            })
+            .min_by_key(|(_, pending)| pending.seq)?;
+        Some(self.pending_txs.remove(index).tx)
+    }
+
+    /// Removes `depth` latest blocks. Transactions and hook events from the removed blocks return to the mempool,
+    /// so they will be included into newly mined blocks (which will have different hashes).
+    pub fn reorg(&mut self, depth: usize) {
+        assert!(
+            depth < self.blocks.len(),
+            "cannot reorg genesis block (depth: {depth}, head: {})",
+            self.head().number
+        );
+        let removed_blocks = self.blocks.split_off(self.blocks.len() - depth);
+        self.reorg_count += 1;
+
+        let mut hook_events = vec![];
+        for block in removed_blocks {
+            for tx_hash in &block.tx_hashes {
+                self.receipts.remove(tx_hash);
+                self.pending_txs.push(PendingTx {
+                    seq: self.next_tx_seq,
+                    tx: self.txs[tx_hash].clone(),
+                });
+                self.next_tx_seq += 1;
+            }
+            hook_events.extend(block.hook_events);
+        }
+        hook_events.append(&mut self.pending_events);
+        self.pending_events = hook_events;
+    }
+}
+
+/// Encodes event parameters (in the ABI order) into log topics and data.
+fn encode_event(event: &ethabi::Event, params: Vec<ethabi::Token>) -> (Vec<H256>, Vec<u8>) {
+    let mut topics = vec![event.signature()];
+    let mut data_tokens = vec![];
+    for (param, token) in event.inputs.iter().zip(params) {
+        if param.indexed {
+            topics.push(H256::from_slice(&ethabi::encode(&[token])));
+        } else {
+            data_tokens.push(token);
+        }
+    }
+    (topics, ethabi::encode(&data_tokens))
+}
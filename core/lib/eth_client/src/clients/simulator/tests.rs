@@ -0,0 +1,442 @@
+use zksync_contracts::multicall_contract;
+use zksync_types::{
+    l1::{L1TxCommonData, OpProcessingType, PriorityQueueType},
+    web3::types::FilterBuilder,
+    Execute,
+};
+
+use super::*;
+use crate::CallFunctionArgs;
+
+const DIAMOND_PROXY_ADDRESS: Address = Address::repeat_byte(0x22);
+
+/// Creates a token of the specified type with all values set to zero / empty.
+fn default_token(param_type: &ParamType) -> Token {
+    match param_type {
+        ParamType::Address => Token::Address(Address::zero()),
+        ParamType::Bytes => Token::Bytes(vec![]),
+        ParamType::Int(_) => Token::Int(U256::zero()),
+        ParamType::Uint(_) => Token::Uint(U256::zero()),
+        ParamType::Bool => Token::Bool(false),
+        ParamType::String => Token::String(String::new()),
+        ParamType::Array(_) => Token::Array(vec![]),
+        ParamType::FixedBytes(len) => Token::FixedBytes(vec![0; *len]),
+        ParamType::FixedArray(item_type, len) => {
+            Token::FixedArray(vec![default_token(item_type); *len])
+        }
+        ParamType::Tuple(item_types) => {
+            Token::Tuple(item_types.iter().map(default_token).collect())
+        }
+    }
+}
+
+/// Creates batch info of the specified type (`StoredBatchInfo` or `CommitBatchInfo`) for the specified batch.
+fn batch_info(param_type: &ParamType, number: u64) -> Token {
+    let Token::Tuple(mut fields) = default_token(param_type) else {
+        panic!("unexpected batch info type: {param_type:?}");
+    };
+    fields[0] = Token::Uint(number.into());
+    Token::Tuple(fields)
+}
+
+fn batch_calldata(contract: &ethabi::Contract, function_name: &str, batches: &[u64]) -> Vec<u8> {
+    let function = contract.function(function_name).unwrap();
+    let param_types: Vec<_> = function.inputs.iter().map(|param| &param.kind).collect();
+    let args = match function_name {
+        "commitBatches" | "proveBatches" => {
+            let ParamType::Array(item_type) = param_types[1] else {
+                panic!("unexpected `{function_name}` signature");
+            };
+            let mut args = vec![
+                batch_info(param_types[0], batches[0] - 1),
+                Token::Array(
+                    batches
+                        .iter()
+                        .map(|&number| batch_info(item_type, number))
+                        .collect(),
+                ),
+            ];
+            args.extend(param_types[2..].iter().map(|&ty| default_token(ty)));
+            args
+        }
+        "executeBatches" => {
+            let ParamType::Array(item_type) = param_types[0] else {
+                panic!("unexpected `{function_name}` signature");
+            };
+            let batches = batches.iter().map(|&number| batch_info(item_type, number));
+            vec![Token::Array(batches.collect())]
+        }
+        _ => unreachable!(),
+    };
+    function.encode_input(&args).unwrap()
+}
+
+async fn send_tx(client: &L1Simulator, calldata: Vec<u8>, options: Options) -> H256 {
+    let signed_tx = client
+        .sign_prepared_tx(calldata, options, "test")
+        .await
+        .unwrap();
+    client.send_raw_tx(signed_tx.raw_tx).await.unwrap()
+}
+
+async fn send_batch_tx(client: &L1Simulator, function_name: &str, batches: &[u64]) -> H256 {
+    let calldata = batch_calldata(client.contract(), function_name, batches);
+    send_tx(client, calldata, Options::default()).await
+}
+
+fn create_l1_tx() -> L1Tx {
+    L1Tx {
+        execute: Execute {
+            contract_address: Address::repeat_byte(0x11),
+            calldata: vec![1, 2, 3],
+            factory_deps: None,
+            value: U256::zero(),
+        },
+        common_data: L1TxCommonData {
+            serial_id: PriorityOpId(0),
+            sender: Address::repeat_byte(1),
+            deadline_block: 0,
+            eth_hash: H256::zero(),
+            eth_block: 0,
+            gas_limit: 1_000_000.into(),
+            max_fee_per_gas: 1_000.into(),
+            gas_per_pubdata_limit: 800.into(),
+            full_fee: U256::zero(),
+            layer_2_tip_fee: U256::zero(),
+            refund_recipient: Address::repeat_byte(2),
+            to_mint: U256::zero(),
+            priority_queue_type: PriorityQueueType::Deque,
+            op_processing_type: OpProcessingType::Common,
+            canonical_tx_hash: H256::zero(),
+        },
+        received_timestamp_ms: 0,
+    }
+}
+
+#[tokio::test]
+async fn mining_transactions() {
+    let client = L1Simulator::new(DIAMOND_PROXY_ADDRESS);
+    assert_eq!(client.block_number("test").await.unwrap(), 0.into());
+
+    let tx_hash = send_tx(&client, b"test".to_vec(), Options::default()).await;
+    assert_eq!(client.pending_nonce("test").await.unwrap(), 1.into());
+    assert_eq!(client.current_nonce("test").await.unwrap(), 0.into());
+    assert!(client
+        .get_tx_status(tx_hash, "test")
+        .await
+        .unwrap()
+        .is_none());
+    let tx = client.get_tx(tx_hash, "test").await.unwrap().unwrap();
+    assert_eq!(tx.from, Some(client.sender_account()));
+    assert_eq!(tx.input.0, b"test");
+    assert_eq!(tx.block_number, None);
+
+    assert_eq!(client.mine_block(), 1);
+    assert_eq!(client.pending_tx_count(), 0);
+    let tx_status = client
+        .get_tx_status(tx_hash, "test")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(tx_status.success);
+    assert_eq!(tx_status.receipt.block_number, Some(1.into()));
+    let nonce = client
+        .nonce_at(BlockNumber::Number(0.into()), "test")
+        .await
+        .unwrap();
+    assert_eq!(nonce, 0.into());
+    assert_eq!(client.current_nonce("test").await.unwrap(), 1.into());
+
+    let block = client
+        .block(BlockId::Number(BlockNumber::Latest), "test")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(block.hash, tx_status.receipt.block_hash);
+    assert_eq!(block.transactions, [tx_hash]);
+}
+
+#[tokio::test]
+async fn executing_batches() {
+    let client = L1Simulator::new(DIAMOND_PROXY_ADDRESS);
+    let commit_tx_hash = send_batch_tx(&client, "commitBatches", &[1, 2]).await;
+    client.mine_block();
+
+    let commit_status = client
+        .get_tx_status(commit_tx_hash, "test")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(commit_status.success);
+    let commit_event = client.contract().event("BlockCommit").unwrap();
+    let committed_batches: Vec<_> = commit_status
+        .receipt
+        .logs
+        .into_iter()
+        .map(|log| {
+            assert_eq!(log.address, DIAMOND_PROXY_ADDRESS);
+            let log = commit_event
+                .parse_log_whole(ethabi::RawLog {
+                    topics: log.topics,
+                    data: log.data.0,
+                })
+                .unwrap();
+            log.params[0].value.clone().into_uint().unwrap()
+        })
+        .collect();
+    assert_eq!(committed_batches, [1.into(), 2.into()]);
+
+    // Proving a batch that is not committed should fail.
+    let prove_tx_hash = send_batch_tx(&client, "proveBatches", &[1, 2, 3]).await;
+    client.mine_block();
+    let prove_status = client
+        .get_tx_status(prove_tx_hash, "test")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!prove_status.success);
+    let failure = client.failure_reason(prove_tx_hash).await.unwrap().unwrap();
+    assert!(failure.revert_reason.contains("#3"), "{failure:?}");
+
+    send_batch_tx(&client, "proveBatches", &[1, 2]).await;
+    send_batch_tx(&client, "executeBatches", &[1]).await;
+    client.mine_block();
+
+    let storage = client.diamond_proxy();
+    assert_eq!(storage.total_batches_committed, 2);
+    assert_eq!(storage.total_batches_verified, 2);
+    assert_eq!(storage.total_batches_executed, 1);
+
+    let execution_event = client.contract().event("BlockExecution").unwrap();
+    let filter = FilterBuilder::default()
+        .address(vec![DIAMOND_PROXY_ADDRESS])
+        .from_block(BlockNumber::Earliest)
+        .to_block(BlockNumber::Latest)
+        .topics(Some(vec![execution_event.signature()]), None, None, None)
+        .build();
+    let logs = client.logs(filter, "test").await.unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].block_number, Some(3.into()));
+}
+
+#[tokio::test]
+async fn querying_getters_via_multicall() {
+    let client = L1Simulator::new(DIAMOND_PROXY_ADDRESS);
+    client.update_diamond_proxy(|storage| {
+        storage.bootloader_bytecode_hash = H256::repeat_byte(1);
+        storage.verifier_params = [H256::repeat_byte(2); 3];
+        storage.protocol_version = 42.into();
+    });
+
+    let contract = client.contract();
+    let calls = [
+        "getL2BootloaderBytecodeHash",
+        "getVerifierParams",
+        "getProtocolVersion",
+    ];
+    let calls = calls.into_iter().map(|name| {
+        let calldata = contract.function(name).unwrap().encode_input(&[]).unwrap();
+        Token::Tuple(vec![
+            Token::Address(DIAMOND_PROXY_ADDRESS),
+            Token::Bool(false),
+            Token::Bytes(calldata),
+        ])
+    });
+    let call = CallFunctionArgs::new("aggregate3", Token::Array(calls.collect()))
+        .for_contract(client.multicall3_address(), multicall_contract());
+    let output = client.call_contract_function(call).await.unwrap();
+
+    let [Token::Array(results)] = output.as_slice() else {
+        panic!("unexpected output: {output:?}");
+    };
+    let results: Vec<_> = results
+        .iter()
+        .map(|result| {
+            let fields = result.clone().into_tuple().unwrap();
+            assert_eq!(fields[0], Token::Bool(true));
+            fields[1].clone().into_bytes().unwrap()
+        })
+        .collect();
+    assert_eq!(results[0], H256::repeat_byte(1).as_bytes());
+    assert_eq!(results[1], [2; 96]);
+    assert_eq!(U256::from_big_endian(&results[2]), 42.into());
+
+    let call = CallFunctionArgs::new("getProtocolVersion", ());
+    let output = client.call_main_contract_function(call).await.unwrap();
+    assert_eq!(output, [Token::Uint(42.into())]);
+}
+
+#[tokio::test]
+async fn emitting_priority_ops() {
+    let client = L1Simulator::new(DIAMOND_PROXY_ADDRESS);
+    assert_eq!(client.add_priority_op(&create_l1_tx()), PriorityOpId(0));
+    assert_eq!(client.add_priority_op(&create_l1_tx()), PriorityOpId(1));
+    client.mine_block();
+    assert_eq!(client.diamond_proxy().total_priority_txs, 2);
+
+    let event = client.contract().event("NewPriorityRequest").unwrap();
+    let filter = FilterBuilder::default()
+        .from_block(BlockNumber::Number(1.into()))
+        .to_block(BlockNumber::Finalized)
+        .topics(Some(vec![event.signature()]), None, None, None)
+        .build();
+    let logs = client.logs(filter, "test").await.unwrap();
+    let txs: Vec<_> = logs
+        .into_iter()
+        .map(|log| L1Tx::try_from(log).unwrap())
+        .collect();
+    assert_eq!(txs.len(), 2);
+    for (i, tx) in txs.iter().enumerate() {
+        assert_eq!(tx.serial_id(), PriorityOpId(i as u64));
+        assert_eq!(tx.eth_block().0, 1);
+        assert_eq!(tx.execute.calldata, [1, 2, 3]);
+        assert_eq!(tx.common_data.refund_recipient, Address::repeat_byte(2));
+    }
+}
+
+#[tokio::test]
+async fn injecting_reverts() {
+    let client = L1Simulator::new(DIAMOND_PROXY_ADDRESS);
+    client.revert_next("commitBatches", 1);
+
+    let tx_hash = send_batch_tx(&client, "commitBatches", &[1]).await;
+    client.mine_block();
+    let status = client
+        .get_tx_status(tx_hash, "test")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!status.success);
+    assert!(status.receipt.logs.is_empty());
+    assert_eq!(client.diamond_proxy().total_batches_committed, 0);
+    let failure = client.failure_reason(tx_hash).await.unwrap().unwrap();
+    assert_eq!(failure.revert_code, REVERT_ERROR_CODE);
+
+    // The revert is consumed, so the next transaction should succeed.
+    let tx_hash = send_batch_tx(&client, "commitBatches", &[1]).await;
+    client.mine_block();
+    let status = client
+        .get_tx_status(tx_hash, "test")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(status.success);
+    assert_eq!(client.diamond_proxy().total_batches_committed, 1);
+}
+
+#[tokio::test]
+async fn reorg_returns_transactions_to_mempool() {
+    let client = L1Simulator::new(DIAMOND_PROXY_ADDRESS);
+    client.mine_block();
+    let tx_hash = send_batch_tx(&client, "commitBatches", &[1]).await;
+    client.mine_block();
+    let status = client
+        .get_tx_status(tx_hash, "test")
+        .await
+        .unwrap()
+        .unwrap();
+    let old_block_hash = status.receipt.block_hash.unwrap();
+
+    client.reorg(1);
+    assert_eq!(client.block_number("test").await.unwrap(), 1.into());
+    assert!(client
+        .get_tx_status(tx_hash, "test")
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(client.diamond_proxy().total_batches_committed, 0);
+    assert_eq!(client.current_nonce("test").await.unwrap(), 0.into());
+    assert_eq!(client.pending_tx_count(), 1);
+
+    client.mine_block();
+    let status = client
+        .get_tx_status(tx_hash, "test")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(status.success);
+    assert_eq!(status.receipt.block_number, Some(2.into()));
+    assert_ne!(status.receipt.block_hash.unwrap(), old_block_hash);
+    assert_eq!(client.diamond_proxy().total_batches_committed, 1);
+}
+
+#[tokio::test]
+async fn fee_spike_delays_transactions() {
+    let client = L1Simulator::new(DIAMOND_PROXY_ADDRESS);
+    let options = Options {
+        max_fee_per_gas: Some(5_000_000_000_u64.into()),
+        ..Options::default()
+    };
+    client.set_base_fee_per_gas(10_000_000_000);
+    let tx_hash = send_tx(&client, vec![], options.clone()).await;
+    client.mine_block();
+    assert!(client
+        .get_tx_status(tx_hash, "test")
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(client.pending_tx_count(), 1);
+    let fee_history = client.base_fee_history(1, 2, "test").await.unwrap();
+    assert_eq!(fee_history, [DEFAULT_BASE_FEE_PER_GAS, 10_000_000_000]);
+
+    // Replacement transactions must have higher fees.
+    let replacement_options = Options {
+        nonce: Some(0.into()),
+        ..options
+    };
+    let underpriced_tx = client
+        .sign_prepared_tx(vec![1], replacement_options, "test")
+        .await
+        .unwrap();
+    client.send_raw_tx(underpriced_tx.raw_tx).await.unwrap_err();
+
+    client.set_base_fee_per_gas(DEFAULT_BASE_FEE_PER_GAS);
+    client.mine_block();
+    let status = client
+        .get_tx_status(tx_hash, "test")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(status.success);
+}
+
+#[tokio::test]
+async fn finalized_block_lags_behind_latest() {
+    let client = L1Simulator::new(DIAMOND_PROXY_ADDRESS);
+    client.set_finality_lag(2, 5);
+    client.mine_blocks(10);
+
+    let block_number = |block| {
+        let client = client.clone();
+        async move {
+            client
+                .block(BlockId::Number(block), "test")
+                .await
+                .unwrap()
+                .unwrap()
+                .number
+                .unwrap()
+        }
+    };
+    assert_eq!(block_number(BlockNumber::Latest).await, 10.into());
+    assert_eq!(block_number(BlockNumber::Safe).await, 8.into());
+    assert_eq!(block_number(BlockNumber::Finalized).await, 5.into());
+}
+
+#[tokio::test]
+async fn erc20_allowance_is_not_supported() {
+    let client = L1Simulator::new(DIAMOND_PROXY_ADDRESS);
+    // The ABI is irrelevant since the simulator doesn't model ERC-20 tokens.
+    let err = client
+        .allowance_on_account(
+            Address::repeat_byte(1),
+            DIAMOND_PROXY_ADDRESS,
+            zksync_contract(),
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::Contract(contract::Error::InterfaceUnsupported)),
+        "{err:?}"
+    );
+}
@@ -15,7 +15,7 @@ use zksync_types::{
 };
 
 /// Wrapper for `Vec<ethabi::Token>` that doesn't wrap them in an additional array in `Tokenize` implementation.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct RawTokens(pub Vec<ethabi::Token>);
 
 impl Tokenize for RawTokens {
@@ -31,7 +31,7 @@ impl Detokenize for RawTokens {
 }
 
 /// Arguments for calling a function in an unspecified Ethereum smart contract.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CallFunctionArgs {
     pub(crate) name: String,
     pub(crate) from: Option<Address>,
@@ -76,7 +76,7 @@ impl CallFunctionArgs {
 
 /// Information sufficient for calling a function in a specific Ethereum smart contract. Instantiated
 /// using [`CallFunctionArgs::for_contract()`].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ContractCall {
     pub(crate) contract_address: Address,
     pub(crate) contract_abi: ethabi::Contract,
@@ -121,6 +121,12 @@ pub enum Error {
     /// EIP4844 transaction lacks `blob_versioned_hashes` field
     #[error("EIP4844 transaction lacks blob_versioned_hashes field")]
     Eip4844MissingBlobVersionedHashes,
+    /// Responses from L1 providers didn't reach the required quorum.
+    #[error("Quorum of {required} L1 providers not reached; at most {agreed} providers agreed")]
+    QuorumNotReached { required: usize, agreed: usize },
+    /// Read quorum in the client config is invalid for the configured number of L1 providers.
+    #[error("Invalid read quorum {quorum}: must be in 1..={providers}")]
+    InvalidQuorum { quorum: usize, providers: usize },
 }
 
 /// Raw transaction bytes.
@@ -230,7 +236,7 @@ pub fn encode_blob_tx_with_sidecar(raw_tx: &[u8], sidecar: &EthTxBlobSidecar) ->
 }
 
 /// State of the executed Ethereum transaction.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ExecutedTxStatus {
     /// The hash of the executed L1 transaction.
     pub tx_hash: H256,
@@ -1,8 +1,9 @@
-use std::convert::TryInto;
+use std::{convert::TryInto, fmt};
 
 pub use kzg::KzgSettings;
 use kzg::{
-    compute_commitment, compute_proof, compute_proof_poly,
+    boojum::pairing::{bls12_381::G1Compressed, EncodedPoint},
+    compute_commitment, compute_proof, compute_proof_poly, verify_proof_poly,
     zkevm_circuits::{
         boojum::pairing::{
             bls12_381::{Fr, FrRepr, G1Affine},
@@ -10,7 +11,7 @@ use kzg::{
             CurveAffine,
         },
         eip_4844::{
-            bitreverse, fft,
+            bitreverse, ethereum_4844_data_into_zksync_pubdata, fft,
             input::{BLOB_CHUNK_SIZE, ELEMENTS_PER_4844_BLOCK},
             zksync_pubdata_into_ethereum_4844_data, zksync_pubdata_into_monomial_form_poly,
         },
@@ -69,6 +70,117 @@ fn commitment_to_versioned_hash(kzg_commitment: G1Affine) -> [u8; 32] {
     versioned_hash
 }
 
+/// Errors that can occur when verifying a blob against its `kzg` commitment and versioned hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobVerificationError {
+    /// One of the sidecar fields has an unexpected length.
+    InvalidLength {
+        field: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// Commitment or proof is not a valid compressed G1 point.
+    InvalidPoint(&'static str),
+    /// Versioned hash doesn't correspond to the `kzg` commitment.
+    VersionedHashMismatch,
+    /// Blob proof doesn't prove that the blob corresponds to the `kzg` commitment.
+    InvalidBlobProof,
+}
+
+impl fmt::Display for BlobVerificationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength {
+                field,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "{field} has invalid length: expected {expected} bytes, got {actual}"
+            ),
+            Self::InvalidPoint(field) => write!(formatter, "{field} is not a valid G1 point"),
+            Self::VersionedHashMismatch => {
+                formatter.write_str("versioned hash doesn't match the kzg commitment")
+            }
+            Self::InvalidBlobProof => {
+                formatter.write_str("blob proof is invalid for the kzg commitment")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BlobVerificationError {}
+
+fn check_length(
+    field: &'static str,
+    data: &[u8],
+    expected: usize,
+) -> Result<(), BlobVerificationError> {
+    if data.len() == expected {
+        Ok(())
+    } else {
+        Err(BlobVerificationError::InvalidLength {
+            field,
+            expected,
+            actual: data.len(),
+        })
+    }
+}
+
+fn bytes_to_g1(field: &'static str, data: &[u8]) -> Result<G1Affine, BlobVerificationError> {
+    let mut compressed = G1Compressed::empty();
+    compressed.as_mut().copy_from_slice(data);
+    compressed
+        .into_affine()
+        .map_err(|_| BlobVerificationError::InvalidPoint(field))
+}
+
+/// Verifies a 4844 blob as it is sent in a transaction sidecar, i.e. checks that:
+///
+/// - `versioned_hash` is derived from `kzg_commitment`
+/// - `blob_proof` proves that `kzg_commitment` commits to `blob`.
+///
+/// This doesn't rely on any L1 data, so it can be used to check blobs long after they were pruned by beacon nodes.
+pub fn verify_blob(
+    blob: &[u8],
+    kzg_commitment: &[u8],
+    blob_proof: &[u8],
+    versioned_hash: &[u8],
+) -> Result<(), BlobVerificationError> {
+    check_length("blob", blob, EIP_4844_BYTES_PER_BLOB)?;
+    check_length("kzg_commitment", kzg_commitment, 48)?;
+    check_length("blob_proof", blob_proof, 48)?;
+    check_length("versioned_hash", versioned_hash, 32)?;
+
+    let commitment = bytes_to_g1("kzg_commitment", kzg_commitment)?;
+    if commitment_to_versioned_hash(commitment) != versioned_hash {
+        return Err(BlobVerificationError::VersionedHashMismatch);
+    }
+
+    let mut blob_bytes = [0u8; EIP_4844_BYTES_PER_BLOB];
+    blob_bytes.copy_from_slice(blob);
+    let zksync_blob = ethereum_4844_data_into_zksync_pubdata(&blob_bytes);
+    let mut poly = zksync_pubdata_into_monomial_form_poly(&zksync_blob);
+    fft(&mut poly);
+    bitreverse(&mut poly);
+
+    let proof = bytes_to_g1("blob_proof", blob_proof)?;
+    if verify_proof_poly(&KZG_SETTINGS, &poly, &commitment, &proof) {
+        Ok(())
+    } else {
+        Err(BlobVerificationError::InvalidBlobProof)
+    }
+}
+
+/// Converts a blob in the EIP-4844 format (e.g., taken from a sidecar) back to zkSync pubdata padded
+/// to [`ZK_SYNC_BYTES_PER_BLOB`] bytes. This is the inverse of the conversion performed in [`KzgInfo::new()`].
+pub fn blob_to_zksync_pubdata(blob: &[u8]) -> Result<Vec<u8>, BlobVerificationError> {
+    check_length("blob", blob, EIP_4844_BYTES_PER_BLOB)?;
+    let mut blob_bytes = [0u8; EIP_4844_BYTES_PER_BLOB];
+    blob_bytes.copy_from_slice(blob);
+    Ok(ethereum_4844_data_into_zksync_pubdata(&blob_bytes).to_vec())
+}
+
 /// Calculate the opening point for a given `linear_hash` and `versioned_hash`. We calculate
 /// this point by hashing together the linear hash and versioned hash and only taking the last 16 bytes
 fn compute_opening_point(linear_hash: [u8; 32], versioned_hash: [u8; 32]) -> u128 {
@@ -133,3 +133,76 @@ fn bytes_test() {
     let decoded_kzg_info = KzgInfo::from_slice(&encoded_info);
     assert_eq!(kzg_info, decoded_kzg_info);
 }
+
+#[test]
+fn verifying_blob() {
+    let kzg_test: KzgTest = serde_json::from_str(KZG_TEST_JSON).unwrap();
+    let kzg_info = KzgInfo::new(&kzg_test.pubdata);
+
+    verify_blob(
+        &kzg_info.blob,
+        &kzg_info.kzg_commitment,
+        &kzg_info.blob_proof,
+        &kzg_info.versioned_hash,
+    )
+    .unwrap();
+
+    let mut invalid_versioned_hash = kzg_info.versioned_hash;
+    invalid_versioned_hash[31] ^= 1;
+    let err = verify_blob(
+        &kzg_info.blob,
+        &kzg_info.kzg_commitment,
+        &kzg_info.blob_proof,
+        &invalid_versioned_hash,
+    )
+    .unwrap_err();
+    assert_eq!(err, BlobVerificationError::VersionedHashMismatch);
+
+    // Change a byte in the middle of the first field element; the element stays canonical.
+    let mut invalid_blob = kzg_info.blob;
+    invalid_blob[16] ^= 1;
+    let err = verify_blob(
+        &invalid_blob,
+        &kzg_info.kzg_commitment,
+        &kzg_info.blob_proof,
+        &kzg_info.versioned_hash,
+    )
+    .unwrap_err();
+    assert_eq!(err, BlobVerificationError::InvalidBlobProof);
+
+    let err = verify_blob(
+        &kzg_info.blob[1..],
+        &kzg_info.kzg_commitment,
+        &kzg_info.blob_proof,
+        &kzg_info.versioned_hash,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            BlobVerificationError::InvalidLength { field: "blob", .. }
+        ),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn converting_blob_to_zksync_pubdata() {
+    let kzg_test: KzgTest = serde_json::from_str(KZG_TEST_JSON).unwrap();
+    let kzg_info = KzgInfo::new(&kzg_test.pubdata);
+
+    let pubdata = blob_to_zksync_pubdata(&kzg_info.blob).unwrap();
+    assert_eq!(pubdata.len(), ZK_SYNC_BYTES_PER_BLOB);
+    let (pubdata, padding) = pubdata.split_at(kzg_test.pubdata.len());
+    assert_eq!(pubdata, kzg_test.pubdata);
+    assert!(padding.iter().all(|&byte| byte == 0));
+
+    let err = blob_to_zksync_pubdata(&kzg_info.blob[1..]).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            BlobVerificationError::InvalidLength { field: "blob", .. }
+        ),
+        "{err:?}"
+    );
+}
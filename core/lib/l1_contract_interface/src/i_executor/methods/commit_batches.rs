@@ -11,6 +11,9 @@ pub struct CommitBatches {
     pub last_committed_l1_batch: L1BatchWithMetadata,
     pub l1_batches: Vec<L1BatchWithMetadata>,
     pub pubdata_da: PubdataDA,
+    /// Inclusion data returned by the external DA layer for each of `l1_batches` (in the same order).
+    /// Only used with [`PubdataDA::Custom`]; empty otherwise.
+    pub da_inclusion_data: Vec<Vec<u8>>,
 }
 
 impl Tokenize for CommitBatches {
@@ -19,7 +22,15 @@ impl Tokenize for CommitBatches {
         let l1_batches_to_commit = self
             .l1_batches
             .iter()
-            .map(|batch| CommitBatchInfo::new(batch, self.pubdata_da).into_token())
+            .enumerate()
+            .map(|(i, batch)| {
+                let info = CommitBatchInfo::new(batch, self.pubdata_da);
+                match self.da_inclusion_data.get(i) {
+                    Some(inclusion_data) => info.with_da_inclusion_data(inclusion_data),
+                    None => info,
+                }
+                .into_token()
+            })
             .collect();
 
         vec![stored_batch_info, Token::Array(l1_batches_to_commit)]
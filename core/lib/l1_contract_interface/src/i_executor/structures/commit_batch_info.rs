@@ -17,12 +17,15 @@ use crate::{
 /// These are used by the L1 Contracts to indicate what DA layer is used for pubdata
 const PUBDATA_SOURCE_CALLDATA: u8 = 0;
 const PUBDATA_SOURCE_BLOBS: u8 = 1;
+const PUBDATA_SOURCE_CUSTOM: u8 = 2;
 
 /// Encoding for `CommitBatchInfo` from `IExecutor.sol`
 #[derive(Debug)]
 pub struct CommitBatchInfo<'a> {
     pub l1_batch_with_metadata: &'a L1BatchWithMetadata,
     pub pubdata_da: PubdataDA,
+    /// Inclusion data returned by the external DA layer. Only used with [`PubdataDA::Custom`].
+    pub da_inclusion_data: Option<&'a [u8]>,
 }
 
 impl<'a> CommitBatchInfo<'a> {
@@ -30,9 +33,16 @@ impl<'a> CommitBatchInfo<'a> {
         Self {
             l1_batch_with_metadata,
             pubdata_da,
+            da_inclusion_data: None,
         }
     }
 
+    /// Sets inclusion data returned by the external DA layer for the batch.
+    pub fn with_da_inclusion_data(mut self, da_inclusion_data: &'a [u8]) -> Self {
+        self.da_inclusion_data = Some(da_inclusion_data);
+        self
+    }
+
     fn base_tokens(&self) -> Vec<Token> {
         if self
             .l1_batch_with_metadata
@@ -205,13 +215,30 @@ impl CommitBatchInfo<'static> {
         match last_reference_token.first() {
             Some(&byte) if byte == PUBDATA_SOURCE_CALLDATA => Ok(PubdataDA::Calldata),
             Some(&byte) if byte == PUBDATA_SOURCE_BLOBS => Ok(PubdataDA::Blobs),
+            Some(&byte) if byte == PUBDATA_SOURCE_CUSTOM => Ok(PubdataDA::Custom),
             Some(&byte) => Err(parse_error(format!(
-                "unexpected first byte of the last reference token; expected one of [{PUBDATA_SOURCE_CALLDATA}, {PUBDATA_SOURCE_BLOBS}], \
+                "unexpected first byte of the last reference token; expected one of \
+                 [{PUBDATA_SOURCE_CALLDATA}, {PUBDATA_SOURCE_BLOBS}, {PUBDATA_SOURCE_CUSTOM}], \
                  got {byte}"
             ))),
             None => Err(parse_error("last reference token is empty")),
         }
     }
+
+    /// Extracts DA inclusion data from the `reference` commitment that uses [`PubdataDA::Custom`].
+    /// Returns `None` if the commitment uses another DA source. Since inclusion data is produced by the external DA layer,
+    /// it cannot be reproduced locally, so this is used to check the remaining parts of the commitment.
+    pub fn extract_da_inclusion_data(reference: &Token) -> Option<&[u8]> {
+        let Token::Tuple(reference) = reference else {
+            return None;
+        };
+        match reference.last()? {
+            Token::Bytes(bytes) if bytes.first() == Some(&PUBDATA_SOURCE_CUSTOM) => {
+                Some(&bytes[1..])
+            }
+            _ => None,
+        }
+    }
 }
 
 impl<'a> Tokenizable for CommitBatchInfo<'a> {
@@ -272,6 +299,15 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
 
                     tokens.push(Token::Bytes(result));
                 }
+                PubdataDA::Custom => {
+                    // Pubdata itself is published to the external DA layer; L1 only receives the inclusion data.
+                    let inclusion_data = self.da_inclusion_data.unwrap_or_default();
+                    let result = std::iter::once(PUBDATA_SOURCE_CUSTOM)
+                        .chain(inclusion_data.iter().copied())
+                        .collect();
+
+                    tokens.push(Token::Bytes(result));
+                }
             }
         }
 
@@ -4,6 +4,9 @@ mod tests;
 mod types;
 
 pub use crate::{
-    mempool_store::{MempoolInfo, MempoolStats, MempoolStore},
+    mempool_store::{
+        AccountContent, EvictedTransaction, EvictionPolicy, EvictionReason, MempoolInfo,
+        MempoolLimits, MempoolStats, MempoolStore,
+    },
     types::L2TxFilter,
 };
@@ -1,15 +1,93 @@
-use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
 };
 
-use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
+use crate::types::{AccountTransactions, L2TxFilter, MempoolScore, TransactionsSize};
+
+/// Policy used to free space once the mempool reaches its global limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Purge accounts that only have transactions queued behind a nonce gap.
+    #[default]
+    PurgeQueued,
+    /// Purge queued accounts, and then evict transactions with the highest nonces
+    /// from the lowest-priority accounts until the mempool fits into its limits.
+    LowestPriority,
+}
+
+/// Limits for L2 transactions in the mempool applied in addition to its capacity.
+#[derive(Debug, Clone, Default)]
+pub struct MempoolLimits {
+    /// Maximum total size of L2 transactions in bytes.
+    pub max_size_bytes: Option<u64>,
+    /// Maximum number of L2 transactions per account.
+    pub max_txs_per_account: Option<usize>,
+    /// Maximum total size of L2 transactions per account in bytes.
+    pub max_bytes_per_account: Option<u64>,
+    pub eviction_policy: EvictionPolicy,
+}
+
+impl MempoolLimits {
+    fn account_overflow(&self, transactions: &AccountTransactions) -> Option<EvictionReason> {
+        if matches!(self.max_txs_per_account, Some(max) if transactions.len() > max) {
+            Some(EvictionReason::AccountTxCountLimit)
+        } else if matches!(self.max_bytes_per_account, Some(max) if transactions.size_bytes() > max)
+        {
+            Some(EvictionReason::AccountSizeLimit)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reason for evicting an L2 transaction from the mempool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionReason {
+    AccountTxCountLimit,
+    AccountSizeLimit,
+    MempoolFull,
+}
+
+impl fmt::Display for EvictionReason {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::AccountTxCountLimit => "account transaction count limit exceeded",
+            Self::AccountSizeLimit => "account transaction size limit exceeded",
+            Self::MempoolFull => "mempool is full",
+        })
+    }
+}
+
+/// L2 transaction evicted from the mempool because of its limits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvictedTransaction {
+    pub hash: H256,
+    pub initiator: Address,
+    pub nonce: Nonce,
+    pub reason: EvictionReason,
+}
+
+impl EvictedTransaction {
+    fn new(transaction: &L2Tx, reason: EvictionReason) -> Self {
+        Self {
+            hash: transaction.hash(),
+            initiator: transaction.initiator_account(),
+            nonce: transaction.nonce(),
+            reason,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct MempoolInfo {
     pub stashed_accounts: Vec<Address>,
     pub purged_accounts: Vec<Address>,
+    pub evicted_transactions: Vec<EvictedTransaction>,
 }
 
 #[derive(Debug)]
@@ -17,6 +95,30 @@ pub struct MempoolStats {
     pub l1_transaction_count: usize,
     pub l2_transaction_count: u64,
     pub l2_priority_queue_size: usize,
+    /// Number of L2 transactions that are not blocked by a nonce gap.
+    pub l2_pending_transaction_count: u64,
+    /// Number of L2 transactions queued behind a nonce gap.
+    pub l2_queued_transaction_count: u64,
+    /// Total size of L2 transactions in bytes.
+    pub l2_size_bytes: u64,
+}
+
+/// Snapshot of L2 transactions of a single account in the mempool.
+#[derive(Debug, Clone)]
+pub struct AccountContent {
+    /// Nonce of the next transaction to be included in block.
+    pub next_nonce: Nonce,
+    /// Transactions that can be included once all preceding ones are included, ordered by nonce.
+    pub pending: Vec<L2Tx>,
+    /// Transactions queued behind a nonce gap, ordered by nonce.
+    pub queued: Vec<L2Tx>,
+}
+
+impl AccountContent {
+    /// Returns the nonce of the missing transaction that blocks queued transactions.
+    pub fn missing_nonce(&self) -> Nonce {
+        self.next_nonce + self.pending.len() as u32
+    }
 }
 
 #[derive(Debug)]
@@ -30,9 +132,11 @@ pub struct MempoolStore {
     /// Next priority operation
     next_priority_id: PriorityOpId,
     stashed_accounts: Vec<Address>,
-    /// Number of L2 transactions in the mempool.
-    size: u64,
+    evicted_transactions: Vec<EvictedTransaction>,
+    /// Number and size of L2 transactions in the mempool.
+    size: TransactionsSize,
     capacity: u64,
+    limits: MempoolLimits,
 }
 
 impl MempoolStore {
@@ -43,11 +147,19 @@ impl MempoolStore {
             l2_priority_queue: BTreeSet::new(),
             next_priority_id,
             stashed_accounts: vec![],
-            size: 0,
+            evicted_transactions: vec![],
+            size: TransactionsSize::default(),
             capacity,
+            limits: MempoolLimits::default(),
         }
     }
 
+    /// Sets limits for L2 transactions in addition to the mempool capacity.
+    pub fn with_limits(mut self, limits: MempoolLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Inserts batch of new transactions to mempool
     /// `initial_nonces` provides current committed nonce information to mempool
     /// variable is used only if account is not present in mempool yet and we have to bootstrap it
@@ -102,24 +214,40 @@ impl MempoolStore {
     ) {
         let account = transaction.initiator_account();
 
-        let metadata = match self.l2_transactions_per_account.entry(account) {
-            hash_map::Entry::Occupied(mut txs) => txs.get_mut().insert(transaction),
-            hash_map::Entry::Vacant(entry) => {
+        let txs = self
+            .l2_transactions_per_account
+            .entry(account)
+            .or_insert_with(|| {
                 let account_nonce = initial_nonces.get(&account).cloned().unwrap_or(Nonce(0));
-                entry
-                    .insert(AccountTransactions::new(account_nonce))
-                    .insert(transaction)
-            }
-        };
+                AccountTransactions::new(account_nonce)
+            });
+        let size_before = txs.size();
+        let metadata = txs.insert(transaction);
         if let Some(score) = metadata.previous_score {
             self.l2_priority_queue.remove(&score);
         }
         if let Some(score) = metadata.new_score {
             self.l2_priority_queue.insert(score);
         }
-        if metadata.is_new {
-            self.size += 1;
+
+        // Enforce per-account limits by evicting transactions with the highest nonces,
+        // which cannot be included before the remaining ones anyway.
+        while let Some(reason) = self.limits.account_overflow(txs) {
+            let (evicted, score) = txs
+                .evict_last()
+                .expect("mempool: account limits exceeded with no transactions");
+            if let Some(score) = score {
+                self.l2_priority_queue.remove(&score);
+            }
+            tracing::debug!(
+                "evicting L2 transaction {:?} from mempool: {reason}",
+                evicted.hash()
+            );
+            self.evicted_transactions
+                .push(EvictedTransaction::new(&evicted, reason));
         }
+        self.size.sub(size_before);
+        self.size.add(txs.size());
     }
 
     /// Returns `true` if there is a transaction in the mempool satisfying the filter.
@@ -139,7 +267,6 @@ impl MempoolStore {
             return Some(transaction.into());
         }
 
-        let mut removed = 0;
         // We want to fetch the next transaction that would match the fee requirements.
         let tx_pointer = self
             .l2_priority_queue
@@ -154,31 +281,47 @@ impl MempoolStore {
             .into_iter()
             .skip(1)
         {
-            removed += self
+            let removed = self
                 .l2_transactions_per_account
                 .remove(&stashed_pointer.account)
-                .expect("mempool: dangling pointer in priority queue")
-                .len();
+                .expect("mempool: dangling pointer in priority queue");
+            self.size.sub(removed.size());
 
             self.stashed_accounts.push(stashed_pointer.account);
         }
         // insert pointer to the next transaction if it exists
-        let (transaction, score) = self
+        let txs = self
             .l2_transactions_per_account
             .get_mut(&tx_pointer.account)
-            .expect("mempool: dangling pointer in priority queue")
-            .next();
+            .expect("mempool: dangling pointer in priority queue");
+        let size_before = txs.size();
+        let (transaction, score) = txs.next();
+        self.size.sub(size_before);
+        self.size.add(txs.size());
 
         if let Some(score) = score {
             self.l2_priority_queue.insert(score);
         }
-        self.size = self
-            .size
-            .checked_sub((removed + 1) as u64)
-            .expect("mempool size can't be negative");
         Some(transaction.into())
     }
 
+    /// Returns up to `limit` L2 transactions that are next in line to be returned by [`Self::next_transaction()`]
+    /// with the same `filter`, without modifying the mempool. At most one transaction per account is returned.
+    pub fn peek_l2_transactions(&self, filter: &L2TxFilter, limit: usize) -> Vec<Transaction> {
+        self.l2_priority_queue
+            .iter()
+            .rev()
+            .filter(|pointer| pointer.matches_filter(filter))
+            .filter_map(|pointer| {
+                self.l2_transactions_per_account
+                    .get(&pointer.account)?
+                    .peek()
+            })
+            .take(limit)
+            .map(|tx| tx.clone().into())
+            .collect()
+    }
+
     /// When a state_keeper starts the block over after a rejected transaction,
     /// we have to rollback the nonces/ids in the mempool and
     /// reinsert the transactions from the block back into mempool.
@@ -190,14 +333,16 @@ impl MempoolStore {
                 self.next_priority_id = self.next_priority_id.min(data.serial_id);
             }
             ExecuteTransactionCommon::L2(_) => {
-                if let Some(score) = self
+                let txs = self
                     .l2_transactions_per_account
                     .get_mut(&tx.initiator_account())
-                    .expect("account is not available in mempool")
-                    .reset(tx)
-                {
+                    .expect("account is not available in mempool");
+                let size_before = txs.size();
+                if let Some(score) = txs.reset(tx) {
                     self.l2_priority_queue.remove(&score);
                 }
+                self.size.sub(size_before);
+                self.size.add(txs.size());
             }
             ExecuteTransactionCommon::ProtocolUpgrade(_) => {
                 panic!("Protocol upgrade tx is not supposed to be in mempool");
@@ -206,22 +351,55 @@ impl MempoolStore {
     }
 
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
+        let purged_accounts = self.gc();
         MempoolInfo {
             stashed_accounts: std::mem::take(&mut self.stashed_accounts),
-            purged_accounts: self.gc(),
+            purged_accounts,
+            evicted_transactions: std::mem::take(&mut self.evicted_transactions),
         }
     }
 
     pub fn stats(&self) -> MempoolStats {
         MempoolStats {
             l1_transaction_count: self.l1_transactions.len(),
-            l2_transaction_count: self.size,
+            l2_transaction_count: self.size.count,
             l2_priority_queue_size: self.l2_priority_queue.len(),
+            l2_pending_transaction_count: self.size.pending,
+            l2_queued_transaction_count: self.size.count - self.size.pending,
+            l2_size_bytes: self.size.bytes,
         }
     }
 
+    /// Returns a snapshot of L2 transactions in the mempool grouped by initiator account.
+    /// Unlike [`Self::get_mempool_info()`], this method doesn't modify the mempool.
+    pub fn l2_content(&self) -> HashMap<Address, AccountContent> {
+        self.l2_transactions_per_account
+            .iter()
+            .filter(|(_, txs)| !txs.is_empty())
+            .map(|(&account, txs)| {
+                let (pending, queued) = txs.split_pending();
+                let content = AccountContent {
+                    next_nonce: txs.nonce(),
+                    pending: pending.into_iter().cloned().collect(),
+                    queued: queued.into_iter().cloned().collect(),
+                };
+                (account, content)
+            })
+            .collect()
+    }
+
+    fn is_full(&self) -> bool {
+        self.size.count >= self.capacity
+            || matches!(self.limits.max_size_bytes, Some(max) if self.size.bytes >= max)
+    }
+
+    fn exceeds_limits(&self) -> bool {
+        self.size.count > self.capacity
+            || matches!(self.limits.max_size_bytes, Some(max) if self.size.bytes > max)
+    }
+
     fn gc(&mut self) -> Vec<Address> {
-        if self.size >= self.capacity {
+        if self.is_full() {
             let index: HashSet<_> = self
                 .l2_priority_queue
                 .iter()
@@ -232,12 +410,42 @@ impl MempoolStore {
                 .into_iter()
                 .partition(|(address, _)| index.contains(address));
             self.l2_transactions_per_account = kept;
-            self.size = self
-                .l2_transactions_per_account
-                .iter()
-                .fold(0, |agg, (_, tnxs)| agg + tnxs.len() as u64);
+            self.size = TransactionsSize::default();
+            for txs in self.l2_transactions_per_account.values() {
+                self.size.add(txs.size());
+            }
+            if self.limits.eviction_policy == EvictionPolicy::LowestPriority {
+                self.evict_lowest_priority();
+            }
             return drained.into_keys().collect();
         }
         vec![]
     }
+
+    /// Evicts transactions with the highest nonces from the lowest-priority accounts
+    /// until the mempool fits into its limits.
+    fn evict_lowest_priority(&mut self) {
+        while self.exceeds_limits() {
+            let Some(pointer) = self.l2_priority_queue.first().cloned() else {
+                break;
+            };
+            let txs = self
+                .l2_transactions_per_account
+                .get_mut(&pointer.account)
+                .expect("mempool: dangling pointer in priority queue");
+            let size_before = txs.size();
+            let (evicted, score) = txs
+                .evict_last()
+                .expect("mempool: account in priority queue has no transactions");
+            if let Some(score) = score {
+                self.l2_priority_queue.remove(&score);
+            }
+            self.size.sub(size_before);
+            self.size.add(txs.size());
+            self.evicted_transactions.push(EvictedTransaction::new(
+                &evicted,
+                EvictionReason::MempoolFull,
+            ));
+        }
+    }
 }
@@ -8,11 +8,14 @@ use zksync_types::{
     helpers::unix_timestamp_ms,
     l1::{OpProcessingType, PriorityQueueType},
     l2::L2Tx,
-    Address, Execute, ExecuteTransactionCommon, L1TxCommonData, Nonce, PriorityOpId, Transaction,
-    H256, U256,
+    Address, Bytes, Execute, ExecuteTransactionCommon, L1TxCommonData, Nonce, PriorityOpId,
+    Transaction, H256, U256,
 };
 
-use crate::{mempool_store::MempoolStore, types::L2TxFilter};
+use crate::{
+    mempool_store::{EvictionPolicy, EvictionReason, MempoolLimits, MempoolStore},
+    types::L2TxFilter,
+};
 
 #[test]
 fn basic_flow() {
@@ -365,6 +368,177 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn peeking_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let now = unix_timestamp_ms();
+    mempool.insert(
+        vec![
+            gen_l2_tx_with_timestamp(account0, Nonce(0), now),
+            gen_l2_tx_with_timestamp(account0, Nonce(1), now + 1),
+            gen_l2_tx_with_timestamp(account1, Nonce(0), now + 2),
+        ],
+        HashMap::new(),
+    );
+
+    let filter = L2TxFilter::default();
+    let peeked: Vec<_> = mempool
+        .peek_l2_transactions(&filter, 10)
+        .into_iter()
+        .map(|tx| view(Some(tx)))
+        .collect();
+    assert_eq!(peeked, [(account0, 0), (account1, 0)]);
+    let peeked = mempool.peek_l2_transactions(&filter, 1);
+    assert_eq!(view(peeked.into_iter().next()), (account0, 0));
+
+    // Peeking must not influence the order of returned transactions.
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    let peeked: Vec<_> = mempool
+        .peek_l2_transactions(&filter, 10)
+        .into_iter()
+        .map(|tx| view(Some(tx)))
+        .collect();
+    assert_eq!(peeked, [(account0, 1), (account1, 0)]);
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 1));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert!(mempool.peek_l2_transactions(&filter, 10).is_empty());
+}
+
+#[test]
+fn pending_and_queued_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account0, Nonce(0)),
+        gen_l2_tx(account0, Nonce(1)),
+        gen_l2_tx(account0, Nonce(3)),
+        gen_l2_tx(account1, Nonce(1)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    let stats = mempool.stats();
+    assert_eq!(stats.l2_pending_transaction_count, 2);
+    assert_eq!(stats.l2_queued_transaction_count, 2);
+
+    let content = mempool.l2_content();
+    let account0_content = &content[&account0];
+    assert_eq!(account0_content.next_nonce, Nonce(0));
+    assert_eq!(account0_content.pending.len(), 2);
+    assert_eq!(account0_content.queued.len(), 1);
+    assert_eq!(account0_content.missing_nonce(), Nonce(2));
+    let account1_content = &content[&account1];
+    assert!(account1_content.pending.is_empty());
+    assert_eq!(account1_content.missing_nonce(), Nonce(0));
+
+    // Filling the nonce gap promotes the queued transaction.
+    mempool.insert(vec![gen_l2_tx(account0, Nonce(2))], HashMap::new());
+    let stats = mempool.stats();
+    assert_eq!(stats.l2_pending_transaction_count, 4);
+    assert_eq!(stats.l2_queued_transaction_count, 1);
+
+    mempool.next_transaction(&L2TxFilter::default()).unwrap();
+    let stats = mempool.stats();
+    assert_eq!(stats.l2_transaction_count, 4);
+    assert_eq!(stats.l2_pending_transaction_count, 3);
+    // Getting a snapshot must not modify the mempool.
+    assert_eq!(mempool.l2_content()[&account0].next_nonce, Nonce(1));
+    assert!(mempool.get_mempool_info().evicted_transactions.is_empty());
+}
+
+#[test]
+fn account_limits() {
+    let limits = MempoolLimits {
+        max_txs_per_account: Some(2),
+        max_bytes_per_account: Some(250),
+        ..MempoolLimits::default()
+    };
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_limits(limits);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account0, Nonce(0)),
+        gen_l2_tx(account0, Nonce(2)),
+        gen_l2_tx(account0, Nonce(1)),
+        gen_l2_tx_with_size(account1, Nonce(0), 100),
+        gen_l2_tx_with_size(account1, Nonce(1), 200),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let stats = mempool.stats();
+    assert_eq!(stats.l2_transaction_count, 3);
+    assert_eq!(stats.l2_size_bytes, 100);
+    let evicted: Vec<_> = mempool
+        .get_mempool_info()
+        .evicted_transactions
+        .into_iter()
+        .map(|tx| (tx.initiator, tx.nonce.0, tx.reason))
+        .collect();
+    assert_eq!(
+        evicted,
+        [
+            (account0, 2, EvictionReason::AccountTxCountLimit),
+            (account1, 1, EvictionReason::AccountSizeLimit),
+        ]
+    );
+
+    let filter = L2TxFilter::default();
+    let executed: HashSet<_> = (0..3)
+        .map(|_| view(mempool.next_transaction(&filter)))
+        .collect();
+    assert_eq!(
+        executed,
+        HashSet::from_iter([(account0, 0), (account0, 1), (account1, 0)])
+    );
+    assert_eq!(mempool.next_transaction(&filter), None);
+    assert_eq!(mempool.stats().l2_size_bytes, 0);
+}
+
+#[test]
+fn evicting_lowest_priority_transactions() {
+    let limits = MempoolLimits {
+        eviction_policy: EvictionPolicy::LowestPriority,
+        ..MempoolLimits::default()
+    };
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 3).with_limits(limits);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let now = unix_timestamp_ms();
+    let transactions = vec![
+        gen_l2_tx_with_timestamp(account0, Nonce(0), now),
+        gen_l2_tx_with_timestamp(account0, Nonce(1), now),
+        gen_l2_tx_with_timestamp(account1, Nonce(0), now + 10),
+        gen_l2_tx_with_timestamp(account1, Nonce(1), now + 10),
+        gen_l2_tx_with_timestamp(account1, Nonce(2), now + 10),
+        gen_l2_tx_with_timestamp(account2, Nonce(1), now),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let info = mempool.get_mempool_info();
+    assert_eq!(info.purged_accounts, [account2]);
+    let evicted: Vec<_> = info
+        .evicted_transactions
+        .into_iter()
+        .map(|tx| (tx.initiator, tx.nonce.0, tx.reason))
+        .collect();
+    assert_eq!(
+        evicted,
+        [
+            (account1, 2, EvictionReason::MempoolFull),
+            (account1, 1, EvictionReason::MempoolFull),
+        ]
+    );
+    assert_eq!(mempool.stats().l2_transaction_count, 3);
+
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 1));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(mempool.next_transaction(&filter), None);
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -381,9 +555,16 @@ fn gen_l2_tx_with_timestamp(address: Address, nonce: Nonce, received_at_ms: u64)
         Default::default(),
     );
     txn.received_timestamp_ms = received_at_ms;
+    txn.set_input(vec![], H256::random());
     txn.into()
 }
 
+fn gen_l2_tx_with_size(address: Address, nonce: Nonce, size: usize) -> Transaction {
+    let mut txn = gen_l2_tx(address, nonce);
+    txn.raw_bytes = Some(Bytes(vec![0; size]));
+    txn
+}
+
 fn gen_l1_tx(priority_id: PriorityOpId) -> Transaction {
     let execute = Execute {
         contract_address: Address::repeat_byte(0x11),
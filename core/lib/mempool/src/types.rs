@@ -4,6 +4,49 @@ use zksync_types::{
     fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, U256,
 };
 
+/// Returns the size of an L2 transaction used to enforce mempool limits. If the raw transaction bytes
+/// are not available, the size is approximated by the calldata and factory dependencies.
+pub(crate) fn tx_size_bytes(transaction: &L2Tx) -> u64 {
+    let size = match &transaction.raw_bytes {
+        Some(raw_bytes) => raw_bytes.0.len(),
+        None => {
+            let factory_deps = transaction
+                .execute
+                .factory_deps
+                .as_deref()
+                .unwrap_or_default();
+            transaction.execute.calldata.len() + factory_deps.iter().map(Vec::len).sum::<usize>()
+        }
+    };
+    size as u64
+}
+
+/// Aggregated size of a set of L2 transactions in the mempool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct TransactionsSize {
+    /// Number of transactions.
+    pub count: u64,
+    /// Total size of transactions in bytes.
+    pub bytes: u64,
+    /// Number of transactions that are not blocked by a nonce gap.
+    pub pending: u64,
+}
+
+impl TransactionsSize {
+    pub fn add(&mut self, other: Self) {
+        self.count += other.count;
+        self.bytes += other.bytes;
+        self.pending += other.pending;
+    }
+
+    pub fn sub(&mut self, other: Self) {
+        const MSG: &str = "mempool size can't be negative";
+        self.count = self.count.checked_sub(other.count).expect(MSG);
+        self.bytes = self.bytes.checked_sub(other.bytes).expect(MSG);
+        self.pending = self.pending.checked_sub(other.pending).expect(MSG);
+    }
+}
+
 /// Pending mempool transactions of account
 #[derive(Debug)]
 pub(crate) struct AccountTransactions {
@@ -12,6 +55,11 @@ pub(crate) struct AccountTransactions {
     /// account nonce in mempool
     /// equals to committed nonce in db + number of transactions sent to state keeper
     nonce: Nonce,
+    /// total size of account transactions in bytes
+    size_bytes: u64,
+    /// number of transactions with contiguous nonces starting from `nonce`; remaining transactions
+    /// are queued behind a nonce gap
+    pending_len: usize,
 }
 
 impl AccountTransactions {
@@ -19,6 +67,8 @@ impl AccountTransactions {
         Self {
             transactions: HashMap::new(),
             nonce,
+            size_bytes: 0,
+            pending_len: 0,
         }
     }
 
@@ -31,11 +81,15 @@ impl AccountTransactions {
             return metadata;
         }
         let new_score = Self::score_for_transaction(&transaction);
-        let previous_score = self
-            .transactions
-            .insert(nonce, transaction)
-            .map(|tx| Self::score_for_transaction(&tx));
+        self.size_bytes += tx_size_bytes(&transaction);
+        let previous_score = self.transactions.insert(nonce, transaction).map(|tx| {
+            self.size_bytes -= tx_size_bytes(&tx);
+            Self::score_for_transaction(&tx)
+        });
         metadata.is_new = previous_score.is_none();
+        if metadata.is_new {
+            self.extend_pending();
+        }
         if nonce == self.nonce {
             metadata.new_score = Some(new_score);
             metadata.previous_score = previous_score;
@@ -51,6 +105,8 @@ impl AccountTransactions {
             .remove(&self.nonce)
             .expect("missing transaction in mempool");
         self.nonce += 1;
+        self.size_bytes -= tx_size_bytes(&transaction);
+        self.pending_len -= 1;
         let score = self
             .transactions
             .get(&self.nonce)
@@ -58,6 +114,11 @@ impl AccountTransactions {
         (transaction, score)
     }
 
+    /// Returns the transaction that would be returned by [`Self::next()`], if any.
+    pub fn peek(&self) -> Option<&L2Tx> {
+        self.transactions.get(&self.nonce)
+    }
+
     /// Handles transaction rejection. Returns optional score of its successor
     pub fn reset(&mut self, transaction: &Transaction) -> Option<MempoolScore> {
         // current nonce for the group needs to be reset
@@ -65,15 +126,67 @@ impl AccountTransactions {
             .nonce()
             .expect("nonce is not set for L2 transaction");
         self.nonce = self.nonce.min(tx_nonce);
+        self.pending_len = 0;
+        self.extend_pending();
         self.transactions
             .get(&(tx_nonce + 1))
             .map(Self::score_for_transaction)
     }
 
+    /// Removes the transaction with the highest nonce. Returns the removed transaction and its score
+    /// if the transaction was the next one to be included in block.
+    pub fn evict_last(&mut self) -> Option<(L2Tx, Option<MempoolScore>)> {
+        let last_nonce = *self.transactions.keys().max()?;
+        let transaction = self.transactions.remove(&last_nonce)?;
+        self.size_bytes -= tx_size_bytes(&transaction);
+        if last_nonce.0 < self.nonce.0 + self.pending_len as u32 {
+            self.pending_len -= 1;
+        }
+        let score = (last_nonce == self.nonce).then(|| Self::score_for_transaction(&transaction));
+        Some((transaction, score))
+    }
+
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    pub fn nonce(&self) -> Nonce {
+        self.nonce
+    }
+
+    pub fn size_bytes(&self) -> u64 {
+        self.size_bytes
+    }
+
+    pub fn size(&self) -> TransactionsSize {
+        TransactionsSize {
+            count: self.transactions.len() as u64,
+            bytes: self.size_bytes,
+            pending: self.pending_len as u64,
+        }
+    }
+
+    /// Returns account transactions ordered by nonce, split into pending ones and ones queued behind a nonce gap.
+    pub fn split_pending(&self) -> (Vec<&L2Tx>, Vec<&L2Tx>) {
+        let mut transactions: Vec<_> = self.transactions.values().collect();
+        transactions.sort_unstable_by_key(|tx| tx.common_data.nonce);
+        let queued = transactions.split_off(self.pending_len);
+        (transactions, queued)
+    }
+
+    fn extend_pending(&mut self) {
+        while self
+            .transactions
+            .contains_key(&(self.nonce + self.pending_len as u32))
+        {
+            self.pending_len += 1;
+        }
+    }
+
     fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
         MempoolScore {
             account: transaction.initiator_account(),
@@ -3,9 +3,11 @@ mod multivm_dispatcher;
 pub mod old_tracers;
 pub mod prestate_tracer;
 pub mod storage_invocation;
+pub mod validation_gas;
 pub mod validator;
 
 pub use call_tracer::CallTracer;
 pub use multivm_dispatcher::TracerDispatcher;
 pub use prestate_tracer::PrestateTracer;
 pub use storage_invocation::StorageInvocations;
+pub use validation_gas::ValidationGasTracer;
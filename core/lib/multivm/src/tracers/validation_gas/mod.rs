@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+
+use crate::glue::tracers::IntoOldVmTracer;
+
+pub mod vm_1_4_1;
+pub mod vm_boojum_integration;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Tracer measuring gas spent by the bootloader on the validation step of a transaction (i.e., account validation
+/// and paymaster validation, if any). Unlike [`ValidationTracer`](crate::tracers::validator::ValidationTracer),
+/// this tracer doesn't check validation rules and doesn't stop execution after the validation step, so it can be
+/// used when executing the entire transaction.
+///
+/// The result is only set if the validation step has ended; it's not set if the VM halts during validation.
+#[derive(Debug, Clone)]
+pub struct ValidationGasTracer {
+    /// Gas remaining in the bootloader frame when the validation step was entered.
+    gas_remaining_on_start: Option<u32>,
+    result: Arc<OnceCell<u32>>,
+}
+
+impl ValidationGasTracer {
+    pub fn new(result: Arc<OnceCell<u32>>) -> Self {
+        Self {
+            gas_remaining_on_start: None,
+            result,
+        }
+    }
+
+    fn on_validation_entered(&mut self, gas_remaining: u32) {
+        self.gas_remaining_on_start.get_or_insert(gas_remaining);
+    }
+
+    fn on_validation_ended(&mut self, gas_remaining: u32) {
+        if let Some(gas_remaining_on_start) = self.gas_remaining_on_start.take() {
+            self.result
+                .set(gas_remaining_on_start.saturating_sub(gas_remaining))
+                .ok();
+        }
+    }
+}
+
+impl IntoOldVmTracer for ValidationGasTracer {}
@@ -0,0 +1,27 @@
+use zk_evm_1_4_1::tracing::{BeforeExecutionData, VmLocalStateData};
+use zksync_state::{StoragePtr, WriteStorage};
+
+use crate::{
+    interface::traits::tracers::dyn_tracers::vm_1_4_1::DynTracer,
+    tracers::validation_gas::ValidationGasTracer,
+    vm_1_4_1::{tracers::utils::VmHook, HistoryMode, SimpleMemory, VmTracer},
+};
+
+impl<S, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for ValidationGasTracer {
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        _memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        let gas_remaining = state.vm_local_state.callstack.current.ergs_remaining;
+        match VmHook::from_opcode_memory(&state, &data) {
+            VmHook::AccountValidationEntered => self.on_validation_entered(gas_remaining),
+            VmHook::ValidationStepEndeded => self.on_validation_ended(gas_remaining),
+            _ => { /* other hooks are irrelevant */ }
+        }
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for ValidationGasTracer {}
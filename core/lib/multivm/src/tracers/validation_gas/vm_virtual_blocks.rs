@@ -0,0 +1,34 @@
+use zk_evm_1_3_3::tracing::{BeforeExecutionData, VmLocalStateData};
+use zksync_state::{StoragePtr, WriteStorage};
+
+use crate::{
+    interface::dyn_tracers::vm_1_3_3::DynTracer,
+    tracers::validation_gas::ValidationGasTracer,
+    vm_virtual_blocks::{
+        tracers::utils::VmHook, ExecutionEndTracer, ExecutionProcessing, HistoryMode, SimpleMemory,
+        VmTracer,
+    },
+};
+
+impl<H: HistoryMode> ExecutionEndTracer<H> for ValidationGasTracer {}
+
+impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for ValidationGasTracer {
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        _memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        let gas_remaining = state.vm_local_state.callstack.current.ergs_remaining;
+        match VmHook::from_opcode_memory(&state, &data) {
+            VmHook::AccountValidationEntered => self.on_validation_entered(gas_remaining),
+            VmHook::ValidationStepEndeded => self.on_validation_ended(gas_remaining),
+            _ => { /* other hooks are irrelevant */ }
+        }
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> ExecutionProcessing<S, H> for ValidationGasTracer {}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for ValidationGasTracer {}
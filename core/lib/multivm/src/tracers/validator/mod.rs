@@ -7,15 +7,20 @@ use zksync_system_constants::{
     L2_ETH_TOKEN_ADDRESS, MSG_VALUE_SIMULATOR_ADDRESS, SYSTEM_CONTEXT_ADDRESS,
 };
 use zksync_types::{
-    vm_trace::ViolatedValidationRule, web3::signing::keccak256, AccountTreeId, Address, StorageKey,
-    H256, U256,
+    vm_trace::{
+        ValidationPhase, ValidationReport, ValidationStorageAccess, ViolatedValidationRule,
+    },
+    web3::signing::keccak256,
+    AccountTreeId, Address, StorageKey, H256, U256,
 };
 use zksync_utils::{be_bytes_to_safe_address, u256_to_account_address, u256_to_h256};
 
 pub use crate::tracers::validator::types::{ValidationError, ValidationTracerParams};
 use crate::{
     glue::tracers::IntoOldVmTracer,
-    tracers::validator::types::{NewTrustedValidationItems, ValidationTracerMode},
+    tracers::validator::types::{
+        NewTrustedValidationItems, ValidationReportCollector, ValidationTracerMode,
+    },
 };
 
 mod types;
@@ -42,6 +47,7 @@ pub struct ValidationTracer<H> {
     computational_gas_used: u32,
     computational_gas_limit: u32,
     pub result: Arc<OnceCell<ViolatedValidationRule>>,
+    report: Option<ValidationReportCollector>,
     _marker: PhantomData<fn(H) -> H>,
 }
 
@@ -64,12 +70,78 @@ impl<H> ValidationTracer<H> {
                 computational_gas_used: 0,
                 computational_gas_limit: params.computational_gas_limit,
                 result: result.clone(),
+                report: None,
                 _marker: Default::default(),
             },
             result,
         )
     }
 
+    /// Enables collecting a detailed [`ValidationReport`]. The report is set once the VM execution is finished.
+    /// Reports are only collected by the latest VM version.
+    pub fn with_report(mut self) -> (Self, Arc<OnceCell<ValidationReport>>) {
+        let output = Arc::new(OnceCell::new());
+        self.report = Some(ValidationReportCollector {
+            report: ValidationReport::default(),
+            seen_storage_accesses: HashSet::new(),
+            output: output.clone(),
+        });
+        (self, output)
+    }
+
+    fn current_phase(&self) -> Option<ValidationPhase> {
+        match self.validation_mode {
+            ValidationTracerMode::UserTxValidation => Some(ValidationPhase::Account),
+            ValidationTracerMode::PaymasterTxValidation => Some(ValidationPhase::Paymaster),
+            ValidationTracerMode::NoValidation => None,
+        }
+    }
+
+    fn record_storage_read(&mut self, address: Address, key: U256, is_allowed: bool) {
+        let Some(phase) = self.current_phase() else {
+            return;
+        };
+        let Some(collector) = &mut self.report else {
+            return;
+        };
+        if collector
+            .seen_storage_accesses
+            .insert((phase, address, key))
+        {
+            collector
+                .report
+                .storage_accesses
+                .push(ValidationStorageAccess {
+                    phase,
+                    address,
+                    key,
+                    is_allowed,
+                });
+        }
+    }
+
+    fn record_violation_call_stack(&mut self, call_stack: impl Iterator<Item = Address>) {
+        if self.result.get().is_some() {
+            return; // Only the first violation is reported
+        }
+        if let Some(collector) = &mut self.report {
+            collector.report.violation_call_stack = call_stack.collect();
+        }
+    }
+
+    fn finalize_report(&mut self) {
+        let Some(collector) = self.report.take() else {
+            return;
+        };
+        let report = ValidationReport {
+            violated_rule: self.result.get().cloned(),
+            computational_gas_used: self.computational_gas_used,
+            computational_gas_limit: self.computational_gas_limit,
+            ..collector.report
+        };
+        collector.output.set(report).ok();
+    }
+
     fn process_validation_round_result(&mut self, result: ValidationRoundResult) {
         match result {
             Ok(NewTrustedValidationItems {
@@ -1,6 +1,10 @@
-use std::{collections::HashSet, fmt::Display};
+use std::{collections::HashSet, fmt::Display, sync::Arc};
 
-use zksync_types::{vm_trace::ViolatedValidationRule, Address, H256, U256};
+use once_cell::sync::OnceCell;
+use zksync_types::{
+    vm_trace::{ValidationPhase, ValidationReport, ViolatedValidationRule},
+    Address, H256, U256,
+};
 
 use crate::interface::Halt;
 
@@ -21,6 +25,14 @@ pub(super) struct NewTrustedValidationItems {
     pub(super) new_trusted_addresses: Vec<Address>,
 }
 
+/// Collector of the [`ValidationReport`] for the validation tracer.
+#[derive(Debug, Clone)]
+pub(super) struct ValidationReportCollector {
+    pub(super) report: ValidationReport,
+    pub(super) seen_storage_accesses: HashSet<(ValidationPhase, Address, U256)>,
+    pub(super) output: Arc<OnceCell<ValidationReport>>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ValidationTracerParams {
     pub user_address: Address,
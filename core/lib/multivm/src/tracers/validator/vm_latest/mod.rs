@@ -12,7 +12,7 @@ use zksync_utils::{h256_to_account_address, u256_to_account_address, u256_to_h25
 use crate::{
     interface::{
         traits::tracers::dyn_tracers::vm_1_4_1::DynTracer,
-        types::tracer::{TracerExecutionStatus, TracerExecutionStopReason},
+        types::tracer::{TracerExecutionStatus, TracerExecutionStopReason, VmExecutionStopReason},
         Halt,
     },
     tracers::validator::{
@@ -101,7 +101,10 @@ impl<H: HistoryMode> ValidationTracer<H> {
                 let this_address = state.vm_local_state.callstack.current.this_address;
                 let msg_sender = state.vm_local_state.callstack.current.msg_sender;
 
-                if !self.is_allowed_storage_read(storage.clone(), this_address, key, msg_sender) {
+                let is_allowed =
+                    self.is_allowed_storage_read(storage.clone(), this_address, key, msg_sender);
+                self.record_storage_read(this_address, key, is_allowed);
+                if !is_allowed {
                     return Err(ViolatedValidationRule::TouchedUnallowedStorageSlots(
                         this_address,
                         key,
@@ -145,7 +148,24 @@ impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H::Vm1_4_2>>
 
             let validation_round_result =
                 self.check_user_restrictions_vm_latest(state, data, memory, storage);
+            if validation_round_result.is_err() {
+                let callstack = &state.vm_local_state.callstack;
+                let call_stack = callstack
+                    .inner
+                    .iter()
+                    .chain([&callstack.current])
+                    .map(|frame| frame.this_address);
+                self.record_violation_call_stack(call_stack);
+            }
             self.process_validation_round_result(validation_round_result);
+        } else if let (
+            ValidationTracerMode::PaymasterTxValidation,
+            Opcode::Log(LogOpcode::StorageRead),
+        ) = (self.validation_mode, data.opcode.variant.opcode)
+        {
+            // Paymaster validation is not restricted, so we only record storage reads for the validation report.
+            let this_address = state.vm_local_state.callstack.current.this_address;
+            self.record_storage_read(this_address, data.src0_value.value, true);
         }
 
         let hook = VmHook::from_opcode_memory(&state, &data);
@@ -198,4 +218,13 @@ impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H::Vm1_4_2> for ValidationTrac
         }
         TracerExecutionStatus::Continue
     }
+
+    fn after_vm_execution(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H::Vm1_4_2>,
+        _bootloader_state: &BootloaderState,
+        _stop_reason: VmExecutionStopReason,
+    ) {
+        self.finalize_report();
+    }
 }
@@ -22,3 +22,4 @@ mod tester;
 mod tracing_execution_error;
 mod upgrade;
 mod utils;
+mod validation_tracer;
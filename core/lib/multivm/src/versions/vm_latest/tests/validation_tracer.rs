@@ -0,0 +1,87 @@
+use zksync_types::{
+    vm_trace::{ValidationPhase, ViolatedValidationRule},
+    Address, Execute,
+};
+
+use crate::{
+    interface::{TxExecutionMode, VmExecutionMode, VmInterface},
+    tracers::validator::{ValidationTracer, ValidationTracerParams},
+    vm_latest::{
+        constants::BLOCK_GAS_LIMIT,
+        tests::{tester::VmTesterBuilder, utils::read_test_contract},
+        HistoryEnabled, ToTracerPointer,
+    },
+};
+
+fn validation_params(
+    user_address: Address,
+    computational_gas_limit: u32,
+) -> ValidationTracerParams {
+    ValidationTracerParams {
+        user_address,
+        paymaster_address: Address::zero(),
+        trusted_slots: Default::default(),
+        trusted_addresses: Default::default(),
+        trusted_address_slots: Default::default(),
+        computational_gas_limit,
+    }
+}
+
+#[test]
+fn test_validation_report() {
+    for computational_gas_limit in [BLOCK_GAS_LIMIT, 10] {
+        let contract = read_test_contract();
+        let address = Address::random();
+        let mut vm = VmTesterBuilder::new(HistoryEnabled)
+            .with_empty_in_memory_storage()
+            .with_random_rich_accounts(1)
+            .with_deployer()
+            .with_gas_limit(BLOCK_GAS_LIMIT)
+            .with_execution_mode(TxExecutionMode::VerifyExecute)
+            .with_custom_contracts(vec![(contract, address, true)])
+            .build();
+
+        let account = &mut vm.rich_accounts[0];
+        let tx = account.get_l2_tx_for_execute(
+            Execute {
+                contract_address: address,
+                calldata: vec![],
+                value: Default::default(),
+                factory_deps: None,
+            },
+            None,
+        );
+        let params = validation_params(account.address, computational_gas_limit);
+        let (tracer, violated_rule) = ValidationTracer::<HistoryEnabled>::new(params);
+        let (tracer, report) = tracer.with_report();
+        vm.vm.push_transaction(tx);
+        vm.vm
+            .inspect(tracer.into_tracer_pointer().into(), VmExecutionMode::OneTx);
+
+        let report = report.get().expect("validation report is not set");
+        assert_eq!(report.computational_gas_limit, computational_gas_limit);
+        assert!(report.computational_gas_used > 0);
+        assert!(!report.storage_accesses.is_empty());
+        assert!(report
+            .storage_accesses
+            .iter()
+            .all(|access| access.phase == ValidationPhase::Account));
+
+        if computational_gas_limit == BLOCK_GAS_LIMIT {
+            assert!(violated_rule.get().is_none());
+            assert!(report.violated_rule.is_none());
+            assert!(report.violation_call_stack.is_empty());
+            assert!(report
+                .storage_accesses
+                .iter()
+                .all(|access| access.is_allowed));
+        } else {
+            assert!(report.computational_gas_used > computational_gas_limit);
+            assert!(matches!(
+                report.violated_rule,
+                Some(ViolatedValidationRule::TookTooManyComputationalGas(10))
+            ));
+            assert!(!report.violation_call_stack.is_empty());
+        }
+    }
+}
@@ -7,6 +7,7 @@ use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use prost::Message;
 use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
+    eth_sender::L1BatchBlobs,
     snapshots::{
         SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
     },
@@ -129,6 +130,27 @@ impl StoredObject for WitnessBlockState {
     serialize_using_bincode!();
 }
 
+impl StoredObject for L1BatchBlobs {
+    const BUCKET: Bucket = Bucket::BatchBlobs;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("l1_batch_{key}_blobs.bin.gzip")
+    }
+
+    // Blobs are padded with zeros, so they compress well.
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        bincode::serialize_into(&mut encoder, self)?;
+        encoder.finish().map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        let decoder = GzDecoder::new(&bytes[..]);
+        bincode::deserialize_from(decoder).map_err(From::from)
+    }
+}
+
 impl dyn ObjectStore + '_ {
     /// Fetches the value for the given key if it exists.
     ///
@@ -167,6 +189,7 @@ impl dyn ObjectStore + '_ {
 #[cfg(test)]
 mod tests {
     use zksync_types::{
+        eth_sender::SidecarBlobV1,
         snapshots::{SnapshotFactoryDependency, SnapshotStorageLog},
         AccountTreeId, Bytes, StorageKey, H160, H256,
     };
@@ -248,4 +271,23 @@ mod tests {
         let reconstructed_factory_deps = store.get(key).await.unwrap();
         assert_eq!(factory_deps, reconstructed_factory_deps);
     }
+
+    #[tokio::test]
+    async fn test_batch_blobs_can_be_serialized_and_deserialized() {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        let key = L1BatchNumber(42);
+        let blobs = L1BatchBlobs {
+            l1_batch_number: key,
+            commit_tx_hash: H256::repeat_byte(1),
+            blobs: vec![SidecarBlobV1 {
+                blob: vec![0; 131_072],
+                commitment: vec![2; 48],
+                proof: vec![3; 48],
+                versioned_hash: vec![4; 32],
+            }],
+        };
+        store.put(key, &blobs).await.unwrap();
+        let reconstructed_blobs = store.get(key).await.unwrap();
+        assert_eq!(blobs, reconstructed_blobs);
+    }
 }
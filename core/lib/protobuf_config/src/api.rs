@@ -5,7 +5,7 @@ use zksync_protobuf::{
     required,
 };
 
-use crate::{parse_h256, proto::api as proto};
+use crate::{parse_h160, parse_h256, proto::api as proto};
 
 impl ProtoRepr for proto::Api {
     type Type = ApiConfig;
@@ -126,6 +126,28 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("mempool_cache_size")?,
+            tx_deny_list_path: self.tx_deny_list_path.clone(),
+            tx_deployer_allow_list: self
+                .tx_deployer_allow_list
+                .as_ref()
+                .map(|list| {
+                    list.addresses
+                        .iter()
+                        .enumerate()
+                        .map(|(i, address)| parse_h160(address).context(i))
+                        .collect::<Result<_, _>>()
+                        .context("addresses")
+                })
+                .transpose()
+                .context("tx_deployer_allow_list")?,
+            max_pending_txs_per_sender: self.max_pending_txs_per_sender,
+            min_priority_fee_per_gas: self.min_priority_fee_per_gas,
+            private_mode: self.private_mode.unwrap_or(false),
+            max_session_token_ttl_sec: self.max_session_token_ttl_sec,
+            txpool_namespace_enabled: self.txpool_namespace_enabled.unwrap_or(false),
+            gas_estimation_stats_enabled: self.gas_estimation_stats_enabled.unwrap_or(false),
+            estimate_gas_min_scale_factor: self.estimate_gas_min_scale_factor,
+            estimate_gas_max_scale_factor: self.estimate_gas_max_scale_factor,
         })
     }
     fn build(this: &Self::Type) -> Self {
@@ -177,6 +199,23 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .websocket_requests_per_minute_limit
                 .map(|x| x.into()),
             tree_api_url: this.tree_api_url.clone(),
+            tx_deny_list_path: this.tx_deny_list_path.clone(),
+            tx_deployer_allow_list: this.tx_deployer_allow_list.as_ref().map(|addresses| {
+                proto::AddressList {
+                    addresses: addresses
+                        .iter()
+                        .map(|address| address.as_bytes().into())
+                        .collect(),
+                }
+            }),
+            max_pending_txs_per_sender: this.max_pending_txs_per_sender,
+            min_priority_fee_per_gas: this.min_priority_fee_per_gas,
+            private_mode: Some(this.private_mode),
+            max_session_token_ttl_sec: this.max_session_token_ttl_sec,
+            txpool_namespace_enabled: Some(this.txpool_namespace_enabled),
+            gas_estimation_stats_enabled: Some(this.gas_estimation_stats_enabled),
+            estimate_gas_min_scale_factor: this.estimate_gas_min_scale_factor,
+            estimate_gas_max_scale_factor: this.estimate_gas_max_scale_factor,
         }
     }
 }
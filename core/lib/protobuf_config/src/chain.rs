@@ -51,6 +51,24 @@ impl proto::FeeModelVersion {
     }
 }
 
+impl proto::MempoolEvictionPolicy {
+    fn new(n: &configs::chain::MempoolEvictionPolicy) -> Self {
+        use configs::chain::MempoolEvictionPolicy as From;
+        match n {
+            From::PurgeQueued => Self::PurgeQueued,
+            From::LowestPriority => Self::LowestPriority,
+        }
+    }
+
+    fn parse(&self) -> configs::chain::MempoolEvictionPolicy {
+        use configs::chain::MempoolEvictionPolicy as To;
+        match self {
+            Self::PurgeQueued => To::PurgeQueued,
+            Self::LowestPriority => To::LowestPriority,
+        }
+    }
+}
+
 impl ProtoRepr for proto::EthNetwork {
     type Type = configs::chain::NetworkConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -140,6 +158,18 @@ impl ProtoRepr for proto::StateKeeper {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("enum_index_migration_chunk_size")?,
+            seal_policy_path: self.seal_policy_path.clone(),
+            shadow_vm_protocol_version: self
+                .shadow_vm_protocol_version
+                .map(|x| x.try_into())
+                .transpose()
+                .context("shadow_vm_protocol_version")?,
+            shadow_vm_divergence_log_path: self.shadow_vm_divergence_log_path.clone(),
+            pre_execution_lookahead: self
+                .pre_execution_lookahead
+                .map(|x| x.try_into())
+                .transpose()
+                .context("pre_execution_lookahead")?,
         })
     }
 
@@ -176,6 +206,13 @@ impl ProtoRepr for proto::StateKeeper {
                 .enum_index_migration_chunk_size
                 .as_ref()
                 .map(|x| (*x).try_into().unwrap()),
+            seal_policy_path: this.seal_policy_path.clone(),
+            shadow_vm_protocol_version: this.shadow_vm_protocol_version.map(Into::into),
+            shadow_vm_divergence_log_path: this.shadow_vm_divergence_log_path.clone(),
+            pre_execution_lookahead: this
+                .pre_execution_lookahead
+                .as_ref()
+                .map(|x| (*x).try_into().unwrap()),
         }
     }
 }
@@ -207,6 +244,19 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: *required(&self.stuck_tx_timeout).context("stuck_tx_timeout")?,
             remove_stuck_txs: *required(&self.remove_stuck_txs).context("remove_stuck_txs")?,
             delay_interval: *required(&self.delay_interval).context("delay_interval")?,
+            max_size_bytes: self.max_size_bytes,
+            max_txs_per_account: self
+                .max_txs_per_account
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_txs_per_account")?,
+            max_bytes_per_account: self.max_bytes_per_account,
+            eviction_policy: self
+                .eviction_policy
+                .map(|x| anyhow::Ok(proto::MempoolEvictionPolicy::try_from(x)?.parse()))
+                .transpose()
+                .context("eviction_policy")?
+                .unwrap_or_default(),
         })
     }
 
@@ -218,6 +268,13 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: Some(this.stuck_tx_timeout),
             remove_stuck_txs: Some(this.remove_stuck_txs),
             delay_interval: Some(this.delay_interval),
+            max_size_bytes: this.max_size_bytes,
+            max_txs_per_account: this
+                .max_txs_per_account
+                .as_ref()
+                .map(|x| (*x).try_into().unwrap()),
+            max_bytes_per_account: this.max_bytes_per_account,
+            eviction_policy: Some(proto::MempoolEvictionPolicy::new(&this.eviction_policy).into()),
         }
     }
 }
@@ -234,6 +291,7 @@ impl ProtoRepr for proto::CircuitBreaker {
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("http_req_retry_interval_sec")?,
             replication_lag_limit_sec: self.replication_lag_limit_sec,
+            priority_op_deadline_margin_sec: self.priority_op_deadline_margin_sec,
         })
     }
 
@@ -243,6 +301,7 @@ impl ProtoRepr for proto::CircuitBreaker {
             http_req_max_retry_number: Some(this.http_req_max_retry_number.try_into().unwrap()),
             http_req_retry_interval_sec: Some(this.http_req_retry_interval_sec.into()),
             replication_lag_limit_sec: this.replication_lag_limit_sec,
+            priority_op_deadline_margin_sec: this.priority_op_deadline_margin_sec,
         }
     }
 }
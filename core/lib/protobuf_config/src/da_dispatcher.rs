@@ -0,0 +1,47 @@
+use anyhow::Context as _;
+use zksync_config::configs::da_dispatcher::{DAClientConfig, DADispatcherConfig};
+use zksync_protobuf::{repr::ProtoRepr, required};
+
+use crate::proto::da_dispatcher as proto;
+
+impl ProtoRepr for proto::DaDispatcher {
+    type Type = DADispatcherConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        let client = match required(&self.client).context("client")? {
+            proto::da_dispatcher::Client::FileBacked(client) => DAClientConfig::FileBacked {
+                file_backed_base_path: required(&client.file_backed_base_path)
+                    .context("file_backed_base_path")?
+                    .clone(),
+            },
+        };
+
+        Ok(Self::Type {
+            client,
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+            max_rows_to_dispatch: *required(&self.max_rows_to_dispatch)
+                .context("max_rows_to_dispatch")?,
+            max_retries: required(&self.max_retries)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("max_retries")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        let client = match &this.client {
+            DAClientConfig::FileBacked {
+                file_backed_base_path,
+            } => proto::da_dispatcher::Client::FileBacked(proto::da_dispatcher::FileBacked {
+                file_backed_base_path: Some(file_backed_base_path.clone()),
+            }),
+        };
+
+        Self {
+            client: Some(client),
+            polling_interval_ms: Some(this.polling_interval_ms),
+            max_rows_to_dispatch: Some(this.max_rows_to_dispatch),
+            max_retries: Some(this.max_retries.into()),
+        }
+    }
+}
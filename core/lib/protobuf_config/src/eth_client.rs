@@ -10,6 +10,9 @@ impl ProtoRepr for proto::EthClient {
         Ok(Self::Type {
             chain_id: *required(&self.chain_id).context("chain_id")?,
             web3_url: required(&self.web3_url).context("web3_url")?.clone(),
+            fallback_web3_urls: self.fallback_web3_urls.clone(),
+            max_block_lag: self.max_block_lag,
+            read_quorum: self.read_quorum,
         })
     }
 
@@ -17,6 +20,9 @@ impl ProtoRepr for proto::EthClient {
         Self {
             chain_id: Some(this.chain_id),
             web3_url: Some(this.web3_url.clone()),
+            fallback_web3_urls: this.fallback_web3_urls.clone(),
+            max_block_lag: this.max_block_lag,
+            read_quorum: this.read_quorum,
         }
     }
 }
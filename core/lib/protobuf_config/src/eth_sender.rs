@@ -48,6 +48,7 @@ impl proto::PubdataSendingMode {
         match x {
             From::Calldata => Self::Calldata,
             From::Blobs => Self::Blobs,
+            From::Custom => Self::Custom,
         }
     }
 
@@ -56,6 +57,27 @@ impl proto::PubdataSendingMode {
         match self {
             Self::Calldata => To::Calldata,
             Self::Blobs => To::Blobs,
+            Self::Custom => To::Custom,
+        }
+    }
+}
+
+impl proto::OperatorRole {
+    fn new(x: &configs::eth_sender::OperatorRole) -> Self {
+        use configs::eth_sender::OperatorRole as From;
+        match x {
+            From::Commit => Self::Commit,
+            From::Prove => Self::Prove,
+            From::Execute => Self::Execute,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_sender::OperatorRole {
+        use configs::eth_sender::OperatorRole as To;
+        match self {
+            Self::Commit => To::Commit,
+            Self::Prove => To::Prove,
+            Self::Execute => To::Execute,
         }
     }
 }
@@ -128,6 +150,17 @@ impl ProtoRepr for proto::Sender {
                 .and_then(|x| Ok(proto::PubdataSendingMode::try_from(*x)?))
                 .context("pubdata_sending_mode")?
                 .parse(),
+            operator_rotation_l1_batch: self.operator_rotation_l1_batch,
+            operator_rotation_roles: self
+                .operator_rotation_roles
+                .iter()
+                .enumerate()
+                .map(|(i, x)| Ok(proto::OperatorRole::try_from(*x).context(i)?.parse()))
+                .collect::<anyhow::Result<_>>()
+                .context("operator_rotation_roles")?,
+            min_operator_balance_gwei: self.min_operator_balance_gwei,
+            l1_fee_postpone_percentile: self.l1_fee_postpone_percentile,
+            l1_fee_max_postpone_seconds: self.l1_fee_max_postpone_seconds,
         })
     }
 
@@ -159,6 +192,15 @@ impl ProtoRepr for proto::Sender {
             pubdata_sending_mode: Some(
                 proto::PubdataSendingMode::new(&this.pubdata_sending_mode).into(),
             ),
+            operator_rotation_l1_batch: this.operator_rotation_l1_batch,
+            operator_rotation_roles: this
+                .operator_rotation_roles
+                .iter()
+                .map(|x| proto::OperatorRole::new(x).into())
+                .collect(),
+            min_operator_balance_gwei: this.min_operator_balance_gwei,
+            l1_fee_postpone_percentile: this.l1_fee_postpone_percentile,
+            l1_fee_max_postpone_seconds: this.l1_fee_max_postpone_seconds,
         }
     }
 }
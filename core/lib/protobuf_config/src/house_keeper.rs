@@ -50,6 +50,10 @@ impl ProtoRepr for proto::HouseKeeper {
                 &self.fri_proof_compressor_stats_reporting_interval_ms,
             )
             .context("fri_proof_compressor_stats_reporting_interval_ms")?,
+            priority_queue_monitoring_interval_ms: *required(
+                &self.priority_queue_monitoring_interval_ms,
+            )
+            .context("priority_queue_monitoring_interval_ms")?,
         })
     }
 
@@ -82,6 +86,7 @@ impl ProtoRepr for proto::HouseKeeper {
             fri_proof_compressor_stats_reporting_interval_ms: Some(
                 this.fri_proof_compressor_stats_reporting_interval_ms,
             ),
+            priority_queue_monitoring_interval_ms: Some(this.priority_queue_monitoring_interval_ms),
         }
     }
 }
@@ -12,6 +12,8 @@ impl ProtoRepr for proto::SnapshotsCreator {
                 .context("storage_logs_chunk_size")?,
             concurrent_queries_count: *required(&self.concurrent_queries_count)
                 .context("concurrent_queries_count")?,
+            max_incremental_snapshots: self.max_incremental_snapshots.unwrap_or(0),
+            retained_full_snapshots: self.retained_full_snapshots,
         })
     }
 
@@ -19,6 +21,8 @@ impl ProtoRepr for proto::SnapshotsCreator {
         Self {
             storage_logs_chunk_size: Some(this.storage_logs_chunk_size),
             concurrent_queries_count: Some(this.concurrent_queries_count),
+            max_incremental_snapshots: Some(this.max_incremental_snapshots),
+            retained_full_snapshots: this.retained_full_snapshots,
         }
     }
 }
@@ -21,6 +21,7 @@ fn test_encoding() {
     encode_decode::<ReprConv<proto::chain::CircuitBreaker>>(rng);
     encode_decode::<ReprConv<proto::contract_verifier::ContractVerifier>>(rng);
     encode_decode::<ReprConv<proto::contracts::Contracts>>(rng);
+    encode_decode::<ReprConv<proto::da_dispatcher::DaDispatcher>>(rng);
     encode_decode::<ReprConv<proto::database::MerkleTree>>(rng);
     encode_decode::<ReprConv<proto::database::Db>>(rng);
     encode_decode::<ReprConv<proto::database::Postgres>>(rng);
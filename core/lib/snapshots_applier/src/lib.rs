@@ -1,6 +1,11 @@
 //! Logic for applying application-level snapshots to Postgres storage.
 
-use std::{collections::HashMap, fmt, time::Duration};
+use std::{
+    collections::HashMap,
+    fmt,
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
@@ -8,16 +13,18 @@ use serde::Serialize;
 use tokio::sync::Semaphore;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, SqlxError};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
-use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_merkle_tree::{recovery::MerkleTreeRecovery, PatchSet, TreeEntry};
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
 use zksync_types::{
     api::en::SyncBlock,
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotHeader, SnapshotRecoveryStatus, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        snapshot_blob_hash, uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotHeader,
+        SnapshotManifest, SnapshotRecoveryStatus, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
     tokens::TokenInfo,
     web3::futures,
-    L1BatchNumber, MiniblockNumber, H256,
+    Address, L1BatchNumber, L2ChainId, MiniblockNumber, H256,
 };
 use zksync_utils::bytecode::hash_bytecode;
 use zksync_web3_decl::{
@@ -98,6 +105,11 @@ pub trait SnapshotsApplierMainNodeClient: fmt::Debug + Send + Sync {
 
     async fn fetch_newest_snapshot(&self) -> EnrichedClientResult<Option<SnapshotHeader>>;
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>>;
+
     async fn fetch_tokens(
         &self,
         at_miniblock: MiniblockNumber,
@@ -121,12 +133,19 @@ impl SnapshotsApplierMainNodeClient for HttpClient {
             .get_all_snapshots()
             .rpc_context("get_all_snapshots")
             .await?;
-        let Some(newest_snapshot) = snapshots.snapshots_l1_batch_numbers.first() else {
+        let Some(&newest_snapshot) = snapshots.snapshots_l1_batch_numbers.first() else {
             return Ok(None);
         };
-        self.get_snapshot_by_l1_batch_number(*newest_snapshot)
+        self.fetch_snapshot(newest_snapshot).await
+    }
+
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        self.get_snapshot_by_l1_batch_number(l1_batch_number)
             .rpc_context("get_snapshot_by_l1_batch_number")
-            .with_arg("number", newest_snapshot)
+            .with_arg("number", &l1_batch_number)
             .await
     }
 
@@ -141,12 +160,27 @@ impl SnapshotsApplierMainNodeClient for HttpClient {
     }
 }
 
+/// Trusted signer of snapshot manifests (normally, the main node operator).
+#[derive(Debug, Clone, Copy)]
+pub struct SnapshotManifestSigner {
+    pub address: Address,
+    pub chain_id: L2ChainId,
+}
+
 /// Snapshot applier configuration options.
 #[derive(Debug)]
 pub struct SnapshotsApplierConfig {
     pub retry_count: usize,
     pub initial_retry_backoff: Duration,
     pub retry_backoff_multiplier: f32,
+    /// If set, all applied snapshots must have manifests signed by this signer, and the Merkle tree root hash
+    /// of the recovered state is checked against the root hash of the snapshot L1 batch. This should be set
+    /// if snapshot files are obtained from an untrusted source. Regardless of this option, hashes of snapshot files
+    /// are checked if they are present in snapshot headers.
+    pub manifest_signer: Option<SnapshotManifestSigner>,
+    /// Maximum number of storage logs chunks fetched, decoded and persisted concurrently. Since each chunk
+    /// is held in memory until it's persisted, this bounds the memory usage of the applier.
+    pub max_concurrency: NonZeroUsize,
     health_updater: HealthUpdater,
 }
 
@@ -156,6 +190,8 @@ impl Default for SnapshotsApplierConfig {
             retry_count: 5,
             initial_retry_backoff: Duration::from_secs(2),
             retry_backoff_multiplier: 2.0,
+            manifest_signer: None,
+            max_concurrency: NonZeroUsize::new(10).unwrap(),
             health_updater: ReactiveHealthCheck::new("snapshot_recovery").1,
         }
     }
@@ -197,7 +233,7 @@ impl SnapshotsApplierConfig {
                 connection_pool,
                 main_node_client,
                 blob_store,
-                &self.health_updater,
+                &self,
             )
             .await;
 
@@ -231,6 +267,77 @@ impl SnapshotsApplierConfig {
     }
 }
 
+/// Snapshot in the chain of snapshots applied by [`SnapshotsApplier`]. The chain starts with a full snapshot;
+/// all following snapshots are incremental, each based on the previous snapshot in the chain.
+#[derive(Debug, Clone)]
+struct ChainedSnapshot {
+    l1_batch_number: L1BatchNumber,
+    is_incremental: bool,
+    chunk_count: usize,
+    /// Index of the first chunk of this snapshot in [`SnapshotRecoveryStatus::storage_logs_chunks_processed`].
+    first_chunk_index: usize,
+    /// Expected hash of the factory deps file, if known.
+    factory_deps_hash: Option<H256>,
+    /// Expected hashes of storage logs chunks ordered by chunk ID, if known.
+    storage_logs_chunk_hashes: Vec<Option<H256>>,
+}
+
+impl ChainedSnapshot {
+    fn new_chain(snapshots: &[SnapshotHeader]) -> Vec<Self> {
+        let mut first_chunk_index = 0;
+        snapshots
+            .iter()
+            .map(|snapshot| {
+                let chained = Self {
+                    l1_batch_number: snapshot.l1_batch_number,
+                    is_incremental: snapshot.base_l1_batch_number.is_some(),
+                    chunk_count: snapshot.storage_logs_chunks.len(),
+                    first_chunk_index,
+                    factory_deps_hash: snapshot.factory_deps_hash,
+                    storage_logs_chunk_hashes: snapshot
+                        .storage_logs_chunks
+                        .iter()
+                        .map(|chunk| chunk.hash)
+                        .collect(),
+                };
+                first_chunk_index += chained.chunk_count;
+                chained
+            })
+            .collect()
+    }
+}
+
+/// Tracks progress of storage logs recovery in order to estimate the remaining time.
+#[derive(Debug)]
+struct StorageLogsProgress {
+    started_at: Instant,
+    chunks_left_at_start: usize,
+}
+
+impl StorageLogsProgress {
+    fn new(chunks_left: usize) -> Self {
+        Self {
+            started_at: Instant::now(),
+            chunks_left_at_start: chunks_left,
+        }
+    }
+
+    /// Estimates the remaining time based on the average chunk processing rate since the start
+    /// and reports it as a metric. Returns `None` if no chunks were processed yet.
+    fn estimate_remaining_time(&self, chunks_left: usize) -> Option<Duration> {
+        let processed_chunks = self.chunks_left_at_start.checked_sub(chunks_left)?;
+        if processed_chunks == 0 {
+            return None;
+        }
+        let eta = self
+            .started_at
+            .elapsed()
+            .mul_f64(chunks_left as f64 / processed_chunks as f64);
+        METRICS.storage_logs_chunks_eta.set(eta);
+        Some(eta)
+    }
+}
+
 /// Applying application-level storage snapshots to the Postgres storage.
 #[derive(Debug)]
 struct SnapshotsApplier<'a> {
@@ -238,6 +345,10 @@ struct SnapshotsApplier<'a> {
     main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
     blob_store: &'a dyn ObjectStore,
     applied_snapshot_status: SnapshotRecoveryStatus,
+    /// Snapshots to apply. Empty if all storage log chunks are already processed.
+    snapshot_chain: Vec<ChainedSnapshot>,
+    manifest_signer: Option<SnapshotManifestSigner>,
+    max_concurrency: NonZeroUsize,
     health_updater: &'a HealthUpdater,
     factory_deps_recovered: bool,
     tokens_recovered: bool,
@@ -310,8 +421,9 @@ impl<'a> SnapshotsApplier<'a> {
         connection_pool: &'a ConnectionPool<Core>,
         main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
         blob_store: &'a dyn ObjectStore,
-        health_updater: &'a HealthUpdater,
+        config: &'a SnapshotsApplierConfig,
     ) -> Result<(), SnapshotsApplierError> {
+        let health_updater = &config.health_updater;
         health_updater.update(HealthStatus::Ready.into());
 
         let mut storage = connection_pool
@@ -325,11 +437,47 @@ impl<'a> SnapshotsApplier<'a> {
             Self::prepare_applied_snapshot_status(&mut storage_transaction, main_node_client)
                 .await?;
 
+        let snapshot_chain = if created_from_scratch
+            || applied_snapshot_status.storage_logs_chunks_left_to_process() > 0
+        {
+            let snapshots = Self::fetch_snapshot_chain(
+                main_node_client,
+                applied_snapshot_status.l1_batch_number,
+            )
+            .await?;
+            if let Some(manifest_signer) = &config.manifest_signer {
+                Self::verify_snapshot_manifests(
+                    manifest_signer,
+                    &snapshots,
+                    &applied_snapshot_status,
+                )?;
+            }
+            let snapshot_chain = ChainedSnapshot::new_chain(&snapshots);
+            let chunk_count: usize = snapshot_chain
+                .iter()
+                .map(|snapshot| snapshot.chunk_count)
+                .sum();
+            let expected_chunk_count = applied_snapshot_status.storage_logs_chunks_processed.len();
+            if chunk_count != expected_chunk_count {
+                let err = anyhow::anyhow!(
+                    "mismatch between the number of storage logs chunks in the snapshot chain on main node ({chunk_count}) \
+                     and in the applied snapshot status ({expected_chunk_count})"
+                );
+                return Err(SnapshotsApplierError::Fatal(err));
+            }
+            snapshot_chain
+        } else {
+            vec![]
+        };
+
         let mut this = Self {
             connection_pool,
             main_node_client,
             blob_store,
             applied_snapshot_status,
+            snapshot_chain,
+            manifest_signer: config.manifest_signer,
+            max_concurrency: config.max_concurrency,
             health_updater,
             factory_deps_recovered: !created_from_scratch,
             tokens_recovered: false,
@@ -380,9 +528,26 @@ impl<'a> SnapshotsApplier<'a> {
             .context("no snapshots on main node; snapshot recovery is impossible")?;
         let l1_batch_number = snapshot.l1_batch_number;
         let miniblock_number = snapshot.miniblock_number;
-        tracing::info!(
-            "Found snapshot with data up to L1 batch #{l1_batch_number}, storage_logs are divided into {} chunk(s)",
+        let chunk_count = if snapshot.base_l1_batch_number.is_some() {
+            let snapshot_chain =
+                Self::fetch_snapshot_chain(main_node_client, l1_batch_number).await?;
+            tracing::info!(
+                "Found incremental snapshot with data up to L1 batch #{l1_batch_number}; snapshot chain consists of \
+                 snapshots for L1 batches {:?}",
+                snapshot_chain
+                    .iter()
+                    .map(|snapshot| snapshot.l1_batch_number)
+                    .collect::<Vec<_>>()
+            );
+            snapshot_chain
+                .iter()
+                .map(|snapshot| snapshot.storage_logs_chunks.len())
+                .sum()
+        } else {
             snapshot.storage_logs_chunks.len()
+        };
+        tracing::info!(
+            "Found snapshot with data up to L1 batch #{l1_batch_number}, storage_logs are divided into {chunk_count} chunk(s)"
         );
 
         let miniblock = main_node_client
@@ -405,7 +570,131 @@ impl<'a> SnapshotsApplier<'a> {
                 .header
                 .protocol_version
                 .unwrap(),
-            storage_logs_chunks_processed: vec![false; snapshot.storage_logs_chunks.len()],
+            storage_logs_chunks_processed: vec![false; chunk_count],
+        })
+    }
+
+    /// Fetches the chain of snapshots ending with the snapshot for `l1_batch_number` from the main node.
+    /// The returned snapshots are ordered by increasing L1 batch number; the first snapshot is full.
+    async fn fetch_snapshot_chain(
+        main_node_client: &dyn SnapshotsApplierMainNodeClient,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Vec<SnapshotHeader>, SnapshotsApplierError> {
+        let mut snapshots = vec![];
+        let mut next_l1_batch_number = Some(l1_batch_number);
+        while let Some(l1_batch_number) = next_l1_batch_number {
+            let snapshot = main_node_client
+                .fetch_snapshot(l1_batch_number)
+                .await?
+                .with_context(|| {
+                    format!("snapshot for L1 batch #{l1_batch_number} is missing on main node")
+                })?;
+            if let Some(base_l1_batch_number) = snapshot.base_l1_batch_number {
+                if base_l1_batch_number >= l1_batch_number {
+                    let err = anyhow::anyhow!(
+                        "snapshot for L1 batch #{l1_batch_number} has invalid base L1 batch #{base_l1_batch_number}"
+                    );
+                    return Err(SnapshotsApplierError::Fatal(err));
+                }
+            }
+            next_l1_batch_number = snapshot.base_l1_batch_number;
+            snapshots.push(snapshot);
+        }
+        snapshots.reverse();
+        Ok(snapshots)
+    }
+
+    /// Verifies operator signatures for manifests of all snapshots in the chain. Also checks that the newest snapshot
+    /// in the chain corresponds to the applied snapshot status.
+    fn verify_snapshot_manifests(
+        manifest_signer: &SnapshotManifestSigner,
+        snapshots: &[SnapshotHeader],
+        applied_snapshot_status: &SnapshotRecoveryStatus,
+    ) -> Result<(), SnapshotsApplierError> {
+        for snapshot in snapshots {
+            let l1_batch_number = snapshot.l1_batch_number;
+            let manifest = SnapshotManifest::from_header(snapshot).with_context(|| {
+                format!("snapshot for L1 batch #{l1_batch_number} lacks file hashes")
+            })?;
+            let signature = snapshot.manifest_signature.as_ref().with_context(|| {
+                format!("snapshot for L1 batch #{l1_batch_number} has no manifest signature")
+            })?;
+            let signer = manifest
+                .recover_signer(manifest_signer.chain_id, signature)
+                .map_err(|err| {
+                    anyhow::anyhow!(
+                        "cannot recover manifest signer for snapshot for L1 batch #{l1_batch_number}: {err}"
+                    )
+                })?;
+            if signer != manifest_signer.address {
+                let err = anyhow::anyhow!(
+                    "manifest for snapshot for L1 batch #{l1_batch_number} is signed by {signer:?}, \
+                     while {:?} is expected",
+                    manifest_signer.address
+                );
+                return Err(SnapshotsApplierError::Fatal(err));
+            }
+        }
+
+        // `unwrap()` is safe: the snapshot chain always contains at least one snapshot
+        let newest_snapshot = snapshots.last().unwrap();
+        let root_hash = newest_snapshot
+            .last_l1_batch_with_metadata
+            .metadata
+            .root_hash;
+        if newest_snapshot.l1_batch_number != applied_snapshot_status.l1_batch_number
+            || root_hash != applied_snapshot_status.l1_batch_root_hash
+        {
+            let err = anyhow::anyhow!(
+                "signed snapshot for L1 batch #{} with root hash {root_hash:?} doesn't match the applied snapshot status {applied_snapshot_status:?}",
+                newest_snapshot.l1_batch_number
+            );
+            return Err(SnapshotsApplierError::Fatal(err));
+        }
+        tracing::info!(
+            "Verified manifest signatures for {} snapshot(s)",
+            snapshots.len()
+        );
+        Ok(())
+    }
+
+    /// Fetches a blob from the object store, checking its hash if the expected hash is known.
+    /// Hashing and decoding are CPU-heavy for large blobs, so they are performed on a blocking thread.
+    async fn get_blob<V: StoredObject + Send + 'static>(
+        &self,
+        key: V::Key<'_>,
+        expected_hash: Option<H256>,
+        description: &str,
+    ) -> Result<V, SnapshotsApplierError> {
+        let key = V::encode_key(key);
+        let bytes = self
+            .blob_store
+            .get_raw(V::BUCKET, &key)
+            .await
+            .map_err(|err| {
+                let context = format!("cannot fetch {description} from object store");
+                SnapshotsApplierError::object_store(err, context)
+            })?;
+
+        let (hash, deserialized) = tokio::task::spawn_blocking(move || {
+            let hash = expected_hash.map(|_| snapshot_blob_hash(&bytes));
+            (hash, V::deserialize(bytes))
+        })
+        .await
+        .with_context(|| format!("panicked while decoding {description}"))?;
+
+        if let (Some(expected_hash), Some(hash)) = (expected_hash, hash) {
+            if hash != expected_hash {
+                let err = anyhow::anyhow!(
+                    "hash mismatch for {description} (object key `{key}`): expected {expected_hash:?}, got {hash:?}; \
+                     the file may be corrupted or tampered with"
+                );
+                return Err(SnapshotsApplierError::Fatal(err));
+            }
+        }
+        deserialized.map_err(|err| {
+            let context = format!("cannot deserialize {description}");
+            SnapshotsApplierError::object_store(ObjectStoreError::Serialization(err), context)
         })
     }
 
@@ -433,24 +722,26 @@ impl<'a> SnapshotsApplier<'a> {
         let latency = METRICS.initial_stage_duration[&InitialStage::ApplyFactoryDeps].start();
 
         tracing::debug!("Fetching factory dependencies from object store");
-        let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
-        let factory_deps: SnapshotFactoryDependencies =
-            self.blob_store.get(l1_batch_number).await.map_err(|err| {
-                let context = format!(
-                    "cannot fetch factory deps for L1 batch #{l1_batch_number} from object store"
-                );
-                SnapshotsApplierError::object_store(err, context)
-            })?;
-        tracing::debug!(
-            "Fetched {} factory dependencies from object store",
-            factory_deps.factory_deps.len()
-        );
+        let mut all_deps_hashmap = HashMap::<H256, Vec<u8>>::new();
+        // Incremental snapshots only contain factory deps added after their base snapshot,
+        // so we need to collect deps from all snapshots in the chain.
+        for snapshot in &self.snapshot_chain {
+            let l1_batch_number = snapshot.l1_batch_number;
+            let description = format!("factory deps for L1 batch #{l1_batch_number}");
+            let factory_deps: SnapshotFactoryDependencies = self
+                .get_blob(l1_batch_number, snapshot.factory_deps_hash, &description)
+                .await?;
+            tracing::debug!(
+                "Fetched {} factory dependencies for L1 batch #{l1_batch_number} from object store",
+                factory_deps.factory_deps.len()
+            );
 
-        let all_deps_hashmap: HashMap<H256, Vec<u8>> = factory_deps
-            .factory_deps
-            .into_iter()
-            .map(|dep| (hash_bytecode(&dep.bytecode.0), dep.bytecode.0))
-            .collect();
+            let deps = factory_deps
+                .factory_deps
+                .into_iter()
+                .map(|dep| (hash_bytecode(&dep.bytecode.0), dep.bytecode.0));
+            all_deps_hashmap.extend(deps);
+        }
         storage
             .factory_deps_dal()
             .insert_factory_deps(
@@ -470,67 +761,79 @@ impl<'a> SnapshotsApplier<'a> {
 
     async fn insert_initial_writes_chunk(
         &self,
+        snapshot: &ChainedSnapshot,
         chunk_id: u64,
         storage_logs: &[SnapshotStorageLog],
         storage: &mut Connection<'_, Core>,
     ) -> Result<(), SnapshotsApplierError> {
-        storage
-            .storage_logs_dedup_dal()
-            .insert_initial_writes_from_snapshot(storage_logs)
-            .await
-            .map_err(|err| {
-                let context =
-                    format!("failed persisting initial writes from storage logs chunk {chunk_id}");
-                SnapshotsApplierError::db(err, context)
-            })?;
+        let mut dal = storage.storage_logs_dedup_dal();
+        let result = if snapshot.is_incremental {
+            dal.insert_new_initial_writes_from_snapshot(storage_logs)
+                .await
+        } else {
+            dal.insert_initial_writes_from_snapshot(storage_logs).await
+        };
+        result.map_err(|err| {
+            let context =
+                format!("failed persisting initial writes from storage logs chunk {chunk_id}");
+            SnapshotsApplierError::db(err, context)
+        })?;
         Ok(())
     }
 
     async fn insert_storage_logs_chunk(
         &self,
+        snapshot: &ChainedSnapshot,
         chunk_id: u64,
         storage_logs: &[SnapshotStorageLog],
         storage: &mut Connection<'_, Core>,
     ) -> Result<(), SnapshotsApplierError> {
-        storage
-            .storage_logs_dal()
-            .insert_storage_logs_from_snapshot(
-                self.applied_snapshot_status.miniblock_number,
-                storage_logs,
-            )
-            .await
-            .map_err(|err| {
-                let context = format!("failed persisting storage logs from chunk {chunk_id}");
-                SnapshotsApplierError::db(err, context)
-            })?;
+        // Logs from all snapshots in the chain are stored at the miniblock of the newest snapshot,
+        // so that incremental snapshots overwrite values from their base snapshots.
+        let miniblock_number = self.applied_snapshot_status.miniblock_number;
+        let mut dal = storage.storage_logs_dal();
+        let result = if snapshot.is_incremental {
+            dal.upsert_storage_logs_from_snapshot(miniblock_number, storage_logs)
+                .await
+        } else {
+            dal.insert_storage_logs_from_snapshot(miniblock_number, storage_logs)
+                .await
+        };
+        result.map_err(|err| {
+            let context = format!("failed persisting storage logs from chunk {chunk_id}");
+            SnapshotsApplierError::db(err, context)
+        })?;
         Ok(())
     }
 
-    #[tracing::instrument(level = "debug", err, skip(self, semaphore))]
+    #[tracing::instrument(level = "debug", err, skip(self, semaphore, progress))]
     async fn recover_storage_logs_single_chunk(
         &self,
         semaphore: &Semaphore,
+        progress: &StorageLogsProgress,
+        snapshot: &ChainedSnapshot,
         chunk_id: u64,
     ) -> Result<(), SnapshotsApplierError> {
-        // `unwrap()` is safe: the semaphore is never closed
+        // The permit is held until the chunk is persisted, so that at most `max_concurrency` chunks
+        // are kept in memory at any time. `unwrap()` is safe: the semaphore is never closed.
         let _permit = semaphore.acquire().await.unwrap();
 
-        tracing::info!("Processing storage logs chunk {chunk_id}");
+        let l1_batch_number = snapshot.l1_batch_number;
+        tracing::info!("Processing storage logs chunk {chunk_id} for L1 batch #{l1_batch_number}");
         let latency =
             METRICS.storage_logs_chunks_duration[&StorageLogsChunksStage::LoadFromGcs].start();
 
         let storage_key = SnapshotStorageLogsStorageKey {
             chunk_id,
-            l1_batch_number: self.applied_snapshot_status.l1_batch_number,
+            l1_batch_number,
         };
-        let storage_snapshot_chunk: SnapshotStorageLogsChunk =
-            self.blob_store.get(storage_key).await.map_err(|err| {
-                let context =
-                    format!("cannot fetch storage logs {storage_key:?} from object store");
-                SnapshotsApplierError::object_store(err, context)
-            })?;
+        let expected_hash = snapshot.storage_logs_chunk_hashes[chunk_id as usize];
+        let description = format!("storage logs {storage_key:?}");
+        let storage_snapshot_chunk: SnapshotStorageLogsChunk = self
+            .get_blob(storage_key, expected_hash, &description)
+            .await?;
         let storage_logs = &storage_snapshot_chunk.storage_logs;
-        self.validate_storage_logs_chunk(storage_logs)?;
+        self.validate_storage_logs_chunk(snapshot, storage_logs)?;
         let latency = latency.observe();
         tracing::info!(
             "Loaded {} storage logs from GCS for chunk {chunk_id} in {latency:?}",
@@ -549,15 +852,24 @@ impl<'a> SnapshotsApplier<'a> {
             SnapshotsApplierError::db(err, context)
         })?;
 
+        // Storage logs are loaded using `COPY` (or bulk upserts for incremental snapshots) in the same transaction
+        // that marks the chunk as processed. Thus, if the applier crashes midway, the chunk is rolled back as a whole
+        // and will be reprocessed after a restart.
         tracing::info!("Loading {} storage logs into Postgres", storage_logs.len());
-        self.insert_storage_logs_chunk(chunk_id, storage_logs, &mut storage_transaction)
-            .await?;
-        self.insert_initial_writes_chunk(chunk_id, storage_logs, &mut storage_transaction)
+        self.insert_storage_logs_chunk(snapshot, chunk_id, storage_logs, &mut storage_transaction)
             .await?;
+        self.insert_initial_writes_chunk(
+            snapshot,
+            chunk_id,
+            storage_logs,
+            &mut storage_transaction,
+        )
+        .await?;
 
+        let chunk_index = snapshot.first_chunk_index as u64 + chunk_id;
         storage_transaction
             .snapshot_recovery_dal()
-            .mark_storage_logs_chunk_as_processed(chunk_id)
+            .mark_storage_logs_chunk_as_processed(chunk_index)
             .await
             .map_err(|err| {
                 let context = format!("failed marking storage logs chunk {chunk_id} as processed");
@@ -570,7 +882,11 @@ impl<'a> SnapshotsApplier<'a> {
 
         let chunks_left = METRICS.storage_logs_chunks_left_to_process.dec_by(1) - 1;
         let latency = latency.observe();
-        tracing::info!("Saved storage logs for chunk {chunk_id} in {latency:?}, there are {chunks_left} left to process");
+        let eta = progress.estimate_remaining_time(chunks_left);
+        tracing::info!(
+            "Saved storage logs for chunk {chunk_id} in {latency:?}, there are {chunks_left} left to process \
+             (ETA: {eta:?})"
+        );
 
         Ok(())
     }
@@ -578,6 +894,7 @@ impl<'a> SnapshotsApplier<'a> {
     /// Performs basic sanity check for a storage logs chunk.
     fn validate_storage_logs_chunk(
         &self,
+        snapshot: &ChainedSnapshot,
         storage_logs: &[SnapshotStorageLog],
     ) -> anyhow::Result<()> {
         for log in storage_logs {
@@ -586,7 +903,7 @@ impl<'a> SnapshotsApplier<'a> {
                 "invalid storage log with zero enumeration_index: {log:?}"
             );
             anyhow::ensure!(
-                log.l1_batch_number_of_initial_write <= self.applied_snapshot_status.l1_batch_number,
+                log.l1_batch_number_of_initial_write <= snapshot.l1_batch_number,
                 "invalid storage log with `l1_batch_number_of_initial_write` from the future: {log:?}"
             );
         }
@@ -594,22 +911,66 @@ impl<'a> SnapshotsApplier<'a> {
     }
 
     async fn recover_storage_logs(&self) -> Result<(), SnapshotsApplierError> {
-        let semaphore = Semaphore::new(self.connection_pool.max_size() as usize);
-        let tasks = self
+        let chunks_left = self
             .applied_snapshot_status
-            .storage_logs_chunks_processed
-            .iter()
-            .enumerate()
-            .filter(|(_, is_processed)| !**is_processed)
-            .map(|(chunk_id, _)| {
-                self.recover_storage_logs_single_chunk(&semaphore, chunk_id as u64)
-            });
-        futures::future::try_join_all(tasks).await?;
+            .storage_logs_chunks_left_to_process();
+        if chunks_left > 0 {
+            let mut storage = self
+                .connection_pool
+                .connection_tagged("snapshots_applier")
+                .await?;
+            storage
+                .snapshot_recovery_dal()
+                .drop_deferred_storage_indexes()
+                .await
+                .map_err(|err| {
+                    SnapshotsApplierError::db(err, "failed dropping secondary storage indexes")
+                })?;
+            tracing::info!("Dropped secondary storage indexes; they will be created after all storage logs are recovered");
+        }
+
+        let semaphore = Semaphore::new(self.max_concurrency.get());
+        let progress = StorageLogsProgress::new(chunks_left);
+        // Snapshots in the chain must be applied sequentially since incremental snapshots overwrite
+        // storage logs from their base snapshots. Chunks within a single snapshot are disjoint
+        // and can be applied concurrently.
+        for snapshot in &self.snapshot_chain {
+            let chunk_indices =
+                snapshot.first_chunk_index..snapshot.first_chunk_index + snapshot.chunk_count;
+            let chunks_processed =
+                &self.applied_snapshot_status.storage_logs_chunks_processed[chunk_indices];
+            let tasks = chunks_processed
+                .iter()
+                .enumerate()
+                .filter(|(_, is_processed)| !**is_processed)
+                .map(|(chunk_id, _)| {
+                    self.recover_storage_logs_single_chunk(
+                        &semaphore,
+                        &progress,
+                        snapshot,
+                        chunk_id as u64,
+                    )
+                });
+            futures::future::try_join_all(tasks).await?;
+        }
 
         let mut storage = self
             .connection_pool
             .connection_tagged("snapshots_applier")
             .await?;
+        // Indexes are created unconditionally since the applier may have crashed after recovering all chunks,
+        // but before creating indexes.
+        let latency = METRICS.index_creation_duration.start();
+        storage
+            .snapshot_recovery_dal()
+            .create_deferred_storage_indexes()
+            .await
+            .map_err(|err| {
+                SnapshotsApplierError::db(err, "failed creating secondary storage indexes")
+            })?;
+        let latency = latency.observe();
+        tracing::info!("Created secondary storage indexes in {latency:?}");
+
         // This DB query is slow, but this is fine for verification purposes.
         let total_log_count = storage
             .storage_logs_dal()
@@ -634,7 +995,63 @@ impl<'a> SnapshotsApplier<'a> {
             );
             return Err(SnapshotsApplierError::Fatal(err));
         }
+        drop(storage);
+
+        if self.manifest_signer.is_some() {
+            self.verify_root_hash().await?;
+        }
+        Ok(())
+    }
 
+    /// Computes the Merkle tree root hash for the recovered storage logs and compares it with the root hash
+    /// of the snapshot L1 batch. The tree is built in memory and discarded afterwards.
+    async fn verify_root_hash(&self) -> Result<(), SnapshotsApplierError> {
+        let latency = METRICS.root_hash_verification_duration.start();
+        let status = &self.applied_snapshot_status;
+        let mut tree =
+            MerkleTreeRecovery::new(PatchSet::default(), status.l1_batch_number.0.into());
+        let chunk_count = status.storage_logs_chunks_processed.len().max(1) as u64;
+        for chunk_id in 0..chunk_count {
+            let key_range = uniform_hashed_keys_chunk(chunk_id, chunk_count);
+            let mut storage = self
+                .connection_pool
+                .connection_tagged("snapshots_applier")
+                .await?;
+            let entries = storage
+                .storage_logs_dal()
+                .get_tree_entries_for_miniblock(status.miniblock_number, key_range)
+                .await
+                .map_err(|err| {
+                    SnapshotsApplierError::db(
+                        err,
+                        "failed fetching tree entries for recovered storage logs",
+                    )
+                })?;
+            drop(storage);
+
+            let entries = entries
+                .into_iter()
+                .map(|entry| TreeEntry {
+                    key: entry.tree_key(),
+                    value: entry.value,
+                    leaf_index: entry.leaf_index,
+                })
+                .collect();
+            tree.extend_linear(entries);
+        }
+
+        let root_hash = tree.root_hash();
+        if root_hash != status.l1_batch_root_hash {
+            let err = anyhow::anyhow!(
+                "root hash of the recovered state {root_hash:?} differs from the root hash {:?} of L1 batch #{}; \
+                 the snapshot may be corrupted",
+                status.l1_batch_root_hash,
+                status.l1_batch_number
+            );
+            return Err(SnapshotsApplierError::Fatal(err));
+        }
+        let latency = latency.observe();
+        tracing::info!("Verified root hash of the recovered state in {latency:?}");
         Ok(())
     }
 
@@ -26,6 +26,9 @@ pub(crate) struct SnapshotsApplierMetrics {
 
     /// Number of chunks left to apply.
     pub storage_logs_chunks_left_to_process: Gauge<usize>,
+    /// Estimated time to apply the remaining chunks, based on the chunk processing rate since the applier start.
+    #[metrics(unit = Unit::Seconds)]
+    pub storage_logs_chunks_eta: Gauge<Duration>,
 
     /// Total latency of applying snapshot.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
@@ -38,6 +41,14 @@ pub(crate) struct SnapshotsApplierMetrics {
     /// Latency of storage log chunk processing split by stage.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub storage_logs_chunks_duration: Family<StorageLogsChunksStage, Histogram<Duration>>,
+
+    /// Latency of creating secondary storage indexes after all storage logs are recovered.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub index_creation_duration: Histogram<Duration>,
+
+    /// Latency of verifying the Merkle tree root hash for the recovered state.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub root_hash_verification_duration: Histogram<Duration>,
 }
 
 #[vise::register]
@@ -6,11 +6,12 @@ use test_casing::test_casing;
 use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
-    get_code_key, Address, L1BatchNumber, ProtocolVersion, ProtocolVersionId,
+    get_code_key, Address, L1BatchNumber, PackedEthSignature, ProtocolVersion, ProtocolVersionId,
 };
 
 use self::utils::{
-    mock_recovery_status, prepare_clients, MockMainNodeClient, ObjectStoreWithErrors,
+    add_incremental_snapshot, mock_recovery_status, prepare_clients, sign_snapshots,
+    tree_root_hash, MockMainNodeClient, ObjectStoreWithErrors,
 };
 use super::*;
 use crate::tests::utils::{mock_tokens, random_storage_logs};
@@ -96,6 +97,100 @@ async fn snapshots_creator_can_successfully_recover_db(
         .unwrap();
 }
 
+#[tokio::test]
+async fn recovering_from_incremental_snapshot() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let base_status = SnapshotRecoveryStatus {
+        l1_batch_number: L1BatchNumber(100),
+        miniblock_number: MiniblockNumber(300),
+        ..mock_recovery_status()
+    };
+    let base_storage_logs = random_storage_logs(base_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&base_status, &base_storage_logs).await;
+
+    let expected_status = SnapshotRecoveryStatus {
+        storage_logs_chunks_processed: vec![true; 4],
+        ..mock_recovery_status()
+    };
+    let updated_logs = base_storage_logs
+        .iter()
+        .step_by(3)
+        .map(|log| SnapshotStorageLog {
+            value: H256::random(),
+            ..log.clone()
+        });
+    let new_logs = random_storage_logs(expected_status.l1_batch_number, 50)
+        .into_iter()
+        .map(|log| SnapshotStorageLog {
+            enumeration_index: log.enumeration_index + 100,
+            ..log
+        });
+    let diff_storage_logs: Vec<_> = updated_logs.chain(new_logs).collect();
+    add_incremental_snapshot(
+        &*object_store,
+        &mut client,
+        &expected_status,
+        &diff_storage_logs,
+        2,
+    )
+    .await;
+
+    SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap();
+
+    let mut storage = pool.connection().await.unwrap();
+    let current_db_status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(current_db_status.unwrap(), expected_status);
+
+    let expected_logs: HashMap<_, _> = base_storage_logs
+        .into_iter()
+        .chain(diff_storage_logs)
+        .map(|log| (log.key.hashed_key(), log))
+        .collect();
+    assert_eq!(expected_logs.len(), 150);
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), expected_logs.len());
+    for db_log in all_storage_logs {
+        let expected_log = &expected_logs[&db_log.hashed_key];
+        assert_eq!(db_log.value, expected_log.value);
+        assert_eq!(db_log.miniblock_number, expected_status.miniblock_number);
+    }
+
+    let all_initial_writes = storage
+        .storage_logs_dedup_dal()
+        .dump_all_initial_writes_for_tests()
+        .await;
+    assert_eq!(all_initial_writes.len(), expected_logs.len());
+    for initial_write in all_initial_writes {
+        let log = &expected_logs[&initial_write.hashed_key];
+        assert_eq!(
+            initial_write.l1_batch_number,
+            log.l1_batch_number_of_initial_write
+        );
+        assert_eq!(initial_write.index, log.enumeration_index);
+    }
+
+    // Factory deps from both snapshots in the chain must be recovered.
+    for bytecode in [(0..32).collect::<Vec<u8>>(), (32..64).collect()] {
+        let bytecode_hash = hash_bytecode(&bytecode);
+        let stored_bytecode = storage
+            .factory_deps_dal()
+            .get_factory_dep(bytecode_hash)
+            .await
+            .unwrap();
+        assert_eq!(stored_bytecode, Some(bytecode));
+    }
+}
+
 #[tokio::test]
 async fn applier_errors_after_genesis() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -209,6 +304,164 @@ async fn applier_returns_error_after_too_many_object_store_retries() {
     }));
 }
 
+#[tokio::test]
+async fn applier_resumes_after_failure_midway() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 200);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+    // With concurrency limited to 1, chunks are processed sequentially, so the first chunk is persisted
+    // before the applier fails on the second one.
+    let object_store_with_errors = ObjectStoreWithErrors::new(object_store.clone(), |key| {
+        if key.ends_with("storage_logs_part_0001.proto.gzip") {
+            Err(ObjectStoreError::KeyNotFound("not found".into()))
+        } else {
+            Ok(())
+        }
+    });
+    let mut config = SnapshotsApplierConfig::for_tests();
+    config.max_concurrency = NonZeroUsize::new(1).unwrap();
+    config
+        .run(&pool, &client, &object_store_with_errors)
+        .await
+        .unwrap_err();
+
+    let mut storage = pool.connection().await.unwrap();
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap()
+        .expect("no recovery status");
+    assert_eq!(status.storage_logs_chunks_processed, [true, false]);
+    let persisted_log_count = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await
+        .len();
+    assert!(persisted_log_count > 0 && persisted_log_count < storage_logs.len());
+
+    let mut config = SnapshotsApplierConfig::for_tests();
+    config.max_concurrency = NonZeroUsize::new(1).unwrap();
+    config.run(&pool, &client, &object_store).await.unwrap();
+
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(status.unwrap(), expected_status);
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), storage_logs.len());
+}
+
+fn manifest_signer(private_key: &H256) -> SnapshotManifestSigner {
+    SnapshotManifestSigner {
+        address: PackedEthSignature::address_from_private_key(private_key).unwrap(),
+        chain_id: L2ChainId::default(),
+    }
+}
+
+#[tokio::test]
+async fn recovering_from_signed_snapshot() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let storage_logs = random_storage_logs(L1BatchNumber(123), 200);
+    let expected_status = SnapshotRecoveryStatus {
+        l1_batch_root_hash: tree_root_hash(&storage_logs),
+        ..mock_recovery_status()
+    };
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let private_key = H256::repeat_byte(1);
+    sign_snapshots(&mut client, &private_key);
+
+    let mut config = SnapshotsApplierConfig::for_tests();
+    config.manifest_signer = Some(manifest_signer(&private_key));
+    config.run(&pool, &client, &object_store).await.unwrap();
+
+    let mut storage = pool.connection().await.unwrap();
+    let current_db_status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(current_db_status.unwrap(), expected_status);
+}
+
+#[tokio::test]
+async fn applier_errors_on_unauthenticated_manifest() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let storage_logs = random_storage_logs(L1BatchNumber(123), 100);
+    let expected_status = SnapshotRecoveryStatus {
+        l1_batch_root_hash: tree_root_hash(&storage_logs),
+        ..mock_recovery_status()
+    };
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    let mut config = SnapshotsApplierConfig::for_tests();
+    config.manifest_signer = Some(manifest_signer(&H256::repeat_byte(1)));
+    let err = config.run(&pool, &client, &object_store).await.unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("no manifest signature"), "{err}");
+
+    sign_snapshots(&mut client, &H256::repeat_byte(2));
+    let mut config = SnapshotsApplierConfig::for_tests();
+    config.manifest_signer = Some(manifest_signer(&H256::repeat_byte(1)));
+    let err = config.run(&pool, &client, &object_store).await.unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("is signed by"), "{err}");
+
+    let mut storage = pool.connection().await.unwrap();
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert!(status.is_none());
+}
+
+#[tokio::test]
+async fn applier_errors_on_root_hash_mismatch() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let private_key = H256::repeat_byte(1);
+    sign_snapshots(&mut client, &private_key);
+
+    let mut config = SnapshotsApplierConfig::for_tests();
+    config.manifest_signer = Some(manifest_signer(&private_key));
+    let err = config.run(&pool, &client, &object_store).await.unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("root hash of the recovered state"), "{err}");
+}
+
+#[tokio::test]
+async fn applier_errors_on_tampered_storage_logs_chunk() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    let chunk_key = SnapshotStorageLogsStorageKey {
+        l1_batch_number: expected_status.l1_batch_number,
+        chunk_id: 1,
+    };
+    let tampered_chunk = SnapshotStorageLogsChunk {
+        storage_logs: random_storage_logs(expected_status.l1_batch_number, 50),
+    };
+    object_store.put(chunk_key, &tampered_chunk).await.unwrap();
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("hash mismatch"), "{err}");
+}
+
 #[tokio::test]
 async fn recovering_tokens() {
     let pool = ConnectionPool::<Core>::test_pool().await;
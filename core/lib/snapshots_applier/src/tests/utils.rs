@@ -3,19 +3,22 @@
 use std::{collections::HashMap, fmt, sync::Arc};
 
 use async_trait::async_trait;
-use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory};
+use zksync_merkle_tree::{MerkleTree, PatchSet, TreeEntry};
+use zksync_object_store::{
+    Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory, StoredObject,
+};
 use zksync_types::{
     api::en::SyncBlock,
     block::L1BatchHeader,
     commitment::{L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotHeader,
-        SnapshotRecoveryStatus, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        snapshot_blob_hash, SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotHeader,
+        SnapshotManifest, SnapshotRecoveryStatus, SnapshotStorageLog, SnapshotStorageLogsChunk,
         SnapshotStorageLogsChunkMetadata, SnapshotStorageLogsStorageKey,
     },
     tokens::{TokenInfo, TokenMetadata},
-    AccountTreeId, Address, Bytes, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey,
-    StorageValue, H160, H256,
+    AccountTreeId, Address, Bytes, Eip712Domain, L1BatchNumber, L2ChainId, MiniblockNumber,
+    PackedEthSignature, ProtocolVersionId, StorageKey, StorageValue, H160, H256,
 };
 use zksync_web3_decl::error::EnrichedClientResult;
 
@@ -25,6 +28,7 @@ use crate::SnapshotsApplierMainNodeClient;
 pub(super) struct MockMainNodeClient {
     pub fetch_l2_block_responses: HashMap<MiniblockNumber, SyncBlock>,
     pub fetch_newest_snapshot_response: Option<SnapshotHeader>,
+    pub fetch_snapshot_responses: HashMap<L1BatchNumber, SnapshotHeader>,
     pub tokens_response: Vec<TokenInfo>,
 }
 
@@ -41,6 +45,13 @@ impl SnapshotsApplierMainNodeClient for MockMainNodeClient {
         Ok(self.fetch_newest_snapshot_response.clone())
     }
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        Ok(self.fetch_snapshot_responses.get(&l1_batch_number).cloned())
+    }
+
     async fn fetch_tokens(
         &self,
         _at_miniblock: MiniblockNumber,
@@ -170,6 +181,17 @@ pub(super) fn random_storage_logs(
         .collect()
 }
 
+/// Computes the Merkle tree root hash for the state consisting of the provided storage logs.
+pub(super) fn tree_root_hash(logs: &[SnapshotStorageLog]) -> H256 {
+    let entries = logs
+        .iter()
+        .map(|log| TreeEntry::new(log.key.hashed_key_u256(), log.enumeration_index, log.value))
+        .collect();
+    MerkleTree::new(PatchSet::default())
+        .extend(entries)
+        .root_hash
+}
+
 pub(super) fn mock_recovery_status() -> SnapshotRecoveryStatus {
     SnapshotRecoveryStatus {
         l1_batch_number: L1BatchNumber(123),
@@ -206,63 +228,98 @@ pub(super) fn mock_tokens() -> Vec<TokenInfo> {
     ]
 }
 
-pub(super) async fn prepare_clients(
-    status: &SnapshotRecoveryStatus,
+/// Hashes of snapshot files.
+#[derive(Debug)]
+struct SnapshotFileHashes {
+    factory_deps: H256,
+    storage_logs_chunks: Vec<H256>,
+}
+
+async fn blob_hash<V: StoredObject>(object_store: &dyn ObjectStore, key: V::Key<'_>) -> H256 {
+    let bytes = object_store
+        .get_raw(V::BUCKET, &V::encode_key(key))
+        .await
+        .unwrap();
+    snapshot_blob_hash(&bytes)
+}
+
+async fn put_snapshot_files(
+    object_store: &dyn ObjectStore,
+    l1_batch_number: L1BatchNumber,
+    factory_dep_bytes: Vec<u8>,
     logs: &[SnapshotStorageLog],
-) -> (Arc<dyn ObjectStore>, MockMainNodeClient) {
-    let object_store_factory = ObjectStoreFactory::mock();
-    let object_store = object_store_factory.create_store().await;
-    let mut client = MockMainNodeClient::default();
-    let factory_dep_bytes: Vec<u8> = (0..32).collect();
+    chunk_count: usize,
+) -> SnapshotFileHashes {
     let factory_deps = SnapshotFactoryDependencies {
         factory_deps: vec![SnapshotFactoryDependency {
             bytecode: Bytes::from(factory_dep_bytes),
         }],
     };
     object_store
-        .put(status.l1_batch_number, &factory_deps)
+        .put(l1_batch_number, &factory_deps)
         .await
         .unwrap();
 
-    let chunk_size = logs
-        .len()
-        .div_ceil(status.storage_logs_chunks_processed.len());
+    let factory_deps_hash =
+        blob_hash::<SnapshotFactoryDependencies>(object_store, l1_batch_number).await;
+
+    let chunk_size = logs.len().div_ceil(chunk_count);
     assert!(chunk_size > 0);
+    let mut chunk_hashes = vec![];
 
     for (chunk_id, chunk) in logs.chunks(chunk_size).enumerate() {
         let chunk_storage_logs = SnapshotStorageLogsChunk {
             storage_logs: chunk.to_vec(),
         };
         let chunk_key = SnapshotStorageLogsStorageKey {
-            l1_batch_number: status.l1_batch_number,
+            l1_batch_number,
             chunk_id: chunk_id as u64,
         };
         object_store
             .put(chunk_key, &chunk_storage_logs)
             .await
             .unwrap();
+        chunk_hashes.push(blob_hash::<SnapshotStorageLogsChunk>(object_store, chunk_key).await);
+    }
+
+    SnapshotFileHashes {
+        factory_deps: factory_deps_hash,
+        storage_logs_chunks: chunk_hashes,
     }
+}
 
+fn register_snapshot(
+    client: &mut MockMainNodeClient,
+    status: &SnapshotRecoveryStatus,
+    base_l1_batch_number: Option<L1BatchNumber>,
+    hashes: SnapshotFileHashes,
+) {
     let snapshot_header = SnapshotHeader {
         l1_batch_number: status.l1_batch_number,
+        base_l1_batch_number,
         miniblock_number: status.miniblock_number,
         last_l1_batch_with_metadata: l1_block_metadata(
             status.l1_batch_number,
             status.l1_batch_root_hash,
         ),
-        storage_logs_chunks: vec![
-            SnapshotStorageLogsChunkMetadata {
-                chunk_id: 0,
-                filepath: "file0".to_string(),
-            },
-            SnapshotStorageLogsChunkMetadata {
-                chunk_id: 1,
-                filepath: "file1".to_string(),
-            },
-        ],
+        storage_logs_chunks: hashes
+            .storage_logs_chunks
+            .into_iter()
+            .enumerate()
+            .map(|(chunk_id, hash)| SnapshotStorageLogsChunkMetadata {
+                chunk_id: chunk_id as u64,
+                filepath: format!("file{chunk_id}"),
+                hash: Some(hash),
+            })
+            .collect(),
         factory_deps_filepath: "some_filepath".to_string(),
+        factory_deps_hash: Some(hashes.factory_deps),
+        manifest_signature: None,
     };
-    client.fetch_newest_snapshot_response = Some(snapshot_header);
+    client.fetch_newest_snapshot_response = Some(snapshot_header.clone());
+    client
+        .fetch_snapshot_responses
+        .insert(status.l1_batch_number, snapshot_header);
     client.fetch_l2_block_responses.insert(
         status.miniblock_number,
         miniblock_metadata(
@@ -271,5 +328,68 @@ pub(super) async fn prepare_clients(
             status.miniblock_hash,
         ),
     );
+}
+
+pub(super) async fn prepare_clients(
+    status: &SnapshotRecoveryStatus,
+    logs: &[SnapshotStorageLog],
+) -> (Arc<dyn ObjectStore>, MockMainNodeClient) {
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut client = MockMainNodeClient::default();
+    let chunk_count = status.storage_logs_chunks_processed.len();
+    let hashes = put_snapshot_files(
+        &*object_store,
+        status.l1_batch_number,
+        (0..32).collect(),
+        logs,
+        chunk_count,
+    )
+    .await;
+    register_snapshot(&mut client, status, None, hashes);
     (object_store, client)
 }
+
+/// Adds an incremental snapshot on top of the newest snapshot registered in `client`.
+pub(super) async fn add_incremental_snapshot(
+    object_store: &dyn ObjectStore,
+    client: &mut MockMainNodeClient,
+    status: &SnapshotRecoveryStatus,
+    logs: &[SnapshotStorageLog],
+    chunk_count: usize,
+) {
+    let base_l1_batch_number = client
+        .fetch_newest_snapshot_response
+        .as_ref()
+        .expect("no base snapshot")
+        .l1_batch_number;
+    let hashes = put_snapshot_files(
+        object_store,
+        status.l1_batch_number,
+        (32..64).collect(),
+        logs,
+        chunk_count,
+    )
+    .await;
+    register_snapshot(client, status, Some(base_l1_batch_number), hashes);
+}
+
+/// Signs manifests for all snapshots registered in `client` with the specified private key.
+pub(super) fn sign_snapshots(client: &mut MockMainNodeClient, private_key: &H256) {
+    let domain = Eip712Domain::new(L2ChainId::default());
+    for snapshot in client.fetch_snapshot_responses.values_mut() {
+        let manifest = SnapshotManifest::from_header(snapshot).expect("no hashes in snapshot");
+        let signature = PackedEthSignature::sign_typed_data(private_key, &domain, &manifest)
+            .expect("failed signing manifest");
+        snapshot.manifest_signature = Some(signature);
+    }
+
+    let newest_snapshot = client
+        .fetch_newest_snapshot_response
+        .as_mut()
+        .expect("no snapshots");
+    newest_snapshot.manifest_signature = client.fetch_snapshot_responses
+        [&newest_snapshot.l1_batch_number]
+        .manifest_signature
+        .clone();
+}
@@ -214,6 +214,18 @@ impl RocksdbStorage {
             .map(RocksbStorageBuilder)
     }
 
+    /// Creates a copy of this storage sharing the same RocksDB instance (and thus its block cache). The copy can be used
+    /// for concurrent reads (e.g., to prefetch storage slots from other threads); it must not be used to update the storage.
+    pub fn read_only_copy(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            pending_patch: self.pending_patch.clone(),
+            enum_index_migration_chunk_size: self.enum_index_migration_chunk_size,
+            #[cfg(test)]
+            listener: RocksdbStorageEventListener::default(),
+        }
+    }
+
     async fn new(path: PathBuf) -> anyhow::Result<Self> {
         tokio::task::spawn_blocking(move || {
             Ok(Self {
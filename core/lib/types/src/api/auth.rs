@@ -0,0 +1,242 @@
+//! Session tokens used to authenticate callers of the Web3 API in the private mode.
+
+use std::{fmt, str::FromStr};
+
+use zksync_basic_types::{Address, L2ChainId, H256};
+
+use crate::tx::primitives::{
+    ecdsa_signature::Error as ParityCryptoError, EIP712TypedStructure, Eip712Domain,
+    PackedEthSignature, StructBuilder,
+};
+
+/// Session token authorizing its bearer to read data related to `account` from a Web3 API server
+/// running in the private mode. The token is signed by the account itself using EIP-712, so it can be issued
+/// by any wallet without interacting with the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiSessionToken {
+    pub account: Address,
+    /// UNIX timestamp (in seconds) after which the token is no longer valid.
+    pub expires_at: u64,
+}
+
+impl ApiSessionToken {
+    /// Returns bytes signed by the account for this token.
+    pub fn signed_bytes(&self, chain_id: L2ChainId) -> H256 {
+        PackedEthSignature::typed_data_to_signed_bytes(&Eip712Domain::new(chain_id), self)
+    }
+
+    /// Signs this token with the provided private key, which should correspond to `account`.
+    pub fn sign(
+        self,
+        private_key: &H256,
+        chain_id: L2ChainId,
+    ) -> Result<SignedApiSessionToken, ParityCryptoError> {
+        let domain = Eip712Domain::new(chain_id);
+        let signature = PackedEthSignature::sign_typed_data(private_key, &domain, &self)?;
+        Ok(SignedApiSessionToken {
+            token: self,
+            signature,
+        })
+    }
+}
+
+impl EIP712TypedStructure for ApiSessionToken {
+    const TYPE_NAME: &'static str = "ApiSessionToken";
+
+    fn build_structure<BUILDER: StructBuilder>(&self, builder: &mut BUILDER) {
+        builder.add_member("account", &self.account);
+        builder.add_member("expiresAt", &self.expires_at);
+    }
+}
+
+/// Errors that can occur when parsing or verifying an [`SignedApiSessionToken`].
+#[derive(Debug, thiserror::Error)]
+pub enum SessionTokenError {
+    #[error("malformed session token: {0}")]
+    Malformed(&'static str),
+    #[error("session token has expired")]
+    Expired,
+    #[error("session token lifetime exceeds the maximum allowed lifetime of {max_ttl_sec}s")]
+    LifetimeTooLong { max_ttl_sec: u64 },
+    #[error("session token is not signed by its account {0:?}")]
+    InvalidSigner(Address),
+    #[error("failed recovering session token signer: {0}")]
+    Signature(#[from] ParityCryptoError),
+}
+
+/// [`ApiSessionToken`] together with the account signature.
+///
+/// The token is serialized as `{account}:{expires_at}:{signature}`, where the account and the packed signature
+/// are hex-encoded with the `0x` prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedApiSessionToken {
+    pub token: ApiSessionToken,
+    pub signature: PackedEthSignature,
+}
+
+impl SignedApiSessionToken {
+    /// Verifies this token at the specified UNIX timestamp (in seconds). Tokens expiring more than `max_ttl_sec`
+    /// seconds after `now` are rejected, so that a leaked token cannot be used indefinitely. Returns the authenticated account.
+    pub fn verify(
+        &self,
+        chain_id: L2ChainId,
+        now: u64,
+        max_ttl_sec: u64,
+    ) -> Result<Address, SessionTokenError> {
+        if self.token.expires_at < now {
+            return Err(SessionTokenError::Expired);
+        }
+        if self.token.expires_at - now > max_ttl_sec {
+            return Err(SessionTokenError::LifetimeTooLong { max_ttl_sec });
+        }
+        let signer = self
+            .signature
+            .signature_recover_signer(&self.token.signed_bytes(chain_id))?;
+        if signer != self.token.account {
+            return Err(SessionTokenError::InvalidSigner(self.token.account));
+        }
+        Ok(signer)
+    }
+}
+
+impl fmt::Display for SignedApiSessionToken {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{:?}:{}:0x{}",
+            self.token.account,
+            self.token.expires_at,
+            hex::encode(self.signature.serialize_packed())
+        )
+    }
+}
+
+impl FromStr for SignedApiSessionToken {
+    type Err = SessionTokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split(':');
+        let (Some(account), Some(expires_at), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(SessionTokenError::Malformed(
+                "expected 3 colon-separated parts",
+            ));
+        };
+
+        let account = account
+            .parse()
+            .map_err(|_| SessionTokenError::Malformed("invalid account"))?;
+        let expires_at = expires_at
+            .parse()
+            .map_err(|_| SessionTokenError::Malformed("invalid expiration timestamp"))?;
+        let signature = signature.strip_prefix("0x").unwrap_or(signature);
+        let signature = hex::decode(signature)
+            .map_err(|_| SessionTokenError::Malformed("invalid signature"))?;
+        let signature = PackedEthSignature::deserialize_packed(&signature)
+            .map_err(|_| SessionTokenError::Malformed("invalid signature"))?;
+        Ok(Self {
+            token: ApiSessionToken {
+                account,
+                expires_at,
+            },
+            signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_TTL: u64 = 3_600;
+
+    #[test]
+    fn signing_and_verifying_session_token() {
+        let private_key = H256::repeat_byte(0x42);
+        let account = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let chain_id = L2ChainId::default();
+        let token = ApiSessionToken {
+            account,
+            expires_at: 1_000,
+        };
+        let signed_token = token.sign(&private_key, chain_id).unwrap();
+        assert_eq!(
+            signed_token.verify(chain_id, 500, MAX_TTL).unwrap(),
+            account
+        );
+        let err = signed_token.verify(chain_id, 1_001, MAX_TTL).unwrap_err();
+        assert!(matches!(err, SessionTokenError::Expired), "{err}");
+
+        let serialized = signed_token.to_string();
+        let parsed: SignedApiSessionToken = serialized.parse().unwrap();
+        assert_eq!(parsed, signed_token);
+
+        let other_account = Address::repeat_byte(1);
+        let forged_token = SignedApiSessionToken {
+            token: ApiSessionToken {
+                account: other_account,
+                ..token
+            },
+            signature: signed_token.signature.clone(),
+        };
+        let err = forged_token.verify(chain_id, 500, MAX_TTL).unwrap_err();
+        assert!(
+            matches!(err, SessionTokenError::InvalidSigner(addr) if addr == other_account),
+            "{err}"
+        );
+
+        let other_chain_id = L2ChainId::from(123_u32);
+        let err = signed_token
+            .verify(other_chain_id, 500, MAX_TTL)
+            .unwrap_err();
+        assert!(matches!(err, SessionTokenError::InvalidSigner(_)), "{err}");
+    }
+
+    #[test]
+    fn rejecting_session_token_with_excessive_lifetime() {
+        let private_key = H256::repeat_byte(0x42);
+        let account = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let chain_id = L2ChainId::default();
+        let token = ApiSessionToken {
+            account,
+            expires_at: 10_000,
+        };
+        let signed_token = token.sign(&private_key, chain_id).unwrap();
+
+        let err = signed_token.verify(chain_id, 1_000, MAX_TTL).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                SessionTokenError::LifetimeTooLong {
+                    max_ttl_sec: MAX_TTL
+                }
+            ),
+            "{err}"
+        );
+        // The same token becomes acceptable once its remaining lifetime is within the limit.
+        let now = 10_000 - MAX_TTL;
+        assert_eq!(
+            signed_token.verify(chain_id, now, MAX_TTL).unwrap(),
+            account
+        );
+    }
+
+    #[test]
+    fn parsing_malformed_session_tokens() {
+        let account = Address::repeat_byte(1);
+        let tokens = [
+            String::new(),
+            format!("{account:?}:1"),
+            format!("{account:?}:1:0x00:1"),
+            "test:1:0x00".to_owned(),
+            format!("{account:?}:-1:0x00"),
+            format!("{account:?}:1:0xtest"),
+            format!("{account:?}:1:0x00"),
+        ];
+        for token in tokens {
+            let err = token.parse::<SignedApiSessionToken>().unwrap_err();
+            assert!(matches!(err, SessionTokenError::Malformed(_)), "{token}");
+        }
+    }
+}
@@ -1,10 +1,12 @@
 //! API types related to the External Node specific methods.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::{Address, L1BatchNumber, MiniblockNumber, H256};
+use zksync_basic_types::{web3::types::Bytes, Address, L1BatchNumber, MiniblockNumber, H256, U256};
 use zksync_contracts::BaseSystemContractsHashes;
 
-use crate::ProtocolVersionId;
+use crate::{block::L1BatchHeader, zk_evm_types::LogQuery, ProtocolVersionId, StorageLog};
 
 /// Representation of the L2 block, as needed for the EN synchronization.
 /// This structure has several fields that describe *L1 batch* rather than
@@ -45,5 +47,62 @@ pub struct SyncBlock {
     pub protocol_version: ProtocolVersionId,
 }
 
+/// Changes to the node state produced by an L2 block. Used by the external node in the light sync mode,
+/// in which transactions are not re-executed locally, but rather their results are copied from the main node.
+///
+/// The integrity of the copied data is not checked when it is received; instead, it is guaranteed by
+/// the consistency checker (which compares L1 batch commitments with ones published on L1) and the Merkle tree
+/// (which recomputes the state root hash from the copied storage logs).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L2BlockStateDiff {
+    /// Number of the L2 block.
+    pub number: MiniblockNumber,
+    /// Base fee per gas used in the L2 block.
+    pub base_fee_per_gas: u64,
+    /// Execution results for transactions in the L2 block, in the order of their execution.
+    pub transactions: Vec<SyncTxResult>,
+    /// Deduplicated storage writes grouped by the hash of the transaction that produced them.
+    /// Writes produced outside of transactions (e.g., in the fictive L2 block) are attributed to the zero hash.
+    pub storage_logs: Vec<(H256, Vec<StorageLog>)>,
+    /// Events grouped by the hash of the transaction that emitted them. Same as with storage logs,
+    /// events emitted outside of transactions are attributed to the zero hash.
+    pub events: Vec<(H256, Vec<SyncEvent>)>,
+    /// Factory dependencies first published in this L2 block, keyed by the bytecode hash.
+    pub factory_deps: HashMap<H256, Bytes>,
+    /// Data necessary to seal the L1 batch. Only present for the last L2 block in the batch.
+    pub l1_batch: Option<L1BatchSealData>,
+}
+
+/// Execution result of a transaction as needed for the light sync mode of the external node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncTxResult {
+    pub hash: H256,
+    /// Error message if the transaction has failed.
+    pub error: Option<String>,
+    pub refunded_gas: u64,
+}
+
+/// Event emitted in an L2 block. Unlike `VmEvent`, doesn't specify the event location since it's implied
+/// by the containing [`L2BlockStateDiff`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEvent {
+    pub address: Address,
+    pub indexed_topics: Vec<H256>,
+    pub value: Bytes,
+}
+
+/// Data necessary to seal an L1 batch without executing it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchSealData {
+    pub header: L1BatchHeader,
+    pub initial_bootloader_contents: Vec<(usize, U256)>,
+    pub events_queue: Vec<LogQuery>,
+    pub storage_refunds: Vec<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsensusGenesis(pub serde_json::Value);
@@ -11,13 +11,16 @@ pub use crate::transaction_request::{
     Eip712Meta, SerializationTransactionError, TransactionRequest,
 };
 use crate::{
+    fee::Fee,
     protocol_version::L1VerifierConfig,
-    vm_trace::{Call, CallType},
+    vm_trace::{Call, CallType, ValidationPhase},
     web3::types::{AccessList, Index, H2048},
     Address, MiniblockNumber, ProtocolVersionId,
 };
 
+pub mod auth;
 pub mod en;
+pub mod txpool;
 
 /// Block Number
 #[derive(Copy, Clone, Debug, PartialEq, Display)]
@@ -733,3 +736,131 @@ pub struct Proof {
     pub address: Address,
     pub storage_proof: Vec<StorageProof>,
 }
+
+/// Blob published on L1 for an L1 batch. Returned by `zks_getBatchBlobs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchBlob {
+    /// 4844-compatible blob containing the batch pubdata.
+    pub blob: Bytes,
+    pub kzg_commitment: Bytes,
+    /// Proof that the blob corresponds to `kzg_commitment`.
+    pub blob_proof: Bytes,
+    pub versioned_hash: H256,
+}
+
+/// Blobs published on L1 for an L1 batch. Returned by `zks_getBatchBlobs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchBlobs {
+    pub l1_batch_number: L1BatchNumber,
+    pub commit_tx_hash: H256,
+    pub blobs: Vec<BatchBlob>,
+}
+
+/// Fee estimate returned by `zks_estimateFee`. Besides the fee itself, contains the breakdown of gas spent
+/// by the transaction during the estimation run; breakdown fields are omitted if they cannot be determined.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimate {
+    #[serde(flatten)]
+    pub fee: Fee,
+    /// Gas spent on the validation step, including the paymaster validation (if any).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation_gas: Option<U256>,
+    /// Gas spent on executing the transaction after the validation step.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_gas: Option<U256>,
+    /// Paymaster-specific information; only set for transactions with a paymaster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paymaster: Option<PaymasterFeeBreakdown>,
+}
+
+impl From<Fee> for FeeEstimate {
+    fn from(fee: Fee) -> Self {
+        Self {
+            fee,
+            validation_gas: None,
+            execution_gas: None,
+            paymaster: None,
+        }
+    }
+}
+
+/// Paymaster-related part of [`FeeEstimate`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymasterFeeBreakdown {
+    pub paymaster: Address,
+    /// Paymaster flow decoded from the paymaster input: `general` or `approvalBased`.
+    /// Not set if the input doesn't follow the `IPaymasterFlow` interface.
+    pub flow: Option<String>,
+    /// Token in which the fee is charged for the `approvalBased` flow.
+    pub token: Option<Address>,
+    /// Minimum allowance for the `approvalBased` flow.
+    pub min_allowance: Option<U256>,
+    /// Amount of `token` transferred from the initiator to the paymaster during the estimated execution.
+    pub token_amount: Option<U256>,
+    /// Decoded reason the paymaster rejected the transaction with. Only set in the data of the error returned
+    /// by `zks_estimateFee` if paymaster validation fails.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+}
+
+/// Accuracy of gas estimation for a contract method. Returned by `zks_getGasEstimationStats`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasEstimationStats {
+    pub contract_address: Address,
+    /// Method selector; empty for calls with calldata shorter than 4 bytes.
+    pub selector: Bytes,
+    /// Number of included transactions with a prior gas estimate.
+    pub samples: U64,
+    /// Number of included transactions with a prior gas estimate that have failed.
+    pub failed_samples: U64,
+    /// Mean ratio between the estimated and actually used gas.
+    pub avg_estimated_to_used_ratio: f64,
+    /// Mean share of the gas limit refunded to the user.
+    pub avg_refund_ratio: f64,
+    /// Scale factor applied when estimating gas for the method. Not set if the global scale factor is applied.
+    pub adaptive_scale_factor: Option<f64>,
+}
+
+/// Report on the transaction validation returned by `debug_traceValidation`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationTrace {
+    /// Whether the transaction passed validation.
+    pub success: bool,
+    /// Error returned by the account or paymaster, or the validation rule violation.
+    pub error: Option<String>,
+    /// First violated validation rule, if any.
+    pub violation: Option<ValidationTraceViolation>,
+    /// Unique storage reads performed during validation, in the order they were performed.
+    pub storage_accesses: Vec<ValidationTraceStorageAccess>,
+    /// Gas spent on the validation step, including paymaster validation (if any).
+    pub gas_used: U256,
+    /// Computational gas spent on account validation. Only account validation is restricted in computational gas.
+    pub computational_gas_used: u32,
+    pub computational_gas_limit: u32,
+}
+
+/// Violated validation rule with the call stack context.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationTraceViolation {
+    pub rule: String,
+    /// Addresses of the call frames (from the outermost to the innermost one) at the moment the rule was violated.
+    pub call_stack: Vec<Address>,
+}
+
+/// Storage slot read during transaction validation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationTraceStorageAccess {
+    pub phase: ValidationPhase,
+    pub address: Address,
+    pub key: H256,
+    /// Whether the read is allowed by the validation rules.
+    pub allowed: bool,
+}
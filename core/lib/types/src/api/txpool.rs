@@ -0,0 +1,59 @@
+//! Types returned by the `txpool` Web3 namespace. The response formats follow the namesake Geth namespace.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{
+    web3::types::{Bytes, U256, U64},
+    Address, H256,
+};
+
+/// L2 transactions grouped by initiator account and nonce.
+pub type TxpoolTransactions<T> = BTreeMap<Address, BTreeMap<u32, T>>;
+
+/// L2 transaction in the mempool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolTransaction {
+    pub hash: H256,
+    pub nonce: U256,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    pub gas: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub gas_per_pubdata_limit: U256,
+    pub input: Bytes,
+    /// Reason why the transaction cannot be included in a block yet. Only set for queued transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queued_reason: Option<String>,
+}
+
+/// Full content of the mempool.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolContent {
+    /// Transactions that can be included in a block once all preceding transactions of the account are included.
+    pub pending: TxpoolTransactions<TxpoolTransaction>,
+    /// Transactions queued behind a nonce gap.
+    pub queued: TxpoolTransactions<TxpoolTransaction>,
+}
+
+/// Textual summary of the mempool content.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolInspect {
+    pub pending: TxpoolTransactions<String>,
+    pub queued: TxpoolTransactions<String>,
+}
+
+/// Number of transactions in the mempool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolStatus {
+    pub pending: U64,
+    pub queued: U64,
+    /// Number of L1 (priority) transactions waiting to be included.
+    pub priority: U64,
+    /// Total size of L2 transactions in the mempool in bytes.
+    pub size_bytes: U64,
+}
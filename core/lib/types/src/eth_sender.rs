@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{aggregated_operations::AggregatedActionType, Address, Nonce, H256};
+use crate::{aggregated_operations::AggregatedActionType, Address, L1BatchNumber, Nonce, H256};
 
 /// A forward-compatible `enum` describing a EIP4844 sidecar
 ///
@@ -38,6 +38,18 @@ pub struct EthTxBlobSidecarV1 {
     pub blobs: Vec<SidecarBlobV1>,
 }
 
+/// Blobs published on L1 by the commit transaction for a single L1 batch.
+///
+/// Beacon nodes prune blob sidecars after a couple of weeks, so these are archived in the object store
+/// to keep the batch pubdata available indefinitely.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct L1BatchBlobs {
+    pub l1_batch_number: L1BatchNumber,
+    /// Hash of the confirmed commit transaction that has published the blobs.
+    pub commit_tx_hash: H256,
+    pub blobs: Vec<SidecarBlobV1>,
+}
+
 #[derive(Clone)]
 pub struct EthTx {
     pub id: u32,
@@ -153,6 +153,40 @@ static PUBLISHED_BYTECODE_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     )
 });
 
+/// Signature of the ERC-20 `Transfer(address,address,uint256)` event.
+pub static ERC20_TRANSFER_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "Transfer",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+        ],
+    )
+});
+
+/// Returns the total amount of `token` transferred from `from` to `to` according to ERC-20 `Transfer` events.
+pub fn extract_erc20_transferred_amount(
+    events: &[VmEvent],
+    token: Address,
+    from: Address,
+    to: Address,
+) -> U256 {
+    events
+        .iter()
+        .filter(|event| {
+            event.address == token
+                && event.indexed_topics.len() == 3
+                && event.indexed_topics[0] == *ERC20_TRANSFER_EVENT_SIGNATURE
+                && h256_to_account_address(&event.indexed_topics[1]) == from
+                && h256_to_account_address(&event.indexed_topics[2]) == to
+                && event.value.len() == 32
+        })
+        .fold(U256::zero(), |acc, event| {
+            acc.saturating_add(U256::from_big_endian(&event.value))
+        })
+}
+
 // moved from Runtime Context
 pub fn extract_added_tokens(
     l2_erc20_bridge_addr: Address,
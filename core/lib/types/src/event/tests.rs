@@ -1,4 +1,5 @@
 use zksync_system_constants::{BOOTLOADER_ADDRESS, L2_ETH_TOKEN_ADDRESS};
+use zksync_utils::address_to_h256;
 
 use super::*;
 
@@ -169,3 +170,31 @@ fn test_convert_vm_events_to_log_queries() {
         assert_eq!(actual_list, expected_list);
     }
 }
+
+#[test]
+fn test_extract_erc20_transferred_amount() {
+    let token = Address::repeat_byte(1);
+    let from = Address::repeat_byte(2);
+    let to = Address::repeat_byte(3);
+    let transfer_event = |address: Address, from: Address, to: Address, amount: u64| VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address,
+        indexed_topics: vec![
+            *ERC20_TRANSFER_EVENT_SIGNATURE,
+            address_to_h256(&from),
+            address_to_h256(&to),
+        ],
+        value: u256_to_bytes_be(&amount.into()),
+    };
+
+    let events = [
+        transfer_event(token, from, to, 100),
+        transfer_event(token, from, to, 23),
+        // Transfers of other tokens or between other accounts should be ignored.
+        transfer_event(Address::repeat_byte(4), from, to, 1_000),
+        transfer_event(token, to, from, 1_000),
+        transfer_event(token, from, Address::repeat_byte(5), 1_000),
+    ];
+    let amount = extract_erc20_transferred_amount(&events, token, from, to);
+    assert_eq!(amount, U256::from(123));
+}
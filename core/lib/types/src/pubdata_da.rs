@@ -9,6 +9,8 @@ use zksync_config::configs::eth_sender::PubdataSendingMode;
 pub enum PubdataDA {
     Calldata = 0,
     Blobs,
+    /// Pubdata is published to an external DA layer; only the DA inclusion data is sent to L1.
+    Custom,
 }
 
 impl From<PubdataSendingMode> for PubdataDA {
@@ -16,6 +18,7 @@ impl From<PubdataSendingMode> for PubdataDA {
         match value {
             PubdataSendingMode::Calldata => PubdataDA::Calldata,
             PubdataSendingMode::Blobs => PubdataDA::Blobs,
+            PubdataSendingMode::Custom => PubdataDA::Custom,
         }
     }
 }
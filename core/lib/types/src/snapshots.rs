@@ -2,12 +2,20 @@ use std::{convert::TryFrom, ops};
 
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::{AccountTreeId, L1BatchNumber, MiniblockNumber, H256};
+use zksync_basic_types::{
+    web3::signing::keccak256, AccountTreeId, Address, L1BatchNumber, L2ChainId, MiniblockNumber,
+    H256,
+};
 use zksync_protobuf::{required, ProtoFmt};
 use zksync_utils::u256_to_h256;
 
 use crate::{
-    commitment::L1BatchWithMetadata, Bytes, ProtocolVersionId, StorageKey, StorageValue, U256,
+    commitment::L1BatchWithMetadata,
+    tx::primitives::{
+        ecdsa_signature::Error as ParityCryptoError, EIP712TypedStructure, Eip712Domain,
+        PackedEthSignature, StructBuilder,
+    },
+    Bytes, ProtocolVersionId, StorageKey, StorageValue, U256,
 };
 
 /// Information about all snapshots persisted by the node.
@@ -24,11 +32,21 @@ pub struct AllSnapshots {
 pub struct SnapshotMetadata {
     /// L1 batch for the snapshot. The data in the snapshot captures node storage at the end of this batch.
     pub l1_batch_number: L1BatchNumber,
+    /// L1 batch of the snapshot this snapshot is based on. If set, the snapshot is incremental, i.e.,
+    /// it only contains storage logs and factory deps added after the base snapshot.
+    pub base_l1_batch_number: Option<L1BatchNumber>,
     /// Path to the factory dependencies blob.
     pub factory_deps_filepath: String,
+    /// Hash of the factory dependencies blob. `None` for snapshots created before hashes were recorded.
+    pub factory_deps_hash: Option<H256>,
     /// Paths to the storage log blobs. Ordered by the chunk ID. If a certain chunk is not produced yet,
     /// the corresponding path is `None`.
     pub storage_logs_filepaths: Vec<Option<String>>,
+    /// Hashes of the storage log blobs. Ordered by the chunk ID; `None` if a chunk is not produced yet
+    /// or the hash is not recorded.
+    pub storage_logs_hashes: Vec<Option<H256>>,
+    /// Operator signature for the snapshot manifest (see [`SnapshotManifest`]).
+    pub manifest_signature: Option<PackedEthSignature>,
 }
 
 impl SnapshotMetadata {
@@ -36,6 +54,11 @@ impl SnapshotMetadata {
     pub fn is_complete(&self) -> bool {
         self.storage_logs_filepaths.iter().all(Option::is_some)
     }
+
+    /// Checks whether a snapshot is incremental, i.e., must be applied on top of its base snapshot.
+    pub fn is_incremental(&self) -> bool {
+        self.base_l1_batch_number.is_some()
+    }
 }
 
 /// Snapshot data returned by using JSON-RPC API.
@@ -44,11 +67,21 @@ impl SnapshotMetadata {
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotHeader {
     pub l1_batch_number: L1BatchNumber,
+    /// L1 batch of the snapshot this snapshot is based on. If set, the snapshot is incremental
+    /// and must be applied on top of the base snapshot (which may be incremental itself).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_l1_batch_number: Option<L1BatchNumber>,
     pub miniblock_number: MiniblockNumber,
     /// Ordered by chunk IDs.
     pub storage_logs_chunks: Vec<SnapshotStorageLogsChunkMetadata>,
     pub factory_deps_filepath: String,
+    /// Hash of the factory dependencies blob, as computed by [`snapshot_blob_hash()`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factory_deps_hash: Option<H256>,
     pub last_l1_batch_with_metadata: L1BatchWithMetadata,
+    /// Operator signature for the [`SnapshotManifest`] corresponding to this header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_signature: Option<PackedEthSignature>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -57,6 +90,81 @@ pub struct SnapshotStorageLogsChunkMetadata {
     pub chunk_id: u64,
     // can be either be a file available under HTTP(s) or local filesystem path
     pub filepath: String,
+    /// Hash of the chunk blob, as computed by [`snapshot_blob_hash()`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<H256>,
+}
+
+/// Computes the hash of a serialized snapshot blob (a storage logs chunk or factory dependencies)
+/// as it is stored in the object store.
+pub fn snapshot_blob_hash(blob: &[u8]) -> H256 {
+    H256(keccak256(blob))
+}
+
+/// Integrity manifest for a snapshot. The manifest is signed by the operator using EIP-712, so that a node
+/// recovering from a snapshot obtained from an untrusted source (e.g., a third-party mirror) can check
+/// that snapshot files were not tampered with or corrupted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotManifest {
+    pub l1_batch_number: L1BatchNumber,
+    pub base_l1_batch_number: Option<L1BatchNumber>,
+    pub miniblock_number: MiniblockNumber,
+    /// Root hash of the Merkle tree after the L1 batch.
+    pub l1_batch_root_hash: H256,
+    pub factory_deps_hash: H256,
+    /// Ordered by chunk IDs.
+    pub storage_logs_chunk_hashes: Vec<H256>,
+}
+
+impl SnapshotManifest {
+    /// Extracts a manifest from the snapshot header. Returns `None` if the header lacks some of the hashes.
+    pub fn from_header(header: &SnapshotHeader) -> Option<Self> {
+        let storage_logs_chunk_hashes = header
+            .storage_logs_chunks
+            .iter()
+            .map(|chunk| chunk.hash)
+            .collect::<Option<_>>()?;
+        Some(Self {
+            l1_batch_number: header.l1_batch_number,
+            base_l1_batch_number: header.base_l1_batch_number,
+            miniblock_number: header.miniblock_number,
+            l1_batch_root_hash: header.last_l1_batch_with_metadata.metadata.root_hash,
+            factory_deps_hash: header.factory_deps_hash?,
+            storage_logs_chunk_hashes,
+        })
+    }
+
+    /// Returns bytes signed by the operator for this manifest.
+    pub fn signed_bytes(&self, chain_id: L2ChainId) -> H256 {
+        PackedEthSignature::typed_data_to_signed_bytes(&Eip712Domain::new(chain_id), self)
+    }
+
+    /// Recovers the address of the manifest signer.
+    pub fn recover_signer(
+        &self,
+        chain_id: L2ChainId,
+        signature: &PackedEthSignature,
+    ) -> Result<Address, ParityCryptoError> {
+        signature.signature_recover_signer(&self.signed_bytes(chain_id))
+    }
+}
+
+impl EIP712TypedStructure for SnapshotManifest {
+    const TYPE_NAME: &'static str = "SnapshotManifest";
+
+    fn build_structure<BUILDER: StructBuilder>(&self, builder: &mut BUILDER) {
+        builder.add_member("l1BatchNumber", &self.l1_batch_number.0);
+        // A snapshot cannot be based on itself, so the snapshot L1 batch is used to mark full snapshots.
+        let base_l1_batch_number = self.base_l1_batch_number.unwrap_or(self.l1_batch_number);
+        builder.add_member("baseL1BatchNumber", &base_l1_batch_number.0);
+        builder.add_member("miniblockNumber", &self.miniblock_number.0);
+        builder.add_member("l1BatchRootHash", &self.l1_batch_root_hash);
+        builder.add_member("factoryDepsHash", &self.factory_deps_hash);
+        builder.add_member(
+            "storageLogsChunkHashes",
+            &self.storage_logs_chunk_hashes.as_slice(),
+        );
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -289,4 +397,43 @@ mod tests {
             assert!(max_chunk_size - min_chunk_size < U256::from(chunks_count));
         }
     }
+
+    #[test]
+    fn signing_snapshot_manifest() {
+        let manifest = SnapshotManifest {
+            l1_batch_number: L1BatchNumber(100),
+            base_l1_batch_number: Some(L1BatchNumber(50)),
+            miniblock_number: MiniblockNumber(1_000),
+            l1_batch_root_hash: H256::repeat_byte(1),
+            factory_deps_hash: H256::repeat_byte(2),
+            storage_logs_chunk_hashes: vec![H256::repeat_byte(3), H256::repeat_byte(4)],
+        };
+        let private_key = H256::repeat_byte(0x42);
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let chain_id = L2ChainId::default();
+        let domain = Eip712Domain::new(chain_id);
+        let signature =
+            PackedEthSignature::sign_typed_data(&private_key, &domain, &manifest).unwrap();
+        assert_eq!(
+            manifest.recover_signer(chain_id, &signature).unwrap(),
+            address
+        );
+
+        let mut tampered_manifest = manifest.clone();
+        tampered_manifest.storage_logs_chunk_hashes[1] = H256::repeat_byte(5);
+        assert_ne!(
+            tampered_manifest
+                .recover_signer(chain_id, &signature)
+                .unwrap(),
+            address
+        );
+        let full_manifest = SnapshotManifest {
+            base_l1_batch_number: None,
+            ..manifest
+        };
+        assert_ne!(
+            full_manifest.recover_signer(chain_id, &signature).unwrap(),
+            address
+        );
+    }
 }
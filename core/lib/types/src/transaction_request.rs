@@ -12,6 +12,7 @@ use zksync_utils::{
 
 use super::{EIP_1559_TX_TYPE, EIP_2930_TX_TYPE, EIP_712_TX_TYPE};
 use crate::{
+    ethabi::{self, ParamType, Token},
     fee::Fee,
     l1::L1Tx,
     l2::{L2Tx, TransactionType},
@@ -265,6 +266,92 @@ impl PaymasterParams {
 
         Ok(result)
     }
+
+    /// Decodes the paymaster input according to the standard `IPaymasterFlow` interface.
+    /// Returns `None` if the input doesn't correspond to any of the standard flows.
+    pub fn flow(&self) -> Option<PaymasterFlow> {
+        PaymasterFlow::decode(&self.paymaster_input)
+    }
+}
+
+/// Standard paymaster flow as defined by the `IPaymasterFlow` interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaymasterFlow {
+    /// `general(bytes input)` flow.
+    General { inner_input: Vec<u8> },
+    /// `approvalBased(address token, uint256 minAllowance, bytes innerInput)` flow. The paymaster
+    /// is expected to charge the fee in `token` from the allowance set by the bootloader.
+    ApprovalBased {
+        token: Address,
+        min_allowance: U256,
+        inner_input: Vec<u8>,
+    },
+}
+
+impl PaymasterFlow {
+    fn general_params() -> [ParamType; 1] {
+        [ParamType::Bytes]
+    }
+
+    fn approval_based_params() -> [ParamType; 3] {
+        [ParamType::Address, ParamType::Uint(256), ParamType::Bytes]
+    }
+
+    fn decode(input: &[u8]) -> Option<Self> {
+        if input.len() < 4 {
+            return None;
+        }
+        let (selector, data) = input.split_at(4);
+
+        if selector == ethabi::short_signature("general", &Self::general_params()) {
+            let tokens = ethabi::decode(&Self::general_params(), data).ok()?;
+            let [Token::Bytes(inner_input)] = <[Token; 1]>::try_from(tokens).ok()? else {
+                return None;
+            };
+            Some(Self::General { inner_input })
+        } else if selector
+            == ethabi::short_signature("approvalBased", &Self::approval_based_params())
+        {
+            let tokens = ethabi::decode(&Self::approval_based_params(), data).ok()?;
+            let [Token::Address(token), Token::Uint(min_allowance), Token::Bytes(inner_input)] =
+                <[Token; 3]>::try_from(tokens).ok()?
+            else {
+                return None;
+            };
+            Some(Self::ApprovalBased {
+                token,
+                min_allowance,
+                inner_input,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Encodes this flow as paymaster input.
+    pub fn encode(&self) -> Vec<u8> {
+        let (selector, tokens) = match self {
+            Self::General { inner_input } => (
+                ethabi::short_signature("general", &Self::general_params()),
+                vec![Token::Bytes(inner_input.clone())],
+            ),
+            Self::ApprovalBased {
+                token,
+                min_allowance,
+                inner_input,
+            } => (
+                ethabi::short_signature("approvalBased", &Self::approval_based_params()),
+                vec![
+                    Token::Address(*token),
+                    Token::Uint(*min_allowance),
+                    Token::Bytes(inner_input.clone()),
+                ],
+            ),
+        };
+        let mut input = selector.to_vec();
+        input.extend_from_slice(&ethabi::encode(&tokens));
+        input
+    }
 }
 
 #[derive(Default, Serialize, Deserialize, Clone, PartialEq, Debug)]
@@ -1530,4 +1617,35 @@ mod tests {
         let tx_request = TransactionRequest::from(call_request.clone());
         assert_eq!(tx_request.input, call_request.input.unwrap());
     }
+
+    #[test]
+    fn decoding_paymaster_flows() {
+        let token = Address::repeat_byte(0x11);
+        let flow = PaymasterFlow::ApprovalBased {
+            token,
+            min_allowance: 1_000.into(),
+            inner_input: vec![1, 2, 3],
+        };
+        let paymaster_params = PaymasterParams {
+            paymaster: Address::repeat_byte(0x22),
+            paymaster_input: flow.encode(),
+        };
+        assert_eq!(
+            paymaster_params.paymaster_input[..4],
+            [0x94, 0x94, 0x31, 0xdc]
+        );
+        assert_eq!(paymaster_params.flow(), Some(flow));
+
+        let flow = PaymasterFlow::General {
+            inner_input: vec![4, 5],
+        };
+        let paymaster_input = flow.encode();
+        assert_eq!(paymaster_input[..4], [0x8c, 0x5a, 0x34, 0x45]);
+        assert_eq!(PaymasterFlow::decode(&paymaster_input), Some(flow));
+
+        assert_eq!(PaymasterFlow::decode(&[]), None);
+        assert_eq!(PaymasterFlow::decode(&[0xde, 0xad, 0xbe, 0xef, 0]), None);
+        // Truncated input with a valid selector.
+        assert_eq!(PaymasterFlow::decode(&paymaster_input[..10]), None);
+    }
 }
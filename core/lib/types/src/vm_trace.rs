@@ -233,3 +233,37 @@ impl Display for ViolatedValidationRule {
         }
     }
 }
+
+/// Validation step of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationPhase {
+    /// Account validation (`validateTransaction`).
+    Account,
+    /// Paymaster validation (`validateAndPayForPaymasterTransaction`).
+    Paymaster,
+}
+
+/// Storage read performed during transaction validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationStorageAccess {
+    pub phase: ValidationPhase,
+    pub address: Address,
+    pub key: U256,
+    /// Whether the read is allowed by the validation rules.
+    pub is_allowed: bool,
+}
+
+/// Detailed report on transaction validation collected by the validation tracer.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// Unique storage reads in the order they were performed.
+    pub storage_accesses: Vec<ValidationStorageAccess>,
+    /// First violated validation rule, if any.
+    pub violated_rule: Option<ViolatedValidationRule>,
+    /// Addresses of the call frames (from the outermost to the innermost one) at the moment
+    /// the validation rule was violated.
+    pub violation_call_stack: Vec<Address>,
+    pub computational_gas_used: u32,
+    pub computational_gas_limit: u32,
+}
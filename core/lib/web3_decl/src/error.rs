@@ -14,7 +14,10 @@ use std::{
 use jsonrpsee::core::ClientError;
 use pin_project_lite::pin_project;
 use thiserror::Error;
-use zksync_types::{api::SerializationTransactionError, L1BatchNumber, MiniblockNumber};
+use zksync_types::{
+    api::{PaymasterFeeBreakdown, SerializationTransactionError},
+    L1BatchNumber, MiniblockNumber,
+};
 
 /// Server-side representation of the RPC error.
 #[derive(Debug, Error)]
@@ -29,6 +32,9 @@ pub enum Web3Error {
     ProxyError(#[from] EnrichedClientError),
     #[error("{0}")]
     SubmitTransactionError(String, Vec<u8>),
+    /// Paymaster rejected the transaction during fee estimation. The breakdown is returned as error data.
+    #[error("{0}")]
+    PaymasterValidationFailed(String, Box<PaymasterFeeBreakdown>),
     #[error("Failed to serialize transaction: {0}")]
     SerializationError(#[from] SerializationTransactionError),
     #[error("More than four topics in filter")]
@@ -44,6 +50,12 @@ pub enum Web3Error {
 
     #[error("Tree API is not available")]
     TreeApiUnavailable,
+    #[error("Mempool is not available on this node")]
+    MempoolUnavailable,
+    #[error("Blob archive is not available on this node")]
+    BlobArchiveUnavailable,
+    #[error("Caller is not authorized to access this data")]
+    Unauthorized,
     #[error("Internal error")]
     InternalError(#[from] anyhow::Error),
 }
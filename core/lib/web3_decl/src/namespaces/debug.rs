@@ -1,6 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig, ValidationTrace},
     debug_flat_call::DebugCallFlat,
     transaction_request::CallRequest,
 };
@@ -51,4 +51,13 @@ pub trait DebugNamespace {
         tx_hash: H256,
         options: Option<TracerConfig>,
     ) -> RpcResult<Option<DebugCall>>;
+    /// Executes the validation step of a transaction (account and paymaster validation) and reports
+    /// storage slots read during validation, the violated validation rule and computational gas used.
+    /// Fee fields and the signature should be set to the values the transaction will be submitted with.
+    #[method(name = "traceValidation")]
+    async fn trace_validation(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+    ) -> RpcResult<ValidationTrace>;
 }
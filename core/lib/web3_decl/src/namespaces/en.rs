@@ -21,6 +21,14 @@ pub trait EnNamespace {
         include_transactions: bool,
     ) -> RpcResult<Option<en::SyncBlock>>;
 
+    /// Returns changes to the node state produced by the specified L2 block. Used by the external node
+    /// in the light sync mode.
+    #[method(name = "syncL2BlockStateDiff")]
+    async fn sync_l2_block_state_diff(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<en::L2BlockStateDiff>>;
+
     #[method(name = "consensusGenesis")]
     async fn consensus_genesis(&self) -> RpcResult<Option<en::ConsensusGenesis>>;
 
@@ -3,13 +3,12 @@ use std::collections::HashMap;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        BatchBlobs, BlockDetails, BridgeAddresses, FeeEstimate, GasEstimationStats, L1BatchDetails,
+        L2ToL1LogProof, Proof, ProtocolVersion, TransactionDetails,
     },
-    fee::Fee,
     fee_model::FeeParams,
     transaction_request::CallRequest,
-    Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
+    Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
 
 use crate::types::Token;
@@ -27,8 +26,19 @@ use crate::types::Token;
     rpc(server, namespace = "zks")
 )]
 pub trait ZksNamespace {
+    /// Estimates the fee for a transaction. Besides the fee, returns its breakdown into validation and execution gas;
+    /// for transactions with a paymaster, also returns the paymaster flow and the token amount charged by the paymaster.
     #[method(name = "estimateFee")]
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee>;
+    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<FeeEstimate>;
+
+    /// Returns statistics on the accuracy of gas estimation for methods of the specified contract.
+    /// If `selector` is specified, only returns statistics for the corresponding method.
+    #[method(name = "getGasEstimationStats")]
+    async fn get_gas_estimation_stats(
+        &self,
+        address: Address,
+        selector: Option<Bytes>,
+    ) -> RpcResult<Vec<GasEstimationStats>>;
 
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
@@ -118,4 +128,12 @@ pub trait ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Option<Proof>>;
+
+    /// Returns blobs published on L1 for the specified L1 batch together with their KZG commitments and proofs.
+    /// Returns `null` if the batch wasn't committed with blobs, or its blobs are not archived yet.
+    #[method(name = "getBatchBlobs")]
+    async fn get_batch_blobs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<BatchBlobs>>;
 }
@@ -1,13 +1,16 @@
 use std::fmt;
 
-use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
+use multivm::{
+    interface::{ExecutionResult, VmExecutionResultAndLogs},
+    tracers::validator,
+};
 use zksync_types::{
     fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, Transaction,
 };
 
 use super::{
     execute::{TransactionExecutionOutput, TransactionExecutor},
-    validate::ValidationError,
+    validate::{ValidationError, ValidationStepOutput},
     BlockArgs,
 };
 
@@ -67,6 +70,22 @@ impl MockTransactionExecutor {
         }
     }
 
+    pub fn execute_validation_step(
+        &self,
+        tx: L2Tx,
+        block_args: &BlockArgs,
+    ) -> ValidationStepOutput {
+        let result = match (self.tx_responses)(&tx.into(), block_args) {
+            ExecutionResult::Halt { reason } => Err(validator::ValidationError::FailedTx(reason)),
+            _ => Ok(()),
+        };
+        ValidationStepOutput {
+            gas_used: 0,
+            result,
+            report: None,
+        }
+    }
+
     pub fn execute_tx(
         &self,
         tx: &Transaction,
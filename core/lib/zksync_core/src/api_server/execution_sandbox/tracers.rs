@@ -1,6 +1,10 @@
 use std::sync::Arc;
 
-use multivm::{tracers::CallTracer, vm_latest::HistoryMode, MultiVMTracer, MultiVmTracerPointer};
+use multivm::{
+    tracers::{CallTracer, ValidationGasTracer},
+    vm_latest::HistoryMode,
+    MultiVMTracer, MultiVmTracerPointer,
+};
 use once_cell::sync::OnceCell;
 use zksync_state::WriteStorage;
 use zksync_types::vm_trace::Call;
@@ -9,6 +13,8 @@ use zksync_types::vm_trace::Call;
 #[derive(Debug)]
 pub(crate) enum ApiTracer {
     CallTracer(Arc<OnceCell<Vec<Call>>>),
+    /// Measures gas spent on the validation step of the transaction.
+    ValidationGas(Arc<OnceCell<u32>>),
 }
 
 impl ApiTracer {
@@ -20,6 +26,9 @@ impl ApiTracer {
     ) -> MultiVmTracerPointer<S, H> {
         match self {
             ApiTracer::CallTracer(tracer) => CallTracer::new(tracer.clone()).into_tracer_pointer(),
+            ApiTracer::ValidationGas(result) => {
+                ValidationGasTracer::new(result.clone()).into_tracer_pointer()
+            }
         }
     }
 }
@@ -11,7 +11,9 @@ use multivm::{
     MultiVMTracer,
 };
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
-use zksync_types::{l2::L2Tx, Transaction, TRUSTED_ADDRESS_SLOTS, TRUSTED_TOKEN_SLOTS};
+use zksync_types::{
+    l2::L2Tx, vm_trace::ValidationReport, Transaction, TRUSTED_ADDRESS_SLOTS, TRUSTED_TOKEN_SLOTS,
+};
 
 use super::{
     apply,
@@ -30,6 +32,16 @@ pub(crate) enum ValidationError {
     Internal(#[from] anyhow::Error),
 }
 
+/// Output of executing the validation step of a transaction in the sandbox.
+#[derive(Debug)]
+pub(crate) struct ValidationStepOutput {
+    /// Gas spent on validation, including the paymaster validation (if any).
+    pub gas_used: u32,
+    pub result: Result<(), validator::ValidationError>,
+    /// Detailed validation report. Only collected on request and only for the latest VM version.
+    pub report: Option<ValidationReport>,
+}
+
 impl TransactionExecutor {
     pub(crate) async fn validate_tx_in_sandbox(
         &self,
@@ -46,6 +58,43 @@ impl TransactionExecutor {
         }
 
         let stage_latency = SANDBOX_METRICS.sandbox[&SandboxStage::ValidateInSandbox].start();
+        let execution_args = TxExecutionArgs::for_validation(&tx);
+        let output = self
+            .execute_validation_step_in_sandbox(
+                connection_pool,
+                vm_permit,
+                tx,
+                shared_args,
+                execution_args,
+                block_args,
+                computational_gas_limit,
+                false,
+            )
+            .await?;
+        stage_latency.observe();
+        output.result.map_err(ValidationError::Vm)
+    }
+
+    /// Executes the validation step of a transaction (i.e., account and paymaster validation) using the provided
+    /// execution args. Unlike [`Self::validate_tx_in_sandbox()`], this method returns gas spent on validation
+    /// and can collect a detailed validation report.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn execute_validation_step_in_sandbox(
+        &self,
+        connection_pool: ConnectionPool<Core>,
+        vm_permit: VmPermit,
+        tx: L2Tx,
+        shared_args: TxSharedArgs,
+        execution_args: TxExecutionArgs,
+        block_args: BlockArgs,
+        computational_gas_limit: u32,
+        collect_report: bool,
+    ) -> anyhow::Result<ValidationStepOutput> {
+        #[cfg(test)]
+        if let Self::Mock(mock) = self {
+            return Ok(mock.execute_validation_step(tx, &block_args));
+        }
+
         let mut connection = connection_pool
             .connection_tagged("api")
             .await
@@ -56,7 +105,6 @@ impl TransactionExecutor {
                 .context("failed getting validation params")?;
         drop(connection);
 
-        let execution_args = TxExecutionArgs::for_validation(&tx);
         let tx: Transaction = tx.into();
 
         let validation_result = tokio::task::spawn_blocking(move || {
@@ -76,6 +124,12 @@ impl TransactionExecutor {
 
                     let (tracer, validation_result) =
                         ValidationTracer::<HistoryDisabled>::new(validation_params);
+                    let (tracer, report) = if collect_report {
+                        let (tracer, report) = tracer.with_report();
+                        (tracer, Some(report))
+                    } else {
+                        (tracer, None)
+                    };
 
                     let result = vm.inspect(
                         vec![
@@ -87,6 +141,7 @@ impl TransactionExecutor {
                         VmExecutionMode::OneTx,
                     );
 
+                    let gas_used = result.statistics.gas_used;
                     let result = match (result.result, validation_result.get()) {
                         (_, Some(err)) => {
                             Err(validator::ValidationError::ViolatedRule(err.clone()))
@@ -99,7 +154,11 @@ impl TransactionExecutor {
 
                     stage_latency.observe();
                     span.exit();
-                    result
+                    ValidationStepOutput {
+                        gas_used,
+                        result,
+                        report: report.and_then(|report| report.get().cloned()),
+                    }
                 },
             );
             span.exit();
@@ -108,8 +167,7 @@ impl TransactionExecutor {
         .await
         .context("transaction validation panicked")??;
 
-        stage_latency.observe();
-        validation_result.map_err(ValidationError::Vm)
+        Ok(validation_result)
     }
 }
 
@@ -0,0 +1,296 @@
+//! Pluggable admission policies for L2 transactions submitted via [`TxSender`](super::TxSender).
+
+use std::{collections::HashSet, fmt, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use vise::{Buckets, Counter, Histogram, LabeledFamily, Metrics};
+use zksync_config::configs::api::Web3JsonRpcConfig;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_system_constants::CONTRACT_DEPLOYER_ADDRESS;
+use zksync_types::{l2::L2Tx, Address, U256};
+
+use super::SubmitTxError;
+use crate::utils::hot_reload::HotReloadedFile;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_tx_admission")]
+struct TxAdmissionMetrics {
+    /// Number of transactions rejected by admission policies.
+    #[metrics(labels = ["policy"])]
+    rejected: LabeledFamily<&'static str, Counter>,
+    /// Latency of checking a transaction against an admission policy.
+    #[metrics(labels = ["policy"], buckets = Buckets::LATENCIES)]
+    check_latency: LabeledFamily<&'static str, Histogram<Duration>>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<TxAdmissionMetrics> = vise::Global::new();
+
+/// Error returned by a [`TxAdmissionPolicy`].
+#[derive(Debug, thiserror::Error)]
+pub enum AdmissionError {
+    /// Transaction is rejected by the policy. The reason is returned to the API caller.
+    #[error("{0}")]
+    Rejected(String),
+    /// Internal error (e.g., a DB error) that should not be exposed to the API caller.
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Policy deciding whether an L2 transaction submitted via the API is admitted to the mempool.
+///
+/// Policies are checked by `TxSender` after basic transaction validation, but before the transaction is executed
+/// in the sandbox and propagated to the [`TxSink`](super::tx_sink::TxSink). Thus, policies should be relatively cheap
+/// to check.
+#[async_trait]
+pub trait TxAdmissionPolicy: fmt::Debug + Send + Sync + 'static {
+    /// Name of the policy used in logs, metrics and rejection messages.
+    fn name(&self) -> &'static str;
+
+    /// Checks whether the transaction should be admitted.
+    async fn check(&self, tx: &L2Tx) -> Result<(), AdmissionError>;
+}
+
+/// Checks the transaction against all `policies` in order, stopping on the first rejection.
+pub(super) async fn check_policies(
+    policies: &[Arc<dyn TxAdmissionPolicy>],
+    tx: &L2Tx,
+) -> Result<(), SubmitTxError> {
+    for policy in policies {
+        let policy_name = policy.name();
+        let latency = METRICS.check_latency[&policy_name].start();
+        let result = policy.check(tx).await;
+        latency.observe();
+
+        match result {
+            Ok(()) => { /* continue checking other policies */ }
+            Err(AdmissionError::Rejected(reason)) => {
+                tracing::info!(
+                    "Transaction {:?} is rejected by admission policy `{policy_name}`: {reason}",
+                    tx.hash()
+                );
+                METRICS.rejected[&policy_name].inc();
+                return Err(SubmitTxError::AdmissionRejected {
+                    policy: policy_name,
+                    reason,
+                });
+            }
+            Err(AdmissionError::Internal(err)) => {
+                let err = err.context(format!("failed checking admission policy `{policy_name}`"));
+                return Err(SubmitTxError::Internal(err));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Builds admission policies specified in the Web3 API config.
+pub async fn build_policies(
+    config: &Web3JsonRpcConfig,
+    replica_pool: ConnectionPool<Core>,
+) -> anyhow::Result<Vec<Arc<dyn TxAdmissionPolicy>>> {
+    let mut policies: Vec<Arc<dyn TxAdmissionPolicy>> = vec![];
+    if let Some(path) = config.tx_deny_list_path() {
+        let deny_list = AddressDenyList::new(path)
+            .await
+            .with_context(|| format!("failed loading address deny list from `{path}`"))?;
+        policies.push(Arc::new(deny_list));
+    }
+    if let Some(allowed_deployers) = &config.tx_deployer_allow_list {
+        let allow_list = DeployerAllowList::new(allowed_deployers.iter().copied());
+        policies.push(Arc::new(allow_list));
+    }
+    if let Some(limit) = config.max_pending_txs_per_sender {
+        policies.push(Arc::new(PendingTxsPerSenderLimit::new(replica_pool, limit)));
+    }
+    if let Some(min_priority_fee) = config.min_priority_fee_per_gas {
+        policies.push(Arc::new(MinPriorityFee::new(min_priority_fee.into())));
+    }
+    Ok(policies)
+}
+
+/// Denies transactions sent from, to or sponsored by (as a paymaster) the addresses listed in a file.
+///
+/// The file contains one hex-encoded address per line; empty lines and `#` comments are ignored. The file
+/// is checked for modifications by a background task at most once per poll interval and is reloaded if it has changed.
+/// If the modified file cannot be read or parsed, the previously loaded list remains in effect.
+#[derive(Debug)]
+pub struct AddressDenyList {
+    pub(super) addresses: HotReloadedFile<HashSet<Address>>,
+}
+
+impl AddressDenyList {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+    /// Loads the deny list from the specified file.
+    pub async fn new(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        Self::with_poll_interval(path, Self::DEFAULT_POLL_INTERVAL).await
+    }
+
+    /// Loads the deny list from the specified file, checking it for modifications with the specified interval.
+    pub async fn with_poll_interval(
+        path: impl Into<PathBuf>,
+        poll_interval: Duration,
+    ) -> anyhow::Result<Self> {
+        let addresses =
+            HotReloadedFile::new(path, "address deny list", poll_interval, Self::parse).await?;
+        tracing::info!(
+            "Address deny list contains {} addresses",
+            addresses.get().len()
+        );
+        Ok(Self { addresses })
+    }
+
+    fn parse(contents: &str) -> anyhow::Result<HashSet<Address>> {
+        let mut addresses = HashSet::new();
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let address = line.strip_prefix("0x").unwrap_or(line);
+            let address = address
+                .parse()
+                .with_context(|| format!("invalid address on line {}: `{line}`", i + 1))?;
+            addresses.insert(address);
+        }
+        Ok(addresses)
+    }
+}
+
+#[async_trait]
+impl TxAdmissionPolicy for AddressDenyList {
+    fn name(&self) -> &'static str {
+        "address_deny_list"
+    }
+
+    async fn check(&self, tx: &L2Tx) -> Result<(), AdmissionError> {
+        let addresses = self.addresses.get();
+        let initiator = tx.initiator_account();
+        if addresses.contains(&initiator) {
+            return Err(AdmissionError::Rejected(format!(
+                "sender {initiator:?} is not allowed to send transactions"
+            )));
+        }
+        let recipient = tx.execute.contract_address;
+        if addresses.contains(&recipient) {
+            return Err(AdmissionError::Rejected(format!(
+                "recipient {recipient:?} is not allowed to receive transactions"
+            )));
+        }
+        let paymaster = tx.common_data.paymaster_params.paymaster;
+        if addresses.contains(&paymaster) {
+            return Err(AdmissionError::Rejected(format!(
+                "paymaster {paymaster:?} is not allowed to sponsor transactions"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Only allows the listed accounts to deploy contracts, i.e., to call the `ContractDeployer` system contract directly.
+#[derive(Debug)]
+pub struct DeployerAllowList {
+    allowed_deployers: HashSet<Address>,
+}
+
+impl DeployerAllowList {
+    pub fn new(allowed_deployers: impl IntoIterator<Item = Address>) -> Self {
+        Self {
+            allowed_deployers: allowed_deployers.into_iter().collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl TxAdmissionPolicy for DeployerAllowList {
+    fn name(&self) -> &'static str {
+        "deployer_allow_list"
+    }
+
+    async fn check(&self, tx: &L2Tx) -> Result<(), AdmissionError> {
+        let initiator = tx.initiator_account();
+        if tx.execute.contract_address == CONTRACT_DEPLOYER_ADDRESS
+            && !self.allowed_deployers.contains(&initiator)
+        {
+            return Err(AdmissionError::Rejected(format!(
+                "account {initiator:?} is not allowed to deploy contracts"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Limits the number of pending transactions in the mempool per sender.
+#[derive(Debug)]
+pub struct PendingTxsPerSenderLimit {
+    pool: ConnectionPool<Core>,
+    limit: u32,
+}
+
+impl PendingTxsPerSenderLimit {
+    pub fn new(pool: ConnectionPool<Core>, limit: u32) -> Self {
+        Self { pool, limit }
+    }
+}
+
+#[async_trait]
+impl TxAdmissionPolicy for PendingTxsPerSenderLimit {
+    fn name(&self) -> &'static str {
+        "pending_txs_per_sender"
+    }
+
+    async fn check(&self, tx: &L2Tx) -> Result<(), AdmissionError> {
+        let initiator = tx.initiator_account();
+        let mut storage = self
+            .pool
+            .connection_tagged("api")
+            .await
+            .context("failed acquiring connection to replica DB")?;
+        let pending_count = storage
+            .transactions_web3_dal()
+            .get_pending_txs_count_by_initiator_account(initiator)
+            .await
+            .with_context(|| format!("failed getting pending transactions for {initiator:?}"))?;
+        if pending_count >= self.limit as usize {
+            return Err(AdmissionError::Rejected(format!(
+                "sender {initiator:?} has {pending_count} pending transactions, while at most {} are allowed",
+                self.limit
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Requires transactions to specify a max priority fee per gas no less than the configured minimum.
+#[derive(Debug)]
+pub struct MinPriorityFee {
+    min_priority_fee_per_gas: U256,
+}
+
+impl MinPriorityFee {
+    pub fn new(min_priority_fee_per_gas: U256) -> Self {
+        Self {
+            min_priority_fee_per_gas,
+        }
+    }
+}
+
+#[async_trait]
+impl TxAdmissionPolicy for MinPriorityFee {
+    fn name(&self) -> &'static str {
+        "min_priority_fee"
+    }
+
+    async fn check(&self, tx: &L2Tx) -> Result<(), AdmissionError> {
+        let priority_fee = tx.common_data.fee.max_priority_fee_per_gas;
+        if priority_fee < self.min_priority_fee_per_gas {
+            return Err(AdmissionError::Rejected(format!(
+                "max priority fee per gas {priority_fee} is lower than the required minimum {}",
+                self.min_priority_fee_per_gas
+            )));
+        }
+        Ok(())
+    }
+}
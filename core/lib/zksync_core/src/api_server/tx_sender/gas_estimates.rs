@@ -0,0 +1,299 @@
+//! Tracking of gas estimation accuracy and adaptive gas estimation scale factors.
+
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use lru::LruCache;
+use vise::{Buckets, Counter, Gauge, Histogram, Metrics};
+use zksync_config::configs::api::Web3JsonRpcConfig;
+use zksync_dal::{
+    gas_estimation_dal::{GasEstimate, GasEstimationSample, GasEstimationStats},
+    ConnectionPool, Core, CoreDal,
+};
+use zksync_types::{l2::L2Tx, Address, Nonce};
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Minimum number of samples for a contract method for the adaptive scale factor to be applied.
+pub(crate) const MIN_SAMPLES_FOR_ADAPTIVE_SCALING: u64 = 10;
+/// Maximum number of estimates kept in memory until the corresponding transactions are submitted.
+const RECENT_ESTIMATES_CAPACITY: usize = 10_000;
+/// Maximum number of estimates processed by [`GasEstimationStatsUpdater`] in a single iteration.
+const UPDATE_BATCH_SIZE: usize = 1_000;
+/// Estimates for transactions not included into a miniblock after this time are discarded.
+const STALE_ESTIMATE_AGE: Duration = Duration::from_secs(3_600);
+
+/// Returns the method selector for the provided calldata, or an empty slice if calldata is too short.
+pub(crate) fn method_selector(calldata: &[u8]) -> &[u8] {
+    calldata.get(..4).unwrap_or_default()
+}
+
+#[derive(Debug, Clone)]
+struct RecentEstimate {
+    contract_address: Address,
+    selector: Vec<u8>,
+    estimated_gas: u64,
+    scale_factor: f64,
+}
+
+/// Tracks gas estimates for L2 transactions so that they can be compared with the actual gas usage
+/// once the transactions are included into miniblocks. Also provides adaptive scale factors for
+/// contract methods based on the collected statistics.
+///
+/// Recent estimates are kept in memory keyed by the transaction initiator and nonce until the transaction
+/// is submitted, and are not shared among API server instances. Hence, if the API is served by multiple replicas,
+/// a transaction estimated by one replica and submitted to another one is not sampled. Likewise, transactions
+/// proxied to the main node by external nodes are not sampled since they are estimated by the external node.
+/// This is acceptable since statistics only need a representative sample of transactions, but it means that
+/// the sample shrinks with the number of replicas unless the load balancer routes requests from the same client
+/// to the same replica.
+/// Scale factors are computed from the statistics in the (shared) DB, so they are consistent across replicas.
+#[derive(Debug)]
+pub struct GasEstimationTracker {
+    /// Pool used to persist estimates. Should point to the master DB.
+    pool: ConnectionPool<Core>,
+    base_scale_factor: f64,
+    scale_factor_bounds: Option<(f64, f64)>,
+    recent_estimates: Mutex<LruCache<(Address, Nonce), RecentEstimate>>,
+    scale_factors: RwLock<HashMap<(Address, Vec<u8>), f64>>,
+}
+
+impl GasEstimationTracker {
+    pub fn new(pool: ConnectionPool<Core>, config: &Web3JsonRpcConfig) -> Self {
+        let capacity = NonZeroUsize::new(RECENT_ESTIMATES_CAPACITY).unwrap();
+        Self {
+            pool,
+            base_scale_factor: config.estimate_gas_scale_factor,
+            scale_factor_bounds: config.estimate_gas_scale_factor_bounds(),
+            recent_estimates: Mutex::new(LruCache::new(capacity)),
+            scale_factors: RwLock::default(),
+        }
+    }
+
+    /// Returns the adaptive scale factor for the specified contract method, or `None` if adaptive scaling is disabled
+    /// or there are not enough samples for the method.
+    pub(crate) fn scale_factor(&self, contract_address: Address, selector: &[u8]) -> Option<f64> {
+        self.scale_factor_bounds?;
+        let scale_factors = self
+            .scale_factors
+            .read()
+            .expect("scale factors are poisoned");
+        scale_factors
+            .get(&(contract_address, selector.to_vec()))
+            .copied()
+    }
+
+    /// Computes the adaptive scale factor from the statistics. The base scale factor is multiplied by the mean ratio
+    /// between the used gas and the unscaled estimate, and is increased proportionally to the share of failed transactions.
+    fn adaptive_scale_factor(&self, stats: &GasEstimationStats) -> Option<f64> {
+        let (min, max) = self.scale_factor_bounds?;
+        if stats.samples < MIN_SAMPLES_FOR_ADAPTIVE_SCALING {
+            return None;
+        }
+        let usage_ratio = stats.avg_unscaled_usage_ratio()?;
+        let failure_rate = stats.failed_samples as f64 / stats.samples as f64;
+        let scale_factor = self.base_scale_factor * usage_ratio * (1.0 + failure_rate);
+        Some(scale_factor.clamp(min, max))
+    }
+
+    pub(super) fn update_scale_factors(&self, stats: &[GasEstimationStats]) {
+        let scale_factors: HashMap<_, _> = stats
+            .iter()
+            .filter_map(|stats| {
+                let key = (stats.contract_address, stats.selector.clone());
+                Some((key, self.adaptive_scale_factor(stats)?))
+            })
+            .collect();
+        GAS_ESTIMATION_METRICS
+            .adaptive_scale_factors
+            .set(scale_factors.len());
+        *self
+            .scale_factors
+            .write()
+            .expect("scale factors are poisoned") = scale_factors;
+    }
+
+    pub(super) fn record_estimate(
+        &self,
+        initiator: Address,
+        nonce: Nonce,
+        contract_address: Address,
+        selector: &[u8],
+        estimated_gas: u64,
+        scale_factor: f64,
+    ) {
+        let estimate = RecentEstimate {
+            contract_address,
+            selector: selector.to_vec(),
+            estimated_gas,
+            scale_factor,
+        };
+        self.recent_estimates
+            .lock()
+            .expect("recent estimates are poisoned")
+            .put((initiator, nonce), estimate);
+    }
+
+    /// Takes the recent estimate for a transaction being submitted, if there is one.
+    pub(super) fn take_estimate(&self, tx: &L2Tx) -> Option<GasEstimate> {
+        let key = (tx.initiator_account(), tx.common_data.nonce);
+        let estimate = self
+            .recent_estimates
+            .lock()
+            .expect("recent estimates are poisoned")
+            .pop(&key)?;
+        if estimate.contract_address != tx.execute.contract_address
+            || estimate.selector != method_selector(&tx.execute.calldata)
+        {
+            // The estimate was made for a different transaction.
+            return None;
+        }
+
+        Some(GasEstimate {
+            tx_hash: tx.hash(),
+            contract_address: estimate.contract_address,
+            selector: estimate.selector,
+            estimated_gas: estimate.estimated_gas,
+            scale_factor: estimate.scale_factor,
+        })
+    }
+
+    pub(super) async fn persist_estimate(&self, estimate: &GasEstimate) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("api").await?;
+        storage
+            .gas_estimation_dal()
+            .insert_gas_estimate(estimate)
+            .await
+            .context("insert_gas_estimate()")?;
+        Ok(())
+    }
+
+    /// Aggregates estimates for included transactions into statistics and reloads adaptive scale factors.
+    pub async fn update_stats(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("api").await?;
+        let mut transaction = storage.start_transaction().await?;
+        let samples = transaction
+            .gas_estimation_dal()
+            .take_included_gas_estimates(UPDATE_BATCH_SIZE)
+            .await
+            .context("take_included_gas_estimates()")?;
+
+        let mut stats = HashMap::<_, GasEstimationStats>::new();
+        for sample in &samples {
+            GAS_ESTIMATION_METRICS.observe_sample(sample);
+            let key = (sample.contract_address, sample.selector.clone());
+            stats
+                .entry(key)
+                .or_insert_with(|| {
+                    GasEstimationStats::new(sample.contract_address, sample.selector.clone())
+                })
+                .add_sample(sample);
+        }
+        let stats: Vec<_> = stats.into_values().collect();
+        transaction
+            .gas_estimation_dal()
+            .update_gas_estimation_stats(&stats)
+            .await
+            .context("update_gas_estimation_stats()")?;
+        let stale_estimates = transaction
+            .gas_estimation_dal()
+            .delete_stale_gas_estimates(STALE_ESTIMATE_AGE)
+            .await
+            .context("delete_stale_gas_estimates()")?;
+        transaction.commit().await?;
+
+        if stale_estimates > 0 {
+            tracing::debug!("Removed {stale_estimates} stale gas estimates");
+            GAS_ESTIMATION_METRICS
+                .stale_estimates
+                .inc_by(stale_estimates);
+        }
+
+        if self.scale_factor_bounds.is_some() {
+            let all_stats = storage
+                .gas_estimation_dal()
+                .get_all_gas_estimation_stats(MIN_SAMPLES_FOR_ADAPTIVE_SCALING)
+                .await
+                .context("get_all_gas_estimation_stats()")?;
+            self.update_scale_factors(&all_stats);
+        }
+        Ok(())
+    }
+}
+
+/// Periodically aggregates gas estimation statistics using a [`GasEstimationTracker`].
+#[derive(Debug)]
+pub struct GasEstimationStatsUpdater {
+    tracker: Arc<GasEstimationTracker>,
+    polling_interval_ms: u64,
+}
+
+impl GasEstimationStatsUpdater {
+    pub fn new(tracker: Arc<GasEstimationTracker>, polling_interval_ms: u64) -> Self {
+        Self {
+            tracker,
+            polling_interval_ms,
+        }
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for GasEstimationStatsUpdater {
+    const SERVICE_NAME: &'static str = "GasEstimationStatsUpdater";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        self.tracker.update_stats().await
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.polling_interval_ms
+    }
+}
+
+const RATIO_BUCKETS: Buckets = Buckets::values(&[
+    0.25, 0.5, 0.75, 0.9, 1.0, 1.1, 1.25, 1.5, 2.0, 3.0, 5.0, 10.0,
+]);
+
+/// Gas estimation accuracy metrics. Metrics for included transactions are computed on a sample of transactions:
+/// a transaction is only sampled if it was estimated and submitted via the same main node API server instance
+/// (see [`GasEstimationTracker`]). Transactions estimated by another replica or proxied from external nodes
+/// are not sampled.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_gas_estimation")]
+struct GasEstimationMetrics {
+    /// Ratio between the estimated and actually used gas for included transactions.
+    #[metrics(buckets = RATIO_BUCKETS)]
+    estimated_to_used_ratio: Histogram<f64>,
+    /// Share of the gas limit refunded for included transactions.
+    #[metrics(buckets = Buckets::linear(0.0..=1.0, 0.1))]
+    refund_ratio: Histogram<f64>,
+    /// Number of included transactions with a prior estimate made by the same API server instance.
+    included_transactions: Counter,
+    /// Number of included transactions with a prior estimate that have failed.
+    failed_transactions: Counter,
+    /// Number of estimates discarded because the corresponding transactions were not included.
+    stale_estimates: Counter,
+    /// Number of contract methods with an adaptive scale factor.
+    adaptive_scale_factors: Gauge<usize>,
+}
+
+impl GasEstimationMetrics {
+    fn observe_sample(&self, sample: &GasEstimationSample) {
+        self.estimated_to_used_ratio
+            .observe(sample.estimated_to_used_ratio());
+        self.refund_ratio.observe(sample.refund_ratio());
+        self.included_transactions.inc();
+        if sample.failed {
+            self.failed_transactions.inc();
+        }
+    }
+}
+
+#[vise::register]
+static GAS_ESTIMATION_METRICS: vise::Global<GasEstimationMetrics> = vise::Global::new();
@@ -1,6 +1,6 @@
 //! Helper module to submit transactions into the zkSync Network.
 
-use std::{cmp, sync::Arc, time::Instant};
+use std::{cmp, mem, sync::Arc, time::Instant};
 
 use anyhow::Context as _;
 use multivm::{
@@ -8,6 +8,7 @@ use multivm::{
     utils::{adjust_pubdata_price_for_tx, derive_base_fee_and_gas_per_pubdata, derive_overhead},
     vm_latest::constants::BLOCK_GAS_LIMIT,
 };
+use once_cell::sync::OnceCell;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{
@@ -15,26 +16,29 @@ use zksync_dal::{
 };
 use zksync_state::PostgresStorageCaches;
 use zksync_types::{
+    api,
+    event::extract_erc20_transferred_amount,
     fee::{Fee, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
     get_code_key, get_intrinsic_constants,
     l1::is_l1_tx_type,
     l2::{error::TxCheckError::TxDuplication, L2Tx},
+    transaction_request::{PaymasterFlow, PaymasterParams},
     utils::storage_key_for_eth_balance,
     AccountTreeId, Address, ExecuteTransactionCommon, L2ChainId, MiniblockNumber, Nonce,
-    PackedEthSignature, ProtocolVersionId, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
-    MAX_NEW_FACTORY_DEPS, U256,
+    PackedEthSignature, ProtocolVersionId, Transaction, VmEvent, VmVersion, H160, H256,
+    MAX_L2_TX_GAS_LIMIT, MAX_NEW_FACTORY_DEPS, U256,
 };
 use zksync_utils::h256_to_u256;
 
 pub(super) use self::result::SubmitTxError;
-use self::tx_sink::TxSink;
+use self::{admission::TxAdmissionPolicy, gas_estimates::GasEstimationTracker, tx_sink::TxSink};
 use crate::{
     api_server::{
         execution_sandbox::{
-            get_pubdata_for_factory_deps, BlockArgs, BlockStartInfo, SubmitTxStage,
-            TransactionExecutor, TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit,
-            SANDBOX_METRICS,
+            get_pubdata_for_factory_deps, ApiTracer, BlockArgs, BlockStartInfo, SubmitTxStage,
+            TransactionExecutor, TxExecutionArgs, TxSharedArgs, ValidationStepOutput,
+            VmConcurrencyLimiter, VmPermit, SANDBOX_METRICS,
         },
         tx_sender::result::ApiCallResult,
     },
@@ -43,6 +47,8 @@ use crate::{
     utils::pending_protocol_version,
 };
 
+pub mod admission;
+pub mod gas_estimates;
 pub mod master_pool_sink;
 pub mod proxy;
 mod result;
@@ -154,6 +160,10 @@ pub struct TxSenderBuilder {
     tx_sink: Arc<dyn TxSink>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Option<Arc<dyn ConditionalSealer>>,
+    /// Policies checked for each submitted transaction.
+    admission_policies: Vec<Arc<dyn TxAdmissionPolicy>>,
+    /// Tracker for gas estimation accuracy.
+    gas_estimates: Option<Arc<GasEstimationTracker>>,
 }
 
 impl TxSenderBuilder {
@@ -167,6 +177,8 @@ impl TxSenderBuilder {
             replica_connection_pool,
             tx_sink,
             sealer: None,
+            admission_policies: vec![],
+            gas_estimates: None,
         }
     }
 
@@ -175,6 +187,18 @@ impl TxSenderBuilder {
         self
     }
 
+    /// Adds a transaction admission policy. Policies are checked in the order they were added.
+    pub fn with_admission_policy(mut self, policy: Arc<dyn TxAdmissionPolicy>) -> Self {
+        self.admission_policies.push(policy);
+        self
+    }
+
+    /// Enables tracking gas estimation accuracy and adaptive gas estimation scale factors.
+    pub fn with_gas_estimates(mut self, tracker: Arc<GasEstimationTracker>) -> Self {
+        self.gas_estimates = Some(tracker);
+        self
+    }
+
     pub async fn build(
         self,
         batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
@@ -194,6 +218,8 @@ impl TxSenderBuilder {
             vm_concurrency_limiter,
             storage_caches,
             sealer,
+            admission_policies: self.admission_policies,
+            gas_estimates: self.gas_estimates,
             executor: TransactionExecutor::Real,
         }))
     }
@@ -252,6 +278,10 @@ pub struct TxSenderInner {
     storage_caches: PostgresStorageCaches,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Arc<dyn ConditionalSealer>,
+    /// Policies checked for each submitted transaction.
+    admission_policies: Vec<Arc<dyn TxAdmissionPolicy>>,
+    /// Tracker for gas estimation accuracy.
+    gas_estimates: Option<Arc<GasEstimationTracker>>,
     pub(super) executor: TransactionExecutor,
 }
 
@@ -273,6 +303,18 @@ impl TxSender {
         self.0.storage_caches.clone()
     }
 
+    /// Returns the adaptive gas estimation scale factor for the specified contract method, if any.
+    pub(crate) fn gas_estimation_scale_factor(
+        &self,
+        contract_address: Address,
+        selector: &[u8],
+    ) -> Option<f64> {
+        self.0
+            .gas_estimates
+            .as_ref()?
+            .scale_factor(contract_address, selector)
+    }
+
     async fn acquire_replica_connection(&self) -> anyhow::Result<Connection<'_, Core>> {
         self.0
             .replica_connection_pool
@@ -286,6 +328,7 @@ impl TxSender {
         let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::Validate].start();
         self.validate_tx(&tx).await?;
         stage_latency.observe();
+        admission::check_policies(&self.0.admission_policies, &tx).await?;
 
         let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::DryRun].start();
         let shared_args = self.shared_args().await;
@@ -346,11 +389,29 @@ impl TxSender {
         let nonce = tx.common_data.nonce.0;
         let hash = tx.hash();
         let initiator_account = tx.initiator_account();
+        let gas_estimate = self
+            .0
+            .gas_estimates
+            .as_ref()
+            .and_then(|tracker| Some((tracker, tracker.take_estimate(&tx)?)));
         let submission_res_handle = self
             .0
             .tx_sink
             .submit_tx(tx, execution_output.metrics)
             .await?;
+        if let Some((tracker, estimate)) = gas_estimate {
+            if matches!(
+                submission_res_handle,
+                L2TxSubmissionResult::Added | L2TxSubmissionResult::Replaced
+            ) {
+                // Failing to persist the estimate must not fail the transaction submission.
+                if let Err(err) = tracker.persist_estimate(&estimate).await {
+                    tracing::warn!(
+                        "Failed persisting gas estimate for transaction {hash:?}: {err:#}"
+                    );
+                }
+            }
+        }
 
         match submission_res_handle {
             L2TxSubmissionResult::AlreadyExecuted => {
@@ -564,6 +625,7 @@ impl TxSender {
         block_args: BlockArgs,
         base_fee: u64,
         vm_version: VmVersion,
+        custom_tracers: Vec<ApiTracer>,
     ) -> anyhow::Result<(VmExecutionResultAndLogs, TransactionExecutionMetrics)> {
         let gas_limit_with_overhead = tx_gas_limit
             + derive_overhead(
@@ -609,7 +671,7 @@ impl TxSender {
                 self.0.replica_connection_pool.clone(),
                 tx.clone(),
                 block_args,
-                vec![],
+                custom_tracers,
             )
             .await?;
         Ok((execution_output.vm, execution_output.metrics))
@@ -631,10 +693,80 @@ impl TxSender {
 
     pub async fn get_txs_fee_in_wei(
         &self,
-        mut tx: Transaction,
+        tx: Transaction,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u32,
     ) -> Result<Fee, SubmitTxError> {
+        let estimate = self
+            .estimate_fee(tx, estimated_fee_scale_factor, acceptable_overestimation)
+            .await?;
+        Ok(estimate.fee)
+    }
+
+    /// Estimates the fee for a transaction and breaks it down into validation and execution gas. For transactions
+    /// with a paymaster, also decodes the paymaster flow and determines the token amount charged by the paymaster.
+    /// The breakdown is computed from the final step of the fee estimation, so it doesn't require additional VM runs.
+    /// If the paymaster rejects the transaction, the returned error contains the paymaster breakdown
+    /// with the decoded revert reason.
+    pub async fn get_txs_fee_estimate(
+        &self,
+        tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+    ) -> Result<api::FeeEstimate, SubmitTxError> {
+        let paymaster_params = match &tx.common_data {
+            ExecuteTransactionCommon::L2(data)
+                if data.paymaster_params.paymaster != Address::zero() =>
+            {
+                Some(data.paymaster_params.clone())
+            }
+            _ => None,
+        };
+        let initiator = tx.initiator_account();
+
+        let estimate = self
+            .estimate_fee(tx, estimated_fee_scale_factor, acceptable_overestimation)
+            .await;
+        let estimate = match (estimate, &paymaster_params) {
+            (Ok(estimate), _) => estimate,
+            (Err(SubmitTxError::PaymasterValidationFailed(reason)), Some(params)) => {
+                let mut paymaster = paymaster_fee_breakdown(params);
+                paymaster.revert_reason = Some(reason);
+                return Err(SubmitTxError::PaymasterFeeEstimationFailed(Box::new(
+                    paymaster,
+                )));
+            }
+            (Err(err), _) => return Err(err),
+        };
+        let total_gas = estimate.gas_used;
+        let validation_gas = estimate.validation_gas.map(|gas| gas.min(total_gas));
+        let paymaster = paymaster_params.map(|params| {
+            let mut paymaster = paymaster_fee_breakdown(&params);
+            if let Some(token) = paymaster.token {
+                paymaster.token_amount = Some(extract_erc20_transferred_amount(
+                    &estimate.events,
+                    token,
+                    initiator,
+                    params.paymaster,
+                ));
+            }
+            paymaster
+        });
+
+        Ok(api::FeeEstimate {
+            fee: estimate.fee,
+            validation_gas: validation_gas.map(U256::from),
+            execution_gas: validation_gas.map(|gas| U256::from(total_gas - gas)),
+            paymaster,
+        })
+    }
+
+    async fn estimate_fee(
+        &self,
+        mut tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+    ) -> Result<FeeEstimate, SubmitTxError> {
         let estimation_started_at = Instant::now();
 
         let mut connection = self.acquire_replica_connection().await?;
@@ -774,6 +906,7 @@ impl TxSender {
                     block_args,
                     base_fee,
                     protocol_version.into(),
+                    vec![],
                 )
                 .await
                 .context("estimate_gas step failed")?;
@@ -798,13 +931,28 @@ impl TxSender {
             .estimate_gas_binary_search_iterations
             .observe(number_of_iterations);
 
+        // For L2 transactions, gas estimation accuracy is tracked per contract method, and the scale factor
+        // may be adapted to the method.
+        let selector = gas_estimates::method_selector(&tx.execute.calldata);
+        let tracked_estimate = match (&self.0.gas_estimates, &tx.common_data) {
+            (Some(tracker), ExecuteTransactionCommon::L2(data)) => {
+                Some((tracker, data.initiator_address, data.nonce))
+            }
+            _ => None,
+        };
+        let estimated_fee_scale_factor = tracked_estimate
+            .and_then(|(tracker, ..)| tracker.scale_factor(tx.execute.contract_address, selector))
+            .unwrap_or(estimated_fee_scale_factor);
+
         let tx_body_gas_limit = cmp::min(
             MAX_L2_TX_GAS_LIMIT as u32,
             ((upper_bound as f64) * estimated_fee_scale_factor) as u32,
         );
 
         let suggested_gas_limit = tx_body_gas_limit + gas_for_bytecodes_pubdata;
-        let (result, tx_metrics) = self
+        // The final step also measures gas spent on validation, so that the estimated fee can be broken down.
+        let validation_gas = Arc::new(OnceCell::new());
+        let (mut result, tx_metrics) = self
             .estimate_gas_step(
                 vm_permit,
                 tx.clone(),
@@ -814,10 +962,13 @@ impl TxSender {
                 block_args,
                 base_fee,
                 protocol_version.into(),
+                vec![ApiTracer::ValidationGas(validation_gas.clone())],
             )
             .await
             .context("final estimate_gas step failed")?;
 
+        let gas_used = result.statistics.gas_used;
+        let events = mem::take(&mut result.logs.events);
         result.into_api_call_result()?;
         self.ensure_tx_executable(tx.clone(), &tx_metrics, false)?;
 
@@ -856,14 +1007,64 @@ impl TxSender {
                 }
             };
 
-        Ok(Fee {
+        let fee = Fee {
             max_fee_per_gas: base_fee.into(),
             max_priority_fee_per_gas: 0u32.into(),
             gas_limit: full_gas_limit.into(),
             gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
+        };
+
+        if let Some((tracker, initiator, nonce)) = tracked_estimate {
+            // Only the transaction body gas is scaled, so the effective scale factor for the full gas limit is lower.
+            let unscaled_gas_limit =
+                u64::from(upper_bound) + u64::from(gas_for_bytecodes_pubdata) + u64::from(overhead);
+            let scale_factor = f64::from(full_gas_limit) / unscaled_gas_limit.max(1) as f64;
+            tracker.record_estimate(
+                initiator,
+                nonce,
+                tx.execute.contract_address,
+                selector,
+                full_gas_limit.into(),
+                scale_factor,
+            );
+        }
+
+        Ok(FeeEstimate {
+            fee,
+            gas_used,
+            events,
+            validation_gas: validation_gas.get().copied(),
         })
     }
 
+    /// Executes the validation step of a transaction in the same way as during transaction submission,
+    /// collecting a detailed validation report.
+    pub(super) async fn trace_validation(
+        &self,
+        tx: L2Tx,
+        block_args: BlockArgs,
+    ) -> Result<ValidationStepOutput, SubmitTxError> {
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+
+        let execution_args = TxExecutionArgs::for_validation(&tx);
+        let output = self
+            .0
+            .executor
+            .execute_validation_step_in_sandbox(
+                self.0.replica_connection_pool.clone(),
+                vm_permit,
+                tx,
+                self.shared_args().await,
+                execution_args,
+                block_args,
+                self.0.sender_config.validation_computational_gas_limit,
+                true,
+            )
+            .await?;
+        Ok(output)
+    }
+
     pub(super) async fn eth_call(
         &self,
         block_args: BlockArgs,
@@ -945,6 +1146,40 @@ impl TxSender {
     }
 }
 
+/// Output of the fee estimation.
+#[derive(Debug)]
+struct FeeEstimate {
+    fee: Fee,
+    /// Gas used by the transaction executed with the estimated gas limit.
+    gas_used: u32,
+    /// Events emitted by the transaction executed with the estimated gas limit.
+    events: Vec<VmEvent>,
+    /// Gas spent on the validation step of the transaction executed with the estimated gas limit.
+    /// Not set if the VM didn't report the end of validation.
+    validation_gas: Option<u32>,
+}
+
+/// Creates a paymaster fee breakdown with the info decoded from the paymaster params.
+fn paymaster_fee_breakdown(params: &PaymasterParams) -> api::PaymasterFeeBreakdown {
+    let (flow, token, min_allowance) = match params.flow() {
+        Some(PaymasterFlow::General { .. }) => (Some("general"), None, None),
+        Some(PaymasterFlow::ApprovalBased {
+            token,
+            min_allowance,
+            ..
+        }) => (Some("approvalBased"), Some(token), Some(min_allowance)),
+        None => (None, None, None),
+    };
+    api::PaymasterFeeBreakdown {
+        paymaster: params.paymaster,
+        flow: flow.map(str::to_owned),
+        token,
+        min_allowance,
+        token_amount: None,
+        revert_reason: None,
+    }
+}
+
 /// During switch to the 1.4.1 protocol version, there will be a moment of discrepancy, when while
 /// the L2 has already upgraded to 1.4.1 (and thus suggests smaller overhead), the L1 is still on the previous version.
 ///
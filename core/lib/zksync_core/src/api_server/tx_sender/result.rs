@@ -1,6 +1,6 @@
 use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
 use thiserror::Error;
-use zksync_types::{l2::error::TxCheckError, U256};
+use zksync_types::{api, l2::error::TxCheckError, U256};
 use zksync_web3_decl::error::EnrichedClientError;
 
 use crate::api_server::execution_sandbox::{SandboxExecutionError, ValidationError};
@@ -36,6 +36,13 @@ pub enum SubmitTxError {
     FailedToChargeFee(String),
     #[error("failed paymaster validation. error message: {0}")]
     PaymasterValidationFailed(String),
+    /// Paymaster validation failed during fee estimation. Contains the paymaster fee breakdown
+    /// with the decoded revert reason, which is returned to the caller as structured error data.
+    #[error(
+        "failed paymaster validation. error message: {}",
+        .0.revert_reason.as_deref().unwrap_or_default()
+    )]
+    PaymasterFeeEstimationFailed(Box<api::PaymasterFeeBreakdown>),
     #[error("failed pre-paymaster preparation. error message: {0}")]
     PrePaymasterPreparationFailed(String),
     #[error("invalid sender. can't start a transaction from a non-account")]
@@ -72,6 +79,12 @@ pub enum SubmitTxError {
     ProxyError(#[from] EnrichedClientError),
     #[error("not enough gas to publish compressed bytecodes")]
     FailedToPublishCompressedBytecodes,
+    /// Transaction is rejected by one of the configured admission policies.
+    #[error("transaction rejected by {policy} policy: {reason}")]
+    AdmissionRejected {
+        policy: &'static str,
+        reason: String,
+    },
     /// Catch-all internal error (e.g., database error) that should not be exposed to the caller.
     #[error("internal error")]
     Internal(#[from] anyhow::Error),
@@ -93,7 +106,9 @@ impl SubmitTxError {
             Self::BootloaderFailure(_) => "bootloader-failure",
             Self::ValidationFailed(_) => "validation-failed",
             Self::FailedToChargeFee(_) => "failed-too-charge-fee",
-            Self::PaymasterValidationFailed(_) => "failed-paymaster-validation",
+            Self::PaymasterValidationFailed(_) | Self::PaymasterFeeEstimationFailed(_) => {
+                "failed-paymaster-validation"
+            }
             Self::PrePaymasterPreparationFailed(_) => "failed-prepaymaster-preparation",
             Self::FromIsNotAnAccount => "from-is-not-an-account",
             Self::MaxFeePerGasTooLow => "max-fee-per-gas-too-low",
@@ -107,6 +122,7 @@ impl SubmitTxError {
             Self::IntrinsicGas => "intrinsic-gas",
             Self::ProxyError(_) => "proxy-error",
             Self::FailedToPublishCompressedBytecodes => "failed-to-publish-compressed-bytecodes",
+            Self::AdmissionRejected { .. } => "admission-rejected",
             Self::Internal(_) => "internal",
         }
     }
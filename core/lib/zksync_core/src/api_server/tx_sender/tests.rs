@@ -1,12 +1,28 @@
 //! Tests for the transaction sender.
 
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use multivm::interface::{ExecutionResult, Halt, VmRevertReason};
+use zksync_dal::gas_estimation_dal::GasEstimationStats;
+use zksync_system_constants::CONTRACT_DEPLOYER_ADDRESS;
 use zksync_types::{get_nonce_key, L1BatchNumber, StorageLog};
+use zksync_web3_decl::error::Web3Error;
 
-use super::*;
+use super::{
+    admission::{
+        AddressDenyList, AdmissionError, DeployerAllowList, MinPriorityFee,
+        PendingTxsPerSenderLimit,
+    },
+    *,
+};
 use crate::{
     api_server::execution_sandbox::{testonly::MockTransactionExecutor, VmConcurrencyBarrier},
     genesis::{ensure_genesis_state, GenesisParams},
-    utils::testonly::{create_miniblock, prepare_recovery_snapshot, MockBatchFeeParamsProvider},
+    utils::testonly::{
+        create_l2_transaction, create_miniblock, prepare_recovery_snapshot,
+        MockBatchFeeParamsProvider,
+    },
 };
 
 pub(crate) async fn create_test_tx_sender(
@@ -28,8 +44,10 @@ pub(crate) async fn create_test_tx_sender(
         pool,
         batch_fee_model_input_provider,
         storage_caches,
+        None,
     )
-    .await;
+    .await
+    .unwrap();
 
     Arc::get_mut(&mut tx_sender.0).unwrap().executor = tx_executor;
     (tx_sender, vm_barrier)
@@ -139,3 +157,280 @@ async fn getting_nonce_for_account_after_snapshot_recovery() {
     let nonce = tx_sender.get_expected_nonce(missing_address).await.unwrap();
     assert_eq!(nonce, Nonce(0));
 }
+
+#[tokio::test]
+async fn address_deny_list_is_reloaded_on_modification() {
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("deny_list.txt");
+    let denied_address = Address::repeat_byte(1);
+    let contents = format!("# Denied addresses\n{denied_address:?}\n");
+    tokio::fs::write(&path, contents).await.unwrap();
+    let deny_list = AddressDenyList::with_poll_interval(&path, POLL_INTERVAL)
+        .await
+        .unwrap();
+    let mut addresses = deny_list.addresses.clone();
+
+    let mut tx = create_l2_transaction(10, 100);
+    deny_list.check(&tx).await.unwrap();
+    tx.common_data.initiator_address = denied_address;
+    let err = deny_list.check(&tx).await.unwrap_err();
+    assert_matches!(err, AdmissionError::Rejected(reason) if reason.contains("sender"));
+
+    // File length is changed, so that the modification is detected even if the file system has coarse timestamps.
+    let other_address = Address::repeat_byte(2);
+    let contents = format!("{other_address:?}\n{:?}\n", Address::repeat_byte(3));
+    tokio::fs::write(&path, contents).await.unwrap();
+    let reloaded = addresses.wait_for_reload().await;
+    assert_eq!(reloaded.len(), 2);
+    deny_list.check(&tx).await.unwrap();
+    tx.execute.contract_address = other_address;
+    let err = deny_list.check(&tx).await.unwrap_err();
+    assert_matches!(err, AdmissionError::Rejected(reason) if reason.contains("recipient"));
+
+    // An invalid file should not affect the loaded list.
+    tokio::fs::write(&path, "not an address").await.unwrap();
+    tokio::time::sleep(POLL_INTERVAL * 5).await;
+    let err = deny_list.check(&tx).await.unwrap_err();
+    assert_matches!(err, AdmissionError::Rejected(_));
+}
+
+#[tokio::test]
+async fn checking_admission_policies() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let sender = Address::repeat_byte(1);
+    let mut pending_tx = create_l2_transaction(10, 100);
+    pending_tx.common_data.initiator_address = sender;
+    storage
+        .transactions_dal()
+        .insert_transaction_l2(pending_tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+
+    let policies: Vec<Arc<dyn TxAdmissionPolicy>> = vec![
+        Arc::new(DeployerAllowList::new([sender])),
+        Arc::new(PendingTxsPerSenderLimit::new(pool.clone(), 1)),
+        Arc::new(MinPriorityFee::new(1.into())),
+    ];
+    let mut tx = create_l2_transaction(10, 100);
+    tx.common_data.fee.max_priority_fee_per_gas = 1.into();
+    admission::check_policies(&policies, &tx).await.unwrap();
+
+    tx.execute.contract_address = CONTRACT_DEPLOYER_ADDRESS;
+    let err = admission::check_policies(&policies, &tx).await.unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::AdmissionRejected {
+            policy: "deployer_allow_list",
+            ..
+        }
+    );
+
+    // `sender` is allowed to deploy contracts, but already has a pending transaction.
+    tx.common_data.initiator_address = sender;
+    let err = admission::check_policies(&policies, &tx).await.unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::AdmissionRejected {
+            policy: "pending_txs_per_sender",
+            ..
+        }
+    );
+
+    let tx = create_l2_transaction(10, 100);
+    let err = admission::check_policies(&policies, &tx).await.unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::AdmissionRejected {
+            policy: "min_priority_fee",
+            ..
+        }
+    );
+}
+
+#[tokio::test]
+async fn estimating_fee_with_paymaster() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let paymaster = Address::repeat_byte(0x33);
+    let token = Address::repeat_byte(0x44);
+    let mut tx = create_l2_transaction(10, 100);
+    tx.common_data.paymaster_params = PaymasterParams {
+        paymaster,
+        paymaster_input: PaymasterFlow::ApprovalBased {
+            token,
+            min_allowance: 1_000.into(),
+            inner_input: vec![],
+        }
+        .encode(),
+    };
+
+    let mut tx_executor = MockTransactionExecutor::default();
+    tx_executor.set_tx_responses(|_, _| ExecutionResult::Success { output: vec![] });
+    let (tx_sender, _) =
+        create_test_tx_sender(pool.clone(), L2ChainId::default(), tx_executor.into()).await;
+    let estimate = tx_sender
+        .get_txs_fee_estimate(tx.clone().into(), 1.2, 1_000)
+        .await
+        .unwrap();
+    assert_eq!(estimate.fee.max_priority_fee_per_gas, 0.into());
+    // The mock executor doesn't run the VM, so the validation step isn't measured.
+    assert_eq!(estimate.validation_gas, None);
+    assert_eq!(estimate.execution_gas, None);
+    let paymaster_breakdown = estimate.paymaster.unwrap();
+    assert_eq!(paymaster_breakdown.paymaster, paymaster);
+    assert_eq!(paymaster_breakdown.flow.as_deref(), Some("approvalBased"));
+    assert_eq!(paymaster_breakdown.token, Some(token));
+    assert_eq!(paymaster_breakdown.min_allowance, Some(1_000.into()));
+    assert_eq!(paymaster_breakdown.token_amount, Some(0.into()));
+    assert_eq!(paymaster_breakdown.revert_reason, None);
+}
+
+#[tokio::test]
+async fn estimating_fee_with_reverting_paymaster() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let paymaster = Address::repeat_byte(0x33);
+    let mut tx = create_l2_transaction(10, 100);
+    tx.common_data.paymaster_params = PaymasterParams {
+        paymaster,
+        paymaster_input: PaymasterFlow::ApprovalBased {
+            token: Address::repeat_byte(0x44),
+            min_allowance: 1_000.into(),
+            inner_input: vec![],
+        }
+        .encode(),
+    };
+
+    let mut tx_executor = MockTransactionExecutor::default();
+    tx_executor.set_tx_responses(|_, _| ExecutionResult::Halt {
+        reason: Halt::PaymasterValidationFailed(VmRevertReason::General {
+            msg: "insufficient allowance".to_owned(),
+            data: vec![],
+        }),
+    });
+    let (tx_sender, _) =
+        create_test_tx_sender(pool, L2ChainId::default(), tx_executor.into()).await;
+    let err = tx_sender
+        .get_txs_fee_estimate(tx.into(), 1.2, 1_000)
+        .await
+        .unwrap_err();
+    let SubmitTxError::PaymasterFeeEstimationFailed(paymaster_breakdown) = &err else {
+        panic!("Unexpected error: {err:?}");
+    };
+    assert_eq!(paymaster_breakdown.paymaster, paymaster);
+    assert_eq!(paymaster_breakdown.flow.as_deref(), Some("approvalBased"));
+    assert_eq!(
+        paymaster_breakdown.revert_reason.as_deref(),
+        Some("insufficient allowance")
+    );
+    assert!(err.to_string().contains("insufficient allowance"), "{err}");
+
+    // Check that the breakdown is returned as structured error data.
+    let err = Web3Error::from(err);
+    let Web3Error::PaymasterValidationFailed(_, breakdown) = &err else {
+        panic!("Unexpected error: {err:?}");
+    };
+    let data = serde_json::to_value(breakdown).unwrap();
+    assert_eq!(data["revertReason"], "insufficient allowance");
+    assert_eq!(data["flow"], "approvalBased");
+}
+
+#[tokio::test]
+async fn adaptive_gas_estimation_scale_factors() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut web3_config = Web3JsonRpcConfig::for_tests();
+    web3_config.gas_estimation_stats_enabled = true;
+    web3_config.estimate_gas_min_scale_factor = Some(1.1);
+    web3_config.estimate_gas_max_scale_factor = Some(2.0);
+    let tracker = GasEstimationTracker::new(pool, &web3_config);
+
+    let selector = vec![1, 2, 3, 4];
+    let stats = |address: Address, usage_ratio: f64, samples: u64, failed_samples: u64| {
+        GasEstimationStats {
+            contract_address: address,
+            selector: selector.clone(),
+            samples,
+            failed_samples,
+            estimated_to_used_ratio_sum: 0.0,
+            refund_ratio_sum: 0.0,
+            unscaled_usage_ratio_sum: usage_ratio * samples as f64,
+        }
+    };
+    tracker.update_scale_factors(&[
+        stats(Address::repeat_byte(1), 1.0, 20, 0),
+        stats(Address::repeat_byte(2), 0.5, 20, 0),
+        stats(Address::repeat_byte(3), 1.5, 20, 10),
+        stats(Address::repeat_byte(4), 1.0, 5, 0),
+    ]);
+
+    assert_eq!(
+        tracker.scale_factor(Address::repeat_byte(1), &selector),
+        Some(1.2)
+    );
+    // Scale factors are clamped to the configured bounds.
+    assert_eq!(
+        tracker.scale_factor(Address::repeat_byte(2), &selector),
+        Some(1.1)
+    );
+    assert_eq!(
+        tracker.scale_factor(Address::repeat_byte(3), &selector),
+        Some(2.0)
+    );
+    // Not enough samples.
+    assert_eq!(
+        tracker.scale_factor(Address::repeat_byte(4), &selector),
+        None
+    );
+    assert_eq!(tracker.scale_factor(Address::repeat_byte(1), &[]), None);
+}
+
+#[tokio::test]
+async fn matching_gas_estimates_with_submitted_transactions() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut web3_config = Web3JsonRpcConfig::for_tests();
+    web3_config.gas_estimation_stats_enabled = true;
+    let tracker = GasEstimationTracker::new(pool, &web3_config);
+
+    let tx = create_l2_transaction(10, 100);
+    let selector = gas_estimates::method_selector(&tx.execute.calldata).to_vec();
+    tracker.record_estimate(
+        tx.initiator_account(),
+        tx.common_data.nonce,
+        tx.execute.contract_address,
+        &selector,
+        500_000,
+        1.2,
+    );
+    let estimate = tracker.take_estimate(&tx).unwrap();
+    assert_eq!(estimate.tx_hash, tx.hash());
+    assert_eq!(estimate.estimated_gas, 500_000);
+    // The estimate must be consumed.
+    assert!(tracker.take_estimate(&tx).is_none());
+
+    // Estimate for another contract must not be matched with the transaction.
+    tracker.record_estimate(
+        tx.initiator_account(),
+        tx.common_data.nonce,
+        Address::repeat_byte(0xff),
+        &selector,
+        500_000,
+        1.2,
+    );
+    assert!(tracker.take_estimate(&tx).is_none());
+}
@@ -0,0 +1,134 @@
+//! Authentication of Web3 API callers in the private mode.
+
+use std::{
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use axum::http::{header, HeaderMap, Request, Response, StatusCode};
+use futures::future;
+use tokio::task::futures::TaskLocalFuture;
+use vise::{Counter, Metrics};
+use zksync_types::{
+    api::auth::{SessionTokenError, SignedApiSessionToken},
+    Address, L2ChainId,
+};
+use zksync_utils::time::seconds_since_epoch;
+
+tokio::task_local! {
+    /// Account authenticated for the currently processed HTTP request.
+    static CALLER: Option<Address>;
+}
+
+/// Returns the account authenticated for the currently processed HTTP request, if any.
+pub(super) fn current_caller() -> Option<Address> {
+    CALLER.try_with(|caller| *caller).ok().flatten()
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_auth")]
+struct AuthMetrics {
+    /// Number of HTTP requests with a valid session token.
+    authenticated: Counter,
+    /// Number of HTTP requests rejected because of an invalid session token.
+    rejected: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<AuthMetrics> = vise::Global::new();
+
+/// HTTP-level [`tower::Layer`] authenticating callers using [`SignedApiSessionToken`]s passed
+/// in the `Authorization: Bearer <token>` header. Requests without the header are processed as unauthenticated;
+/// requests with an invalid or expired token, or a token with the lifetime exceeding the configured maximum,
+/// are rejected with the 401 status code.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SessionAuthLayer {
+    chain_id: L2ChainId,
+    max_ttl_sec: u64,
+}
+
+impl SessionAuthLayer {
+    pub fn new(chain_id: L2ChainId, max_ttl: Duration) -> Self {
+        Self {
+            chain_id,
+            max_ttl_sec: max_ttl.as_secs(),
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for SessionAuthLayer {
+    type Service = SessionAuthMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SessionAuthMiddleware {
+            inner,
+            chain_id: self.chain_id,
+            max_ttl_sec: self.max_ttl_sec,
+        }
+    }
+}
+
+/// Service produced by [`SessionAuthLayer`]. The authenticated account is made available to RPC-level middleware
+/// (and thus to method handlers via [`MethodTracer`](super::MethodTracer)) using a task-local variable.
+#[derive(Debug, Clone)]
+pub(crate) struct SessionAuthMiddleware<S> {
+    inner: S,
+    chain_id: L2ChainId,
+    max_ttl_sec: u64,
+}
+
+impl<S> SessionAuthMiddleware<S> {
+    fn authenticate(&self, headers: &HeaderMap) -> Result<Option<Address>, SessionTokenError> {
+        let Some(header) = headers.get(header::AUTHORIZATION) else {
+            return Ok(None);
+        };
+        let token = header
+            .to_str()
+            .ok()
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .ok_or(SessionTokenError::Malformed(
+                "expected `Bearer` authorization scheme",
+            ))?;
+        let token: SignedApiSessionToken = token.parse()?;
+        token
+            .verify(self.chain_id, seconds_since_epoch(), self.max_ttl_sec)
+            .map(Some)
+    }
+}
+
+impl<S, B, ResBody> tower::Service<Request<B>> for SessionAuthMiddleware<S>
+where
+    S: tower::Service<Request<B>, Response = Response<ResBody>>,
+    ResBody: From<String>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = future::Either<
+        TaskLocalFuture<Option<Address>, S::Future>,
+        future::Ready<Result<S::Response, S::Error>>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        match self.authenticate(request.headers()) {
+            Ok(caller) => {
+                if caller.is_some() {
+                    METRICS.authenticated.inc();
+                }
+                future::Either::Left(CALLER.scope(caller, self.inner.call(request)))
+            }
+            Err(err) => {
+                tracing::debug!("Rejected HTTP request with invalid session token: {err}");
+                METRICS.rejected.inc();
+                let response = Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .body(ResBody::from(err.to_string()))
+                    .expect("failed building HTTP response");
+                future::Either::Right(future::ready(Ok(response)))
+            }
+        }
+    }
+}
@@ -3,12 +3,13 @@
 use std::{cell::RefCell, mem, sync::Arc, time::Instant};
 
 use thread_local::ThreadLocal;
-use zksync_types::api;
+use zksync_types::{api, Address};
 use zksync_web3_decl::{
     error::Web3Error,
     jsonrpsee::{helpers::MethodResponseResult, MethodResponse},
 };
 
+use super::auth::current_caller;
 #[cfg(test)]
 use super::testonly::RecordedMethodCalls;
 use crate::api_server::web3::metrics::API_METRICS;
@@ -24,6 +25,8 @@ pub(crate) struct MethodMetadata {
     pub block_diff: Option<u32>,
     /// Did this call return an app-level error?
     pub has_app_error: bool,
+    /// Account authenticated using a session token. Only set for HTTP calls in the private mode.
+    pub caller: Option<Address>,
 }
 
 impl MethodMetadata {
@@ -34,6 +37,7 @@ impl MethodMetadata {
             block_id: None,
             block_diff: None,
             has_app_error: false,
+            caller: current_caller(),
         }
     }
 }
@@ -88,6 +92,15 @@ impl MethodTracer {
         }
     }
 
+    /// Returns the account authenticated for the current JSON-RPC method call, if any.
+    ///
+    /// This should be called inside JSON-RPC method handlers; otherwise, this method returns `None`.
+    pub fn caller(&self) -> Option<Address> {
+        let cell = self.inner.get_or_default();
+        let metadata = cell.borrow();
+        metadata.as_ref().and_then(|metadata| metadata.caller)
+    }
+
     pub(super) fn new_call(self: &Arc<Self>, name: &'static str) -> MethodCall {
         MethodCall {
             tracer: self.clone(),
@@ -8,11 +8,13 @@ use zksync_web3_decl::{
 };
 
 pub(crate) use self::{
+    auth::SessionAuthLayer,
     metadata::{MethodMetadata, MethodTracer},
     middleware::{LimitMiddleware, MetadataMiddleware, ShutdownMiddleware, TrafficTracker},
 };
 use crate::api_server::tx_sender::SubmitTxError;
 
+mod auth;
 mod metadata;
 mod middleware;
 pub mod namespaces;
@@ -23,9 +25,14 @@ impl MethodTracer {
     pub(crate) fn map_err(&self, err: Web3Error) -> ErrorObjectOwned {
         self.observe_error(&err);
 
-        let data = match &err {
-            Web3Error::SubmitTransactionError(_, data) => Some(format!("0x{}", hex::encode(data))),
-            Web3Error::ProxyError(_) => Some("0x".to_owned()),
+        let data: Option<serde_json::Value> = match &err {
+            Web3Error::SubmitTransactionError(_, data) => {
+                Some(format!("0x{}", hex::encode(data)).into())
+            }
+            Web3Error::ProxyError(_) => Some("0x".into()),
+            Web3Error::PaymasterValidationFailed(_, breakdown) => {
+                serde_json::to_value(breakdown).ok()
+            }
             _ => None,
         };
         let code = match err {
@@ -40,15 +47,20 @@ impl MethodTracer {
             | Web3Error::InvalidFilterBlockHash
             | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
             Web3Error::SubmitTransactionError(_, _)
+            | Web3Error::PaymasterValidationFailed(_, _)
             | Web3Error::SerializationError(_)
             | Web3Error::ProxyError(_) => 3,
-            Web3Error::TreeApiUnavailable => 6,
+            Web3Error::TreeApiUnavailable
+            | Web3Error::MempoolUnavailable
+            | Web3Error::BlobArchiveUnavailable => 6,
+            Web3Error::Unauthorized => ErrorCode::ServerError(401).code(),
         };
         let message = match err {
             // Do not expose internal error details to the client.
             Web3Error::InternalError(_) => "Internal error".to_owned(),
             Web3Error::ProxyError(err) => err.as_ref().to_string(),
-            Web3Error::SubmitTransactionError(message, _) => message,
+            Web3Error::SubmitTransactionError(message, _)
+            | Web3Error::PaymasterValidationFailed(message, _) => message,
             _ => err.to_string(),
         };
 
@@ -61,6 +73,9 @@ impl From<SubmitTxError> for Web3Error {
         match err {
             SubmitTxError::Internal(err) => Self::InternalError(err),
             SubmitTxError::ProxyError(err) => Self::ProxyError(err),
+            SubmitTxError::PaymasterFeeEstimationFailed(ref breakdown) => {
+                Self::PaymasterValidationFailed(err.to_string(), breakdown.clone())
+            }
             _ => Self::SubmitTransactionError(err.to_string(), err.data()),
         }
     }
@@ -1,5 +1,5 @@
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig, ValidationTrace},
     debug_flat_call::DebugCallFlat,
     transaction_request::CallRequest,
     H256,
@@ -63,4 +63,14 @@ impl DebugNamespaceServer for DebugNamespace {
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
+
+    async fn trace_validation(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+    ) -> RpcResult<ValidationTrace> {
+        self.debug_trace_validation_impl(request, block)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
 }
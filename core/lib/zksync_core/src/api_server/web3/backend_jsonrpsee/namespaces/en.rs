@@ -18,6 +18,15 @@ impl EnNamespaceServer for EnNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn sync_l2_block_state_diff(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<en::L2BlockStateDiff>> {
+        self.sync_l2_block_state_diff_impl(block_number)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn consensus_genesis(&self) -> RpcResult<Option<en::ConsensusGenesis>> {
         self.consensus_genesis_impl()
             .await
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use zksync_types::api::txpool::{TxpoolContent, TxpoolInspect, TxpoolStatus};
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::TxpoolNamespaceServer};
+
+use crate::api_server::web3::namespaces::TxpoolNamespace;
+
+#[async_trait]
+impl TxpoolNamespaceServer for TxpoolNamespace {
+    async fn content(&self) -> RpcResult<TxpoolContent> {
+        self.content_impl()
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn status(&self) -> RpcResult<TxpoolStatus> {
+        self.status_impl()
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn inspect(&self) -> RpcResult<TxpoolInspect> {
+        self.inspect_impl()
+            .map_err(|err| self.current_method().map_err(err))
+    }
+}
@@ -2,13 +2,12 @@ use std::collections::HashMap;
 
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        BatchBlobs, BlockDetails, BridgeAddresses, FeeEstimate, GasEstimationStats, L1BatchDetails,
+        L2ToL1LogProof, Proof, ProtocolVersion, TransactionDetails,
     },
-    fee::Fee,
     fee_model::FeeParams,
     transaction_request::CallRequest,
-    Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
+    Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
@@ -20,12 +19,22 @@ use crate::api_server::web3::ZksNamespace;
 
 #[async_trait]
 impl ZksNamespaceServer for ZksNamespace {
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee> {
+    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<FeeEstimate> {
         self.estimate_fee_impl(req)
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_gas_estimation_stats(
+        &self,
+        address: Address,
+        selector: Option<Bytes>,
+    ) -> RpcResult<Vec<GasEstimationStats>> {
+        self.get_gas_estimation_stats_impl(address, selector)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256> {
         self.estimate_l1_to_l2_gas_impl(req)
             .await
@@ -167,4 +176,13 @@ impl ZksNamespaceServer for ZksNamespace {
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
+
+    async fn get_batch_blobs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<BatchBlobs>> {
+        self.get_batch_blobs_impl(l1_batch_number)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
 }
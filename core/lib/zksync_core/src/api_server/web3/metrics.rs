@@ -101,6 +101,9 @@ enum Web3ErrorKind {
     LogsLimitExceeded,
     InvalidFilterBlockHash,
     TreeApiUnavailable,
+    MempoolUnavailable,
+    BlobArchiveUnavailable,
+    Unauthorized,
     Internal,
 }
 
@@ -109,7 +112,9 @@ impl Web3ErrorKind {
         match err {
             Web3Error::NoBlock => Self::NoBlock,
             Web3Error::PrunedBlock(_) | Web3Error::PrunedL1Batch(_) => Self::Pruned,
-            Web3Error::SubmitTransactionError(..) => Self::SubmitTransaction,
+            Web3Error::SubmitTransactionError(..) | Web3Error::PaymasterValidationFailed(..) => {
+                Self::SubmitTransaction
+            }
             Web3Error::ProxyError(_) => Self::Proxy,
             Web3Error::SerializationError(_) => Self::TransactionSerialization,
             Web3Error::TooManyTopics => Self::TooManyTopics,
@@ -117,6 +122,9 @@ impl Web3ErrorKind {
             Web3Error::LogsLimitExceeded(..) => Self::LogsLimitExceeded,
             Web3Error::InvalidFilterBlockHash => Self::InvalidFilterBlockHash,
             Web3Error::TreeApiUnavailable => Self::TreeApiUnavailable,
+            Web3Error::MempoolUnavailable => Self::MempoolUnavailable,
+            Web3Error::BlobArchiveUnavailable => Self::BlobArchiveUnavailable,
+            Web3Error::Unauthorized => Self::Unauthorized,
             Web3Error::InternalError(_) | Web3Error::NotImplemented => Self::Internal,
         }
     }
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, net::SocketAddr, num::NonZeroU32, sync::Arc, time::Duration};
+use std::{collections::HashSet, mem, net::SocketAddr, num::NonZeroU32, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use chrono::NaiveDateTime;
@@ -11,6 +11,7 @@ use tokio::{
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
 use zksync_dal::{ConnectionPool, Core};
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_object_store::ObjectStore;
 use zksync_state::MempoolCache;
 use zksync_types::MiniblockNumber;
 use zksync_web3_decl::{
@@ -20,19 +21,21 @@ use zksync_web3_decl::{
     },
     namespaces::{
         DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, EthPubSubServer,
-        NetNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
+        NetNamespaceServer, SnapshotsNamespaceServer, TxpoolNamespaceServer, Web3NamespaceServer,
+        ZksNamespaceServer,
     },
     types::Filter,
 };
 
 use self::{
     backend_jsonrpsee::{
-        LimitMiddleware, MetadataMiddleware, MethodTracer, ShutdownMiddleware, TrafficTracker,
+        LimitMiddleware, MetadataMiddleware, MethodTracer, SessionAuthLayer, ShutdownMiddleware,
+        TrafficTracker,
     },
     metrics::API_METRICS,
     namespaces::{
-        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace, Web3Namespace,
-        ZksNamespace,
+        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace,
+        TxpoolNamespace, Web3Namespace, ZksNamespace,
     },
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
     state::{Filters, InternalApiConfig, RpcState, SealedMiniblockNumber},
@@ -43,6 +46,7 @@ use crate::{
         tree::TreeApiClient,
         tx_sender::TxSender,
     },
+    state_keeper::MempoolGuard,
     sync_layer::SyncState,
     utils::wait_for_l1_batch,
 };
@@ -94,6 +98,7 @@ pub enum Namespace {
     En,
     Pubsub,
     Snapshots,
+    Txpool,
 }
 
 impl Namespace {
@@ -105,6 +110,15 @@ impl Namespace {
         Self::En,
         Self::Pubsub,
     ];
+
+    /// Returns `true` if this namespace exposes data of all accounts without filtering and thus cannot be
+    /// enabled in the private mode.
+    fn exposes_all_accounts(&self) -> bool {
+        matches!(
+            self,
+            Self::Debug | Self::En | Self::Snapshots | Self::Txpool
+        )
+    }
 }
 
 /// Handles to the initialized API server.
@@ -127,6 +141,8 @@ struct OptionalApiParams {
     response_body_size_limit: Option<usize>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tree_api: Option<Arc<dyn TreeApiClient>>,
+    blob_store: Option<Arc<dyn ObjectStore>>,
+    mempool: Option<MempoolGuard>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -242,6 +258,12 @@ impl ApiBuilder {
         self
     }
 
+    /// Sets the state keeper mempool used by the `txpool` namespace.
+    pub fn with_mempool(mut self, mempool: MempoolGuard) -> Self {
+        self.optional.mempool = Some(mempool);
+        self
+    }
+
     pub fn with_polling_interval(mut self, polling_interval: Duration) -> Self {
         self.polling_interval = polling_interval;
         self
@@ -258,6 +280,12 @@ impl ApiBuilder {
         self
     }
 
+    /// Sets the object store with L1 batch blobs archived by the ETH sender, which is used by `zks_getBatchBlobs`.
+    pub fn with_blob_store(mut self, blob_store: Arc<dyn ObjectStore>) -> Self {
+        self.optional.blob_store = Some(blob_store);
+        self
+    }
+
     #[cfg(test)]
     fn with_pub_sub_events(mut self, sender: mpsc::UnboundedSender<PubSubEvent>) -> Self {
         self.optional.pub_sub_events_sender = Some(sender);
@@ -333,6 +361,8 @@ impl ApiServer {
             mempool_cache,
             last_sealed_miniblock,
             tree_api: self.optional.tree_api,
+            blob_store: self.optional.blob_store,
+            mempool: self.optional.mempool,
         })
     }
 
@@ -380,14 +410,18 @@ impl ApiServer {
                 .expect("Can't merge debug namespace");
         }
         if namespaces.contains(&Namespace::Snapshots) {
-            rpc.merge(SnapshotsNamespace::new(rpc_state).into_rpc())
+            rpc.merge(SnapshotsNamespace::new(rpc_state.clone()).into_rpc())
                 .expect("Can't merge snapshots namespace");
         }
+        if namespaces.contains(&Namespace::Txpool) {
+            rpc.merge(TxpoolNamespace::new(rpc_state).into_rpc())
+                .expect("Can't merge txpool namespace");
+        }
         Ok(rpc)
     }
 
     pub async fn run(
-        self,
+        mut self,
         stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<ApiServerHandles> {
         if self.config.filters_disabled {
@@ -420,6 +454,23 @@ impl ApiServer {
             _ => {}
         }
 
+        if self.config.private_mode {
+            let (disabled, enabled) = mem::take(&mut self.namespaces)
+                .into_iter()
+                .partition::<Vec<_>, _>(Namespace::exposes_all_accounts);
+            if !disabled.is_empty() {
+                tracing::warn!(
+                    "Namespaces {disabled:?} expose data of all accounts and are disabled in the private mode"
+                );
+            }
+            self.namespaces = enabled;
+            if matches!(&self.transport, ApiTransport::WebSocket(_)) {
+                tracing::info!(
+                    "Callers cannot be authenticated via WebSocket; account-specific data will not be available"
+                );
+            }
+        }
+
         self.build_jsonrpsee(stop_receiver).await
     }
 
@@ -469,6 +520,7 @@ impl ApiServer {
             && self.namespaces.contains(&Namespace::Pubsub)
         {
             let mut pub_sub = EthSubscribe::new();
+            pub_sub.set_private_mode(self.config.private_mode);
             if let Some(sender) = &self.optional.pub_sub_events_sender {
                 pub_sub.set_events_sender(sender.clone());
             }
@@ -550,6 +602,9 @@ impl ApiServer {
         let vm_barrier = self.optional.vm_barrier.clone();
         let health_updater = self.health_updater.clone();
         let method_tracer = self.method_tracer.clone();
+        let private_mode = self.config.private_mode;
+        let l2_chain_id = self.config.l2_chain_id;
+        let max_session_token_ttl = self.config.max_session_token_ttl;
 
         let rpc = self
             .build_rpc_module(pub_sub, last_sealed_miniblock, mempool_cache)
@@ -567,8 +622,18 @@ impl ApiServer {
                 .allow_methods([reqwest::Method::POST])
                 // Allow requests from any origin
                 .allow_origin(tower_http::cors::Any)
-                .allow_headers([reqwest::header::CONTENT_TYPE])
+                .allow_headers(if private_mode {
+                    vec![
+                        reqwest::header::CONTENT_TYPE,
+                        reqwest::header::AUTHORIZATION,
+                    ]
+                } else {
+                    vec![reqwest::header::CONTENT_TYPE]
+                })
         });
+        // Session tokens are only supported for HTTP; WS callers are always unauthenticated.
+        let session_auth = (is_http && private_mode)
+            .then(|| SessionAuthLayer::new(l2_chain_id, max_session_token_ttl));
         // Setup metrics for the number of in-flight requests.
         let (in_flight_requests, counter) = InFlightRequestsLayer::pair();
         tokio::spawn(
@@ -580,7 +645,8 @@ impl ApiServer {
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .option_layer(cors)
+            .option_layer(session_auth);
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
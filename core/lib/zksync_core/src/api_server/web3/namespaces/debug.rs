@@ -6,7 +6,10 @@ use once_cell::sync::OnceCell;
 use zksync_dal::CoreDal;
 use zksync_system_constants::MAX_ENCODED_TX_SIZE;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig, ValidationTrace,
+        ValidationTraceStorageAccess, ValidationTraceViolation,
+    },
     debug_flat_call::{flatten_debug_calls, DebugCallFlat},
     fee_model::BatchFeeInput,
     l2::L2Tx,
@@ -14,6 +17,7 @@ use zksync_types::{
     vm_trace::Call,
     AccountTreeId, H256,
 };
+use zksync_utils::u256_to_h256;
 use zksync_web3_decl::error::Web3Error;
 
 use crate::api_server::{
@@ -211,6 +215,62 @@ impl DebugNamespace {
         Ok(call.into())
     }
 
+    #[tracing::instrument(skip(self, request, block_id))]
+    pub async fn debug_trace_validation_impl(
+        &self,
+        mut request: CallRequest,
+        block_id: Option<BlockId>,
+    ) -> Result<ValidationTrace, Web3Error> {
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        self.current_method().set_block_id(block_id);
+
+        self.state.set_nonce_for_call_request(&mut request).await?;
+        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
+        let block_args = self
+            .state
+            .resolve_block_args(&mut connection, block_id)
+            .await?;
+        drop(connection);
+
+        self.current_method().set_block_diff(
+            self.state
+                .last_sealed_miniblock
+                .diff_with_block_args(&block_args),
+        );
+        let tx = L2Tx::from_request(request.into(), self.state.api_config.max_tx_size)?;
+        let output = self
+            .state
+            .tx_sender
+            .trace_validation(tx, block_args)
+            .await?;
+        // Reports are not collected for legacy VM versions.
+        let report = output.report.ok_or(Web3Error::NotImplemented)?;
+
+        let violation = report.violated_rule.map(|rule| ValidationTraceViolation {
+            rule: rule.to_string(),
+            call_stack: report.violation_call_stack,
+        });
+        let storage_accesses = report
+            .storage_accesses
+            .into_iter()
+            .map(|access| ValidationTraceStorageAccess {
+                phase: access.phase,
+                address: access.address,
+                key: u256_to_h256(access.key),
+                allowed: access.is_allowed,
+            })
+            .collect();
+        Ok(ValidationTrace {
+            success: output.result.is_ok(),
+            error: output.result.err().map(|err| err.to_string()),
+            violation,
+            storage_accesses,
+            gas_used: output.gas_used.into(),
+            computational_gas_used: report.computational_gas_used,
+            computational_gas_limit: report.computational_gas_limit,
+        })
+    }
+
     fn shared_args(&self) -> TxSharedArgs {
         let sender_config = self.sender_config();
         TxSharedArgs {
@@ -52,6 +52,19 @@ impl EnNamespace {
             .context("sync_block")?)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn sync_l2_block_state_diff_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<en::L2BlockStateDiff>, Web3Error> {
+        let mut storage = self.state.connection_pool.connection_tagged("api").await?;
+        Ok(storage
+            .sync_dal()
+            .sync_l2_block_state_diff(block_number)
+            .await
+            .context("sync_l2_block_state_diff")?)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn sync_tokens_impl(
         &self,
@@ -60,6 +60,10 @@ impl EthNamespace {
         request: CallRequest,
         block_id: Option<BlockId>,
     ) -> Result<Bytes, Web3Error> {
+        // In the private mode, calls can only be performed on behalf of the authenticated account. Note that this
+        // doesn't prevent reading data of other accounts via view methods of contracts.
+        self.state
+            .ensure_account_access(request.from.unwrap_or_default())?;
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         self.current_method().set_block_id(block_id);
 
@@ -86,6 +90,8 @@ impl EthNamespace {
         request: CallRequest,
         _block: Option<BlockNumber>,
     ) -> Result<U256, Web3Error> {
+        self.state
+            .ensure_account_access(request.from.unwrap_or_default())?;
         let mut request_with_gas_per_pubdata_overridden = request;
         self.state
             .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
@@ -143,6 +149,7 @@ impl EthNamespace {
         address: Address,
         block_id: Option<BlockId>,
     ) -> Result<U256, Web3Error> {
+        self.state.ensure_account_access(address)?;
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         self.current_method().set_block_id(block_id);
 
@@ -236,7 +243,8 @@ impl EthNamespace {
         };
         self.set_block_diff(block_number);
 
-        let transactions = if full_transactions {
+        // In the private mode, we need full transactions to filter out ones not involving the caller.
+        let transactions = if full_transactions || self.state.api_config.private_mode {
             let mut transactions = storage
                 .transactions_web3_dal()
                 .get_transactions(&block.transactions, self.state.api_config.l2_chain_id)
@@ -258,7 +266,14 @@ impl EthNamespace {
 
             transactions
                 .into_iter()
-                .map(TransactionVariant::Full)
+                .filter(|tx| self.state.can_access_transaction(tx))
+                .map(|tx| {
+                    if full_transactions {
+                        TransactionVariant::Full(tx)
+                    } else {
+                        TransactionVariant::Hash(tx.hash)
+                    }
+                })
                 .collect()
         } else {
             block
@@ -288,11 +303,24 @@ impl EthNamespace {
         else {
             return Ok(None);
         };
-        let tx_count = storage
+        let mut tx_count = storage
             .blocks_web3_dal()
             .get_block_tx_count(block_number)
             .await
             .with_context(|| format!("get_block_tx_count({block_number})"))?;
+        if tx_count.is_some() && self.state.api_config.private_mode {
+            // Only count transactions accessible to the authenticated account.
+            let transactions = storage
+                .transactions_web3_dal()
+                .get_raw_miniblock_transactions(block_number)
+                .await
+                .with_context(|| format!("get_raw_miniblock_transactions({block_number})"))?;
+            let accessible_count = transactions
+                .iter()
+                .filter(|tx| self.state.can_access_raw_transaction(tx))
+                .count();
+            tx_count = Some(accessible_count as u64);
+        }
 
         if tx_count.is_some() {
             self.set_block_diff(block_number); // only report block diff for existing miniblocks
@@ -331,6 +359,7 @@ impl EthNamespace {
             .get_transaction_receipts(&block.transactions)
             .await
             .with_context(|| format!("get_transaction_receipts({block_number})"))?;
+        receipts.retain(|receipt| self.state.can_access_receipt(receipt));
         receipts.sort_unstable_by_key(|receipt| receipt.transaction_index);
         Ok(Some(receipts))
     }
@@ -368,6 +397,7 @@ impl EthNamespace {
         idx: U256,
         block_id: Option<BlockId>,
     ) -> Result<H256, Web3Error> {
+        self.state.ensure_account_access(address)?;
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         self.current_method().set_block_id(block_id);
 
@@ -390,6 +420,7 @@ impl EthNamespace {
         address: Address,
         block_id: Option<BlockId>,
     ) -> Result<U256, Web3Error> {
+        self.state.ensure_account_access(address)?;
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         self.current_method().set_block_id(block_id);
 
@@ -469,7 +500,7 @@ impl EthNamespace {
         if transaction.is_none() {
             transaction = self.state.tx_sink().lookup_tx(id).await?;
         }
-        Ok(transaction)
+        Ok(transaction.filter(|tx| self.state.can_access_transaction(tx)))
     }
 
     #[tracing::instrument(skip(self))]
@@ -486,7 +517,10 @@ impl EthNamespace {
             .get_transaction_receipts(&[hash])
             .await
             .context("get_transaction_receipts")?;
-        Ok(receipts.into_iter().next())
+        Ok(receipts
+            .into_iter()
+            .next()
+            .filter(|receipt| self.state.can_access_receipt(receipt)))
     }
 
     #[tracing::instrument(skip(self))]
@@ -540,6 +574,10 @@ impl EthNamespace {
             .installed_filters
             .as_ref()
             .ok_or(Web3Error::NotImplemented)?;
+        // Pending transaction filters only return hashes, so they cannot be filtered by the caller.
+        if self.state.api_config.private_mode {
+            return Err(Web3Error::Unauthorized);
+        }
         Ok(installed_filters
             .lock()
             .await
@@ -797,11 +835,12 @@ impl EthNamespace {
                     }
                 }
 
-                let logs = storage
+                let mut logs = storage
                     .events_web3_dal()
                     .get_logs(get_logs_filter, i32::MAX as usize)
                     .await
                     .context("get_logs")?;
+                logs.retain(|log| self.state.can_access_log(log));
                 *from_block = to_block + 1;
                 FilterChanges::Logs(logs)
             }
@@ -55,11 +55,13 @@ impl SnapshotsNamespace {
 
         let chunks = snapshot_files
             .into_iter()
+            .zip(snapshot_metadata.storage_logs_hashes)
             .enumerate()
-            .filter_map(|(chunk_id, filepath)| {
+            .filter_map(|(chunk_id, (filepath, hash))| {
                 Some(SnapshotStorageLogsChunkMetadata {
                     chunk_id: chunk_id as u64,
                     filepath: filepath?,
+                    hash,
                 })
             })
             .collect();
@@ -78,10 +80,13 @@ impl SnapshotsNamespace {
 
         Ok(Some(SnapshotHeader {
             l1_batch_number: snapshot_metadata.l1_batch_number,
+            base_l1_batch_number: snapshot_metadata.base_l1_batch_number,
             miniblock_number,
             last_l1_batch_with_metadata: l1_batch_with_metadata,
             storage_logs_chunks: chunks,
             factory_deps_filepath: snapshot_metadata.factory_deps_filepath,
+            factory_deps_hash: snapshot_metadata.factory_deps_hash,
+            manifest_signature: snapshot_metadata.manifest_signature,
         }))
     }
 }
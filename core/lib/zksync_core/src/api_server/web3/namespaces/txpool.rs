@@ -0,0 +1,122 @@
+use zksync_mempool::AccountContent;
+use zksync_types::{
+    api::txpool::{TxpoolContent, TxpoolInspect, TxpoolStatus, TxpoolTransaction},
+    l2::L2Tx,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::{
+    api_server::web3::{backend_jsonrpsee::MethodTracer, state::RpcState},
+    state_keeper::MempoolGuard,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct TxpoolNamespace {
+    state: RpcState,
+}
+
+impl TxpoolNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    pub(crate) fn current_method(&self) -> &MethodTracer {
+        &self.state.current_method
+    }
+
+    fn mempool(&self) -> Result<&MempoolGuard, Web3Error> {
+        self.state
+            .mempool
+            .as_ref()
+            .ok_or(Web3Error::MempoolUnavailable)
+    }
+
+    pub fn content_impl(&self) -> Result<TxpoolContent, Web3Error> {
+        let mut content = TxpoolContent::default();
+        for (account, account_content) in self.mempool()?.l2_content() {
+            let queued_reason = queued_reason(&account_content);
+            if !account_content.pending.is_empty() {
+                let pending = account_content
+                    .pending
+                    .iter()
+                    .map(|tx| (tx.nonce().0, txpool_transaction(tx, None)));
+                content.pending.insert(account, pending.collect());
+            }
+            if !account_content.queued.is_empty() {
+                let queued = account_content.queued.iter().map(|tx| {
+                    let transaction = txpool_transaction(tx, Some(queued_reason.clone()));
+                    (tx.nonce().0, transaction)
+                });
+                content.queued.insert(account, queued.collect());
+            }
+        }
+        Ok(content)
+    }
+
+    pub fn status_impl(&self) -> Result<TxpoolStatus, Web3Error> {
+        let stats = self.mempool()?.stats();
+        Ok(TxpoolStatus {
+            pending: stats.l2_pending_transaction_count.into(),
+            queued: stats.l2_queued_transaction_count.into(),
+            priority: (stats.l1_transaction_count as u64).into(),
+            size_bytes: stats.l2_size_bytes.into(),
+        })
+    }
+
+    pub fn inspect_impl(&self) -> Result<TxpoolInspect, Web3Error> {
+        let mut inspect = TxpoolInspect::default();
+        for (account, account_content) in self.mempool()?.l2_content() {
+            let queued_reason = queued_reason(&account_content);
+            if !account_content.pending.is_empty() {
+                let pending = account_content
+                    .pending
+                    .iter()
+                    .map(|tx| (tx.nonce().0, inspect_transaction(tx)));
+                inspect.pending.insert(account, pending.collect());
+            }
+            if !account_content.queued.is_empty() {
+                let queued = account_content.queued.iter().map(|tx| {
+                    let summary = format!("{} ({queued_reason})", inspect_transaction(tx));
+                    (tx.nonce().0, summary)
+                });
+                inspect.queued.insert(account, queued.collect());
+            }
+        }
+        Ok(inspect)
+    }
+}
+
+fn queued_reason(content: &AccountContent) -> String {
+    format!(
+        "nonce gap: transaction with nonce {} is missing",
+        content.missing_nonce()
+    )
+}
+
+fn txpool_transaction(tx: &L2Tx, queued_reason: Option<String>) -> TxpoolTransaction {
+    let fee = &tx.common_data.fee;
+    TxpoolTransaction {
+        hash: tx.hash(),
+        nonce: tx.nonce().0.into(),
+        from: tx.initiator_account(),
+        to: tx.recipient_account(),
+        value: tx.execute.value,
+        gas: fee.gas_limit,
+        max_fee_per_gas: fee.max_fee_per_gas,
+        max_priority_fee_per_gas: fee.max_priority_fee_per_gas,
+        gas_per_pubdata_limit: fee.gas_per_pubdata_limit,
+        input: tx.execute.calldata.clone().into(),
+        queued_reason,
+    }
+}
+
+fn inspect_transaction(tx: &L2Tx) -> String {
+    let fee = &tx.common_data.fee;
+    format!(
+        "{:?}: {} wei + {} gas × {} wei",
+        tx.recipient_account(),
+        tx.execute.value,
+        fee.gas_limit,
+        fee.max_fee_per_gas
+    )
+}
@@ -3,12 +3,15 @@ use std::{collections::HashMap, convert::TryInto};
 use anyhow::Context as _;
 use zksync_dal::{Connection, Core, CoreDal};
 use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_object_store::ObjectStoreError;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        BatchBlob, BatchBlobs, BlockDetails, BridgeAddresses, FeeEstimate, GasEstimationStats,
+        GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion, StorageProof,
+        TransactionDetails,
     },
+    eth_sender::L1BatchBlobs,
     fee::Fee,
     fee_model::FeeParams,
     l1::L1Tx,
@@ -17,8 +20,9 @@ use zksync_types::{
     tokens::ETHEREUM_ADDRESS,
     transaction_request::CallRequest,
     utils::storage_key_for_standard_token_balance,
-    AccountTreeId, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey, Transaction,
-    L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
+    AccountTreeId, Bytes, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey,
+    Transaction, L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
 };
 use zksync_utils::{address_to_h256, h256_to_u256};
 use zksync_web3_decl::{
@@ -50,7 +54,9 @@ impl ZksNamespace {
     }
 
     #[tracing::instrument(skip(self, request))]
-    pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<Fee, Web3Error> {
+    pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<FeeEstimate, Web3Error> {
+        self.state
+            .ensure_account_access(request.from.unwrap_or_default())?;
         let mut request_with_gas_per_pubdata_overridden = request;
         self.state
             .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
@@ -69,7 +75,46 @@ impl ZksNamespace {
         // not consider provided ones.
         tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
         tx.common_data.fee.gas_per_pubdata_limit = U256::from(DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE);
-        self.estimate_fee(tx.into()).await
+
+        let scale_factor = self.state.api_config.estimate_gas_scale_factor;
+        let acceptable_overestimation =
+            self.state.api_config.estimate_gas_acceptable_overestimation;
+        Ok(self
+            .state
+            .tx_sender
+            .get_txs_fee_estimate(tx.into(), scale_factor, acceptable_overestimation)
+            .await?)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_gas_estimation_stats_impl(
+        &self,
+        address: Address,
+        selector: Option<Bytes>,
+    ) -> Result<Vec<GasEstimationStats>, Web3Error> {
+        let mut storage = self.connection().await?;
+        let stats = storage
+            .gas_estimation_dal()
+            .get_gas_estimation_stats(address, selector.as_ref().map(|selector| &selector.0[..]))
+            .await
+            .context("get_gas_estimation_stats")?;
+        drop(storage);
+
+        Ok(stats
+            .into_iter()
+            .map(|stats| GasEstimationStats {
+                contract_address: stats.contract_address,
+                adaptive_scale_factor: self
+                    .state
+                    .tx_sender
+                    .gas_estimation_scale_factor(stats.contract_address, &stats.selector),
+                samples: stats.samples.into(),
+                failed_samples: stats.failed_samples.into(),
+                avg_estimated_to_used_ratio: stats.avg_estimated_to_used_ratio().unwrap_or(0.0),
+                avg_refund_ratio: stats.avg_refund_ratio().unwrap_or(0.0),
+                selector: stats.selector.into(),
+            })
+            .collect())
     }
 
     #[tracing::instrument(skip(self, request))]
@@ -164,6 +209,7 @@ impl ZksNamespace {
         &self,
         address: Address,
     ) -> Result<HashMap<Address, U256>, Web3Error> {
+        self.state.ensure_account_access(address)?;
         let mut storage = self.connection().await?;
         let tokens = storage
             .tokens_dal()
@@ -377,11 +423,30 @@ impl ZksNamespace {
     ) -> Result<Option<BlockDetails>, Web3Error> {
         self.state.start_info.ensure_not_pruned(block_number)?;
         let mut storage = self.connection().await?;
-        Ok(storage
+        let Some(mut details) = storage
             .blocks_web3_dal()
             .get_block_details(block_number)
             .await
-            .context("get_block_details")?)
+            .context("get_block_details")?
+        else {
+            return Ok(None);
+        };
+
+        if self.state.api_config.private_mode {
+            // Only count transactions accessible to the authenticated account.
+            let transactions = storage
+                .transactions_web3_dal()
+                .get_raw_miniblock_transactions(block_number)
+                .await
+                .context("get_raw_miniblock_transactions")?;
+            let (l1_txs, l2_txs): (Vec<_>, Vec<_>) = transactions
+                .iter()
+                .filter(|tx| self.state.can_access_raw_transaction(tx))
+                .partition(|tx| tx.is_l1());
+            details.base.l1_tx_count = l1_txs.len();
+            details.base.l2_tx_count = l2_txs.len();
+        }
+        Ok(Some(details))
     }
 
     #[tracing::instrument(skip(self))]
@@ -391,11 +456,13 @@ impl ZksNamespace {
     ) -> Result<Vec<Transaction>, Web3Error> {
         self.state.start_info.ensure_not_pruned(block_number)?;
         let mut storage = self.connection().await?;
-        Ok(storage
+        let mut transactions = storage
             .transactions_web3_dal()
             .get_raw_miniblock_transactions(block_number)
             .await
-            .context("get_raw_miniblock_transactions")?)
+            .context("get_raw_miniblock_transactions")?;
+        transactions.retain(|tx| self.state.can_access_raw_transaction(tx));
+        Ok(transactions)
     }
 
     #[tracing::instrument(skip(self))]
@@ -414,7 +481,7 @@ impl ZksNamespace {
         if tx_details.is_none() {
             tx_details = self.state.tx_sink().lookup_tx_details(hash).await?;
         }
-        Ok(tx_details)
+        Ok(tx_details.filter(|details| self.state.can_access_account(details.initiator_address)))
     }
 
     #[tracing::instrument(skip(self))]
@@ -496,6 +563,7 @@ impl ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> Result<Option<Proof>, Web3Error> {
+        self.state.ensure_account_access(address)?;
         self.state.start_info.ensure_not_pruned(l1_batch_number)?;
         let hashed_keys = keys
             .iter()
@@ -538,4 +606,42 @@ impl ZksNamespace {
             storage_proof,
         }))
     }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_batch_blobs_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<BatchBlobs>, Web3Error> {
+        let blob_store = self
+            .state
+            .blob_store
+            .as_deref()
+            .ok_or(Web3Error::BlobArchiveUnavailable)?;
+        let batch_blobs: L1BatchBlobs = match blob_store.get(l1_batch_number).await {
+            Ok(blobs) => blobs,
+            Err(ObjectStoreError::KeyNotFound(_)) => return Ok(None),
+            Err(err) => {
+                return Err(anyhow::Error::from(err)
+                    .context(format!(
+                        "failed loading blobs for L1 batch #{l1_batch_number}"
+                    ))
+                    .into())
+            }
+        };
+
+        Ok(Some(BatchBlobs {
+            l1_batch_number: batch_blobs.l1_batch_number,
+            commit_tx_hash: batch_blobs.commit_tx_hash,
+            blobs: batch_blobs
+                .blobs
+                .into_iter()
+                .map(|blob| BatchBlob {
+                    blob: blob.blob.into(),
+                    kzg_commitment: blob.commitment.into(),
+                    blob_proof: blob.proof.into(),
+                    versioned_hash: H256::from_slice(&blob.versioned_hash),
+                })
+                .collect(),
+        }))
+    }
 }
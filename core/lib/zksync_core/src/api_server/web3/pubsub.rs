@@ -222,6 +222,9 @@ pub(super) struct EthSubscribe {
     transactions: broadcast::Sender<Vec<PubSubResult>>,
     logs: broadcast::Sender<Vec<PubSubResult>>,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+    /// If set, subscriptions exposing account-specific data (logs and pending transactions) are rejected,
+    /// since WebSocket callers cannot be authenticated.
+    private_mode: bool,
 }
 
 impl EthSubscribe {
@@ -235,6 +238,7 @@ impl EthSubscribe {
             transactions,
             logs,
             events_sender: None,
+            private_mode: false,
         }
     }
 
@@ -242,6 +246,10 @@ impl EthSubscribe {
         self.events_sender = Some(sender);
     }
 
+    pub fn set_private_mode(&mut self, private_mode: bool) {
+        self.private_mode = private_mode;
+    }
+
     async fn reject(sink: PendingSubscriptionSink) {
         sink.reject(ErrorObject::borrowed(
             ErrorCode::InvalidParams.code(),
@@ -338,6 +346,16 @@ impl EthSubscribe {
         params: Option<PubSubFilter>,
     ) {
         let sub_type = match sub_type.as_str() {
+            "newPendingTransactions" | "logs" if self.private_mode => {
+                pending_sink
+                    .reject(ErrorObject::borrowed(
+                        ErrorCode::InvalidParams.code(),
+                        "Rejecting subscription - not available in private mode.",
+                        None,
+                    ))
+                    .await;
+                None
+            }
             "newHeads" => {
                 let Ok(sink) = pending_sink.accept().await else {
                     return;
@@ -13,11 +13,13 @@ use tokio::sync::{watch, Mutex};
 use vise::GaugeGuard;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::NetworkConfig, ContractsConfig};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_object_store::ObjectStore;
 use zksync_state::MempoolCache;
 use zksync_types::{
     api, l2::L2Tx, transaction_request::CallRequest, Address, L1BatchNumber, L1ChainId, L2ChainId,
-    MiniblockNumber, H256, U256, U64,
+    MiniblockNumber, Transaction, H256, U256, U64,
 };
+use zksync_utils::address_to_h256;
 use zksync_web3_decl::{error::Web3Error, types::Filter};
 
 use super::{
@@ -31,6 +33,7 @@ use crate::{
         tree::TreeApiClient,
         tx_sender::{tx_sink::TxSink, TxSender},
     },
+    state_keeper::MempoolGuard,
     sync_layer::SyncState,
 };
 
@@ -92,6 +95,8 @@ pub struct InternalApiConfig {
     pub req_entities_limit: usize,
     pub fee_history_limit: u64,
     pub filters_disabled: bool,
+    pub private_mode: bool,
+    pub max_session_token_ttl: Duration,
     pub mempool_cache_update_interval: Duration,
     pub mempool_cache_size: usize,
 }
@@ -121,6 +126,8 @@ impl InternalApiConfig {
             req_entities_limit: web3_config.req_entities_limit(),
             fee_history_limit: web3_config.fee_history_limit(),
             filters_disabled: web3_config.filters_disabled,
+            private_mode: web3_config.private_mode,
+            max_session_token_ttl: web3_config.max_session_token_ttl(),
             mempool_cache_update_interval: web3_config.mempool_cache_update_interval(),
             mempool_cache_size: web3_config.mempool_cache_size(),
         }
@@ -209,6 +216,8 @@ pub(crate) struct RpcState {
     pub(super) installed_filters: Option<Arc<Mutex<Filters>>>,
     pub(super) connection_pool: ConnectionPool<Core>,
     pub(super) tree_api: Option<Arc<dyn TreeApiClient>>,
+    /// Object store with archived L1 batch blobs.
+    pub(super) blob_store: Option<Arc<dyn ObjectStore>>,
     pub(super) tx_sender: TxSender,
     pub(super) sync_state: Option<SyncState>,
     pub(super) api_config: InternalApiConfig,
@@ -217,6 +226,8 @@ pub(crate) struct RpcState {
     pub(super) start_info: BlockStartInfo,
     pub(super) mempool_cache: MempoolCache,
     pub(super) last_sealed_miniblock: SealedMiniblockNumber,
+    /// State keeper mempool; only available on the main node if the state keeper runs in the same process.
+    pub(super) mempool: Option<MempoolGuard>,
 }
 
 impl RpcState {
@@ -242,6 +253,59 @@ impl RpcState {
         self.tx_sender.0.tx_sink.as_ref()
     }
 
+    /// Checks whether the caller of the current method can access data related to `account`. If the private mode
+    /// is disabled, any data is accessible; otherwise, only data of the account authenticated with a session token.
+    pub(crate) fn can_access_account(&self, account: Address) -> bool {
+        !self.api_config.private_mode || self.current_method.caller() == Some(account)
+    }
+
+    /// Same as [`Self::can_access_account()`], but returns an error if the account is not accessible.
+    pub(crate) fn ensure_account_access(&self, account: Address) -> Result<(), Web3Error> {
+        if self.can_access_account(account) {
+            Ok(())
+        } else {
+            Err(Web3Error::Unauthorized)
+        }
+    }
+
+    /// Checks whether the caller of the current method can access the specified log. In the private mode, a log
+    /// is accessible if it was emitted by the authenticated account, or if the account is one of the log topics
+    /// (e.g., for token transfers from or to the account).
+    pub(crate) fn can_access_log(&self, log: &api::Log) -> bool {
+        if !self.api_config.private_mode {
+            return true;
+        }
+        let Some(caller) = self.current_method.caller() else {
+            return false;
+        };
+        log.address == caller || log.topics.contains(&address_to_h256(&caller))
+    }
+
+    /// Checks whether the caller of the current method can access the specified transaction, i.e., it is
+    /// the transaction initiator or recipient.
+    pub(crate) fn can_access_transaction(&self, tx: &api::Transaction) -> bool {
+        !self.api_config.private_mode
+            || [tx.from, tx.to]
+                .into_iter()
+                .flatten()
+                .any(|address| self.can_access_account(address))
+    }
+
+    /// Same as [`Self::can_access_transaction()`], but for raw transactions as stored in miniblocks.
+    pub(crate) fn can_access_raw_transaction(&self, tx: &Transaction) -> bool {
+        self.can_access_account(tx.initiator_account())
+            || self.can_access_account(tx.recipient_account())
+    }
+
+    /// Checks whether the caller of the current method can access the specified transaction receipt.
+    pub(crate) fn can_access_receipt(&self, receipt: &api::TransactionReceipt) -> bool {
+        !self.api_config.private_mode
+            || [Some(receipt.from), receipt.to, receipt.contract_address]
+                .into_iter()
+                .flatten()
+                .any(|address| self.can_access_account(address))
+    }
+
     /// Resolves the specified block ID to a block number, which is guaranteed to be present in the node storage.
     pub(crate) async fn resolve_block(
         &self,
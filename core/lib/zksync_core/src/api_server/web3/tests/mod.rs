@@ -17,9 +17,11 @@ use zksync_config::configs::{
 };
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, Connection, ConnectionPool, CoreDal};
 use zksync_health_check::CheckHealth;
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_types::{
     api,
     block::MiniblockHeader,
+    eth_sender::{L1BatchBlobs, SidecarBlobV1},
     fee::TransactionExecutionMetrics,
     get_nonce_key,
     l2::L2Tx,
@@ -53,6 +55,7 @@ use crate::{
 
 mod debug;
 mod filters;
+mod private;
 mod snapshots;
 mod vm;
 mod ws;
@@ -118,6 +121,7 @@ pub(crate) async fn spawn_http_server(
         None,
         tx_executor,
         method_tracer,
+        None,
         stop_receiver,
     )
     .await
@@ -137,6 +141,7 @@ async fn spawn_ws_server(
         websocket_requests_per_minute_limit,
         MockTransactionExecutor::default(),
         Arc::default(),
+        None,
         stop_receiver,
     )
     .await
@@ -149,6 +154,7 @@ async fn spawn_server(
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tx_executor: MockTransactionExecutor,
     method_tracer: Arc<MethodTracer>,
+    blob_store: Option<Arc<dyn ObjectStore>>,
     stop_receiver: watch::Receiver<bool>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
     let (tx_sender, vm_barrier) =
@@ -158,7 +164,7 @@ async fn spawn_server(
     let mut namespaces = Namespace::DEFAULT.to_vec();
     namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
 
-    let server_builder = match transport {
+    let mut server_builder = match transport {
         ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
         ApiTransportLabel::Ws => {
             let mut builder = ApiBuilder::jsonrpsee_backend(api_config, pool)
@@ -171,6 +177,9 @@ async fn spawn_server(
             builder
         }
     };
+    if let Some(blob_store) = blob_store {
+        server_builder = server_builder.with_blob_store(blob_store);
+    }
     let server_handles = server_builder
         .with_polling_interval(POLL_INTERVAL)
         .with_tx_sender(tx_sender)
@@ -201,6 +210,11 @@ trait HttpTest: Send + Sync {
         Arc::default()
     }
 
+    /// Object store with archived L1 batch blobs. If not set, `zks_getBatchBlobs` is unavailable.
+    fn blob_store(&self) -> Option<Arc<dyn ObjectStore>> {
+        None
+    }
+
     async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()>;
 
     /// Overrides the `filters_disabled` configuration parameter for HTTP server startup
@@ -283,11 +297,14 @@ async fn test_http_server(test: impl HttpTest) {
     let web3_config = Web3JsonRpcConfig::for_tests();
     let mut api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
     api_config.filters_disabled = test.filters_disabled();
-    let mut server_handles = spawn_http_server(
+    let (mut server_handles, _) = spawn_server(
+        ApiTransportLabel::Http,
         api_config,
         pool.clone(),
+        None,
         test.transaction_executor(),
         test.method_tracer(),
+        test.blob_store(),
         stop_receiver,
     )
     .await;
@@ -1077,3 +1094,76 @@ impl HttpTest for RpcCallsTracingTest {
 async fn tracing_rpc_calls() {
     test_http_server(RpcCallsTracingTest::default()).await;
 }
+
+#[derive(Debug)]
+struct BatchBlobsTest {
+    blob_store: Option<Arc<dyn ObjectStore>>,
+}
+
+#[async_trait]
+impl HttpTest for BatchBlobsTest {
+    fn blob_store(&self) -> Option<Arc<dyn ObjectStore>> {
+        self.blob_store.clone()
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let Some(blob_store) = &self.blob_store else {
+            let error = client.get_batch_blobs(L1BatchNumber(1)).await.unwrap_err();
+            if let ClientError::Call(error) = error {
+                assert_eq!(error.code(), 6);
+                assert!(error.message().contains("Blob archive"), "{error:?}");
+            } else {
+                panic!("Unexpected error: {error:?}");
+            }
+            return Ok(());
+        };
+
+        let blobs = client.get_batch_blobs(L1BatchNumber(1)).await?;
+        assert_eq!(blobs, None);
+
+        let archived_blobs = L1BatchBlobs {
+            l1_batch_number: L1BatchNumber(1),
+            commit_tx_hash: H256::repeat_byte(1),
+            blobs: vec![SidecarBlobV1 {
+                blob: vec![1; 32],
+                commitment: vec![2; 48],
+                proof: vec![3; 48],
+                versioned_hash: vec![4; 32],
+            }],
+        };
+        blob_store.put(L1BatchNumber(1), &archived_blobs).await?;
+
+        let blobs = client
+            .get_batch_blobs(L1BatchNumber(1))
+            .await?
+            .expect("archived blobs are not returned");
+        assert_eq!(
+            blobs,
+            api::BatchBlobs {
+                l1_batch_number: L1BatchNumber(1),
+                commit_tx_hash: H256::repeat_byte(1),
+                blobs: vec![api::BatchBlob {
+                    blob: vec![1; 32].into(),
+                    kzg_commitment: vec![2; 48].into(),
+                    blob_proof: vec![3; 48].into(),
+                    versioned_hash: H256::repeat_byte(4),
+                }],
+            }
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_batch_blobs() {
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    test_http_server(BatchBlobsTest {
+        blob_store: Some(blob_store),
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn getting_batch_blobs_without_blob_store() {
+    test_http_server(BatchBlobsTest { blob_store: None }).await;
+}
@@ -0,0 +1,229 @@
+//! Tests for the private mode of the Web3 API server.
+
+use zksync_types::{api::auth::ApiSessionToken, PackedEthSignature};
+use zksync_utils::{address_to_h256, time::seconds_since_epoch};
+use zksync_web3_decl::jsonrpsee::http_client::{HeaderMap, HeaderValue};
+
+use super::*;
+
+const PRIVATE_KEY: H256 = H256::repeat_byte(0x42);
+
+fn account() -> Address {
+    PackedEthSignature::address_from_private_key(&PRIVATE_KEY).unwrap()
+}
+
+fn create_client(local_addr: SocketAddr, token: Option<&str>) -> HttpClient {
+    let mut headers = HeaderMap::new();
+    if let Some(token) = token {
+        let header = HeaderValue::from_str(&format!("Bearer {token}")).unwrap();
+        headers.insert(reqwest::header::AUTHORIZATION, header);
+    }
+    <HttpClient>::builder()
+        .set_headers(headers)
+        .build(format!("http://{local_addr}/"))
+        .unwrap()
+}
+
+fn assert_unauthorized(err: &ClientError) {
+    if let ClientError::Call(err) = err {
+        assert_eq!(err.code(), ErrorCode::ServerError(401).code());
+    } else {
+        panic!("Unexpected error: {err:?}");
+    }
+}
+
+/// Stores miniblock #1 with 2 transactions (one of which is initiated by the authenticated account)
+/// and 3 events (2 of which relate to the account). Returns hashes of the transactions.
+async fn prepare_storage(storage: &mut Connection<'_, Core>) -> anyhow::Result<(H256, H256)> {
+    let mut account_tx = create_l2_transaction(10, 200);
+    account_tx.common_data.initiator_address = account();
+    let other_tx = create_l2_transaction(10, 200);
+    let tx_results = [
+        execute_l2_transaction(account_tx),
+        execute_l2_transaction(other_tx),
+    ];
+    store_miniblock(storage, MiniblockNumber(1), &tx_results).await?;
+
+    let tx_location = IncludedTxLocation {
+        tx_hash: tx_results[1].hash,
+        tx_index_in_miniblock: 1,
+        tx_initiator_address: tx_results[1].transaction.initiator_account(),
+    };
+    let events: Vec<_> = [
+        (account(), vec![]),
+        (Address::repeat_byte(1), vec![address_to_h256(&account())]),
+        (Address::repeat_byte(1), vec![H256::repeat_byte(1)]),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (address, indexed_topics))| VmEvent {
+        location: (L1BatchNumber(1), i as u32),
+        address,
+        indexed_topics,
+        value: vec![],
+    })
+    .collect();
+    storage
+        .events_dal()
+        .save_events(
+            MiniblockNumber(1),
+            &[(tx_location, events.iter().collect())],
+        )
+        .await;
+    Ok((tx_results[0].hash, tx_results[1].hash))
+}
+
+#[tokio::test]
+async fn private_mode_basics() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let network_config = NetworkConfig::for_tests();
+    let mut storage = pool.connection().await.unwrap();
+    StorageInitialization::Genesis
+        .prepare_storage(&network_config, &mut storage)
+        .await
+        .unwrap();
+    let (account_tx_hash, other_tx_hash) = prepare_storage(&mut storage).await.unwrap();
+    drop(storage);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let contracts_config = ContractsConfig::for_tests();
+    let web3_config = Web3JsonRpcConfig::for_tests();
+    let mut api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
+    api_config.private_mode = true;
+    api_config.max_session_token_ttl = Duration::from_secs(7_200);
+    let chain_id = api_config.l2_chain_id;
+    let mut server_handles = spawn_http_server(
+        api_config,
+        pool.clone(),
+        MockTransactionExecutor::default(),
+        Arc::default(),
+        stop_receiver,
+    )
+    .await;
+    let local_addr = server_handles.wait_until_ready().await;
+
+    // Unauthenticated client can only access public data.
+    let client = create_client(local_addr, None);
+    let block_number = client.get_block_number().await.unwrap();
+    assert_eq!(block_number, U64::from(1));
+    let err = client.get_balance(account(), None).await.unwrap_err();
+    assert_unauthorized(&err);
+    let err = client
+        .get_transaction_count(account(), None)
+        .await
+        .unwrap_err();
+    assert_unauthorized(&err);
+    let tx = client
+        .get_transaction_by_hash(account_tx_hash)
+        .await
+        .unwrap();
+    assert!(tx.is_none());
+    let block = client
+        .get_block_by_number(api::BlockNumber::Number(1.into()), false)
+        .await
+        .unwrap()
+        .expect("no block");
+    assert!(block.transactions.is_empty());
+    let tx_count = client
+        .get_block_transaction_count_by_number(api::BlockNumber::Number(1.into()))
+        .await
+        .unwrap();
+    assert_eq!(tx_count, Some(U256::zero()));
+    let block_details = client
+        .get_block_details(MiniblockNumber(1))
+        .await
+        .unwrap()
+        .expect("no block details");
+    assert_eq!(block_details.base.l2_tx_count, 0);
+    let tx_details = client
+        .get_transaction_details(account_tx_hash)
+        .await
+        .unwrap();
+    assert!(tx_details.is_none());
+    let logs = client.get_logs(Filter::default()).await.unwrap();
+    assert!(logs.is_empty(), "{logs:?}");
+
+    // Authenticated client can access data related to the authenticated account.
+    let token = ApiSessionToken {
+        account: account(),
+        expires_at: seconds_since_epoch() + 3_600,
+    };
+    let token = token.sign(&PRIVATE_KEY, chain_id).unwrap().to_string();
+    let client = create_client(local_addr, Some(&token));
+    client.get_balance(account(), None).await.unwrap();
+    let err = client
+        .get_balance(Address::repeat_byte(1), None)
+        .await
+        .unwrap_err();
+    assert_unauthorized(&err);
+    let tx = client
+        .get_transaction_by_hash(account_tx_hash)
+        .await
+        .unwrap()
+        .expect("no transaction");
+    assert_eq!(tx.from, Some(account()));
+    let tx = client.get_transaction_by_hash(other_tx_hash).await.unwrap();
+    assert!(tx.is_none());
+    let receipt = client.get_transaction_receipt(other_tx_hash).await.unwrap();
+    assert!(receipt.is_none());
+    let block = client
+        .get_block_by_number(api::BlockNumber::Number(1.into()), false)
+        .await
+        .unwrap()
+        .expect("no block");
+    assert_eq!(
+        block.transactions,
+        [api::TransactionVariant::Hash(account_tx_hash)]
+    );
+    let tx_count = client
+        .get_block_transaction_count_by_number(api::BlockNumber::Number(1.into()))
+        .await
+        .unwrap();
+    assert_eq!(tx_count, Some(U256::one()));
+    let block_details = client
+        .get_block_details(MiniblockNumber(1))
+        .await
+        .unwrap()
+        .expect("no block details");
+    assert_eq!(block_details.base.l1_tx_count, 0);
+    assert_eq!(block_details.base.l2_tx_count, 1);
+    let tx_details = client
+        .get_transaction_details(account_tx_hash)
+        .await
+        .unwrap()
+        .expect("no transaction details");
+    assert_eq!(tx_details.initiator_address, account());
+    let tx_details = client.get_transaction_details(other_tx_hash).await.unwrap();
+    assert!(tx_details.is_none());
+    let logs = client.get_logs(Filter::default()).await.unwrap();
+    assert_eq!(logs.len(), 2, "{logs:?}");
+    assert_eq!(logs[0].address, account());
+    assert_eq!(logs[1].topics, [address_to_h256(&account())]);
+
+    // Requests with an expired token are rejected.
+    let expired_token = ApiSessionToken {
+        account: account(),
+        expires_at: seconds_since_epoch() - 1,
+    };
+    let expired_token = expired_token
+        .sign(&PRIVATE_KEY, chain_id)
+        .unwrap()
+        .to_string();
+    let client = create_client(local_addr, Some(&expired_token));
+    client.get_block_number().await.unwrap_err();
+
+    // Tokens with a lifetime exceeding the server limit are rejected as well.
+    let long_lived_token = ApiSessionToken {
+        account: account(),
+        expires_at: seconds_since_epoch() + 86_400,
+    };
+    let long_lived_token = long_lived_token
+        .sign(&PRIVATE_KEY, chain_id)
+        .unwrap()
+        .to_string();
+    let client = create_client(local_addr, Some(&long_lived_token));
+    client.get_block_number().await.unwrap_err();
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
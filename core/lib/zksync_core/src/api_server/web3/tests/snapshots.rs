@@ -38,14 +38,25 @@ impl HttpTest for SnapshotBasicsTest {
         seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
         storage
             .snapshots_dal()
-            .add_snapshot(L1BatchNumber(1), Self::CHUNK_COUNT, "file:///factory_deps")
+            .add_snapshot(
+                L1BatchNumber(1),
+                None,
+                Self::CHUNK_COUNT,
+                "file:///factory_deps",
+                H256::repeat_byte(0xff),
+            )
             .await?;
 
         for &chunk_id in &self.chunk_ids {
             let path = format!("file:///storage_logs/chunk{chunk_id}");
             storage
                 .snapshots_dal()
-                .add_storage_logs_filepath_for_snapshot(L1BatchNumber(1), chunk_id, &path)
+                .add_storage_logs_filepath_for_snapshot(
+                    L1BatchNumber(1),
+                    chunk_id,
+                    &path,
+                    H256::from_low_u64_be(chunk_id),
+                )
                 .await?;
         }
 
@@ -72,6 +83,11 @@ impl HttpTest for SnapshotBasicsTest {
             snapshot_header.factory_deps_filepath,
             "file:///factory_deps"
         );
+        assert_eq!(
+            snapshot_header.factory_deps_hash,
+            Some(H256::repeat_byte(0xff))
+        );
+        assert_eq!(snapshot_header.manifest_signature, None);
 
         assert_eq!(
             snapshot_header.storage_logs_chunks.len(),
@@ -80,6 +96,7 @@ impl HttpTest for SnapshotBasicsTest {
         for chunk in &snapshot_header.storage_logs_chunks {
             assert!(self.chunk_ids.contains(&chunk.chunk_id));
             assert!(chunk.filepath.starts_with("file:///storage_logs/"));
+            assert_eq!(chunk.hash, Some(H256::from_low_u64_be(chunk.chunk_id)));
         }
         Ok(())
     }
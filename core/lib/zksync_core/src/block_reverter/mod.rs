@@ -1,27 +1,32 @@
-use std::{path::Path, time::Duration};
+use std::{path::Path, sync::Arc, time::Duration};
 
+use anyhow::Context as _;
 use bitflags::bitflags;
 use serde::Serialize;
 use tokio::time::sleep;
-use zksync_config::{ContractsConfig, ETHSenderConfig};
+use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
 use zksync_contracts::zksync_contract;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
-use zksync_eth_signer::{EthereumSigner, PrivateKeySigner, TransactionParameters};
+use zksync_eth_client::{
+    clients::PKSigningClient, BoundEthInterface, CallFunctionArgs, EthInterface, Options,
+};
 use zksync_merkle_tree::domain::ZkSyncTree;
 use zksync_state::RocksdbStorage;
 use zksync_storage::RocksDB;
 use zksync_types::{
-    aggregated_operations::AggregatedActionType,
-    ethabi::Token,
-    web3::{
-        contract::{Contract, Options},
-        transports::Http,
-        types::{BlockId, BlockNumber},
-        Web3,
-    },
-    L1BatchNumber, PackedEthSignature, H160, H256, U256,
+    aggregated_operations::AggregatedActionType, ethabi::Token, web3::contract::tokens::Detokenize,
+    L1BatchNumber, H160, H256, U256,
+};
+
+pub use self::plan::{
+    L1ContractState, L1RevertTransaction, RemovedEthTx, RollbackPlan, RollbackReport,
+    RollbackRequest,
 };
 
+mod plan;
+#[cfg(test)]
+mod tests;
+
 bitflags! {
     pub struct BlockReverterFlags: u32 {
         const POSTGRES = 0b_0001;
@@ -44,30 +49,32 @@ pub enum L1ExecutedBatchesRevert {
 
 #[derive(Debug)]
 pub struct BlockReverterEthConfig {
-    eth_client_url: String,
-    reverter_private_key: H256,
-    reverter_address: H160,
-    diamond_proxy_addr: H160,
+    /// L1 client bound to the diamond proxy contract and signing transactions with the reverter private key.
+    eth_client: Arc<dyn BoundEthInterface>,
     validator_timelock_addr: H160,
     default_priority_fee_per_gas: u64,
+    /// Interval between checks for the revert transaction receipt.
+    receipt_poll_interval: Duration,
 }
 
 impl BlockReverterEthConfig {
-    pub fn new(eth_config: ETHSenderConfig, contract: ContractsConfig, web3_url: String) -> Self {
-        let pk = eth_config
-            .sender
-            .private_key()
-            .expect("Private key is required for block reversion");
-        let operator_address = PackedEthSignature::address_from_private_key(&pk)
-            .expect("Failed to get address from private key");
-
+    /// Creates a config with an L1 client routing requests to the main and fallback L1 providers specified
+    /// in `eth_client`.
+    pub fn new(
+        eth_config: ETHSenderConfig,
+        contract: ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> Self {
+        assert!(
+            eth_config.sender.private_key().is_some(),
+            "Private key is required for block reversion"
+        );
+        let eth_client = PKSigningClient::from_config(&eth_config, &contract, eth_client);
         Self {
-            eth_client_url: web3_url,
-            reverter_private_key: pk,
-            reverter_address: operator_address,
-            diamond_proxy_addr: contract.diamond_proxy_addr,
+            eth_client: Arc::new(eth_client),
             validator_timelock_addr: contract.validator_timelock_addr,
             default_priority_fee_per_gas: eth_config.gas_adjuster.default_priority_fee_per_gas,
+            receipt_poll_interval: Duration::from_secs(5),
         }
     }
 }
@@ -142,12 +149,13 @@ impl BlockReverter {
                 .blocks_dal()
                 .get_number_of_last_l1_batch_executed_on_eth()
                 .await
-                .unwrap()
-                .expect("failed to get last executed L1 batch");
-            assert!(
-                last_l1_batch_to_keep >= last_executed_l1_batch,
-                "Attempt to revert already executed L1 batches"
-            );
+                .unwrap();
+            if let Some(last_executed_l1_batch) = last_executed_l1_batch {
+                assert!(
+                    last_l1_batch_to_keep >= last_executed_l1_batch,
+                    "Attempt to revert already executed L1 batches"
+                );
+            }
         }
 
         // Tree needs to be reverted first to keep state recoverable
@@ -319,73 +327,73 @@ impl BlockReverter {
         transaction.commit().await.unwrap();
     }
 
-    /// Sends revert transaction to L1.
+    /// Sends revert transaction to L1 and waits until it is successfully executed.
     pub async fn send_ethereum_revert_transaction(
         &self,
         last_l1_batch_to_keep: L1BatchNumber,
         priority_fee_per_gas: U256,
         nonce: u64,
-    ) {
+    ) -> anyhow::Result<()> {
         let eth_config = self
             .eth_config
             .as_ref()
-            .expect("eth_config is not provided");
+            .context("eth_config is not provided")?;
+        let eth_client = eth_config.eth_client.as_ref();
 
-        let web3 = Web3::new(Http::new(&eth_config.eth_client_url).unwrap());
         let contract = zksync_contract();
-        let signer = PrivateKeySigner::new(eth_config.reverter_private_key);
-        let chain_id = web3.eth().chain_id().await.unwrap().as_u64();
-
         let revert_function = contract
             .function("revertBlocks")
             .or_else(|_| contract.function("revertBatches"))
-            .expect(
+            .context(
                 "Either `revertBlocks` or `revertBatches` function must be present in contract",
-            );
+            )?;
         let data = revert_function
             .encode_input(&[Token::Uint(last_l1_batch_to_keep.0.into())])
-            .unwrap();
-
-        let base_fee = web3
-            .eth()
-            .block(BlockId::Number(BlockNumber::Pending))
-            .await
-            .unwrap()
-            .unwrap()
-            .base_fee_per_gas
-            .unwrap();
+            .context("failed encoding revert transaction calldata")?;
 
-        let tx = TransactionParameters {
-            to: eth_config.validator_timelock_addr.into(),
-            data,
-            chain_id,
-            nonce: nonce.into(),
-            max_priority_fee_per_gas: priority_fee_per_gas,
-            max_fee_per_gas: base_fee + priority_fee_per_gas,
-            gas: 5_000_000.into(),
+        let options = Options {
+            nonce: Some(nonce.into()),
+            max_priority_fee_per_gas: Some(priority_fee_per_gas),
+            gas: Some(5_000_000.into()),
             ..Default::default()
         };
-
-        let signed_tx = signer.sign_transaction(tx).await.unwrap();
-        let hash = web3
-            .eth()
-            .send_raw_transaction(signed_tx.into())
+        let signed_tx = eth_client
+            .sign_prepared_tx_for_addr(
+                data,
+                eth_config.validator_timelock_addr,
+                options,
+                "block_reverter",
+            )
             .await
-            .unwrap();
+            .context("failed signing revert transaction")?;
+        let hash = eth_client
+            .send_raw_tx(signed_tx.raw_tx)
+            .await
+            .context("failed sending revert transaction")?;
+        tracing::info!("sent revert transaction {hash:?}");
 
         loop {
-            if let Some(receipt) = web3.eth().transaction_receipt(hash).await.unwrap() {
-                assert_eq!(receipt.status, Some(1.into()), "revert transaction failed");
+            let receipt = eth_client
+                .tx_receipt(hash, "block_reverter")
+                .await
+                .context("failed getting revert transaction receipt")?;
+            if let Some(receipt) = receipt {
+                anyhow::ensure!(
+                    receipt.status == Some(1.into()),
+                    "revert transaction {hash:?} failed"
+                );
                 tracing::info!("revert transaction has completed");
-                return;
-            } else {
-                tracing::info!("waiting for L1 transaction confirmation...");
-                sleep(Duration::from_secs(5)).await;
+                return Ok(());
             }
+            tracing::info!("waiting for L1 transaction confirmation...");
+            sleep(eth_config.receipt_poll_interval).await;
         }
     }
 
-    async fn get_l1_batch_number_from_contract(&self, op: AggregatedActionType) -> L1BatchNumber {
+    async fn get_l1_batch_number_from_contract(
+        &self,
+        op: AggregatedActionType,
+    ) -> anyhow::Result<L1BatchNumber> {
         let function_name = match op {
             AggregatedActionType::Commit => "getTotalBatchesCommitted",
             AggregatedActionType::PublishProofOnchain => "getTotalBatchesVerified",
@@ -394,34 +402,29 @@ impl BlockReverter {
         let eth_config = self
             .eth_config
             .as_ref()
-            .expect("eth_config is not provided");
+            .context("eth_config is not provided")?;
 
-        let web3 = Web3::new(Http::new(&eth_config.eth_client_url).unwrap());
-        let contract = {
-            let abi = zksync_contract();
-            let contract_address = eth_config.diamond_proxy_addr;
-            Contract::new(web3.eth(), contract_address, abi)
-        };
-
-        let block_number: U256 = contract
-            .query(function_name, (), None, Options::default(), None)
+        let tokens = eth_config
+            .eth_client
+            .call_main_contract_function(CallFunctionArgs::new(function_name, ()))
             .await
-            .unwrap();
-
-        L1BatchNumber(block_number.as_u32())
+            .with_context(|| format!("failed calling `{function_name}` on L1 contract"))?;
+        let block_number = U256::from_tokens(tokens)
+            .with_context(|| format!("invalid `{function_name}` output"))?;
+        Ok(L1BatchNumber(block_number.as_u32()))
     }
 
     /// Returns suggested values for rollback.
-    pub async fn suggested_values(&self) -> SuggestedRollbackValues {
+    pub async fn suggested_values(&self) -> anyhow::Result<SuggestedRollbackValues> {
         let last_committed_l1_batch_number = self
             .get_l1_batch_number_from_contract(AggregatedActionType::Commit)
-            .await;
+            .await?;
         let last_verified_l1_batch_number = self
             .get_l1_batch_number_from_contract(AggregatedActionType::PublishProofOnchain)
-            .await;
+            .await?;
         let last_executed_l1_batch_number = self
             .get_l1_batch_number_from_contract(AggregatedActionType::Execute)
-            .await;
+            .await?;
         tracing::info!(
             "Last L1 batch numbers on contract: committed {last_committed_l1_batch_number}, \
              verified {last_verified_l1_batch_number}, executed {last_executed_l1_batch_number}"
@@ -430,23 +433,22 @@ impl BlockReverter {
         let eth_config = self
             .eth_config
             .as_ref()
-            .expect("eth_config is not provided");
+            .context("eth_config is not provided")?;
 
         let priority_fee = eth_config.default_priority_fee_per_gas;
 
-        let web3 = Web3::new(Http::new(&eth_config.eth_client_url).unwrap());
-        let nonce = web3
-            .eth()
-            .transaction_count(eth_config.reverter_address, Some(BlockNumber::Pending))
+        let nonce = eth_config
+            .eth_client
+            .pending_nonce("block_reverter")
             .await
-            .unwrap()
+            .context("failed getting pending nonce")?
             .as_u64();
 
-        SuggestedRollbackValues {
+        Ok(SuggestedRollbackValues {
             last_executed_l1_batch_number,
             nonce,
             priority_fee,
-        }
+        })
     }
 
     /// Clears failed L1 transactions
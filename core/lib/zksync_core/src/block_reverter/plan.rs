@@ -0,0 +1,374 @@
+//! Dry-run planning of rollbacks.
+
+use std::path::Path;
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_dal::CoreDal;
+use zksync_eth_client::BoundEthInterface;
+use zksync_merkle_tree::domain::ZkSyncTree;
+use zksync_state::RocksdbStorage;
+use zksync_storage::RocksDB;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, L1BatchNumber, MiniblockNumber, U256,
+};
+
+use super::{BlockReverter, BlockReverterFlags};
+
+/// Parts of the node state requested to be rolled back.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RollbackRequest {
+    pub last_l1_batch_to_keep: L1BatchNumber,
+    pub rollback_postgres: bool,
+    pub rollback_tree: bool,
+    pub rollback_sk_cache: bool,
+    /// Whether to send a transaction reverting L1 batches committed on L1.
+    pub revert_on_l1: bool,
+    /// Priority fee for the L1 revert transaction. If not specified, the default priority fee is used.
+    pub priority_fee_per_gas: Option<u64>,
+}
+
+impl RollbackRequest {
+    fn flags(&self) -> BlockReverterFlags {
+        let mut flags = BlockReverterFlags::empty();
+        flags.set(BlockReverterFlags::POSTGRES, self.rollback_postgres);
+        flags.set(BlockReverterFlags::TREE, self.rollback_tree);
+        flags.set(BlockReverterFlags::SK_CACHE, self.rollback_sk_cache);
+        flags
+    }
+}
+
+/// Parameters of the L1 transaction reverting committed L1 batches.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct L1RevertTransaction {
+    pub nonce: u64,
+    pub priority_fee_per_gas: u64,
+}
+
+/// Numbers of the last L1 batches committed, proven and executed according to the L1 contract.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct L1ContractState {
+    pub last_committed_l1_batch: L1BatchNumber,
+    pub last_proven_l1_batch: L1BatchNumber,
+    pub last_executed_l1_batch: L1BatchNumber,
+}
+
+/// Ethereum transaction removed from the `eth_txs` table during the rollback.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemovedEthTx {
+    pub id: u32,
+    pub tx_type: String,
+    pub confirmed: bool,
+}
+
+/// Report on what would be reverted by a rollback. All numbers reflect the node state
+/// at the time the plan was created.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RollbackReport {
+    pub last_sealed_l1_batch: L1BatchNumber,
+    pub last_sealed_miniblock: MiniblockNumber,
+    pub last_miniblock_to_keep: MiniblockNumber,
+    pub reverted_l1_batch_count: u32,
+    pub reverted_miniblock_count: u32,
+    /// Number of L1 (priority) transactions returned to the mempool.
+    pub l1_txs_returned_to_mempool: u64,
+    /// Number of L2 transactions returned to the mempool.
+    pub l2_txs_returned_to_mempool: u64,
+    pub removed_eth_txs: Vec<RemovedEthTx>,
+    /// L1 batches with snapshots that become invalid after the rollback. Snapshots must be removed separately.
+    pub invalidated_snapshots: Vec<L1BatchNumber>,
+    /// Reverted L1 batches with generated proofs.
+    pub invalidated_proofs: Vec<L1BatchNumber>,
+    /// State of the L1 contract; `None` if the Ethereum config is not provided.
+    pub l1_contract_state: Option<L1ContractState>,
+    /// Last L1 batch committed on L1 according to Postgres.
+    pub last_committed_l1_batch_in_db: Option<L1BatchNumber>,
+    /// Whether any of the reverted L1 batches is committed on L1.
+    pub reverts_committed_l1_batches: bool,
+    /// Next L1 batch to be processed by the Merkle tree; `None` if the tree is not found.
+    pub tree_next_l1_batch: Option<L1BatchNumber>,
+    /// Next L1 batch to be processed by the state keeper cache; `None` if the cache is not found.
+    pub sk_cache_next_l1_batch: Option<L1BatchNumber>,
+}
+
+/// Rollback plan produced by [`BlockReverter::plan_rollback()`]. The plan is serializable, so that it can
+/// be reviewed and then passed to [`BlockReverter::execute_plan()`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RollbackPlan {
+    pub request: RollbackRequest,
+    /// L1 revert transaction to send; `None` if L1 batches are not reverted on L1.
+    pub l1_revert_transaction: Option<L1RevertTransaction>,
+    pub report: RollbackReport,
+}
+
+impl BlockReverter {
+    /// Creates a plan for the requested rollback without changing any state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the requested rollback is unsafe, e.g. reverts L1 batches executed on L1,
+    /// or leaves the node in an inconsistent state.
+    pub async fn plan_rollback(&self, request: RollbackRequest) -> anyhow::Result<RollbackPlan> {
+        let last_l1_batch_to_keep = request.last_l1_batch_to_keep;
+        anyhow::ensure!(
+            request.rollback_postgres
+                || request.rollback_tree
+                || request.rollback_sk_cache
+                || request.revert_on_l1,
+            "rollback plan doesn't roll back anything; specify at least one of the state components"
+        );
+
+        let mut storage = self.connection_pool.connection().await?;
+        let last_sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .context("no L1 batches in Postgres")?;
+        anyhow::ensure!(
+            last_l1_batch_to_keep < last_sealed_l1_batch,
+            "nothing to revert: L1 batch #{last_l1_batch_to_keep} is the last sealed one or is not sealed yet"
+        );
+        let last_sealed_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await?
+            .context("no miniblocks in Postgres")?;
+        let (_, last_miniblock_to_keep) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch_to_keep)
+            .await?
+            .with_context(|| format!("L1 batch #{last_l1_batch_to_keep} has no miniblocks"))?;
+
+        let last_executed_l1_batch_in_db = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?;
+        let last_committed_l1_batch_in_db = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_committed_on_eth()
+            .await?;
+        let (l1_tx_count, l2_tx_count) = storage
+            .transactions_dal()
+            .count_transactions_after_miniblock(last_miniblock_to_keep)
+            .await?;
+        let removed_eth_txs = storage
+            .eth_sender_dal()
+            .get_eth_txs_to_delete(last_l1_batch_to_keep)
+            .await?
+            .into_iter()
+            .map(|tx| RemovedEthTx {
+                id: tx.id,
+                tx_type: tx.tx_type.to_string(),
+                confirmed: tx.confirmed,
+            })
+            .collect();
+        let mut invalidated_snapshots: Vec<_> = storage
+            .snapshots_dal()
+            .get_all_snapshots_metadata()
+            .await?
+            .into_iter()
+            .map(|snapshot| snapshot.l1_batch_number)
+            .filter(|&number| number > last_l1_batch_to_keep)
+            .collect();
+        invalidated_snapshots.sort_unstable();
+        let invalidated_proofs = storage
+            .proof_generation_dal()
+            .get_l1_batches_with_generated_proofs_after(last_l1_batch_to_keep)
+            .await?;
+        drop(storage);
+
+        let l1_contract_state = if self.eth_config.is_some() {
+            Some(L1ContractState {
+                last_committed_l1_batch: self
+                    .get_l1_batch_number_from_contract(AggregatedActionType::Commit)
+                    .await?,
+                last_proven_l1_batch: self
+                    .get_l1_batch_number_from_contract(AggregatedActionType::PublishProofOnchain)
+                    .await?,
+                last_executed_l1_batch: self
+                    .get_l1_batch_number_from_contract(AggregatedActionType::Execute)
+                    .await?,
+            })
+        } else {
+            None
+        };
+
+        let last_executed_l1_batch = l1_contract_state
+            .map(|state| state.last_executed_l1_batch)
+            .max(last_executed_l1_batch_in_db);
+        if let Some(last_executed_l1_batch) = last_executed_l1_batch {
+            anyhow::ensure!(
+                last_l1_batch_to_keep >= last_executed_l1_batch,
+                "attempt to revert L1 batches executed on L1 (last executed L1 batch: #{last_executed_l1_batch})"
+            );
+        }
+        let last_committed_l1_batch = l1_contract_state
+            .map(|state| state.last_committed_l1_batch)
+            .max(last_committed_l1_batch_in_db);
+        let reverts_committed_l1_batches =
+            last_committed_l1_batch.map_or(false, |number| number > last_l1_batch_to_keep);
+
+        if request.revert_on_l1 {
+            anyhow::ensure!(
+                self.eth_config.is_some(),
+                "reverting L1 batches on L1 requires the Ethereum config"
+            );
+            anyhow::ensure!(
+                reverts_committed_l1_batches,
+                "nothing to revert on L1: no L1 batches after #{last_l1_batch_to_keep} are committed"
+            );
+        } else if request.rollback_postgres && reverts_committed_l1_batches {
+            anyhow::bail!(
+                "rolling back Postgres would revert L1 batches committed on L1 (last committed L1 batch: #{}); \
+                 these L1 batches must be reverted on L1 as well",
+                last_committed_l1_batch.unwrap()
+            );
+        }
+
+        let tree_next_l1_batch = self.tree_next_l1_batch()?;
+        if request.rollback_postgres && !request.rollback_tree {
+            if let Some(tree_next_l1_batch) = tree_next_l1_batch {
+                anyhow::ensure!(
+                    tree_next_l1_batch <= last_l1_batch_to_keep + 1,
+                    "rolling back Postgres without rolling back Merkle tree (next L1 batch: #{tree_next_l1_batch}) \
+                     will require rebuilding the tree from scratch"
+                );
+            }
+        }
+
+        let sk_cache_next_l1_batch = self.sk_cache_next_l1_batch().await?;
+        if request.rollback_sk_cache {
+            anyhow::ensure!(
+                sk_cache_next_l1_batch.is_some(),
+                "state keeper cache is not found at `{}`",
+                self.state_keeper_cache_path
+            );
+        }
+
+        let l1_revert_transaction = if request.revert_on_l1 {
+            Some(
+                self.l1_revert_transaction(request.priority_fee_per_gas)
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        Ok(RollbackPlan {
+            request,
+            l1_revert_transaction,
+            report: RollbackReport {
+                last_sealed_l1_batch,
+                last_sealed_miniblock,
+                last_miniblock_to_keep,
+                reverted_l1_batch_count: last_sealed_l1_batch.0 - last_l1_batch_to_keep.0,
+                reverted_miniblock_count: last_sealed_miniblock.0 - last_miniblock_to_keep.0,
+                l1_txs_returned_to_mempool: l1_tx_count,
+                l2_txs_returned_to_mempool: l2_tx_count,
+                removed_eth_txs,
+                invalidated_snapshots,
+                invalidated_proofs,
+                l1_contract_state,
+                last_committed_l1_batch_in_db,
+                reverts_committed_l1_batches,
+                tree_next_l1_batch,
+                sk_cache_next_l1_batch,
+            },
+        })
+    }
+
+    /// Executes a plan previously created by [`Self::plan_rollback()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node state has changed since the plan was created, or if the L1 revert transaction
+    /// has failed or hasn't reverted all requested L1 batches. In the latter cases, the local node state is not rolled back.
+    pub async fn execute_plan(&self, plan: &RollbackPlan) -> anyhow::Result<()> {
+        let actual_plan = self
+            .plan_rollback(plan.request)
+            .await
+            .context("rollback is no longer safe")?;
+        anyhow::ensure!(
+            actual_plan == *plan,
+            "node state has changed since the plan was created; create a new plan. Actual plan: {actual_plan:#?}"
+        );
+
+        let last_l1_batch_to_keep = plan.request.last_l1_batch_to_keep;
+        if let Some(tx) = &plan.l1_revert_transaction {
+            tracing::info!("sending L1 revert transaction with nonce {}", tx.nonce);
+            self.send_ethereum_revert_transaction(
+                last_l1_batch_to_keep,
+                U256::from(tx.priority_fee_per_gas),
+                tx.nonce,
+            )
+            .await
+            .context("failed reverting L1 batches on L1")?;
+
+            // Rolling back Postgres while reverted L1 batches are still committed on L1 would make the node state
+            // inconsistent with L1, so we double-check the L1 state.
+            let last_committed_l1_batch = self
+                .get_l1_batch_number_from_contract(AggregatedActionType::Commit)
+                .await?;
+            anyhow::ensure!(
+                last_committed_l1_batch <= last_l1_batch_to_keep,
+                "L1 batches are still committed on L1 after the revert transaction (last committed L1 batch: \
+                 #{last_committed_l1_batch}); the local node state is not rolled back"
+            );
+        }
+        self.rollback_db(last_l1_batch_to_keep, plan.request.flags())
+            .await;
+        Ok(())
+    }
+
+    fn tree_next_l1_batch(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        let merkle_tree_path = Path::new(&self.merkle_tree_path);
+        if !merkle_tree_path.exists() {
+            return Ok(None);
+        }
+        let db = RocksDB::new(merkle_tree_path).with_context(|| {
+            format!(
+                "failed initializing RocksDB for Merkle tree at `{}`",
+                self.merkle_tree_path
+            )
+        })?;
+        Ok(Some(
+            ZkSyncTree::new_lightweight(db.into()).next_l1_batch_number(),
+        ))
+    }
+
+    async fn sk_cache_next_l1_batch(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        if !Path::new(&self.state_keeper_cache_path).exists() {
+            return Ok(None);
+        }
+        let sk_cache = RocksdbStorage::builder(self.state_keeper_cache_path.as_ref())
+            .await
+            .with_context(|| {
+                format!(
+                    "failed initializing state keeper cache at `{}`",
+                    self.state_keeper_cache_path
+                )
+            })?;
+        Ok(sk_cache.l1_batch_number().await)
+    }
+
+    async fn l1_revert_transaction(
+        &self,
+        priority_fee_per_gas: Option<u64>,
+    ) -> anyhow::Result<L1RevertTransaction> {
+        let eth_config = self
+            .eth_config
+            .as_ref()
+            .context("eth_config is not provided")?;
+        let nonce = eth_config
+            .eth_client
+            .pending_nonce("block_reverter")
+            .await
+            .context("failed getting pending nonce for L1 revert transaction")?
+            .as_u64();
+        Ok(L1RevertTransaction {
+            nonce,
+            priority_fee_per_gas: priority_fee_per_gas
+                .unwrap_or(eth_config.default_priority_fee_per_gas),
+        })
+    }
+}
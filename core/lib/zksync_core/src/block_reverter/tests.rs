@@ -0,0 +1,344 @@
+//! Tests for the block reverter.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use test_casing::test_casing;
+use zksync_dal::Connection;
+use zksync_eth_client::clients::MockEthereum;
+use zksync_types::{L2ChainId, MiniblockNumber};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{create_l1_batch, create_miniblock},
+};
+
+/// Seals an L1 batch with the specified number consisting of a single miniblock with the same number.
+async fn seal_l1_batch(storage: &mut Connection<'_, Core>, number: u32) {
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(number))
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(number))
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+        .await
+        .unwrap();
+}
+
+async fn prepare_storage(pool: &ConnectionPool<Core>, last_l1_batch: u32) {
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=last_l1_batch {
+        seal_l1_batch(&mut storage, number).await;
+    }
+}
+
+async fn mark_l1_batch_as_sent(
+    pool: &ConnectionPool<Core>,
+    number: u32,
+    tx_type: AggregatedActionType,
+) {
+    pool.connection()
+        .await
+        .unwrap()
+        .eth_sender_dal()
+        .insert_bogus_confirmed_eth_tx(
+            L1BatchNumber(number),
+            tx_type,
+            H256::from_low_u64_be(number.into()),
+            chrono::Utc::now(),
+        )
+        .await
+        .unwrap();
+}
+
+fn create_reverter(
+    pool: ConnectionPool<Core>,
+    eth_config: Option<BlockReverterEthConfig>,
+) -> BlockReverter {
+    // Neither the Merkle tree nor the state keeper cache exist at these paths.
+    BlockReverter::new(
+        NodeRole::External,
+        "/nonexistent/state_keeper_cache".to_owned(),
+        "/nonexistent/merkle_tree".to_owned(),
+        eth_config,
+        pool,
+        L1ExecutedBatchesRevert::Disallowed,
+    )
+}
+
+fn postgres_rollback_request(last_l1_batch_to_keep: u32) -> RollbackRequest {
+    RollbackRequest {
+        last_l1_batch_to_keep: L1BatchNumber(last_l1_batch_to_keep),
+        rollback_postgres: true,
+        rollback_tree: false,
+        rollback_sk_cache: false,
+        revert_on_l1: false,
+        priority_fee_per_gas: None,
+    }
+}
+
+/// Numbers of the last L1 batches committed, proven and executed according to the mock L1 contract.
+#[derive(Debug, Default)]
+struct MockL1State {
+    committed_batches: AtomicU32,
+    proven_batches: AtomicU32,
+    executed_batches: AtomicU32,
+}
+
+fn mock_eth_config(l1_state: Arc<MockL1State>) -> (BlockReverterEthConfig, Arc<MockEthereum>) {
+    let eth_client = MockEthereum::default().with_call_handler(move |call| {
+        let count = match call.function_name() {
+            "getTotalBatchesCommitted" => &l1_state.committed_batches,
+            "getTotalBatchesVerified" => &l1_state.proven_batches,
+            "getTotalBatchesExecuted" => &l1_state.executed_batches,
+            name => panic!("unexpected L1 call: {name}"),
+        };
+        Token::Uint(count.load(Ordering::SeqCst).into())
+    });
+    let eth_client = Arc::new(eth_client);
+    let eth_config = BlockReverterEthConfig {
+        eth_client: eth_client.clone(),
+        validator_timelock_addr: H160::repeat_byte(0x23),
+        default_priority_fee_per_gas: 10,
+        receipt_poll_interval: Duration::from_millis(10),
+    };
+    (eth_config, eth_client)
+}
+
+async fn last_sealed_l1_batch(pool: &ConnectionPool<Core>) -> L1BatchNumber {
+    let mut storage = pool.connection().await.unwrap();
+    storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .unwrap()
+        .expect("no L1 batches")
+}
+
+#[tokio::test]
+async fn planning_postgres_rollback() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let block_reverter = create_reverter(pool.clone(), None);
+
+    let plan = block_reverter
+        .plan_rollback(postgres_rollback_request(1))
+        .await
+        .unwrap();
+    assert_eq!(plan.l1_revert_transaction, None);
+    let report = &plan.report;
+    assert_eq!(report.last_sealed_l1_batch, L1BatchNumber(3));
+    assert_eq!(report.last_sealed_miniblock, MiniblockNumber(3));
+    assert_eq!(report.last_miniblock_to_keep, MiniblockNumber(1));
+    assert_eq!(report.reverted_l1_batch_count, 2);
+    assert_eq!(report.reverted_miniblock_count, 2);
+    assert_eq!(report.l1_txs_returned_to_mempool, 0);
+    assert_eq!(report.l2_txs_returned_to_mempool, 0);
+    assert!(report.removed_eth_txs.is_empty());
+    assert_eq!(report.l1_contract_state, None);
+    assert_eq!(report.last_committed_l1_batch_in_db, None);
+    assert!(!report.reverts_committed_l1_batches);
+    assert_eq!(report.tree_next_l1_batch, None);
+    assert_eq!(report.sk_cache_next_l1_batch, None);
+
+    // Planning must not change the node state.
+    assert_eq!(last_sealed_l1_batch(&pool).await, L1BatchNumber(3));
+}
+
+#[tokio::test]
+async fn planning_rejects_empty_rollbacks() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let block_reverter = create_reverter(pool, None);
+
+    let request = RollbackRequest {
+        rollback_postgres: false,
+        ..postgres_rollback_request(1)
+    };
+    let err = block_reverter.plan_rollback(request).await.unwrap_err();
+    assert!(
+        err.to_string().contains("doesn't roll back anything"),
+        "{err:#}"
+    );
+
+    let err = block_reverter
+        .plan_rollback(postgres_rollback_request(3))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("nothing to revert"), "{err:#}");
+}
+
+#[tokio::test]
+async fn planning_rejects_reverting_executed_l1_batches() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    mark_l1_batch_as_sent(&pool, 2, AggregatedActionType::Execute).await;
+    let block_reverter = create_reverter(pool, None);
+
+    let err = block_reverter
+        .plan_rollback(postgres_rollback_request(1))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("executed on L1"), "{err:#}");
+}
+
+#[tokio::test]
+async fn planning_rejects_postgres_rollback_of_committed_l1_batches() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    mark_l1_batch_as_sent(&pool, 2, AggregatedActionType::Commit).await;
+    let block_reverter = create_reverter(pool, None);
+
+    let err = block_reverter
+        .plan_rollback(postgres_rollback_request(1))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("must be reverted on L1"),
+        "{err:#}"
+    );
+
+    let request = RollbackRequest {
+        revert_on_l1: true,
+        ..postgres_rollback_request(1)
+    };
+    let err = block_reverter.plan_rollback(request).await.unwrap_err();
+    assert!(
+        err.to_string().contains("requires the Ethereum config"),
+        "{err:#}"
+    );
+}
+
+#[tokio::test]
+async fn executing_postgres_rollback_plan() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let block_reverter = create_reverter(pool.clone(), None);
+
+    let plan = block_reverter
+        .plan_rollback(postgres_rollback_request(1))
+        .await
+        .unwrap();
+    block_reverter.execute_plan(&plan).await.unwrap();
+
+    assert_eq!(last_sealed_l1_batch(&pool).await, L1BatchNumber(1));
+    let mut storage = pool.connection().await.unwrap();
+    let last_miniblock = storage
+        .blocks_dal()
+        .get_sealed_miniblock_number()
+        .await
+        .unwrap();
+    assert_eq!(last_miniblock, Some(MiniblockNumber(1)));
+}
+
+#[tokio::test]
+async fn executing_outdated_plan() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let block_reverter = create_reverter(pool.clone(), None);
+
+    let plan = block_reverter
+        .plan_rollback(postgres_rollback_request(1))
+        .await
+        .unwrap();
+    seal_l1_batch(&mut pool.connection().await.unwrap(), 4).await;
+
+    let err = block_reverter.execute_plan(&plan).await.unwrap_err();
+    assert!(
+        err.to_string().contains("node state has changed"),
+        "{err:#}"
+    );
+    assert_eq!(last_sealed_l1_batch(&pool).await, L1BatchNumber(4));
+}
+
+#[derive(Debug, Clone, Copy)]
+enum L1RevertOutcome {
+    Success,
+    FailedTransaction,
+    BatchesStillCommitted,
+}
+
+#[test_casing(3, [
+    L1RevertOutcome::Success,
+    L1RevertOutcome::FailedTransaction,
+    L1RevertOutcome::BatchesStillCommitted,
+])]
+#[tokio::test]
+async fn executing_plan_with_l1_revert(outcome: L1RevertOutcome) {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let l1_state = Arc::new(MockL1State::default());
+    l1_state.committed_batches.store(3, Ordering::SeqCst);
+    let (eth_config, eth_client) = mock_eth_config(l1_state.clone());
+    let block_reverter = create_reverter(pool.clone(), Some(eth_config));
+
+    let request = RollbackRequest {
+        revert_on_l1: true,
+        ..postgres_rollback_request(1)
+    };
+    let plan = block_reverter.plan_rollback(request).await.unwrap();
+    assert_eq!(
+        plan.l1_revert_transaction,
+        Some(L1RevertTransaction {
+            nonce: 0,
+            priority_fee_per_gas: 10,
+        })
+    );
+    assert_eq!(
+        plan.report.l1_contract_state,
+        Some(L1ContractState {
+            last_committed_l1_batch: L1BatchNumber(3),
+            last_proven_l1_batch: L1BatchNumber(0),
+            last_executed_l1_batch: L1BatchNumber(0),
+        })
+    );
+    assert!(plan.report.reverts_committed_l1_batches);
+
+    let l1_driver = async {
+        while eth_client.sent_tx_count() == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // Let the reverter poll the receipt of the pending transaction a few times.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(last_sealed_l1_batch(&pool).await, L1BatchNumber(3));
+
+        let tx_hashes = eth_client.sent_tx_hashes();
+        assert_eq!(tx_hashes.len(), 1);
+        if matches!(outcome, L1RevertOutcome::Success) {
+            l1_state.committed_batches.store(1, Ordering::SeqCst);
+        }
+        let success = !matches!(outcome, L1RevertOutcome::FailedTransaction);
+        eth_client.execute_tx(tx_hashes[0], success, 1);
+    };
+    let (result, ()) = tokio::join!(block_reverter.execute_plan(&plan), l1_driver);
+
+    match outcome {
+        L1RevertOutcome::Success => {
+            result.unwrap();
+            assert_eq!(last_sealed_l1_batch(&pool).await, L1BatchNumber(1));
+        }
+        L1RevertOutcome::FailedTransaction => {
+            let err = result.unwrap_err();
+            assert!(
+                err.to_string().contains("failed reverting L1 batches"),
+                "{err:#}"
+            );
+            assert_eq!(last_sealed_l1_batch(&pool).await, L1BatchNumber(3));
+        }
+        L1RevertOutcome::BatchesStillCommitted => {
+            let err = result.unwrap_err();
+            assert!(err.to_string().contains("still committed"), "{err:#}");
+            assert_eq!(last_sealed_l1_batch(&pool).await, L1BatchNumber(3));
+        }
+    }
+}
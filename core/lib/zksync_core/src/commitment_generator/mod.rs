@@ -119,7 +119,7 @@ impl CommitmentGenerator {
         })
     }
 
-    async fn prepare_input(
+    pub(crate) async fn prepare_input(
         &self,
         l1_batch_number: L1BatchNumber,
     ) -> anyhow::Result<CommitmentInput> {
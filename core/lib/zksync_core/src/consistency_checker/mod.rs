@@ -5,9 +5,7 @@ use serde::Serialize;
 use tokio::sync::watch;
 use zksync_contracts::PRE_BOOJUM_COMMIT_FUNCTION;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
-use zksync_eth_client::{
-    clients::QueryClient, CallFunctionArgs, Error as L1ClientError, EthInterface,
-};
+use zksync_eth_client::{CallFunctionArgs, Error as L1ClientError, EthInterface};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::{
     i_executor::{commit::kzg::ZK_SYNC_BYTES_PER_BLOB, structures::CommitBatchInfo},
@@ -231,7 +229,15 @@ impl LocalL1BatchCommitData {
             );
         }
 
-        let local_token = CommitBatchInfo::new(&self.l1_batch, da).into_token();
+        let mut local_commitment = CommitBatchInfo::new(&self.l1_batch, da);
+        // Inclusion data is produced by the external DA layer and cannot be reproduced locally,
+        // so it's taken from the reference; all other commitment fields are still checked.
+        if matches!(da, PubdataDA::Custom) {
+            let inclusion_data = CommitBatchInfo::extract_da_inclusion_data(reference)
+                .context("cannot extract DA inclusion data from reference commitment token")?;
+            local_commitment = local_commitment.with_da_inclusion_data(inclusion_data);
+        }
+        let local_token = local_commitment.into_token();
         anyhow::ensure!(
             local_token == *reference,
             "Locally reproduced commitment differs from the reference obtained from L1; \
@@ -261,23 +267,22 @@ impl ConsistencyChecker {
     const DEFAULT_SLEEP_INTERVAL: Duration = Duration::from_secs(5);
 
     pub fn new(
-        web3_url: &str,
+        l1_client: Box<dyn EthInterface>,
         max_batches_to_recheck: u32,
         pool: ConnectionPool<Core>,
-    ) -> anyhow::Result<Self> {
-        let web3 = QueryClient::new(web3_url).context("cannot create L1 Web3 client")?;
+    ) -> Self {
         let (health_check, health_updater) = ConsistencyCheckerHealthUpdater::new();
-        Ok(Self {
+        Self {
             contract: zksync_contracts::zksync_contract(),
             diamond_proxy_addr: None,
             max_batches_to_recheck,
             sleep_interval: Self::DEFAULT_SLEEP_INTERVAL,
-            l1_client: Box::new(web3),
+            l1_client,
             event_handler: Box::new(health_updater),
             l1_data_mismatch_behavior: L1DataMismatchBehavior::Log,
             pool,
             health_check,
-        })
+        }
     }
 
     pub fn with_diamond_proxy_addr(mut self, address: Address) -> Self {
@@ -379,7 +384,7 @@ impl ConsistencyChecker {
     }
 
     /// All returned errors are validation errors.
-    fn extract_commit_data(
+    pub(crate) fn extract_commit_data(
         commit_tx_input_data: &[u8],
         commit_function: &ethabi::Function,
         batch_number: L1BatchNumber,
@@ -220,6 +220,31 @@ fn extracting_commit_data_for_pre_boojum_batch() {
     );
 }
 
+#[test]
+fn verifying_commitment_with_custom_da() {
+    let local_data = LocalL1BatchCommitData {
+        l1_batch: create_l1_batch_with_metadata(1),
+        commit_tx_hash: H256::zero(),
+    };
+    let inclusion_data = b"inclusion data";
+    let reference = CommitBatchInfo::new(&local_data.l1_batch, PubdataDA::Custom)
+        .with_da_inclusion_data(inclusion_data)
+        .into_token();
+    assert_eq!(
+        CommitBatchInfo::extract_da_inclusion_data(&reference),
+        Some(&inclusion_data[..])
+    );
+    local_data.verify_commitment(&reference).unwrap();
+
+    let ethabi::Token::Tuple(mut bogus_reference) = reference else {
+        unreachable!();
+    };
+    bogus_reference[0] = ethabi::Token::Uint(2.into());
+    local_data
+        .verify_commitment(&ethabi::Token::Tuple(bogus_reference))
+        .unwrap_err();
+}
+
 #[derive(Debug, Clone, Copy)]
 enum SaveAction<'a> {
     InsertBatch(&'a L1BatchWithMetadata),
@@ -0,0 +1,93 @@
+//! Clients for external data availability (DA) layers.
+
+use std::{fmt, path::PathBuf};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use tokio::fs;
+use zksync_types::{web3::signing::keccak256, L1BatchNumber};
+
+/// Response returned by the DA layer when pubdata is dispatched to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatchResponse {
+    /// Identifier of the published pubdata (e.g., a blob pointer) used to query its inclusion status.
+    pub blob_id: String,
+}
+
+/// Data proving that the pubdata was included in the DA layer. It is embedded into the commit operation
+/// for the corresponding L1 batch and is interpreted by the DA verifier on L1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InclusionData {
+    pub data: Vec<u8>,
+}
+
+/// Client for an external DA layer used with `PubdataDA::Custom`.
+#[async_trait]
+pub trait DataAvailabilityClient: 'static + Send + Sync + fmt::Debug {
+    /// Publishes pubdata of the specified L1 batch to the DA layer.
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        pubdata: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse>;
+
+    /// Returns inclusion data for the previously dispatched blob, or `None` if its inclusion
+    /// is not confirmed yet.
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<InclusionData>>;
+}
+
+/// [`DataAvailabilityClient`] storing pubdata in the local file system. Inclusion is confirmed as soon as
+/// the pubdata is persisted; the inclusion data is the keccak256 hash of the stored pubdata.
+///
+/// Intended for local development and as a reference implementation / test double.
+#[derive(Debug)]
+pub struct FileBackedDAClient {
+    base_dir: PathBuf,
+}
+
+impl FileBackedDAClient {
+    pub async fn new(base_dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let base_dir = base_dir.into();
+        fs::create_dir_all(&base_dir)
+            .await
+            .with_context(|| format!("failed creating DA directory `{}`", base_dir.display()))?;
+        Ok(Self { base_dir })
+    }
+
+    fn blob_path(&self, blob_id: &str) -> PathBuf {
+        self.base_dir.join(format!("{blob_id}.bin"))
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for FileBackedDAClient {
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        pubdata: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        let blob_id = format!(
+            "l1_batch_{}_{}",
+            l1_batch_number.0,
+            hex::encode(keccak256(&pubdata))
+        );
+        let path = self.blob_path(&blob_id);
+        fs::write(&path, pubdata)
+            .await
+            .with_context(|| format!("failed writing blob to `{}`", path.display()))?;
+        Ok(DispatchResponse { blob_id })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<InclusionData>> {
+        let path = self.blob_path(blob_id);
+        match fs::read(&path).await {
+            Ok(pubdata) => Ok(Some(InclusionData {
+                data: keccak256(&pubdata).to_vec(),
+            })),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => {
+                Err(err).with_context(|| format!("failed reading blob from `{}`", path.display()))
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Gauge, Histogram, Metrics, Unit};
+
+const BLOB_SIZE_BUCKETS: Buckets = Buckets::exponential(1.0..=1_048_576.0, 4.0);
+
+/// Metrics for the data availability dispatcher.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_da_dispatcher")]
+pub(super) struct DataAvailabilityDispatcherMetrics {
+    /// Latency of dispatching pubdata of a single L1 batch to the DA layer, including retries.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub blob_dispatch_latency: Histogram<Duration>,
+    /// Size of the pubdata dispatched to the DA layer.
+    #[metrics(buckets = BLOB_SIZE_BUCKETS, unit = Unit::Bytes)]
+    pub blob_size: Histogram<usize>,
+    /// Number of failed calls to the DA layer that were retried.
+    pub call_retries: Counter,
+    /// Number of the last L1 batch dispatched to the DA layer.
+    pub last_dispatched_l1_batch: Gauge<u64>,
+    /// Number of the last L1 batch with confirmed inclusion in the DA layer.
+    pub last_included_l1_batch: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DataAvailabilityDispatcherMetrics> = vise::Global::new();
@@ -0,0 +1,178 @@
+//! Data availability (DA) dispatcher. Publishes pubdata of sealed L1 batches to an external DA layer
+//! and tracks its inclusion; with `PubdataDA::Custom`, an L1 batch is only eligible to be committed
+//! once its inclusion data is persisted by the dispatcher.
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use tokio::{sync::watch, time::Instant};
+use zksync_config::configs::{da_dispatcher::DAClientConfig, DADispatcherConfig};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+pub use self::client::{
+    DataAvailabilityClient, DispatchResponse, FileBackedDAClient, InclusionData,
+};
+use self::metrics::METRICS;
+
+mod client;
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Creates a DA client based on the provided config.
+pub async fn create_da_client(
+    config: &DADispatcherConfig,
+) -> anyhow::Result<Arc<dyn DataAvailabilityClient>> {
+    Ok(match &config.client {
+        DAClientConfig::FileBacked {
+            file_backed_base_path,
+        } => Arc::new(FileBackedDAClient::new(file_backed_base_path).await?),
+    })
+}
+
+#[derive(Debug)]
+pub struct DataAvailabilityDispatcher {
+    client: Arc<dyn DataAvailabilityClient>,
+    pool: ConnectionPool<Core>,
+    config: DADispatcherConfig,
+    retry_backoff: Duration,
+}
+
+impl DataAvailabilityDispatcher {
+    const INITIAL_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        config: DADispatcherConfig,
+        client: Arc<dyn DataAvailabilityClient>,
+    ) -> Self {
+        Self {
+            client,
+            pool,
+            config,
+            retry_backoff: Self::INITIAL_RETRY_BACKOFF,
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, DA dispatcher is shutting down");
+                break;
+            }
+
+            self.dispatch().await.context("dispatch()")?;
+            self.poll_for_inclusion()
+                .await
+                .context("poll_for_inclusion()")?;
+
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.config.polling_interval(), stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+
+    /// Dispatches pubdata of L1 batches that weren't dispatched yet to the DA layer.
+    async fn dispatch(&self) -> anyhow::Result<()> {
+        let mut conn = self.pool.connection_tagged("da_dispatcher").await?;
+        let batches = conn
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(self.config.max_rows_to_dispatch as usize)
+            .await?;
+        drop(conn);
+
+        for batch in batches {
+            let l1_batch_number = batch.l1_batch_number;
+            let started_at = Instant::now();
+            METRICS.blob_size.observe(batch.pubdata.len());
+            let response = self
+                .retry(|| {
+                    self.client
+                        .dispatch_blob(l1_batch_number, batch.pubdata.clone())
+                })
+                .await
+                .with_context(|| {
+                    format!("failed dispatching pubdata for L1 batch #{l1_batch_number}")
+                })?;
+
+            self.pool
+                .connection_tagged("da_dispatcher")
+                .await?
+                .data_availability_dal()
+                .insert_l1_batch_da(l1_batch_number, &response.blob_id)
+                .await?;
+
+            METRICS.blob_dispatch_latency.observe(started_at.elapsed());
+            METRICS
+                .last_dispatched_l1_batch
+                .set(l1_batch_number.0.into());
+            tracing::info!(
+                "Dispatched pubdata for L1 batch #{l1_batch_number} to the DA layer; blob ID: {}",
+                response.blob_id
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks inclusion of dispatched blobs in the DA layer and persists inclusion data, which makes
+    /// the corresponding L1 batches eligible to be committed. Blobs are checked in order, stopping
+    /// at the first one that isn't included yet.
+    async fn poll_for_inclusion(&self) -> anyhow::Result<()> {
+        let mut conn = self.pool.connection_tagged("da_dispatcher").await?;
+        let blobs = conn
+            .data_availability_dal()
+            .get_da_blobs_awaiting_inclusion(self.config.max_rows_to_dispatch as usize)
+            .await?;
+        drop(conn);
+
+        for blob in blobs {
+            let l1_batch_number = blob.l1_batch_number;
+            let inclusion_data = self
+                .retry(|| self.client.get_inclusion_data(&blob.blob_id))
+                .await
+                .with_context(|| {
+                    format!("failed getting inclusion data for L1 batch #{l1_batch_number}")
+                })?;
+            let Some(inclusion_data) = inclusion_data else {
+                break;
+            };
+
+            self.pool
+                .connection_tagged("da_dispatcher")
+                .await?
+                .data_availability_dal()
+                .save_l1_batch_inclusion_data(l1_batch_number, &inclusion_data.data)
+                .await?;
+
+            METRICS.last_included_l1_batch.set(l1_batch_number.0.into());
+            tracing::info!("Inclusion of pubdata for L1 batch #{l1_batch_number} is confirmed");
+        }
+        Ok(())
+    }
+
+    async fn retry<T, Fut>(&self, mut call: impl FnMut() -> Fut) -> anyhow::Result<T>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut backoff = self.retry_backoff;
+        let mut attempt = 1;
+        loop {
+            match call().await {
+                Ok(output) => return Ok(output),
+                Err(err) if attempt < self.config.max_retries => {
+                    tracing::warn!(
+                        "Call to the DA layer failed (attempt {attempt}/{}), retrying in {backoff:?}: {err:#}",
+                        self.config.max_retries
+                    );
+                    METRICS.call_retries.inc();
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Tests for the DA dispatcher.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use zksync_dal::Connection;
+use zksync_types::{web3::signing::keccak256, L1BatchNumber, L2ChainId};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::create_l1_batch,
+};
+
+fn mock_config() -> DADispatcherConfig {
+    DADispatcherConfig {
+        client: DAClientConfig::FileBacked {
+            file_backed_base_path: String::new(),
+        },
+        polling_interval_ms: 10,
+        max_rows_to_dispatch: 10,
+        max_retries: 3,
+    }
+}
+
+async fn seal_l1_batch(storage: &mut Connection<'_, Core>, number: u32, pubdata: Option<Vec<u8>>) {
+    let mut header = create_l1_batch(number);
+    header.pubdata_input = pubdata;
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&header)
+        .await
+        .unwrap();
+}
+
+async fn prepare_storage(pool: &ConnectionPool<Core>) {
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    seal_l1_batch(&mut storage, 1, Some(vec![1; 32])).await;
+    seal_l1_batch(&mut storage, 2, Some(vec![2; 64])).await;
+    // Batches without pubdata input must not be dispatched.
+    seal_l1_batch(&mut storage, 3, None).await;
+}
+
+async fn get_inclusion_data(pool: &ConnectionPool<Core>) -> HashMap<L1BatchNumber, Vec<u8>> {
+    pool.connection()
+        .await
+        .unwrap()
+        .data_availability_dal()
+        .get_l1_batches_inclusion_data(L1BatchNumber(0), L1BatchNumber(10))
+        .await
+        .unwrap()
+}
+
+/// DA client with manually controlled inclusion of blobs that fails a configurable number of calls.
+#[derive(Debug, Default)]
+struct MockDAClient {
+    included_blobs: Mutex<HashSet<String>>,
+    failures_left: AtomicUsize,
+    dispatch_calls: AtomicUsize,
+}
+
+impl MockDAClient {
+    fn include(&self, blob_id: &str) {
+        self.included_blobs
+            .lock()
+            .unwrap()
+            .insert(blob_id.to_owned());
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for MockDAClient {
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        _pubdata: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        self.dispatch_calls.fetch_add(1, Ordering::SeqCst);
+        let failures_left = self.failures_left.load(Ordering::SeqCst);
+        if failures_left > 0 {
+            self.failures_left
+                .store(failures_left - 1, Ordering::SeqCst);
+            anyhow::bail!("DA layer is unavailable");
+        }
+        Ok(DispatchResponse {
+            blob_id: format!("blob{}", l1_batch_number.0),
+        })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<InclusionData>> {
+        let is_included = self.included_blobs.lock().unwrap().contains(blob_id);
+        Ok(is_included.then(|| InclusionData {
+            data: blob_id.as_bytes().to_vec(),
+        }))
+    }
+}
+
+#[tokio::test]
+async fn file_backed_client_basics() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let client = FileBackedDAClient::new(temp_dir.path()).await.unwrap();
+
+    let pubdata = vec![42; 100];
+    let response = client
+        .dispatch_blob(L1BatchNumber(1), pubdata.clone())
+        .await
+        .unwrap();
+    let inclusion_data = client
+        .get_inclusion_data(&response.blob_id)
+        .await
+        .unwrap()
+        .expect("blob is not included");
+    assert_eq!(inclusion_data.data, keccak256(&pubdata));
+
+    let missing = client.get_inclusion_data("missing").await.unwrap();
+    assert_eq!(missing, None);
+}
+
+#[tokio::test]
+async fn dispatching_pubdata_with_file_backed_client() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool).await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let client = FileBackedDAClient::new(temp_dir.path()).await.unwrap();
+    let dispatcher = DataAvailabilityDispatcher::new(pool.clone(), mock_config(), Arc::new(client));
+
+    dispatcher.dispatch().await.unwrap();
+    assert!(get_inclusion_data(&pool).await.is_empty());
+    dispatcher.poll_for_inclusion().await.unwrap();
+
+    let inclusion_data = get_inclusion_data(&pool).await;
+    assert_eq!(inclusion_data.len(), 2, "{inclusion_data:?}");
+    assert_eq!(inclusion_data[&L1BatchNumber(1)], keccak256(&[1; 32]));
+    assert_eq!(inclusion_data[&L1BatchNumber(2)], keccak256(&[2; 64]));
+
+    // Repeated iterations should be no-op.
+    dispatcher.dispatch().await.unwrap();
+    dispatcher.poll_for_inclusion().await.unwrap();
+    assert_eq!(get_inclusion_data(&pool).await, inclusion_data);
+}
+
+#[tokio::test]
+async fn inclusion_is_confirmed_in_order() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool).await;
+    let client = Arc::new(MockDAClient::default());
+    let dispatcher = DataAvailabilityDispatcher::new(pool.clone(), mock_config(), client.clone());
+
+    dispatcher.dispatch().await.unwrap();
+    client.include("blob2");
+    dispatcher.poll_for_inclusion().await.unwrap();
+    // L1 batch #2 must not become eligible to commit before L1 batch #1.
+    assert!(get_inclusion_data(&pool).await.is_empty());
+
+    client.include("blob1");
+    dispatcher.poll_for_inclusion().await.unwrap();
+    let inclusion_data = get_inclusion_data(&pool).await;
+    assert_eq!(inclusion_data[&L1BatchNumber(1)], b"blob1");
+    assert_eq!(inclusion_data[&L1BatchNumber(2)], b"blob2");
+}
+
+#[tokio::test]
+async fn dispatch_is_retried() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool).await;
+    let client = Arc::new(MockDAClient {
+        failures_left: AtomicUsize::new(2),
+        ..MockDAClient::default()
+    });
+    let mut dispatcher =
+        DataAvailabilityDispatcher::new(pool.clone(), mock_config(), client.clone());
+    dispatcher.retry_backoff = Duration::ZERO;
+
+    dispatcher.dispatch().await.unwrap();
+    assert_eq!(client.dispatch_calls.load(Ordering::SeqCst), 4);
+
+    client.failures_left.store(3, Ordering::SeqCst);
+    let mut storage = pool.connection().await.unwrap();
+    seal_l1_batch(&mut storage, 4, Some(vec![4; 16])).await;
+    let err = dispatcher.dispatch().await.unwrap_err();
+    assert!(
+        format!("{err:#}").contains("DA layer is unavailable"),
+        "{err:#}"
+    );
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use zksync_config::configs::eth_sender::{ProofLoadingMode, ProofSendingMode, SenderConfig};
 use zksync_contracts::BaseSystemContractsHashes;
@@ -17,10 +17,11 @@ use zksync_types::{
 use super::{
     aggregated_operations::AggregatedOperation,
     publish_criterion::{
-        DataSizeCriterion, GasCriterion, L1BatchPublishCriterion, NumberCriterion,
+        DataSizeCriterion, GasCriterion, L1BatchPublishCriterion, L1FeeCriterion, NumberCriterion,
         TimestampDeadlineCriterion,
     },
 };
+use crate::l1_gas_price::L1FeeStatisticsProvider;
 
 #[derive(Debug)]
 pub struct Aggregator {
@@ -44,7 +45,11 @@ impl Aggregator {
         blob_store: Arc<dyn ObjectStore>,
         operate_4844_mode: bool,
         pubdata_da: PubdataDA,
+        l1_fee_statistics: Option<Arc<dyn L1FeeStatisticsProvider>>,
     ) -> Self {
+        let deadline_criterion = |timestamp: TimestampDeadlineCriterion| {
+            Self::deadline_criterion(&config, timestamp, l1_fee_statistics.clone(), pubdata_da)
+        };
         Self {
             commit_criteria: vec![
                 Box::from(NumberCriterion {
@@ -60,7 +65,7 @@ impl Aggregator {
                     data_limit: config.max_eth_tx_data_size,
                     pubdata_da,
                 }),
-                Box::from(TimestampDeadlineCriterion {
+                deadline_criterion(TimestampDeadlineCriterion {
                     op: AggregatedActionType::Commit,
                     deadline_seconds: config.aggregated_block_commit_deadline,
                     max_allowed_lag: Some(config.timestamp_criteria_max_allowed_lag),
@@ -75,7 +80,7 @@ impl Aggregator {
                     AggregatedActionType::PublishProofOnchain,
                     config.max_aggregated_tx_gas,
                 )),
-                Box::from(TimestampDeadlineCriterion {
+                deadline_criterion(TimestampDeadlineCriterion {
                     op: AggregatedActionType::PublishProofOnchain,
                     deadline_seconds: config.aggregated_block_prove_deadline,
                     // Currently, we can't use this functionality for proof criterion
@@ -93,7 +98,7 @@ impl Aggregator {
                     AggregatedActionType::Execute,
                     config.max_aggregated_tx_gas,
                 )),
-                Box::from(TimestampDeadlineCriterion {
+                deadline_criterion(TimestampDeadlineCriterion {
                     op: AggregatedActionType::Execute,
                     deadline_seconds: config.aggregated_block_execute_deadline,
                     max_allowed_lag: Some(config.timestamp_criteria_max_allowed_lag),
@@ -106,6 +111,30 @@ impl Aggregator {
         }
     }
 
+    /// Wraps `timestamp` into [`L1FeeCriterion`] if postponing operations on high L1 fees is enabled.
+    fn deadline_criterion(
+        config: &SenderConfig,
+        timestamp: TimestampDeadlineCriterion,
+        l1_fee_statistics: Option<Arc<dyn L1FeeStatisticsProvider>>,
+        pubdata_da: PubdataDA,
+    ) -> Box<dyn L1BatchPublishCriterion> {
+        let (Some(percentile), Some(l1_fee_statistics)) =
+            (config.l1_fee_postpone_percentile, l1_fee_statistics)
+        else {
+            return Box::new(timestamp);
+        };
+        // Blob fees are only paid when committing L1 batches in the blobs mode.
+        let check_blob_base_fee =
+            timestamp.op == AggregatedActionType::Commit && pubdata_da == PubdataDA::Blobs;
+        Box::new(L1FeeCriterion::new(
+            timestamp,
+            l1_fee_statistics,
+            percentile,
+            config.l1_fee_max_postpone_seconds(),
+            check_blob_base_fee,
+        ))
+    }
+
     pub async fn get_next_ready_operation(
         &mut self,
         storage: &mut Connection<'_, Core>,
@@ -194,7 +223,7 @@ impl Aggregator {
             .await
             .unwrap()?;
 
-        let ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
+        let mut ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
             blocks_dal
                 .pre_boojum_get_ready_for_commit_l1_batches(
                     limit,
@@ -227,6 +256,12 @@ impl Aggregator {
                 }
             });
 
+        let mut da_inclusion_data = if self.pubdata_da == PubdataDA::Custom {
+            Self::retain_batches_with_da_inclusion(storage, &mut ready_for_commit_l1_batches).await
+        } else {
+            HashMap::new()
+        };
+
         let batches = extract_ready_subrange(
             storage,
             &mut self.commit_criteria,
@@ -235,13 +270,57 @@ impl Aggregator {
         )
         .await;
 
-        batches.map(|batches| CommitBatches {
-            last_committed_l1_batch,
-            l1_batches: batches,
-            pubdata_da: self.pubdata_da,
+        batches.map(|batches| {
+            let da_inclusion_data = if self.pubdata_da == PubdataDA::Custom {
+                batches
+                    .iter()
+                    .map(|batch| {
+                        // `unwrap()` is safe: batches without inclusion data are filtered out above
+                        da_inclusion_data.remove(&batch.header.number).unwrap()
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+            CommitBatches {
+                last_committed_l1_batch,
+                l1_batches: batches,
+                pubdata_da: self.pubdata_da,
+                da_inclusion_data,
+            }
         })
     }
 
+    /// With [`PubdataDA::Custom`], an L1 batch is only eligible to be committed once the DA dispatcher
+    /// has confirmed inclusion of its pubdata in the DA layer. Truncates `l1_batches` at the first batch
+    /// without inclusion data and returns inclusion data for the remaining batches.
+    async fn retain_batches_with_da_inclusion(
+        storage: &mut Connection<'_, Core>,
+        l1_batches: &mut Vec<L1BatchWithMetadata>,
+    ) -> HashMap<L1BatchNumber, Vec<u8>> {
+        let (Some(first_batch), Some(last_batch)) = (l1_batches.first(), l1_batches.last()) else {
+            return HashMap::new();
+        };
+        let inclusion_data = storage
+            .data_availability_dal()
+            .get_l1_batches_inclusion_data(first_batch.header.number, last_batch.header.number)
+            .await
+            .unwrap();
+
+        let eligible_count = l1_batches
+            .iter()
+            .take_while(|batch| inclusion_data.contains_key(&batch.header.number))
+            .count();
+        if eligible_count < l1_batches.len() {
+            tracing::debug!(
+                "L1 batch #{} is not yet included in the DA layer; waiting for it to commit",
+                l1_batches[eligible_count].header.number
+            );
+        }
+        l1_batches.truncate(eligible_count);
+        inclusion_data
+    }
+
     async fn load_dummy_proof_operations(
         storage: &mut Connection<'_, Core>,
         limit: usize,
@@ -470,6 +549,10 @@ impl Aggregator {
     pub fn pubdata_da(&self) -> PubdataDA {
         self.pubdata_da
     }
+
+    pub(super) fn blob_store(&self) -> &Arc<dyn ObjectStore> {
+        &self.blob_store
+    }
 }
 
 async fn extract_ready_subrange(
@@ -0,0 +1,106 @@
+//! Archival of blobs published on L1 by commit transactions.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use zksync_dal::{Connection, Core, CoreDal};
+use zksync_l1_contract_interface::i_executor::commit::kzg::verify_blob;
+use zksync_object_store::ObjectStore;
+use zksync_types::eth_sender::L1BatchBlobs;
+
+use super::metrics::METRICS;
+
+/// Stores blobs published by confirmed commit transactions in the object store. Beacon nodes prune blob sidecars
+/// after ~18 days, so without archival the pubdata of L1 batches committed with blobs would become unavailable.
+///
+/// Before archiving, blobs are verified locally against their KZG commitments and the versioned hashes
+/// recorded in `eth_txs`. Blobs failing verification are not archived; instead, the failure is recorded
+/// in Postgres and reported via metrics, so that it doesn't block archiving blobs for subsequent L1 batches.
+#[derive(Debug)]
+pub(super) struct BlobArchiver {
+    blob_store: Arc<dyn ObjectStore>,
+}
+
+impl BlobArchiver {
+    /// Maximum number of L1 batches archived in a single iteration.
+    const BATCHES_PER_ITERATION: usize = 10;
+
+    pub fn new(blob_store: Arc<dyn ObjectStore>) -> Self {
+        Self { blob_store }
+    }
+
+    /// Archives blobs for L1 batches committed since the previous call. Returns the number of archived batches.
+    pub async fn archive_confirmed_blobs(
+        &self,
+        storage: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<usize> {
+        let unarchived_blobs = storage
+            .eth_sender_dal()
+            .get_unarchived_batch_blobs(Self::BATCHES_PER_ITERATION)
+            .await
+            .context("get_unarchived_batch_blobs()")?;
+
+        let mut archived_count = 0;
+        for (eth_tx_id, batch_blobs) in unarchived_blobs {
+            let l1_batch_number = batch_blobs.l1_batch_number;
+            let blobs_count = batch_blobs.blobs.len();
+            // KZG verification is CPU-heavy, so it's performed on a blocking thread.
+            let (batch_blobs, verification_result) = tokio::task::spawn_blocking(move || {
+                let result = Self::verify(&batch_blobs);
+                (batch_blobs, result)
+            })
+            .await
+            .context("blob verification panicked")?;
+
+            if let Err(err) = verification_result {
+                METRICS.invalid_batch_blobs.inc();
+                tracing::error!(
+                    "Blobs for L1 batch #{l1_batch_number} published by eth_tx {eth_tx_id} are invalid \
+                     and will not be archived: {err:#}"
+                );
+                storage
+                    .eth_sender_dal()
+                    .mark_batch_blobs_as_invalid(
+                        l1_batch_number,
+                        eth_tx_id,
+                        blobs_count,
+                        &format!("{err:#}"),
+                    )
+                    .await
+                    .context("mark_batch_blobs_as_invalid()")?;
+                continue;
+            }
+
+            let key = self
+                .blob_store
+                .put(l1_batch_number, &batch_blobs)
+                .await
+                .with_context(|| format!("failed saving blobs for L1 batch #{l1_batch_number}"))?;
+            storage
+                .eth_sender_dal()
+                .mark_batch_blobs_as_archived(l1_batch_number, eth_tx_id, blobs_count)
+                .await
+                .context("mark_batch_blobs_as_archived()")?;
+
+            METRICS.archived_batch_blobs.inc();
+            archived_count += 1;
+            tracing::info!(
+                "Archived {blobs_count} blob(s) for L1 batch #{l1_batch_number} at `{key}`"
+            );
+        }
+        Ok(archived_count)
+    }
+
+    fn verify(batch_blobs: &L1BatchBlobs) -> anyhow::Result<()> {
+        for (i, blob) in batch_blobs.blobs.iter().enumerate() {
+            verify_blob(
+                &blob.blob,
+                &blob.commitment,
+                &blob.proof,
+                &blob.versioned_hash,
+            )
+            .with_context(|| format!("blob #{i} failed verification"))?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use zksync_types::web3::contract;
+use zksync_types::{web3::contract, Address};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ETHSenderError {
@@ -6,4 +6,6 @@ pub enum ETHSenderError {
     EthereumGateWayError(#[from] zksync_eth_client::Error),
     #[error("Token parsing Error: {0}")]
     ParseError(#[from] contract::Error),
+    #[error("eth_tx {eth_tx_id} is sent from {sender:?}, which is not among configured operator accounts")]
+    UnknownOperatorAccount { eth_tx_id: u32, sender: Address },
 }
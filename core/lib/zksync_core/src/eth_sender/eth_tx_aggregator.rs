@@ -1,28 +1,30 @@
-use std::{convert::TryInto, sync::Arc};
+use std::{collections::HashMap, convert::TryInto};
 
 use tokio::sync::watch;
 use zksync_config::configs::eth_sender::SenderConfig;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
-use zksync_eth_client::{BoundEthInterface, CallFunctionArgs};
+use zksync_eth_client::CallFunctionArgs;
 use zksync_l1_contract_interface::{
     i_executor::commit::kzg::{KzgInfo, ZK_SYNC_BYTES_PER_BLOB},
     multicall3::{Multicall3Call, Multicall3Result},
     Detokenize, Tokenizable, Tokenize,
 };
 use zksync_types::{
-    aggregated_operations::AggregatedActionType,
     commitment::SerializeCommitment,
     eth_sender::{EthTx, EthTxBlobSidecar, EthTxBlobSidecarV1, SidecarBlobV1},
     ethabi::Token,
     l2_to_l1_log::UserL2ToL1Log,
     protocol_version::{L1VerifierConfig, VerifierParams},
     pubdata_da::PubdataDA,
-    web3::{contract::Error as Web3ContractError, types::BlockNumber},
+    web3::contract::Error as Web3ContractError,
     Address, L2ChainId, ProtocolVersionId, H256, U256,
 };
 
-use super::aggregated_operations::AggregatedOperation;
+use super::{
+    aggregated_operations::AggregatedOperation, blob_archiver::BlobArchiver,
+    operator_accounts::OperatorAccounts,
+};
 use crate::{
     eth_sender::{
         metrics::{PubdataKind, METRICS},
@@ -48,20 +50,17 @@ pub struct MulticallData {
 #[derive(Debug)]
 pub struct EthTxAggregator {
     aggregator: Aggregator,
-    eth_client: Arc<dyn BoundEthInterface>,
+    accounts: OperatorAccounts,
     config: SenderConfig,
     timelock_contract_address: Address,
     l1_multicall3_address: Address,
     pub(super) main_zksync_contract_address: Address,
     functions: ZkSyncFunctions,
-    base_nonce: u64,
-    base_nonce_custom_commit_sender: Option<u64>,
+    /// Pending nonces of operator accounts on L1 at the start of the aggregator.
+    base_nonces: HashMap<Address, u64>,
     rollup_chain_id: L2ChainId,
-    /// If set to `Some` node is operating in the 4844 mode with two operator
-    /// addresses at play: the main one and the custom address for sending commit
-    /// transactions. The `Some` then contains the address of this custom operator
-    /// address.
-    custom_commit_sender_addr: Option<Address>,
+    /// Set if pubdata is published in blobs.
+    blob_archiver: Option<BlobArchiver>,
     pool: ConnectionPool<Core>,
 }
 
@@ -76,48 +75,55 @@ impl EthTxAggregator {
         pool: ConnectionPool<Core>,
         config: SenderConfig,
         aggregator: Aggregator,
-        eth_client: Arc<dyn BoundEthInterface>,
+        accounts: OperatorAccounts,
         timelock_contract_address: Address,
         l1_multicall3_address: Address,
         main_zksync_contract_address: Address,
         rollup_chain_id: L2ChainId,
-        custom_commit_sender_addr: Option<Address>,
     ) -> Self {
         let functions = ZkSyncFunctions::default();
-        let base_nonce = eth_client
-            .pending_nonce("eth_sender")
-            .await
-            .unwrap()
-            .as_u64();
-
-        let base_nonce_custom_commit_sender = match custom_commit_sender_addr {
-            Some(addr) => Some(
-                eth_client
-                    .nonce_at_for_account(addr, BlockNumber::Pending, "eth_sender")
-                    .await
-                    .unwrap()
-                    .as_u64(),
-            ),
-            None => None,
-        };
+        let mut base_nonces = HashMap::new();
+        for account in accounts.iter() {
+            let nonce = account
+                .gateway()
+                .pending_nonce("eth_sender")
+                .await
+                .unwrap()
+                .as_u64();
+            base_nonces.insert(account.address(), nonce);
+        }
+        let blob_archiver = matches!(aggregator.pubdata_da(), PubdataDA::Blobs)
+            .then(|| BlobArchiver::new(aggregator.blob_store().clone()));
         Self {
             config,
             aggregator,
-            eth_client,
+            accounts,
             timelock_contract_address,
             l1_multicall3_address,
             main_zksync_contract_address,
             functions,
-            base_nonce,
-            base_nonce_custom_commit_sender,
+            base_nonces,
             rollup_chain_id,
-            custom_commit_sender_addr,
+            blob_archiver,
             pool,
         }
     }
 
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let pool = self.pool.clone();
+        let main_address = self.accounts.main().address();
+        let legacy_tx_count = pool
+            .connection_tagged("eth_sender")
+            .await?
+            .eth_sender_dal()
+            .assign_sender_to_legacy_txs(main_address)
+            .await?;
+        if legacy_tx_count > 0 {
+            tracing::info!(
+                "Assigned {legacy_tx_count} unconfirmed eth_txs without a sender to the main operator {main_address:?}"
+            );
+        }
+
         loop {
             let mut storage = pool.connection_tagged("eth_sender").await.unwrap();
 
@@ -131,6 +137,11 @@ impl EthTxAggregator {
                 // and anything more important is already properly reported.
                 tracing::warn!("eth_sender error {err:?}");
             }
+            if let Some(blob_archiver) = &self.blob_archiver {
+                if let Err(err) = blob_archiver.archive_confirmed_blobs(&mut storage).await {
+                    tracing::error!("Failed archiving blobs: {err:#}");
+                }
+            }
 
             tokio::time::sleep(self.config.aggregate_tx_poll_period()).await;
         }
@@ -143,7 +154,12 @@ impl EthTxAggregator {
             self.l1_multicall3_address,
             self.functions.multicall_contract.clone(),
         );
-        let aggregate3_result = self.eth_client.call_contract_function(args).await?;
+        let aggregate3_result = self
+            .accounts
+            .main()
+            .gateway()
+            .call_contract_function(args)
+            .await?;
         self.parse_multicall_data(Token::from_tokens(aggregate3_result)?)
     }
 
@@ -335,7 +351,12 @@ impl EthTxAggregator {
         let get_vk_hash = &self.functions.verification_key_hash;
         let args = CallFunctionArgs::new(&get_vk_hash.name, ())
             .for_contract(verifier_address, self.functions.verifier_contract.clone());
-        let vk_hash = self.eth_client.call_contract_function(args).await?;
+        let vk_hash = self
+            .accounts
+            .main()
+            .gateway()
+            .call_contract_function(args)
+            .await?;
         Ok(H256::from_tokens(vk_hash)?)
     }
 
@@ -525,17 +546,14 @@ impl EthTxAggregator {
     ) -> Result<EthTx, ETHSenderError> {
         let mut transaction = storage.start_transaction().await.unwrap();
         let op_type = aggregated_op.get_action_type();
-        // We may be using a custom sender for commit transactions, so use this
-        // var whatever it actually is: a `None` for single-addr operator or `Some`
-        // for multi-addr operator in 4844 mode.
-        let sender_addr = match op_type {
-            AggregatedActionType::Commit => self.custom_commit_sender_addr,
-            _ => None,
-        };
+        let l1_batch_number_range = aggregated_op.l1_batch_range();
+        let sender_addr = self
+            .accounts
+            .for_operation(op_type, *l1_batch_number_range.start())
+            .address();
         let nonce = self.get_next_nonce(&mut transaction, sender_addr).await?;
         let encoded_aggregated_op =
             self.encode_aggregated_op(aggregated_op, contracts_are_pre_shared_bridge);
-        let l1_batch_number_range = aggregated_op.l1_batch_range();
 
         let predicted_gas_for_batches = transaction
             .blocks_dal()
@@ -552,7 +570,7 @@ impl EthTxAggregator {
                 op_type,
                 self.timelock_contract_address,
                 eth_tx_predicted_gas,
-                sender_addr,
+                Some(sender_addr),
                 encoded_aggregated_op.sidecar,
             )
             .await
@@ -570,7 +588,7 @@ impl EthTxAggregator {
     async fn get_next_nonce(
         &self,
         storage: &mut Connection<'_, Core>,
-        from_addr: Option<Address>,
+        from_addr: Address,
     ) -> Result<u64, ETHSenderError> {
         let db_nonce = storage
             .eth_sender_dal()
@@ -578,15 +596,12 @@ impl EthTxAggregator {
             .await
             .unwrap()
             .unwrap_or(0);
+        let base_nonce = *self
+            .base_nonces
+            .get(&from_addr)
+            .expect("base nonce is initialized for all operator accounts; qed");
         // Between server starts we can execute some txs using operator account or remove some txs from the database
         // At the start we have to consider this fact and get the max nonce.
-        Ok(if from_addr.is_none() {
-            db_nonce.max(self.base_nonce)
-        } else {
-            db_nonce.max(
-                self.base_nonce_custom_commit_sender
-                    .expect("custom base nonce is expected to be initialized; qed"),
-            )
-        })
+        Ok(db_nonce.max(base_nonce))
     }
 }
@@ -1,15 +1,21 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_config::configs::eth_sender::SenderConfig;
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{
+    l1_reorgs_dal::{L1BlockCheckpoint, L1Reorg},
+    Connection, ConnectionPool, Core, CoreDal,
+};
 use zksync_eth_client::{
     encode_blob_tx_with_sidecar, BoundEthInterface, Error, EthInterface, ExecutedTxStatus, Options,
     RawTransactionBytes, SignedCallResult,
 };
 use zksync_types::{
-    aggregated_operations::AggregatedActionType,
     eth_sender::{EthTx, EthTxBlobSidecar},
     web3::{
         error::Error as Web3Error,
@@ -19,9 +25,12 @@ use zksync_types::{
 };
 use zksync_utils::time::seconds_since_epoch;
 
-use super::{metrics::METRICS, ETHSenderError};
+use super::{metrics::METRICS, operator_accounts::OperatorAccounts, ETHSenderError};
 use crate::{l1_gas_price::L1TxParamsProvider, metrics::BlockL1Stage};
 
+/// Number of most recent L1 blocks in which confirmed transactions are re-checked for reorgs.
+const L1_REORG_CHECK_WINDOW: u64 = 1_024;
+
 #[derive(Debug)]
 struct EthFee {
     base_fee_per_gas: u64,
@@ -51,11 +60,8 @@ pub(super) struct L1BlockNumbers {
 /// with higher gas price
 #[derive(Debug)]
 pub struct EthTxManager {
-    /// A gateway through which the operator normally sends all its transactions.
-    ethereum_gateway: Arc<dyn BoundEthInterface>,
-    /// If the operator is in 4844 mode this is sent to `Some` and used to send
-    /// commit transactions.
-    ethereum_gateway_blobs: Option<Arc<dyn BoundEthInterface>>,
+    /// Accounts signing transactions. The main account is also used for querying L1.
+    accounts: OperatorAccounts,
     config: SenderConfig,
     gas_adjuster: Arc<dyn L1TxParamsProvider>,
     pool: ConnectionPool<Core>,
@@ -66,23 +72,38 @@ impl EthTxManager {
         pool: ConnectionPool<Core>,
         config: SenderConfig,
         gas_adjuster: Arc<dyn L1TxParamsProvider>,
-        ethereum_gateway: Arc<dyn BoundEthInterface>,
-        ethereum_gateway_blobs: Option<Arc<dyn BoundEthInterface>>,
+        accounts: OperatorAccounts,
     ) -> Self {
         Self {
-            ethereum_gateway,
-            ethereum_gateway_blobs,
+            accounts,
             config,
             gas_adjuster,
             pool,
         }
     }
 
+    fn main_gateway(&self) -> &dyn BoundEthInterface {
+        self.accounts.main().gateway().as_ref()
+    }
+
+    /// Returns the gateway of the account that the transaction is sent from. Errors if the account
+    /// is not configured (e.g., if its key was removed from the config before all its transactions were confirmed).
+    fn sender_gateway(&self, tx: &EthTx) -> Result<Arc<dyn BoundEthInterface>, ETHSenderError> {
+        let account = self.accounts.by_sender(tx.from_addr).ok_or_else(|| {
+            ETHSenderError::UnknownOperatorAccount {
+                eth_tx_id: tx.id,
+                sender: tx.from_addr.unwrap_or_default(),
+            }
+        })?;
+        Ok(account.gateway().clone())
+    }
+
     async fn get_tx_status(
         &self,
+        gateway: &dyn BoundEthInterface,
         tx_hash: H256,
     ) -> Result<Option<ExecutedTxStatus>, ETHSenderError> {
-        self.ethereum_gateway
+        gateway
             .get_tx_status(tx_hash, "eth_tx_manager")
             .await
             .map_err(Into::into)
@@ -92,7 +113,8 @@ impl EthTxManager {
         &self,
         storage: &mut Connection<'_, Core>,
         op: &EthTx,
-    ) -> Option<ExecutedTxStatus> {
+    ) -> Result<Option<ExecutedTxStatus>, ETHSenderError> {
+        let gateway = self.sender_gateway(op)?;
         // Checking history items, starting from most recently sent.
         for history_item in storage
             .eth_sender_dal()
@@ -103,8 +125,11 @@ impl EthTxManager {
             // `status` is a Result here and we don't unwrap it with `?`
             // because if we do and get an `Err`, we won't finish the for loop,
             // which means we might miss the transaction that actually succeeded.
-            match self.get_tx_status(history_item.tx_hash).await {
-                Ok(Some(s)) => return Some(s),
+            match self
+                .get_tx_status(gateway.as_ref(), history_item.tx_hash)
+                .await
+            {
+                Ok(Some(s)) => return Ok(Some(s)),
                 Ok(_) => continue,
                 Err(err) => tracing::warn!(
                     "Can't check transaction {:?}: {:?}",
@@ -113,7 +138,7 @@ impl EthTxManager {
                 ),
             }
         }
-        None
+        Ok(None)
     }
 
     async fn calculate_fee(
@@ -234,6 +259,7 @@ impl EthTxManager {
         time_in_mempool: u32,
         current_block: L1BlockNumber,
     ) -> Result<H256, ETHSenderError> {
+        let gateway = self.sender_gateway(tx)?;
         let EthFee {
             base_fee_per_gas,
             priority_fee_per_gas,
@@ -256,7 +282,13 @@ impl EthTxManager {
         };
 
         let mut signed_tx = self
-            .sign_tx(tx, base_fee_per_gas, priority_fee_per_gas, blob_gas_price)
+            .sign_tx(
+                gateway.as_ref(),
+                tx,
+                base_fee_per_gas,
+                priority_fee_per_gas,
+                blob_gas_price,
+            )
             .await;
 
         if let Some(blob_sidecar) = &tx.blob_sidecar {
@@ -280,7 +312,13 @@ impl EthTxManager {
             .unwrap()
         {
             if let Err(error) = self
-                .send_raw_transaction(storage, tx_history_id, signed_tx.raw_tx, current_block)
+                .send_raw_transaction(
+                    storage,
+                    gateway.as_ref(),
+                    tx_history_id,
+                    signed_tx.raw_tx,
+                    current_block,
+                )
                 .await
             {
                 tracing::warn!(
@@ -298,11 +336,12 @@ impl EthTxManager {
     async fn send_raw_transaction(
         &self,
         storage: &mut Connection<'_, Core>,
+        gateway: &dyn BoundEthInterface,
         tx_history_id: u32,
         raw_tx: RawTransactionBytes,
         current_block: L1BlockNumber,
     ) -> Result<H256, ETHSenderError> {
-        match self.ethereum_gateway.send_raw_tx(raw_tx).await {
+        match gateway.send_raw_tx(raw_tx).await {
             Ok(tx_hash) => {
                 storage
                     .eth_sender_dal()
@@ -323,18 +362,16 @@ impl EthTxManager {
     }
 
     async fn get_operator_nonce(
-        &self,
+        gateway: &dyn BoundEthInterface,
         block_numbers: L1BlockNumbers,
     ) -> Result<OperatorNonce, ETHSenderError> {
-        let finalized = self
-            .ethereum_gateway
+        let finalized = gateway
             .nonce_at(block_numbers.finalized.0.into(), "eth_tx_manager")
             .await?
             .as_u32()
             .into();
 
-        let latest = self
-            .ethereum_gateway
+        let latest = gateway
             .nonce_at(block_numbers.latest.0.into(), "eth_tx_manager")
             .await?
             .as_u32()
@@ -342,33 +379,10 @@ impl EthTxManager {
         Ok(OperatorNonce { finalized, latest })
     }
 
-    async fn get_blobs_operator_nonce(
-        &self,
-        block_numbers: L1BlockNumbers,
-    ) -> Result<Option<OperatorNonce>, ETHSenderError> {
-        match &self.ethereum_gateway_blobs {
-            None => Ok(None),
-            Some(gateway) => {
-                let finalized = gateway
-                    .nonce_at(block_numbers.finalized.0.into(), "eth_tx_manager")
-                    .await?
-                    .as_u32()
-                    .into();
-
-                let latest = gateway
-                    .nonce_at(block_numbers.latest.0.into(), "eth_tx_manager")
-                    .await?
-                    .as_u32()
-                    .into();
-                Ok(Some(OperatorNonce { finalized, latest }))
-            }
-        }
-    }
-
     async fn get_l1_block_numbers(&self) -> Result<L1BlockNumbers, ETHSenderError> {
         let (finalized, safe) = if let Some(confirmations) = self.config.wait_confirmations {
             let latest_block_number = self
-                .ethereum_gateway
+                .main_gateway()
                 .block_number("eth_tx_manager")
                 .await?
                 .as_u64();
@@ -377,7 +391,7 @@ impl EthTxManager {
             (finalized, finalized)
         } else {
             let finalized = self
-                .ethereum_gateway
+                .main_gateway()
                 .block(BlockId::Number(BlockNumber::Finalized), "eth_tx_manager")
                 .await?
                 .expect("Finalized block must be present on L1")
@@ -387,7 +401,7 @@ impl EthTxManager {
                 .into();
 
             let safe = self
-                .ethereum_gateway
+                .main_gateway()
                 .block(BlockId::Number(BlockNumber::Safe), "eth_tx_manager")
                 .await?
                 .expect("Safe block must be present on L1")
@@ -399,7 +413,7 @@ impl EthTxManager {
         };
 
         let latest = self
-            .ethereum_gateway
+            .main_gateway()
             .block_number("eth_tx_manager")
             .await?
             .as_u32()
@@ -420,36 +434,28 @@ impl EthTxManager {
         l1_block_numbers: L1BlockNumbers,
     ) -> Result<Option<(EthTx, u32)>, ETHSenderError> {
         METRICS.track_block_numbers(&l1_block_numbers);
-        let operator_nonce = self.get_operator_nonce(l1_block_numbers).await?;
-        let blobs_operator_nonce = self.get_blobs_operator_nonce(l1_block_numbers).await?;
-        let blobs_operator_address = self
-            .ethereum_gateway_blobs
-            .as_ref()
-            .map(|s| s.sender_account());
-
-        if let Some(res) = self
-            .monitor_inflight_transactions_inner(storage, l1_block_numbers, operator_nonce, None)
-            .await?
-        {
-            return Ok(Some(res));
-        };
-
-        if let Some(blobs_operator_nonce) = blobs_operator_nonce {
-            // need to check if both nonce and address are `Some`
-            if blobs_operator_address.is_none() {
-                panic!("blobs_operator_address has to be set its nonce is known; qed");
+        let accounts = self.accounts.clone();
+        let mut monitored_addresses = HashSet::new();
+        for account in accounts.iter() {
+            let operator_address = account.address();
+            if !monitored_addresses.insert(operator_address) {
+                continue; // The same key may be configured for several accounts
             }
-            Ok(self
+            let operator_nonce =
+                Self::get_operator_nonce(account.gateway().as_ref(), l1_block_numbers).await?;
+            if let Some(res) = self
                 .monitor_inflight_transactions_inner(
                     storage,
                     l1_block_numbers,
-                    blobs_operator_nonce,
-                    blobs_operator_address,
+                    operator_nonce,
+                    operator_address,
                 )
-                .await?)
-        } else {
-            Ok(None)
+                .await?
+            {
+                return Ok(Some(res));
+            }
         }
+        Ok(None)
     }
 
     async fn monitor_inflight_transactions_inner(
@@ -457,13 +463,14 @@ impl EthTxManager {
         storage: &mut Connection<'_, Core>,
         l1_block_numbers: L1BlockNumbers,
         operator_nonce: OperatorNonce,
-        operator_address: Option<Address>,
+        operator_address: Address,
     ) -> Result<Option<(EthTx, u32)>, ETHSenderError> {
+        let main_address = self.accounts.main().address();
         let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
         METRICS.number_of_inflight_txs.set(inflight_txs.len());
 
         tracing::trace!(
-            "Going through not confirmed txs. \
+            "Going through not confirmed txs of operator {operator_address:?}. \
              Block numbers: latest {}, finalized {}, \
              operator's nonce: latest {}, finalized {}",
             l1_block_numbers.latest,
@@ -475,7 +482,9 @@ impl EthTxManager {
         // Not confirmed transactions, ordered by nonce
         for tx in inflight_txs {
             tracing::trace!("Checking tx id: {}", tx.id,);
-            if tx.from_addr != operator_address {
+            // Transactions without a sender address were saved before per-account nonce tracking
+            // and are always sent by the main operator.
+            if tx.from_addr.unwrap_or(main_address) != operator_address {
                 continue;
             }
 
@@ -508,7 +517,7 @@ impl EthTxManager {
                 tx.nonce,
             );
 
-            match self.check_all_sending_attempts(storage, &tx).await {
+            match self.check_all_sending_attempts(storage, &tx).await? {
                 Some(tx_status) => {
                     self.apply_tx_status(storage, &tx, tx_status, l1_block_numbers.finalized)
                         .await;
@@ -528,27 +537,88 @@ impl EthTxManager {
         Ok(None)
     }
 
+    /// Checks whether L1 blocks containing recently confirmed transactions are still canonical.
+    /// Confirmations of transactions from reorged blocks are reverted, so that the transactions become in-flight
+    /// again and are re-checked or resent.
+    pub(super) async fn revert_reorged_confirmations(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        l1_block_numbers: L1BlockNumbers,
+    ) -> Result<(), ETHSenderError> {
+        let from_block = u64::from(l1_block_numbers.latest.0).saturating_sub(L1_REORG_CHECK_WINDOW);
+        let confirmations = storage
+            .eth_sender_dal()
+            .get_confirmation_blocks_since(from_block)
+            .await
+            .unwrap();
+
+        let mut canonical_hashes = HashMap::new();
+        let mut reverted_to_l1_block = None;
+        for (eth_tx_id, block) in confirmations {
+            let canonical_hash = match canonical_hashes.get(&block.number) {
+                Some(&hash) => hash,
+                None => {
+                    let hash = self
+                        .main_gateway()
+                        .block(
+                            BlockId::Number(BlockNumber::Number(block.number.into())),
+                            "eth_tx_manager",
+                        )
+                        .await?
+                        .and_then(|block| block.hash);
+                    canonical_hashes.insert(block.number, hash);
+                    hash
+                }
+            };
+            if canonical_hash == Some(block.hash) {
+                continue;
+            }
+
+            tracing::warn!(
+                "L1 block #{} with eth_tx {eth_tx_id} was reorged: expected hash {:?}, got {canonical_hash:?}; \
+                 reverting tx confirmation",
+                block.number,
+                block.hash
+            );
+            storage
+                .eth_sender_dal()
+                .revert_tx_confirmation(eth_tx_id)
+                .await
+                .unwrap();
+            if let Some(tx) = storage
+                .eth_sender_dal()
+                .get_eth_tx(eth_tx_id)
+                .await
+                .unwrap()
+            {
+                METRICS.l1_reorged_txs[&tx.tx_type.into()].inc();
+            }
+            // Confirmations are ordered by the block number, so the first reorged block is the earliest one.
+            reverted_to_l1_block.get_or_insert(block.number.saturating_sub(1));
+        }
+
+        if let Some(reverted_to_l1_block) = reverted_to_l1_block {
+            storage
+                .l1_reorgs_dal()
+                .insert_l1_reorg(&L1Reorg {
+                    component: "eth_tx_manager".to_owned(),
+                    reverted_to_l1_block,
+                    is_fatal: false,
+                })
+                .await
+                .unwrap();
+        }
+        Ok(())
+    }
+
     async fn sign_tx(
         &self,
+        signing_gateway: &dyn BoundEthInterface,
         tx: &EthTx,
         base_fee_per_gas: u64,
         priority_fee_per_gas: u64,
         blob_gas_price: Option<U256>,
     ) -> SignedCallResult {
-        // Chose the signing gateway. Use a custom one in case
-        // the operator is in 4844 mode and the operation at hand is Commit.
-        // then the optional gateway is used to send this transaction from a
-        // custom sender account.
-        let signing_gateway = if let Some(blobs_gateway) = self.ethereum_gateway_blobs.as_ref() {
-            if tx.tx_type == AggregatedActionType::Commit {
-                blobs_gateway
-            } else {
-                &self.ethereum_gateway
-            }
-        } else {
-            &self.ethereum_gateway
-        };
-
         signing_gateway
             .sign_prepared_tx_for_addr(
                 tx.raw_tx.clone(),
@@ -588,7 +658,7 @@ impl EthTxManager {
             // Check already sent txs not marked as sent and mark them as sent.
             // The common reason for this behavior is that we sent tx and stop the server
             // before updating the database
-            let tx_status = self.get_tx_status(tx.tx_hash).await;
+            let tx_status = self.get_tx_status(self.main_gateway(), tx.tx_hash).await;
 
             if let Ok(Some(tx_status)) = tx_status {
                 tracing::info!("The tx {:?} has been already sent", tx.tx_hash);
@@ -610,6 +680,7 @@ impl EthTxManager {
             } else if let Err(error) = self
                 .send_raw_transaction(
                     storage,
+                    self.main_gateway(),
                     tx.id,
                     RawTransactionBytes::new_unchecked(tx.signed_raw_tx.clone()),
                     l1_block_numbers.latest,
@@ -656,7 +727,7 @@ impl EthTxManager {
             .await
             .unwrap();
         let failure_reason = self
-            .ethereum_gateway
+            .main_gateway()
             .failure_reason(tx_status.receipt.transaction_hash)
             .await
             .expect(
@@ -689,6 +760,19 @@ impl EthTxManager {
             .confirm_tx(tx_status.tx_hash, gas_used)
             .await
             .unwrap();
+        if let (Some(number), Some(hash)) =
+            (tx_status.receipt.block_number, tx_status.receipt.block_hash)
+        {
+            let block = L1BlockCheckpoint {
+                number: number.as_u64(),
+                hash,
+            };
+            storage
+                .eth_sender_dal()
+                .save_confirmation_block(tx.id, block)
+                .await
+                .unwrap();
+        }
 
         METRICS
             .track_eth_tx_metrics(storage, BlockL1Stage::Mined, tx)
@@ -759,21 +843,16 @@ impl EthTxManager {
         Ok(())
     }
 
-    async fn send_new_eth_txs(
+    pub(super) async fn send_new_eth_txs(
         &mut self,
         storage: &mut Connection<'_, Core>,
         current_block: L1BlockNumber,
     ) {
-        let number_inflight_txs = storage
-            .eth_sender_dal()
-            .get_inflight_txs()
-            .await
-            .unwrap()
-            .len();
+        let mut inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
         let number_of_available_slots_for_eth_txs = self
             .config
             .max_txs_in_flight
-            .saturating_sub(number_inflight_txs as u64);
+            .saturating_sub(inflight_txs.len() as u64);
 
         if number_of_available_slots_for_eth_txs > 0 {
             // Get the new eth tx and create history item for them
@@ -784,11 +863,36 @@ impl EthTxManager {
                 .unwrap();
 
             for tx in new_eth_tx {
-                let _ = self.send_eth_tx(storage, &tx, 0, current_block).await;
+                if let Some(draining_tx) = self.find_draining_tx(&inflight_txs, &tx) {
+                    // Operations of the same type sent from different accounts (e.g., during a key rotation)
+                    // can be mined out of order, so the new account must wait for the old one to drain.
+                    tracing::info!(
+                        "Postponing eth_tx {} ({}) from {:?} until in-flight eth_tx {} from {:?} is confirmed",
+                        tx.id,
+                        tx.tx_type,
+                        tx.from_addr,
+                        draining_tx.id,
+                        draining_tx.from_addr
+                    );
+                    break;
+                }
+                if let Err(err) = self.send_eth_tx(storage, &tx, 0, current_block).await {
+                    tracing::warn!("Error sending eth_tx {}: {err}", tx.id);
+                }
+                inflight_txs.push(tx);
             }
         }
     }
 
+    /// Finds an in-flight transaction of the same type as `tx` sent from another operator account.
+    fn find_draining_tx<'a>(&self, inflight_txs: &'a [EthTx], tx: &EthTx) -> Option<&'a EthTx> {
+        let main_address = self.accounts.main().address();
+        let sender = |tx: &EthTx| tx.from_addr.unwrap_or(main_address);
+        inflight_txs.iter().find(|inflight_tx| {
+            inflight_tx.tx_type == tx.tx_type && sender(inflight_tx) != sender(tx)
+        })
+    }
+
     #[tracing::instrument(skip(self, storage))]
     async fn loop_iteration(
         &mut self,
@@ -805,6 +909,9 @@ impl EthTxManager {
             return Ok(previous_block);
         }
 
+        self.revert_reorged_confirmations(storage, l1_block_numbers)
+            .await?;
+
         if let Some((tx, sent_at_block)) = self
             .monitor_inflight_transactions(storage, l1_block_numbers)
             .await?
@@ -62,6 +62,32 @@ impl From<(AggregatedActionType, &'static str)> for AggregationReasonLabels {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(super) enum L1FeeDecision {
+    /// L1 fees are acceptable; the operation is not postponed.
+    Allowed,
+    /// L1 fees are high; the operation is postponed.
+    Postponed,
+    /// L1 fees are high, but the operation cannot be postponed any longer.
+    DeadlineReached,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct L1FeeDecisionLabels {
+    op: ActionTypeLabel,
+    decision: L1FeeDecision,
+}
+
+impl From<(AggregatedActionType, L1FeeDecision)> for L1FeeDecisionLabels {
+    fn from((op, decision): (AggregatedActionType, L1FeeDecision)) -> Self {
+        Self {
+            op: op.into(),
+            decision,
+        }
+    }
+}
+
 /// Roughly exponential buckets for fees (100M – 500B).
 const FEE_BUCKETS: Buckets = Buckets::values(&[
     1e7, 2e7, 5e7, 1e8, 2e8, 5e8, 1e9, 2e9, 5e9, 1e10, 2e10, 5e10, 1e11, 2e11, 5e11,
@@ -100,6 +126,18 @@ pub(super) struct EthSenderMetrics {
     pub l1_blocks_waited_in_mempool: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of L1 batches aggregated for publishing with a specific reason.
     pub block_aggregation_reason: Family<AggregationReasonLabels, Counter>,
+    /// Number of confirmed transactions that were reverted to in-flight because of L1 reorgs.
+    pub l1_reorged_txs: Family<ActionTypeLabel, Counter>,
+    /// Number of L1 batches with blobs archived in the object store.
+    pub archived_batch_blobs: Counter,
+    /// Number of L1 batches with blobs that have failed local KZG verification.
+    pub invalid_batch_blobs: Counter,
+    /// Decisions made by the L1 fee publish criterion.
+    pub l1_fee_criterion_decisions: Family<L1FeeDecisionLabels, Counter>,
+    /// L1 fee threshold above which operations are postponed, in wei.
+    pub l1_fee_postpone_threshold: Family<ActionTypeLabel, Gauge<u64>>,
+    /// Estimated amount of L1 fees saved by postponing operations, in gwei.
+    pub l1_fee_estimated_savings_gwei: Family<ActionTypeLabel, Counter>,
 }
 
 impl EthSenderMetrics {
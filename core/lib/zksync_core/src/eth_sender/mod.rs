@@ -1,9 +1,11 @@
 mod aggregated_operations;
 mod aggregator;
+mod blob_archiver;
 mod error;
 mod eth_tx_aggregator;
 mod eth_tx_manager;
 mod metrics;
+mod operator_accounts;
 mod publish_criterion;
 mod zksync_functions;
 
@@ -11,6 +13,9 @@ mod zksync_functions;
 mod tests;
 
 pub use self::{
-    aggregator::Aggregator, error::ETHSenderError, eth_tx_aggregator::EthTxAggregator,
+    aggregator::Aggregator,
+    error::ETHSenderError,
+    eth_tx_aggregator::EthTxAggregator,
     eth_tx_manager::EthTxManager,
+    operator_accounts::{OperatorAccount, OperatorAccounts},
 };
@@ -0,0 +1,185 @@
+//! Operator accounts used to sign and send L1 transactions.
+
+use std::sync::Arc;
+
+use zksync_config::{
+    configs::eth_sender::OperatorRole, ContractsConfig, ETHClientConfig, ETHSenderConfig,
+};
+use zksync_eth_client::{clients::PKSigningClient, BoundEthInterface};
+use zksync_types::{aggregated_operations::AggregatedActionType, Address, L1BatchNumber};
+
+const ALL_ROLES: [AggregatedActionType; 3] = [
+    AggregatedActionType::Commit,
+    AggregatedActionType::PublishProofOnchain,
+    AggregatedActionType::Execute,
+];
+
+fn action_type(role: OperatorRole) -> AggregatedActionType {
+    match role {
+        OperatorRole::Commit => AggregatedActionType::Commit,
+        OperatorRole::Prove => AggregatedActionType::PublishProofOnchain,
+        OperatorRole::Execute => AggregatedActionType::Execute,
+    }
+}
+
+/// L1 account used by the operator to send operations of certain types (aka roles).
+#[derive(Debug, Clone)]
+pub struct OperatorAccount {
+    gateway: Arc<dyn BoundEthInterface>,
+    roles: Vec<AggregatedActionType>,
+    /// First L1 batch for which the account sends operations.
+    active_from_l1_batch: L1BatchNumber,
+}
+
+impl OperatorAccount {
+    pub fn address(&self) -> Address {
+        self.gateway.sender_account()
+    }
+
+    pub fn gateway(&self) -> &Arc<dyn BoundEthInterface> {
+        &self.gateway
+    }
+}
+
+/// Set of operator accounts used by `eth_sender`.
+///
+/// Each operation is sent by the account having the operation type among its roles and activated
+/// most recently for the first L1 batch in the operation. This allows rotating operator keys without
+/// downtime: the new account is registered with [`Self::with_rotation()`] and takes over its roles
+/// starting from the specified L1 batch.
+#[derive(Debug, Clone)]
+pub struct OperatorAccounts {
+    /// The first account is the main one. It's used for L1 queries and owns legacy transactions
+    /// without a sender address.
+    accounts: Vec<OperatorAccount>,
+}
+
+impl OperatorAccounts {
+    /// Creates a set with the main operator account sending all operations.
+    pub fn new(main_gateway: Arc<dyn BoundEthInterface>) -> Self {
+        Self {
+            accounts: vec![OperatorAccount {
+                gateway: main_gateway,
+                roles: ALL_ROLES.to_vec(),
+                active_from_l1_batch: L1BatchNumber(0),
+            }],
+        }
+    }
+
+    /// Adds an account sending commit operations (e.g., blob transactions in the 4844 mode).
+    pub fn with_blobs_operator(mut self, gateway: Arc<dyn BoundEthInterface>) -> Self {
+        self.accounts.push(OperatorAccount {
+            gateway,
+            roles: vec![AggregatedActionType::Commit],
+            active_from_l1_batch: L1BatchNumber(0),
+        });
+        self
+    }
+
+    /// Adds an account taking over `roles` (all roles if empty) for operations starting from `l1_batch`.
+    pub fn with_rotation(
+        mut self,
+        gateway: Arc<dyn BoundEthInterface>,
+        roles: &[OperatorRole],
+        l1_batch: L1BatchNumber,
+    ) -> Self {
+        let roles = if roles.is_empty() {
+            ALL_ROLES.to_vec()
+        } else {
+            roles.iter().copied().map(action_type).collect()
+        };
+        self.accounts.push(OperatorAccount {
+            gateway,
+            roles,
+            active_from_l1_batch: l1_batch,
+        });
+        self
+    }
+
+    /// Creates operator accounts based on the private keys in the environment.
+    pub fn from_config(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client_config: &ETHClientConfig,
+    ) -> anyhow::Result<Self> {
+        let main_client =
+            PKSigningClient::from_config(eth_sender, contracts_config, eth_client_config);
+        Self::new(Arc::new(main_client)).with_configured_accounts(
+            eth_sender,
+            contracts_config,
+            eth_client_config,
+        )
+    }
+
+    /// Adds the blobs and rotation operator accounts if their private keys are present in the environment.
+    pub fn with_configured_accounts(
+        mut self,
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client_config: &ETHClientConfig,
+    ) -> anyhow::Result<Self> {
+        if let Some(client) =
+            PKSigningClient::from_config_blobs(eth_sender, contracts_config, eth_client_config)
+        {
+            self = self.with_blobs_operator(Arc::new(client));
+        }
+
+        let rotation_client =
+            PKSigningClient::from_config_rotation(eth_sender, contracts_config, eth_client_config);
+        match (
+            rotation_client,
+            eth_sender.sender.operator_rotation_l1_batch,
+        ) {
+            (Some(client), Some(l1_batch)) => {
+                self = self.with_rotation(
+                    Arc::new(client),
+                    &eth_sender.sender.operator_rotation_roles,
+                    L1BatchNumber(l1_batch),
+                );
+            }
+            (None, None) => { /* no rotation is configured */ }
+            (Some(_), None) => {
+                anyhow::bail!("operator rotation key is set, but the rotation L1 batch is not")
+            }
+            (None, Some(_)) => {
+                anyhow::bail!("operator rotation L1 batch is set, but the rotation key is not")
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn main(&self) -> &OperatorAccount {
+        &self.accounts[0]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &OperatorAccount> + '_ {
+        self.accounts.iter()
+    }
+
+    /// Returns the account that should send an operation of the specified type, which starts
+    /// from `first_l1_batch`.
+    pub(super) fn for_operation(
+        &self,
+        op_type: AggregatedActionType,
+        first_l1_batch: L1BatchNumber,
+    ) -> &OperatorAccount {
+        // `max_by_key()` returns the last of equal elements, so accounts added later take precedence.
+        self.accounts
+            .iter()
+            .filter(|account| {
+                account.roles.contains(&op_type) && account.active_from_l1_batch <= first_l1_batch
+            })
+            .max_by_key(|account| account.active_from_l1_batch)
+            .unwrap_or_else(|| self.main())
+    }
+
+    /// Returns the account that a transaction with the specified `from_addr` was sent from.
+    pub(super) fn by_sender(&self, from_addr: Option<Address>) -> Option<&OperatorAccount> {
+        let Some(address) = from_addr else {
+            return Some(self.main());
+        };
+        self.accounts
+            .iter()
+            .find(|account| account.address() == address)
+    }
+}
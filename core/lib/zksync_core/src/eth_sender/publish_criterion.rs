@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{collections::HashMap, fmt, sync::Arc};
 
 use async_trait::async_trait;
 use chrono::Utc;
@@ -9,8 +9,8 @@ use zksync_types::{
     pubdata_da::PubdataDA, L1BatchNumber,
 };
 
-use super::metrics::METRICS;
-use crate::gas_tracker::agg_l1_batch_base_cost;
+use super::metrics::{L1FeeDecision, METRICS};
+use crate::{gas_tracker::agg_l1_batch_base_cost, l1_gas_price::L1FeeStatisticsProvider};
 
 #[async_trait]
 pub trait L1BatchPublishCriterion: fmt::Debug + Send + Sync {
@@ -120,6 +120,159 @@ impl L1BatchPublishCriterion for TimestampDeadlineCriterion {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+struct PostponedRange {
+    first_l1_batch: L1BatchNumber,
+    base_fee: u64,
+}
+
+/// Postpones publishing L1 batches while L1 fees are high compared to the recent L1 blocks.
+///
+/// While fees are acceptable, the criterion behaves like the wrapped [`TimestampDeadlineCriterion`].
+/// While fees are high, the timestamp deadline is extended by `max_postpone_seconds`, so that L1 batches
+/// are only published once other criteria (e.g., [`NumberCriterion`]) trigger, i.e., more L1 batches
+/// are aggregated into a single L1 transaction.
+#[derive(Debug)]
+pub struct L1FeeCriterion {
+    timestamp: TimestampDeadlineCriterion,
+    fee_statistics: Arc<dyn L1FeeStatisticsProvider>,
+    /// Percentile of fees in the recent L1 blocks above which L1 batches are postponed.
+    percentile: f64,
+    max_postpone_seconds: u64,
+    /// Whether to take the blob base fee into account (e.g., for commit operations in the blobs mode).
+    check_blob_base_fee: bool,
+    postponed: Option<PostponedRange>,
+}
+
+impl L1FeeCriterion {
+    pub fn new(
+        timestamp: TimestampDeadlineCriterion,
+        fee_statistics: Arc<dyn L1FeeStatisticsProvider>,
+        percentile: f64,
+        max_postpone_seconds: u64,
+        check_blob_base_fee: bool,
+    ) -> Self {
+        Self {
+            timestamp,
+            fee_statistics,
+            percentile,
+            max_postpone_seconds,
+            check_blob_base_fee,
+            postponed: None,
+        }
+    }
+
+    fn fees_are_high(&self) -> bool {
+        let base_fee_threshold = self.fee_statistics.base_fee_percentile(self.percentile);
+        METRICS.l1_fee_postpone_threshold[&self.timestamp.op.into()].set(base_fee_threshold);
+        if self.fee_statistics.current_base_fee() > base_fee_threshold {
+            return true;
+        }
+        self.check_blob_base_fee
+            && self.fee_statistics.current_blob_base_fee()
+                > self
+                    .fee_statistics
+                    .blob_base_fee_percentile(self.percentile)
+    }
+
+    /// Estimates savings from postponing the operation if the postponed L1 batches are still unpublished.
+    async fn record_savings(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        consecutive_l1_batches: &[L1BatchWithMetadata],
+    ) {
+        let Some(postponed) = self.postponed.take() else {
+            return;
+        };
+        let (Some(first), Some(last)) = (
+            consecutive_l1_batches.first(),
+            consecutive_l1_batches.last(),
+        ) else {
+            return;
+        };
+        if first.header.number != postponed.first_l1_batch {
+            // Postponed L1 batches were published by other criteria in the meantime.
+            return;
+        }
+
+        let saved_per_gas = postponed
+            .base_fee
+            .saturating_sub(self.fee_statistics.current_base_fee());
+        if saved_per_gas == 0 {
+            return;
+        }
+        let op = self.timestamp.op;
+        let predicted_gas = storage
+            .blocks_dal()
+            .get_l1_batches_predicted_gas(first.header.number..=last.header.number, op)
+            .await
+            .unwrap();
+        let gas = u64::from(predicted_gas) + u64::from(agg_l1_batch_base_cost(op));
+        let savings_gwei = gas.saturating_mul(saved_per_gas) / 1_000_000_000;
+        tracing::info!(
+            "Postponing op {op} for L1 batches starting from #{} saved an estimated {savings_gwei} gwei",
+            first.header.number
+        );
+        METRICS.l1_fee_estimated_savings_gwei[&op.into()].inc_by(savings_gwei);
+    }
+}
+
+#[async_trait]
+impl L1BatchPublishCriterion for L1FeeCriterion {
+    fn name(&self) -> &'static str {
+        "l1_fee"
+    }
+
+    async fn last_l1_batch_to_publish(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        consecutive_l1_batches: &[L1BatchWithMetadata],
+        last_sealed_l1_batch: L1BatchNumber,
+    ) -> Option<L1BatchNumber> {
+        let op = self.timestamp.op;
+        let first_l1_batch = consecutive_l1_batches.first()?;
+        if !self.fees_are_high() {
+            METRICS.l1_fee_criterion_decisions[&(op, L1FeeDecision::Allowed).into()].inc();
+            self.record_savings(storage, consecutive_l1_batches).await;
+            return self
+                .timestamp
+                .last_l1_batch_to_publish(storage, consecutive_l1_batches, last_sealed_l1_batch)
+                .await;
+        }
+
+        let oldest_l1_batch_age_seconds =
+            (Utc::now().timestamp() as u64).saturating_sub(first_l1_batch.header.timestamp);
+        let deadline_seconds = self.timestamp.deadline_seconds + self.max_postpone_seconds;
+        if oldest_l1_batch_age_seconds >= deadline_seconds {
+            tracing::info!(
+                "L1 fees are high, but op {op} for L1 batch #{} cannot be postponed any longer (age: {oldest_l1_batch_age_seconds}s)",
+                first_l1_batch.header.number
+            );
+            METRICS.l1_fee_criterion_decisions[&(op, L1FeeDecision::DeadlineReached).into()].inc();
+            self.postponed = None;
+            return self
+                .timestamp
+                .last_l1_batch_to_publish(storage, consecutive_l1_batches, last_sealed_l1_batch)
+                .await;
+        }
+
+        let first_l1_batch_number = first_l1_batch.header.number;
+        if self.postponed.map_or(true, |postponed| {
+            postponed.first_l1_batch != first_l1_batch_number
+        }) {
+            self.postponed = Some(PostponedRange {
+                first_l1_batch: first_l1_batch_number,
+                base_fee: self.fee_statistics.current_base_fee(),
+            });
+        }
+        tracing::debug!(
+            "`l1_fee` publish criterion postponed op {op} for L1 batch #{first_l1_batch_number} (age: {oldest_l1_batch_age_seconds}s)"
+        );
+        METRICS.l1_fee_criterion_decisions[&(op, L1FeeDecision::Postponed).into()].inc();
+        None
+    }
+}
+
 #[derive(Debug)]
 pub struct GasCriterion {
     pub op: AggregatedActionType,
@@ -210,22 +363,35 @@ impl L1BatchPublishCriterion for DataSizeCriterion {
 
     async fn last_l1_batch_to_publish(
         &mut self,
-        _storage: &mut Connection<'_, Core>,
+        storage: &mut Connection<'_, Core>,
         consecutive_l1_batches: &[L1BatchWithMetadata],
         _last_sealed_l1_batch: L1BatchNumber,
     ) -> Option<L1BatchNumber> {
         const STORED_BLOCK_INFO_SIZE: usize = 96; // size of `StoredBlockInfo` solidity struct
         let mut data_size_left = self.data_limit - STORED_BLOCK_INFO_SIZE;
 
+        // With custom DA, commit data contains inclusion data returned by the DA layer instead of pubdata.
+        let da_inclusion_data = match (
+            self.pubdata_da,
+            consecutive_l1_batches.first(),
+            consecutive_l1_batches.last(),
+        ) {
+            (PubdataDA::Custom, Some(first), Some(last)) => storage
+                .data_availability_dal()
+                .get_l1_batches_inclusion_data(first.header.number, last.header.number)
+                .await
+                .unwrap(),
+            _ => HashMap::new(),
+        };
+
         for (index, l1_batch) in consecutive_l1_batches.iter().enumerate() {
+            let mut commit_info = CommitBatchInfo::new(l1_batch, self.pubdata_da);
+            if let Some(inclusion_data) = da_inclusion_data.get(&l1_batch.header.number) {
+                commit_info = commit_info.with_da_inclusion_data(inclusion_data);
+            }
             // TODO (PLA-771): Make sure that this estimation is correct.
             let l1_commit_data_size =
-                ethabi::encode(&[ethabi::Token::Array(vec![CommitBatchInfo::new(
-                    l1_batch,
-                    self.pubdata_da,
-                )
-                .into_token()])])
-                .len();
+                ethabi::encode(&[ethabi::Token::Array(vec![commit_info.into_token()])]).len();
             if data_size_left < l1_commit_data_size {
                 if index == 0 {
                     panic!(
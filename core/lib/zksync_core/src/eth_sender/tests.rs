@@ -1,34 +1,44 @@
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
 use assert_matches::assert_matches;
 use once_cell::sync::Lazy;
 use test_casing::test_casing;
 use zksync_config::{
-    configs::eth_sender::{ProofSendingMode, PubdataSendingMode, SenderConfig},
+    configs::eth_sender::{OperatorRole, ProofSendingMode, PubdataSendingMode, SenderConfig},
     ContractsConfig, ETHSenderConfig, GasAdjusterConfig,
 };
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_eth_client::{clients::MockEthereum, EthInterface};
-use zksync_l1_contract_interface::i_executor::methods::{
-    CommitBatches, ExecuteBatches, ProveBatches,
+use zksync_l1_contract_interface::i_executor::{
+    commit::kzg::KzgInfo,
+    methods::{CommitBatches, ExecuteBatches, ProveBatches},
 };
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{ObjectStoreError, ObjectStoreFactory};
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     block::L1BatchHeader,
     commitment::{L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata},
+    eth_sender::{EthTxBlobSidecarV1, L1BatchBlobs, SidecarBlobV1},
     ethabi::Token,
     helpers::unix_timestamp_ms,
     pubdata_da::PubdataDA,
     web3::contract::Error,
-    Address, L1BatchNumber, L1BlockNumber, ProtocolVersionId, H256,
+    Address, L1BatchNumber, L1BlockNumber, ProtocolVersionId, H256, U256,
 };
+use zksync_utils::time::seconds_since_epoch;
 
 use crate::{
     eth_sender::{
-        aggregated_operations::AggregatedOperation, eth_tx_manager::L1BlockNumbers, Aggregator,
-        ETHSenderError, EthTxAggregator, EthTxManager,
+        aggregated_operations::AggregatedOperation,
+        blob_archiver::BlobArchiver,
+        eth_tx_manager::L1BlockNumbers,
+        publish_criterion::{L1BatchPublishCriterion, L1FeeCriterion, TimestampDeadlineCriterion},
+        Aggregator, ETHSenderError, EthTxAggregator, EthTxManager, OperatorAccounts,
     },
-    l1_gas_price::GasAdjuster,
+    l1_gas_price::{GasAdjuster, L1FeeStatisticsProvider},
     utils::testonly::{create_l1_batch, l1_batch_metadata_to_commitment_artifacts},
 };
 
@@ -66,6 +76,8 @@ fn mock_multicall_response() -> Token {
 struct EthSenderTester {
     conn: ConnectionPool<Core>,
     gateway: Arc<MockEthereum>,
+    /// Gateway of the operator account taking over all roles in [`EthSenderTester::ROTATION_L1_BATCH`].
+    rotation_gateway: Option<Arc<MockEthereum>>,
     manager: MockEthTxManager,
     aggregator: EthTxAggregator,
     gas_adjuster: Arc<GasAdjuster>,
@@ -74,12 +86,34 @@ struct EthSenderTester {
 impl EthSenderTester {
     const WAIT_CONFIRMATIONS: u64 = 10;
     const MAX_BASE_FEE_SAMPLES: usize = 3;
+    const ROTATION_L1_BATCH: L1BatchNumber = L1BatchNumber(2);
 
     async fn new(
         connection_pool: ConnectionPool<Core>,
         history: Vec<u64>,
         non_ordering_confirmations: bool,
         aggregator_operate_4844_mode: bool,
+    ) -> Self {
+        Self::new_inner(
+            connection_pool,
+            history,
+            non_ordering_confirmations,
+            aggregator_operate_4844_mode,
+            false,
+        )
+        .await
+    }
+
+    async fn with_operator_rotation(connection_pool: ConnectionPool<Core>) -> Self {
+        Self::new_inner(connection_pool, vec![10; 100], false, false, true).await
+    }
+
+    async fn new_inner(
+        connection_pool: ConnectionPool<Core>,
+        history: Vec<u64>,
+        non_ordering_confirmations: bool,
+        aggregator_operate_4844_mode: bool,
+        rotate_operator: bool,
     ) -> Self {
         let eth_sender_config = ETHSenderConfig::for_tests();
         let contracts_config = ContractsConfig::for_tests();
@@ -103,6 +137,21 @@ impl EthSenderTester {
         gateway.advance_block_number(Self::WAIT_CONFIRMATIONS);
         let gateway = Arc::new(gateway);
 
+        let mut operator_accounts = OperatorAccounts::new(gateway.clone());
+        let rotation_gateway = rotate_operator.then(|| {
+            let rotation_gateway =
+                MockEthereum::default().with_sender_account(Address::repeat_byte(0x33));
+            rotation_gateway.advance_block_number(Self::WAIT_CONFIRMATIONS);
+            Arc::new(rotation_gateway)
+        });
+        if let Some(rotation_gateway) = &rotation_gateway {
+            operator_accounts = operator_accounts.with_rotation(
+                rotation_gateway.clone(),
+                &[],
+                Self::ROTATION_L1_BATCH,
+            );
+        }
+
         let gas_adjuster = Arc::new(
             GasAdjuster::new(
                 gateway.clone(),
@@ -131,14 +180,14 @@ impl EthSenderTester {
                 store_factory.create_store().await,
                 aggregator_operate_4844_mode,
                 PubdataDA::Calldata,
+                None,
             ),
-            gateway.clone(),
+            operator_accounts.clone(),
             // zkSync contract address
             Address::random(),
             contracts_config.l1_multicall3_addr,
             Address::random(),
             Default::default(),
-            None,
         )
         .await;
 
@@ -146,11 +195,11 @@ impl EthSenderTester {
             connection_pool.clone(),
             eth_sender_config.sender,
             gas_adjuster.clone(),
-            gateway.clone(),
-            None,
+            operator_accounts,
         );
         Self {
             gateway,
+            rotation_gateway,
             manager,
             aggregator,
             gas_adjuster,
@@ -432,6 +481,271 @@ async fn dont_resend_already_mined() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn reverting_confirmation_on_l1_reorg() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false, false).await;
+    let tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.connection().await.unwrap(),
+            &DUMMY_OPERATION,
+            true,
+        )
+        .await?;
+    let sent_at_block = tester.gateway.block_number("").await?.as_u64();
+    let hash = tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.connection().await.unwrap(),
+            &tx,
+            0,
+            L1BlockNumber(sent_at_block as u32),
+        )
+        .await?;
+    tester
+        .gateway
+        .execute_tx(hash, true, EthSenderTester::WAIT_CONFIRMATIONS);
+
+    let mut storage = tester.conn.connection().await.unwrap();
+    let block_numbers = tester.get_block_numbers().await;
+    tester
+        .manager
+        .monitor_inflight_transactions(&mut storage, block_numbers)
+        .await?;
+    assert!(storage
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await?
+        .is_empty());
+
+    // Reorg not affecting the tx block should be ignored.
+    tester.gateway.reorg(sent_at_block + 1);
+    tester
+        .manager
+        .revert_reorged_confirmations(&mut storage, block_numbers)
+        .await?;
+    assert!(storage
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await?
+        .is_empty());
+
+    tester.gateway.reorg(sent_at_block);
+    tester
+        .manager
+        .revert_reorged_confirmations(&mut storage, block_numbers)
+        .await?;
+    let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await?;
+    assert_eq!(inflight_txs.len(), 1);
+    assert_eq!(inflight_txs[0].id, tx.id);
+    let fatal_reorg = storage.l1_reorgs_dal().get_last_fatal_l1_reorg().await?;
+    assert_eq!(fatal_reorg, None);
+
+    // The transaction is included into the new fork as well, so it should be confirmed again.
+    let to_resend = tester
+        .manager
+        .monitor_inflight_transactions(&mut storage, block_numbers)
+        .await?;
+    assert!(to_resend.is_none());
+    assert!(storage
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await?
+        .is_empty());
+    tester
+        .manager
+        .revert_reorged_confirmations(&mut storage, block_numbers)
+        .await?;
+    assert!(storage
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+fn execute_operation(l1_batch_number: u32) -> AggregatedOperation {
+    AggregatedOperation::Execute(ExecuteBatches {
+        l1_batches: vec![L1BatchWithMetadata {
+            header: create_l1_batch(l1_batch_number),
+            metadata: default_l1_batch_metadata(),
+            raw_published_factory_deps: Vec::new(),
+        }],
+    })
+}
+
+#[test]
+fn selecting_operator_account_for_operation() {
+    let main_gateway = Arc::new(MockEthereum::default());
+    let blobs_gateway =
+        Arc::new(MockEthereum::default().with_sender_account(Address::repeat_byte(0x22)));
+    let rotation_gateway =
+        Arc::new(MockEthereum::default().with_sender_account(Address::repeat_byte(0x33)));
+    let accounts = OperatorAccounts::new(main_gateway)
+        .with_blobs_operator(blobs_gateway)
+        .with_rotation(rotation_gateway, &[OperatorRole::Prove], L1BatchNumber(10));
+
+    let sender = |op_type, l1_batch| {
+        accounts
+            .for_operation(op_type, L1BatchNumber(l1_batch))
+            .address()
+    };
+    for l1_batch in [1, 9, 10, 20] {
+        assert_eq!(
+            sender(AggregatedActionType::Commit, l1_batch),
+            Address::repeat_byte(0x22)
+        );
+        assert_eq!(
+            sender(AggregatedActionType::Execute, l1_batch),
+            Address::repeat_byte(0x11)
+        );
+    }
+    assert_eq!(
+        sender(AggregatedActionType::PublishProofOnchain, 9),
+        Address::repeat_byte(0x11)
+    );
+    assert_eq!(
+        sender(AggregatedActionType::PublishProofOnchain, 10),
+        Address::repeat_byte(0x33)
+    );
+
+    let main_account = accounts.by_sender(None).unwrap();
+    assert_eq!(main_account.address(), Address::repeat_byte(0x11));
+    assert!(accounts
+        .by_sender(Some(Address::repeat_byte(0x44)))
+        .is_none());
+}
+
+// Tests that the rotated operator account takes over operations starting from the rotation L1 batch,
+// uses its own nonces, and only starts sending once in-flight transactions of the old account are confirmed.
+#[tokio::test]
+async fn operator_key_rotation() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut tester = EthSenderTester::with_operator_rotation(connection_pool).await;
+    let rotation_gateway = tester.rotation_gateway.clone().unwrap();
+
+    let mut txs = vec![];
+    for l1_batch_number in [1, 2] {
+        let tx = tester
+            .aggregator
+            .save_eth_tx(
+                &mut tester.conn.connection().await.unwrap(),
+                &execute_operation(l1_batch_number),
+                true,
+            )
+            .await?;
+        txs.push(tx);
+    }
+    assert_eq!(txs[0].from_addr, Some(Address::repeat_byte(0x11)));
+    assert_eq!(txs[1].from_addr, Some(Address::repeat_byte(0x33)));
+    // Nonces are tracked separately for each account.
+    assert_eq!(txs[0].nonce.0, 0);
+    assert_eq!(txs[1].nonce.0, 0);
+
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    tester
+        .manager
+        .send_new_eth_txs(&mut tester.conn.connection().await.unwrap(), block)
+        .await;
+    assert_eq!(tester.gateway.sent_tx_count(), 1);
+    // The transaction from the rotated account must wait for the old account to drain.
+    assert_eq!(rotation_gateway.sent_tx_count(), 0);
+
+    let old_account_tx_hash = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_last_sent_eth_tx(txs[0].id)
+        .await?
+        .unwrap()
+        .tx_hash;
+    confirm_tx(&mut tester, old_account_tx_hash).await;
+
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    tester
+        .manager
+        .send_new_eth_txs(&mut tester.conn.connection().await.unwrap(), block)
+        .await;
+    assert_eq!(tester.gateway.sent_tx_count(), 1);
+    assert_eq!(rotation_gateway.sent_tx_count(), 1);
+
+    let new_account_tx_hash = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_last_sent_eth_tx(txs[1].id)
+        .await?
+        .unwrap()
+        .tx_hash;
+    rotation_gateway.execute_tx(
+        new_account_tx_hash,
+        true,
+        EthSenderTester::WAIT_CONFIRMATIONS,
+    );
+    let to_resend = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.connection().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await?;
+    assert!(to_resend.is_none());
+    let inflight_txs = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await?;
+    assert!(inflight_txs.is_empty(), "{inflight_txs:?}");
+    Ok(())
+}
+
+// Tests that transactions from an operator account that was removed from the config are not sent
+// and do not crash `eth_sender`.
+#[tokio::test]
+async fn sending_tx_from_removed_operator_account() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut tester = EthSenderTester::with_operator_rotation(connection_pool.clone()).await;
+    let tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.connection().await.unwrap(),
+            &execute_operation(2),
+            true,
+        )
+        .await?;
+    assert_eq!(tx.from_addr, Some(Address::repeat_byte(0x33)));
+
+    // Emulate the rotation account key being removed from the config.
+    let mut manager = EthTxManager::new(
+        connection_pool,
+        ETHSenderConfig::for_tests().sender,
+        tester.gas_adjuster.clone(),
+        OperatorAccounts::new(tester.gateway.clone()),
+    );
+    let mut storage = tester.storage().await;
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    let err = manager
+        .send_eth_tx(&mut storage, &tx, 0, block)
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        ETHSenderError::UnknownOperatorAccount { eth_tx_id, sender }
+            if eth_tx_id == tx.id && sender == Address::repeat_byte(0x33)
+    );
+
+    manager.send_new_eth_txs(&mut storage, block).await;
+    assert_eq!(tester.gateway.sent_tx_count(), 0);
+    let to_resend = manager
+        .monitor_inflight_transactions(&mut storage, tester.get_block_numbers().await)
+        .await?;
+    assert!(to_resend.is_none());
+    Ok(())
+}
+
 #[tokio::test]
 async fn three_scenarios() -> anyhow::Result<()> {
     let connection_pool = ConnectionPool::<Core>::test_pool().await;
@@ -818,6 +1132,93 @@ async fn skipped_l1_batch_in_the_middle() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// L1 fee statistics returning a fixed value for all base fee percentiles.
+#[derive(Debug)]
+struct MockL1FeeStatistics {
+    current_base_fee: AtomicU64,
+}
+
+impl MockL1FeeStatistics {
+    const BASE_FEE_PERCENTILE: u64 = 100;
+
+    fn new() -> Self {
+        Self {
+            current_base_fee: AtomicU64::new(Self::BASE_FEE_PERCENTILE),
+        }
+    }
+
+    fn set_current_base_fee(&self, base_fee: u64) {
+        self.current_base_fee.store(base_fee, Ordering::Relaxed);
+    }
+}
+
+impl L1FeeStatisticsProvider for MockL1FeeStatistics {
+    fn current_base_fee(&self) -> u64 {
+        self.current_base_fee.load(Ordering::Relaxed)
+    }
+
+    fn base_fee_percentile(&self, _percentile: f64) -> u64 {
+        Self::BASE_FEE_PERCENTILE
+    }
+
+    fn current_blob_base_fee(&self) -> U256 {
+        U256::one()
+    }
+
+    fn blob_base_fee_percentile(&self, _percentile: f64) -> U256 {
+        U256::one()
+    }
+}
+
+#[tokio::test]
+async fn postponing_operations_on_high_l1_fees() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = connection_pool.connection().await.unwrap();
+    let fee_statistics = Arc::new(MockL1FeeStatistics::new());
+    let mut criterion = L1FeeCriterion::new(
+        TimestampDeadlineCriterion {
+            op: AggregatedActionType::Commit,
+            deadline_seconds: 0,
+            max_allowed_lag: None,
+        },
+        fee_statistics.clone(),
+        75.0,
+        3_600,
+        false,
+    );
+
+    let mut header = create_l1_batch(1);
+    header.timestamp = seconds_since_epoch();
+    let l1_batches = [l1_batch_with_metadata(header.clone())];
+    let last_l1_batch = criterion
+        .last_l1_batch_to_publish(&mut storage, &l1_batches, L1BatchNumber(1))
+        .await;
+    assert_eq!(last_l1_batch, Some(L1BatchNumber(1)));
+
+    // High fees should postpone the operation...
+    fee_statistics.set_current_base_fee(200);
+    let last_l1_batch = criterion
+        .last_l1_batch_to_publish(&mut storage, &l1_batches, L1BatchNumber(1))
+        .await;
+    assert_eq!(last_l1_batch, None);
+
+    // ...until fees are back to normal...
+    fee_statistics.set_current_base_fee(50);
+    let last_l1_batch = criterion
+        .last_l1_batch_to_publish(&mut storage, &l1_batches, L1BatchNumber(1))
+        .await;
+    assert_eq!(last_l1_batch, Some(L1BatchNumber(1)));
+
+    // ...or the maximum postponement time has passed.
+    fee_statistics.set_current_base_fee(200);
+    header.timestamp -= 3_600;
+    let l1_batches = [l1_batch_with_metadata(header)];
+    let last_l1_batch = criterion
+        .last_l1_batch_to_publish(&mut storage, &l1_batches, L1BatchNumber(1))
+        .await;
+    assert_eq!(last_l1_batch, Some(L1BatchNumber(1)));
+}
+
 #[tokio::test]
 async fn test_parse_multicall_data() {
     let connection_pool = ConnectionPool::<Core>::test_pool().await;
@@ -891,6 +1292,166 @@ async fn get_multicall_data() {
     assert!(multicall_data.is_ok());
 }
 
+fn sidecar_blob(pubdata: &[u8]) -> SidecarBlobV1 {
+    let kzg_info = KzgInfo::new(pubdata);
+    SidecarBlobV1 {
+        blob: kzg_info.blob.to_vec(),
+        commitment: kzg_info.kzg_commitment.to_vec(),
+        proof: kzg_info.blob_proof.to_vec(),
+        versioned_hash: kzg_info.versioned_hash.to_vec(),
+    }
+}
+
+/// Saves a commit transaction with the specified blobs for `l1_batch` and marks it as confirmed.
+async fn save_confirmed_blob_commit_tx(
+    storage: &mut Connection<'_, Core>,
+    l1_batch: L1BatchNumber,
+    blobs: Vec<SidecarBlobV1>,
+) -> H256 {
+    let eth_tx = storage
+        .eth_sender_dal()
+        .save_eth_tx(
+            l1_batch.0.into(),
+            vec![],
+            AggregatedActionType::Commit,
+            Address::zero(),
+            0,
+            Some(Address::repeat_byte(1)),
+            Some(EthTxBlobSidecarV1 { blobs }.into()),
+        )
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .set_eth_tx_id(l1_batch..=l1_batch, eth_tx.id, AggregatedActionType::Commit)
+        .await
+        .unwrap();
+
+    let tx_hash = H256::from_low_u64_be(l1_batch.0.into());
+    storage
+        .eth_sender_dal()
+        .insert_tx_history(eth_tx.id, 0, 0, Some(0), tx_hash, &[])
+        .await
+        .unwrap();
+    storage
+        .eth_sender_dal()
+        .confirm_tx(tx_hash, 0.into())
+        .await
+        .unwrap();
+    tx_hash
+}
+
+#[tokio::test]
+async fn archiving_blobs() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let tester = EthSenderTester::new(connection_pool, vec![100; 100], false, true).await;
+    insert_genesis_protocol_version(&tester).await;
+    for number in 1..=3 {
+        insert_l1_batch(&tester, L1BatchNumber(number)).await;
+    }
+
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let archiver = BlobArchiver::new(blob_store.clone());
+    let mut storage = tester.storage().await;
+    assert_eq!(
+        archiver
+            .archive_confirmed_blobs(&mut storage)
+            .await
+            .unwrap(),
+        0
+    );
+
+    let first_blobs = vec![sidecar_blob(b"first"), sidecar_blob(&[1; 1_000])];
+    let first_tx_hash =
+        save_confirmed_blob_commit_tx(&mut storage, L1BatchNumber(1), first_blobs.clone()).await;
+    let second_blobs = vec![sidecar_blob(b"second")];
+    let second_tx_hash =
+        save_confirmed_blob_commit_tx(&mut storage, L1BatchNumber(2), second_blobs.clone()).await;
+
+    assert_eq!(
+        archiver
+            .archive_confirmed_blobs(&mut storage)
+            .await
+            .unwrap(),
+        2
+    );
+    let archived_blobs: L1BatchBlobs = blob_store.get(L1BatchNumber(1)).await.unwrap();
+    assert_eq!(
+        archived_blobs,
+        L1BatchBlobs {
+            l1_batch_number: L1BatchNumber(1),
+            commit_tx_hash: first_tx_hash,
+            blobs: first_blobs,
+        }
+    );
+    let archived_blobs: L1BatchBlobs = blob_store.get(L1BatchNumber(2)).await.unwrap();
+    assert_eq!(archived_blobs.commit_tx_hash, second_tx_hash);
+    assert_eq!(archived_blobs.blobs, second_blobs);
+    let err = blob_store
+        .get::<L1BatchBlobs>(L1BatchNumber(3))
+        .await
+        .unwrap_err();
+    assert_matches!(err, ObjectStoreError::KeyNotFound(_));
+
+    // Blobs should not be archived twice.
+    assert_eq!(
+        archiver
+            .archive_confirmed_blobs(&mut storage)
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn archiving_invalid_blobs() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let tester = EthSenderTester::new(connection_pool, vec![100; 100], false, true).await;
+    insert_genesis_protocol_version(&tester).await;
+    insert_l1_batch(&tester, L1BatchNumber(1)).await;
+    insert_l1_batch(&tester, L1BatchNumber(2)).await;
+
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let archiver = BlobArchiver::new(blob_store.clone());
+    let mut storage = tester.storage().await;
+    let mut blob = sidecar_blob(b"test");
+    blob.versioned_hash = sidecar_blob(b"other").versioned_hash;
+    save_confirmed_blob_commit_tx(&mut storage, L1BatchNumber(1), vec![blob]).await;
+    let valid_blobs = vec![sidecar_blob(b"valid")];
+    save_confirmed_blob_commit_tx(&mut storage, L1BatchNumber(2), valid_blobs.clone()).await;
+
+    // Invalid blobs should not block archiving blobs for subsequent batches.
+    assert_eq!(
+        archiver
+            .archive_confirmed_blobs(&mut storage)
+            .await
+            .unwrap(),
+        1
+    );
+    let err = blob_store
+        .get::<L1BatchBlobs>(L1BatchNumber(1))
+        .await
+        .unwrap_err();
+    assert_matches!(err, ObjectStoreError::KeyNotFound(_));
+    let archived_blobs: L1BatchBlobs = blob_store.get(L1BatchNumber(2)).await.unwrap();
+    assert_eq!(archived_blobs.blobs, valid_blobs);
+
+    // Invalid blobs should not be re-checked on subsequent iterations.
+    let unarchived_blobs = storage
+        .eth_sender_dal()
+        .get_unarchived_batch_blobs(10)
+        .await
+        .unwrap();
+    assert!(unarchived_blobs.is_empty(), "{unarchived_blobs:?}");
+    assert_eq!(
+        archiver
+            .archive_confirmed_blobs(&mut storage)
+            .await
+            .unwrap(),
+        0
+    );
+}
+
 async fn insert_genesis_protocol_version(tester: &EthSenderTester) {
     tester
         .storage()
@@ -968,6 +1529,7 @@ async fn commit_l1_batch(
         last_committed_l1_batch: l1_batch_with_metadata(last_committed_l1_batch),
         l1_batches: vec![l1_batch_with_metadata(l1_batch)],
         pubdata_da: PubdataDA::Calldata,
+        da_inclusion_data: vec![],
     });
     send_operation(tester, operation, confirm).await
 }
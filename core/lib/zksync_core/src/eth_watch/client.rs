@@ -1,6 +1,7 @@
 use std::{fmt, sync::Arc};
 
 use zksync_contracts::verifier_contract;
+use zksync_dal::SqlxError;
 use zksync_eth_client::{CallFunctionArgs, Error as EthClientError, EthInterface};
 use zksync_types::{
     ethabi::Contract,
@@ -22,6 +23,10 @@ pub enum Error {
     EthClient(#[from] EthClientError),
     #[error("Infinite recursion caused by too many responses")]
     InfiniteRecursion,
+    #[error("Database error: {0}")]
+    Database(#[from] SqlxError),
+    #[error("L1 reorg cannot be handled automatically; reverted to L1 block #{0}")]
+    FatalL1Reorg(u64),
 }
 
 impl From<web3::contract::Error> for Error {
@@ -41,10 +46,14 @@ pub trait EthClient: 'static + fmt::Debug + Send + Sync {
     ) -> Result<Vec<Log>, Error>;
     /// Returns finalized L1 block number.
     async fn finalized_block_number(&self) -> Result<u64, Error>;
+    /// Returns the hash of the L1 block with the specified number, or `None` if there is no such block.
+    async fn block_hash(&self, number: u64) -> Result<Option<H256>, Error>;
     /// Returns scheduler verification key hash by verifier address.
     async fn scheduler_vk_hash(&self, verifier_address: Address) -> Result<H256, Error>;
     /// Sets list of topics to return events for.
     fn set_topics(&mut self, topics: Vec<H256>);
+    /// Sets addresses of L1 contracts to return events for in addition to the diamond proxy and governance contracts.
+    fn set_extra_addresses(&mut self, addresses: Vec<Address>);
 }
 
 pub const RETRY_LIMIT: usize = 5;
@@ -59,6 +68,8 @@ pub struct EthHttpQueryClient {
     /// Address of the `Governance` contract. It's optional because it is present only for post-boojum chains.
     /// If address is some then client will listen to events coming from it.
     governance_address: Option<Address>,
+    /// Addresses of L1 contracts watched by extra event processors.
+    extra_addresses: Vec<Address>,
     verifier_contract_abi: Contract,
     confirmations_for_eth_event: Option<u64>,
 }
@@ -80,6 +91,7 @@ impl EthHttpQueryClient {
             topics: Vec::new(),
             zksync_contract_addr,
             governance_address,
+            extra_addresses: Vec::new(),
             verifier_contract_abi: verifier_contract(),
             confirmations_for_eth_event,
         }
@@ -91,14 +103,13 @@ impl EthHttpQueryClient {
         to: BlockNumber,
         topics: Vec<H256>,
     ) -> Result<Vec<Log>, Error> {
+        let addresses = [Some(self.zksync_contract_addr), self.governance_address]
+            .into_iter()
+            .flatten()
+            .chain(self.extra_addresses.iter().copied())
+            .collect();
         let filter = FilterBuilder::default()
-            .address(
-                [Some(self.zksync_contract_addr), self.governance_address]
-                    .iter()
-                    .flatten()
-                    .copied()
-                    .collect(),
-            )
+            .address(addresses)
             .from_block(from)
             .to_block(to)
             .topics(Some(topics), None, None, None)
@@ -218,7 +229,19 @@ impl EthClient for EthHttpQueryClient {
         }
     }
 
+    async fn block_hash(&self, number: u64) -> Result<Option<H256>, Error> {
+        let block = self
+            .client
+            .block(BlockId::Number(BlockNumber::Number(number.into())), "watch")
+            .await?;
+        Ok(block.and_then(|block| block.hash))
+    }
+
     fn set_topics(&mut self, topics: Vec<H256>) {
         self.topics = topics;
     }
+
+    fn set_extra_addresses(&mut self, addresses: Vec<Address>) {
+        self.extra_addresses = addresses;
+    }
 }
@@ -15,6 +15,7 @@ use crate::eth_watch::{
 #[derive(Debug)]
 pub struct GovernanceUpgradesEventProcessor {
     diamond_proxy_address: Address,
+    governance_address: Address,
     /// Last protocol version seen. Used to skip events for already known upgrade proposals.
     last_seen_version_id: ProtocolVersionId,
     upgrade_proposal_signature: H256,
@@ -23,11 +24,13 @@ pub struct GovernanceUpgradesEventProcessor {
 impl GovernanceUpgradesEventProcessor {
     pub fn new(
         diamond_proxy_address: Address,
+        governance_address: Address,
         last_seen_version_id: ProtocolVersionId,
         governance_contract: &Contract,
     ) -> Self {
         Self {
             diamond_proxy_address,
+            governance_address,
             last_seen_version_id,
             upgrade_proposal_signature: governance_contract
                 .event("TransparentOperationScheduled")
@@ -46,10 +49,10 @@ impl EventProcessor for GovernanceUpgradesEventProcessor {
         events: Vec<Log>,
     ) -> Result<(), Error> {
         let mut upgrades = Vec::new();
-        for event in events
-            .into_iter()
-            .filter(|event| event.topics[0] == self.upgrade_proposal_signature)
-        {
+        for event in events.into_iter().filter(|event| {
+            event.address == self.governance_address
+                && event.topics[0] == self.upgrade_proposal_signature
+        }) {
             let governance_operation = GovernanceOperation::try_from(event)
                 .map_err(|err| Error::LogParse(format!("{:?}", err)))?;
             // Some calls can target other contracts than Diamond proxy, skip them.
@@ -117,4 +120,19 @@ impl EventProcessor for GovernanceUpgradesEventProcessor {
     fn relevant_topic(&self) -> H256 {
         self.upgrade_proposal_signature
     }
+
+    async fn handle_l1_reorg(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        _reverted_to_l1_block: u64,
+    ) -> Result<(), Error> {
+        // Protocol versions are never removed on L1 reorgs (reorgs of blocks with upgrade transactions are fatal),
+        // so upgrade proposals re-emitted in the re-processed L1 blocks will be skipped based on the reloaded version.
+        self.last_seen_version_id = storage
+            .protocol_versions_dal()
+            .last_version_id()
+            .await
+            .expect("Expected at least one (genesis) version to be present in DB");
+        Ok(())
+    }
 }
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use zksync_dal::{Connection, Core};
-use zksync_types::{web3::types::Log, H256};
+use zksync_types::{web3::types::Log, Address, H256};
 
 use crate::eth_watch::client::{Error, EthClient};
 
@@ -21,4 +21,20 @@ pub trait EventProcessor: 'static + fmt::Debug + Send + Sync {
 
     /// Relevant topic which defines what events to be processed
     fn relevant_topic(&self) -> H256;
+
+    /// Address of the L1 contract emitting relevant events. If not specified, events are expected
+    /// to be emitted by the diamond proxy or governance contracts.
+    fn relevant_address(&self) -> Option<Address> {
+        None
+    }
+
+    /// Resets the processor state after an L1 reorg. Events from L1 blocks after `reverted_to_l1_block`
+    /// will be processed again.
+    async fn handle_l1_reorg(
+        &mut self,
+        _storage: &mut Connection<'_, Core>,
+        _reverted_to_l1_block: u64,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
 }
@@ -2,7 +2,7 @@ use std::convert::TryFrom;
 
 use zksync_contracts::zksync_contract;
 use zksync_dal::{Connection, Core, CoreDal};
-use zksync_types::{l1::L1Tx, web3::types::Log, PriorityOpId, H256};
+use zksync_types::{l1::L1Tx, web3::types::Log, Address, PriorityOpId, H256};
 
 use crate::{
     eth_watch::{
@@ -16,13 +16,15 @@ use crate::{
 /// Responsible for saving new priority L1 transactions to the database.
 #[derive(Debug)]
 pub struct PriorityOpsEventProcessor {
+    diamond_proxy_address: Address,
     next_expected_priority_id: PriorityOpId,
     new_priority_request_signature: H256,
 }
 
 impl PriorityOpsEventProcessor {
-    pub fn new(next_expected_priority_id: PriorityOpId) -> Self {
+    pub fn new(diamond_proxy_address: Address, next_expected_priority_id: PriorityOpId) -> Self {
         Self {
+            diamond_proxy_address,
             next_expected_priority_id,
             new_priority_request_signature: zksync_contract()
                 .event("NewPriorityRequest")
@@ -41,10 +43,10 @@ impl EventProcessor for PriorityOpsEventProcessor {
         events: Vec<Log>,
     ) -> Result<(), Error> {
         let mut priority_ops = Vec::new();
-        for event in events
-            .into_iter()
-            .filter(|event| event.topics[0] == self.new_priority_request_signature)
-        {
+        for event in events.into_iter().filter(|event| {
+            event.address == self.diamond_proxy_address
+                && event.topics[0] == self.new_priority_request_signature
+        }) {
             let tx = L1Tx::try_from(event).map_err(|err| Error::LogParse(format!("{}", err)))?;
             priority_ops.push(tx);
         }
@@ -102,4 +104,18 @@ impl EventProcessor for PriorityOpsEventProcessor {
     fn relevant_topic(&self) -> H256 {
         self.new_priority_request_signature
     }
+
+    async fn handle_l1_reorg(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        _reverted_to_l1_block: u64,
+    ) -> Result<(), Error> {
+        // Priority ops from the reverted L1 blocks are removed from the storage, so we can just reload the state.
+        self.next_expected_priority_id = storage
+            .transactions_dal()
+            .last_priority_id()
+            .await
+            .map_or(PriorityOpId(0), |id| id + 1);
+        Ok(())
+    }
 }
@@ -1,7 +1,7 @@
 use std::convert::TryFrom;
 
 use zksync_dal::{Connection, Core, CoreDal};
-use zksync_types::{web3::types::Log, ProtocolUpgrade, ProtocolVersionId, H256};
+use zksync_types::{web3::types::Log, Address, ProtocolUpgrade, ProtocolVersionId, H256};
 
 use crate::eth_watch::{
     client::{Error, EthClient},
@@ -17,12 +17,14 @@ pub(crate) const UPGRADE_PROPOSAL_SIGNATURE: H256 = H256([
 /// Responsible for saving new protocol upgrade proposals to the database.
 #[derive(Debug)]
 pub struct UpgradesEventProcessor {
+    diamond_proxy_address: Address,
     last_seen_version_id: ProtocolVersionId,
 }
 
 impl UpgradesEventProcessor {
-    pub fn new(last_seen_version_id: ProtocolVersionId) -> Self {
+    pub fn new(diamond_proxy_address: Address, last_seen_version_id: ProtocolVersionId) -> Self {
         Self {
+            diamond_proxy_address,
             last_seen_version_id,
         }
     }
@@ -37,10 +39,10 @@ impl EventProcessor for UpgradesEventProcessor {
         events: Vec<Log>,
     ) -> Result<(), Error> {
         let mut upgrades = Vec::new();
-        for event in events
-            .into_iter()
-            .filter(|event| event.topics[0] == UPGRADE_PROPOSAL_SIGNATURE)
-        {
+        for event in events.into_iter().filter(|event| {
+            event.address == self.diamond_proxy_address
+                && event.topics[0] == UPGRADE_PROPOSAL_SIGNATURE
+        }) {
             let upgrade = ProtocolUpgrade::try_from(event)
                 .map_err(|err| Error::LogParse(format!("{:?}", err)))?;
             // Scheduler VK is not present in proposal event. It is hard coded in verifier contract.
@@ -86,4 +88,19 @@ impl EventProcessor for UpgradesEventProcessor {
     fn relevant_topic(&self) -> H256 {
         UPGRADE_PROPOSAL_SIGNATURE
     }
+
+    async fn handle_l1_reorg(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        _reverted_to_l1_block: u64,
+    ) -> Result<(), Error> {
+        // Protocol versions are never removed on L1 reorgs (reorgs of blocks with upgrade transactions are fatal),
+        // so upgrade proposals re-emitted in the re-processed L1 blocks will be skipped based on the reloaded version.
+        self.last_seen_version_id = storage
+            .protocol_versions_dal()
+            .last_version_id()
+            .await
+            .expect("Expected at least one (genesis) version to be present in DB");
+        Ok(())
+    }
 }
@@ -0,0 +1,163 @@
+//! Event processors registered in addition to the built-in ones.
+
+use zksync_dal::{Connection, Core, CoreDal};
+use zksync_types::web3::types::{BlockNumber as Web3BlockNumber, Log};
+
+use super::{
+    client::{Error, EthClient, RETRY_LIMIT},
+    event_processors::EventProcessor,
+    metrics::METRICS,
+};
+
+/// Maximum number of L1 blocks processed in a single iteration while an extra processor is backfilled.
+const MAX_BACKFILL_BLOCKS: u64 = 10_000;
+
+/// Event processor registered in `EthWatch` in addition to the built-in ones (e.g., to handle events
+/// from custom L1 contracts of a hyperchain).
+///
+/// Unlike built-in processors, each extra processor has its own cursor persisted in Postgres, so it can be
+/// added to a running chain. On the first run, the processor is backfilled starting from the configured L1 block;
+/// until it catches up with the watcher, events for it are fetched separately in chunks. Events are passed to
+/// the processor exactly once: processing and advancing the cursor happen in a single DB transaction.
+#[derive(Debug)]
+pub struct ExtraEventProcessor {
+    name: String,
+    processor: Box<dyn EventProcessor>,
+    backfill_from: Option<u64>,
+    /// Last processed L1 block; `None` until the cursor is loaded from Postgres.
+    cursor: Option<u64>,
+}
+
+impl ExtraEventProcessor {
+    /// Creates a processor with the specified name, which identifies its cursor in Postgres.
+    pub fn new(name: impl Into<String>, processor: Box<dyn EventProcessor>) -> Self {
+        Self {
+            name: name.into(),
+            processor,
+            backfill_from: None,
+            cursor: None,
+        }
+    }
+
+    /// Sets the first L1 block to process events from. Only has an effect if the processor has no persisted cursor
+    /// (i.e., it runs for the first time). By default, the processor starts from the block the watcher is at.
+    #[must_use]
+    pub fn with_backfill_from(mut self, l1_block: u64) -> Self {
+        self.backfill_from = Some(l1_block);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(super) fn processor(&self) -> &dyn EventProcessor {
+        self.processor.as_ref()
+    }
+
+    /// Processes events up to `to_block` inclusive, or a chunk of them if the processor is being backfilled.
+    /// `events` are the events fetched by the watcher starting from `watcher_from_block` inclusive.
+    pub(super) async fn process(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        client: &dyn EthClient,
+        watcher_from_block: u64,
+        to_block: u64,
+        events: &[Log],
+    ) -> Result<(), Error> {
+        let cursor = match self.cursor {
+            Some(cursor) => cursor,
+            None => self.init_cursor(storage, watcher_from_block).await?,
+        };
+        if cursor >= to_block {
+            return Ok(());
+        }
+
+        let from_block = cursor + 1;
+        let (events, to_block) = if from_block >= watcher_from_block {
+            let events = events
+                .iter()
+                .filter(|event| Self::block_number(event) >= from_block)
+                .cloned()
+                .collect();
+            (events, to_block)
+        } else {
+            let to_block = to_block.min(cursor + MAX_BACKFILL_BLOCKS);
+            tracing::info!(
+                "Backfilling event processor `{}` for L1 blocks {from_block}..={to_block}",
+                self.name
+            );
+            let events = client
+                .get_events(
+                    Web3BlockNumber::Number(from_block.into()),
+                    Web3BlockNumber::Number(to_block.into()),
+                    RETRY_LIMIT,
+                )
+                .await?;
+            (events, to_block)
+        };
+        let events = self.filter_relevant(events);
+
+        let mut transaction = storage.start_transaction().await?;
+        self.processor
+            .process_events(&mut transaction, client, events)
+            .await?;
+        transaction
+            .eth_watch_dal()
+            .set_processor_cursor(&self.name, to_block)
+            .await?;
+        transaction.commit().await?;
+
+        self.cursor = Some(to_block);
+        METRICS.extra_processor_cursor[&self.name].set(to_block);
+        Ok(())
+    }
+
+    async fn init_cursor(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        watcher_from_block: u64,
+    ) -> Result<u64, Error> {
+        let initial_cursor = self
+            .backfill_from
+            .map_or(watcher_from_block, |block| block.saturating_sub(1));
+        let cursor = storage
+            .eth_watch_dal()
+            .init_processor_cursor(&self.name, initial_cursor)
+            .await?;
+        tracing::info!(
+            "Initialized event processor `{}` with last processed L1 block #{cursor}",
+            self.name
+        );
+        self.cursor = Some(cursor);
+        Ok(cursor)
+    }
+
+    fn block_number(event: &Log) -> u64 {
+        event.block_number.map_or(0, |number| number.as_u64())
+    }
+
+    fn filter_relevant(&self, events: Vec<Log>) -> Vec<Log> {
+        let topic = self.processor.relevant_topic();
+        let address = self.processor.relevant_address();
+        events
+            .into_iter()
+            .filter(|event| {
+                event.topics.first() == Some(&topic)
+                    && address.map_or(true, |address| event.address == address)
+            })
+            .collect()
+    }
+
+    /// Resets the processor state after an L1 reorg. The persisted cursor must be rewound by the caller.
+    pub(super) async fn handle_l1_reorg(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        reverted_to_l1_block: u64,
+    ) -> Result<(), Error> {
+        self.cursor = self.cursor.map(|cursor| cursor.min(reverted_to_l1_block));
+        self.processor
+            .handle_l1_reorg(storage, reverted_to_l1_block)
+            .await
+    }
+}
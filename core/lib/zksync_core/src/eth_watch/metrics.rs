@@ -2,7 +2,10 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    Metrics,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
@@ -20,6 +23,11 @@ pub(super) struct EthWatcherMetrics {
     pub poll_eth_node: Family<PollStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub get_priority_op_events: Histogram<Duration>,
+    /// Number of detected L1 reorgs affecting processed L1 blocks.
+    pub l1_reorgs: Counter,
+    /// Last L1 block processed by an extra event processor.
+    #[metrics(labels = ["processor"])]
+    pub extra_processor_cursor: LabeledFamily<String, Gauge<u64>>,
 }
 
 #[vise::register]
@@ -3,19 +3,32 @@
 //!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
+//!
+//! Hashes of processed L1 blocks are persisted and re-checked on each poll. If an L1 reorg deeper than
+//! the number of confirmations is detected, the watcher rolls back to the last L1 block that is still canonical
+//! and removes priority ops received in orphaned blocks. If some of these ops are already loaded into the state keeper
+//! mempool or executed, or if orphaned blocks contain protocol upgrades, the reorg is recorded as fatal, which stops
+//! the watcher and trips the corresponding circuit breaker.
+//!
+//! Besides the built-in event processors, the watcher can run [`ExtraEventProcessor`]s registered
+//! via [`EthWatch::register_processor()`]. Each of them has a cursor persisted in Postgres.
 
 use std::{sync::Arc, time::Duration};
 
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_config::ETHWatchConfig;
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{
+    l1_reorgs_dal::{L1BlockCheckpoint, L1Reorg},
+    Connection, ConnectionPool, Core, CoreDal,
+};
 use zksync_eth_client::EthInterface;
 use zksync_system_constants::PRIORITY_EXPIRATION;
 use zksync_types::{
-    ethabi::Contract, web3::types::BlockNumber as Web3BlockNumber, Address, PriorityOpId,
-    ProtocolVersionId,
+    ethabi::Contract, web3::types::BlockNumber as Web3BlockNumber, Address, L1BlockNumber,
+    PriorityOpId, ProtocolVersionId,
 };
 
+pub use self::extra_processors::ExtraEventProcessor;
 use self::{
     client::{Error, EthClient, EthHttpQueryClient, RETRY_LIMIT},
     event_processors::{
@@ -26,11 +39,15 @@ use self::{
 };
 
 pub mod client;
-mod event_processors;
+pub mod event_processors;
+mod extra_processors;
 mod metrics;
 #[cfg(test)]
 mod tests;
 
+/// Number of most recent processed L1 blocks which are re-checked for reorgs.
+const L1_REORG_CHECK_WINDOW: u64 = 1_024;
+
 #[derive(Debug)]
 struct EthWatchState {
     last_seen_version_id: ProtocolVersionId,
@@ -43,6 +60,7 @@ pub struct EthWatch {
     client: Box<dyn EthClient>,
     poll_interval: Duration,
     event_processors: Vec<Box<dyn EventProcessor>>,
+    extra_processors: Vec<ExtraEventProcessor>,
 
     last_processed_ethereum_block: u64,
     pool: ConnectionPool<Core>,
@@ -51,8 +69,8 @@ pub struct EthWatch {
 impl EthWatch {
     pub async fn new(
         diamond_proxy_address: Address,
-        governance_contract: Option<Contract>,
-        mut client: Box<dyn EthClient>,
+        governance: Option<(Contract, Address)>,
+        client: Box<dyn EthClient>,
         pool: ConnectionPool<Core>,
         poll_interval: Duration,
     ) -> Self {
@@ -65,35 +83,66 @@ impl EthWatch {
         drop(storage);
 
         let priority_ops_processor =
-            PriorityOpsEventProcessor::new(state.next_expected_priority_id);
-        let upgrades_processor = UpgradesEventProcessor::new(state.last_seen_version_id);
+            PriorityOpsEventProcessor::new(diamond_proxy_address, state.next_expected_priority_id);
+        let upgrades_processor =
+            UpgradesEventProcessor::new(diamond_proxy_address, state.last_seen_version_id);
         let mut event_processors: Vec<Box<dyn EventProcessor>> = vec![
             Box::new(priority_ops_processor),
             Box::new(upgrades_processor),
         ];
 
-        if let Some(governance_contract) = governance_contract {
+        if let Some((governance_contract, governance_address)) = governance {
             let governance_upgrades_processor = GovernanceUpgradesEventProcessor::new(
                 diamond_proxy_address,
+                governance_address,
                 state.last_seen_version_id,
                 &governance_contract,
             );
             event_processors.push(Box::new(governance_upgrades_processor))
         }
 
-        let topics = event_processors
-            .iter()
-            .map(|p| p.relevant_topic())
-            .collect();
-        client.set_topics(topics);
-
-        Self {
+        let mut this = Self {
             client,
             poll_interval,
             event_processors,
+            extra_processors: Vec::new(),
             last_processed_ethereum_block: state.last_processed_ethereum_block,
             pool,
-        }
+        };
+        this.update_client_filter();
+        this
+    }
+
+    /// Registers an extra event processor. Its name must be unique among registered processors.
+    pub fn register_processor(&mut self, processor: ExtraEventProcessor) -> anyhow::Result<()> {
+        let name = processor.name();
+        anyhow::ensure!(
+            self.extra_processors.iter().all(|p| p.name() != name),
+            "event processor `{name}` is already registered"
+        );
+        tracing::info!("Registered extra event processor `{name}`");
+        self.extra_processors.push(processor);
+        self.update_client_filter();
+        Ok(())
+    }
+
+    fn update_client_filter(&mut self) {
+        let builtin_filters = self
+            .event_processors
+            .iter()
+            .map(|p| (p.relevant_topic(), p.relevant_address()));
+        let extra_filters = self.extra_processors.iter().map(|p| {
+            (
+                p.processor().relevant_topic(),
+                p.processor().relevant_address(),
+            )
+        });
+        let (topics, addresses): (Vec<_>, Vec<_>) = builtin_filters.chain(extra_filters).unzip();
+        let mut addresses: Vec<_> = addresses.into_iter().flatten().collect();
+        addresses.sort_unstable();
+        addresses.dedup();
+        self.client.set_topics(topics);
+        self.client.set_extra_addresses(addresses);
     }
 
     async fn initialize_state(
@@ -163,11 +212,15 @@ impl EthWatch {
 
     #[tracing::instrument(skip(self, storage))]
     async fn loop_iteration(&mut self, storage: &mut Connection<'_, Core>) -> Result<(), Error> {
+        self.check_for_l1_reorg(storage).await?;
+
         let stage_latency = METRICS.poll_eth_node[&PollStage::Request].start();
         let to_block = self.client.finalized_block_number().await?;
         if to_block <= self.last_processed_ethereum_block {
             return Ok(());
         }
+        // The hash is requested before events, so that a reorg happening in between is detected on the next iteration.
+        let to_block_hash = self.client.block_hash(to_block).await?;
 
         let events = self
             .client
@@ -184,7 +237,130 @@ impl EthWatch {
                 .process_events(storage, &*self.client, events.clone())
                 .await?;
         }
+        for processor in &mut self.extra_processors {
+            processor
+                .process(
+                    storage,
+                    &*self.client,
+                    self.last_processed_ethereum_block,
+                    to_block,
+                    &events,
+                )
+                .await?;
+        }
         self.last_processed_ethereum_block = to_block;
+
+        if let Some(hash) = to_block_hash {
+            let mut reorgs_dal = storage.l1_reorgs_dal();
+            reorgs_dal
+                .insert_eth_watch_checkpoint(L1BlockCheckpoint {
+                    number: to_block,
+                    hash,
+                })
+                .await?;
+            reorgs_dal
+                .prune_eth_watch_checkpoints(to_block.saturating_sub(L1_REORG_CHECK_WINDOW))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Checks whether L1 blocks processed by the watcher are still canonical. If they are not, rolls back
+    /// the watcher state to the most recent canonical block.
+    async fn check_for_l1_reorg(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+    ) -> Result<(), Error> {
+        if let Some(reorg) = storage.l1_reorgs_dal().get_last_fatal_l1_reorg().await? {
+            return Err(Error::FatalL1Reorg(reorg.reverted_to_l1_block));
+        }
+
+        let checkpoints = storage
+            .l1_reorgs_dal()
+            .get_eth_watch_checkpoints(L1_REORG_CHECK_WINDOW as usize)
+            .await?;
+        // Since a block hash commits to all previous blocks, it's sufficient to find the newest canonical checkpoint.
+        let mut canonical_checkpoint = None;
+        for (i, checkpoint) in checkpoints.iter().enumerate() {
+            let actual_hash = self.client.block_hash(checkpoint.number).await?;
+            if actual_hash == Some(checkpoint.hash) {
+                canonical_checkpoint = Some((i, checkpoint.number));
+                break;
+            }
+            tracing::warn!(
+                "L1 block #{} processed by eth_watch was reorged: expected hash {:?}, got {actual_hash:?}",
+                checkpoint.number,
+                checkpoint.hash
+            );
+        }
+
+        let (reverted_to_l1_block, is_deeper_than_window) = match canonical_checkpoint {
+            Some((0, _)) => return Ok(()),
+            None if checkpoints.is_empty() => return Ok(()),
+            Some((_, number)) => (number, false),
+            None => (checkpoints.last().unwrap().number.saturating_sub(1), true),
+        };
+        METRICS.l1_reorgs.inc();
+
+        let mut transaction = storage.start_transaction().await?;
+        let reverted_to = L1BlockNumber(reverted_to_l1_block as u32);
+        // Priority ops already loaded into the state keeper mempool cannot be evicted from it, and protocol versions
+        // cannot be removed from the storage, so reverting either of them requires manual intervention.
+        let loaded_priority_ops = transaction
+            .transactions_dal()
+            .count_loaded_priority_ops_after_l1_block(reverted_to)
+            .await?;
+        let reverted_upgrades = transaction
+            .protocol_versions_dal()
+            .count_upgrades_after_l1_block(reverted_to)
+            .await?;
+        let is_fatal = is_deeper_than_window || loaded_priority_ops > 0 || reverted_upgrades > 0;
+        transaction
+            .l1_reorgs_dal()
+            .insert_l1_reorg(&L1Reorg {
+                component: "eth_watch".to_owned(),
+                reverted_to_l1_block,
+                is_fatal,
+            })
+            .await?;
+        if is_fatal {
+            transaction.commit().await?;
+            tracing::error!(
+                "Fatal L1 reorg detected: reverting to L1 block #{reverted_to_l1_block} would affect \
+                 {loaded_priority_ops} priority ops loaded into mempool or executed and {reverted_upgrades} protocol upgrades \
+                 (reorg deeper than checked window: {is_deeper_than_window})"
+            );
+            return Err(Error::FatalL1Reorg(reverted_to_l1_block));
+        }
+
+        let removed_priority_ops = transaction
+            .transactions_dal()
+            .remove_pending_priority_ops_after_l1_block(reverted_to)
+            .await?;
+        transaction
+            .l1_reorgs_dal()
+            .delete_eth_watch_checkpoints_after(reverted_to_l1_block)
+            .await?;
+        transaction
+            .eth_watch_dal()
+            .rewind_processor_cursors(reverted_to_l1_block)
+            .await?;
+        transaction.commit().await?;
+        tracing::warn!(
+            "L1 reorg detected; reverted eth_watch to L1 block #{reverted_to_l1_block} removing {removed_priority_ops} pending priority ops"
+        );
+
+        self.last_processed_ethereum_block = reverted_to_l1_block;
+        for processor in &mut self.event_processors {
+            processor
+                .handle_l1_reorg(storage, reverted_to_l1_block)
+                .await?;
+        }
+        for processor in &mut self.extra_processors {
+            processor
+                .handle_l1_reorg(storage, reverted_to_l1_block)
+                .await?;
+        }
         Ok(())
     }
 }
@@ -206,7 +382,7 @@ pub async fn start_eth_watch(
 
     let eth_watch = EthWatch::new(
         diamond_proxy_addr,
-        Some(governance.0),
+        Some(governance),
         Box::new(eth_client),
         pool,
         config.poll_interval(),
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, convert::TryInto, sync::Arc};
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    sync::{Arc, Mutex},
+};
 
 use tokio::sync::RwLock;
 use zksync_contracts::{governance_contract, zksync_contract};
@@ -14,15 +18,22 @@ use zksync_types::{
 
 use super::client::Error;
 use crate::eth_watch::{
-    client::EthClient, event_processors::upgrades::UPGRADE_PROPOSAL_SIGNATURE, EthWatch,
+    client::EthClient,
+    event_processors::{upgrades::UPGRADE_PROPOSAL_SIGNATURE, EventProcessor},
+    EthWatch, ExtraEventProcessor,
 };
 
+const DIAMOND_PROXY_ADDR: Address = Address::repeat_byte(0x01);
+const GOVERNANCE_ADDR: Address = Address::repeat_byte(0x02);
+
 #[derive(Debug)]
 struct FakeEthClientData {
     transactions: HashMap<u64, Vec<Log>>,
     diamond_upgrades: HashMap<u64, Vec<Log>>,
     governance_upgrades: HashMap<u64, Vec<Log>>,
     last_finalized_block_number: u64,
+    /// Blocks starting from this number have hashes from an alternative fork.
+    fork_start: Option<u64>,
 }
 
 impl FakeEthClientData {
@@ -32,6 +43,7 @@ impl FakeEthClientData {
             diamond_upgrades: Default::default(),
             governance_upgrades: Default::default(),
             last_finalized_block_number: 0,
+            fork_start: None,
         }
     }
 
@@ -45,6 +57,16 @@ impl FakeEthClientData {
         }
     }
 
+    fn add_logs(&mut self, logs: &[Log]) {
+        for log in logs {
+            let eth_block = log.block_number.expect("no block number").as_u64();
+            self.transactions
+                .entry(eth_block)
+                .or_default()
+                .push(log.clone());
+        }
+    }
+
     fn add_diamond_upgrades(&mut self, upgrades: &[(ProtocolUpgrade, u64)]) {
         for (upgrade, eth_block) in upgrades {
             self.diamond_upgrades
@@ -66,6 +88,22 @@ impl FakeEthClientData {
     fn set_last_finalized_block_number(&mut self, number: u64) {
         self.last_finalized_block_number = number;
     }
+
+    fn reorg(&mut self, fork_start: u64) {
+        self.transactions.retain(|&number, _| number < fork_start);
+        self.diamond_upgrades
+            .retain(|&number, _| number < fork_start);
+        self.governance_upgrades
+            .retain(|&number, _| number < fork_start);
+        self.fork_start = Some(fork_start);
+    }
+
+    fn block_hash(&self, number: u64) -> H256 {
+        match self.fork_start {
+            Some(fork_start) if number >= fork_start => H256::from_low_u64_be(number + (1 << 32)),
+            _ => H256::from_low_u64_be(number),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +122,10 @@ impl FakeEthClient {
         self.inner.write().await.add_transactions(transactions);
     }
 
+    async fn add_logs(&mut self, logs: &[Log]) {
+        self.inner.write().await.add_logs(logs);
+    }
+
     async fn add_diamond_upgrades(&mut self, upgrades: &[(ProtocolUpgrade, u64)]) {
         self.inner.write().await.add_diamond_upgrades(upgrades);
     }
@@ -99,6 +141,11 @@ impl FakeEthClient {
             .set_last_finalized_block_number(number);
     }
 
+    /// Emulates an L1 reorg removing all events starting from the specified block.
+    async fn reorg(&mut self, fork_start: u64) {
+        self.inner.write().await.reorg(fork_start);
+    }
+
     async fn block_to_number(&self, block: BlockNumber) -> u64 {
         match block {
             BlockNumber::Earliest => 0,
@@ -138,6 +185,8 @@ impl EthClient for FakeEthClient {
 
     fn set_topics(&mut self, _topics: Vec<Hash>) {}
 
+    fn set_extra_addresses(&mut self, _addresses: Vec<Address>) {}
+
     async fn scheduler_vk_hash(&self, _verifier_address: Address) -> Result<H256, Error> {
         Ok(H256::zero())
     }
@@ -145,6 +194,10 @@ impl EthClient for FakeEthClient {
     async fn finalized_block_number(&self) -> Result<u64, Error> {
         Ok(self.inner.read().await.last_finalized_block_number)
     }
+
+    async fn block_hash(&self, number: u64) -> Result<Option<H256>, Error> {
+        Ok(Some(self.inner.read().await.block_hash(number)))
+    }
 }
 
 fn build_l1_tx(serial_id: u64, eth_block: u64) -> L1Tx {
@@ -207,7 +260,7 @@ async fn test_normal_operation_l1_txs() {
 
     let mut client = FakeEthClient::new();
     let mut watcher = EthWatch::new(
-        Address::default(),
+        DIAMOND_PROXY_ADDR,
         None,
         Box::new(client.clone()),
         connection_pool.clone(),
@@ -248,6 +301,275 @@ async fn test_normal_operation_l1_txs() {
     assert_eq!(db_tx.common_data.serial_id.0, 2);
 }
 
+#[tokio::test]
+async fn events_from_foreign_addresses_are_ignored() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        DIAMOND_PROXY_ADDR,
+        Some((governance_contract(), GOVERNANCE_ADDR)),
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    let foreign_address = Address::repeat_byte(0xff);
+    // A foreign contract emits an event with the priority op topic and a colliding serial ID.
+    let mut foreign_tx_log = tx_into_log(build_l1_tx(1, 12));
+    foreign_tx_log.address = foreign_address;
+    let mut foreign_upgrade_log = upgrade_into_diamond_proxy_log(
+        ProtocolUpgrade {
+            id: ProtocolVersionId::latest(),
+            tx: None,
+            ..Default::default()
+        },
+        12,
+    );
+    foreign_upgrade_log.address = foreign_address;
+    client
+        .add_logs(&[foreign_tx_log, foreign_upgrade_log])
+        .await;
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14)])
+        .await;
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let mut db_txs: Vec<L1Tx> = get_all_db_txs(&mut storage)
+        .await
+        .into_iter()
+        .map(|tx| tx.try_into().unwrap())
+        .collect();
+    db_txs.sort_by_key(|tx| tx.common_data.serial_id);
+    assert_eq!(db_txs.len(), 2);
+    assert_eq!(db_txs[1].common_data.serial_id, PriorityOpId(1));
+    assert_eq!(db_txs[1].common_data.eth_block, 14);
+
+    // Only the genesis version should be present.
+    let db_ids = storage.protocol_versions_dal().all_version_ids().await;
+    assert_eq!(db_ids.len(), 1);
+}
+
+#[tokio::test]
+async fn l1_reorg_reverts_pending_priority_ops() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        DIAMOND_PROXY_ADDR,
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14), build_l1_tx(2, 18)])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_all_db_txs(&mut storage).await.len(), 3);
+
+    // The last priority op gets included into a different L1 block.
+    client.reorg(16).await;
+    client.add_transactions(&[build_l1_tx(2, 19)]).await;
+    client.set_last_finalized_block_number(22).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let mut db_txs: Vec<L1Tx> = get_all_db_txs(&mut storage)
+        .await
+        .into_iter()
+        .map(|tx| tx.try_into().unwrap())
+        .collect();
+    db_txs.sort_by_key(|tx| tx.common_data.serial_id);
+    assert_eq!(db_txs.len(), 3);
+    assert_eq!(db_txs[2].common_data.serial_id.0, 2);
+    assert_eq!(db_txs[2].common_data.eth_block, 19);
+
+    let fatal_reorg = storage
+        .l1_reorgs_dal()
+        .get_last_fatal_l1_reorg()
+        .await
+        .unwrap();
+    assert_eq!(fatal_reorg, None);
+}
+
+#[tokio::test]
+async fn l1_reorg_deeper_than_checked_window_is_fatal() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        DIAMOND_PROXY_ADDR,
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client.add_transactions(&[build_l1_tx(0, 10)]).await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    client.reorg(5).await;
+    client.set_last_finalized_block_number(20).await;
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert!(matches!(err, Error::FatalL1Reorg(14)), "{err:?}");
+    // The watcher shouldn't process new blocks until the reorg is resolved manually.
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert!(matches!(err, Error::FatalL1Reorg(14)), "{err:?}");
+
+    let fatal_reorg = storage
+        .l1_reorgs_dal()
+        .get_last_fatal_l1_reorg()
+        .await
+        .unwrap()
+        .expect("no fatal reorg");
+    assert_eq!(fatal_reorg.component, "eth_watch");
+    assert_eq!(get_all_db_txs(&mut storage).await.len(), 1);
+}
+
+#[tokio::test]
+async fn l1_reorg_of_priority_op_loaded_into_mempool_is_fatal() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        DIAMOND_PROXY_ADDR,
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 18)])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    // Emulate the state keeper loading priority ops into its mempool.
+    let loaded_txs = storage
+        .transactions_dal()
+        .sync_mempool(&[], &[], 0, 0, 1000)
+        .await
+        .unwrap();
+    assert_eq!(loaded_txs.len(), 2);
+
+    client.reorg(16).await;
+    client.add_transactions(&[build_l1_tx(1, 19)]).await;
+    client.set_last_finalized_block_number(22).await;
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert!(matches!(err, Error::FatalL1Reorg(15)), "{err:?}");
+
+    // The loaded priority op must not be removed.
+    let db_txs: Vec<L1Tx> = get_all_db_txs(&mut storage)
+        .await
+        .into_iter()
+        .map(|tx| tx.try_into().unwrap())
+        .collect();
+    assert_eq!(db_txs.len(), 2);
+    assert!(db_txs
+        .iter()
+        .any(|tx| tx.common_data.serial_id.0 == 1 && tx.common_data.eth_block == 18));
+}
+
+#[tokio::test]
+async fn l1_reorg_of_protocol_upgrade_is_fatal() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        DIAMOND_PROXY_ADDR,
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    let upgrade = ProtocolUpgrade {
+        id: ProtocolVersionId::latest(),
+        tx: Some(build_upgrade_tx(ProtocolVersionId::latest(), 18)),
+        ..Default::default()
+    };
+    client.add_diamond_upgrades(&[(upgrade, 18)]).await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    client.reorg(16).await;
+    client.set_last_finalized_block_number(22).await;
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert!(matches!(err, Error::FatalL1Reorg(15)), "{err:?}");
+
+    let db_ids = storage.protocol_versions_dal().all_version_ids().await;
+    assert_eq!(db_ids.len(), 2);
+}
+
+#[tokio::test]
+async fn l1_reorg_reprocesses_upgrade_proposals_without_tx() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        DIAMOND_PROXY_ADDR,
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    let upgrade = ProtocolUpgrade {
+        id: ProtocolVersionId::latest(),
+        tx: None,
+        ..Default::default()
+    };
+    client.add_diamond_upgrades(&[(upgrade.clone(), 18)]).await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    // The same upgrade proposal gets included into a different L1 block.
+    client.reorg(16).await;
+    client.add_diamond_upgrades(&[(upgrade, 19)]).await;
+    client.set_last_finalized_block_number(22).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let db_ids = storage.protocol_versions_dal().all_version_ids().await;
+    assert_eq!(db_ids.len(), 2);
+    assert_eq!(db_ids[1], ProtocolVersionId::latest());
+    let fatal_reorg = storage
+        .l1_reorgs_dal()
+        .get_last_fatal_l1_reorg()
+        .await
+        .unwrap();
+    assert_eq!(fatal_reorg, None);
+}
+
 #[tokio::test]
 async fn test_normal_operation_upgrades() {
     let connection_pool = ConnectionPool::<Core>::test_pool().await;
@@ -255,7 +577,7 @@ async fn test_normal_operation_upgrades() {
 
     let mut client = FakeEthClient::new();
     let mut watcher = EthWatch::new(
-        Address::default(),
+        DIAMOND_PROXY_ADDR,
         None,
         Box::new(client.clone()),
         connection_pool.clone(),
@@ -316,7 +638,7 @@ async fn test_gap_in_upgrades() {
 
     let mut client = FakeEthClient::new();
     let mut watcher = EthWatch::new(
-        Address::default(),
+        DIAMOND_PROXY_ADDR,
         None,
         Box::new(client.clone()),
         connection_pool.clone(),
@@ -355,8 +677,8 @@ async fn test_normal_operation_governance_upgrades() {
 
     let mut client = FakeEthClient::new();
     let mut watcher = EthWatch::new(
-        Address::default(),
-        Some(governance_contract()),
+        DIAMOND_PROXY_ADDR,
+        Some((governance_contract(), GOVERNANCE_ADDR)),
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
@@ -417,7 +739,7 @@ async fn test_gap_in_single_batch() {
 
     let mut client = FakeEthClient::new();
     let mut watcher = EthWatch::new(
-        Address::default(),
+        DIAMOND_PROXY_ADDR,
         None,
         Box::new(client.clone()),
         connection_pool.clone(),
@@ -447,7 +769,7 @@ async fn test_gap_between_batches() {
 
     let mut client = FakeEthClient::new();
     let mut watcher = EthWatch::new(
-        Address::default(),
+        DIAMOND_PROXY_ADDR,
         None,
         Box::new(client.clone()),
         connection_pool.clone(),
@@ -482,7 +804,7 @@ async fn test_overlapping_batches() {
 
     let mut client = FakeEthClient::new();
     let mut watcher = EthWatch::new(
-        Address::default(),
+        DIAMOND_PROXY_ADDR,
         None,
         Box::new(client.clone()),
         connection_pool.clone(),
@@ -523,13 +845,148 @@ async fn test_overlapping_batches() {
     assert_eq!(tx.common_data.serial_id.0, 4);
 }
 
+/// Event processor recording L1 blocks of priority op events passed to it.
+#[derive(Debug, Clone, Default)]
+struct RecordingEventProcessor {
+    l1_blocks: Arc<Mutex<Vec<u64>>>,
+}
+
+impl RecordingEventProcessor {
+    fn l1_blocks(&self) -> Vec<u64> {
+        self.l1_blocks.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl EventProcessor for RecordingEventProcessor {
+    async fn process_events(
+        &mut self,
+        _storage: &mut Connection<'_, Core>,
+        _client: &dyn EthClient,
+        events: Vec<Log>,
+    ) -> Result<(), Error> {
+        let l1_blocks = events
+            .iter()
+            .map(|event| event.block_number.unwrap().as_u64());
+        self.l1_blocks.lock().unwrap().extend(l1_blocks);
+        Ok(())
+    }
+
+    fn relevant_topic(&self) -> H256 {
+        zksync_contract()
+            .event("NewPriorityRequest")
+            .unwrap()
+            .signature()
+    }
+}
+
+#[tokio::test]
+async fn extra_processor_is_backfilled_and_resumed() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        DIAMOND_PROXY_ADDR,
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14), build_l1_tx(2, 18)])
+        .await;
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    // Add an extra processor to the running chain, backfilling it from the L1 block preceding the first priority op.
+    let processor = RecordingEventProcessor::default();
+    let mut watcher = EthWatch::new(
+        DIAMOND_PROXY_ADDR,
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+    watcher
+        .register_processor(
+            ExtraEventProcessor::new("recording", Box::new(processor.clone()))
+                .with_backfill_from(5),
+        )
+        .unwrap();
+    client.set_last_finalized_block_number(25).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    assert_eq!(processor.l1_blocks(), [10, 14, 18]);
+    let cursor = storage
+        .eth_watch_dal()
+        .get_processor_cursor("recording")
+        .await
+        .unwrap();
+    assert_eq!(cursor, Some(25));
+
+    // After a restart, the processor should resume from the persisted cursor ignoring the backfill setting.
+    let mut watcher = EthWatch::new(
+        DIAMOND_PROXY_ADDR,
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+    watcher
+        .register_processor(
+            ExtraEventProcessor::new("recording", Box::new(processor.clone()))
+                .with_backfill_from(5),
+        )
+        .unwrap();
+    client.add_transactions(&[build_l1_tx(3, 27)]).await;
+    client.set_last_finalized_block_number(30).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    assert_eq!(processor.l1_blocks(), [10, 14, 18, 27]);
+    assert_eq!(get_all_db_txs(&mut storage).await.len(), 4);
+}
+
+#[tokio::test]
+async fn extra_processors_must_have_unique_names() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut watcher = EthWatch::new(
+        DIAMOND_PROXY_ADDR,
+        None,
+        Box::new(FakeEthClient::new()),
+        connection_pool,
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+    let processor = RecordingEventProcessor::default();
+    watcher
+        .register_processor(ExtraEventProcessor::new(
+            "recording",
+            Box::new(processor.clone()),
+        ))
+        .unwrap();
+    let err = watcher
+        .register_processor(ExtraEventProcessor::new("recording", Box::new(processor)))
+        .unwrap_err();
+    assert!(err.to_string().contains("already registered"), "{err}");
+}
+
 async fn get_all_db_txs(storage: &mut Connection<'_, Core>) -> Vec<Transaction> {
     storage.transactions_dal().reset_mempool().await.unwrap();
-    storage
+    let txs = storage
         .transactions_dal()
         .sync_mempool(&[], &[], 0, 0, 1000)
         .await
-        .unwrap()
+        .unwrap();
+    // Loading transactions into the mempool affects L1 reorg handling, so we reset the mempool again.
+    storage.transactions_dal().reset_mempool().await.unwrap();
+    txs
 }
 
 fn tx_into_log(tx: L1Tx) -> Log {
@@ -568,7 +1025,7 @@ fn tx_into_log(tx: L1Tx) -> Log {
     ]);
 
     Log {
-        address: Address::repeat_byte(0x1),
+        address: DIAMOND_PROXY_ADDR,
         topics: vec![zksync_contract()
             .event("NewPriorityRequest")
             .expect("NewPriorityRequest event is missing in abi")
@@ -589,7 +1046,7 @@ fn upgrade_into_diamond_proxy_log(upgrade: ProtocolUpgrade, eth_block: u64) -> L
     let diamond_cut = upgrade_into_diamond_cut(upgrade);
     let data = encode(&[diamond_cut, Token::FixedBytes(vec![0u8; 32])]);
     Log {
-        address: Address::repeat_byte(0x1),
+        address: DIAMOND_PROXY_ADDR,
         topics: vec![UPGRADE_PROPOSAL_SIGNATURE],
         data: data.into(),
         block_hash: Some(H256::repeat_byte(0x11)),
@@ -615,7 +1072,7 @@ fn upgrade_into_governor_log(upgrade: ProtocolUpgrade, eth_block: u64) -> Log {
         .chain(encode(&[diamond_cut]))
         .collect();
     let governance_call = Token::Tuple(vec![
-        Token::Address(Default::default()),
+        Token::Address(DIAMOND_PROXY_ADDR),
         Token::Uint(U256::default()),
         Token::Bytes(diamond_upgrade_calldata),
     ]);
@@ -627,7 +1084,7 @@ fn upgrade_into_governor_log(upgrade: ProtocolUpgrade, eth_block: u64) -> Log {
     let final_data = encode(&[Token::FixedBytes(vec![0u8; 32]), governance_operation]);
 
     Log {
-        address: Address::repeat_byte(0x1),
+        address: GOVERNANCE_ADDR,
         topics: vec![
             governance_contract()
                 .event("TransparentOperationScheduled")
@@ -0,0 +1,451 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::Serialize;
+use vise::{Buckets, Gauge, Histogram, Metrics};
+use zksync_circuit_breaker::priority_ops::{unix_timestamp, L1PriorityQueueFront};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_eth_client::EthInterface;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{
+    ethabi,
+    web3::types::{BlockId, BlockNumber},
+    Address, L1BlockNumber, PriorityOpId,
+};
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Buckets for the L2 inclusion latency of priority operations: 1 second to ~3 days.
+const INCLUSION_LATENCY_BUCKETS: Buckets = Buckets::exponential(1.0..=262_144.0, 4.0);
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_priority_queue")]
+struct PriorityQueueMetrics {
+    /// Number of priority operations not yet included into a miniblock.
+    depth: Gauge<u64>,
+    /// Age of the oldest priority operation not yet included into a miniblock based on the timestamp of its L1 block.
+    oldest_op_age: Gauge<Duration>,
+    /// Number of priority operations in the L1 queue, i.e. not yet processed by L1 batches executed on L1.
+    l1_queue_size: Gauge<u64>,
+    /// Time left until the oldest priority operation in the L1 queue expires, as enforced by L1 contracts.
+    time_to_deadline: Gauge<Duration>,
+    /// Latency between creating a priority operation on L1 and including it into a miniblock.
+    #[metrics(buckets = INCLUSION_LATENCY_BUCKETS)]
+    inclusion_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<PriorityQueueMetrics> = vise::Global::new();
+
+#[derive(Debug, Serialize)]
+struct PriorityQueueHealthDetails {
+    depth: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oldest_op_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oldest_op_l1_block: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oldest_op_age_sec: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_included_op_inclusion_latency_sec: Option<u64>,
+    l1_queue_size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    l1_front_op_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seconds_to_deadline: Option<u64>,
+}
+
+/// Reports the depth of the priority queue, the latency of including each priority operation into a miniblock
+/// relative to its L1 block, and how close the oldest priority operation in the L1 queue is to its deadline
+/// enforced by L1 contracts. The health check becomes affected once the operation gets within the configured
+/// margin from the deadline.
+#[derive(Debug)]
+pub struct PriorityQueueMonitor {
+    polling_interval_ms: u64,
+    connection_pool: ConnectionPool<Core>,
+    eth_client: Arc<dyn EthInterface>,
+    diamond_proxy_addr: Address,
+    diamond_proxy_abi: ethabi::Contract,
+    deadline_margin: Duration,
+    health_updater: HealthUpdater,
+    /// ID of the next priority operation to check for inclusion into a miniblock. Initialized on the first iteration.
+    next_op_to_check: Option<PriorityOpId>,
+    l1_block_timestamps: BTreeMap<L1BlockNumber, u64>,
+    last_inclusion_latency: Option<Duration>,
+}
+
+impl PriorityQueueMonitor {
+    /// Maximum number of included priority operations processed in a single iteration.
+    const MAX_INCLUDED_OPS_PER_ITERATION: usize = 1_000;
+
+    pub fn new(
+        polling_interval_ms: u64,
+        connection_pool: ConnectionPool<Core>,
+        eth_client: Arc<dyn EthInterface>,
+        diamond_proxy_addr: Address,
+        diamond_proxy_abi: ethabi::Contract,
+        deadline_margin: Duration,
+    ) -> Self {
+        Self {
+            polling_interval_ms,
+            connection_pool,
+            eth_client,
+            diamond_proxy_addr,
+            diamond_proxy_abi,
+            deadline_margin,
+            health_updater: ReactiveHealthCheck::new("priority_queue").1,
+            next_op_to_check: None,
+            l1_block_timestamps: BTreeMap::new(),
+            last_inclusion_latency: None,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    async fn l1_block_timestamp(&mut self, number: L1BlockNumber) -> anyhow::Result<u64> {
+        if let Some(&timestamp) = self.l1_block_timestamps.get(&number) {
+            return Ok(timestamp);
+        }
+        let block_id = BlockId::Number(BlockNumber::Number(number.0.into()));
+        let block = self
+            .eth_client
+            .block(block_id, "house_keeper")
+            .await
+            .with_context(|| format!("cannot get L1 block #{number}"))?
+            .with_context(|| format!("L1 block #{number} is missing"))?;
+        let timestamp = block.timestamp.as_u64();
+        self.l1_block_timestamps.insert(number, timestamp);
+        Ok(timestamp)
+    }
+
+    /// Observes the inclusion latency of priority operations included into miniblocks since the previous iteration.
+    async fn check_included_ops(&mut self) -> anyhow::Result<()> {
+        let mut storage = self
+            .connection_pool
+            .connection_tagged("house_keeper")
+            .await?;
+        let next_op_to_check = match self.next_op_to_check {
+            Some(op_id) => op_id,
+            // Do not report latencies for operations included before the monitor has started.
+            None => storage.transactions_dal().next_priority_id().await,
+        };
+        let included_ops = storage
+            .transactions_dal()
+            .get_included_priority_ops(next_op_to_check, Self::MAX_INCLUDED_OPS_PER_ITERATION)
+            .await
+            .context("cannot get included priority ops")?;
+        drop(storage);
+        self.next_op_to_check = Some(next_op_to_check);
+
+        for op in included_ops {
+            let l1_timestamp = self.l1_block_timestamp(op.l1_block).await?;
+            let latency = Duration::from_secs(op.miniblock_timestamp.saturating_sub(l1_timestamp));
+            tracing::debug!(
+                "Priority operation #{} from L1 block #{} is included into a miniblock in {latency:?}",
+                op.id.0,
+                op.l1_block
+            );
+            METRICS.inclusion_latency.observe(latency);
+            self.last_inclusion_latency = Some(latency);
+            self.next_op_to_check = Some(PriorityOpId(op.id.0 + 1));
+            // Priority operations are created in the order of L1 blocks, so older blocks are no longer needed.
+            self.l1_block_timestamps = self.l1_block_timestamps.split_off(&op.l1_block);
+        }
+        Ok(())
+    }
+
+    async fn report(&mut self) -> anyhow::Result<()> {
+        self.check_included_ops().await?;
+
+        let mut storage = self
+            .connection_pool
+            .connection_tagged("house_keeper")
+            .await?;
+        let queue_info = storage
+            .transactions_dal()
+            .get_priority_queue_info()
+            .await
+            .context("cannot get priority queue info")?;
+        drop(storage);
+
+        METRICS.depth.set(queue_info.depth);
+        let mut details = PriorityQueueHealthDetails {
+            depth: queue_info.depth,
+            oldest_op_id: queue_info.oldest_op_id.map(|id| id.0),
+            oldest_op_l1_block: queue_info.oldest_op_l1_block.map(|number| number.0),
+            oldest_op_age_sec: None,
+            last_included_op_inclusion_latency_sec: self
+                .last_inclusion_latency
+                .map(|latency| latency.as_secs()),
+            l1_queue_size: 0,
+            l1_front_op_id: None,
+            seconds_to_deadline: None,
+        };
+
+        let now = unix_timestamp();
+        if let Some(l1_block) = queue_info.oldest_op_l1_block {
+            let l1_timestamp = self.l1_block_timestamp(l1_block).await?;
+            let age = Duration::from_secs(now.saturating_sub(l1_timestamp));
+            METRICS.oldest_op_age.set(age);
+            details.oldest_op_age_sec = Some(age.as_secs());
+        } else {
+            METRICS.oldest_op_age.set(Duration::ZERO);
+        }
+
+        let front_op = L1PriorityQueueFront::load(
+            self.eth_client.as_ref(),
+            self.diamond_proxy_addr,
+            &self.diamond_proxy_abi,
+        )
+        .await?;
+        let mut health_status = HealthStatus::Ready;
+        if let Some(front_op) = front_op {
+            let time_to_deadline = front_op.time_to_deadline(now);
+            METRICS.l1_queue_size.set(front_op.queue_size);
+            METRICS.time_to_deadline.set(time_to_deadline);
+            details.l1_queue_size = front_op.queue_size;
+            details.l1_front_op_id = Some(front_op.id.0);
+            details.seconds_to_deadline = Some(time_to_deadline.as_secs());
+            if time_to_deadline < self.deadline_margin {
+                tracing::warn!(
+                    "Priority operation #{} is {time_to_deadline:?} away from its deadline on L1",
+                    front_op.id.0
+                );
+                health_status = HealthStatus::Affected;
+            }
+        } else {
+            METRICS.l1_queue_size.set(0);
+        }
+
+        let health = Health::from(health_status).with_details(details);
+        self.health_updater.update(health);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for PriorityQueueMonitor {
+    const SERVICE_NAME: &'static str = "PriorityQueueMonitor";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        // L1 client errors are transient; they shouldn't bring the house keeper down.
+        if let Err(err) = self.report().await {
+            tracing::warn!("Failed reporting priority queue status: {err:#}");
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.polling_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_eth_client::clients::MockEthereum;
+    use zksync_health_check::CheckHealth;
+    use zksync_types::{
+        block::MiniblockHeader,
+        ethabi::Token,
+        l1::{L1Tx, L1TxCommonData},
+        Execute, L2ChainId, H256,
+    };
+
+    use super::*;
+    use crate::{
+        genesis::{ensure_genesis_state, GenesisParams},
+        utils::testonly::{create_l2_transaction, create_miniblock, execute_l2_transaction},
+    };
+
+    const DIAMOND_PROXY_ADDR: Address = Address::repeat_byte(0x01);
+
+    fn create_l1_tx(serial_id: u64, l1_block: u32) -> L1Tx {
+        L1Tx {
+            execute: Execute {
+                contract_address: Address::repeat_byte(0x11),
+                calldata: vec![1, 2, 3],
+                factory_deps: None,
+                value: 0.into(),
+            },
+            common_data: L1TxCommonData {
+                serial_id: PriorityOpId(serial_id),
+                eth_block: l1_block.into(),
+                canonical_tx_hash: H256::from_low_u64_be(serial_id + 1),
+                ..L1TxCommonData::default()
+            },
+            received_timestamp_ms: 0,
+        }
+    }
+
+    fn mock_eth_client(
+        l1_block_timestamps: Vec<u64>,
+        l1_queue_size: u64,
+        expiration_timestamp: u64,
+    ) -> MockEthereum {
+        MockEthereum::default()
+            .with_block_timestamp_history(l1_block_timestamps)
+            .with_call_handler(move |call| {
+                assert_eq!(call.contract_address(), DIAMOND_PROXY_ADDR);
+                match call.function_name() {
+                    "getPriorityQueueSize" => Token::Uint(l1_queue_size.into()),
+                    "getFirstUnprocessedPriorityTx" => Token::Uint(0.into()),
+                    "priorityQueueFrontOperation" => Token::Tuple(vec![
+                        Token::FixedBytes(H256::from_low_u64_be(1).0.to_vec()),
+                        Token::Uint(expiration_timestamp.into()),
+                        Token::Uint(0.into()),
+                    ]),
+                    name => panic!("unexpected call: {name}"),
+                }
+            })
+    }
+
+    fn create_monitor(
+        pool: ConnectionPool<Core>,
+        eth_client: MockEthereum,
+    ) -> PriorityQueueMonitor {
+        PriorityQueueMonitor::new(
+            100,
+            pool,
+            Arc::new(eth_client),
+            DIAMOND_PROXY_ADDR,
+            // The ABI is not used by the mock client.
+            ethabi::Contract::load(&b"[]"[..]).unwrap(),
+            Duration::from_secs(3_600),
+        )
+    }
+
+    async fn prepare_storage(pool: &ConnectionPool<Core>, txs: &[L1Tx]) {
+        let mut storage = pool.connection().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        for tx in txs {
+            let l1_block = L1BlockNumber(tx.common_data.eth_block as u32);
+            storage
+                .transactions_dal()
+                .insert_transaction_l1(tx.clone(), l1_block)
+                .await;
+        }
+    }
+
+    async fn include_txs(pool: &ConnectionPool<Core>, miniblock: MiniblockHeader, txs: &[L1Tx]) {
+        let mut storage = pool.connection().await.unwrap();
+        let miniblock_number = miniblock.number;
+        storage
+            .blocks_dal()
+            .insert_miniblock(&miniblock)
+            .await
+            .unwrap();
+        let tx_results: Vec<_> = txs
+            .iter()
+            .map(|tx| {
+                let mut result = execute_l2_transaction(create_l2_transaction(10, 100));
+                result.hash = tx.hash();
+                result.transaction = tx.clone().into();
+                result
+            })
+            .collect();
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_miniblock(miniblock_number, &tx_results, 1.into())
+            .await;
+    }
+
+    async fn health_details(monitor: &PriorityQueueMonitor) -> (HealthStatus, serde_json::Value) {
+        let health = monitor.health_check().check_health().await;
+        let details = serde_json::to_value(&health).unwrap()["details"].clone();
+        (health.status(), details)
+    }
+
+    #[tokio::test]
+    async fn monitoring_priority_queue() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let now = unix_timestamp();
+        // L1 blocks #0, #1 and #2 are mined 1 hour, 30 minutes and 10 minutes ago, respectively.
+        let l1_block_timestamps = vec![now - 3_600, now - 1_800, now - 600];
+        let txs: Vec<_> = (0..3).map(|id| create_l1_tx(id, id as u32)).collect();
+        prepare_storage(&pool, &txs).await;
+        let eth_client = mock_eth_client(l1_block_timestamps, 3, now + 7_200);
+        let mut monitor = create_monitor(pool.clone(), eth_client);
+
+        monitor.report().await.unwrap();
+        assert_eq!(monitor.next_op_to_check, Some(PriorityOpId(0)));
+        let (status, details) = health_details(&monitor).await;
+        assert_matches!(status, HealthStatus::Ready);
+        assert_eq!(details["depth"], 3);
+        assert_eq!(details["oldest_op_id"], 0);
+        assert_eq!(details["oldest_op_l1_block"], 0);
+        assert!(details["oldest_op_age_sec"].as_u64().unwrap() >= 3_600);
+        assert_eq!(details["l1_queue_size"], 3);
+        assert_eq!(details["l1_front_op_id"], 0);
+        assert!(details["seconds_to_deadline"].as_u64().unwrap() <= 7_200);
+
+        // Include the first 2 operations into a miniblock mined 20 minutes ago.
+        let miniblock = MiniblockHeader {
+            timestamp: now - 1_200,
+            l1_tx_count: 2,
+            ..create_miniblock(1)
+        };
+        include_txs(&pool, miniblock, &txs[..2]).await;
+
+        monitor.report().await.unwrap();
+        assert_eq!(monitor.next_op_to_check, Some(PriorityOpId(2)));
+        // The latency is tracked for each included operation rather than only for the oldest one.
+        assert_eq!(
+            monitor.last_inclusion_latency,
+            Some(Duration::from_secs(600))
+        );
+        let (status, details) = health_details(&monitor).await;
+        assert_matches!(status, HealthStatus::Ready);
+        assert_eq!(details["depth"], 1);
+        assert_eq!(details["oldest_op_id"], 2);
+        assert_eq!(details["oldest_op_l1_block"], 2);
+        assert_eq!(details["last_included_op_inclusion_latency_sec"], 600);
+    }
+
+    #[tokio::test]
+    async fn priority_op_near_deadline_affects_health() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let now = unix_timestamp();
+        let txs = [create_l1_tx(0, 0)];
+        prepare_storage(&pool, &txs).await;
+        // The deadline is taken from the L1 contract, regardless of the L1 block timestamp.
+        let eth_client = mock_eth_client(vec![now - 60], 1, now + 600);
+        let mut monitor = create_monitor(pool, eth_client);
+
+        monitor.report().await.unwrap();
+        let (status, details) = health_details(&monitor).await;
+        assert_matches!(status, HealthStatus::Affected);
+        assert_eq!(details["l1_front_op_id"], 0);
+        assert!(details["seconds_to_deadline"].as_u64().unwrap() <= 600);
+    }
+
+    #[tokio::test]
+    async fn monitor_does_not_report_ops_included_before_start() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let now = unix_timestamp();
+        let txs: Vec<_> = (0..2).map(|id| create_l1_tx(id, 0)).collect();
+        prepare_storage(&pool, &txs).await;
+        let miniblock = MiniblockHeader {
+            timestamp: now,
+            l1_tx_count: 2,
+            ..create_miniblock(1)
+        };
+        include_txs(&pool, miniblock, &txs).await;
+        let eth_client = mock_eth_client(vec![now - 60], 0, 0);
+        let mut monitor = create_monitor(pool, eth_client);
+
+        monitor.report().await.unwrap();
+        assert_eq!(monitor.next_op_to_check, Some(PriorityOpId(2)));
+        assert_eq!(monitor.last_inclusion_latency, None);
+        let (status, details) = health_details(&monitor).await;
+        assert_matches!(status, HealthStatus::Ready);
+        assert_eq!(details["depth"], 0);
+        assert_eq!(details["l1_queue_size"], 0);
+        assert!(details.get("seconds_to_deadline").is_none());
+    }
+}
@@ -13,7 +13,7 @@ use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{U256, U64};
 
 use self::metrics::METRICS;
-use super::L1TxParamsProvider;
+use super::{L1FeeStatisticsProvider, L1TxParamsProvider};
 use crate::state_keeper::metrics::KEEPER_METRICS;
 
 mod metrics;
@@ -203,6 +203,8 @@ impl GasAdjuster {
             PubdataSendingMode::Calldata => {
                 self.estimate_effective_gas_price() * L1_GAS_PER_PUBDATA_BYTE as u64
             }
+            // Pubdata isn't published on L1 in this mode; the cost of the external DA layer isn't accounted for.
+            PubdataSendingMode::Custom => 0,
         }
     }
 
@@ -320,6 +322,24 @@ impl L1TxParamsProvider for GasAdjuster {
     }
 }
 
+impl L1FeeStatisticsProvider for GasAdjuster {
+    fn current_base_fee(&self) -> u64 {
+        self.base_fee_statistics.last_added_value()
+    }
+
+    fn base_fee_percentile(&self, percentile: f64) -> u64 {
+        self.base_fee_statistics.percentile(percentile)
+    }
+
+    fn current_blob_base_fee(&self) -> U256 {
+        self.blob_base_fee_statistics.last_added_value()
+    }
+
+    fn blob_base_fee_percentile(&self, percentile: f64) -> U256 {
+        self.blob_base_fee_statistics.percentile(percentile)
+    }
+}
+
 /// Helper structure responsible for collecting the data about recent transactions,
 /// calculating the median base fee.
 #[derive(Debug, Clone, Default)]
@@ -355,6 +375,17 @@ impl<T: Ord + Copy + Default> GasStatisticsInner<T> {
         self.samples.back().copied().unwrap_or(self.median_cached)
     }
 
+    /// Returns the specified percentile (0..=100) of the collected samples using the nearest-rank method.
+    fn percentile(&self, percentile: f64) -> T {
+        if self.samples.is_empty() {
+            return self.median_cached;
+        }
+        let mut samples: Vec<_> = self.samples.iter().copied().collect();
+        let rank = (samples.len() - 1) as f64 * percentile.clamp(0.0, 100.0) / 100.0;
+        let (_, &mut value, _) = samples.select_nth_unstable(rank.round() as usize);
+        value
+    }
+
     fn add_samples(&mut self, fees: &[T]) {
         self.samples.extend(fees);
         self.last_processed_block += fees.len();
@@ -391,6 +422,10 @@ impl<T: Ord + Copy + Default> GasStatistics<T> {
         self.0.read().unwrap().last_added_value()
     }
 
+    pub fn percentile(&self, percentile: f64) -> T {
+        self.0.read().unwrap().percentile(percentile)
+    }
+
     pub fn add_samples(&self, fees: &[T]) {
         self.0.write().unwrap().add_samples(fees)
     }
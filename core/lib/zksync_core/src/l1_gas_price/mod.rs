@@ -2,6 +2,8 @@
 
 use std::fmt;
 
+use zksync_types::U256;
+
 pub use gas_adjuster::GasAdjuster;
 pub use main_node_fetcher::MainNodeFeeParamsFetcher;
 pub use singleton::GasAdjusterSingleton;
@@ -27,3 +29,19 @@ pub trait L1TxParamsProvider: fmt::Debug + 'static + Send + Sync {
     /// Returns a lower bound for the `base_fee` value for the next L1 block.
     fn get_next_block_minimal_base_fee(&self) -> u64;
 }
+
+/// Abstraction that provides statistics on fees in recent L1 blocks. Can be used to schedule sending L1 transactions
+/// to the periods of lower fees.
+pub trait L1FeeStatisticsProvider: fmt::Debug + 'static + Send + Sync {
+    /// Returns the base fee in the latest observed L1 block.
+    fn current_base_fee(&self) -> u64;
+
+    /// Returns the specified percentile (0..=100) of base fees in recent L1 blocks.
+    fn base_fee_percentile(&self, percentile: f64) -> u64;
+
+    /// Returns the blob base fee in the latest observed L1 block.
+    fn current_blob_base_fee(&self) -> U256;
+
+    /// Returns the specified percentile (0..=100) of blob base fees in recent L1 blocks.
+    fn blob_base_fee_percentile(&self, percentile: f64) -> U256;
+}
@@ -5,8 +5,8 @@ use tokio::{
     sync::{watch, OnceCell},
     task::JoinHandle,
 };
-use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
-use zksync_eth_client::clients::QueryClient;
+use zksync_config::{configs::eth_sender::PubdataSendingMode, ETHClientConfig, GasAdjusterConfig};
+use zksync_eth_client::clients::FallbackEthClient;
 
 use crate::l1_gas_price::GasAdjuster;
 
@@ -14,7 +14,7 @@ use crate::l1_gas_price::GasAdjuster;
 /// This is needed only for running the server.
 #[derive(Debug)]
 pub struct GasAdjusterSingleton {
-    web3_url: String,
+    eth_client_config: ETHClientConfig,
     gas_adjuster_config: GasAdjusterConfig,
     pubdata_sending_mode: PubdataSendingMode,
     singleton: OnceCell<Result<Arc<GasAdjuster>, Error>>,
@@ -32,12 +32,12 @@ impl From<anyhow::Error> for Error {
 
 impl GasAdjusterSingleton {
     pub fn new(
-        web3_url: String,
+        eth_client_config: ETHClientConfig,
         gas_adjuster_config: GasAdjusterConfig,
         pubdata_sending_mode: PubdataSendingMode,
     ) -> Self {
         Self {
-            web3_url,
+            eth_client_config,
             gas_adjuster_config,
             pubdata_sending_mode,
             singleton: OnceCell::new(),
@@ -48,10 +48,10 @@ impl GasAdjusterSingleton {
         let adjuster = self
             .singleton
             .get_or_init(|| async {
-                let query_client =
-                    QueryClient::new(&self.web3_url).context("QueryClient::new()")?;
+                let query_client = FallbackEthClient::from_config(&self.eth_client_config)
+                    .context("FallbackEthClient::from_config()")?;
                 let adjuster = GasAdjuster::new(
-                    Arc::new(query_client.clone()),
+                    Arc::new(query_client),
                     self.gas_adjuster_config,
                     self.pubdata_sending_mode,
                 )
@@ -0,0 +1,786 @@
+//! Recovery of the node state from data published on L1.
+//!
+//! [`L1StateRecovery`] scans `BlockCommit` events emitted by the diamond proxy contract, loads the corresponding
+//! commit transactions and parses pubdata (from calldata or, if a [`BlobProvider`] is configured, from blobs)
+//! for each executed L1 batch. State diffs from pubdata are applied to a Merkle tree, and the resulting root hash
+//! and leaf count are checked against the values committed on L1. Initial writes and factory deps are written
+//! to the regular node tables; recovered storage logs are kept in a dedicated table (see below).
+//!
+//! # Limitations
+//!
+//! Pubdata only contains hashed storage keys, so the original `(address, key)` pairs cannot be recovered.
+//! Since `storage_logs` requires key preimages, recovered storage logs are stored in the `l1_recovery_storage_logs`
+//! table, and Postgres is *not* converted to a state a node can be started from. The result of recovery is the Merkle
+//! tree and storage values verified against L1 commitments. Transaction history, events and L2 blocks
+//! are not published on L1 and are not recovered either. Pre-boojum L1 batches use a different pubdata format
+//! and are not supported.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use tokio::sync::watch;
+use zksync_dal::{
+    l1_recovery_dal::{RecoveredL1Batch, RecoveredStorageEntry},
+    Connection, ConnectionPool, Core, CoreDal,
+};
+use zksync_eth_client::{CallFunctionArgs, EthInterface};
+use zksync_l1_contract_interface::i_executor::commit::kzg::{
+    blob_to_zksync_pubdata, KzgInfo, ZK_SYNC_BYTES_PER_BLOB,
+};
+use zksync_merkle_tree::{MerkleTree, RocksDBWrapper, TreeEntry, TreeInstruction};
+use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_system_constants::{
+    SYSTEM_CONTEXT_ADDRESS, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+};
+use zksync_types::{
+    block::unpack_block_info,
+    eth_sender::L1BatchBlobs,
+    web3::{
+        contract::tokens::Detokenize,
+        ethabi,
+        types::{BlockNumber, FilterBuilder},
+    },
+    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey, H256, U256,
+};
+use zksync_utils::{bytecode::hash_bytecode, h256_to_u256, u256_to_h256};
+
+use self::pubdata::{Pubdata, StateDiffs};
+use crate::{consistency_checker::ConsistencyChecker, metadata_calculator::L1BatchWithLogs};
+
+mod pubdata;
+#[cfg(test)]
+mod tests;
+
+/// Pubdata source byte used by L1 contracts for pubdata published in calldata.
+const PUBDATA_SOURCE_CALLDATA: u8 = 0;
+/// Pubdata source byte used by L1 contracts for pubdata published in blobs.
+const PUBDATA_SOURCE_BLOBS: u8 = 1;
+/// Size of a pubdata commitment for a single blob: opening point (16 bytes) || claimed value (32 bytes)
+/// || KZG commitment (48 bytes) || opening proof (48 bytes).
+const BYTES_PER_PUBDATA_COMMITMENT: usize = 144;
+/// Size of the blob commitment appended to pubdata published in calldata.
+const BYTES_PER_BLOB_COMMITMENT: usize = 32;
+
+/// Provider of blobs referenced by L1 batch commitments. Blobs are not available via the regular L1 JSON-RPC API,
+/// so they must be obtained from a beacon node, a blob archive etc.
+#[async_trait]
+pub trait BlobProvider: fmt::Debug + Send + Sync {
+    /// Returns the blob with the specified KZG commitment published for the specified L1 batch in the zkSync format
+    /// (i.e., as `ZK_SYNC_BYTES_PER_BLOB` bytes of pubdata, before conversion to the EIP-4844 format),
+    /// or `None` if the blob is not available.
+    async fn get_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        kzg_commitment: &[u8],
+    ) -> anyhow::Result<Option<Vec<u8>>>;
+}
+
+/// [`BlobProvider`] taking blobs from the object store populated by the blob archiver.
+#[derive(Debug)]
+pub struct ObjectStoreBlobProvider {
+    blob_store: Arc<dyn ObjectStore>,
+}
+
+impl ObjectStoreBlobProvider {
+    pub fn new(blob_store: Arc<dyn ObjectStore>) -> Self {
+        Self { blob_store }
+    }
+}
+
+#[async_trait]
+impl BlobProvider for ObjectStoreBlobProvider {
+    async fn get_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        kzg_commitment: &[u8],
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let blobs: L1BatchBlobs = match self.blob_store.get(l1_batch_number).await {
+            Ok(blobs) => blobs,
+            Err(ObjectStoreError::KeyNotFound(_)) => return Ok(None),
+            Err(err) => {
+                return Err(anyhow::Error::from(err).context(format!(
+                    "failed loading archived blobs for L1 batch #{l1_batch_number}"
+                )))
+            }
+        };
+        let Some(blob) = blobs
+            .blobs
+            .into_iter()
+            .find(|blob| blob.commitment == kzg_commitment)
+        else {
+            return Ok(None);
+        };
+        let pubdata = blob_to_zksync_pubdata(&blob.blob).with_context(|| {
+            format!("archived blob for L1 batch #{l1_batch_number} has unexpected format")
+        })?;
+        Ok(Some(pubdata))
+    }
+}
+
+/// Configuration for [`L1StateRecovery`].
+#[derive(Debug, Clone)]
+pub struct L1RecoveryConfig {
+    /// Address of the diamond proxy contract on L1.
+    pub diamond_proxy_addr: Address,
+    /// L1 block to start scanning for `BlockCommit` events from. Should be no later than the block
+    /// in which the first L1 batch was committed.
+    pub from_l1_block: u64,
+    /// Maximum number of L1 blocks to query events for in a single request.
+    pub l1_block_range: u64,
+    /// Path to the RocksDB directory for the recovered Merkle tree.
+    pub merkle_tree_path: PathBuf,
+}
+
+/// Commitment data for an L1 batch extracted from the commit transaction calldata.
+#[derive(Debug)]
+struct CommitmentData {
+    /// Value of `indexRepeatedStorageChanges`, i.e., the tree leaf count after the batch + 1.
+    index_repeated_storage_changes: u64,
+    new_state_root: H256,
+    /// `totalL2ToL1Pubdata` / `pubdataCommitments`.
+    pubdata: Vec<u8>,
+}
+
+impl CommitmentData {
+    /// Number of fields in the post-boojum `CommitBatchInfo` struct.
+    const FIELD_COUNT: usize = 10;
+
+    fn from_token(token: ethabi::Token) -> anyhow::Result<Self> {
+        let ethabi::Token::Tuple(tokens) = token else {
+            anyhow::bail!("unexpected commitment shape; expected a tuple, got {token:?}");
+        };
+        anyhow::ensure!(
+            tokens.len() == Self::FIELD_COUNT,
+            "unexpected number of fields in commitment: expected {}, got {} (is this a pre-boojum batch?)",
+            Self::FIELD_COUNT,
+            tokens.len()
+        );
+
+        let mut tokens = tokens.into_iter();
+        let index_repeated_storage_changes = tokens
+            .nth(2)
+            .and_then(ethabi::Token::into_uint)
+            .context("unexpected `indexRepeatedStorageChanges` token")?;
+        let new_state_root = tokens
+            .next()
+            .and_then(ethabi::Token::into_fixed_bytes)
+            .filter(|bytes| bytes.len() == 32)
+            .context("unexpected `newStateRoot` token")?;
+        let pubdata = tokens
+            .last()
+            .and_then(ethabi::Token::into_bytes)
+            .context("unexpected pubdata token")?;
+
+        Ok(Self {
+            index_repeated_storage_changes: index_repeated_storage_changes.as_u64(),
+            new_state_root: H256::from_slice(&new_state_root),
+            pubdata,
+        })
+    }
+}
+
+/// Converts a hashed storage key to the Merkle tree key.
+fn tree_key(hashed_key: H256) -> U256 {
+    U256::from_little_endian(hashed_key.as_bytes())
+}
+
+fn tree_entries(entries: &[RecoveredStorageEntry]) -> Vec<TreeEntry> {
+    entries
+        .iter()
+        .map(|entry| {
+            TreeEntry::new(
+                tree_key(entry.hashed_key),
+                entry.enumeration_index,
+                entry.value,
+            )
+        })
+        .collect()
+}
+
+fn system_context_key(position: H256) -> StorageKey {
+    StorageKey::new(AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS), position)
+}
+
+/// Tool reconstructing the Merkle tree and storage state from pubdata published on L1.
+///
+/// Recovery is incremental: each recovered L1 batch is persisted in Postgres together with its state root,
+/// so the tool can be restarted, and it will continue from the last recovered batch.
+#[derive(Debug)]
+pub struct L1StateRecovery {
+    l1_client: Box<dyn EthInterface>,
+    blob_provider: Option<Box<dyn BlobProvider>>,
+    pool: ConnectionPool<Core>,
+    config: L1RecoveryConfig,
+    contract: ethabi::Contract,
+    commit_function: ethabi::Function,
+}
+
+impl L1StateRecovery {
+    const COMPONENT: &'static str = "l1_recovery";
+
+    pub fn new(
+        l1_client: Box<dyn EthInterface>,
+        pool: ConnectionPool<Core>,
+        config: L1RecoveryConfig,
+    ) -> Self {
+        let contract = zksync_contracts::zksync_contract();
+        let commit_function = contract
+            .function("commitBatches")
+            .expect("L1 contract does not have `commitBatches` function")
+            .clone();
+        Self {
+            l1_client,
+            blob_provider: None,
+            pool,
+            config,
+            contract,
+            commit_function,
+        }
+    }
+
+    /// Sets the provider of blobs. Without a provider, recovery will fail on the first L1 batch
+    /// that has pubdata published in blobs.
+    #[must_use]
+    pub fn with_blob_provider(mut self, provider: Box<dyn BlobProvider>) -> Self {
+        self.blob_provider = Some(provider);
+        self
+    }
+
+    /// Recovers all L1 batches executed on L1. Returns the number of the last recovered L1 batch.
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<L1BatchNumber> {
+        let mut storage = self.pool.connection_tagged(Self::COMPONENT).await?;
+        let last_recovered_batch = storage.l1_recovery_dal().get_last_recovered_batch().await?;
+        let snapshot_recovery = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?;
+        if let Some(status) = snapshot_recovery {
+            anyhow::bail!(
+                "Postgres is recovered from a snapshot for L1 batch #{}; L1 state recovery must be run \
+                 on Postgres initialized with genesis",
+                status.l1_batch_number
+            );
+        }
+
+        let sealed_l1_batch = storage.blocks_dal().get_sealed_l1_batch_number().await?;
+        anyhow::ensure!(
+            sealed_l1_batch == Some(L1BatchNumber(0)),
+            "L1 state recovery must be run on Postgres initialized with genesis and containing no other L1 batches; \
+             the last sealed L1 batch is {sealed_l1_batch:?}"
+        );
+        drop(storage);
+
+        let tree_path = self.config.merkle_tree_path.clone();
+        let tree = tokio::task::spawn_blocking(move || {
+            let db = RocksDBWrapper::new(&tree_path).with_context(|| {
+                format!(
+                    "failed opening Merkle tree RocksDB at `{}`",
+                    tree_path.display()
+                )
+            })?;
+            anyhow::Ok(MerkleTree::new(db))
+        })
+        .await
+        .context("panicked opening Merkle tree")??;
+
+        let (mut tree, mut last_recovered_batch) = match last_recovered_batch {
+            Some(batch) => (self.sync_tree_with_postgres(tree, &batch).await?, batch),
+            None => self.recover_genesis(tree).await?,
+        };
+
+        let last_executed_batch = self.total_batches_executed().await?;
+        tracing::info!(
+            "Last recovered L1 batch is #{}; last L1 batch executed on L1 is #{last_executed_batch}",
+            last_recovered_batch.number
+        );
+        if last_recovered_batch.number < last_executed_batch {
+            let commit_logs = self
+                .load_commit_logs(last_recovered_batch.number + 1, &stop_receiver)
+                .await?;
+            for (&l1_batch_number, &commit_tx_hash) in
+                commit_logs.range(last_recovered_batch.number + 1..=last_executed_batch)
+            {
+                if *stop_receiver.borrow() {
+                    break;
+                }
+                anyhow::ensure!(
+                    l1_batch_number == last_recovered_batch.number + 1,
+                    "commit transaction for L1 batch #{} is not found; check that `from_l1_block` is set correctly",
+                    last_recovered_batch.number + 1
+                );
+                (tree, last_recovered_batch) = self
+                    .recover_batch(tree, &last_recovered_batch, l1_batch_number, commit_tx_hash)
+                    .await
+                    .with_context(|| format!("failed recovering L1 batch #{l1_batch_number}"))?;
+            }
+        }
+
+        if *stop_receiver.borrow() {
+            tracing::info!("Stop signal received, L1 state recovery is shutting down");
+            return Ok(last_recovered_batch.number);
+        }
+        anyhow::ensure!(
+            last_recovered_batch.number >= last_executed_batch,
+            "commit transaction for L1 batch #{} is not found; check that `from_l1_block` is set correctly",
+            last_recovered_batch.number + 1
+        );
+        tracing::info!(
+            "Recovered state up to L1 batch #{} with root hash {:?}",
+            last_recovered_batch.number,
+            last_recovered_batch.root_hash
+        );
+        Ok(last_recovered_batch.number)
+    }
+
+    async fn call_getter(&self, name: &str) -> anyhow::Result<U256> {
+        let args = CallFunctionArgs::new(name, ())
+            .for_contract(self.config.diamond_proxy_addr, self.contract.clone());
+        let tokens = self.l1_client.call_contract_function(args).await?;
+        U256::from_tokens(tokens).with_context(|| format!("unexpected `{name}` response"))
+    }
+
+    async fn total_batches_executed(&self) -> anyhow::Result<L1BatchNumber> {
+        let count = self.call_getter("getTotalBatchesExecuted").await?;
+        let count = u32::try_from(count)
+            .map_err(|err| anyhow::anyhow!("executed L1 batch count {count} overflows: {err}"))?;
+        Ok(L1BatchNumber(count))
+    }
+
+    /// Truncates the tree to the last L1 batch recovered in Postgres. The tree may be ahead of Postgres
+    /// if the tool was interrupted after extending the tree, but before committing the Postgres transaction.
+    async fn sync_tree_with_postgres(
+        &self,
+        mut tree: MerkleTree<RocksDBWrapper>,
+        last_recovered_batch: &RecoveredL1Batch,
+    ) -> anyhow::Result<MerkleTree<RocksDBWrapper>> {
+        let expected_version = u64::from(last_recovered_batch.number.0);
+        let tree_version = tree.latest_version();
+        anyhow::ensure!(
+            tree_version.map_or(false, |version| version >= expected_version),
+            "Merkle tree (latest version: {tree_version:?}) is behind the recovered state in Postgres \
+             (last recovered L1 batch: #{}); the tree must be recovered from scratch",
+            last_recovered_batch.number
+        );
+
+        let tree = tokio::task::spawn_blocking(move || {
+            tree.truncate_recent_versions(expected_version + 1);
+            tree
+        })
+        .await
+        .context("panicked truncating Merkle tree")?;
+        let root_hash = tree.latest_root_hash();
+        anyhow::ensure!(
+            root_hash == last_recovered_batch.root_hash,
+            "Merkle tree root hash {root_hash:?} differs from the recovered root hash {:?} for L1 batch #{}",
+            last_recovered_batch.root_hash,
+            last_recovered_batch.number
+        );
+        Ok(tree)
+    }
+
+    /// Initializes the recovered state from the genesis L1 batch stored in Postgres. Genesis is not published on L1,
+    /// but it is deterministic given the genesis params, so it can be created beforehand by the regular genesis procedure.
+    async fn recover_genesis(
+        &self,
+        mut tree: MerkleTree<RocksDBWrapper>,
+    ) -> anyhow::Result<(MerkleTree<RocksDBWrapper>, RecoveredL1Batch)> {
+        let mut storage = self.pool.connection_tagged(Self::COMPONENT).await?;
+        let genesis_batch = L1BatchWithLogs::new(&mut storage, L1BatchNumber(0))
+            .await
+            .context("genesis L1 batch is not present in Postgres; the node must be initialized with genesis first")?;
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(L1BatchNumber(0))
+            .await?
+            .context("genesis L1 batch has no state root hash")?;
+
+        let tree_entries: Vec<_> = genesis_batch
+            .storage_logs
+            .into_iter()
+            .filter_map(|instruction| match instruction {
+                TreeInstruction::Write(entry) => Some(entry),
+                TreeInstruction::Read(_) => None,
+            })
+            .collect();
+        tracing::info!(
+            "Recovering genesis L1 batch with {} entries",
+            tree_entries.len()
+        );
+
+        let (tree, output) = tokio::task::spawn_blocking(move || {
+            tree.truncate_recent_versions(0);
+            let output = tree.extend(tree_entries);
+            (tree, output)
+        })
+        .await
+        .context("panicked extending Merkle tree")?;
+        anyhow::ensure!(
+            output.root_hash == expected_root_hash,
+            "genesis root hash mismatch: expected {expected_root_hash:?}, got {:?}",
+            output.root_hash
+        );
+
+        // Genesis storage logs and initial writes are already present in Postgres, so only the progress is recorded.
+        let genesis = RecoveredL1Batch {
+            number: L1BatchNumber(0),
+            miniblock_number: MiniblockNumber(0),
+            root_hash: output.root_hash,
+        };
+        storage
+            .l1_recovery_dal()
+            .insert_recovered_batch(&genesis, H256::zero())
+            .await?;
+        Ok((tree, genesis))
+    }
+
+    /// Loads hashes of commit transactions for L1 batches starting from `first_batch`. If an L1 batch was committed
+    /// several times (e.g., after a revert), the latest commit is used.
+    async fn load_commit_logs(
+        &self,
+        first_batch: L1BatchNumber,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<BTreeMap<L1BatchNumber, H256>> {
+        let event_signature = self
+            .contract
+            .event("BlockCommit")
+            .context("`BlockCommit` event not found for zkSync L1 contract")?
+            .signature();
+        let last_l1_block = self.l1_client.block_number(Self::COMPONENT).await?.as_u64();
+        let block_range = self.config.l1_block_range.max(1);
+        let mut commit_logs = BTreeMap::new();
+        let mut from_block = self.config.from_l1_block;
+        while from_block <= last_l1_block && !*stop_receiver.borrow() {
+            let to_block = (from_block + block_range - 1).min(last_l1_block);
+            tracing::debug!("Loading commit logs for L1 blocks {from_block}..={to_block}");
+            let filter = FilterBuilder::default()
+                .address(vec![self.config.diamond_proxy_addr])
+                .from_block(BlockNumber::Number(from_block.into()))
+                .to_block(BlockNumber::Number(to_block.into()))
+                .topics(Some(vec![event_signature]), None, None, None)
+                .build();
+            let logs = self.l1_client.logs(filter, Self::COMPONENT).await?;
+            for log in logs {
+                if log.removed == Some(true) {
+                    continue;
+                }
+                let (Some(&l1_batch_number), Some(tx_hash)) =
+                    (log.topics.get(1), log.transaction_hash)
+                else {
+                    continue;
+                };
+                let Ok(l1_batch_number) = u32::try_from(h256_to_u256(l1_batch_number)) else {
+                    continue;
+                };
+                let l1_batch_number = L1BatchNumber(l1_batch_number);
+                if l1_batch_number >= first_batch {
+                    commit_logs.insert(l1_batch_number, tx_hash);
+                }
+            }
+            from_block = to_block + 1;
+        }
+        tracing::info!(
+            "Loaded {} commit logs for L1 batches starting from #{first_batch}",
+            commit_logs.len()
+        );
+        Ok(commit_logs)
+    }
+
+    async fn recover_batch(
+        &self,
+        tree: MerkleTree<RocksDBWrapper>,
+        prev_batch: &RecoveredL1Batch,
+        l1_batch_number: L1BatchNumber,
+        commit_tx_hash: H256,
+    ) -> anyhow::Result<(MerkleTree<RocksDBWrapper>, RecoveredL1Batch)> {
+        tracing::info!("Recovering L1 batch #{l1_batch_number} from commit tx {commit_tx_hash:?}");
+        let commit_tx = self
+            .l1_client
+            .get_tx(commit_tx_hash, Self::COMPONENT)
+            .await?
+            .with_context(|| format!("commit transaction {commit_tx_hash:?} not found on L1"))?;
+        let commitment = ConsistencyChecker::extract_commit_data(
+            &commit_tx.input.0,
+            &self.commit_function,
+            l1_batch_number,
+        )?;
+        let commitment = CommitmentData::from_token(commitment)?;
+        let pubdata = self
+            .parse_pubdata(l1_batch_number, &commitment.pubdata)
+            .await?;
+        tracing::debug!(
+            "Parsed pubdata for L1 batch #{l1_batch_number}: {} L2-to-L1 logs, {} messages, {} bytecodes, \
+             {} initial writes, {} repeated writes",
+            pubdata.l2_to_l1_log_count,
+            pubdata.message_count,
+            pubdata.bytecodes.len(),
+            pubdata.state_diffs.initial_writes.len(),
+            pubdata.state_diffs.repeated_writes.len()
+        );
+
+        let mut storage = self.pool.connection_tagged(Self::COMPONENT).await?;
+        let (initial_writes, repeated_writes) = Self::resolve_state_diffs(
+            &mut storage,
+            pubdata.state_diffs,
+            commitment.index_repeated_storage_changes,
+        )
+        .await?;
+        let entries: Vec<_> = repeated_writes
+            .into_iter()
+            .chain(initial_writes.iter().copied())
+            .collect();
+        let miniblock_number = Self::last_miniblock_number(&entries)?;
+        anyhow::ensure!(
+            miniblock_number > prev_batch.miniblock_number,
+            "last miniblock #{miniblock_number} in L1 batch #{l1_batch_number} is not greater than \
+             last miniblock #{} in the previous L1 batch",
+            prev_batch.miniblock_number
+        );
+
+        let tree_entries = tree_entries(&entries);
+        let (tree, output) = tokio::task::spawn_blocking(move || {
+            let mut tree = tree;
+            let output = tree.extend(tree_entries);
+            (tree, output)
+        })
+        .await
+        .context("panicked extending Merkle tree")?;
+
+        if output.root_hash != commitment.new_state_root
+            || output.leaf_count + 1 != commitment.index_repeated_storage_changes
+        {
+            tokio::task::spawn_blocking(move || {
+                let mut tree = tree;
+                tree.truncate_recent_versions(l1_batch_number.0.into());
+            })
+            .await
+            .context("panicked truncating Merkle tree")?;
+            anyhow::bail!(
+                "recovered state diverges from the commitment on L1: expected root hash {:?} and leaf count {}, \
+                 got {:?} and {}",
+                commitment.new_state_root,
+                commitment.index_repeated_storage_changes.saturating_sub(1),
+                output.root_hash,
+                output.leaf_count
+            );
+        }
+
+        let recovered_batch = RecoveredL1Batch {
+            number: l1_batch_number,
+            miniblock_number,
+            root_hash: output.root_hash,
+        };
+        let factory_deps = pubdata
+            .bytecodes
+            .into_iter()
+            .map(|bytecode| (hash_bytecode(&bytecode), bytecode))
+            .collect();
+        let mut transaction = storage.start_transaction().await?;
+        transaction
+            .l1_recovery_dal()
+            .insert_initial_writes(l1_batch_number, &initial_writes)
+            .await?;
+        transaction
+            .l1_recovery_dal()
+            .insert_storage_logs(l1_batch_number, &entries)
+            .await?;
+        transaction
+            .factory_deps_dal()
+            .insert_factory_deps(miniblock_number, &factory_deps)
+            .await?;
+        transaction
+            .l1_recovery_dal()
+            .insert_recovered_batch(&recovered_batch, commit_tx_hash)
+            .await?;
+        transaction.commit().await?;
+
+        tracing::info!(
+            "Recovered L1 batch #{l1_batch_number} (last miniblock #{miniblock_number}) with root hash {:?}",
+            output.root_hash
+        );
+        Ok((tree, recovered_batch))
+    }
+
+    /// Returns the number of the last (fictive) miniblock in an L1 batch. It is taken from the `SystemContext` storage,
+    /// which is updated in each L1 batch.
+    fn last_miniblock_number(entries: &[RecoveredStorageEntry]) -> anyhow::Result<MiniblockNumber> {
+        let block_info_key =
+            system_context_key(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION).hashed_key();
+        let block_info = entries
+            .iter()
+            .find(|entry| entry.hashed_key == block_info_key)
+            .context("state diffs do not update current L2 block info in `SystemContext`")?;
+        let (miniblock_number, _) = unpack_block_info(h256_to_u256(block_info.value));
+        let miniblock_number = u32::try_from(miniblock_number)
+            .with_context(|| format!("miniblock number {miniblock_number} overflows"))?;
+        Ok(MiniblockNumber(miniblock_number))
+    }
+
+    async fn parse_pubdata(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        raw_pubdata: &[u8],
+    ) -> anyhow::Result<Pubdata> {
+        match raw_pubdata.first() {
+            Some(&PUBDATA_SOURCE_BLOBS) => {
+                return self
+                    .load_pubdata_from_blobs(l1_batch_number, &raw_pubdata[1..])
+                    .await
+            }
+            Some(&PUBDATA_SOURCE_CALLDATA) if raw_pubdata.len() > 1 + BYTES_PER_BLOB_COMMITMENT => {
+                // Pubdata is followed by the blob commitment used to verify the proof.
+                let pubdata = &raw_pubdata[1..raw_pubdata.len() - BYTES_PER_BLOB_COMMITMENT];
+                if let Ok(pubdata) = Pubdata::parse(pubdata, false) {
+                    return Ok(pubdata);
+                }
+            }
+            _ => { /* fall through to the pre-1.4.2 format */ }
+        }
+        // Before protocol version 1.4.2, pubdata was published in calldata without the source byte.
+        Pubdata::parse(raw_pubdata, false).context("failed parsing pubdata")
+    }
+
+    async fn load_pubdata_from_blobs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        pubdata_commitments: &[u8],
+    ) -> anyhow::Result<Pubdata> {
+        let blob_provider = self
+            .blob_provider
+            .as_deref()
+            .context("pubdata is published in blobs, but blob provider is not configured")?;
+        anyhow::ensure!(
+            pubdata_commitments.len() % BYTES_PER_PUBDATA_COMMITMENT == 0,
+            "unexpected pubdata commitments length: {}",
+            pubdata_commitments.len()
+        );
+
+        let mut pubdata = vec![];
+        for commitment in pubdata_commitments.chunks(BYTES_PER_PUBDATA_COMMITMENT) {
+            let kzg_commitment = &commitment[48..96];
+            let blob = blob_provider
+                .get_blob(l1_batch_number, kzg_commitment)
+                .await?
+                .with_context(|| {
+                    format!(
+                        "blob with KZG commitment 0x{} is not available",
+                        hex::encode(kzg_commitment)
+                    )
+                })?;
+            anyhow::ensure!(
+                blob.len() == ZK_SYNC_BYTES_PER_BLOB,
+                "unexpected blob length: expected {ZK_SYNC_BYTES_PER_BLOB}, got {}",
+                blob.len()
+            );
+
+            let (blob, actual_commitment) = tokio::task::spawn_blocking(move || {
+                let actual_commitment = KzgInfo::new(&blob).kzg_commitment;
+                (blob, actual_commitment)
+            })
+            .await
+            .context("panicked computing KZG commitment")?;
+            anyhow::ensure!(
+                actual_commitment.as_slice() == kzg_commitment,
+                "KZG commitment mismatch for blob provided for commitment 0x{}",
+                hex::encode(kzg_commitment)
+            );
+            pubdata.extend(blob);
+        }
+        Pubdata::parse(&pubdata, true).context("failed parsing pubdata from blobs")
+    }
+
+    /// Returns the latest recovered values for the specified keys. Keys not written in recovered L1 batches
+    /// are read from the genesis storage logs.
+    async fn get_storage_values(
+        storage: &mut Connection<'_, Core>,
+        hashed_keys: &[H256],
+    ) -> anyhow::Result<HashMap<H256, H256>> {
+        let mut values = storage
+            .l1_recovery_dal()
+            .get_storage_values(hashed_keys)
+            .await?;
+        let genesis_keys: Vec<_> = hashed_keys
+            .iter()
+            .filter(|key| !values.contains_key(key))
+            .copied()
+            .collect();
+        let genesis_values = storage
+            .storage_logs_dal()
+            .get_storage_values(&genesis_keys, MiniblockNumber(0))
+            .await?;
+        values.extend(
+            genesis_values
+                .into_iter()
+                .filter_map(|(key, value)| Some((key, value?))),
+        );
+        Ok(values)
+    }
+
+    /// Resolves state diffs to the full storage entries; returns initial and repeated writes, respectively.
+    /// Repeated writes are resolved using the previously recovered state; initial writes are assigned
+    /// enumeration indices in the order of their appearance in pubdata.
+    async fn resolve_state_diffs(
+        storage: &mut Connection<'_, Core>,
+        state_diffs: StateDiffs,
+        index_repeated_storage_changes: u64,
+    ) -> anyhow::Result<(Vec<RecoveredStorageEntry>, Vec<RecoveredStorageEntry>)> {
+        let StateDiffs {
+            initial_writes,
+            repeated_writes,
+        } = state_diffs;
+
+        let indices: Vec<_> = repeated_writes.iter().map(|&(index, _)| index).collect();
+        let hashed_keys = storage
+            .l1_recovery_dal()
+            .get_hashed_keys_by_enumeration_index(&indices)
+            .await?;
+        let keys: Vec<_> = hashed_keys.values().copied().collect();
+        let prev_values = Self::get_storage_values(storage, &keys).await?;
+        let mut resolved_repeated_writes = Vec::with_capacity(repeated_writes.len());
+        for (index, value) in repeated_writes {
+            let hashed_key = *hashed_keys.get(&index).with_context(|| {
+                format!("repeated write references unknown enumeration index {index}")
+            })?;
+            let prev_value = prev_values.get(&hashed_key).copied().unwrap_or_default();
+            let value = value.apply(h256_to_u256(prev_value));
+            resolved_repeated_writes.push(RecoveredStorageEntry {
+                hashed_key,
+                enumeration_index: index,
+                value: u256_to_h256(value),
+            });
+        }
+
+        let new_keys: Vec<_> = initial_writes.iter().map(|&(key, _)| key).collect();
+        let existing_keys = storage
+            .storage_logs_dedup_dal()
+            .filter_written_slots(&new_keys)
+            .await?;
+        anyhow::ensure!(
+            existing_keys.is_empty(),
+            "initial writes for already written keys: {existing_keys:?}"
+        );
+
+        // `indexRepeatedStorageChanges` is the next free enumeration index after the batch is applied.
+        let first_index = index_repeated_storage_changes
+            .checked_sub(initial_writes.len() as u64)
+            .filter(|&index| index > 0)
+            .context(
+                "`indexRepeatedStorageChanges` is inconsistent with the number of initial writes",
+            )?;
+        let resolved_initial_writes = initial_writes
+            .into_iter()
+            .enumerate()
+            .map(|(i, (hashed_key, value))| RecoveredStorageEntry {
+                hashed_key,
+                enumeration_index: first_index + i as u64,
+                value: u256_to_h256(value.apply(U256::zero())),
+            })
+            .collect();
+        Ok((resolved_initial_writes, resolved_repeated_writes))
+    }
+}
@@ -0,0 +1,292 @@
+//! Parsing of L1 batch pubdata published on L1.
+
+use anyhow::Context as _;
+use zksync_types::{
+    writes::{compression::COMPRESSION_VERSION_NUMBER, BYTES_PER_DERIVED_KEY},
+    H256, U256,
+};
+
+/// Size of a serialized L2-to-L1 log in pubdata.
+const L2_TO_L1_LOG_SIZE: usize = 88;
+
+/// Storage value compressed relative to the previous value of the slot, as published in pubdata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CompressedValue {
+    /// The new value is published as is.
+    Full(U256),
+    /// The new value is `prev_value + diff`.
+    Add(U256),
+    /// The new value is `prev_value - diff`.
+    Sub(U256),
+    /// The new value is published in a truncated form.
+    Transform(U256),
+}
+
+impl CompressedValue {
+    fn read(reader: &mut PubdataReader<'_>) -> anyhow::Result<Self> {
+        let metadata = reader.read_u8()?;
+        let len = usize::from(metadata >> 3);
+        let operation = metadata & 7;
+        if operation == 0 {
+            anyhow::ensure!(
+                len == 0,
+                "unexpected length {len} for uncompressed storage value"
+            );
+            return Ok(Self::Full(U256::from_big_endian(reader.read_bytes(32)?)));
+        }
+
+        anyhow::ensure!(
+            len < 32,
+            "compressed storage value has invalid length {len}"
+        );
+        let value = U256::from_big_endian(reader.read_bytes(len)?);
+        Ok(match operation {
+            1 => Self::Add(value),
+            2 => Self::Sub(value),
+            3 => Self::Transform(value),
+            _ => anyhow::bail!("unknown storage value compression operation: {operation}"),
+        })
+    }
+
+    /// Decompresses this value given the previous value of the storage slot.
+    pub fn apply(self, prev_value: U256) -> U256 {
+        match self {
+            Self::Full(value) | Self::Transform(value) => value,
+            Self::Add(diff) => prev_value.overflowing_add(diff).0,
+            Self::Sub(diff) => prev_value.overflowing_sub(diff).0,
+        }
+    }
+}
+
+/// State diffs from pubdata.
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct StateDiffs {
+    /// Initial writes in the order of assigned enumeration indices. Keys are hashed storage keys.
+    pub initial_writes: Vec<(H256, CompressedValue)>,
+    /// Repeated writes referencing enumeration indices of previously written slots.
+    pub repeated_writes: Vec<(u64, CompressedValue)>,
+}
+
+/// Parsed L1 batch pubdata. L2-to-L1 logs and messages are not needed for state recovery, so only their count is kept.
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct Pubdata {
+    pub l2_to_l1_log_count: usize,
+    pub message_count: usize,
+    pub bytecodes: Vec<Vec<u8>>,
+    pub state_diffs: StateDiffs,
+}
+
+impl Pubdata {
+    /// Parses pubdata in the format produced by the bootloader (see `L1BatchWithMetadata::construct_pubdata()`).
+    /// If `allow_padding` is set, `bytes` may contain trailing zero bytes (e.g., when pubdata was published in blobs).
+    pub fn parse(bytes: &[u8], allow_padding: bool) -> anyhow::Result<Self> {
+        let mut reader = PubdataReader { bytes };
+
+        let l2_to_l1_log_count = reader.read_u32()? as usize;
+        reader
+            .read_bytes(l2_to_l1_log_count * L2_TO_L1_LOG_SIZE)
+            .context("failed reading L2-to-L1 logs")?;
+
+        let message_count = reader.read_u32()? as usize;
+        for _ in 0..message_count {
+            let len = reader.read_u32()? as usize;
+            reader.read_bytes(len).context("failed reading message")?;
+        }
+
+        let bytecode_count = reader.read_u32()? as usize;
+        let mut bytecodes = Vec::with_capacity(bytecode_count);
+        for _ in 0..bytecode_count {
+            let len = reader.read_u32()? as usize;
+            let bytecode = reader.read_bytes(len).context("failed reading bytecode")?;
+            bytecodes.push(bytecode.to_vec());
+        }
+
+        let state_diffs =
+            Self::parse_state_diffs(&mut reader).context("failed parsing state diffs")?;
+        if allow_padding {
+            anyhow::ensure!(
+                reader.bytes.iter().all(|&byte| byte == 0),
+                "pubdata has non-zero trailing bytes"
+            );
+        } else {
+            anyhow::ensure!(
+                reader.bytes.is_empty(),
+                "pubdata has {} unexpected trailing bytes",
+                reader.bytes.len()
+            );
+        }
+
+        Ok(Self {
+            l2_to_l1_log_count,
+            message_count,
+            bytecodes,
+            state_diffs,
+        })
+    }
+
+    fn parse_state_diffs(reader: &mut PubdataReader<'_>) -> anyhow::Result<StateDiffs> {
+        let version = reader.read_u8()?;
+        anyhow::ensure!(
+            version == COMPRESSION_VERSION_NUMBER,
+            "unsupported state diff compression version: {version}"
+        );
+        let total_len = reader.read_bytes(3)?;
+        let total_len = u32::from_be_bytes([0, total_len[0], total_len[1], total_len[2]]) as usize;
+        let enumeration_index_size = usize::from(reader.read_u8()?);
+        anyhow::ensure!(
+            (1..=8).contains(&enumeration_index_size),
+            "invalid enumeration index size: {enumeration_index_size}"
+        );
+
+        let mut reader = PubdataReader {
+            bytes: reader
+                .read_bytes(total_len)
+                .context("failed reading compressed state diffs")?,
+        };
+        let initial_write_count = reader.read_u16()?;
+        let mut initial_writes = Vec::with_capacity(initial_write_count.into());
+        for _ in 0..initial_write_count {
+            let derived_key = reader.read_bytes(BYTES_PER_DERIVED_KEY.into())?;
+            let value = CompressedValue::read(&mut reader)?;
+            initial_writes.push((H256::from_slice(derived_key), value));
+        }
+
+        let mut repeated_writes = vec![];
+        while !reader.bytes.is_empty() {
+            let index = reader.read_bytes(enumeration_index_size)?;
+            let index = index
+                .iter()
+                .fold(0_u64, |acc, &byte| (acc << 8) | u64::from(byte));
+            let value = CompressedValue::read(&mut reader)?;
+            repeated_writes.push((index, value));
+        }
+
+        Ok(StateDiffs {
+            initial_writes,
+            repeated_writes,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct PubdataReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> PubdataReader<'a> {
+    fn read_bytes(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        anyhow::ensure!(
+            self.bytes.len() >= len,
+            "unexpected end of pubdata: expected {len} more bytes, got {}",
+            self.bytes.len()
+        );
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn read_u8(&mut self) -> anyhow::Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> anyhow::Result<u16> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> anyhow::Result<u32> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        block::L1BatchHeader,
+        commitment::L1BatchWithMetadata,
+        writes::{compress_state_diffs, StateDiffRecord},
+        Address, L1BatchNumber, ProtocolVersionId,
+    };
+
+    use super::*;
+    use crate::utils::testonly::create_l1_batch_metadata;
+
+    #[test]
+    fn decompressing_values() {
+        let cases = [
+            (U256::zero(), U256::from(1_000_000)),
+            (U256::from(1_000_000), U256::from(1_000_001)),
+            (U256::from(1_000_000), U256::from(10)),
+            (U256::from(1_000_000), U256::MAX),
+            (U256::MAX, U256::zero()),
+        ];
+        for (prev_value, new_value) in cases {
+            let compressed = zksync_types::writes::compression::compress_with_best_strategy(
+                prev_value, new_value,
+            );
+            let mut reader = PubdataReader { bytes: &compressed };
+            let value = CompressedValue::read(&mut reader).unwrap();
+            assert!(reader.bytes.is_empty());
+            assert_eq!(value.apply(prev_value), new_value, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn parsing_pubdata() {
+        let state_diffs = vec![
+            StateDiffRecord {
+                address: Address::repeat_byte(1),
+                key: 1.into(),
+                derived_key: [1; 32],
+                enumeration_index: 0,
+                initial_value: 0.into(),
+                final_value: 42.into(),
+            },
+            StateDiffRecord {
+                address: Address::repeat_byte(2),
+                key: 2.into(),
+                derived_key: [2; 32],
+                enumeration_index: 5,
+                initial_value: 100.into(),
+                final_value: 99.into(),
+            },
+        ];
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        header.l2_to_l1_messages = vec![vec![1, 2, 3]];
+        let mut metadata = create_l1_batch_metadata(1);
+        metadata.state_diffs_compressed = compress_state_diffs(state_diffs);
+        let l1_batch = L1BatchWithMetadata {
+            header,
+            metadata,
+            raw_published_factory_deps: vec![vec![0; 64]],
+        };
+        let raw_pubdata = l1_batch.construct_pubdata();
+
+        let pubdata = Pubdata::parse(&raw_pubdata, false).unwrap();
+        assert_eq!(pubdata.l2_to_l1_log_count, 0);
+        assert_eq!(pubdata.message_count, 1);
+        assert_eq!(pubdata.bytecodes, [vec![0; 64]]);
+        let StateDiffs {
+            initial_writes,
+            repeated_writes,
+        } = pubdata.state_diffs;
+        assert_eq!(initial_writes.len(), 1);
+        assert_eq!(initial_writes[0].0, H256::repeat_byte(1));
+        assert_eq!(initial_writes[0].1.apply(U256::zero()), 42.into());
+        assert_eq!(repeated_writes.len(), 1);
+        assert_eq!(repeated_writes[0].0, 5);
+        assert_eq!(repeated_writes[0].1.apply(100.into()), 99.into());
+
+        let mut padded_pubdata = raw_pubdata.clone();
+        padded_pubdata.extend([0; 32]);
+        Pubdata::parse(&padded_pubdata, false).unwrap_err();
+        let padded = Pubdata::parse(&padded_pubdata, true).unwrap();
+        assert_eq!(padded.bytecodes, [vec![0; 64]]);
+    }
+}
@@ -0,0 +1,395 @@
+//! Tests for L1 state recovery.
+
+use std::collections::HashMap;
+
+use tempfile::TempDir;
+use zksync_eth_client::{clients::MockEthereum, BoundEthInterface, Options};
+use zksync_l1_contract_interface::{
+    i_executor::structures::{CommitBatchInfo, StoredBatchInfo},
+    Tokenizable,
+};
+use zksync_merkle_tree::PatchSet;
+use zksync_object_store::ObjectStoreFactory;
+use zksync_system_constants::{
+    SYSTEM_CONTEXT_BLOCK_INFO_POSITION, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION,
+};
+use zksync_types::{
+    block::{pack_block_info, L1BatchHeader},
+    commitment::L1BatchWithMetadata,
+    eth_sender::SidecarBlobV1,
+    pubdata_da::PubdataDA,
+    web3::types::Log,
+    writes::{compress_state_diffs, StateDiffRecord},
+    L2ChainId, ProtocolVersionId,
+};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::create_l1_batch_metadata,
+};
+
+const DIAMOND_PROXY_ADDR: Address = Address::repeat_byte(1);
+const BATCH_TIMESTAMP: u64 = 100;
+const LAST_MINIBLOCK: MiniblockNumber = MiniblockNumber(2);
+const FACTORY_DEP: [u8; 32] = [1; 32];
+
+/// L1 batch #1 on top of the genesis state together with the storage writes performed in it.
+#[derive(Debug)]
+struct TestBatch {
+    batch: L1BatchWithMetadata,
+    writes: Vec<(StorageKey, H256)>,
+    initial_writes: Vec<StorageKey>,
+}
+
+async fn genesis_entries(storage: &mut Connection<'_, Core>) -> Vec<(StorageKey, u64, H256)> {
+    let genesis_batch = L1BatchWithLogs::new(storage, L1BatchNumber(0))
+        .await
+        .unwrap();
+    genesis_batch
+        .storage_logs
+        .into_iter()
+        .filter_map(|instruction| match instruction {
+            TreeInstruction::Write(entry) => Some((entry.key, entry.leaf_index, entry.value)),
+            TreeInstruction::Read(_) => None,
+        })
+        .collect()
+}
+
+/// Creates L1 batch #1 with 2 initial writes, a repeated write and `SystemContext` updates
+/// for the last miniblock in the batch.
+async fn create_l1_batch(storage: &mut Connection<'_, Core>) -> TestBatch {
+    let genesis_entries = genesis_entries(storage).await;
+    let mut tree = MerkleTree::new(PatchSet::default());
+    let genesis_tree_entries = genesis_entries
+        .iter()
+        .map(|(key, leaf_index, value)| TreeEntry::new(key.hashed_key_u256(), *leaf_index, *value));
+    let genesis_output = tree.extend(genesis_tree_entries.collect());
+
+    let (updated_key, _, prev_value) = genesis_entries[0];
+    let prev_miniblock_hash_position = u256_to_h256(
+        h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION)
+            + u64::from(LAST_MINIBLOCK.0 - 1),
+    );
+    let mut writes = vec![
+        (updated_key, u256_to_h256(h256_to_u256(prev_value) + 1)),
+        (
+            system_context_key(SYSTEM_CONTEXT_BLOCK_INFO_POSITION),
+            u256_to_h256(pack_block_info(1, BATCH_TIMESTAMP)),
+        ),
+        (
+            system_context_key(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION),
+            u256_to_h256(pack_block_info(LAST_MINIBLOCK.0.into(), BATCH_TIMESTAMP)),
+        ),
+        (
+            system_context_key(prev_miniblock_hash_position),
+            H256::repeat_byte(0x23),
+        ),
+    ];
+    writes.extend([1_u64, 2].map(|i| {
+        let key = StorageKey::new(
+            AccountTreeId::new(Address::repeat_byte(0xaa)),
+            H256::from_low_u64_be(i),
+        );
+        (key, H256::from_low_u64_be(i + 100))
+    }));
+    // Initial writes are enumerated in the order of state diffs in pubdata.
+    writes.sort_unstable_by_key(|(key, _)| (*key.address(), h256_to_u256(*key.key())));
+
+    let genesis_values: HashMap<_, _> = genesis_entries
+        .iter()
+        .map(|&(key, leaf_index, value)| (key, (leaf_index, value)))
+        .collect();
+    let mut next_leaf_index = genesis_output.leaf_count + 1;
+    let mut initial_writes = vec![];
+    let mut state_diffs = vec![];
+    let mut batch_tree_entries = vec![];
+    for &(key, value) in &writes {
+        let (enumeration_index, initial_value) =
+            genesis_values.get(&key).copied().unwrap_or_default();
+        let leaf_index = if enumeration_index == 0 {
+            initial_writes.push(key);
+            next_leaf_index += 1;
+            next_leaf_index - 1
+        } else {
+            enumeration_index
+        };
+        state_diffs.push(StateDiffRecord {
+            address: *key.address(),
+            key: h256_to_u256(*key.key()),
+            derived_key: key.hashed_key().0,
+            enumeration_index,
+            initial_value: h256_to_u256(initial_value),
+            final_value: h256_to_u256(value),
+        });
+        batch_tree_entries.push(TreeEntry::new(key.hashed_key_u256(), leaf_index, value));
+    }
+    let output = tree.extend(batch_tree_entries);
+
+    let header = L1BatchHeader::new(
+        L1BatchNumber(1),
+        BATCH_TIMESTAMP,
+        Default::default(),
+        ProtocolVersionId::latest(),
+    );
+    let mut metadata = create_l1_batch_metadata(1);
+    metadata.root_hash = output.root_hash;
+    metadata.merkle_root_hash = output.root_hash;
+    metadata.rollup_last_leaf_index = output.leaf_count + 1;
+    metadata.state_diffs_compressed = compress_state_diffs(state_diffs);
+    let batch = L1BatchWithMetadata {
+        header,
+        metadata,
+        raw_published_factory_deps: vec![FACTORY_DEP.to_vec()],
+    };
+    TestBatch {
+        batch,
+        writes,
+        initial_writes,
+    }
+}
+
+fn l1_batch_commit_log(batch: &L1BatchWithMetadata) -> Log {
+    let event_signature = zksync_contracts::zksync_contract()
+        .event("BlockCommit")
+        .unwrap()
+        .signature();
+    Log {
+        address: DIAMOND_PROXY_ADDR,
+        topics: vec![
+            event_signature,
+            H256::from_low_u64_be(batch.header.number.0.into()),
+            batch.metadata.root_hash,
+            batch.metadata.commitment,
+        ],
+        data: vec![].into(),
+        block_hash: None,
+        block_number: None,
+        transaction_hash: None,
+        transaction_index: None,
+        log_index: None,
+        transaction_log_index: None,
+        log_type: Some("mined".into()),
+        removed: None,
+    }
+}
+
+/// Sends a `commitBatches` transaction for the batch to the mock L1 client and executes it.
+async fn commit(client: &MockEthereum, batch: &L1BatchWithMetadata) {
+    let commit_tokens =
+        ethabi::Token::Array(vec![
+            CommitBatchInfo::new(batch, PubdataDA::Calldata).into_token()
+        ]);
+    let mut prev_batch = batch.clone();
+    prev_batch.header.number = L1BatchNumber(0);
+    let prev_batch_token = StoredBatchInfo(&prev_batch).into_token();
+    let calldata = client
+        .contract()
+        .function("commitBatches")
+        .unwrap()
+        .encode_input(&[prev_batch_token, commit_tokens])
+        .unwrap();
+
+    let options = Options {
+        nonce: Some(0.into()),
+        ..Options::default()
+    };
+    let signed_tx = client
+        .sign_prepared_tx(calldata, DIAMOND_PROXY_ADDR, options)
+        .unwrap();
+    client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+    client
+        .execute_tx(signed_tx.hash, true, 1)
+        .with_logs(vec![l1_batch_commit_log(batch)]);
+}
+
+fn mock_config(merkle_tree_path: PathBuf) -> L1RecoveryConfig {
+    L1RecoveryConfig {
+        diamond_proxy_addr: DIAMOND_PROXY_ADDR,
+        from_l1_block: 0,
+        l1_block_range: 3,
+        merkle_tree_path,
+    }
+}
+
+async fn prepare_recovery(pool: &ConnectionPool<Core>) -> (TestBatch, Arc<MockEthereum>) {
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let test_batch = create_l1_batch(&mut storage).await;
+    let client = MockEthereum::default().with_call_handler(|call| {
+        assert_eq!(call.contract_address(), DIAMOND_PROXY_ADDR);
+        match call.function_name() {
+            "getTotalBatchesExecuted" => ethabi::Token::Uint(1.into()),
+            name => panic!("unexpected L1 call: {name}"),
+        }
+    });
+    // Advance L1 blocks so that commit logs are queried in several ranges.
+    client.advance_block_number(5);
+    (test_batch, Arc::new(client))
+}
+
+#[tokio::test]
+async fn recovering_state_from_l1() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let (test_batch, client) = prepare_recovery(&pool).await;
+    commit(&client, &test_batch.batch).await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let recovery = L1StateRecovery::new(
+        Box::new(client.clone()),
+        pool.clone(),
+        mock_config(temp_dir.path().to_owned()),
+    );
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let last_recovered_batch = recovery.run(stop_receiver).await.unwrap();
+    assert_eq!(last_recovered_batch, L1BatchNumber(1));
+
+    let mut storage = pool.connection().await.unwrap();
+    let hashed_keys: Vec<_> = test_batch
+        .writes
+        .iter()
+        .map(|(key, _)| key.hashed_key())
+        .collect();
+    let recovered_values = storage
+        .l1_recovery_dal()
+        .get_storage_values(&hashed_keys)
+        .await
+        .unwrap();
+    for (key, value) in &test_batch.writes {
+        assert_eq!(recovered_values[&key.hashed_key()], *value, "{key:?}");
+    }
+    // Recovered storage logs lack key preimages and must not be written to the node storage.
+    let node_values = storage
+        .storage_logs_dal()
+        .get_storage_values(&hashed_keys, MiniblockNumber(u32::MAX))
+        .await
+        .unwrap();
+    for (key, value) in &test_batch.writes {
+        assert_ne!(node_values[&key.hashed_key()], Some(*value), "{key:?}");
+    }
+    let new_keys: Vec<_> = test_batch
+        .initial_writes
+        .iter()
+        .map(StorageKey::hashed_key)
+        .collect();
+    let written_keys = storage
+        .storage_logs_dedup_dal()
+        .filter_written_slots(&new_keys)
+        .await
+        .unwrap();
+    assert_eq!(written_keys.len(), new_keys.len());
+    let factory_dep = storage
+        .factory_deps_dal()
+        .get_factory_dep(hash_bytecode(&FACTORY_DEP))
+        .await
+        .unwrap();
+    assert_eq!(factory_dep.as_deref(), Some(FACTORY_DEP.as_slice()));
+
+    // Check that node block headers are untouched.
+    assert_eq!(
+        storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap(),
+        Some(L1BatchNumber(0))
+    );
+    let last_recovered_batch = storage
+        .l1_recovery_dal()
+        .get_last_recovered_batch()
+        .await
+        .unwrap()
+        .expect("no recovered batches");
+    assert_eq!(last_recovered_batch.number, L1BatchNumber(1));
+    assert_eq!(last_recovered_batch.miniblock_number, LAST_MINIBLOCK);
+    assert_eq!(
+        last_recovered_batch.root_hash,
+        test_batch.batch.metadata.root_hash
+    );
+
+    // Check that the tree is persisted and recovery is idempotent.
+    let recovery = L1StateRecovery::new(
+        Box::new(client),
+        pool.clone(),
+        mock_config(temp_dir.path().to_owned()),
+    );
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let last_recovered_batch = recovery.run(stop_receiver).await.unwrap();
+    assert_eq!(last_recovered_batch, L1BatchNumber(1));
+    let tree = MerkleTree::new(RocksDBWrapper::new(temp_dir.path()).unwrap());
+    assert_eq!(tree.latest_version(), Some(1));
+    assert_eq!(tree.latest_root_hash(), test_batch.batch.metadata.root_hash);
+}
+
+#[tokio::test]
+async fn recovery_fails_on_root_hash_mismatch() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let (mut test_batch, client) = prepare_recovery(&pool).await;
+    test_batch.batch.metadata.merkle_root_hash = H256::repeat_byte(0xff);
+    commit(&client, &test_batch.batch).await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let recovery = L1StateRecovery::new(
+        Box::new(client),
+        pool.clone(),
+        mock_config(temp_dir.path().to_owned()),
+    );
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = recovery.run(stop_receiver).await.unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("diverges from the commitment"), "{err}");
+
+    let mut storage = pool.connection().await.unwrap();
+    let last_recovered_batch = storage
+        .l1_recovery_dal()
+        .get_last_recovered_batch()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(last_recovered_batch.number, L1BatchNumber(0));
+    let tree = MerkleTree::new(RocksDBWrapper::new(temp_dir.path()).unwrap());
+    assert_eq!(tree.latest_version(), Some(0));
+}
+
+fn sidecar_blob(pubdata: &[u8]) -> SidecarBlobV1 {
+    let kzg_info = KzgInfo::new(pubdata);
+    SidecarBlobV1 {
+        blob: kzg_info.blob.to_vec(),
+        commitment: kzg_info.kzg_commitment.to_vec(),
+        proof: kzg_info.blob_proof.to_vec(),
+        versioned_hash: kzg_info.versioned_hash.to_vec(),
+    }
+}
+
+#[tokio::test]
+async fn getting_blobs_from_object_store() {
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let pubdata = vec![1_u8; 100];
+    let blob = sidecar_blob(&pubdata);
+    let blobs = L1BatchBlobs {
+        l1_batch_number: L1BatchNumber(1),
+        commit_tx_hash: H256::repeat_byte(1),
+        blobs: vec![blob.clone()],
+    };
+    blob_store.put(L1BatchNumber(1), &blobs).await.unwrap();
+    let provider = ObjectStoreBlobProvider::new(blob_store);
+
+    let loaded_blob = provider
+        .get_blob(L1BatchNumber(1), &blob.commitment)
+        .await
+        .unwrap()
+        .expect("blob not found");
+    assert_eq!(loaded_blob.len(), ZK_SYNC_BYTES_PER_BLOB);
+    assert_eq!(loaded_blob[..pubdata.len()], pubdata);
+    assert!(loaded_blob[pubdata.len()..].iter().all(|&byte| byte == 0));
+
+    let missing_blob = provider.get_blob(L1BatchNumber(1), &[0; 48]).await.unwrap();
+    assert_eq!(missing_blob, None);
+    let missing_blob = provider
+        .get_blob(L1BatchNumber(2), &blob.commitment)
+        .await
+        .unwrap();
+    assert_eq!(missing_blob, None);
+}
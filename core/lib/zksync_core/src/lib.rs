@@ -18,8 +18,10 @@ use tokio::{
     task::JoinHandle,
 };
 use zksync_circuit_breaker::{
-    l1_txs::FailedL1TransactionChecker, replication_lag::ReplicationLagChecker, CircuitBreaker,
-    CircuitBreakerChecker, CircuitBreakerError,
+    l1_reorgs::FatalL1ReorgChecker, l1_txs::FailedL1TransactionChecker,
+    operator_balance::OperatorBalanceChecker, priority_ops::PriorityOpsDeadlineChecker,
+    replication_lag::ReplicationLagChecker, CircuitBreaker, CircuitBreakerChecker,
+    CircuitBreakerError,
 };
 use zksync_concurrency::{ctx, scope};
 use zksync_config::{
@@ -31,15 +33,16 @@ use zksync_config::{
         },
         contracts::ProverAtGenesis,
         database::{MerkleTreeConfig, MerkleTreeMode},
+        eth_sender::PubdataSendingMode,
     },
-    ApiConfig, ContractsConfig, DBConfig, ETHSenderConfig, PostgresConfig,
+    ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, PostgresConfig,
 };
-use zksync_contracts::{governance_contract, BaseSystemContracts};
+use zksync_contracts::{governance_contract, zksync_contract, BaseSystemContracts};
 use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core, CoreDal};
 use zksync_db_connection::healthcheck::ConnectionPoolHealthCheck;
 use zksync_eth_client::{
-    clients::{PKSigningClient, QueryClient},
-    BoundEthInterface, CallFunctionArgs, EthInterface,
+    clients::{FallbackEthClient, QueryClient},
+    CallFunctionArgs, EthInterface,
 };
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
@@ -59,12 +62,17 @@ use crate::{
         execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
         healthcheck::HealthCheckHandle,
         tree::TreeApiHttpClient,
-        tx_sender::{ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig},
+        tx_sender::{
+            admission,
+            gas_estimates::{GasEstimationStatsUpdater, GasEstimationTracker},
+            ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig,
+        },
         web3::{self, state::InternalApiConfig, Namespace},
     },
     basic_witness_input_producer::BasicWitnessInputProducer,
     commitment_generator::CommitmentGenerator,
-    eth_sender::{Aggregator, EthTxAggregator, EthTxManager},
+    da_dispatcher::{create_da_client, DataAvailabilityDispatcher},
+    eth_sender::{Aggregator, EthTxAggregator, EthTxManager, OperatorAccounts},
     eth_watch::start_eth_watch,
     house_keeper::{
         blocks_state_reporter::L1BatchMetricsReporter,
@@ -75,10 +83,10 @@ use crate::{
         fri_scheduler_circuit_queuer::SchedulerCircuitQueuer,
         fri_witness_generator_jobs_retry_manager::FriWitnessGeneratorJobRetryManager,
         fri_witness_generator_queue_monitor::FriWitnessGeneratorStatsReporter,
-        periodic_job::PeriodicJob,
+        periodic_job::PeriodicJob, priority_queue_monitor::PriorityQueueMonitor,
         waiting_to_queued_fri_witness_job_mover::WaitingToQueuedFriWitnessJobMover,
     },
-    l1_gas_price::GasAdjusterSingleton,
+    l1_gas_price::{GasAdjusterSingleton, L1FeeStatisticsProvider},
     metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
     metrics::{InitStage, APP_METRICS},
     state_keeper::{
@@ -92,6 +100,7 @@ pub mod block_reverter;
 pub mod commitment_generator;
 pub mod consensus;
 pub mod consistency_checker;
+pub mod da_dispatcher;
 pub mod eth_sender;
 pub mod eth_watch;
 pub mod fee_model;
@@ -99,6 +108,7 @@ pub mod gas_tracker;
 pub mod genesis;
 pub mod house_keeper;
 pub mod l1_gas_price;
+pub mod l1_recovery;
 pub mod metadata_calculator;
 mod metrics;
 pub mod proof_data_handler;
@@ -262,6 +272,8 @@ pub enum Component {
     Consensus,
     /// Component generating commitment for L1 batches.
     CommitmentGenerator,
+    /// Component publishing L1 batch pubdata to an external DA layer and tracking its inclusion.
+    DADispatcher,
 }
 
 #[derive(Debug)]
@@ -298,6 +310,7 @@ impl FromStr for Components {
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
             "consensus" => Ok(Components(vec![Component::Consensus])),
             "commitment_generator" => Ok(Components(vec![Component::CommitmentGenerator])),
+            "da_dispatcher" => Ok(Components(vec![Component::DADispatcher])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
@@ -367,25 +380,36 @@ pub async fn initialize_components(
         .clone()
         .context("circuit_breaker_config")?;
 
-    let circuit_breakers =
-        circuit_breakers_for_components(components, &postgres_config, &circuit_breaker_config)
-            .await
-            .context("circuit_breakers_for_components")?;
-    let (circuit_breaker_checker, circuit_breaker_error) =
-        CircuitBreakerChecker::new(circuit_breakers, &circuit_breaker_config);
-    circuit_breaker_checker.check().await.unwrap_or_else(|err| {
-        panic!("Circuit breaker triggered: {}", err);
-    });
-
-    let query_client = QueryClient::new(&eth_client_config.web3_url).unwrap();
+    let query_client: Arc<dyn EthInterface> = Arc::new(
+        FallbackEthClient::from_config(&eth_client_config)
+            .context("FallbackEthClient::from_config()")?,
+    );
     let gas_adjuster_config = configs.gas_adjuster_config.context("gas_adjuster_config")?;
 
     let eth_sender_config = configs
         .eth_sender_config
         .clone()
         .context("eth_sender_config")?;
+
+    let circuit_breakers = circuit_breakers_for_components(
+        components,
+        &postgres_config,
+        &circuit_breaker_config,
+        &eth_sender_config,
+        &contracts_config,
+        &eth_client_config,
+        &query_client,
+    )
+    .await
+    .context("circuit_breakers_for_components")?;
+    let (circuit_breaker_checker, circuit_breaker_error) =
+        CircuitBreakerChecker::new(circuit_breakers, &circuit_breaker_config);
+    circuit_breaker_checker.check().await.unwrap_or_else(|err| {
+        panic!("Circuit breaker triggered: {}", err);
+    });
+
     let mut gas_adjuster = GasAdjusterSingleton::new(
-        eth_client_config.web3_url.clone(),
+        eth_client_config.clone(),
         gas_adjuster_config,
         eth_sender_config.sender.pubdata_sending_mode,
     );
@@ -415,6 +439,26 @@ pub async fn initialize_components(
         tokio::spawn(circuit_breaker_checker.run(stop_receiver.clone())),
     ];
 
+    let object_store_config = configs
+        .object_store_config
+        .clone()
+        .context("object_store_config")?;
+    let store_factory = ObjectStoreFactory::new(object_store_config);
+
+    // The mempool is shared by the state keeper and the `txpool` API namespace, so it's created before both of them.
+    let mempool = if components.contains(&Component::StateKeeper) {
+        let mempool_config = configs.mempool_config.clone().context("mempool_config")?;
+        let mut storage = connection_pool
+            .connection()
+            .await
+            .context("Access storage to build mempool")?;
+        let mempool = MempoolGuard::from_storage(&mut storage, &mempool_config).await;
+        mempool.register_metrics();
+        Some(mempool)
+    } else {
+        None
+    };
+
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
         || components.contains(&Component::ContractVerificationApi)
@@ -436,6 +480,32 @@ pub async fn initialize_components(
             &contracts_config,
         );
 
+        // The tracker is shared by HTTP and WS APIs so that estimates made via one server can be matched with
+        // transactions submitted via another one.
+        let gas_estimates = if api_config.web3_json_rpc.gas_estimation_stats_enabled
+            && (components.contains(&Component::HttpApi) || components.contains(&Component::WsApi))
+        {
+            let tracker = Arc::new(GasEstimationTracker::new(
+                connection_pool.clone(),
+                &api_config.web3_json_rpc,
+            ));
+            let updater = GasEstimationStatsUpdater::new(tracker.clone(), 10_000);
+            task_futures.push(tokio::spawn(updater.run(stop_receiver.clone())));
+            Some(tracker)
+        } else {
+            None
+        };
+
+        // Blobs are only archived if pubdata is published in blobs.
+        let blob_store = if eth_sender_config.sender.pubdata_sending_mode
+            == PubdataSendingMode::Blobs
+            && (components.contains(&Component::HttpApi) || components.contains(&Component::WsApi))
+        {
+            Some(store_factory.create_store().await)
+        } else {
+            None
+        };
+
         // Lazily initialize storage caches only when they are needed (e.g., skip their initialization
         // if we only run the explorer APIs). This is required because the cache update task will
         // terminate immediately if storage caches are dropped, which will lead to the (unexpected)
@@ -477,6 +547,9 @@ pub async fn initialize_components(
                 batch_fee_input_provider,
                 state_keeper_config.save_call_traces,
                 storage_caches.clone().unwrap(),
+                mempool.clone(),
+                gas_estimates.clone(),
+                blob_store.clone(),
             )
             .await
             .context("run_http_api")?;
@@ -524,6 +597,9 @@ pub async fn initialize_components(
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 storage_caches,
+                mempool.clone(),
+                gas_estimates.clone(),
+                blob_store.clone(),
             )
             .await
             .context("run_ws_api")?;
@@ -551,12 +627,6 @@ pub async fn initialize_components(
         }
     }
 
-    let object_store_config = configs
-        .object_store_config
-        .clone()
-        .context("object_store_config")?;
-    let store_factory = ObjectStoreFactory::new(object_store_config);
-
     if components.contains(&Component::StateKeeper) {
         let started_at = Instant::now();
         tracing::info!("initializing State Keeper");
@@ -580,6 +650,7 @@ pub async fn initialize_components(
             &configs.network_config.clone().context("network_config")?,
             &db_config,
             &configs.mempool_config.clone().context("mempool_config")?,
+            mempool.context("mempool is not initialized")?,
             batch_fee_input_provider,
             store_factory.create_store().await,
             stop_receiver.clone(),
@@ -649,7 +720,7 @@ pub async fn initialize_components(
             start_eth_watch(
                 eth_watch_config,
                 eth_watch_pool,
-                Arc::new(query_client.clone()),
+                query_client.clone(),
                 main_zksync_contract_address,
                 governance,
                 stop_receiver.clone(),
@@ -674,11 +745,18 @@ pub async fn initialize_components(
             .eth_sender_config
             .clone()
             .context("eth_sender_config")?;
-        let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_client_blobs_addr =
-            PKSigningClient::from_config_blobs(&eth_sender, &contracts_config, &eth_client_config)
-                .map(|k| k.sender_account());
+        let operator_accounts =
+            OperatorAccounts::from_config(&eth_sender, &contracts_config, &eth_client_config)
+                .context("OperatorAccounts::from_config()")?;
+        let l1_fee_statistics = if eth_sender.sender.l1_fee_postpone_percentile.is_some() {
+            let gas_adjuster = gas_adjuster
+                .get_or_init()
+                .await
+                .context("gas_adjuster.get_or_init()")?;
+            Some(gas_adjuster as Arc<dyn L1FeeStatisticsProvider>)
+        } else {
+            None
+        };
 
         let eth_tx_aggregator_actor = EthTxAggregator::new(
             eth_sender_pool,
@@ -686,10 +764,11 @@ pub async fn initialize_components(
             Aggregator::new(
                 eth_sender.sender.clone(),
                 store_factory.create_store().await,
-                eth_client_blobs_addr.is_some(),
+                eth_sender.sender.private_key_blobs().is_some(),
                 eth_sender.sender.pubdata_sending_mode.into(),
+                l1_fee_statistics,
             ),
-            Arc::new(eth_client),
+            operator_accounts,
             contracts_config.validator_timelock_addr,
             contracts_config.l1_multicall3_addr,
             main_zksync_contract_address,
@@ -698,7 +777,6 @@ pub async fn initialize_components(
                 .as_ref()
                 .context("network_config")?
                 .zksync_network_id,
-            eth_client_blobs_addr,
         )
         .await;
         task_futures.push(tokio::spawn(
@@ -720,10 +798,9 @@ pub async fn initialize_components(
             .eth_sender_config
             .clone()
             .context("eth_sender_config")?;
-        let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_client_blobs =
-            PKSigningClient::from_config_blobs(&eth_sender, &contracts_config, &eth_client_config);
+        let operator_accounts =
+            OperatorAccounts::from_config(&eth_sender, &contracts_config, &eth_client_config)
+                .context("OperatorAccounts::from_config()")?;
         let eth_tx_manager_actor = EthTxManager::new(
             eth_manager_pool,
             eth_sender.sender,
@@ -731,8 +808,7 @@ pub async fn initialize_components(
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?,
-            Arc::new(eth_client),
-            eth_client_blobs.map(|c| Arc::new(c) as Arc<dyn BoundEthInterface>),
+            operator_accounts,
         );
         task_futures.extend([tokio::spawn(
             eth_tx_manager_actor.run(stop_receiver.clone()),
@@ -772,9 +848,15 @@ pub async fn initialize_components(
     }
 
     if components.contains(&Component::Housekeeper) {
-        add_house_keeper_to_task_futures(configs, &mut task_futures, stop_receiver.clone())
-            .await
-            .context("add_house_keeper_to_task_futures()")?;
+        add_house_keeper_to_task_futures(
+            configs,
+            &mut task_futures,
+            &app_health,
+            query_client.clone(),
+            stop_receiver.clone(),
+        )
+        .await
+        .context("add_house_keeper_to_task_futures()")?;
     }
 
     if components.contains(&Component::ProofDataHandler) {
@@ -806,6 +888,23 @@ pub async fn initialize_components(
         ));
     }
 
+    if components.contains(&Component::DADispatcher) {
+        let da_dispatcher_config = configs
+            .da_dispatcher_config
+            .clone()
+            .context("da_dispatcher_config")?;
+        let da_dispatcher_pool = ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build da_dispatcher_pool")?;
+        let da_client = create_da_client(&da_dispatcher_config)
+            .await
+            .context("create_da_client()")?;
+        let da_dispatcher =
+            DataAvailabilityDispatcher::new(da_dispatcher_pool, da_dispatcher_config, da_client);
+        task_futures.push(tokio::spawn(da_dispatcher.run(stop_receiver.clone())));
+    }
+
     // Run healthcheck server for all components.
     let db_health_check = ConnectionPoolHealthCheck::new(replica_connection_pool);
     app_health.insert_custom_component(Arc::new(db_health_check));
@@ -832,6 +931,7 @@ async fn add_state_keeper_to_task_futures(
     network_config: &NetworkConfig,
     db_config: &DBConfig,
     mempool_config: &MempoolConfig,
+    mempool: MempoolGuard,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     object_store: Arc<dyn ObjectStore>,
     stop_receiver: watch::Receiver<bool>,
@@ -841,15 +941,6 @@ async fn add_state_keeper_to_task_futures(
         .build()
         .await
         .context("failed to build state_keeper_pool")?;
-    let mempool = {
-        let mut storage = state_keeper_pool
-            .connection()
-            .await
-            .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config.capacity).await;
-        mempool.register_metrics();
-        mempool
-    };
 
     let miniblock_sealer_pool = pool_builder
         .build()
@@ -1026,6 +1117,8 @@ async fn add_basic_witness_input_producer_to_task_futures(
 async fn add_house_keeper_to_task_futures(
     configs: &TempConfigStore,
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    app_health: &AppHealthCheck,
+    eth_client: Arc<dyn EthInterface>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let house_keeper_config = configs
@@ -1070,6 +1163,27 @@ async fn add_house_keeper_to_task_futures(
     let task = l1_batch_metrics_reporter.run(stop_receiver.clone());
     task_futures.push(tokio::spawn(task));
 
+    let contracts_config = configs
+        .contracts_config
+        .as_ref()
+        .context("contracts_config")?;
+    // The circuit breaker config is only used for the deadline margin, so it is not required.
+    let deadline_margin = configs.circuit_breaker_config.as_ref().map_or(
+        Duration::from_secs(CircuitBreakerConfig::DEFAULT_PRIORITY_OP_DEADLINE_MARGIN_SEC),
+        CircuitBreakerConfig::priority_op_deadline_margin,
+    );
+    let priority_queue_monitor = PriorityQueueMonitor::new(
+        house_keeper_config.priority_queue_monitoring_interval_ms,
+        connection_pool.clone(),
+        eth_client,
+        contracts_config.diamond_proxy_addr,
+        zksync_contract(),
+        deadline_margin,
+    );
+    app_health.insert_component(priority_queue_monitor.health_check());
+    let task = priority_queue_monitor.run(stop_receiver.clone());
+    task_futures.push(tokio::spawn(task));
+
     // All FRI Prover related components are configured below.
     let fri_prover_config = configs
         .fri_prover_config
@@ -1185,15 +1299,25 @@ async fn build_tx_sender(
     master_pool: ConnectionPool<Core>,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     storage_caches: PostgresStorageCaches,
-) -> (TxSender, VmConcurrencyBarrier) {
+    gas_estimates: Option<Arc<GasEstimationTracker>>,
+) -> anyhow::Result<(TxSender, VmConcurrencyBarrier)> {
     let sequencer_sealer = SequencerSealer::new(state_keeper_config.clone());
     let master_pool_sink = MasterPoolSink::new(master_pool);
-    let tx_sender_builder = TxSenderBuilder::new(
+    let mut tx_sender_builder = TxSenderBuilder::new(
         tx_sender_config.clone(),
         replica_pool.clone(),
         Arc::new(master_pool_sink),
     )
     .with_sealer(Arc::new(sequencer_sealer));
+    let admission_policies = admission::build_policies(web3_json_config, replica_pool.clone())
+        .await
+        .context("failed building transaction admission policies")?;
+    for policy in admission_policies {
+        tx_sender_builder = tx_sender_builder.with_admission_policy(policy);
+    }
+    if let Some(tracker) = gas_estimates {
+        tx_sender_builder = tx_sender_builder.with_gas_estimates(tracker);
+    }
 
     let max_concurrency = web3_json_config.vm_concurrency_limit();
     let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
@@ -1209,7 +1333,7 @@ async fn build_tx_sender(
             storage_caches,
         )
         .await;
-    (tx_sender, vm_barrier)
+    Ok((tx_sender, vm_barrier))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1227,6 +1351,9 @@ async fn run_http_api(
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     with_debug_namespace: bool,
     storage_caches: PostgresStorageCaches,
+    mempool: Option<MempoolGuard>,
+    gas_estimates: Option<Arc<GasEstimationTracker>>,
+    blob_store: Option<Arc<dyn ObjectStore>>,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1236,14 +1363,18 @@ async fn run_http_api(
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        gas_estimates,
     )
-    .await;
+    .await?;
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
     if with_debug_namespace {
         namespaces.push(Namespace::Debug)
     }
     namespaces.push(Namespace::Snapshots);
+    if api_config.web3_json_rpc.txpool_namespace_enabled {
+        namespaces.push(Namespace::Txpool);
+    }
 
     let updaters_pool = ConnectionPool::<Core>::builder(postgres_config.replica_url()?, 2)
         .build()
@@ -1265,6 +1396,12 @@ async fn run_http_api(
         api_builder = api_builder.with_tree_api(tree_api.clone());
         app_health.insert_custom_component(tree_api);
     }
+    if let Some(mempool) = mempool {
+        api_builder = api_builder.with_mempool(mempool);
+    }
+    if let Some(blob_store) = blob_store {
+        api_builder = api_builder.with_blob_store(blob_store);
+    }
 
     let server_handles = api_builder
         .build()
@@ -1290,6 +1427,9 @@ async fn run_ws_api(
     replica_connection_pool: ConnectionPool<Core>,
     stop_receiver: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
+    mempool: Option<MempoolGuard>,
+    gas_estimates: Option<Arc<GasEstimationTracker>>,
+    blob_store: Option<Arc<dyn ObjectStore>>,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1299,8 +1439,9 @@ async fn run_ws_api(
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        gas_estimates,
     )
-    .await;
+    .await?;
     let last_miniblock_pool = ConnectionPool::<Core>::singleton(postgres_config.replica_url()?)
         .build()
         .await
@@ -1308,6 +1449,9 @@ async fn run_ws_api(
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
     namespaces.push(Namespace::Snapshots);
+    if api_config.web3_json_rpc.txpool_namespace_enabled {
+        namespaces.push(Namespace::Txpool);
+    }
 
     let mut api_builder =
         web3::ApiBuilder::jsonrpsee_backend(internal_api.clone(), replica_connection_pool)
@@ -1331,6 +1475,12 @@ async fn run_ws_api(
         api_builder = api_builder.with_tree_api(tree_api.clone());
         app_health.insert_custom_component(tree_api);
     }
+    if let Some(mempool) = mempool {
+        api_builder = api_builder.with_mempool(mempool);
+    }
+    if let Some(blob_store) = blob_store {
+        api_builder = api_builder.with_blob_store(blob_store);
+    }
 
     let server_handles = api_builder
         .build()
@@ -1346,6 +1496,10 @@ async fn circuit_breakers_for_components(
     components: &[Component],
     postgres_config: &PostgresConfig,
     circuit_breaker_config: &CircuitBreakerConfig,
+    eth_sender_config: &ETHSenderConfig,
+    contracts_config: &ContractsConfig,
+    eth_client_config: &ETHClientConfig,
+    eth_client: &Arc<dyn EthInterface>,
 ) -> anyhow::Result<Vec<Box<dyn CircuitBreaker>>> {
     let mut circuit_breakers: Vec<Box<dyn CircuitBreaker>> = Vec::new();
 
@@ -1360,6 +1514,49 @@ async fn circuit_breakers_for_components(
         circuit_breakers.push(Box::new(FailedL1TransactionChecker { pool }));
     }
 
+    if components.iter().any(|c| {
+        matches!(
+            c,
+            Component::EthWatcher | Component::EthTxAggregator | Component::EthTxManager
+        )
+    }) {
+        let pool = ConnectionPool::<Core>::singleton(postgres_config.replica_url()?)
+            .build()
+            .await
+            .context("failed to build a connection pool")?;
+        circuit_breakers.push(Box::new(FatalL1ReorgChecker { pool }));
+    }
+
+    if let Some(min_balance_gwei) = eth_sender_config.sender.min_operator_balance_gwei {
+        if components.contains(&Component::EthTxManager) {
+            let accounts = OperatorAccounts::from_config(
+                eth_sender_config,
+                contracts_config,
+                eth_client_config,
+            )
+            .context("OperatorAccounts::from_config()")?;
+            circuit_breakers.push(Box::new(OperatorBalanceChecker {
+                accounts: accounts
+                    .iter()
+                    .map(|account| account.gateway().clone())
+                    .collect(),
+                min_balance_gwei,
+            }));
+        }
+    }
+
+    if components
+        .iter()
+        .any(|c| matches!(c, Component::EthWatcher | Component::StateKeeper))
+    {
+        circuit_breakers.push(Box::new(PriorityOpsDeadlineChecker {
+            eth_client: eth_client.clone(),
+            diamond_proxy_addr: contracts_config.diamond_proxy_addr,
+            diamond_proxy_abi: zksync_contract(),
+            deadline_margin: circuit_breaker_config.priority_op_deadline_margin(),
+        }));
+    }
+
     if components.iter().any(|c| {
         matches!(
             c,
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use anyhow::Context as _;
 use async_trait::async_trait;
 use multivm::{
     interface::{
@@ -11,13 +12,20 @@ use multivm::{
     MultiVMTracer, VmInstance,
 };
 use once_cell::sync::OnceCell;
-use tokio::sync::{mpsc, watch};
+use tokio::{
+    runtime::Handle,
+    sync::{mpsc, watch},
+};
 use zksync_dal::{ConnectionPool, Core};
-use zksync_state::{RocksdbStorage, StorageView, WriteStorage};
-use zksync_types::{vm_trace::Call, Transaction, U256};
+use zksync_state::{PostgresStorage, ReadStorage, RocksdbStorage, StorageView, WriteStorage};
+use zksync_types::{vm_trace::Call, MiniblockNumber, Transaction, VmVersion, U256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
-use super::{BatchExecutor, BatchExecutorHandle, Command, TxExecutionResult};
+use super::{
+    pre_execution::{PreExecutionOptions, TxPreExecutor},
+    shadow::{ShadowVmHandle, ShadowVmOptions},
+    BatchExecutor, BatchExecutorHandle, Command, TxExecutionResult,
+};
 use crate::{
     metrics::{InteractionType, TxStage, APP_METRICS},
     state_keeper::{
@@ -37,6 +45,8 @@ pub struct MainBatchExecutor {
     upload_witness_inputs_to_gcs: bool,
     enum_index_migration_chunk_size: usize,
     optional_bytecode_compression: bool,
+    shadow_vm: Option<ShadowVmOptions>,
+    pre_execution: Option<PreExecutionOptions>,
 }
 
 impl MainBatchExecutor {
@@ -57,8 +67,72 @@ impl MainBatchExecutor {
             upload_witness_inputs_to_gcs,
             enum_index_migration_chunk_size,
             optional_bytecode_compression,
+            shadow_vm: None,
+            pre_execution: None,
         }
     }
+
+    /// Enables shadow execution of all L1 batches on the specified candidate VM version.
+    /// Shadow execution never influences the main execution; divergences are only reported.
+    pub fn with_shadow_vm(mut self, options: ShadowVmOptions) -> Self {
+        self.shadow_vm = Some(options);
+        self
+    }
+
+    /// Enables speculative pre-execution of upcoming transactions on worker threads.
+    pub fn with_pre_execution(mut self, options: PreExecutionOptions) -> Self {
+        self.pre_execution = Some(options);
+        self
+    }
+
+    /// Starts executing an L1 batch on top of Postgres storage as of `storage_miniblock_number` rather than
+    /// on top of the state keeper cache. This allows re-executing historical L1 batches; in this case,
+    /// `storage_miniblock_number` must be the last miniblock of the previous L1 batch. If `vm_version` is specified,
+    /// it overrides the VM version implied by the protocol version in `system_env`.
+    pub(crate) async fn init_batch_with_postgres_storage(
+        &self,
+        l1_batch_params: L1BatchEnv,
+        system_env: SystemEnv,
+        storage_miniblock_number: MiniblockNumber,
+        vm_version: Option<VmVersion>,
+    ) -> anyhow::Result<BatchExecutorHandle> {
+        let (commands_sender, commands_receiver) = mpsc::channel(1);
+        let executor = CommandReceiver {
+            save_call_traces: self.save_call_traces,
+            max_allowed_tx_gas_limit: self.max_allowed_tx_gas_limit,
+            optional_bytecode_compression: self.optional_bytecode_compression,
+            vm_version,
+            shadow_vm: None,
+            commands: commands_receiver,
+        };
+        let upload_witness_inputs_to_gcs = self.upload_witness_inputs_to_gcs;
+        // The connection is acquired before spawning the executor thread, so that connection errors
+        // are returned to the caller rather than panicking the thread.
+        let connection = self
+            .pool
+            .connection_tagged("state_keeper")
+            .await
+            .context("failed getting connection for Postgres storage")?;
+        let handle = tokio::task::spawn_blocking(move || {
+            let storage = PostgresStorage::new(
+                Handle::current(),
+                connection,
+                storage_miniblock_number,
+                true,
+            );
+            executor.run(
+                storage,
+                l1_batch_params,
+                system_env,
+                upload_witness_inputs_to_gcs,
+            )
+        });
+        Ok(BatchExecutorHandle {
+            handle,
+            commands: commands_sender,
+            pre_executor: None,
+        })
+    }
 }
 
 #[async_trait]
@@ -79,6 +153,32 @@ impl BatchExecutor for MainBatchExecutor {
             .await
             .expect("Failed synchronizing secondary state keeper storage")?;
 
+        drop(conn);
+
+        let pre_executor = self.pre_execution.map(|options| {
+            TxPreExecutor::new(
+                options,
+                self.max_allowed_tx_gas_limit,
+                secondary_storage.read_only_copy(),
+                l1_batch_params.clone(),
+                system_env.clone(),
+            )
+        });
+
+        let shadow_vm = self.shadow_vm.as_ref().map(|options| {
+            // The shadow VM starts from the state as of the last miniblock of the previous L1 batch,
+            // i.e., the same state the secondary storage is synchronized to.
+            let storage_miniblock_number = l1_batch_params.first_l2_block.number - 1;
+            ShadowVmHandle::spawn(
+                options,
+                self.pool.clone(),
+                l1_batch_params.clone(),
+                system_env.clone(),
+                MiniblockNumber(storage_miniblock_number),
+                self.optional_bytecode_compression,
+            )
+        });
+
         // Since we process `BatchExecutor` commands one-by-one (the next command is never enqueued
         // until a previous command is processed), capacity 1 is enough for the commands channel.
         let (commands_sender, commands_receiver) = mpsc::channel(1);
@@ -86,6 +186,8 @@ impl BatchExecutor for MainBatchExecutor {
             save_call_traces: self.save_call_traces,
             max_allowed_tx_gas_limit: self.max_allowed_tx_gas_limit,
             optional_bytecode_compression: self.optional_bytecode_compression,
+            vm_version: None,
+            shadow_vm,
             commands: commands_receiver,
         };
         let upload_witness_inputs_to_gcs = self.upload_witness_inputs_to_gcs;
@@ -101,6 +203,7 @@ impl BatchExecutor for MainBatchExecutor {
         Some(BatchExecutorHandle {
             handle,
             commands: commands_sender,
+            pre_executor,
         })
     }
 }
@@ -116,13 +219,16 @@ struct CommandReceiver {
     save_call_traces: bool,
     max_allowed_tx_gas_limit: U256,
     optional_bytecode_compression: bool,
+    vm_version: Option<VmVersion>,
+    /// Shadow VM mirroring all executed commands, if enabled.
+    shadow_vm: Option<ShadowVmHandle>,
     commands: mpsc::Receiver<Command>,
 }
 
 impl CommandReceiver {
-    pub(super) fn run(
+    pub(super) fn run<S: ReadStorage>(
         mut self,
-        secondary_storage: RocksdbStorage,
+        secondary_storage: S,
         l1_batch_params: L1BatchEnv,
         system_env: SystemEnv,
         upload_witness_inputs_to_gcs: bool,
@@ -131,24 +237,45 @@ impl CommandReceiver {
 
         let storage_view = StorageView::new(secondary_storage).to_rc_ptr();
 
-        let mut vm = VmInstance::new(l1_batch_params, system_env, storage_view.clone());
+        let mut vm = if let Some(vm_version) = self.vm_version {
+            VmInstance::new_with_specific_version(
+                l1_batch_params,
+                system_env,
+                storage_view.clone(),
+                vm_version,
+            )
+        } else {
+            VmInstance::new(l1_batch_params, system_env, storage_view.clone())
+        };
 
         while let Some(cmd) = self.commands.blocking_recv() {
             match cmd {
                 Command::ExecuteTx(tx, resp) => {
                     let result = self.execute_tx(&tx, &mut vm);
+                    if let Some(shadow_vm) = &mut self.shadow_vm {
+                        shadow_vm.execute_tx(&tx, &result);
+                    }
                     resp.send(result).unwrap();
                 }
                 Command::RollbackLastTx(resp) => {
                     self.rollback_last_tx(&mut vm);
+                    if let Some(shadow_vm) = &mut self.shadow_vm {
+                        shadow_vm.rollback_last_tx();
+                    }
                     resp.send(()).unwrap();
                 }
                 Command::StartNextMiniblock(l2_block_env, resp) => {
+                    if let Some(shadow_vm) = &mut self.shadow_vm {
+                        shadow_vm.start_next_miniblock(l2_block_env);
+                    }
                     self.start_next_miniblock(l2_block_env, &mut vm);
                     resp.send(()).unwrap();
                 }
                 Command::FinishBatch(resp) => {
                     let vm_block_result = self.finish_batch(&mut vm);
+                    if let Some(shadow_vm) = &mut self.shadow_vm {
+                        shadow_vm.finish_batch(&vm_block_result);
+                    }
                     let witness_block_state = if upload_witness_inputs_to_gcs {
                         Some(storage_view.borrow_mut().witness_block_state())
                     } else {
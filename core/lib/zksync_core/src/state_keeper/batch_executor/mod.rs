@@ -19,7 +19,12 @@ use crate::state_keeper::{
 #[cfg(test)]
 mod tests;
 
+use self::pre_execution::TxPreExecutor;
+pub use self::{pre_execution::PreExecutionOptions, shadow::ShadowVmOptions};
+
 pub mod main_executor;
+mod pre_execution;
+mod shadow;
 
 /// Representation of a transaction executed in the virtual machine.
 #[derive(Debug, Clone)]
@@ -71,6 +76,8 @@ pub trait BatchExecutor: 'static + Send + Sync + fmt::Debug {
 pub struct BatchExecutorHandle {
     handle: JoinHandle<()>,
     commands: mpsc::Sender<Command>,
+    /// Speculative pre-executor of upcoming transactions, if enabled.
+    pre_executor: Option<TxPreExecutor>,
 }
 
 impl BatchExecutorHandle {
@@ -78,7 +85,39 @@ impl BatchExecutorHandle {
     /// Can be used to inject an alternative batch executor implementation.
     #[cfg(test)]
     pub(super) fn from_raw(handle: JoinHandle<()>, commands: mpsc::Sender<Command>) -> Self {
-        Self { handle, commands }
+        Self {
+            handle,
+            commands,
+            pre_executor: None,
+        }
+    }
+
+    /// Returns the maximum number of upcoming transactions that should be supplied to [`Self::pre_execute()`].
+    /// Returns 0 if pre-execution is disabled.
+    pub(super) fn pre_execution_lookahead(&self) -> usize {
+        self.pre_executor
+            .as_ref()
+            .map_or(0, TxPreExecutor::lookahead)
+    }
+
+    /// Speculatively pre-executes the upcoming transactions in the background.
+    pub(super) fn pre_execute(&self, txs: Vec<Transaction>) {
+        if let Some(pre_executor) = &self.pre_executor {
+            pre_executor.pre_execute(txs);
+        }
+    }
+
+    /// Returns the rejection reason if the transaction is certain to be rejected by the VM based on its pre-execution.
+    /// In this case, the transaction must not be executed.
+    pub(super) fn certain_rejection(&self, tx: &Transaction) -> Option<Halt> {
+        self.pre_executor.as_ref()?.certain_rejection(tx)
+    }
+
+    #[cfg(test)]
+    pub(super) async fn wait_for_pre_execution(&self) {
+        if let Some(pre_executor) = &self.pre_executor {
+            pre_executor.wait_for_pre_execution().await;
+        }
     }
 
     pub(super) async fn execute_tx(&self, tx: Transaction) -> TxExecutionResult {
@@ -95,6 +134,9 @@ impl BatchExecutorHandle {
             .start();
         let res = response_receiver.await.unwrap();
         let elapsed = latency.observe();
+        if let Some(pre_executor) = &self.pre_executor {
+            pre_executor.observe_executed_tx(&res);
+        }
 
         if let TxExecutionResult::Success { tx_metrics, .. } = &res {
             let gas_per_nanosecond = tx_metrics.execution_metrics.computational_gas_used as f64
@@ -141,6 +183,9 @@ impl BatchExecutorHandle {
             .start();
         response_receiver.await.unwrap();
         latency.observe();
+        if let Some(pre_executor) = &self.pre_executor {
+            pre_executor.observe_rollback();
+        }
     }
 
     pub(super) async fn finish_batch(self) -> (FinishedL1Batch, Option<WitnessBlockState>) {
@@ -0,0 +1,465 @@
+//! Speculative pre-execution of upcoming mempool transactions.
+//!
+//! Transactions are pre-executed on worker threads on top of the state as of the start of the current L1 batch,
+//! using read-only copies of the state keeper RocksDB storage. This prefetches storage slots touched by transactions
+//! into the RocksDB block cache shared with the main batch executor. Additionally, a pre-executed transaction can be
+//! rejected without executing it in the main VM if its rejection is certain, i.e., if the main VM would reject
+//! the transaction with the same reason. This is the case if all of the following holds:
+//!
+//! - The transaction is rejected during account validation.
+//! - Validation adheres to the validation rules (i.e., it doesn't access block context and only accesses
+//!   storage slots related to the account); this is checked using the validation tracer.
+//! - None of the storage slots read during pre-execution were written in the current L1 batch.
+//! - The L1 batch has enough bootloader gas left to process the transaction.
+//!
+//! Since a rejected transaction is rolled back in the main VM, skipping its execution doesn't change the state of
+//! the main VM, so execution order and results of other transactions are not affected.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use multivm::{
+    interface::{
+        ExecutionResult, Halt, L1BatchEnv, SystemEnv, VmExecutionMode, VmExecutionResultAndLogs,
+        VmInterface,
+    },
+    tracers::validator::{ValidationTracer, ValidationTracerParams},
+    vm_latest::HistoryDisabled,
+    MultiVMTracer, VmInstance,
+};
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics};
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_state::{RocksdbStorage, StorageView, WriteStorage};
+use zksync_system_constants::SYSTEM_CONTEXT_ADDRESS;
+use zksync_types::{AccountTreeId, ExecuteTransactionCommon, StorageKey, Transaction, H256, U256};
+use zksync_utils::u256_to_h256;
+
+use super::TxExecutionResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+enum PreExecutionOutcome {
+    /// Pre-execution has not finished by the time the transaction was executed.
+    NotReady,
+    /// Transaction is not certain to be rejected.
+    Executed,
+    /// Transaction was rejected during pre-execution, but the rejection cannot be relied upon.
+    UncertainRejection,
+    /// Transaction was rejected without executing it in the main VM.
+    Rejected,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "state_keeper_pre_execution")]
+struct PreExecutionMetrics {
+    /// Latency of pre-executing a single transaction.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    latency: Histogram<Duration>,
+    /// Number of pre-executed transactions.
+    pre_executed_txs: Counter,
+    /// Outcomes of pre-execution for transactions handed to the state keeper.
+    outcomes: Family<PreExecutionOutcome, Counter>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<PreExecutionMetrics> = vise::Global::new();
+
+/// Options for speculative pre-execution of mempool transactions.
+#[derive(Debug, Clone, Copy)]
+pub struct PreExecutionOptions {
+    /// Maximum number of transactions pre-executed concurrently.
+    pub lookahead: usize,
+    /// Computational gas limit for account validation.
+    pub validation_computational_gas_limit: u32,
+}
+
+impl PreExecutionOptions {
+    /// Extracts pre-execution options from the state keeper config. Returns `None` if pre-execution is disabled.
+    pub fn from_config(config: &StateKeeperConfig) -> Option<Self> {
+        let lookahead = config
+            .pre_execution_lookahead
+            .filter(|&lookahead| lookahead > 0)?;
+        Some(Self {
+            lookahead,
+            validation_computational_gas_limit: config.validation_computational_gas_limit,
+        })
+    }
+}
+
+/// Rejection of a transaction observed during pre-execution.
+#[derive(Debug)]
+struct PreExecutedRejection {
+    reason: Halt,
+    /// Storage slots read from the state as of the start of the L1 batch, excluding block context.
+    read_keys: HashSet<StorageKey>,
+    /// Bootloader gas spent during pre-execution (including L1 batch initialization, which makes it an overestimate).
+    bootloader_gas_used: u32,
+}
+
+#[derive(Debug)]
+enum PreExecutionStatus {
+    InProgress,
+    NotRejected,
+    Rejected(PreExecutedRejection),
+}
+
+/// Tracks the effects of transactions executed in the main VM.
+#[derive(Debug, Default)]
+struct MainExecutionState {
+    /// Storage slots written by the transactions in the L1 batch.
+    written_keys: HashSet<StorageKey>,
+    /// Bootloader gas remaining after the last executed transaction; `None` at the start of the L1 batch.
+    gas_remaining: Option<u32>,
+    /// Effects of the last executed transaction, which may still be rolled back.
+    last_tx: Option<(Vec<StorageKey>, u32)>,
+}
+
+impl MainExecutionState {
+    fn commit_last_tx(&mut self) {
+        if let Some((written_keys, gas_remaining)) = self.last_tx.take() {
+            self.written_keys.extend(written_keys);
+            self.gas_remaining = Some(gas_remaining);
+        }
+    }
+
+    fn record_tx(&mut self, tx_result: &VmExecutionResultAndLogs, gas_remaining: u32) {
+        self.commit_last_tx();
+        let written_keys = tx_result
+            .logs
+            .storage_logs
+            .iter()
+            .filter(|log| log.log_query.rw_flag)
+            .map(|log| {
+                StorageKey::new(
+                    AccountTreeId::new(log.log_query.address),
+                    u256_to_h256(log.log_query.key),
+                )
+            })
+            .collect();
+        self.last_tx = Some((written_keys, gas_remaining));
+    }
+
+    /// Checks whether the main VM would reject a transaction in the same way as during pre-execution.
+    fn confirms_rejection(&mut self, rejection: &PreExecutedRejection, tx_gas_limit: U256) -> bool {
+        // By the time a transaction is handed to the state keeper, the previous transaction is either committed
+        // or rolled back.
+        self.commit_last_tx();
+        let has_enough_gas = self.gas_remaining.map_or(true, |gas_remaining| {
+            U256::from(gas_remaining) > U256::from(rejection.bootloader_gas_used) + tx_gas_limit
+        });
+        let has_conflicts = rejection
+            .read_keys
+            .iter()
+            .any(|key| self.written_keys.contains(key));
+        has_enough_gas && !has_conflicts
+    }
+}
+
+/// Pre-executor of transactions for a single L1 batch.
+#[derive(Debug)]
+pub(super) struct TxPreExecutor {
+    options: PreExecutionOptions,
+    max_allowed_tx_gas_limit: U256,
+    storage: RocksdbStorage,
+    l1_batch_env: L1BatchEnv,
+    system_env: SystemEnv,
+    /// Pre-execution statuses keyed by the transaction hash.
+    results: Arc<Mutex<HashMap<H256, PreExecutionStatus>>>,
+    main_execution: Mutex<MainExecutionState>,
+}
+
+impl TxPreExecutor {
+    pub fn new(
+        options: PreExecutionOptions,
+        max_allowed_tx_gas_limit: U256,
+        storage: RocksdbStorage,
+        l1_batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+    ) -> Self {
+        Self {
+            options,
+            max_allowed_tx_gas_limit,
+            storage,
+            l1_batch_env,
+            system_env,
+            results: Arc::default(),
+            main_execution: Mutex::default(),
+        }
+    }
+
+    pub fn lookahead(&self) -> usize {
+        self.options.lookahead
+    }
+
+    /// Starts pre-executing the provided transactions, skipping ones that are already pre-executed. Never blocks.
+    pub fn pre_execute(&self, txs: Vec<Transaction>) {
+        let mut results = self
+            .results
+            .lock()
+            .expect("pre-execution results are poisoned");
+        // Drop stale results for transactions that were never handed to the state keeper (e.g., replaced ones).
+        if results.len() > self.options.lookahead * 16 {
+            results.retain(|_, status| matches!(status, PreExecutionStatus::InProgress));
+        }
+
+        let mut in_flight = results
+            .values()
+            .filter(|status| matches!(status, PreExecutionStatus::InProgress))
+            .count();
+        for tx in txs {
+            if in_flight >= self.options.lookahead {
+                break;
+            }
+            if !self.should_pre_execute(&tx) || results.contains_key(&tx.hash()) {
+                continue;
+            }
+            results.insert(tx.hash(), PreExecutionStatus::InProgress);
+            in_flight += 1;
+
+            let storage = self.storage.read_only_copy();
+            let l1_batch_env = self.l1_batch_env.clone();
+            let system_env = self.system_env.clone();
+            let gas_limit = self.options.validation_computational_gas_limit;
+            let results = self.results.clone();
+            tokio::task::spawn_blocking(move || {
+                let tx_hash = tx.hash();
+                let latency = METRICS.latency.start();
+                let rejection = pre_execute_tx(storage, l1_batch_env, system_env, tx, gas_limit);
+                latency.observe();
+                METRICS.pre_executed_txs.inc();
+                let mut results = results.lock().expect("pre-execution results are poisoned");
+                // The entry may have been removed if the transaction was already executed in the main VM.
+                if let Some(status) = results.get_mut(&tx_hash) {
+                    *status = match rejection {
+                        Some(rejection) => PreExecutionStatus::Rejected(rejection),
+                        None => PreExecutionStatus::NotRejected,
+                    };
+                }
+            });
+        }
+    }
+
+    /// Waits until all started pre-executions are finished.
+    #[cfg(test)]
+    pub async fn wait_for_pre_execution(&self) {
+        loop {
+            let in_progress = self
+                .results
+                .lock()
+                .expect("pre-execution results are poisoned")
+                .values()
+                .any(|status| matches!(status, PreExecutionStatus::InProgress));
+            if !in_progress {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    fn should_pre_execute(&self, tx: &Transaction) -> bool {
+        // Only L2 transactions can be rejected. Transactions with factory deps are excluded since their rejection reason
+        // depends on bytecode compression in the main VM, and ones with too big gas limit are rejected without execution anyway.
+        matches!(tx.common_data, ExecuteTransactionCommon::L2(_))
+            && tx.execute.factory_deps.as_ref().map_or(true, Vec::is_empty)
+            && tx.gas_limit() <= self.max_allowed_tx_gas_limit
+    }
+
+    /// Returns the reason to reject the transaction if it's certain to be rejected by the main VM. In this case,
+    /// the transaction must not be executed in the main VM.
+    pub fn certain_rejection(&self, tx: &Transaction) -> Option<Halt> {
+        let status = self
+            .results
+            .lock()
+            .expect("pre-execution results are poisoned")
+            .remove(&tx.hash());
+        let rejection = match status? {
+            PreExecutionStatus::Rejected(rejection) => rejection,
+            PreExecutionStatus::NotRejected => {
+                METRICS.outcomes[&PreExecutionOutcome::Executed].inc();
+                return None;
+            }
+            PreExecutionStatus::InProgress => {
+                METRICS.outcomes[&PreExecutionOutcome::NotReady].inc();
+                return None;
+            }
+        };
+
+        let confirmed = self
+            .main_execution
+            .lock()
+            .expect("main execution state is poisoned")
+            .confirms_rejection(&rejection, tx.gas_limit());
+        if !confirmed {
+            METRICS.outcomes[&PreExecutionOutcome::UncertainRejection].inc();
+            return None;
+        }
+        METRICS.outcomes[&PreExecutionOutcome::Rejected].inc();
+        Some(rejection.reason)
+    }
+
+    /// Records the result of executing a transaction in the main VM.
+    pub fn observe_executed_tx(&self, result: &TxExecutionResult) {
+        let mut main_execution = self
+            .main_execution
+            .lock()
+            .expect("main execution state is poisoned");
+        if let TxExecutionResult::Success {
+            tx_result,
+            gas_remaining,
+            ..
+        } = result
+        {
+            main_execution.record_tx(tx_result, *gas_remaining);
+        } else {
+            // Unsuccessful transactions are always rolled back.
+            main_execution.commit_last_tx();
+        }
+    }
+
+    /// Records a rollback of the last transaction executed in the main VM.
+    pub fn observe_rollback(&self) {
+        self.main_execution
+            .lock()
+            .expect("main execution state is poisoned")
+            .last_tx = None;
+    }
+}
+
+fn is_validation_failure(reason: &Halt) -> bool {
+    matches!(reason, Halt::ValidationFailed(_) | Halt::FromIsNotAnAccount)
+}
+
+/// Executes a transaction on top of the state as of the start of the L1 batch.
+fn pre_execute_tx(
+    storage: RocksdbStorage,
+    l1_batch_env: L1BatchEnv,
+    system_env: SystemEnv,
+    tx: Transaction,
+    validation_computational_gas_limit: u32,
+) -> Option<PreExecutedRejection> {
+    let ExecuteTransactionCommon::L2(common_data) = &tx.common_data else {
+        return None;
+    };
+    let validation_params = ValidationTracerParams {
+        user_address: common_data.initiator_address,
+        paymaster_address: common_data.paymaster_params.paymaster,
+        trusted_slots: HashSet::new(),
+        trusted_addresses: HashSet::new(),
+        trusted_address_slots: HashSet::new(),
+        computational_gas_limit: validation_computational_gas_limit,
+    };
+
+    let storage_view = StorageView::new(storage).to_rc_ptr();
+    let mut vm: VmInstance<_, HistoryDisabled> =
+        VmInstance::new(l1_batch_env, system_env, storage_view.clone());
+    let initial_gas = vm.gas_remaining();
+    let (tracer, violated_rule) = ValidationTracer::<HistoryDisabled>::new(validation_params);
+    vm.push_transaction(tx);
+    let result = vm.inspect(
+        vec![tracer.into_tracer_pointer()].into(),
+        VmExecutionMode::OneTx,
+    );
+
+    if violated_rule.get().is_some() {
+        return None;
+    }
+    let ExecutionResult::Halt { reason } = result.result else {
+        return None;
+    };
+    if !is_validation_failure(&reason) {
+        return None;
+    }
+    let bootloader_gas_used = initial_gas.saturating_sub(vm.gas_remaining());
+    drop(vm);
+
+    // Block context (which is stored in the system context contract) differs between pre-execution and the main VM.
+    // It's not read during validation (otherwise, the validation tracer would report a violated rule), so it's safe
+    // to ignore it.
+    let read_keys = storage_view
+        .borrow()
+        .read_storage_keys()
+        .keys()
+        .filter(|key| *key.address() != SYSTEM_CONTEXT_ADDRESS)
+        .copied()
+        .collect();
+    Some(PreExecutedRejection {
+        reason,
+        read_keys,
+        bootloader_gas_used,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use multivm::interface::VmRevertReason;
+    use zksync_types::Address;
+
+    use super::*;
+    use crate::state_keeper::tests::{create_execution_result, Query};
+
+    fn key(byte: u8) -> StorageKey {
+        StorageKey::new(
+            AccountTreeId::new(Address::default()),
+            H256::repeat_byte(byte),
+        )
+    }
+
+    fn rejection(read_keys: impl IntoIterator<Item = StorageKey>) -> PreExecutedRejection {
+        PreExecutedRejection {
+            reason: Halt::ValidationFailed(VmRevertReason::General {
+                msg: "invalid signature".to_owned(),
+                data: vec![],
+            }),
+            read_keys: read_keys.into_iter().collect(),
+            bootloader_gas_used: 1_000,
+        }
+    }
+
+    fn tx_result(written_key: StorageKey, read_key: StorageKey) -> VmExecutionResultAndLogs {
+        create_execution_result(
+            0,
+            [
+                (
+                    U256::from_big_endian(written_key.key().as_bytes()),
+                    Query::InitialWrite(U256::one()),
+                ),
+                (
+                    U256::from_big_endian(read_key.key().as_bytes()),
+                    Query::Read(U256::one()),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn rejection_is_confirmed_without_conflicts() {
+        let mut state = MainExecutionState::default();
+        assert!(state.confirms_rejection(&rejection([key(1), key(2)]), U256::from(1_000)));
+
+        state.record_tx(&tx_result(key(1), key(3)), 1_000_000);
+        // The last transaction is committed once the next transaction is handed to the state keeper.
+        assert!(!state.confirms_rejection(&rejection([key(1)]), U256::from(1_000)));
+        assert!(state.confirms_rejection(&rejection([key(2), key(3)]), U256::from(1_000)));
+    }
+
+    #[test]
+    fn rolled_back_txs_are_ignored() {
+        let mut state = MainExecutionState::default();
+        state.record_tx(&tx_result(key(1), key(2)), 1_000_000);
+        state.last_tx = None; // Emulates a rollback
+        assert!(state.confirms_rejection(&rejection([key(1)]), U256::from(1_000)));
+        assert!(state.written_keys.is_empty());
+        assert_eq!(state.gas_remaining, None);
+    }
+
+    #[test]
+    fn rejection_is_not_confirmed_without_enough_gas() {
+        let mut state = MainExecutionState::default();
+        state.record_tx(&tx_result(key(1), key(2)), 10_000);
+        assert!(state.confirms_rejection(&rejection([key(3)]), U256::from(1_000)));
+        assert!(!state.confirms_rejection(&rejection([key(3)]), U256::from(9_000)));
+    }
+}
@@ -0,0 +1,553 @@
+//! Shadow execution of L1 batches on a candidate VM version.
+
+use std::{
+    fs,
+    io::{BufWriter, Write as _},
+    path::PathBuf,
+};
+
+use anyhow::Context as _;
+use multivm::{
+    interface::{
+        ExecutionResult, FinishedL1Batch, Halt, L1BatchEnv, L2BlockEnv, SystemEnv,
+        VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled,
+    },
+    vm_latest::HistoryEnabled,
+    VmInstance,
+};
+use serde::Serialize;
+use tokio::{runtime::Handle, sync::mpsc};
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Metrics};
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_dal::{ConnectionPool, Core};
+use zksync_state::{PostgresStorage, StorageView, WriteStorage};
+use zksync_types::{
+    L1BatchNumber, MiniblockNumber, ProtocolVersionId, Transaction, VmVersion, H256,
+};
+
+use super::TxExecutionResult;
+
+/// Kind of divergence between the main and the shadow VM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet, Serialize)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+enum DivergenceKind {
+    Result,
+    GasUsed,
+    Refunds,
+    Events,
+    L2ToL1Logs,
+    StorageLogs,
+    BatchState,
+    BootloaderMemory,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "state_keeper_shadow_vm")]
+struct ShadowVmMetrics {
+    /// Number of transactions executed by the shadow VM.
+    executed_txs: Counter,
+    /// Number of L1 batches finished by the shadow VM.
+    finished_batches: Counter,
+    /// Number of divergences between the main and the shadow VM.
+    divergences: Family<DivergenceKind, Counter>,
+    /// Number of L1 batches for which shadow execution was disabled because the shadow VM lagged behind.
+    disabled_batches: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ShadowVmMetrics> = vise::Global::new();
+
+/// Options for shadow execution of L1 batches.
+///
+/// In the shadow mode, each transaction executed by the state keeper is re-executed on the candidate VM version
+/// in a separate thread, and the results are compared. Divergences are reported via metrics and, optionally,
+/// appended to a JSON Lines file. The shadow VM never affects the main execution: if it lags behind, shadow
+/// execution is disabled until the end of the L1 batch.
+#[derive(Debug, Clone)]
+pub struct ShadowVmOptions {
+    /// Candidate VM version.
+    pub vm_version: VmVersion,
+    /// Path to the file to append divergences to.
+    pub divergence_log_path: Option<PathBuf>,
+}
+
+impl ShadowVmOptions {
+    /// Capacity of the command queue for the shadow VM. If it is exceeded, shadow execution for the L1 batch is disabled.
+    const COMMAND_CAPACITY: usize = 1_000;
+
+    /// Extracts shadow VM options from the state keeper config. Returns `Ok(None)` if shadow execution is disabled.
+    pub fn from_config(config: &StateKeeperConfig) -> anyhow::Result<Option<Self>> {
+        let Some(protocol_version) = config.shadow_vm_protocol_version else {
+            return Ok(None);
+        };
+        let protocol_version = ProtocolVersionId::try_from(protocol_version)
+            .with_context(|| format!("unknown shadow VM protocol version: {protocol_version}"))?;
+        Ok(Some(Self {
+            vm_version: protocol_version.into(),
+            divergence_log_path: config
+                .shadow_vm_divergence_log_path
+                .as_ref()
+                .map(Into::into),
+        }))
+    }
+}
+
+#[derive(Debug)]
+enum ShadowCommand {
+    ExecuteTx {
+        tx: Box<Transaction>,
+        expected: Box<TxExecutionResult>,
+    },
+    RollbackLastTx,
+    StartNextMiniblock(L2BlockEnv),
+    FinishBatch(Box<FinishedL1Batch>),
+}
+
+/// Handle for the shadow VM held by the main batch executor.
+#[derive(Debug)]
+pub(super) struct ShadowVmHandle {
+    l1_batch_number: L1BatchNumber,
+    /// Set to `None` once shadow execution is disabled for the batch.
+    commands: Option<mpsc::Sender<ShadowCommand>>,
+}
+
+impl ShadowVmHandle {
+    /// Spawns the shadow VM executing on top of Postgres storage as of `storage_miniblock_number`.
+    pub fn spawn(
+        options: &ShadowVmOptions,
+        pool: ConnectionPool<Core>,
+        l1_batch_params: L1BatchEnv,
+        system_env: SystemEnv,
+        storage_miniblock_number: MiniblockNumber,
+        optional_bytecode_compression: bool,
+    ) -> Self {
+        let l1_batch_number = l1_batch_params.number;
+        let (commands_sender, commands_receiver) = mpsc::channel(ShadowVmOptions::COMMAND_CAPACITY);
+        let shadow_vm = ShadowVm {
+            l1_batch_number,
+            vm_version: options.vm_version,
+            optional_bytecode_compression,
+            divergence_log: DivergenceLog::new(options.divergence_log_path.clone()),
+            commands: commands_receiver,
+        };
+        tokio::task::spawn_blocking(move || {
+            let rt_handle = Handle::current();
+            let connection = match rt_handle.block_on(pool.connection_tagged("shadow_vm")) {
+                Ok(connection) => connection,
+                Err(err) => {
+                    tracing::warn!("Failed getting connection for shadow VM: {err:#}");
+                    return;
+                }
+            };
+            let storage =
+                PostgresStorage::new(rt_handle, connection, storage_miniblock_number, true);
+            shadow_vm.run(storage, l1_batch_params, system_env);
+        });
+
+        Self {
+            l1_batch_number,
+            commands: Some(commands_sender),
+        }
+    }
+
+    fn send(&mut self, command: ShadowCommand) {
+        let Some(commands) = &self.commands else {
+            return;
+        };
+        if let Err(err) = commands.try_send(command) {
+            let reason = match err {
+                mpsc::error::TrySendError::Full(_) => "shadow VM lags behind",
+                mpsc::error::TrySendError::Closed(_) => "shadow VM has terminated",
+            };
+            tracing::warn!(
+                "Disabling shadow execution for L1 batch #{}: {reason}",
+                self.l1_batch_number
+            );
+            METRICS.disabled_batches.inc();
+            self.commands = None;
+        }
+    }
+
+    pub fn execute_tx(&mut self, tx: &Transaction, expected: &TxExecutionResult) {
+        if self.commands.is_some() {
+            self.send(ShadowCommand::ExecuteTx {
+                tx: Box::new(tx.clone()),
+                expected: Box::new(expected.clone()),
+            });
+        }
+    }
+
+    pub fn rollback_last_tx(&mut self) {
+        self.send(ShadowCommand::RollbackLastTx);
+    }
+
+    pub fn start_next_miniblock(&mut self, l2_block_env: L2BlockEnv) {
+        self.send(ShadowCommand::StartNextMiniblock(l2_block_env));
+    }
+
+    pub fn finish_batch(&mut self, expected: &FinishedL1Batch) {
+        if self.commands.is_some() {
+            self.send(ShadowCommand::FinishBatch(Box::new(expected.clone())));
+        }
+    }
+}
+
+/// Summary of execution results persisted in the divergence log.
+#[derive(Debug, Serialize)]
+struct ExecutionSummary {
+    result: String,
+    gas_used: u32,
+    gas_refunded: u32,
+    operator_suggested_refund: u32,
+    events: usize,
+    l2_to_l1_logs: usize,
+    storage_logs: usize,
+}
+
+impl ExecutionSummary {
+    fn new(result: &ExecutionResult, logs_and_stats: Option<&VmExecutionResultAndLogs>) -> Self {
+        Self {
+            result: format!("{result:?}"),
+            gas_used: logs_and_stats.map_or(0, |res| res.statistics.gas_used),
+            gas_refunded: logs_and_stats.map_or(0, |res| res.refunds.gas_refunded),
+            operator_suggested_refund: logs_and_stats
+                .map_or(0, |res| res.refunds.operator_suggested_refund),
+            events: logs_and_stats.map_or(0, |res| res.logs.events.len()),
+            l2_to_l1_logs: logs_and_stats.map_or(0, |res| {
+                res.logs.user_l2_to_l1_logs.len() + res.logs.system_l2_to_l1_logs.len()
+            }),
+            storage_logs: logs_and_stats.map_or(0, |res| res.logs.storage_logs.len()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DivergenceRecord<'a> {
+    l1_batch_number: L1BatchNumber,
+    shadow_vm_version: String,
+    /// `None` for divergences in the batch finalization.
+    tx_hash: Option<H256>,
+    kinds: &'a [DivergenceKind],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected: Option<ExecutionSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual: Option<ExecutionSummary>,
+}
+
+/// Persisted log of divergences in the JSON Lines format.
+#[derive(Debug)]
+struct DivergenceLog {
+    path: Option<PathBuf>,
+    writer: Option<BufWriter<fs::File>>,
+}
+
+impl DivergenceLog {
+    fn new(path: Option<PathBuf>) -> Self {
+        Self { path, writer: None }
+    }
+
+    fn write(&mut self, record: &DivergenceRecord<'_>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = (|| {
+            if self.writer.is_none() {
+                let file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .context("failed opening file")?;
+                self.writer = Some(BufWriter::new(file));
+            }
+            let writer = self.writer.as_mut().unwrap();
+            serde_json::to_writer(&mut *writer, record).context("failed serializing record")?;
+            writeln!(writer)?;
+            writer.flush()?;
+            anyhow::Ok(())
+        })();
+        if let Err(err) = result {
+            tracing::warn!(
+                "Failed writing shadow VM divergence to `{}`: {err:#}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Shadow VM executing commands mirrored from the main batch executor.
+#[derive(Debug)]
+struct ShadowVm {
+    l1_batch_number: L1BatchNumber,
+    vm_version: VmVersion,
+    optional_bytecode_compression: bool,
+    divergence_log: DivergenceLog,
+    commands: mpsc::Receiver<ShadowCommand>,
+}
+
+impl ShadowVm {
+    fn run(
+        mut self,
+        storage: PostgresStorage<'_>,
+        l1_batch_params: L1BatchEnv,
+        system_env: SystemEnv,
+    ) {
+        tracing::info!(
+            "Starting shadow execution of L1 batch #{} using VM {:?}",
+            self.l1_batch_number,
+            self.vm_version
+        );
+        let storage_view = StorageView::new(storage).to_rc_ptr();
+        let mut vm = VmInstance::new_with_specific_version(
+            l1_batch_params,
+            system_env,
+            storage_view,
+            self.vm_version,
+        );
+
+        while let Some(command) = self.commands.blocking_recv() {
+            match command {
+                ShadowCommand::ExecuteTx { tx, expected } => {
+                    vm.make_snapshot();
+                    // Transactions with too big gas limit are rejected without execution.
+                    if !matches!(
+                        *expected,
+                        TxExecutionResult::RejectedByVm {
+                            reason: Halt::TooBigGasLimit
+                        }
+                    ) {
+                        let actual = self.execute_tx(&tx, &mut vm);
+                        METRICS.executed_txs.inc();
+                        self.compare_tx_results(tx.hash(), &expected, &actual);
+                    }
+                }
+                ShadowCommand::RollbackLastTx => vm.rollback_to_the_latest_snapshot(),
+                ShadowCommand::StartNextMiniblock(l2_block_env) => {
+                    vm.start_new_l2_block(l2_block_env);
+                }
+                ShadowCommand::FinishBatch(expected) => {
+                    let actual = vm.finish_batch();
+                    self.compare_batch_results(&expected, &actual);
+                    METRICS.finished_batches.inc();
+                    return;
+                }
+            }
+        }
+        tracing::info!(
+            "Shadow execution of L1 batch #{} was terminated before the batch was finished",
+            self.l1_batch_number
+        );
+    }
+
+    /// Executes a transaction in the same way as the main batch executor.
+    fn execute_tx<S: WriteStorage>(
+        &self,
+        tx: &Transaction,
+        vm: &mut VmInstance<S, HistoryEnabled>,
+    ) -> VmExecutionResultAndLogs {
+        if self.optional_bytecode_compression {
+            vm.make_snapshot();
+            if let (Ok(()), result) =
+                vm.execute_transaction_with_bytecode_compression(tx.clone(), true)
+            {
+                vm.pop_snapshot_no_rollback();
+                return result;
+            }
+            vm.rollback_to_the_latest_snapshot();
+            return vm
+                .execute_transaction_with_bytecode_compression(tx.clone(), false)
+                .1;
+        }
+
+        let (published_bytecodes, mut result) =
+            vm.execute_transaction_with_bytecode_compression(tx.clone(), true);
+        if published_bytecodes.is_err() {
+            result.result = ExecutionResult::Halt {
+                reason: Halt::FailedToPublishCompressedBytecodes,
+            };
+        }
+        result
+    }
+
+    fn compare_tx_results(
+        &mut self,
+        tx_hash: H256,
+        expected: &TxExecutionResult,
+        actual: &VmExecutionResultAndLogs,
+    ) {
+        let (expected_result, expected_logs) = match expected {
+            TxExecutionResult::Success { tx_result, .. } => {
+                (tx_result.result.clone(), Some(tx_result.as_ref()))
+            }
+            TxExecutionResult::RejectedByVm { reason } => (
+                ExecutionResult::Halt {
+                    reason: reason.clone(),
+                },
+                None,
+            ),
+            TxExecutionResult::BootloaderOutOfGasForTx => (
+                ExecutionResult::Halt {
+                    reason: Halt::BootloaderOutOfGas,
+                },
+                None,
+            ),
+        };
+
+        let mut kinds = vec![];
+        if expected_result != actual.result {
+            kinds.push(DivergenceKind::Result);
+        }
+        if let Some(expected) = expected_logs {
+            kinds.extend(compare_execution_results(expected, actual));
+        }
+        if kinds.is_empty() {
+            return;
+        }
+
+        tracing::warn!(
+            "Shadow VM diverged on transaction {tx_hash:?} in L1 batch #{}: {kinds:?}",
+            self.l1_batch_number
+        );
+        self.report(
+            Some(tx_hash),
+            &kinds,
+            Some(ExecutionSummary::new(&expected_result, expected_logs)),
+            Some(ExecutionSummary::new(&actual.result, Some(actual))),
+        );
+    }
+
+    fn compare_batch_results(&mut self, expected: &FinishedL1Batch, actual: &FinishedL1Batch) {
+        let mut kinds = vec![];
+        if expected.block_tip_execution_result.result != actual.block_tip_execution_result.result {
+            kinds.push(DivergenceKind::Result);
+        }
+        if expected.final_execution_state != actual.final_execution_state {
+            kinds.push(DivergenceKind::BatchState);
+        }
+        if expected.final_bootloader_memory != actual.final_bootloader_memory {
+            kinds.push(DivergenceKind::BootloaderMemory);
+        }
+        if kinds.is_empty() {
+            tracing::info!(
+                "Shadow execution of L1 batch #{} matches the main execution",
+                self.l1_batch_number
+            );
+            return;
+        }
+
+        tracing::warn!(
+            "Shadow VM diverged when finishing L1 batch #{}: {kinds:?}",
+            self.l1_batch_number
+        );
+        self.report(None, &kinds, None, None);
+    }
+
+    fn report(
+        &mut self,
+        tx_hash: Option<H256>,
+        kinds: &[DivergenceKind],
+        expected: Option<ExecutionSummary>,
+        actual: Option<ExecutionSummary>,
+    ) {
+        for &kind in kinds {
+            METRICS.divergences[&kind].inc();
+        }
+        self.divergence_log.write(&DivergenceRecord {
+            l1_batch_number: self.l1_batch_number,
+            shadow_vm_version: format!("{:?}", self.vm_version),
+            tx_hash,
+            kinds,
+            expected,
+            actual,
+        });
+    }
+}
+
+/// Returns the number of L1 batches finished by shadow VMs so far. Used in tests to wait for shadow execution.
+#[cfg(test)]
+pub(super) fn finished_batch_count() -> u64 {
+    METRICS.finished_batches.get()
+}
+
+fn compare_execution_results(
+    expected: &VmExecutionResultAndLogs,
+    actual: &VmExecutionResultAndLogs,
+) -> Vec<DivergenceKind> {
+    let mut kinds = vec![];
+    if expected.statistics.gas_used != actual.statistics.gas_used {
+        kinds.push(DivergenceKind::GasUsed);
+    }
+    if expected.refunds.gas_refunded != actual.refunds.gas_refunded
+        || expected.refunds.operator_suggested_refund != actual.refunds.operator_suggested_refund
+    {
+        kinds.push(DivergenceKind::Refunds);
+    }
+    if expected.logs.events != actual.logs.events {
+        kinds.push(DivergenceKind::Events);
+    }
+    if expected.logs.user_l2_to_l1_logs != actual.logs.user_l2_to_l1_logs
+        || expected.logs.system_l2_to_l1_logs != actual.logs.system_l2_to_l1_logs
+    {
+        kinds.push(DivergenceKind::L2ToL1Logs);
+    }
+    if expected.logs.storage_logs != actual.logs.storage_logs {
+        kinds.push(DivergenceKind::StorageLogs);
+    }
+    kinds
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{Address, VmEvent};
+
+    use super::*;
+    use crate::state_keeper::tests::create_execution_result;
+
+    #[test]
+    fn comparing_execution_results() {
+        let expected = create_execution_result(0, []);
+        assert!(compare_execution_results(&expected, &expected.clone()).is_empty());
+
+        let mut actual = expected.clone();
+        actual.statistics.gas_used += 1;
+        actual.refunds.gas_refunded += 1;
+        actual.logs.events.push(VmEvent {
+            address: Address::repeat_byte(1),
+            ..VmEvent::default()
+        });
+        assert_eq!(
+            compare_execution_results(&expected, &actual),
+            [
+                DivergenceKind::GasUsed,
+                DivergenceKind::Refunds,
+                DivergenceKind::Events
+            ]
+        );
+    }
+
+    #[test]
+    fn writing_divergence_log() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("divergences.jsonl");
+        let mut log = DivergenceLog::new(Some(path.clone()));
+        for i in 0..2 {
+            log.write(&DivergenceRecord {
+                l1_batch_number: L1BatchNumber(i),
+                shadow_vm_version: format!("{:?}", VmVersion::latest()),
+                tx_hash: Some(H256::repeat_byte(1)),
+                kinds: &[DivergenceKind::GasUsed],
+                expected: None,
+                actual: None,
+            });
+        }
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let records: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1]["l1_batch_number"], 1);
+        assert_eq!(records[1]["kinds"], serde_json::json!(["gas_used"]));
+    }
+}
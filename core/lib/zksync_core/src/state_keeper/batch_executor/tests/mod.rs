@@ -1,11 +1,24 @@
+use std::time::Duration;
+
 use assert_matches::assert_matches;
+use multivm::{
+    interface::{CurrentExecutionState, ExecutionResult},
+    vm_latest::VmExecutionLogs,
+};
+use tempfile::TempDir;
 use test_casing::test_casing;
 use zksync_dal::{ConnectionPool, Core};
 use zksync_test_account::Account;
-use zksync_types::{get_nonce_key, utils::storage_key_for_eth_balance, PriorityOpId};
+use zksync_types::{
+    get_nonce_key, utils::storage_key_for_eth_balance, ExecuteTransactionCommon, L2ChainId,
+    PriorityOpId, Transaction, VmVersion,
+};
 
 use self::tester::{AccountLoadNextExecutable, StorageSnapshot, TestConfig, Tester};
-use super::TxExecutionResult;
+use super::{
+    shadow::finished_batch_count, PreExecutionOptions, ShadowVmOptions, TxExecutionResult,
+};
+use crate::state_keeper::BatchReplayer;
 
 mod tester;
 
@@ -363,6 +376,162 @@ async fn execute_realistic_scenario() {
     executor.finish_batch().await;
 }
 
+/// Observable outcome of handing a transaction to the batch executor the same way the state keeper does.
+#[derive(Debug, PartialEq)]
+enum TxOutcome {
+    Rejected(String),
+    Executed {
+        result: ExecutionResult,
+        logs: VmExecutionLogs,
+        gas_refunded: u32,
+        operator_suggested_refund: u32,
+    },
+}
+
+/// Executes transactions in a single L1 batch, emulating the state keeper: upcoming transactions are pre-executed
+/// (if pre-execution is enabled), transactions certain to be rejected are skipped, and rejected transactions
+/// are rolled back. Returns transaction outcomes, the number of transactions rejected based on pre-execution
+/// and the final execution state of the batch.
+async fn execute_batch(
+    config: TestConfig,
+    alice: &Account,
+    txs: &[Transaction],
+) -> (Vec<TxOutcome>, usize, CurrentExecutionState) {
+    // The shadow VM (if enabled) needs a separate connection.
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(2).await;
+    let tester = Tester::with_config(connection_pool, config);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let executor = tester.create_batch_executor().await;
+
+    let mut outcomes = vec![];
+    let mut pre_execution_rejections = 0;
+    for (i, tx) in txs.iter().enumerate() {
+        let lookahead = executor.pre_execution_lookahead();
+        if lookahead > 0 {
+            executor.pre_execute(txs[i..].iter().take(lookahead).cloned().collect());
+            executor.wait_for_pre_execution().await;
+        }
+        if let Some(reason) = executor.certain_rejection(tx) {
+            pre_execution_rejections += 1;
+            outcomes.push(TxOutcome::Rejected(reason.to_string()));
+            continue;
+        }
+
+        match executor.execute_tx(tx.clone()).await {
+            TxExecutionResult::Success { tx_result, .. } => {
+                outcomes.push(TxOutcome::Executed {
+                    result: tx_result.result,
+                    logs: tx_result.logs,
+                    gas_refunded: tx_result.refunds.gas_refunded,
+                    operator_suggested_refund: tx_result.refunds.operator_suggested_refund,
+                });
+            }
+            TxExecutionResult::RejectedByVm { reason } => {
+                executor.rollback_last_tx().await;
+                outcomes.push(TxOutcome::Rejected(reason.to_string()));
+            }
+            TxExecutionResult::BootloaderOutOfGasForTx => {
+                panic!("Unexpected bootloader out of gas for transaction #{i}");
+            }
+        }
+    }
+    let (finished_batch, _) = executor.finish_batch().await;
+    (
+        outcomes,
+        pre_execution_rejections,
+        finished_batch.final_execution_state,
+    )
+}
+
+fn with_invalid_signature(mut tx: Transaction) -> Transaction {
+    let ExecuteTransactionCommon::L2(common_data) = &mut tx.common_data else {
+        unreachable!("not an L2 transaction");
+    };
+    common_data.signature = vec![1; 65];
+    tx
+}
+
+/// Checks that pre-execution of transactions doesn't influence execution results.
+#[tokio::test]
+async fn pre_execution_does_not_change_execution_results() {
+    let mut alice = Account::random();
+    let mut bob = Account::random();
+
+    let mut txs = vec![];
+    // Transaction with an invalid signature at the start of the batch, where its rejection is certain.
+    txs.push(with_invalid_signature(alice.execute()));
+    alice.nonce -= 1;
+    txs.push(alice.execute());
+    // Bob is not funded, so his transaction is rejected.
+    txs.push(bob.execute());
+    txs.push(alice.execute());
+    // Alice's nonce is updated in the batch, so this rejection must be confirmed by the main VM.
+    txs.push(with_invalid_signature(alice.execute()));
+    alice.nonce -= 1;
+    txs.push(alice.execute());
+
+    let (outcomes, pre_execution_rejections, final_state) =
+        execute_batch(TestConfig::new(), &alice, &txs).await;
+    assert_eq!(pre_execution_rejections, 0);
+    let rejected_count = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, TxOutcome::Rejected(_)))
+        .count();
+    assert_eq!(rejected_count, 3, "{outcomes:#?}");
+
+    let pre_execution = PreExecutionOptions {
+        lookahead: 4,
+        validation_computational_gas_limit: TestConfig::new().validation_computational_gas_limit,
+    };
+    let mut config = TestConfig::new();
+    config.pre_execution = Some(pre_execution);
+    let (outcomes_with_pre_execution, pre_execution_rejections, final_state_with_pre_execution) =
+        execute_batch(config, &alice, &txs).await;
+    assert!(pre_execution_rejections > 0);
+    assert_eq!(outcomes_with_pre_execution, outcomes);
+    assert_eq!(final_state_with_pre_execution, final_state);
+}
+
+/// Checks that shadow execution on the same VM version doesn't report divergences and doesn't influence
+/// execution results.
+#[tokio::test]
+async fn shadow_vm_does_not_change_execution_results() {
+    let mut alice = Account::random();
+    let mut bob = Account::random();
+    // Bob is not funded, so his transaction is rejected and rolled back.
+    let txs = vec![alice.execute(), bob.execute(), alice.execute()];
+
+    let (outcomes, _, final_state) = execute_batch(TestConfig::new(), &alice, &txs).await;
+
+    let temp_dir = TempDir::new().unwrap();
+    let divergence_log_path = temp_dir.path().join("divergences.jsonl");
+    let finished_shadow_batches = finished_batch_count();
+    let mut config = TestConfig::new();
+    config.shadow_vm = Some(ShadowVmOptions {
+        vm_version: VmVersion::latest(),
+        divergence_log_path: Some(divergence_log_path.clone()),
+    });
+    let (outcomes_with_shadow_vm, _, final_state_with_shadow_vm) =
+        execute_batch(config, &alice, &txs).await;
+    assert_eq!(outcomes_with_shadow_vm, outcomes);
+    assert_eq!(final_state_with_shadow_vm, final_state);
+
+    // The shadow VM runs in the background; wait until it finishes the batch.
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while finished_batch_count() == finished_shadow_batches {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("shadow VM didn't finish the batch");
+    assert!(
+        !divergence_log_path.exists(),
+        "{}",
+        std::fs::read_to_string(&divergence_log_path).unwrap()
+    );
+}
+
 /// Checks that we handle the bootloader out of gas error on execution phase.
 #[tokio::test]
 async fn bootloader_out_of_gas_for_any_tx() {
@@ -377,6 +546,8 @@ async fn bootloader_out_of_gas_for_any_tx() {
             max_allowed_tx_gas_limit: u32::MAX,
             validation_computational_gas_limit: u32::MAX,
             upload_witness_inputs_to_gcs: false,
+            pre_execution: None,
+            shadow_vm: None,
         },
     );
 
@@ -414,6 +585,8 @@ async fn bootloader_tip_out_of_gas() {
         max_allowed_tx_gas_limit: u32::MAX,
         validation_computational_gas_limit: u32::MAX,
         upload_witness_inputs_to_gcs: false,
+        pre_execution: None,
+        shadow_vm: None,
     });
 
     let second_executor = tester.create_batch_executor().await;
@@ -421,3 +594,24 @@ async fn bootloader_tip_out_of_gas() {
     let res = second_executor.execute_tx(alice.execute()).await;
     assert_matches!(res, TxExecutionResult::BootloaderOutOfGasForTx);
 }
+
+/// Checks that a batch sealed the same way as by the state keeper is replayed without mismatches.
+#[tokio::test]
+async fn replaying_sealed_batch() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut alice = Account::random();
+    let tester = Tester::new(connection_pool.clone());
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+
+    let txs = vec![alice.execute(), alice.execute()];
+    let l1_batch_number = tester.execute_and_seal_batch(txs).await;
+
+    let replayer = BatchReplayer::new(connection_pool, L2ChainId::from(270));
+    let report = replayer.replay(l1_batch_number).await.unwrap();
+    assert_eq!(report.l1_batch_number, l1_batch_number);
+    assert_eq!(report.tx_count, 2);
+    assert!(report.is_consistent(), "{:?}", report.mismatches);
+    // The Merkle tree hasn't processed the batch, so the commitment cannot be checked.
+    assert!(!report.commitment_checked);
+}
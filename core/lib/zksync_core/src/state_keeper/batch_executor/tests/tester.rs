@@ -14,9 +14,13 @@ use zksync_contracts::{get_loadnext_contract, test_contracts::LoadnextContractEx
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_test_account::{Account, DeployContractsTx, TxType};
 use zksync_types::{
-    block::MiniblockHasher, ethabi::Token, fee::Fee, snapshots::SnapshotRecoveryStatus,
+    block::{L1BatchTreeData, MiniblockHasher},
+    ethabi::Token,
+    fee::Fee,
+    snapshots::SnapshotRecoveryStatus,
     storage_writes_deduplicator::StorageWritesDeduplicator,
-    system_contracts::get_system_smart_contracts, utils::storage_key_for_standard_token_balance,
+    system_contracts::get_system_smart_contracts,
+    utils::storage_key_for_standard_token_balance,
     AccountTreeId, Address, Execute, L1BatchNumber, L2ChainId, MiniblockNumber, PriorityOpId,
     ProtocolVersionId, StorageKey, StorageLog, Transaction, H256, L2_ETH_TOKEN_ADDRESS,
     SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256,
@@ -26,8 +30,12 @@ use zksync_utils::u256_to_h256;
 use crate::{
     genesis::create_genesis_l1_batch,
     state_keeper::{
-        batch_executor::{BatchExecutorHandle, TxExecutionResult},
+        batch_executor::{
+            BatchExecutorHandle, PreExecutionOptions, ShadowVmOptions, TxExecutionResult,
+        },
+        io::MiniblockParams,
         tests::{default_l1_batch_env, default_system_env, BASE_SYSTEM_CONTRACTS},
+        updates::UpdatesManager,
         BatchExecutor, MainBatchExecutor,
     },
     utils::testonly::prepare_recovery_snapshot,
@@ -45,6 +53,8 @@ pub(super) struct TestConfig {
     pub(super) max_allowed_tx_gas_limit: u32,
     pub(super) validation_computational_gas_limit: u32,
     pub(super) upload_witness_inputs_to_gcs: bool,
+    pub(super) pre_execution: Option<PreExecutionOptions>,
+    pub(super) shadow_vm: Option<ShadowVmOptions>,
 }
 
 impl TestConfig {
@@ -57,6 +67,8 @@ impl TestConfig {
             max_allowed_tx_gas_limit: config.max_allowed_l2_tx_gas_limit,
             validation_computational_gas_limit: config.validation_computational_gas_limit,
             upload_witness_inputs_to_gcs: false,
+            pre_execution: None,
+            shadow_vm: None,
         }
     }
 }
@@ -112,6 +124,12 @@ impl Tester {
             100,
             false,
         );
+        if let Some(options) = self.config.pre_execution {
+            builder = builder.with_pre_execution(options);
+        }
+        if let Some(options) = self.config.shadow_vm.clone() {
+            builder = builder.with_shadow_vm(options);
+        }
         let (_stop_sender, stop_receiver) = watch::channel(false);
         builder
             .init_batch(l1_batch_env, system_env, &stop_receiver)
@@ -138,6 +156,93 @@ impl Tester {
             .await
     }
 
+    /// Executes the provided transactions in L1 batch #1 (in a single miniblock) and seals the batch in Postgres
+    /// the same way the state keeper does. Returns the number of the sealed batch.
+    pub(super) async fn execute_and_seal_batch(&self, txs: Vec<Transaction>) -> L1BatchNumber {
+        let l1_batch_number = L1BatchNumber(1);
+        let mut storage = self.pool.connection_tagged("state_keeper").await.unwrap();
+        // The genesis root hash is normally computed by the Merkle tree; its value doesn't matter for execution.
+        let prev_batch_hash = H256::repeat_byte(0x11);
+        let tree_data = L1BatchTreeData {
+            hash: prev_batch_hash,
+            rollup_last_leaf_index: 1,
+        };
+        storage
+            .blocks_dal()
+            .save_l1_batch_tree_data(L1BatchNumber(0), &tree_data)
+            .await
+            .unwrap();
+        let prev_miniblock_hash = storage
+            .blocks_web3_dal()
+            .get_miniblock_hash(MiniblockNumber(0))
+            .await
+            .unwrap()
+            .expect("no genesis miniblock");
+        drop(storage);
+
+        let (mut l1_batch_env, system_env) = self.batch_params(l1_batch_number, 100);
+        l1_batch_env.previous_batch_hash = Some(prev_batch_hash);
+        l1_batch_env.first_l2_block.prev_block_hash = prev_miniblock_hash;
+        let executor = self
+            .create_batch_executor_inner(l1_batch_env.clone(), system_env.clone())
+            .await;
+        let mut updates_manager = UpdatesManager::new(&l1_batch_env, &system_env);
+        for tx in txs {
+            let res = executor.execute_tx(tx.clone()).await;
+            let TxExecutionResult::Success {
+                tx_result,
+                tx_metrics,
+                compressed_bytecodes,
+                call_tracer_result,
+                ..
+            } = res
+            else {
+                panic!("Unexpected tx execution result: {res:?}");
+            };
+            updates_manager.extend_from_executed_transaction(
+                tx,
+                *tx_result,
+                compressed_bytecodes,
+                tx_metrics.l1_gas,
+                tx_metrics.execution_metrics,
+                call_tracer_result,
+            );
+        }
+
+        let mut storage = self.pool.connection_tagged("state_keeper").await.unwrap();
+        let first_miniblock_number = MiniblockNumber(l1_batch_env.first_l2_block.number);
+        updates_manager
+            .seal_miniblock_command(
+                l1_batch_number,
+                first_miniblock_number,
+                Address::zero(),
+                false,
+            )
+            .seal(&mut storage)
+            .await
+            .unwrap();
+        // Start the fictive miniblock.
+        updates_manager.push_miniblock(MiniblockParams {
+            timestamp: l1_batch_env.timestamp + 1,
+            virtual_blocks: 1,
+        });
+        executor
+            .start_next_miniblock(updates_manager.miniblock.get_miniblock_env())
+            .await;
+        let (finished_batch, _) = executor.finish_batch().await;
+        updates_manager
+            .seal_l1_batch(
+                &mut storage,
+                first_miniblock_number + 1,
+                &l1_batch_env,
+                finished_batch,
+                Address::zero(),
+            )
+            .await
+            .unwrap();
+        l1_batch_number
+    }
+
     /// Creates test batch params that can be fed into the VM.
     fn batch_params(
         &self,
@@ -34,7 +34,7 @@ use crate::{
         },
         mempool_actor::l2_tx_filter,
         metrics::KEEPER_METRICS,
-        seal_criteria::{IoSealCriteria, TimeoutSealer},
+        seal_criteria::{IoSealCriteria, PolicySealer, TimeoutSealer},
         updates::{MiniblockUpdates, UpdatesManager},
         MempoolGuard,
     },
@@ -50,6 +50,7 @@ pub struct MempoolIO {
     pool: ConnectionPool<Core>,
     object_store: Arc<dyn ObjectStore>,
     timeout_sealer: TimeoutSealer,
+    policy_sealer: Option<PolicySealer>,
     filter: L2TxFilter,
     current_miniblock_number: MiniblockNumber,
     prev_miniblock_hash: H256,
@@ -71,8 +72,15 @@ pub struct MempoolIO {
 
 impl IoSealCriteria for MempoolIO {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
-        self.timeout_sealer
+        if self
+            .timeout_sealer
             .should_seal_l1_batch_unconditionally(manager)
+        {
+            return true;
+        }
+        self.policy_sealer.as_mut().map_or(false, |sealer| {
+            sealer.should_seal_l1_batch_unconditionally(manager)
+        })
     }
 
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
@@ -256,6 +264,10 @@ impl StateKeeperIO for MempoolIO {
         None
     }
 
+    fn peek_next_txs(&self, limit: usize) -> Vec<Transaction> {
+        self.mempool.peek_l2_transactions(&self.filter, limit)
+    }
+
     async fn rollback(&mut self, tx: Transaction) {
         // Reset nonces in the mempool.
         self.mempool.rollback(&tx);
@@ -342,7 +354,7 @@ impl StateKeeperIO for MempoolIO {
                 finished_batch,
                 self.l2_erc20_bridge_addr,
             )
-            .await;
+            .await?;
         self.update_miniblock_fields(&fictive_miniblock);
         self.current_l1_batch_number += 1;
         Ok(())
@@ -452,11 +464,21 @@ impl MempoolIO {
         fee_address_migration::migrate_pending_miniblocks(&mut storage).await?;
         drop(storage);
 
+        let policy_sealer = match &config.seal_policy_path {
+            Some(path) => Some(
+                PolicySealer::new(path)
+                    .await
+                    .context("failed loading seal policy")?,
+            ),
+            None => None,
+        };
+
         Ok(Self {
             mempool,
             object_store,
             pool,
             timeout_sealer: TimeoutSealer::new(config),
+            policy_sealer,
             filter: L2TxFilter::default(),
             // ^ Will be initialized properly on the first newly opened batch
             current_l1_batch_number: cursor.l1_batch,
@@ -3,6 +3,7 @@ use std::{
     time::{Duration, Instant},
 };
 
+use anyhow::Context as _;
 use async_trait::async_trait;
 use multivm::interface::{FinishedL1Batch, L1BatchEnv, SystemEnv};
 use tokio::sync::{mpsc, oneshot};
@@ -91,6 +92,11 @@ pub trait StateKeeperIO: 'static + Send + IoSealCriteria {
     /// Blocks for up to `max_wait` until the next transaction is available for execution.
     /// Returns `None` if no transaction became available until the timeout.
     async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction>;
+    /// Returns up to `limit` transactions that are likely to be returned by the following [`Self::wait_for_next_tx()`]
+    /// calls, without consuming them. Used for speculative pre-execution; the default implementation returns no transactions.
+    fn peek_next_txs(&self, _limit: usize) -> Vec<Transaction> {
+        vec![]
+    }
     /// Marks the transaction as "not executed", so it can be retrieved from the IO again.
     async fn rollback(&mut self, tx: Transaction);
     /// Marks the transaction as "rejected", e.g. one that is not correct and can't be executed.
@@ -266,7 +272,11 @@ impl MiniblockSealer {
         // an earlier one.
         while let Some(completable) = self.next_command().await {
             let mut conn = self.pool.connection_tagged("state_keeper").await.unwrap();
-            completable.command.seal(&mut conn).await;
+            completable
+                .command
+                .seal(&mut conn)
+                .await
+                .context("failed sealing miniblock")?;
             if let Some(delta) = miniblock_seal_delta {
                 MINIBLOCK_METRICS.seal_delta.observe(delta.elapsed());
             }
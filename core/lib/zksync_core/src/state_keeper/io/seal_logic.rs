@@ -3,6 +3,7 @@
 
 use std::time::{Duration, Instant};
 
+use anyhow::Context as _;
 use itertools::Itertools;
 use multivm::{
     interface::{FinishedL1Batch, L1BatchEnv},
@@ -53,7 +54,7 @@ impl UpdatesManager {
         l1_batch_env: &L1BatchEnv,
         finished_batch: FinishedL1Batch,
         l2_erc20_bridge_addr: Address,
-    ) -> MiniblockUpdates {
+    ) -> anyhow::Result<MiniblockUpdates> {
         let started_at = Instant::now();
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::VmFinalization);
         let mut transaction = storage.start_transaction().await.unwrap();
@@ -76,7 +77,10 @@ impl UpdatesManager {
             l2_erc20_bridge_addr,
             false, // fictive miniblocks don't have txs, so it's fine to pass `false` here.
         );
-        miniblock_command.seal_inner(&mut transaction, true).await;
+        miniblock_command
+            .seal_inner(&mut transaction, true)
+            .await
+            .context("failed sealing fictive miniblock")?;
         progress.observe(None);
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::LogDeduplication);
@@ -163,12 +167,15 @@ impl UpdatesManager {
         progress.observe(None);
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::MarkTxsAsExecutedInL1Batch);
+        let tx_hashes: Vec<_> = self
+            .l1_batch
+            .executed_transactions
+            .iter()
+            .map(|tx| tx.hash)
+            .collect();
         transaction
             .transactions_dal()
-            .mark_txs_as_executed_in_l1_batch(
-                l1_batch_env.number,
-                &self.l1_batch.executed_transactions,
-            )
+            .mark_txs_as_executed_in_l1_batch(l1_batch_env.number, &tx_hashes)
             .await;
         progress.observe(None);
 
@@ -236,7 +243,7 @@ impl UpdatesManager {
             l1_batch_env.timestamp,
             &writes_metrics,
         );
-        miniblock_command.miniblock
+        Ok(miniblock_command.miniblock)
     }
 
     fn report_l1_batch_metrics(
@@ -268,43 +275,20 @@ impl UpdatesManager {
 }
 
 impl MiniblockSealCommand {
-    pub async fn seal(&self, storage: &mut Connection<'_, Core>) {
-        self.seal_inner(storage, false).await;
+    pub async fn seal(&self, storage: &mut Connection<'_, Core>) -> anyhow::Result<()> {
+        self.seal_inner(storage, false).await
     }
 
-    async fn insert_transactions(&self, transaction: &mut Connection<'_, Core>) {
+    async fn insert_transactions(
+        &self,
+        transaction: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<()> {
         for tx_result in &self.miniblock.executed_transactions {
-            let tx = tx_result.transaction.clone();
-            match &tx.common_data {
-                ExecuteTransactionCommon::L1(_) => {
-                    // `unwrap` is safe due to the check above
-                    let l1_tx = L1Tx::try_from(tx).unwrap();
-                    let l1_block_number = L1BlockNumber(l1_tx.common_data.eth_block as u32);
-                    transaction
-                        .transactions_dal()
-                        .insert_transaction_l1(l1_tx, l1_block_number)
-                        .await;
-                }
-                ExecuteTransactionCommon::L2(_) => {
-                    // `unwrap` is safe due to the check above
-                    let l2_tx = L2Tx::try_from(tx).unwrap();
-                    // Using `Default` for execution metrics should be OK here, since this data is not used on the EN.
-                    transaction
-                        .transactions_dal()
-                        .insert_transaction_l2(l2_tx, Default::default())
-                        .await
-                        .unwrap();
-                }
-                ExecuteTransactionCommon::ProtocolUpgrade(_) => {
-                    // `unwrap` is safe due to the check above
-                    let protocol_system_upgrade_tx = ProtocolUpgradeTx::try_from(tx).unwrap();
-                    transaction
-                        .transactions_dal()
-                        .insert_system_transaction(protocol_system_upgrade_tx)
-                        .await;
-                }
-            }
+            insert_transaction(transaction, tx_result.transaction.clone())
+                .await
+                .with_context(|| format!("failed inserting transaction {:?}", tx_result.hash))?;
         }
+        Ok(())
     }
 
     /// Seals a miniblock with the given number.
@@ -316,13 +300,17 @@ impl MiniblockSealCommand {
     /// one for sending fees to the operator).
     ///
     /// `l2_erc20_bridge_addr` is required to extract the information on newly added tokens.
-    async fn seal_inner(&self, storage: &mut Connection<'_, Core>, is_fictive: bool) {
+    async fn seal_inner(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        is_fictive: bool,
+    ) -> anyhow::Result<()> {
         self.assert_valid_miniblock(is_fictive);
 
         let mut transaction = storage.start_transaction().await.unwrap();
         if self.pre_insert_txs {
             let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::PreInsertTxs, is_fictive);
-            self.insert_transactions(&mut transaction).await;
+            self.insert_transactions(&mut transaction).await?;
             progress.observe(Some(self.miniblock.executed_transactions.len()));
         }
 
@@ -485,6 +473,7 @@ impl MiniblockSealCommand {
         progress.observe(Some(self.miniblock.executed_transactions.len()));
 
         self.report_miniblock_metrics(started_at, current_l2_virtual_block_number);
+        Ok(())
     }
 
     /// Performs several sanity checks to make sure that the miniblock is valid.
@@ -657,6 +646,60 @@ impl MiniblockSealCommand {
     }
 }
 
+/// Inserts a transaction into Postgres without marking it as executed. Used on the external node,
+/// where transactions don't pass through the mempool.
+pub(crate) async fn insert_transaction(
+    storage: &mut Connection<'_, Core>,
+    tx: Transaction,
+) -> anyhow::Result<()> {
+    let Transaction {
+        common_data,
+        execute,
+        received_timestamp_ms,
+        raw_bytes,
+    } = tx;
+    match common_data {
+        ExecuteTransactionCommon::L1(common_data) => {
+            let l1_block_number = L1BlockNumber(common_data.eth_block as u32);
+            let l1_tx = L1Tx {
+                execute,
+                common_data,
+                received_timestamp_ms,
+            };
+            storage
+                .transactions_dal()
+                .insert_transaction_l1(l1_tx, l1_block_number)
+                .await;
+        }
+        ExecuteTransactionCommon::L2(common_data) => {
+            let l2_tx = L2Tx {
+                execute,
+                common_data,
+                received_timestamp_ms,
+                raw_bytes,
+            };
+            // Using `Default` for execution metrics should be OK here, since this data is not used on the EN.
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(l2_tx, Default::default())
+                .await
+                .context("failed inserting L2 transaction")?;
+        }
+        ExecuteTransactionCommon::ProtocolUpgrade(common_data) => {
+            let protocol_system_upgrade_tx = ProtocolUpgradeTx {
+                execute,
+                common_data,
+                received_timestamp_ms,
+            };
+            storage
+                .transactions_dal()
+                .insert_system_transaction(protocol_system_upgrade_tx)
+                .await;
+        }
+    }
+    Ok(())
+}
+
 fn l1_l2_tx_count(executed_transactions: &[TransactionExecutionResult]) -> (usize, usize) {
     let mut l1_tx_count = 0;
     let mut l2_tx_count = 0;
@@ -265,7 +265,7 @@ async fn processing_storage_logs_when_sealing_miniblock() {
     conn.protocol_versions_dal()
         .save_protocol_version_with_tx(Default::default())
         .await;
-    seal_command.seal(&mut conn).await;
+    seal_command.seal(&mut conn).await.unwrap();
 
     // Manually mark the miniblock as executed so that getting touched slots from it works
     conn.blocks_dal()
@@ -343,7 +343,7 @@ async fn processing_events_when_sealing_miniblock() {
     conn.protocol_versions_dal()
         .save_protocol_version_with_tx(Default::default())
         .await;
-    seal_command.seal(&mut conn).await;
+    seal_command.seal(&mut conn).await.unwrap();
 
     let logs = conn
         .events_web3_dal()
@@ -199,6 +199,7 @@ impl Tester {
         tx_results: &[TransactionExecutionResult],
     ) {
         let batch_header = create_l1_batch(number);
+        let tx_hashes: Vec<_> = tx_results.iter().map(|tx| tx.hash).collect();
         let mut storage = pool.connection_tagged("state_keeper").await.unwrap();
         storage
             .blocks_dal()
@@ -212,7 +213,7 @@ impl Tester {
             .unwrap();
         storage
             .transactions_dal()
-            .mark_txs_as_executed_in_l1_batch(batch_header.number, tx_results)
+            .mark_txs_as_executed_in_l1_batch(batch_header.number, &tx_hashes)
             .await;
         storage
             .blocks_dal()
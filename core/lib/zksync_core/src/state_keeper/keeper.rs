@@ -481,6 +481,11 @@ impl ZkSyncStateKeeper {
                     .await;
             }
 
+            let pre_execution_lookahead = batch_executor.pre_execution_lookahead();
+            if pre_execution_lookahead > 0 {
+                batch_executor.pre_execute(self.io.peek_next_txs(pre_execution_lookahead));
+            }
+
             let waiting_latency = KEEPER_METRICS.waiting_for_tx.start();
             let Some(tx) = self.io.wait_for_next_tx(POLL_WAIT_DURATION).await else {
                 waiting_latency.observe();
@@ -490,6 +495,15 @@ impl ZkSyncStateKeeper {
             waiting_latency.observe();
 
             let tx_hash = tx.hash();
+            if let Some(reason) = batch_executor.certain_rejection(&tx) {
+                // The VM would reject the transaction with the same reason and roll it back,
+                // so we can reject it right away without changing execution results.
+                self.io
+                    .reject(&tx, &reason.to_string())
+                    .await
+                    .with_context(|| format!("cannot reject transaction {tx_hash:?}"))?;
+                continue;
+            }
             let (seal_resolution, exec_result) = self
                 .process_one_tx(batch_executor, updates_manager, tx.clone())
                 .await;
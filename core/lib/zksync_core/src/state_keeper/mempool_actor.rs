@@ -88,6 +88,21 @@ impl MempoolFetcher {
             let latency = KEEPER_METRICS.mempool_sync.start();
             let mut storage = self.pool.connection_tagged("state_keeper").await?;
             let mempool_info = self.mempool.get_mempool_info();
+            if !mempool_info.evicted_transactions.is_empty() {
+                let evicted: Vec<_> = mempool_info
+                    .evicted_transactions
+                    .iter()
+                    .map(|tx| (tx.hash, format!("evicted from mempool: {}", tx.reason)))
+                    .collect();
+                storage
+                    .transactions_dal()
+                    .mark_txs_as_evicted(&evicted)
+                    .await
+                    .context("failed marking evicted transactions")?;
+                KEEPER_METRICS
+                    .evicted_transactions
+                    .inc_by(evicted.len() as u64);
+            }
             let protocol_version = pending_protocol_version(&mut storage)
                 .await
                 .context("failed getting pending protocol version")?;
@@ -160,9 +175,10 @@ async fn get_transaction_nonces(
 
 #[cfg(test)]
 mod tests {
+    use zksync_config::configs::chain::MempoolEvictionPolicy;
     use zksync_types::{
-        fee::TransactionExecutionMetrics, L2ChainId, MiniblockNumber, PriorityOpId,
-        ProtocolVersionId, StorageLog, H256,
+        api::TransactionStatus, fee::TransactionExecutionMetrics, L2ChainId, MiniblockNumber,
+        PriorityOpId, ProtocolVersionId, StorageLog, H256,
     };
     use zksync_utils::u256_to_h256;
 
@@ -179,6 +195,10 @@ mod tests {
         stuck_tx_timeout: 0,
         remove_stuck_txs: false,
         delay_interval: 10,
+        max_size_bytes: None,
+        max_txs_per_account: None,
+        max_bytes_per_account: None,
+        eviction_policy: MempoolEvictionPolicy::PurgeQueued,
     };
 
     #[tokio::test]
@@ -261,6 +281,65 @@ mod tests {
         fetcher_task.await.unwrap().expect("fetcher errored");
     }
 
+    #[tokio::test]
+    async fn evicting_transactions_exceeding_account_limits() {
+        let pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+        let mut storage = pool.connection().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let config = MempoolConfig {
+            max_txs_per_account: Some(0),
+            ..TEST_MEMPOOL_CONFIG
+        };
+        let mempool = MempoolGuard::with_config(PriorityOpId(0), &config);
+        let fee_params_provider = Arc::new(MockBatchFeeParamsProvider::default());
+        let fee_input = fee_params_provider.get_batch_fee_input().await;
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, ProtocolVersionId::latest().into());
+
+        let mut fetcher =
+            MempoolFetcher::new(mempool.clone(), fee_params_provider, &config, pool.clone());
+        let (tx_hashes_sender, mut tx_hashes_receiver) = mpsc::unbounded_channel();
+        fetcher.transaction_hashes_sender = tx_hashes_sender;
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let fetcher_task = tokio::spawn(fetcher.run(stop_receiver));
+
+        let transaction = create_l2_transaction(base_fee, gas_per_pubdata);
+        let transaction_hash = transaction.hash();
+        let mut storage = pool.connection().await.unwrap();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(transaction, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let tx_hashes = wait_for_new_transactions(&mut tx_hashes_receiver).await;
+        assert_eq!(tx_hashes, [transaction_hash]);
+        assert_eq!(mempool.stats().l2_transaction_count, 0);
+
+        // The eviction should be persisted on the next mempool sync.
+        loop {
+            tokio::time::sleep(config.sync_interval()).await;
+            let mut storage = pool.connection().await.unwrap();
+            let tx_details = storage
+                .transactions_web3_dal()
+                .get_transaction_details(transaction_hash)
+                .await
+                .unwrap()
+                .expect("transaction is not persisted");
+            if matches!(tx_details.status, TransactionStatus::Failed) {
+                break;
+            }
+        }
+
+        stop_sender.send_replace(true);
+        fetcher_task.await.unwrap().expect("fetcher errored");
+    }
+
     async fn wait_for_new_transactions(
         tx_hashes_receiver: &mut mpsc::UnboundedReceiver<Vec<H256>>,
     ) -> Vec<H256> {
@@ -59,6 +59,8 @@ pub(crate) struct StateKeeperMetrics {
     pub get_tx_from_mempool: Histogram<Duration>,
     /// Number of transactions rejected by the state keeper.
     pub rejected_transactions: Counter,
+    /// Number of L2 transactions evicted from the mempool because of its limits.
+    pub evicted_transactions: Counter,
     /// Time spent waiting for the hash of a previous L1 batch.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub wait_for_prev_hash_time: Histogram<Duration>,
@@ -90,6 +92,10 @@ pub(super) struct StateKeeperGauges {
     mempool_l2_size: Gauge<u64>,
     /// Current size of the L2 priority queue.
     l2_priority_queue_size: Gauge<usize>,
+    /// Current number of L2 transactions in the mempool queued behind a nonce gap.
+    mempool_l2_queued_size: Gauge<u64>,
+    /// Current total size of L2 transactions in the mempool in bytes.
+    mempool_l2_size_bytes: Gauge<u64>,
 }
 
 impl StateKeeperGauges {
@@ -109,6 +115,10 @@ impl StateKeeperGauges {
                     .l2_priority_queue_size
                     .set(stats.l2_priority_queue_size);
                 gauges
+                    .mempool_l2_queued_size
+                    .set(stats.l2_queued_transaction_count);
+                gauges.mempool_l2_size_bytes.set(stats.l2_size_bytes);
+                gauges
             })
         });
         if res.is_err() {
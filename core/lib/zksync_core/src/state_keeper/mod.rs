@@ -9,10 +9,15 @@ use zksync_dal::{ConnectionPool, Core};
 use zksync_object_store::ObjectStore;
 
 pub use self::{
-    batch_executor::{main_executor::MainBatchExecutor, BatchExecutor},
+    batch_executor::{
+        main_executor::MainBatchExecutor, BatchExecutor, PreExecutionOptions, ShadowVmOptions,
+    },
     io::{mempool::MempoolIO, MiniblockSealer, MiniblockSealerHandle, StateKeeperIO},
     keeper::ZkSyncStateKeeper,
     mempool_actor::MempoolFetcher,
+    replay::{
+        BatchReplayReport, BatchReplayer, ReplayMismatch, ReplayOptions, ReplayedTx, TxCallTraces,
+    },
     seal_criteria::SequencerSealer,
     types::MempoolGuard,
 };
@@ -24,6 +29,7 @@ pub(crate) mod io;
 mod keeper;
 mod mempool_actor;
 pub(crate) mod metrics;
+mod replay;
 pub mod seal_criteria;
 #[cfg(test)]
 pub(crate) mod tests;
@@ -44,7 +50,7 @@ pub(crate) async fn create_state_keeper(
     object_store: Arc<dyn ObjectStore>,
     stop_receiver: watch::Receiver<bool>,
 ) -> ZkSyncStateKeeper {
-    let batch_executor_base = MainBatchExecutor::new(
+    let mut batch_executor_base = MainBatchExecutor::new(
         db_config.state_keeper_db_path.clone(),
         pool.clone(),
         state_keeper_config.max_allowed_l2_tx_gas_limit.into(),
@@ -53,6 +59,15 @@ pub(crate) async fn create_state_keeper(
         state_keeper_config.enum_index_migration_chunk_size(),
         false,
     );
+    let shadow_vm = ShadowVmOptions::from_config(&state_keeper_config)
+        .expect("Invalid shadow VM configuration for state keeper");
+    if let Some(shadow_vm) = shadow_vm {
+        tracing::info!("Enabling shadow execution of L1 batches: {shadow_vm:?}");
+        batch_executor_base = batch_executor_base.with_shadow_vm(shadow_vm);
+    }
+    if let Some(pre_execution) = PreExecutionOptions::from_config(&state_keeper_config) {
+        batch_executor_base = batch_executor_base.with_pre_execution(pre_execution);
+    }
 
     let io = MempoolIO::new(
         mempool,
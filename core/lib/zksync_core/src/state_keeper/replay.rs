@@ -0,0 +1,718 @@
+//! Deterministic re-execution of sealed L1 batches used to debug divergences between the state keeper
+//! and downstream components (e.g., when a batch fails proof generation).
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::Context as _;
+use multivm::interface::{FinishedL1Batch, L2BlockEnv};
+use serde::Serialize;
+use vm_utils::storage::L1BatchParamsProvider;
+use zksync_commitment_utils::{bootloader_initial_content_commitment, events_queue_commitment};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_l1_contract_interface::i_executor::commit::kzg::pubdata_to_blob_commitments;
+use zksync_types::{
+    commitment::{CommitmentInput, L1BatchCommitment},
+    l2_to_l1_log::L2ToL1Log,
+    vm_trace::Call,
+    web3::signing::keccak256,
+    AccountTreeId, Address, L1BatchNumber, L2ChainId, ProtocolVersionId, StorageKey, VmEvent,
+    VmVersion, H256, U256,
+};
+use zksync_utils::u256_to_h256;
+
+use super::{batch_executor::TxExecutionResult, MainBatchExecutor};
+use crate::commitment_generator::CommitmentGenerator;
+
+/// Options for [`BatchReplayer`].
+#[derive(Debug, Clone, Default)]
+pub struct ReplayOptions {
+    /// VM version to use instead of the one implied by the protocol version of the replayed batch.
+    pub vm_version: Option<VmVersion>,
+    /// Whether to collect call traces for replayed transactions.
+    pub save_call_traces: bool,
+    /// Whether to fall back to executing transactions without bytecode compression (as on external nodes).
+    pub optional_bytecode_compression: bool,
+}
+
+/// Call traces of a replayed transaction.
+#[derive(Debug, Serialize)]
+pub struct TxCallTraces {
+    pub tx_index_in_l1_batch: usize,
+    pub tx_hash: H256,
+    pub calls: Vec<Call>,
+}
+
+/// Transaction in the replayed L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ReplayedTx {
+    pub index_in_l1_batch: usize,
+    pub hash: H256,
+}
+
+/// Single difference between the replayed L1 batch and the data stored in Postgres.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReplayMismatch {
+    /// Transaction included into the stored batch was rejected during replay.
+    RejectedTx { tx: ReplayedTx, reason: String },
+    /// Final value of a storage slot differs; `None` means that the slot was not touched.
+    StorageWrite {
+        address: Address,
+        key: H256,
+        expected: Option<H256>,
+        actual: Option<H256>,
+        /// Last replayed transaction that has written to the slot.
+        last_writer: Option<ReplayedTx>,
+    },
+    /// Events emitted by a transaction (or the batch tip if `tx` is `None`) differ.
+    Events {
+        tx_index_in_l1_batch: u32,
+        tx: Option<ReplayedTx>,
+        expected_count: usize,
+        actual_count: usize,
+    },
+    /// User or system L2-to-L1 logs differ starting from the specified log.
+    L2ToL1Logs {
+        system: bool,
+        first_mismatch_index: usize,
+        expected: Option<L2ToL1Log>,
+        actual: Option<L2ToL1Log>,
+    },
+    /// Initial bootloader memory differs.
+    BootloaderMemory {
+        expected_hash: H256,
+        actual_hash: H256,
+        first_mismatch_slot: Option<usize>,
+    },
+    /// Batch commitment recomputed from the replayed data differs from the stored one.
+    Commitment { expected: H256, actual: H256 },
+}
+
+impl ReplayMismatch {
+    fn tx_index(&self) -> Option<usize> {
+        match self {
+            Self::RejectedTx { tx, .. } => Some(tx.index_in_l1_batch),
+            Self::StorageWrite { last_writer, .. } => last_writer.map(|tx| tx.index_in_l1_batch),
+            Self::Events { tx, .. } => tx.map(|tx| tx.index_in_l1_batch),
+            Self::L2ToL1Logs {
+                expected, actual, ..
+            } => expected
+                .as_ref()
+                .or(actual.as_ref())
+                .map(|log| log.tx_number_in_block.into()),
+            Self::BootloaderMemory { .. } | Self::Commitment { .. } => None,
+        }
+    }
+}
+
+/// Report produced by [`BatchReplayer`].
+#[derive(Debug, Serialize)]
+pub struct BatchReplayReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub vm_version: String,
+    pub tx_count: usize,
+    /// Earliest transaction that mismatches can be attributed to.
+    pub first_diverged_tx: Option<ReplayedTx>,
+    pub mismatches: Vec<ReplayMismatch>,
+    /// Whether the batch commitment was checked. The commitment cannot be checked if the Merkle tree
+    /// hasn't processed the batch yet.
+    pub commitment_checked: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub call_traces: Vec<TxCallTraces>,
+}
+
+impl BatchReplayReport {
+    /// Checks whether the replayed batch fully matches the stored data.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Data produced by replaying an L1 batch.
+#[derive(Debug, Default)]
+struct ReplayedBatch {
+    txs: Vec<H256>,
+    rejected_txs: Vec<(usize, String)>,
+    /// Index of the last transaction writing to each storage slot.
+    last_writers: HashMap<StorageKey, usize>,
+    call_traces: Vec<TxCallTraces>,
+}
+
+impl ReplayedBatch {
+    fn tx(&self, index: usize) -> Option<ReplayedTx> {
+        let hash = *self.txs.get(index)?;
+        Some(ReplayedTx {
+            index_in_l1_batch: index,
+            hash,
+        })
+    }
+}
+
+/// Data for an L1 batch persisted by the state keeper.
+#[derive(Debug, Default)]
+struct StoredBatch {
+    storage_writes: HashMap<StorageKey, H256>,
+    events: Vec<VmEvent>,
+    user_l2_to_l1_logs: Vec<L2ToL1Log>,
+    system_l2_to_l1_logs: Vec<L2ToL1Log>,
+    bootloader_memory: Vec<(usize, U256)>,
+}
+
+/// Deterministically re-executes a sealed L1 batch on top of the Postgres state at the end of the previous batch
+/// and compares execution results with the data persisted by the state keeper.
+#[derive(Debug)]
+pub struct BatchReplayer {
+    pool: ConnectionPool<Core>,
+    l2_chain_id: L2ChainId,
+    options: ReplayOptions,
+}
+
+impl BatchReplayer {
+    pub fn new(pool: ConnectionPool<Core>, l2_chain_id: L2ChainId) -> Self {
+        Self {
+            pool,
+            l2_chain_id,
+            options: ReplayOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: ReplayOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Replays the specified L1 batch and compares its results with the stored data.
+    pub async fn replay(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<BatchReplayReport> {
+        anyhow::ensure!(
+            l1_batch_number > L1BatchNumber(0),
+            "genesis L1 batch cannot be replayed since it is not produced by the VM"
+        );
+        let mut storage = self.pool.connection_tagged("batch_replay").await?;
+        let params_provider = L1BatchParamsProvider::new(&mut storage)
+            .await
+            .context("failed initializing L1 batch params provider")?;
+        let first_miniblock_in_batch = params_provider
+            .load_first_miniblock_in_batch(&mut storage, l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed loading first miniblock in L1 batch #{l1_batch_number}")
+            })?
+            .with_context(|| format!("no miniblocks persisted for L1 batch #{l1_batch_number}"))?;
+        // The batch has already been executed by the state keeper, so we don't want to reject any transactions.
+        let (system_env, l1_batch_env) = params_provider
+            .load_l1_batch_params(
+                &mut storage,
+                &first_miniblock_in_batch,
+                u32::MAX,
+                self.l2_chain_id,
+            )
+            .await
+            .with_context(|| format!("failed loading params for L1 batch #{l1_batch_number}"))?;
+        let miniblocks = storage
+            .transactions_dal()
+            .get_miniblocks_to_execute_for_l1_batch(l1_batch_number)
+            .await?;
+        let stored = Self::load_stored_batch(&mut storage, l1_batch_number).await?;
+        drop(storage);
+
+        let protocol_version = system_env.version;
+        let vm_version = self
+            .options
+            .vm_version
+            .unwrap_or_else(|| VmVersion::from(protocol_version));
+        tracing::info!(
+            "Replaying L1 batch #{l1_batch_number} with {} miniblocks using VM {vm_version:?}",
+            miniblocks.len()
+        );
+
+        // The state keeper cache is not used, so the corresponding params are irrelevant.
+        let executor = MainBatchExecutor::new(
+            String::new(),
+            self.pool.clone(),
+            U256::MAX,
+            self.options.save_call_traces,
+            false,
+            0,
+            self.options.optional_bytecode_compression,
+        );
+        let handle = executor
+            .init_batch_with_postgres_storage(
+                l1_batch_env,
+                system_env,
+                first_miniblock_in_batch.number() - 1,
+                Some(vm_version),
+            )
+            .await?;
+
+        let mut replayed = ReplayedBatch::default();
+        let next_miniblocks = miniblocks.iter().skip(1).map(Some).chain([None]);
+        for (miniblock, next_miniblock) in miniblocks.iter().zip(next_miniblocks) {
+            for tx in &miniblock.txs {
+                let tx_index = replayed.txs.len();
+                let tx_hash = tx.hash();
+                replayed.txs.push(tx_hash);
+                match handle.execute_tx(tx.clone()).await {
+                    TxExecutionResult::Success {
+                        tx_result,
+                        call_tracer_result,
+                        ..
+                    } => {
+                        for log in &tx_result.logs.storage_logs {
+                            let query = &log.log_query;
+                            if query.rw_flag {
+                                let key = StorageKey::new(
+                                    AccountTreeId::new(query.address),
+                                    u256_to_h256(query.key),
+                                );
+                                replayed.last_writers.insert(key, tx_index);
+                            }
+                        }
+                        if self.options.save_call_traces {
+                            replayed.call_traces.push(TxCallTraces {
+                                tx_index_in_l1_batch: tx_index,
+                                tx_hash,
+                                calls: call_tracer_result,
+                            });
+                        }
+                    }
+                    result => {
+                        let reason = result.err().map(ToString::to_string).unwrap_or_default();
+                        tracing::warn!(
+                            "Transaction {tx_hash:?} was rejected during replay: {reason}"
+                        );
+                        replayed.rejected_txs.push((tx_index, reason));
+                        handle.rollback_last_tx().await;
+                    }
+                }
+            }
+            if let Some(next_miniblock) = next_miniblock {
+                handle
+                    .start_next_miniblock(L2BlockEnv::from_miniblock_data(next_miniblock))
+                    .await;
+            }
+        }
+        let (finished_batch, _) = handle.finish_batch().await;
+
+        let mut mismatches = compare_batches(&stored, &replayed, &finished_batch);
+        let commitment_mismatch = self
+            .check_commitment(l1_batch_number, &finished_batch, protocol_version)
+            .await?;
+        let commitment_checked = commitment_mismatch.is_some();
+        mismatches.extend(commitment_mismatch.flatten());
+
+        let first_diverged_tx = mismatches
+            .iter()
+            .filter_map(ReplayMismatch::tx_index)
+            .min()
+            .and_then(|index| replayed.tx(index));
+        Ok(BatchReplayReport {
+            l1_batch_number,
+            vm_version: format!("{vm_version:?}"),
+            tx_count: replayed.txs.len(),
+            first_diverged_tx,
+            mismatches,
+            commitment_checked,
+            call_traces: replayed.call_traces,
+        })
+    }
+
+    async fn load_stored_batch(
+        storage: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<StoredBatch> {
+        let header = storage
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not sealed"))?;
+        let storage_writes = storage
+            .storage_logs_dal()
+            .get_touched_slots_for_l1_batch(l1_batch_number)
+            .await?;
+        let events = storage
+            .events_dal()
+            .get_vm_events_for_l1_batch(l1_batch_number)
+            .await?
+            .unwrap_or_default();
+        let bootloader_memory = storage
+            .blocks_dal()
+            .get_initial_bootloader_heap(l1_batch_number)
+            .await?
+            .unwrap_or_default();
+        Ok(StoredBatch {
+            storage_writes,
+            events,
+            user_l2_to_l1_logs: header.l2_to_l1_logs.into_iter().map(|log| log.0).collect(),
+            system_l2_to_l1_logs: header.system_logs.into_iter().map(|log| log.0).collect(),
+            bootloader_memory,
+        })
+    }
+
+    /// Recomputes the batch commitment with execution-dependent inputs replaced by the replayed data.
+    /// Returns `None` if the commitment cannot be checked.
+    async fn check_commitment(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        finished_batch: &FinishedL1Batch,
+        protocol_version: ProtocolVersionId,
+    ) -> anyhow::Result<Option<Option<ReplayMismatch>>> {
+        let mut storage = self.pool.connection_tagged("batch_replay").await?;
+        let stored_metadata = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(l1_batch_number)
+            .await?;
+        drop(storage);
+        let Some(stored_metadata) = stored_metadata else {
+            tracing::info!(
+                "Metadata for L1 batch #{l1_batch_number} is not computed yet; skipping commitment check"
+            );
+            return Ok(None);
+        };
+
+        let mut input = CommitmentGenerator::new(self.pool.clone())
+            .prepare_input(l1_batch_number)
+            .await
+            .context("failed preparing commitment input")?;
+        let execution_state = &finished_batch.final_execution_state;
+        match &mut input {
+            CommitmentInput::PreBoojum { common, .. } => {
+                common.l2_to_l1_logs = execution_state.user_l2_to_l1_logs.clone();
+            }
+            CommitmentInput::PostBoojum {
+                common,
+                system_logs,
+                aux_commitments,
+                blob_commitments,
+                ..
+            } => {
+                common.l2_to_l1_logs = execution_state.user_l2_to_l1_logs.clone();
+                *system_logs = execution_state.system_logs.clone();
+                if let Some(commitment) = events_queue_commitment(
+                    &execution_state.deduplicated_events_logs,
+                    protocol_version,
+                ) {
+                    aux_commitments.events_queue_commitment = commitment;
+                }
+                let bootloader_memory = finished_batch
+                    .final_bootloader_memory
+                    .as_deref()
+                    .unwrap_or_default();
+                if let Some(commitment) =
+                    bootloader_initial_content_commitment(bootloader_memory, protocol_version)
+                {
+                    aux_commitments.bootloader_initial_content_commitment = commitment;
+                }
+                if protocol_version.is_post_1_4_2() {
+                    if let Some(pubdata_input) = &finished_batch.pubdata_input {
+                        *blob_commitments = pubdata_to_blob_commitments(pubdata_input);
+                    }
+                }
+            }
+        }
+
+        let actual = L1BatchCommitment::new(input).hash().commitment;
+        let expected = stored_metadata.metadata.commitment;
+        Ok(Some((actual != expected).then_some(
+            ReplayMismatch::Commitment { expected, actual },
+        )))
+    }
+}
+
+fn compare_batches(
+    stored: &StoredBatch,
+    replayed: &ReplayedBatch,
+    finished_batch: &FinishedL1Batch,
+) -> Vec<ReplayMismatch> {
+    let mut mismatches: Vec<_> = replayed
+        .rejected_txs
+        .iter()
+        .filter_map(|(index, reason)| {
+            Some(ReplayMismatch::RejectedTx {
+                tx: replayed.tx(*index)?,
+                reason: reason.clone(),
+            })
+        })
+        .collect();
+
+    let execution_state = &finished_batch.final_execution_state;
+    let replayed_writes: HashMap<_, _> = execution_state
+        .deduplicated_storage_log_queries
+        .iter()
+        .filter(|query| query.rw_flag)
+        .map(|query| {
+            let key = StorageKey::new(AccountTreeId::new(query.address), u256_to_h256(query.key));
+            (key, u256_to_h256(query.written_value))
+        })
+        .collect();
+    mismatches.extend(compare_storage_writes(
+        &stored.storage_writes,
+        &replayed_writes,
+        replayed,
+    ));
+
+    mismatches.extend(compare_events(
+        &stored.events,
+        &execution_state.events,
+        replayed,
+    ));
+
+    let replayed_user_logs: Vec<_> = execution_state
+        .user_l2_to_l1_logs
+        .iter()
+        .map(|log| log.0.clone())
+        .collect();
+    mismatches.extend(compare_l2_to_l1_logs(
+        &stored.user_l2_to_l1_logs,
+        &replayed_user_logs,
+        false,
+    ));
+    let replayed_system_logs: Vec<_> = execution_state
+        .system_logs
+        .iter()
+        .map(|log| log.0.clone())
+        .collect();
+    mismatches.extend(compare_l2_to_l1_logs(
+        &stored.system_l2_to_l1_logs,
+        &replayed_system_logs,
+        true,
+    ));
+
+    let replayed_memory = finished_batch
+        .final_bootloader_memory
+        .as_deref()
+        .unwrap_or_default();
+    let expected_hash = bootloader_memory_hash(&stored.bootloader_memory);
+    let actual_hash = bootloader_memory_hash(replayed_memory);
+    if expected_hash != actual_hash {
+        let first_mismatch_slot = stored
+            .bootloader_memory
+            .iter()
+            .zip(replayed_memory)
+            .find(|(expected, actual)| expected != actual)
+            .map(|(expected, _)| expected.0);
+        mismatches.push(ReplayMismatch::BootloaderMemory {
+            expected_hash,
+            actual_hash,
+            first_mismatch_slot,
+        });
+    }
+    mismatches
+}
+
+fn compare_storage_writes(
+    expected: &HashMap<StorageKey, H256>,
+    actual: &HashMap<StorageKey, H256>,
+    replayed: &ReplayedBatch,
+) -> Vec<ReplayMismatch> {
+    let all_keys: BTreeSet<_> = expected.keys().chain(actual.keys()).collect();
+    all_keys
+        .into_iter()
+        .filter_map(|key| {
+            let expected = expected.get(key).copied();
+            let actual = actual.get(key).copied();
+            (expected != actual).then(|| ReplayMismatch::StorageWrite {
+                address: *key.address(),
+                key: *key.key(),
+                expected,
+                actual,
+                last_writer: replayed
+                    .last_writers
+                    .get(key)
+                    .and_then(|&index| replayed.tx(index)),
+            })
+        })
+        .collect()
+}
+
+fn compare_events(
+    expected: &[VmEvent],
+    actual: &[VmEvent],
+    replayed: &ReplayedBatch,
+) -> Vec<ReplayMismatch> {
+    fn group_by_tx(events: &[VmEvent]) -> BTreeMap<u32, Vec<&VmEvent>> {
+        let mut grouped = BTreeMap::<_, Vec<_>>::new();
+        for event in events {
+            grouped.entry(event.location.1).or_default().push(event);
+        }
+        grouped
+    }
+
+    let expected = group_by_tx(expected);
+    let actual = group_by_tx(actual);
+    let all_indices: BTreeSet<_> = expected.keys().chain(actual.keys()).copied().collect();
+    all_indices
+        .into_iter()
+        .filter_map(|tx_index| {
+            let expected = expected.get(&tx_index).map_or(&[][..], Vec::as_slice);
+            let actual = actual.get(&tx_index).map_or(&[][..], Vec::as_slice);
+            (expected != actual).then(|| ReplayMismatch::Events {
+                tx_index_in_l1_batch: tx_index,
+                tx: replayed.tx(tx_index as usize),
+                expected_count: expected.len(),
+                actual_count: actual.len(),
+            })
+        })
+        .collect()
+}
+
+fn compare_l2_to_l1_logs(
+    expected: &[L2ToL1Log],
+    actual: &[L2ToL1Log],
+    system: bool,
+) -> Option<ReplayMismatch> {
+    let len = expected.len().max(actual.len());
+    (0..len)
+        .find(|&i| expected.get(i) != actual.get(i))
+        .map(|i| ReplayMismatch::L2ToL1Logs {
+            system,
+            first_mismatch_index: i,
+            expected: expected.get(i).cloned(),
+            actual: actual.get(i).cloned(),
+        })
+}
+
+/// Hashes bootloader memory as a sequence of `(slot, value)` pairs, each encoded as two 32-byte big-endian words.
+fn bootloader_memory_hash(memory: &[(usize, U256)]) -> H256 {
+    let mut bytes = Vec::with_capacity(memory.len() * 64);
+    for &(slot, value) in memory {
+        bytes.extend_from_slice(u256_to_h256(slot.into()).as_bytes());
+        bytes.extend_from_slice(u256_to_h256(value).as_bytes());
+    }
+    H256(keccak256(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replayed_batch(tx_count: u8) -> ReplayedBatch {
+        ReplayedBatch {
+            txs: (0..tx_count).map(H256::repeat_byte).collect(),
+            ..ReplayedBatch::default()
+        }
+    }
+
+    fn event(tx_index: u32, topic: u8) -> VmEvent {
+        VmEvent {
+            location: (L1BatchNumber(1), tx_index),
+            address: Address::repeat_byte(1),
+            indexed_topics: vec![H256::repeat_byte(topic)],
+            value: vec![],
+        }
+    }
+
+    #[test]
+    fn comparing_storage_writes() {
+        let mut replayed = replayed_batch(3);
+        let key = |byte| {
+            StorageKey::new(
+                AccountTreeId::new(Address::repeat_byte(1)),
+                H256::repeat_byte(byte),
+            )
+        };
+        replayed.last_writers.insert(key(2), 1);
+
+        let expected = HashMap::from([
+            (key(1), H256::repeat_byte(1)),
+            (key(2), H256::repeat_byte(2)),
+        ]);
+        let mut actual = expected.clone();
+        assert!(compare_storage_writes(&expected, &actual, &replayed).is_empty());
+
+        actual.insert(key(2), H256::repeat_byte(3));
+        actual.insert(key(3), H256::repeat_byte(3));
+        let mismatches = compare_storage_writes(&expected, &actual, &replayed);
+        assert_eq!(
+            mismatches,
+            [
+                ReplayMismatch::StorageWrite {
+                    address: Address::repeat_byte(1),
+                    key: H256::repeat_byte(2),
+                    expected: Some(H256::repeat_byte(2)),
+                    actual: Some(H256::repeat_byte(3)),
+                    last_writer: replayed.tx(1),
+                },
+                ReplayMismatch::StorageWrite {
+                    address: Address::repeat_byte(1),
+                    key: H256::repeat_byte(3),
+                    expected: None,
+                    actual: Some(H256::repeat_byte(3)),
+                    last_writer: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn comparing_events() {
+        let replayed = replayed_batch(2);
+        let expected = [event(0, 1), event(1, 1), event(1, 2), event(2, 3)];
+        assert!(compare_events(&expected, &expected, &replayed).is_empty());
+
+        let actual = [event(0, 1), event(1, 1), event(2, 3)];
+        let mismatches = compare_events(&expected, &actual, &replayed);
+        assert_eq!(
+            mismatches,
+            [ReplayMismatch::Events {
+                tx_index_in_l1_batch: 1,
+                tx: replayed.tx(1),
+                expected_count: 2,
+                actual_count: 1,
+            }]
+        );
+        assert_eq!(mismatches[0].tx_index(), Some(1));
+
+        // Batch tip events cannot be attributed to a transaction.
+        let actual = [event(0, 1), event(1, 1), event(1, 2)];
+        let mismatches = compare_events(&expected, &actual, &replayed);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].tx_index(), None);
+    }
+
+    #[test]
+    fn comparing_l2_to_l1_logs() {
+        let log = |tx_number_in_block| L2ToL1Log {
+            tx_number_in_block,
+            ..L2ToL1Log::default()
+        };
+        let expected = [log(0), log(2)];
+        assert_eq!(compare_l2_to_l1_logs(&expected, &expected, false), None);
+
+        let mismatch = compare_l2_to_l1_logs(&expected, &[log(0)], false).unwrap();
+        assert_eq!(mismatch.tx_index(), Some(2));
+        assert_eq!(
+            mismatch,
+            ReplayMismatch::L2ToL1Logs {
+                system: false,
+                first_mismatch_index: 1,
+                expected: Some(log(2)),
+                actual: None,
+            }
+        );
+    }
+
+    #[test]
+    fn bootloader_memory_hash_depends_on_slots() {
+        let memory = [(0, U256::one()), (1, U256::from(2))];
+        assert_eq!(
+            bootloader_memory_hash(&memory),
+            bootloader_memory_hash(&memory)
+        );
+        let other_memory = [(0, U256::one()), (2, U256::from(2))];
+        assert_ne!(
+            bootloader_memory_hash(&memory),
+            bootloader_memory_hash(&other_memory)
+        );
+    }
+
+    #[tokio::test]
+    async fn replaying_genesis_batch_is_rejected() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let replayer = BatchReplayer::new(pool, L2ChainId::default());
+        let err = replayer.replay(L1BatchNumber(0)).await.unwrap_err();
+        assert!(err.to_string().contains("genesis"), "{err}");
+    }
+}
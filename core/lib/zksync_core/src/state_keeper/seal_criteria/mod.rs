@@ -24,8 +24,10 @@ use zksync_utils::time::millis_since;
 
 mod conditional_sealer;
 pub(super) mod criteria;
+mod policy;
 
 pub use self::conditional_sealer::{ConditionalSealer, NoopSealer, SequencerSealer};
+pub(crate) use self::policy::PolicySealer;
 use super::{extractors, metrics::AGGREGATION_METRICS, updates::UpdatesManager};
 use crate::gas_tracker::{gas_count_from_tx_and_metrics, gas_count_from_writes};
 
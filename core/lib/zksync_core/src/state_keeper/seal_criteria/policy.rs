@@ -0,0 +1,431 @@
+//! Declarative seal policies that can be changed without restarting the state keeper.
+
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use zksync_types::{Address, H256};
+use zksync_utils::time::seconds_since_epoch;
+
+use super::IoSealCriteria;
+use crate::{
+    state_keeper::{metrics::AGGREGATION_METRICS, updates::UpdatesManager},
+    utils::hot_reload::HotReloadedFile,
+};
+
+/// Scaling of the [`SealRule::Cadence`] interval depending on the L1 gas price.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct L1GasPriceScaling {
+    /// L1 gas price (in wei) starting from which the interval is scaled.
+    pub high_l1_gas_price: u64,
+    /// Multiplier applied to the interval if the L1 gas price of the batch is high.
+    pub interval_multiplier: u64,
+}
+
+/// Single rule of a [`SealPolicy`]. Each rule can only seal an L1 batch; it cannot prevent sealing
+/// by other criteria.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum SealRule {
+    /// Seals the batch once it contains the specified number of transactions.
+    TxCount { max_txs: usize },
+    /// Seals the batch once it contains the specified number of priority operations (i.e., L1 transactions).
+    PriorityOps { max_priority_ops: usize },
+    /// Seals the batch once a transaction in it emits an event from `contract`. If `topic` is specified,
+    /// the first indexed topic of the event must be equal to it.
+    ContractEvent {
+        contract: Address,
+        #[serde(default)]
+        topic: Option<H256>,
+    },
+    /// Seals the batch once wall-clock time crosses an epoch boundary, with epochs starting at
+    /// `offset_sec + k * interval_sec` seconds since the UNIX epoch. For example, `interval_sec: 86400`
+    /// and `offset_sec: 43200` seals batches at noon UTC.
+    Cadence {
+        interval_sec: u64,
+        #[serde(default)]
+        offset_sec: u64,
+        #[serde(default)]
+        l1_gas_price_scaling: Option<L1GasPriceScaling>,
+    },
+}
+
+impl SealRule {
+    fn validate(&self) -> anyhow::Result<()> {
+        match self {
+            Self::TxCount { max_txs } => {
+                anyhow::ensure!(*max_txs > 0, "`max_txs` must be positive")
+            }
+            Self::PriorityOps { max_priority_ops } => {
+                anyhow::ensure!(*max_priority_ops > 0, "`max_priority_ops` must be positive");
+            }
+            Self::ContractEvent { .. } => { /* no restrictions */ }
+            Self::Cadence {
+                interval_sec,
+                l1_gas_price_scaling,
+                ..
+            } => {
+                anyhow::ensure!(*interval_sec > 0, "`interval_sec` must be positive");
+                if let Some(scaling) = l1_gas_price_scaling {
+                    anyhow::ensure!(
+                        scaling.interval_multiplier > 0,
+                        "`interval_multiplier` must be positive"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether this rule wants to seal the L1 batch. `now` is the current UNIX timestamp in seconds.
+    ///
+    /// Event-based rules only inspect the pending miniblock; since the rules are checked after each executed transaction,
+    /// an event cannot be moved to a sealed miniblock before it is inspected.
+    fn should_seal(&self, manager: &UpdatesManager, now: u64) -> bool {
+        match self {
+            Self::TxCount { max_txs } => manager.pending_executed_transactions_len() >= *max_txs,
+            Self::PriorityOps { max_priority_ops } => {
+                let pending_priority_ops = manager
+                    .miniblock
+                    .executed_transactions
+                    .iter()
+                    .filter(|tx| tx.transaction.is_l1())
+                    .count();
+                manager.l1_batch.priority_ops_onchain_data.len() + pending_priority_ops
+                    >= *max_priority_ops
+            }
+            Self::ContractEvent { contract, topic } => {
+                manager.miniblock.events.iter().any(|event| {
+                    event.address == *contract
+                        && topic.map_or(true, |topic| event.indexed_topics.first() == Some(&topic))
+                })
+            }
+            Self::Cadence {
+                interval_sec,
+                offset_sec,
+                l1_gas_price_scaling,
+            } => {
+                let mut interval_sec = *interval_sec;
+                if let Some(scaling) = l1_gas_price_scaling {
+                    if manager.batch_fee_input().l1_gas_price() >= scaling.high_l1_gas_price {
+                        interval_sec = interval_sec.saturating_mul(scaling.interval_multiplier);
+                    }
+                }
+                let epoch = |timestamp: u64| timestamp.saturating_sub(*offset_sec) / interval_sec;
+                epoch(now) > epoch(manager.batch_timestamp())
+            }
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        match self {
+            Self::TxCount { .. } => "policy_tx_count",
+            Self::PriorityOps { .. } => "policy_priority_ops",
+            Self::ContractEvent { .. } => "policy_contract_event",
+            Self::Cadence { .. } => "policy_cadence",
+        }
+    }
+}
+
+/// Declarative seal policy consisting of [`SealRule`]s. An L1 batch is sealed if any of the rules triggers.
+///
+/// The policy is loaded from a JSON file like this:
+///
+/// ```json
+/// {
+///   "rules": [
+///     { "type": "priority_ops", "max_priority_ops": 10 },
+///     { "type": "contract_event", "contract": "0x000000000000000000000000000000000000dead" },
+///     { "type": "cadence", "interval_sec": 600, "offset_sec": 60 }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SealPolicy {
+    pub rules: Vec<SealRule>,
+}
+
+impl SealPolicy {
+    fn parse(contents: &str) -> anyhow::Result<Self> {
+        let policy: Self = serde_json::from_str(contents).context("failed parsing seal policy")?;
+        for (i, rule) in policy.rules.iter().enumerate() {
+            rule.validate()
+                .with_context(|| format!("invalid seal rule #{i}: {rule:?}"))?;
+        }
+        Ok(policy)
+    }
+}
+
+/// [`IoSealCriteria`] implementation for a [`SealPolicy`] loaded from a file.
+///
+/// The file is checked for modifications by a background task at most once per poll interval and is reloaded
+/// if it has changed, so that checking seal criteria never performs I/O. If the modified file cannot be read
+/// or parsed, the previously loaded policy remains in effect.
+#[derive(Debug)]
+pub(crate) struct PolicySealer {
+    policy: HotReloadedFile<SealPolicy>,
+}
+
+impl PolicySealer {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+    /// Loads the seal policy from the specified file.
+    pub async fn new(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        Self::with_poll_interval(path, Self::DEFAULT_POLL_INTERVAL).await
+    }
+
+    /// Loads the seal policy from the specified file, checking it for modifications with the specified interval.
+    pub async fn with_poll_interval(
+        path: impl Into<PathBuf>,
+        poll_interval: Duration,
+    ) -> anyhow::Result<Self> {
+        let policy =
+            HotReloadedFile::new(path, "seal policy", poll_interval, SealPolicy::parse).await?;
+        tracing::info!("Seal policy contains {} rules", policy.get().rules.len());
+        Ok(Self { policy })
+    }
+}
+
+impl IoSealCriteria for PolicySealer {
+    fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
+        if manager.pending_executed_transactions_len() == 0 {
+            // Similarly to `TimeoutSealer`, we never want to seal an empty batch.
+            return false;
+        }
+
+        let policy = self.policy.get();
+        let now = seconds_since_epoch();
+        let triggered_rule = policy
+            .rules
+            .iter()
+            .find(|rule| rule.should_seal(manager, now));
+        if let Some(rule) = triggered_rule {
+            let rule_name = rule.prom_criterion_name();
+            AGGREGATION_METRICS.inc_criterion(rule_name);
+            tracing::debug!("Decided to seal L1 batch using policy rule `{rule_name}`: {rule:?}");
+        }
+        triggered_rule.is_some()
+    }
+
+    fn should_seal_miniblock(&mut self, _manager: &UpdatesManager) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        block::BlockGasCount, l1::L1TxCommonData, tx::tx_execution_info::ExecutionMetrics,
+        vm_trace::Call, ExecuteTransactionCommon, L1BatchNumber, Transaction, VmEvent,
+    };
+
+    use super::*;
+    use crate::state_keeper::tests::{
+        create_execution_result, create_transaction, create_updates_manager,
+    };
+
+    fn apply_tx(manager: &mut UpdatesManager, tx: Transaction, events: Vec<VmEvent>) {
+        let mut result = create_execution_result(0, []);
+        result.logs.events = events;
+        manager.extend_from_executed_transaction(
+            tx,
+            result,
+            vec![],
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+            vec![],
+        );
+    }
+
+    fn create_l1_transaction() -> Transaction {
+        Transaction {
+            common_data: ExecuteTransactionCommon::L1(L1TxCommonData::default()),
+            execute: Default::default(),
+            received_timestamp_ms: 0,
+            raw_bytes: None,
+        }
+    }
+
+    #[test]
+    fn parsing_seal_policy() {
+        let policy = r#"{
+            "rules": [
+                { "type": "tx_count", "max_txs": 100 },
+                { "type": "priority_ops", "max_priority_ops": 5 },
+                {
+                    "type": "contract_event",
+                    "contract": "0x000000000000000000000000000000000000dead",
+                    "topic": "0x0101010101010101010101010101010101010101010101010101010101010101"
+                },
+                {
+                    "type": "cadence",
+                    "interval_sec": 600,
+                    "offset_sec": 30,
+                    "l1_gas_price_scaling": { "high_l1_gas_price": 100000000000, "interval_multiplier": 2 }
+                }
+            ]
+        }"#;
+        let policy = SealPolicy::parse(policy).unwrap();
+        assert_eq!(
+            policy.rules,
+            [
+                SealRule::TxCount { max_txs: 100 },
+                SealRule::PriorityOps {
+                    max_priority_ops: 5
+                },
+                SealRule::ContractEvent {
+                    contract: "0x000000000000000000000000000000000000dead"
+                        .parse()
+                        .unwrap(),
+                    topic: Some(H256::repeat_byte(1)),
+                },
+                SealRule::Cadence {
+                    interval_sec: 600,
+                    offset_sec: 30,
+                    l1_gas_price_scaling: Some(L1GasPriceScaling {
+                        high_l1_gas_price: 100_000_000_000,
+                        interval_multiplier: 2,
+                    }),
+                },
+            ]
+        );
+
+        let err = SealPolicy::parse(r#"{ "rules": [{ "type": "tx_count", "max_txs": 0 }] }"#)
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("max_txs"), "{err:#}");
+        SealPolicy::parse(r#"{ "rules": [{ "type": "unknown" }] }"#).unwrap_err();
+    }
+
+    #[test]
+    fn count_rules() {
+        let mut manager = create_updates_manager();
+        let tx_rule = SealRule::TxCount { max_txs: 2 };
+        let priority_ops_rule = SealRule::PriorityOps {
+            max_priority_ops: 2,
+        };
+        assert!(!tx_rule.should_seal(&manager, 0));
+
+        apply_tx(&mut manager, create_l1_transaction(), vec![]);
+        assert!(!tx_rule.should_seal(&manager, 0));
+        assert!(!priority_ops_rule.should_seal(&manager, 0));
+        apply_tx(&mut manager, create_transaction(10, 100), vec![]);
+        assert!(tx_rule.should_seal(&manager, 0));
+        assert!(!priority_ops_rule.should_seal(&manager, 0));
+
+        // Priority ops in sealed miniblocks should be taken into account as well.
+        let sealed_miniblock = manager.miniblock.clone();
+        manager
+            .l1_batch
+            .extend_from_sealed_miniblock(sealed_miniblock);
+        manager.miniblock.executed_transactions.clear();
+        apply_tx(&mut manager, create_l1_transaction(), vec![]);
+        assert!(priority_ops_rule.should_seal(&manager, 0));
+    }
+
+    #[test]
+    fn contract_event_rule() {
+        let contract = Address::repeat_byte(0xde);
+        let rule = SealRule::ContractEvent {
+            contract,
+            topic: Some(H256::repeat_byte(1)),
+        };
+        let mut manager = create_updates_manager();
+        let other_event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: contract,
+            indexed_topics: vec![H256::repeat_byte(2)],
+            value: vec![],
+        };
+        apply_tx(&mut manager, create_transaction(10, 100), vec![other_event]);
+        assert!(!rule.should_seal(&manager, 0));
+
+        let matching_event = VmEvent {
+            location: (L1BatchNumber(1), 1),
+            address: contract,
+            indexed_topics: vec![H256::repeat_byte(1), H256::zero()],
+            value: vec![],
+        };
+        apply_tx(
+            &mut manager,
+            create_transaction(10, 100),
+            vec![matching_event],
+        );
+        assert!(rule.should_seal(&manager, 0));
+    }
+
+    #[test]
+    fn cadence_rule() {
+        let manager = create_updates_manager();
+        let batch_timestamp = manager.batch_timestamp();
+        let l1_gas_price = manager.batch_fee_input().l1_gas_price();
+        let rule = SealRule::Cadence {
+            interval_sec: 100,
+            offset_sec: batch_timestamp % 100 + 10,
+            l1_gas_price_scaling: None,
+        };
+        // The batch is opened 10s before the boundary between epochs.
+        assert!(!rule.should_seal(&manager, batch_timestamp + 5));
+        assert!(rule.should_seal(&manager, batch_timestamp + 10));
+
+        let scaled_rule = SealRule::Cadence {
+            interval_sec: 100,
+            offset_sec: batch_timestamp % 100 + 10,
+            l1_gas_price_scaling: Some(L1GasPriceScaling {
+                high_l1_gas_price: l1_gas_price,
+                interval_multiplier: 1_000_000,
+            }),
+        };
+        assert!(!scaled_rule.should_seal(&manager, batch_timestamp + 10));
+
+        let unscaled_rule = SealRule::Cadence {
+            interval_sec: 100,
+            offset_sec: batch_timestamp % 100 + 10,
+            l1_gas_price_scaling: Some(L1GasPriceScaling {
+                high_l1_gas_price: l1_gas_price + 1,
+                interval_multiplier: 1_000_000,
+            }),
+        };
+        assert!(unscaled_rule.should_seal(&manager, batch_timestamp + 10));
+    }
+
+    #[tokio::test]
+    async fn policy_sealer_reloads_policy() {
+        const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("seal_policy.json");
+        tokio::fs::write(
+            &path,
+            r#"{ "rules": [{ "type": "tx_count", "max_txs": 2 }] }"#,
+        )
+        .await
+        .unwrap();
+        let mut sealer = PolicySealer::with_poll_interval(&path, POLL_INTERVAL)
+            .await
+            .unwrap();
+        let mut policy = sealer.policy.clone();
+
+        let mut manager = create_updates_manager();
+        assert!(!sealer.should_seal_l1_batch_unconditionally(&manager));
+        apply_tx(&mut manager, create_transaction(10, 100), vec![]);
+        assert!(!sealer.should_seal_l1_batch_unconditionally(&manager));
+
+        tokio::fs::write(
+            &path,
+            r#"{ "rules": [{ "type": "tx_count", "max_txs": 1 }, { "type": "priority_ops", "max_priority_ops": 1 }] }"#,
+        )
+        .await
+        .unwrap();
+        let reloaded_policy = policy.wait_for_reload().await;
+        assert_eq!(reloaded_policy.rules.len(), 2);
+        assert!(sealer.should_seal_l1_batch_unconditionally(&manager));
+
+        // An invalid policy should not replace the previously loaded one.
+        tokio::fs::write(&path, "{ invalid").await.unwrap();
+        tokio::time::sleep(POLL_INTERVAL * 5).await;
+        assert!(sealer.should_seal_l1_batch_unconditionally(&manager));
+        assert_eq!(sealer.policy.get().rules.len(), 2);
+    }
+}
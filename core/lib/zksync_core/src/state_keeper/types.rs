@@ -4,8 +4,11 @@ use std::{
 };
 
 use multivm::interface::VmExecutionResultAndLogs;
+use zksync_config::configs::chain::{MempoolConfig, MempoolEvictionPolicy};
 use zksync_dal::{Connection, Core, CoreDal};
-use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
+use zksync_mempool::{
+    AccountContent, EvictionPolicy, L2TxFilter, MempoolInfo, MempoolLimits, MempoolStore,
+};
 use zksync_types::{
     block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
 };
@@ -17,12 +20,15 @@ use crate::gas_tracker::{gas_count_from_metrics, gas_count_from_tx_and_metrics};
 pub struct MempoolGuard(Arc<Mutex<MempoolStore>>);
 
 impl MempoolGuard {
-    pub async fn from_storage(storage_processor: &mut Connection<'_, Core>, capacity: u64) -> Self {
+    pub async fn from_storage(
+        storage_processor: &mut Connection<'_, Core>,
+        config: &MempoolConfig,
+    ) -> Self {
         let next_priority_id = storage_processor
             .transactions_dal()
             .next_priority_id()
             .await;
-        Self::new(next_priority_id, capacity)
+        Self::with_config(next_priority_id, config)
     }
 
     pub(super) fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
@@ -30,6 +36,20 @@ impl MempoolGuard {
         Self(Arc::new(Mutex::new(store)))
     }
 
+    pub(super) fn with_config(next_priority_id: PriorityOpId, config: &MempoolConfig) -> Self {
+        let limits = MempoolLimits {
+            max_size_bytes: config.max_size_bytes,
+            max_txs_per_account: config.max_txs_per_account,
+            max_bytes_per_account: config.max_bytes_per_account,
+            eviction_policy: match config.eviction_policy {
+                MempoolEvictionPolicy::PurgeQueued => EvictionPolicy::PurgeQueued,
+                MempoolEvictionPolicy::LowestPriority => EvictionPolicy::LowestPriority,
+            },
+        };
+        let store = MempoolStore::new(next_priority_id, config.capacity).with_limits(limits);
+        Self(Arc::new(Mutex::new(store)))
+    }
+
     pub fn insert(&mut self, transactions: Vec<Transaction>, nonces: HashMap<Address, Nonce>) {
         self.0
             .lock()
@@ -51,6 +71,13 @@ impl MempoolGuard {
             .next_transaction(filter)
     }
 
+    pub fn peek_l2_transactions(&self, filter: &L2TxFilter, limit: usize) -> Vec<Transaction> {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .peek_l2_transactions(filter, limit)
+    }
+
     pub fn rollback(&mut self, rejected: &Transaction) {
         self.0
             .lock()
@@ -65,7 +92,6 @@ impl MempoolGuard {
             .get_mempool_info()
     }
 
-    #[cfg(test)]
     pub fn stats(&self) -> zksync_mempool::MempoolStats {
         self.0
             .lock()
@@ -73,6 +99,13 @@ impl MempoolGuard {
             .stats()
     }
 
+    pub fn l2_content(&self) -> HashMap<Address, AccountContent> {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .l2_content()
+    }
+
     pub fn register_metrics(&self) {
         StateKeeperGauges::register(Arc::downgrade(&self.0));
     }
@@ -63,6 +63,10 @@ impl UpdatesManager {
         self.batch_timestamp
     }
 
+    pub(crate) fn batch_fee_input(&self) -> BatchFeeInput {
+        self.batch_fee_input
+    }
+
     pub(crate) fn base_system_contract_hashes(&self) -> BaseSystemContractsHashes {
         self.base_system_contract_hashes
     }
@@ -535,7 +535,7 @@ impl StateKeeperIO for ExternalIO {
                 finished_batch,
                 self.l2_erc20_bridge_addr,
             )
-            .await;
+            .await?;
         drop(storage);
 
         self.update_miniblock_fields(&fictive_miniblock);
@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
 use zksync_types::aggregated_operations::AggregatedActionType;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
@@ -10,6 +10,9 @@ use zksync_types::aggregated_operations::AggregatedActionType;
 pub(super) enum FetchStage {
     GetMiniblockRange,
     GetBlockDetails,
+    GetL2BlockNumber,
+    GetL2Block,
+    GetL2BlockStateDiff,
 }
 
 #[derive(
@@ -54,3 +57,20 @@ pub(super) struct ActionQueueMetrics {
 
 #[vise::register]
 pub(super) static QUEUE_METRICS: vise::Global<ActionQueueMetrics> = vise::Global::new();
+
+/// Metrics for the light sync mode of the external node.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "external_node_state_diff_syncer")]
+pub(super) struct StateDiffSyncerMetrics {
+    /// Latency of persisting a single miniblock (including L1 batch data if the miniblock is the last in its batch).
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub persist_miniblock: Histogram<Duration>,
+    /// Number of storage logs applied from state diffs.
+    pub storage_logs: Counter,
+    /// Number of transactions persisted without execution.
+    pub transactions: Counter,
+}
+
+#[vise::register]
+pub(super) static STATE_DIFF_SYNCER_METRICS: vise::Global<StateDiffSyncerMetrics> =
+    vise::Global::new();
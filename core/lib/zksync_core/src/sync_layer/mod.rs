@@ -4,6 +4,7 @@ pub mod external_io;
 pub mod fetcher;
 pub mod genesis;
 mod metrics;
+pub mod state_diff_syncer;
 pub(crate) mod sync_action;
 mod sync_state;
 #[cfg(test)]
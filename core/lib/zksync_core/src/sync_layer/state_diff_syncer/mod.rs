@@ -0,0 +1,632 @@
+//! Light sync mode of the external node.
+//!
+//! In this mode, transactions are not re-executed locally. Instead, [`StateDiffSyncer`] fetches execution results
+//! (storage logs, events, factory deps and L1 batch data) from the main node and persists them directly.
+//! The received data is not trusted: its integrity is checked by the Merkle tree (which recomputes state root hashes
+//! from the persisted storage logs), by the re-org detector (which compares these root hashes with the main node)
+//! and by the consistency checker (which compares L1 batch commitments with ones published on L1).
+
+use std::{collections::HashMap, fmt, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use multivm::utils::get_max_gas_per_pubdata_byte;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{
+    api::en,
+    block::{MiniblockHasher, MiniblockHeader},
+    circuit::CircuitStatistic,
+    event::extract_added_tokens,
+    fee_model::BatchFeeInput,
+    tx::{
+        tx_execution_info::TxExecutionStatus, ExecutionMetrics, IncludedTxLocation,
+        TransactionExecutionResult,
+    },
+    Address, L1BatchNumber, MiniblockNumber, StorageKey, Transaction, VmEvent, H256,
+};
+use zksync_web3_decl::{
+    error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult},
+    jsonrpsee::http_client::HttpClient,
+    namespaces::{EnNamespaceClient, EthNamespaceClient},
+};
+
+use super::{
+    metrics::{FetchStage, L1BatchStage, FETCHER_METRICS, STATE_DIFF_SYNCER_METRICS},
+    SyncState,
+};
+use crate::state_keeper::io::{common::IoCursor, seal_logic::insert_transaction};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, thiserror::Error)]
+enum SyncError {
+    #[error("JSON-RPC error communicating with main node")]
+    Web3(#[from] EnrichedClientError),
+    #[error("Internal error")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<zksync_dal::SqlxError> for SyncError {
+    fn from(err: zksync_dal::SqlxError) -> Self {
+        Self::Internal(err.into())
+    }
+}
+
+#[async_trait]
+trait MainNodeClient: fmt::Debug + Send + Sync {
+    async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber>;
+
+    async fn fetch_l2_block(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<en::SyncBlock>>;
+
+    async fn fetch_l2_block_state_diff(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<en::L2BlockStateDiff>>;
+}
+
+#[async_trait]
+impl MainNodeClient for HttpClient {
+    async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+        let request_latency = FETCHER_METRICS.requests[&FetchStage::GetL2BlockNumber].start();
+        let number = self
+            .get_block_number()
+            .rpc_context("get_block_number")
+            .await?;
+        let number = u32::try_from(number)
+            .map_err(|err| EnrichedClientError::custom(err, "u32::try_from"))?;
+        request_latency.observe();
+        Ok(MiniblockNumber(number))
+    }
+
+    async fn fetch_l2_block(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<en::SyncBlock>> {
+        let request_latency = FETCHER_METRICS.requests[&FetchStage::GetL2Block].start();
+        let block = self
+            .sync_l2_block(number, true)
+            .rpc_context("sync_l2_block")
+            .with_arg("number", &number)
+            .await?;
+        request_latency.observe();
+        Ok(block)
+    }
+
+    async fn fetch_l2_block_state_diff(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<en::L2BlockStateDiff>> {
+        let request_latency = FETCHER_METRICS.requests[&FetchStage::GetL2BlockStateDiff].start();
+        let diff = self
+            .sync_l2_block_state_diff(number)
+            .rpc_context("sync_l2_block_state_diff")
+            .with_arg("number", &number)
+            .await?;
+        request_latency.observe();
+        Ok(diff)
+    }
+}
+
+/// Cursor of the [`StateDiffSyncer`] progress.
+#[derive(Debug, Clone, Serialize)]
+struct SyncerCursor {
+    next_miniblock: MiniblockNumber,
+    prev_miniblock_hash: H256,
+    /// Number of the currently open (i.e., not sealed) L1 batch.
+    l1_batch: L1BatchNumber,
+    /// Hashes of transactions in the currently open L1 batch, in the execution order.
+    #[serde(skip)]
+    pending_tx_hashes: Vec<H256>,
+}
+
+impl SyncerCursor {
+    async fn new(storage: &mut Connection<'_, Core>) -> anyhow::Result<Self> {
+        let IoCursor {
+            next_miniblock,
+            prev_miniblock_hash,
+            l1_batch,
+            ..
+        } = IoCursor::new(storage).await?;
+        let pending_miniblocks = storage
+            .transactions_dal()
+            .get_miniblocks_to_reexecute()
+            .await
+            .context("failed loading pending miniblocks")?;
+        let pending_tx_hashes = pending_miniblocks
+            .iter()
+            .flat_map(|miniblock| miniblock.txs.iter().map(Transaction::hash))
+            .collect();
+        Ok(Self {
+            next_miniblock,
+            prev_miniblock_hash,
+            l1_batch,
+            pending_tx_hashes,
+        })
+    }
+}
+
+/// Block and its state diff fetched from the main node.
+#[derive(Debug)]
+struct FetchedBlockWithDiff {
+    block: en::SyncBlock,
+    transactions: Vec<Transaction>,
+    diff: en::L2BlockStateDiff,
+}
+
+impl FetchedBlockWithDiff {
+    fn new(block: en::SyncBlock, diff: en::L2BlockStateDiff) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            block.number == diff.number,
+            "Mismatch between L2 block number {} and state diff number {}",
+            block.number,
+            diff.number
+        );
+        let transactions = block
+            .transactions
+            .clone()
+            .context("transactions are always requested")?;
+        anyhow::ensure!(
+            transactions.len() == diff.transactions.len(),
+            "Mismatch between number of transactions ({}) and transaction results ({}) in L2 block #{}",
+            transactions.len(),
+            diff.transactions.len(),
+            block.number
+        );
+        for (tx, tx_result) in transactions.iter().zip(&diff.transactions) {
+            anyhow::ensure!(
+                tx.hash() == tx_result.hash,
+                "Mismatch between transaction hash {:?} and result hash {:?} in L2 block #{}",
+                tx.hash(),
+                tx_result.hash,
+                block.number
+            );
+        }
+        anyhow::ensure!(
+            block.last_in_batch == diff.l1_batch.is_some(),
+            "L1 batch data must be present if and only if L2 block #{} is the last in its batch",
+            block.number
+        );
+        if let Some(l1_batch) = &diff.l1_batch {
+            anyhow::ensure!(
+                l1_batch.header.number == block.l1_batch_number,
+                "Mismatch between L1 batch number for L2 block #{} ({}) and the sealed L1 batch ({})",
+                block.number,
+                block.l1_batch_number,
+                l1_batch.header.number
+            );
+        }
+        Ok(Self {
+            block,
+            transactions,
+            diff,
+        })
+    }
+
+    fn compute_hash(&self, prev_miniblock_hash: H256) -> H256 {
+        let mut hasher =
+            MiniblockHasher::new(self.block.number, self.block.timestamp, prev_miniblock_hash);
+        for tx in &self.transactions {
+            hasher.push_tx_hash(tx.hash());
+        }
+        hasher.finalize(self.block.protocol_version)
+    }
+
+    fn executed_transactions(&self) -> anyhow::Result<Vec<TransactionExecutionResult>> {
+        let tx_results = self.transactions.iter().zip(&self.diff.transactions);
+        tx_results
+            .map(|(tx, tx_result)| {
+                let refunded_gas = u32::try_from(tx_result.refunded_gas).with_context(|| {
+                    format!(
+                        "refunded gas for transaction {:?} does not fit into u32: {}",
+                        tx_result.hash, tx_result.refunded_gas
+                    )
+                })?;
+                Ok(TransactionExecutionResult {
+                    transaction: tx.clone(),
+                    hash: tx_result.hash,
+                    // Execution metrics are not used on the external node.
+                    execution_info: ExecutionMetrics::default(),
+                    execution_status: if tx_result.error.is_some() {
+                        TxExecutionStatus::Failure
+                    } else {
+                        TxExecutionStatus::Success
+                    },
+                    refunded_gas,
+                    operator_suggested_refund: 0,
+                    compressed_bytecodes: vec![],
+                    call_traces: vec![],
+                    revert_reason: tx_result.error.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Converts events into the format used by the state keeper. `first_tx_index` is the index of
+    /// the first transaction in this block among all transactions in the L1 batch.
+    fn events(
+        &self,
+        first_tx_index: usize,
+    ) -> anyhow::Result<Vec<(IncludedTxLocation, Vec<VmEvent>)>> {
+        let l1_batch_number = self.block.l1_batch_number;
+        let events = self.diff.events.iter().map(|(tx_hash, events)| {
+            let (location, tx_index_in_l1_batch) = if tx_hash.is_zero() {
+                // Events emitted outside of transactions are attributed to the fictive transaction
+                // following all real ones, in the same way it's done by the state keeper.
+                let location = IncludedTxLocation {
+                    tx_hash: H256::zero(),
+                    tx_index_in_miniblock: 0,
+                    tx_initiator_address: Address::zero(),
+                };
+                (location, first_tx_index + self.transactions.len())
+            } else {
+                let tx_index = self
+                    .transactions
+                    .iter()
+                    .position(|tx| tx.hash() == *tx_hash)
+                    .with_context(|| {
+                        format!(
+                            "events reference transaction {tx_hash:?} not present in L2 block #{}",
+                            self.block.number
+                        )
+                    })?;
+                let location = IncludedTxLocation {
+                    tx_hash: *tx_hash,
+                    tx_index_in_miniblock: tx_index as u32,
+                    tx_initiator_address: self.transactions[tx_index].initiator_account(),
+                };
+                (location, first_tx_index + tx_index)
+            };
+
+            let events = events.iter().map(|event| VmEvent {
+                location: (l1_batch_number, tx_index_in_l1_batch as u32),
+                address: event.address,
+                indexed_topics: event.indexed_topics.clone(),
+                value: event.value.0.clone(),
+            });
+            Ok((location, events.collect()))
+        });
+        events.collect()
+    }
+}
+
+/// Component persisting L2 blocks and L1 batches fetched from the main node without re-executing their transactions.
+/// Used instead of the state keeper in the light sync mode of the external node.
+#[derive(Debug)]
+pub struct StateDiffSyncer {
+    client: Box<dyn MainNodeClient>,
+    pool: ConnectionPool<Core>,
+    sync_state: SyncState,
+    l2_erc20_bridge_addr: Address,
+    health_updater: HealthUpdater,
+    sleep_interval: Duration,
+}
+
+impl StateDiffSyncer {
+    const DEFAULT_SLEEP_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn new(
+        client: HttpClient,
+        pool: ConnectionPool<Core>,
+        sync_state: SyncState,
+        l2_erc20_bridge_addr: Address,
+    ) -> Self {
+        Self::from_parts(
+            Box::new(client),
+            pool,
+            sync_state,
+            l2_erc20_bridge_addr,
+            Self::DEFAULT_SLEEP_INTERVAL,
+        )
+    }
+
+    fn from_parts(
+        client: Box<dyn MainNodeClient>,
+        pool: ConnectionPool<Core>,
+        sync_state: SyncState,
+        l2_erc20_bridge_addr: Address,
+        sleep_interval: Duration,
+    ) -> Self {
+        Self {
+            client,
+            pool,
+            sync_state,
+            l2_erc20_bridge_addr,
+            health_updater: ReactiveHealthCheck::new("state_diff_syncer").1,
+            sleep_interval,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("sync_layer").await?;
+        let mut cursor = SyncerCursor::new(&mut storage).await?;
+        drop(storage);
+        tracing::info!("Initialized state diff syncer cursor: {cursor:?}");
+        self.health_updater
+            .update(Health::from(HealthStatus::Ready).with_details(&cursor));
+        if let Some(last_miniblock) = cursor.next_miniblock.0.checked_sub(1) {
+            self.sync_state
+                .set_local_block(MiniblockNumber(last_miniblock));
+        }
+
+        let mut main_node_block = MiniblockNumber(0);
+        while !*stop_receiver.borrow_and_update() {
+            let result = if cursor.next_miniblock > main_node_block {
+                self.client
+                    .fetch_l2_block_number()
+                    .await
+                    .map(|number| {
+                        main_node_block = number;
+                        self.sync_state.set_main_node_block(number);
+                        // Sleep only if there are no new blocks on the main node.
+                        cursor.next_miniblock <= main_node_block
+                    })
+                    .map_err(SyncError::from)
+            } else {
+                self.sync_next_block(&mut cursor).await
+            };
+
+            match result {
+                Ok(true) => {
+                    self.health_updater
+                        .update(Health::from(HealthStatus::Ready).with_details(&cursor));
+                    continue;
+                }
+                Ok(false) => { /* no new blocks */ }
+                Err(SyncError::Web3(err)) => {
+                    tracing::warn!("Failed fetching data from the main node: {err}");
+                }
+                Err(SyncError::Internal(err)) => return Err(err),
+            }
+
+            if tokio::time::timeout(self.sleep_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, state diff syncer is shutting down");
+        Ok(())
+    }
+
+    /// Fetches and persists the next L2 block. Returns `false` if the block isn't available on the main node yet.
+    async fn sync_next_block(&self, cursor: &mut SyncerCursor) -> Result<bool, SyncError> {
+        let number = cursor.next_miniblock;
+        let Some(block) = self.client.fetch_l2_block(number).await? else {
+            return Ok(false);
+        };
+        let diff = self
+            .client
+            .fetch_l2_block_state_diff(number)
+            .await?
+            .ok_or_else(|| {
+                // Can happen if the block was reverted on the main node between the calls.
+                EnrichedClientError::custom(
+                    "main node returned L2 block, but not its state diff",
+                    "sync_l2_block_state_diff",
+                )
+                .with_arg("number", &number)
+            })?;
+        let block = FetchedBlockWithDiff::new(block, diff)?;
+        let l1_batch_number = block.block.l1_batch_number;
+        if l1_batch_number != cursor.l1_batch {
+            let err = anyhow::anyhow!(
+                "L2 block #{number} belongs to unexpected L1 batch #{l1_batch_number}; expected #{}",
+                cursor.l1_batch
+            );
+            return Err(err.into());
+        }
+
+        let latency = STATE_DIFF_SYNCER_METRICS.persist_miniblock.start();
+        let mut storage = self.pool.connection_tagged("sync_layer").await?;
+        let mut transaction = storage.start_transaction().await?;
+        let miniblock_hash = self
+            .persist_miniblock(&mut transaction, &block, cursor)
+            .await?;
+        if let Some(l1_batch) = &block.diff.l1_batch {
+            let mut tx_hashes = cursor.pending_tx_hashes.clone();
+            tx_hashes.extend(block.diff.transactions.iter().map(|tx| tx.hash));
+            Self::persist_l1_batch(&mut transaction, l1_batch, &tx_hashes).await?;
+        }
+        transaction.commit().await?;
+        latency.observe();
+
+        // Only update the cursor after the changes are committed, so that it remains consistent with Postgres.
+        cursor.next_miniblock += 1;
+        cursor.prev_miniblock_hash = miniblock_hash;
+        if block.diff.l1_batch.is_some() {
+            tracing::info!("Sealed L1 batch #{l1_batch_number} from state diffs");
+            FETCHER_METRICS.l1_batch[&L1BatchStage::Open].set(cursor.l1_batch.0.into());
+            cursor.l1_batch += 1;
+            cursor.pending_tx_hashes.clear();
+        } else {
+            let tx_hashes = block.diff.transactions.iter().map(|tx| tx.hash);
+            cursor.pending_tx_hashes.extend(tx_hashes);
+        }
+        FETCHER_METRICS.miniblock.set(number.0.into());
+        self.sync_state.set_local_block(number);
+        Ok(true)
+    }
+
+    async fn persist_miniblock(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        block: &FetchedBlockWithDiff,
+        cursor: &SyncerCursor,
+    ) -> anyhow::Result<H256> {
+        let number = block.block.number;
+        let miniblock_hash = block.compute_hash(cursor.prev_miniblock_hash);
+        if let Some(reference_hash) = block.block.hash {
+            // This is a warning, not an error because hash mismatch may occur after a reorg, which will be detected
+            // by the re-org detector.
+            if miniblock_hash != reference_hash {
+                tracing::warn!(
+                    "Mismatch between the locally computed and received miniblock hash for #{number}; \
+                     local hash = {miniblock_hash:?}, reference hash = {reference_hash:?}"
+                );
+            }
+        }
+
+        for tx in &block.transactions {
+            insert_transaction(storage, tx.clone())
+                .await
+                .with_context(|| format!("failed inserting transaction {:?}", tx.hash()))?;
+        }
+
+        let l1_tx_count = block.transactions.iter().filter(|tx| tx.is_l1()).count();
+        let l2_tx_count = block.transactions.len() - l1_tx_count;
+        let protocol_version = block.block.protocol_version;
+        let miniblock_header = MiniblockHeader {
+            number,
+            timestamp: block.block.timestamp,
+            hash: miniblock_hash,
+            l1_tx_count: l1_tx_count as u16,
+            l2_tx_count: l2_tx_count as u16,
+            fee_account_address: block.block.operator_address,
+            base_fee_per_gas: block.diff.base_fee_per_gas,
+            batch_fee_input: BatchFeeInput::for_protocol_version(
+                protocol_version,
+                block.block.l2_fair_gas_price,
+                block.block.fair_pubdata_price,
+                block.block.l1_gas_price,
+            ),
+            gas_per_pubdata_limit: get_max_gas_per_pubdata_byte(protocol_version.into()),
+            base_system_contracts_hashes: block.block.base_system_contracts_hashes,
+            protocol_version: Some(protocol_version),
+            virtual_blocks: block.block.virtual_blocks.unwrap_or(0),
+        };
+        storage
+            .blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await
+            .with_context(|| format!("failed inserting miniblock #{number}"))?;
+
+        let executed_transactions = block.executed_transactions()?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_miniblock(
+                number,
+                &executed_transactions,
+                block.diff.base_fee_per_gas.into(),
+            )
+            .await;
+        STATE_DIFF_SYNCER_METRICS
+            .transactions
+            .inc_by(executed_transactions.len() as u64);
+
+        let storage_logs = &block.diff.storage_logs;
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(number, storage_logs)
+            .await
+            .with_context(|| format!("failed inserting storage logs for miniblock #{number}"))?;
+        #[allow(deprecated)] // Will be removed shortly
+        {
+            storage.storage_dal().apply_storage_logs(storage_logs).await;
+        }
+        let storage_log_count: usize = storage_logs.iter().map(|(_, logs)| logs.len()).sum();
+        STATE_DIFF_SYNCER_METRICS
+            .storage_logs
+            .inc_by(storage_log_count as u64);
+
+        if !block.diff.factory_deps.is_empty() {
+            let factory_deps: HashMap<_, _> = block
+                .diff
+                .factory_deps
+                .iter()
+                .map(|(hash, bytecode)| (*hash, bytecode.0.clone()))
+                .collect();
+            storage
+                .factory_deps_dal()
+                .insert_factory_deps(number, &factory_deps)
+                .await
+                .with_context(|| {
+                    format!("failed inserting factory deps for miniblock #{number}")
+                })?;
+        }
+
+        // Transactions in the L1 batch are indexed continuously across its miniblocks.
+        let events = block.events(cursor.pending_tx_hashes.len())?;
+        let all_events: Vec<_> = events
+            .iter()
+            .flat_map(|(_, events)| events.iter().cloned())
+            .collect();
+        let added_tokens = extract_added_tokens(self.l2_erc20_bridge_addr, &all_events);
+        if !added_tokens.is_empty() {
+            storage
+                .tokens_dal()
+                .add_tokens(&added_tokens)
+                .await
+                .context("failed inserting added tokens")?;
+        }
+        let events: Vec<_> = events
+            .iter()
+            .map(|(location, events)| (*location, events.iter().collect()))
+            .collect();
+        storage.events_dal().save_events(number, &events).await;
+        Ok(miniblock_hash)
+    }
+
+    async fn persist_l1_batch(
+        storage: &mut Connection<'_, Core>,
+        l1_batch: &en::L1BatchSealData,
+        tx_hashes: &[H256],
+    ) -> anyhow::Result<()> {
+        let header = &l1_batch.header;
+        let number = header.number;
+        storage
+            .blocks_dal()
+            .insert_l1_batch(
+                header,
+                &l1_batch.initial_bootloader_contents,
+                // Gas and circuit predictions are only used by the main node to seal batches.
+                Default::default(),
+                &l1_batch.events_queue,
+                &l1_batch.storage_refunds,
+                CircuitStatistic::default(),
+            )
+            .await
+            .with_context(|| format!("failed inserting L1 batch #{number}"))?;
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(number)
+            .await?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(number, tx_hashes)
+            .await;
+
+        // Unlike the state keeper, we don't have deduplicated VM storage logs, so we derive written slots
+        // from storage logs of the batch miniblocks. Protective reads are not available, but they are not
+        // required for the Merkle tree in the lightweight mode used by the external node.
+        let touched_slots = storage
+            .storage_logs_dal()
+            .get_touched_slots_for_l1_batch(number)
+            .await?;
+        let hashed_keys: Vec<_> = touched_slots.keys().map(StorageKey::hashed_key).collect();
+        let non_initial_writes = storage
+            .storage_logs_dedup_dal()
+            .filter_written_slots(&hashed_keys)
+            .await?;
+        let initial_writes: Vec<_> = touched_slots
+            .into_keys()
+            .filter(|key| !non_initial_writes.contains(&key.hashed_key()))
+            .collect();
+        storage
+            .storage_logs_dedup_dal()
+            .insert_initial_writes(number, &initial_writes)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+//! Tests for the state diff syncer.
+
+use std::sync::Mutex;
+
+use zksync_types::{
+    api::en::{L1BatchSealData, SyncEvent, SyncTxResult},
+    block::L1BatchHeader,
+    AccountTreeId, L2ChainId, ProtocolVersionId, StorageLog, U256,
+};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::create_l2_transaction,
+};
+
+#[derive(Debug, Default)]
+struct MockMainNodeClient {
+    blocks: Mutex<HashMap<MiniblockNumber, (en::SyncBlock, en::L2BlockStateDiff)>>,
+}
+
+impl MockMainNodeClient {
+    fn insert(&self, block: en::SyncBlock, diff: en::L2BlockStateDiff) {
+        self.blocks
+            .lock()
+            .unwrap()
+            .insert(block.number, (block, diff));
+    }
+}
+
+#[async_trait]
+impl MainNodeClient for MockMainNodeClient {
+    async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+        let blocks = self.blocks.lock().unwrap();
+        Ok(blocks.keys().copied().max().unwrap_or_default())
+    }
+
+    async fn fetch_l2_block(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<en::SyncBlock>> {
+        let blocks = self.blocks.lock().unwrap();
+        Ok(blocks.get(&number).map(|(block, _)| block.clone()))
+    }
+
+    async fn fetch_l2_block_state_diff(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<en::L2BlockStateDiff>> {
+        let blocks = self.blocks.lock().unwrap();
+        Ok(blocks.get(&number).map(|(_, diff)| diff.clone()))
+    }
+}
+
+fn sync_block(
+    number: u32,
+    genesis_header: &MiniblockHeader,
+    transactions: Vec<Transaction>,
+    last_in_batch: bool,
+) -> en::SyncBlock {
+    en::SyncBlock {
+        number: MiniblockNumber(number),
+        l1_batch_number: L1BatchNumber(1),
+        last_in_batch,
+        timestamp: number.into(),
+        l1_gas_price: 2,
+        l2_fair_gas_price: 3,
+        fair_pubdata_price: Some(4),
+        base_system_contracts_hashes: genesis_header.base_system_contracts_hashes,
+        operator_address: Address::repeat_byte(1),
+        transactions: Some(transactions),
+        virtual_blocks: Some(1),
+        hash: None,
+        protocol_version: ProtocolVersionId::latest(),
+    }
+}
+
+#[tokio::test]
+async fn syncing_l1_batch_from_state_diffs() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let genesis_header = storage
+        .blocks_dal()
+        .get_miniblock_header(MiniblockNumber(0))
+        .await
+        .unwrap()
+        .expect("no genesis miniblock");
+
+    let tx: Transaction = create_l2_transaction(10, 100).into();
+    let tx_hash = tx.hash();
+    let storage_key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(2)), H256::zero());
+    let storage_value = H256::repeat_byte(3);
+    let bytecode_hash = H256::repeat_byte(4);
+    let event = SyncEvent {
+        address: Address::repeat_byte(5),
+        indexed_topics: vec![H256::repeat_byte(6)],
+        value: vec![1, 2, 3].into(),
+    };
+
+    let client = MockMainNodeClient::default();
+    let block = sync_block(1, &genesis_header, vec![tx], false);
+    let diff = en::L2BlockStateDiff {
+        number: MiniblockNumber(1),
+        base_fee_per_gas: 3,
+        transactions: vec![SyncTxResult {
+            hash: tx_hash,
+            error: None,
+            refunded_gas: 100,
+        }],
+        storage_logs: vec![(
+            tx_hash,
+            vec![StorageLog::new_write_log(storage_key, storage_value)],
+        )],
+        events: vec![(tx_hash, vec![event])],
+        factory_deps: HashMap::from([(bytecode_hash, vec![0; 32].into())]),
+        l1_batch: None,
+    };
+    client.insert(block, diff);
+
+    let fictive_block = sync_block(2, &genesis_header, vec![], true);
+    let mut l1_batch_header = L1BatchHeader::new(
+        L1BatchNumber(1),
+        2,
+        genesis_header.base_system_contracts_hashes,
+        ProtocolVersionId::latest(),
+    );
+    l1_batch_header.l2_tx_count = 1;
+    let fictive_diff = en::L2BlockStateDiff {
+        number: MiniblockNumber(2),
+        base_fee_per_gas: 3,
+        transactions: vec![],
+        storage_logs: vec![],
+        events: vec![],
+        factory_deps: HashMap::new(),
+        l1_batch: Some(L1BatchSealData {
+            header: l1_batch_header,
+            initial_bootloader_contents: vec![(0, U256::one())],
+            events_queue: vec![],
+            storage_refunds: vec![0],
+        }),
+    };
+    client.insert(fictive_block, fictive_diff);
+
+    let sync_state = SyncState::default();
+    let syncer = StateDiffSyncer::from_parts(
+        Box::new(client),
+        pool.clone(),
+        sync_state.clone(),
+        Address::zero(),
+        Duration::from_millis(10),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let syncer_task = tokio::spawn(syncer.run(stop_receiver));
+
+    while sync_state.get_local_block() < MiniblockNumber(2) {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    stop_sender.send_replace(true);
+    syncer_task.await.unwrap().unwrap();
+    assert_eq!(sync_state.get_local_block(), MiniblockNumber(2));
+
+    assert_eq!(
+        storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .unwrap(),
+        Some(MiniblockNumber(2))
+    );
+    assert_eq!(
+        storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap(),
+        Some(L1BatchNumber(1))
+    );
+    let miniblock_header = storage
+        .blocks_dal()
+        .get_miniblock_header(MiniblockNumber(1))
+        .await
+        .unwrap()
+        .expect("no miniblock #1");
+    assert_eq!(miniblock_header.l2_tx_count, 1);
+    assert_eq!(
+        miniblock_header.fee_account_address,
+        Address::repeat_byte(1)
+    );
+
+    let receipts = storage
+        .transactions_web3_dal()
+        .get_transaction_receipts(&[tx_hash])
+        .await
+        .unwrap();
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].status, 1.into());
+    assert_eq!(receipts[0].l1_batch_number, Some(1.into()));
+
+    let value = storage
+        .storage_web3_dal()
+        .get_value(&storage_key)
+        .await
+        .unwrap();
+    assert_eq!(value, storage_value);
+    let initial_writes = storage
+        .storage_logs_dedup_dal()
+        .initial_writes_for_batch(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(initial_writes.len(), 1);
+    assert_eq!(initial_writes[0].0, storage_key.hashed_key());
+
+    let logs = storage
+        .events_web3_dal()
+        .get_all_logs(MiniblockNumber(0))
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].transaction_hash, Some(tx_hash));
+    assert_eq!(logs[0].topics, [H256::repeat_byte(6)]);
+
+    let factory_dep = storage
+        .factory_deps_dal()
+        .get_factory_dep(bytecode_hash)
+        .await
+        .unwrap();
+    assert_eq!(factory_dep, Some(vec![0; 32]));
+}
+
+#[tokio::test]
+async fn mismatched_state_diff_is_rejected() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let genesis_header = storage
+        .blocks_dal()
+        .get_miniblock_header(MiniblockNumber(0))
+        .await
+        .unwrap()
+        .expect("no genesis miniblock");
+
+    let tx: Transaction = create_l2_transaction(10, 100).into();
+    let block = sync_block(1, &genesis_header, vec![tx], false);
+    let diff = en::L2BlockStateDiff {
+        number: MiniblockNumber(1),
+        base_fee_per_gas: 3,
+        transactions: vec![SyncTxResult {
+            hash: H256::repeat_byte(1), // doesn't match the transaction
+            error: None,
+            refunded_gas: 0,
+        }],
+        storage_logs: vec![],
+        events: vec![],
+        factory_deps: HashMap::new(),
+        l1_batch: None,
+    };
+    let client = MockMainNodeClient::default();
+    client.insert(block, diff);
+
+    let syncer = StateDiffSyncer::from_parts(
+        Box::new(client),
+        pool.clone(),
+        SyncState::default(),
+        Address::zero(),
+        Duration::from_millis(10),
+    );
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = syncer.run(stop_receiver).await.unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("Mismatch between transaction hash"), "{err}");
+
+    assert_eq!(
+        storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .unwrap(),
+        Some(MiniblockNumber(0))
+    );
+}
+
+#[tokio::test]
+async fn overflowing_refunded_gas_is_rejected() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let genesis_header = storage
+        .blocks_dal()
+        .get_miniblock_header(MiniblockNumber(0))
+        .await
+        .unwrap()
+        .expect("no genesis miniblock");
+
+    let tx: Transaction = create_l2_transaction(10, 100).into();
+    let tx_hash = tx.hash();
+    let block = sync_block(1, &genesis_header, vec![tx], false);
+    let diff = en::L2BlockStateDiff {
+        number: MiniblockNumber(1),
+        base_fee_per_gas: 3,
+        transactions: vec![SyncTxResult {
+            hash: tx_hash,
+            error: None,
+            refunded_gas: u64::from(u32::MAX) + 1,
+        }],
+        storage_logs: vec![],
+        events: vec![],
+        factory_deps: HashMap::new(),
+        l1_batch: None,
+    };
+    let block = FetchedBlockWithDiff::new(block, diff).unwrap();
+    let err = block.executed_transactions().unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("does not fit into u32"), "{err}");
+}
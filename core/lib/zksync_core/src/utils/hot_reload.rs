@@ -0,0 +1,140 @@
+//! Files that are reloaded in the background once they are modified.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+
+/// Modification time and length of a file. Used to detect file modifications without reading the file.
+type FileVersion = (Option<SystemTime>, u64);
+
+/// Handle to the contents of a file parsed into `T`. The file is checked for modifications
+/// by a background task at most once per poll interval and is reloaded if it has changed.
+/// If the modified file cannot be read or parsed, the previously loaded contents remain in effect.
+///
+/// Getting the current contents is cheap and never blocks on I/O, so it can be used on hot paths
+/// (e.g., in the state keeper). The background task terminates once all handles are dropped.
+#[derive(Debug, Clone)]
+pub(crate) struct HotReloadedFile<T> {
+    receiver: watch::Receiver<Arc<T>>,
+}
+
+impl<T: Send + Sync + 'static> HotReloadedFile<T> {
+    /// Loads the file and spawns a background task reloading it. `description` is a human-readable
+    /// description of the file contents used in logs (e.g., "seal policy").
+    pub async fn new(
+        path: impl Into<PathBuf>,
+        description: &'static str,
+        poll_interval: Duration,
+        parse: fn(&str) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Self> {
+        let path = path.into();
+        let file_version = file_version(&path).await?;
+        let contents = load(&path, parse).await?;
+        tracing::info!("Loaded {description} from `{}`", path.display());
+
+        let (sender, receiver) = watch::channel(Arc::new(contents));
+        let reloader = FileReloader {
+            path,
+            description,
+            poll_interval,
+            parse,
+            file_version,
+            sender,
+        };
+        tokio::spawn(reloader.run());
+        Ok(Self { receiver })
+    }
+
+    /// Returns the most recently loaded file contents.
+    pub fn get(&self) -> Arc<T> {
+        self.receiver.borrow().clone()
+    }
+
+    /// Waits until the file contents are reloaded.
+    #[cfg(test)]
+    pub async fn wait_for_reload(&mut self) -> Arc<T> {
+        self.receiver
+            .changed()
+            .await
+            .expect("file reloader terminated");
+        self.get()
+    }
+}
+
+async fn file_version(path: &Path) -> anyhow::Result<FileVersion> {
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .context("failed getting file metadata")?;
+    Ok((metadata.modified().ok(), metadata.len()))
+}
+
+async fn load<T>(path: &Path, parse: fn(&str) -> anyhow::Result<T>) -> anyhow::Result<T> {
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .context("failed reading file")?;
+    parse(&contents)
+}
+
+#[derive(Debug)]
+struct FileReloader<T> {
+    path: PathBuf,
+    description: &'static str,
+    poll_interval: Duration,
+    parse: fn(&str) -> anyhow::Result<T>,
+    file_version: FileVersion,
+    sender: watch::Sender<Arc<T>>,
+}
+
+impl<T> FileReloader<T> {
+    async fn run(mut self) {
+        loop {
+            tokio::select! {
+                () = self.sender.closed() => {
+                    tracing::debug!(
+                        "All handles to {} from `{}` are dropped; stopping reloading it",
+                        self.description,
+                        self.path.display()
+                    );
+                    return;
+                }
+                () = tokio::time::sleep(self.poll_interval) => {}
+            }
+            self.reload_if_modified().await;
+        }
+    }
+
+    async fn reload_if_modified(&mut self) {
+        let reload_result = match file_version(&self.path).await {
+            Ok(version) if version == self.file_version => Ok(None),
+            Ok(version) => load(&self.path, self.parse)
+                .await
+                .map(|contents| Some((version, contents))),
+            Err(err) => Err(err),
+        };
+
+        match reload_result {
+            Ok(None) => { /* file is not modified */ }
+            Ok(Some((version, contents))) => {
+                tracing::info!(
+                    "Reloaded {} from `{}`",
+                    self.description,
+                    self.path.display()
+                );
+                self.file_version = version;
+                self.sender.send_replace(Arc::new(contents));
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "Failed reloading {} from `{}`, continuing to use the previously loaded version: {err:#}",
+                    self.description,
+                    self.path.display()
+                );
+            }
+        }
+    }
+}
@@ -5,7 +5,10 @@
 use anyhow::Context;
 use zksync_config::{
     configs::{
-        chain::{MempoolConfig, NetworkConfig, OperationsManagerConfig, StateKeeperConfig},
+        chain::{
+            CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
+            StateKeeperConfig,
+        },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
         FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, ObservabilityConfig,
@@ -90,7 +93,7 @@ impl MainNodeBuilder {
 
     fn add_query_eth_client_layer(mut self) -> anyhow::Result<Self> {
         let eth_client_config = ETHClientConfig::from_env()?;
-        let query_eth_client_layer = QueryEthClientLayer::new(eth_client_config.web3_url);
+        let query_eth_client_layer = QueryEthClientLayer::new(eth_client_config);
         self.node.add_layer(query_eth_client_layer);
         Ok(self)
     }
@@ -277,6 +280,8 @@ impl MainNodeBuilder {
 
     fn add_house_keeper_layer(mut self) -> anyhow::Result<Self> {
         let house_keeper_config = HouseKeeperConfig::from_env()?;
+        let circuit_breaker_config = CircuitBreakerConfig::from_env()?;
+        let contracts_config = ContractsConfig::from_env()?;
         let fri_prover_config = FriProverConfig::from_env()?;
         let fri_witness_generator_config = FriWitnessGeneratorConfig::from_env()?;
         let fri_prover_group_config = FriProverGroupConfig::from_env()?;
@@ -284,6 +289,8 @@ impl MainNodeBuilder {
 
         self.node.add_layer(HouseKeeperLayer::new(
             house_keeper_config,
+            circuit_breaker_config,
+            contracts_config,
             fri_prover_config,
             fri_witness_generator_config,
             fri_prover_group_config,
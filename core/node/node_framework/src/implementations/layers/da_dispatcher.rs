@@ -0,0 +1,60 @@
+use zksync_config::configs::DADispatcherConfig;
+use zksync_core::da_dispatcher::{create_da_client, DataAvailabilityDispatcher};
+
+use crate::{
+    implementations::resources::pools::MasterPoolResource,
+    service::{ServiceContext, StopReceiver},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Builder for a data availability dispatcher.
+///
+/// ## Effects
+///
+/// - Resolves `MasterPoolResource`.
+/// - Adds `da_dispatcher` to the node.
+#[derive(Debug)]
+pub struct DADispatcherLayer {
+    config: DADispatcherConfig,
+}
+
+impl DADispatcherLayer {
+    pub fn new(config: DADispatcherConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for DADispatcherLayer {
+    fn layer_name(&self) -> &'static str {
+        "da_dispatcher_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<MasterPoolResource>().await?;
+        let main_pool = pool_resource.get().await.unwrap();
+
+        let da_client = create_da_client(&self.config).await?;
+        let da_dispatcher = DataAvailabilityDispatcher::new(main_pool, self.config, da_client);
+
+        context.add_task(Box::new(DADispatcherTask { da_dispatcher }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct DADispatcherTask {
+    da_dispatcher: DataAvailabilityDispatcher,
+}
+
+#[async_trait::async_trait]
+impl Task for DADispatcherTask {
+    fn name(&self) -> &'static str {
+        "da_dispatcher"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.da_dispatcher.run(stop_receiver.0).await
+    }
+}
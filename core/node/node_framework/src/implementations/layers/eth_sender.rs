@@ -1,15 +1,14 @@
-use std::sync::Arc;
-
 use zksync_config::configs::{
     chain::NetworkConfig, eth_sender::ETHSenderConfig, ContractsConfig, ETHClientConfig,
 };
-use zksync_core::eth_sender::{Aggregator, EthTxAggregator, EthTxManager};
-use zksync_eth_client::{clients::PKSigningClient, BoundEthInterface};
+use zksync_core::eth_sender::{Aggregator, EthTxAggregator, EthTxManager, OperatorAccounts};
 
 use crate::{
     implementations::resources::{
-        eth_interface::BoundEthInterfaceResource, l1_tx_params::L1TxParamsResource,
-        object_store::ObjectStoreResource, pools::MasterPoolResource,
+        eth_interface::BoundEthInterfaceResource,
+        l1_tx_params::{L1FeeStatisticsResource, L1TxParamsResource},
+        object_store::ObjectStoreResource,
+        pools::MasterPoolResource,
     },
     service::{ServiceContext, StopReceiver},
     task::Task,
@@ -55,19 +54,30 @@ impl WiringLayer for EthSenderLayer {
 
         let object_store = context.get_resource::<ObjectStoreResource>().await?.0;
 
+        let l1_fee_statistics = if self
+            .eth_sender_config
+            .sender
+            .l1_fee_postpone_percentile
+            .is_some()
+        {
+            Some(context.get_resource::<L1FeeStatisticsResource>().await?.0)
+        } else {
+            None
+        };
+
         // Create and add tasks
-        let eth_client_blobs = PKSigningClient::from_config_blobs(
+        let operator_accounts = OperatorAccounts::new(eth_client).with_configured_accounts(
             &self.eth_sender_config,
             &self.contracts_config,
             &self.eth_client_config,
-        );
-        let eth_client_blobs_addr = eth_client_blobs.clone().map(|k| k.sender_account());
+        )?;
 
         let aggregator = Aggregator::new(
             self.eth_sender_config.sender.clone(),
             object_store,
-            eth_client_blobs_addr.is_some(),
+            self.eth_sender_config.sender.private_key_blobs().is_some(),
             self.eth_sender_config.sender.pubdata_sending_mode.into(),
+            l1_fee_statistics,
         );
 
         let config = self.eth_sender_config.sender;
@@ -76,12 +86,11 @@ impl WiringLayer for EthSenderLayer {
             pool.clone(),
             config.clone(),
             aggregator,
-            eth_client.clone(),
+            operator_accounts.clone(),
             self.contracts_config.validator_timelock_addr,
             self.contracts_config.l1_multicall3_addr,
             self.contracts_config.diamond_proxy_addr,
             self.network_config.zksync_network_id,
-            eth_client_blobs_addr,
         )
         .await;
 
@@ -91,13 +100,7 @@ impl WiringLayer for EthSenderLayer {
 
         let gas_adjuster = context.get_resource::<L1TxParamsResource>().await?.0;
 
-        let eth_tx_manager_actor = EthTxManager::new(
-            pool,
-            config,
-            gas_adjuster,
-            eth_client,
-            eth_client_blobs.map(|c| Arc::new(c) as Arc<dyn BoundEthInterface>),
-        );
+        let eth_tx_manager_actor = EthTxManager::new(pool, config, gas_adjuster, operator_accounts);
 
         context.add_task(Box::new(EthTxManagerTask {
             eth_tx_manager_actor,
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use anyhow::Context as _;
 use zksync_config::{ContractsConfig, ETHWatchConfig};
 use zksync_contracts::governance_contract;
 use zksync_core::eth_watch::{client::EthHttpQueryClient, EthWatch};
@@ -7,12 +8,23 @@ use zksync_dal::{ConnectionPool, Core};
 use zksync_types::{ethabi::Contract, Address};
 
 use crate::{
-    implementations::resources::{eth_interface::EthInterfaceResource, pools::MasterPoolResource},
+    implementations::resources::{
+        eth_interface::EthInterfaceResource, eth_watch::EthWatchEventProcessorResource,
+        pools::MasterPoolResource,
+    },
+    resource::ResourceCollection,
     service::{ServiceContext, StopReceiver},
     task::Task,
     wiring_layer::{WiringError, WiringLayer},
 };
 
+/// Wiring layer for `eth_watch`.
+///
+/// ## Effects
+///
+/// - Resolves `ResourceCollection<EthWatchEventProcessorResource>` and registers the provided
+///   event processors in addition to the built-in ones.
+/// - Adds `eth_watch` to the node.
 #[derive(Debug)]
 pub struct EthWatchLayer {
     eth_watch_config: ETHWatchConfig,
@@ -39,6 +51,9 @@ impl WiringLayer for EthWatchLayer {
         let main_pool = pool_resource.get().await.unwrap();
 
         let client = context.get_resource::<EthInterfaceResource>().await?.0;
+        let extra_processors = context
+            .get_resource_or_default::<ResourceCollection<EthWatchEventProcessorResource>>()
+            .await;
 
         let eth_client = EthHttpQueryClient::new(
             client,
@@ -49,9 +64,10 @@ impl WiringLayer for EthWatchLayer {
         context.add_task(Box::new(EthWatchTask {
             main_pool,
             client: eth_client,
-            governance_contract: Some(governance_contract()),
+            governance: Some((governance_contract(), self.contracts_config.governance_addr)),
             diamond_proxy_address: self.contracts_config.diamond_proxy_addr,
             poll_interval: self.eth_watch_config.poll_interval(),
+            extra_processors,
         }));
 
         Ok(())
@@ -62,9 +78,10 @@ impl WiringLayer for EthWatchLayer {
 struct EthWatchTask {
     main_pool: ConnectionPool<Core>,
     client: EthHttpQueryClient,
-    governance_contract: Option<Contract>,
+    governance: Option<(Contract, Address)>,
     diamond_proxy_address: Address,
     poll_interval: Duration,
+    extra_processors: ResourceCollection<EthWatchEventProcessorResource>,
 }
 
 #[async_trait::async_trait]
@@ -74,15 +91,21 @@ impl Task for EthWatchTask {
     }
 
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
-        let eth_watch = EthWatch::new(
+        let mut eth_watch = EthWatch::new(
             self.diamond_proxy_address,
-            self.governance_contract,
+            self.governance,
             Box::new(self.client),
             self.main_pool,
             self.poll_interval,
         )
         .await;
 
+        for EthWatchEventProcessorResource(processor) in self.extra_processors.resolve().await {
+            let processor = processor
+                .take()
+                .context("event processor was already taken by another task")?;
+            eth_watch.register_processor(processor)?;
+        }
         eth_watch.run(stop_receiver.0).await
     }
 }
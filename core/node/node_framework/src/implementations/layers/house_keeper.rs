@@ -1,9 +1,14 @@
 use std::time::Duration;
 
-use zksync_config::configs::{
-    fri_prover_group::FriProverGroupConfig, house_keeper::HouseKeeperConfig,
-    FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig,
+use zksync_config::{
+    configs::{
+        chain::CircuitBreakerConfig, fri_prover_group::FriProverGroupConfig,
+        house_keeper::HouseKeeperConfig, FriProofCompressorConfig, FriProverConfig,
+        FriWitnessGeneratorConfig,
+    },
+    ContractsConfig,
 };
+use zksync_contracts::zksync_contract;
 use zksync_core::house_keeper::{
     blocks_state_reporter::L1BatchMetricsReporter,
     fri_proof_compressor_job_retry_manager::FriProofCompressorJobRetryManager,
@@ -13,13 +18,17 @@ use zksync_core::house_keeper::{
     fri_scheduler_circuit_queuer::SchedulerCircuitQueuer,
     fri_witness_generator_jobs_retry_manager::FriWitnessGeneratorJobRetryManager,
     fri_witness_generator_queue_monitor::FriWitnessGeneratorStatsReporter,
-    periodic_job::PeriodicJob,
+    periodic_job::PeriodicJob, priority_queue_monitor::PriorityQueueMonitor,
     waiting_to_queued_fri_witness_job_mover::WaitingToQueuedFriWitnessJobMover,
 };
 use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core};
 
 use crate::{
-    implementations::resources::pools::{ProverPoolResource, ReplicaPoolResource},
+    implementations::resources::{
+        eth_interface::EthInterfaceResource,
+        healthcheck::AppHealthCheckResource,
+        pools::{ProverPoolResource, ReplicaPoolResource},
+    },
     service::{ServiceContext, StopReceiver},
     task::Task,
     wiring_layer::{WiringError, WiringLayer},
@@ -30,6 +39,8 @@ const SCRAPE_INTERVAL: Duration = Duration::from_secs(60);
 #[derive(Debug)]
 pub struct HouseKeeperLayer {
     house_keeper_config: HouseKeeperConfig,
+    circuit_breaker_config: CircuitBreakerConfig,
+    contracts_config: ContractsConfig,
     fri_prover_config: FriProverConfig,
     fri_witness_generator_config: FriWitnessGeneratorConfig,
     fri_prover_group_config: FriProverGroupConfig,
@@ -39,6 +50,8 @@ pub struct HouseKeeperLayer {
 impl HouseKeeperLayer {
     pub fn new(
         house_keeper_config: HouseKeeperConfig,
+        circuit_breaker_config: CircuitBreakerConfig,
+        contracts_config: ContractsConfig,
         fri_prover_config: FriProverConfig,
         fri_witness_generator_config: FriWitnessGeneratorConfig,
         fri_prover_group_config: FriProverGroupConfig,
@@ -46,6 +59,8 @@ impl HouseKeeperLayer {
     ) -> Self {
         Self {
             house_keeper_config,
+            circuit_breaker_config,
+            contracts_config,
             fri_prover_config,
             fri_witness_generator_config,
             fri_prover_group_config,
@@ -68,6 +83,8 @@ impl WiringLayer for HouseKeeperLayer {
         let prover_pool_resource = context.get_resource::<ProverPoolResource>().await?;
         let prover_pool = prover_pool_resource.get().await?;
 
+        let eth_client = context.get_resource::<EthInterfaceResource>().await?.0;
+
         // initialize and add tasks
         let pool_for_metrics = replica_pool.clone();
         context.add_task(Box::new(PoolForMetricsTask { pool_for_metrics }));
@@ -81,6 +98,21 @@ impl WiringLayer for HouseKeeperLayer {
             l1_batch_metrics_reporter,
         }));
 
+        let priority_queue_monitor = PriorityQueueMonitor::new(
+            self.house_keeper_config
+                .priority_queue_monitoring_interval_ms,
+            replica_pool.clone(),
+            eth_client,
+            self.contracts_config.diamond_proxy_addr,
+            zksync_contract(),
+            self.circuit_breaker_config.priority_op_deadline_margin(),
+        );
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health.insert_component(priority_queue_monitor.health_check());
+        context.add_task(Box::new(PriorityQueueMonitorTask {
+            priority_queue_monitor,
+        }));
+
         let fri_prover_job_retry_manager = FriProverJobRetryManager::new(
             self.fri_prover_config.max_attempts,
             self.fri_prover_config.proof_generation_timeout(),
@@ -196,6 +228,22 @@ impl Task for L1BatchMetricsReporterTask {
     }
 }
 
+#[derive(Debug)]
+struct PriorityQueueMonitorTask {
+    priority_queue_monitor: PriorityQueueMonitor,
+}
+
+#[async_trait::async_trait]
+impl Task for PriorityQueueMonitorTask {
+    fn name(&self) -> &'static str {
+        "priority_queue_monitor"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.priority_queue_monitor.run(stop_receiver.0).await
+    }
+}
+
 #[derive(Debug)]
 struct FriProverJobRetryManagerTask {
     fri_prover_job_retry_manager: FriProverJobRetryManager,
@@ -10,8 +10,9 @@ use zksync_types::fee_model::FeeModelConfig;
 
 use crate::{
     implementations::resources::{
-        eth_interface::EthInterfaceResource, fee_input::FeeInputResource,
-        l1_tx_params::L1TxParamsResource,
+        eth_interface::EthInterfaceResource,
+        fee_input::FeeInputResource,
+        l1_tx_params::{L1FeeStatisticsResource, L1TxParamsResource},
     },
     service::{ServiceContext, StopReceiver},
     task::Task,
@@ -60,6 +61,7 @@ impl WiringLayer for SequencerL1GasLayer {
         context.insert_resource(FeeInputResource(batch_fee_input_provider))?;
 
         context.insert_resource(L1TxParamsResource(gas_adjuster.clone()))?;
+        context.insert_resource(L1FeeStatisticsResource(gas_adjuster.clone()))?;
 
         context.add_task(Box::new(GasAdjusterTask { gas_adjuster }));
         Ok(())
@@ -1,5 +1,6 @@
 pub mod commitment_generator;
 pub mod contract_verification_api;
+pub mod da_dispatcher;
 pub mod eth_sender;
 pub mod eth_watch;
 pub mod healtcheck_server;
@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use anyhow::Context;
-use zksync_eth_client::clients::QueryClient;
+use zksync_config::ETHClientConfig;
+use zksync_eth_client::clients::FallbackEthClient;
 
 use crate::{
     implementations::resources::eth_interface::EthInterfaceResource,
@@ -11,12 +12,12 @@ use crate::{
 
 #[derive(Debug)]
 pub struct QueryEthClientLayer {
-    web3_url: String,
+    eth_client_config: ETHClientConfig,
 }
 
 impl QueryEthClientLayer {
-    pub fn new(web3_url: String) -> Self {
-        Self { web3_url }
+    pub fn new(eth_client_config: ETHClientConfig) -> Self {
+        Self { eth_client_config }
     }
 }
 
@@ -27,7 +28,8 @@ impl WiringLayer for QueryEthClientLayer {
     }
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
-        let query_client = QueryClient::new(&self.web3_url).context("QueryClient::new()")?;
+        let query_client = FallbackEthClient::from_config(&self.eth_client_config)
+            .context("FallbackEthClient::from_config()")?;
         context.insert_resource(EthInterfaceResource(Arc::new(query_client)))?;
         Ok(())
     }
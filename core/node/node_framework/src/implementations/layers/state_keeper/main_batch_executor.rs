@@ -1,5 +1,5 @@
 use zksync_config::{configs::chain::StateKeeperConfig, DBConfig};
-use zksync_core::state_keeper::MainBatchExecutor;
+use zksync_core::state_keeper::{MainBatchExecutor, PreExecutionOptions, ShadowVmOptions};
 
 use crate::{
     implementations::resources::{pools::MasterPoolResource, state_keeper::BatchExecutorResource},
@@ -31,16 +31,30 @@ impl WiringLayer for MainBatchExecutorLayer {
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
         let master_pool = context.get_resource::<MasterPoolResource>().await?;
+        let shadow_vm = ShadowVmOptions::from_config(&self.state_keeper_config)
+            .map_err(|err| WiringError::Configuration(format!("{err:#}")))?;
+        // The shadow VM holds a separate connection for the whole L1 batch.
+        let pool = if shadow_vm.is_some() {
+            master_pool.get_custom(2).await?
+        } else {
+            master_pool.get_singleton().await?
+        };
 
-        let builder = MainBatchExecutor::new(
+        let mut builder = MainBatchExecutor::new(
             self.db_config.state_keeper_db_path,
-            master_pool.get_singleton().await?,
+            pool,
             self.state_keeper_config.max_allowed_l2_tx_gas_limit.into(),
             self.state_keeper_config.save_call_traces,
             self.state_keeper_config.upload_witness_inputs_to_gcs,
             self.state_keeper_config.enum_index_migration_chunk_size(),
             false,
         );
+        if let Some(shadow_vm) = shadow_vm {
+            builder = builder.with_shadow_vm(shadow_vm);
+        }
+        if let Some(pre_execution) = PreExecutionOptions::from_config(&self.state_keeper_config) {
+            builder = builder.with_pre_execution(pre_execution);
+        }
 
         context.insert_resource(BatchExecutorResource(Unique::new(Box::new(builder))))?;
         Ok(())
@@ -14,7 +14,7 @@ use crate::{
         fee_input::FeeInputResource,
         object_store::ObjectStoreResource,
         pools::MasterPoolResource,
-        state_keeper::{ConditionalSealerResource, StateKeeperIOResource},
+        state_keeper::{ConditionalSealerResource, MempoolResource, StateKeeperIOResource},
     },
     resource::Unique,
     service::{ServiceContext, StopReceiver},
@@ -57,7 +57,7 @@ impl MempoolIOLayer {
             .connection()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, self.mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(&mut storage, &self.mempool_config).await;
         mempool.register_metrics();
         Ok(mempool)
     }
@@ -98,6 +98,7 @@ impl WiringLayer for MempoolIOLayer {
             mempool_fetcher_pool,
         );
         context.add_task(Box::new(MempoolFetcherTask(mempool_fetcher)));
+        context.insert_resource(MempoolResource(mempool_guard.clone()))?;
 
         // Create mempool IO resource.
         let mempool_db_pool = master_pool
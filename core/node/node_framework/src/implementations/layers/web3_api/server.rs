@@ -6,7 +6,9 @@ use zksync_core::api_server::web3::{state::InternalApiConfig, ApiBuilder, ApiSer
 use crate::{
     implementations::resources::{
         healthcheck::AppHealthCheckResource,
+        object_store::ObjectStoreResource,
         pools::ReplicaPoolResource,
+        state_keeper::MempoolResource,
         sync_state::SyncStateResource,
         web3_api::{TreeApiClientResource, TxSenderResource},
     },
@@ -120,6 +122,16 @@ impl WiringLayer for Web3ServerLayer {
             Err(WiringError::ResourceLacking(_)) => None,
             Err(err) => return Err(err),
         };
+        let mempool = match context.get_resource::<MempoolResource>().await {
+            Ok(mempool) => Some(mempool.0),
+            Err(WiringError::ResourceLacking(_)) => None,
+            Err(err) => return Err(err),
+        };
+        let blob_store = match context.get_resource::<ObjectStoreResource>().await {
+            Ok(object_store) => Some(object_store.0),
+            Err(WiringError::ResourceLacking(_)) => None,
+            Err(err) => return Err(err),
+        };
 
         // Build server.
         let mut api_builder = ApiBuilder::jsonrpsee_backend(self.internal_api_config, replica_pool)
@@ -139,6 +151,12 @@ impl WiringLayer for Web3ServerLayer {
         if let Some(sync_state) = sync_state {
             api_builder = api_builder.with_sync_state(sync_state);
         }
+        if let Some(mempool) = mempool {
+            api_builder = api_builder.with_mempool(mempool);
+        }
+        if let Some(blob_store) = blob_store {
+            api_builder = api_builder.with_blob_store(blob_store);
+        }
         api_builder = self.optional_config.apply(api_builder);
         let server = api_builder.build()?;
 
@@ -0,0 +1,16 @@
+use zksync_core::eth_watch::ExtraEventProcessor;
+
+use crate::resource::{Resource, ResourceId, Unique};
+
+/// Event processor to be registered in `eth_watch` in addition to the built-in ones.
+///
+/// Layers should add processors to the `ResourceCollection<EthWatchEventProcessorResource>`,
+/// which is resolved by the `eth_watch` task.
+#[derive(Debug, Clone)]
+pub struct EthWatchEventProcessorResource(pub Unique<ExtraEventProcessor>);
+
+impl Resource for EthWatchEventProcessorResource {
+    fn resource_id() -> ResourceId {
+        "eth_watch/event_processor".into()
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use zksync_core::l1_gas_price::L1TxParamsProvider;
+use zksync_core::l1_gas_price::{L1FeeStatisticsProvider, L1TxParamsProvider};
 
 use crate::resource::{Resource, ResourceId};
 
@@ -13,3 +13,13 @@ impl Resource for L1TxParamsResource {
         "common/l1_tx_params".into()
     }
 }
+
+/// Wrapper for the provider of statistics on recent L1 fees.
+#[derive(Debug, Clone)]
+pub struct L1FeeStatisticsResource(pub Arc<dyn L1FeeStatisticsProvider>);
+
+impl Resource for L1FeeStatisticsResource {
+    fn resource_id() -> ResourceId {
+        "common/l1_fee_statistics".into()
+    }
+}
@@ -1,4 +1,5 @@
 pub mod eth_interface;
+pub mod eth_watch;
 pub mod fee_input;
 pub mod healthcheck;
 pub mod l1_tx_params;
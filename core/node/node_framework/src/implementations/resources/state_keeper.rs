@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
-use zksync_core::state_keeper::{seal_criteria::ConditionalSealer, BatchExecutor, StateKeeperIO};
+use zksync_core::state_keeper::{
+    seal_criteria::ConditionalSealer, BatchExecutor, MempoolGuard, StateKeeperIO,
+};
 
 use crate::resource::{Resource, ResourceId, Unique};
 
@@ -30,3 +32,12 @@ impl Resource for ConditionalSealerResource {
         "state_keeper/conditional_sealer".into()
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct MempoolResource(pub MempoolGuard);
+
+impl Resource for MempoolResource {
+    fn resource_id() -> ResourceId {
+        "state_keeper/mempool".into()
+    }
+}